@@ -1,7 +1,7 @@
 pub mod x86;
 
 use crate::ir::aarch64_encoding::logical_imm64_encodable;
-use crate::ir::instructions::logical_imm32_value;
+use crate::ir::instructions::{add_sub_imm12, logical_imm32_value, move_wide_chunks, movn_chunk};
 use crate::ir::types::{
     AccessWidth, AddressOperand, Condition, ExtendKind, IndexMode, LabelId, PairAccessWidth,
     ShiftKind, VectorArrangement,
@@ -682,9 +682,12 @@ impl AArch64Assembler {
         let mut ops = dynasmrt::aarch64::Assembler::new()
             .map_err(|e| format!("Failed to create assembler: {:?}", e))?;
 
-        for (idx, instr) in instructions.iter().enumerate() {
-            let current_pc = base_address.wrapping_add((idx as u64) * 4);
+        // A wide `MovImm` emits several words, so each PC is the running
+        // byte offset rather than `index * 4`.
+        let mut current_pc = base_address;
+        for instr in instructions {
             self.encode_instruction_on(&mut ops, instr, current_pc)?;
+            current_pc = current_pc.wrapping_add(instr.encoded_length() as u64);
         }
 
         ops.finalize()
@@ -726,9 +729,43 @@ impl AArch64Assembler {
             Instruction::MovImm { rd, imm } => {
                 let rd_reg = register_to_dynasm(*rd)?;
 
-                if *imm < 0 || *imm > 0xFFFF {
+                // Values whose complement is one halfword (`-1`,
+                // `0xFFFF_FFFF_FFFF_0000`, ...) are a single MOVN.
+                if !(0..=0xFFFF).contains(imm)
+                    && let Some((chunk, shift)) = movn_chunk(*imm)
+                {
+                    let movn = Instruction::MovN {
+                        rd: *rd,
+                        imm: chunk,
+                        shift,
+                    };
+                    return self.encode_instruction_on(ops, &movn, current_pc);
+                }
+                if *imm < 0 {
                     return Err(format!("Immediate {} out of range for MOV", imm));
                 }
+                if *imm > 0xFFFF {
+                    // Wide immediates (discovered under
+                    // `SearchConfig::max_immediate_bits > 16`) lower to a
+                    // MOVZ followed by one MOVK per further nonzero chunk.
+                    for (i, (chunk, shift)) in move_wide_chunks(*imm).into_iter().enumerate() {
+                        let part = if i == 0 {
+                            Instruction::MovZ {
+                                rd: *rd,
+                                imm: chunk,
+                                shift,
+                            }
+                        } else {
+                            Instruction::MovK {
+                                rd: *rd,
+                                imm: chunk,
+                                shift,
+                            }
+                        };
+                        self.encode_instruction_on(ops, &part, current_pc)?;
+                    }
+                    return Ok(());
+                }
 
                 dynasm!(ops
                     ; .arch aarch64
@@ -796,9 +833,9 @@ impl AArch64Assembler {
                         Ok(())
                     }
                     Operand::Immediate(imm) => {
-                        if *imm < 0 || *imm > 0xFFF {
+                        let Some((imm12, shifted)) = add_sub_imm12(*imm) else {
                             return Err(format!("Immediate {} out of range for ADD", imm));
-                        }
+                        };
                         // ADD immediate uses the Xn|SP register type for both rd
                         // and rn per AArch64 spec (`ADD <Xd|SP>, <Xn|SP>, #imm`).
                         // register_to_dynasm_xsp accepts SP (so `ADD SP, SP, #imm`
@@ -806,10 +843,11 @@ impl AArch64Assembler {
                         // would otherwise silently alias to SP.
                         let rd_reg = register_to_dynasm_xsp(*rd)?;
                         let rn_reg = register_to_dynasm_xsp(*rn)?;
-                        dynasm!(ops
-                            ; .arch aarch64
-                            ; add XSP(rd_reg), XSP(rn_reg), #*imm as u32
-                        );
+                        if shifted {
+                            dynasm!(ops ; .arch aarch64 ; add XSP(rd_reg), XSP(rn_reg), #imm12, LSL #12);
+                        } else {
+                            dynasm!(ops ; .arch aarch64 ; add XSP(rd_reg), XSP(rn_reg), #imm12);
+                        }
                         Ok(())
                     }
                     Operand::ShiftedRegister { reg, kind, amount } => {
@@ -848,15 +886,16 @@ impl AArch64Assembler {
                     Ok(())
                 }
                 Operand::Immediate(imm) => {
-                    if *imm < 0 || *imm > 0xFFF {
+                    let Some((imm12, shifted)) = add_sub_imm12(*imm) else {
                         return Err(format!("Immediate {} out of range for ADD W", imm));
-                    }
+                    };
                     let rd_reg = register_to_dynasm_wsp(*rd)?;
                     let rn_reg = register_to_dynasm_wsp(*rn)?;
-                    dynasm!(ops
-                        ; .arch aarch64
-                        ; add WSP(rd_reg), WSP(rn_reg), #*imm as u32
-                    );
+                    if shifted {
+                        dynasm!(ops ; .arch aarch64 ; add WSP(rd_reg), WSP(rn_reg), #imm12, LSL #12);
+                    } else {
+                        dynasm!(ops ; .arch aarch64 ; add WSP(rd_reg), WSP(rn_reg), #imm12);
+                    }
                     Ok(())
                 }
                 Operand::ShiftedRegister { reg, kind, amount } => {
@@ -887,19 +926,20 @@ impl AArch64Assembler {
                         Ok(())
                     }
                     Operand::Immediate(imm) => {
-                        if *imm < 0 || *imm > 0xFFF {
+                        let Some((imm12, shifted)) = add_sub_imm12(*imm) else {
                             return Err(format!("Immediate {} out of range for SUB", imm));
-                        }
+                        };
                         // SUB immediate uses the Xn|SP register type for both rd
                         // and rn (`SUB <Xd|SP>, <Xn|SP>, #imm`). register_to_dynasm_xsp
                         // accepts SP (so `SUB SP, SP, #imm` encodes) and rejects
                         // XZR, which would otherwise alias to SP via index 31.
                         let rd_reg = register_to_dynasm_xsp(*rd)?;
                         let rn_reg = register_to_dynasm_xsp(*rn)?;
-                        dynasm!(ops
-                            ; .arch aarch64
-                            ; sub XSP(rd_reg), XSP(rn_reg), #*imm as u32
-                        );
+                        if shifted {
+                            dynasm!(ops ; .arch aarch64 ; sub XSP(rd_reg), XSP(rn_reg), #imm12, LSL #12);
+                        } else {
+                            dynasm!(ops ; .arch aarch64 ; sub XSP(rd_reg), XSP(rn_reg), #imm12);
+                        }
                         Ok(())
                     }
                     Operand::ShiftedRegister { reg, kind, amount } => {
@@ -934,15 +974,16 @@ impl AArch64Assembler {
                     Ok(())
                 }
                 Operand::Immediate(imm) => {
-                    if *imm < 0 || *imm > 0xFFF {
+                    let Some((imm12, shifted)) = add_sub_imm12(*imm) else {
                         return Err(format!("Immediate {} out of range for SUB W", imm));
-                    }
+                    };
                     let rd_reg = register_to_dynasm_wsp(*rd)?;
                     let rn_reg = register_to_dynasm_wsp(*rn)?;
-                    dynasm!(ops
-                        ; .arch aarch64
-                        ; sub WSP(rd_reg), WSP(rn_reg), #*imm as u32
-                    );
+                    if shifted {
+                        dynasm!(ops ; .arch aarch64 ; sub WSP(rd_reg), WSP(rn_reg), #imm12, LSL #12);
+                    } else {
+                        dynasm!(ops ; .arch aarch64 ; sub WSP(rd_reg), WSP(rn_reg), #imm12);
+                    }
                     Ok(())
                 }
                 Operand::ShiftedRegister { reg, kind, amount } => {
@@ -1370,14 +1411,15 @@ impl AArch64Assembler {
                         Ok(())
                     }
                     Operand::Immediate(imm) => {
-                        if *imm < 0 || *imm > 0xFFF {
+                        let Some((imm12, shifted)) = add_sub_imm12(*imm) else {
                             return Err(format!("Immediate {} out of range for CMP", imm));
-                        }
+                        };
                         let rn_reg = register_to_dynasm_xsp(*rn)?;
-                        dynasm!(ops
-                            ; .arch aarch64
-                            ; cmp XSP(rn_reg), #*imm as u32
-                        );
+                        if shifted {
+                            dynasm!(ops ; .arch aarch64 ; cmp XSP(rn_reg), #imm12, LSL #12);
+                        } else {
+                            dynasm!(ops ; .arch aarch64 ; cmp XSP(rn_reg), #imm12);
+                        }
                         Ok(())
                     }
                     Operand::ShiftedRegister { reg, kind, amount } => {
@@ -1407,14 +1449,15 @@ impl AArch64Assembler {
                         Ok(())
                     }
                     Operand::Immediate(imm) => {
-                        if *imm < 0 || *imm > 0xFFF {
+                        let Some((imm12, shifted)) = add_sub_imm12(*imm) else {
                             return Err(format!("Immediate {} out of range for CMN", imm));
-                        }
+                        };
                         let rn_reg = register_to_dynasm_xsp(*rn)?;
-                        dynasm!(ops
-                            ; .arch aarch64
-                            ; cmn XSP(rn_reg), #*imm as u32
-                        );
+                        if shifted {
+                            dynasm!(ops ; .arch aarch64 ; cmn XSP(rn_reg), #imm12, LSL #12);
+                        } else {
+                            dynasm!(ops ; .arch aarch64 ; cmn XSP(rn_reg), #imm12);
+                        }
                         Ok(())
                     }
                     Operand::ShiftedRegister { reg, kind, amount } => {
@@ -1778,17 +1821,20 @@ impl AArch64Assembler {
                         Ok(())
                     }
                     Operand::Immediate(imm) => {
-                        if *imm < 0 || *imm > 0xFFF {
+                        let Some((imm12, shifted)) = add_sub_imm12(*imm) else {
                             return Err(format!("Immediate {} out of range for ADDS", imm));
-                        }
+                        };
                         // The immediate-form encoding uses the `Xn|SP` slot —
                         // 31 decodes as SP, not XZR. `register_to_dynasm_xsp`
                         // accepts SP and rejects XZR, keeping the encoding
                         // unambiguous and consistent with what the parser /
                         // is_encodable_aarch64 admit.
                         let rn_reg = register_to_dynasm_xsp(*rn)?;
-                        let imm = *imm as u32;
-                        dynasm!(ops ; .arch aarch64 ; adds X(rd_reg), XSP(rn_reg), imm);
+                        if shifted {
+                            dynasm!(ops ; .arch aarch64 ; adds X(rd_reg), XSP(rn_reg), imm12, LSL 12);
+                        } else {
+                            dynasm!(ops ; .arch aarch64 ; adds X(rd_reg), XSP(rn_reg), imm12);
+                        }
                         Ok(())
                     }
                     Operand::ShiftedRegister { reg, kind, amount } => {
@@ -1816,15 +1862,18 @@ impl AArch64Assembler {
                         Ok(())
                     }
                     Operand::Immediate(imm) => {
-                        if *imm < 0 || *imm > 0xFFF {
+                        let Some((imm12, shifted)) = add_sub_imm12(*imm) else {
                             return Err(format!("Immediate {} out of range for SUBS", imm));
-                        }
+                        };
                         // Immediate form uses the Xn|SP slot — same caveat
                         // as ADDS above; `register_to_dynasm_xsp` accepts SP
                         // and rejects XZR.
                         let rn_reg = register_to_dynasm_xsp(*rn)?;
-                        let imm = *imm as u32;
-                        dynasm!(ops ; .arch aarch64 ; subs X(rd_reg), XSP(rn_reg), imm);
+                        if shifted {
+                            dynasm!(ops ; .arch aarch64 ; subs X(rd_reg), XSP(rn_reg), imm12, LSL 12);
+                        } else {
+                            dynasm!(ops ; .arch aarch64 ; subs X(rd_reg), XSP(rn_reg), imm12);
+                        }
                         Ok(())
                     }
                     Operand::ShiftedRegister { reg, kind, amount } => {
@@ -2482,6 +2531,75 @@ mod tests {
         }
     }
 
+    #[test]
+    fn encoded_length_matches_the_assembled_byte_count() {
+        let mut instructions: Vec<Instruction> = aarch64_instruction_families()
+            .into_iter()
+            .map(|fixture| fixture.instruction)
+            .collect();
+        for imm in [
+            0,
+            0xFFFF,
+            0x1_0000,
+            0x1234_5678,
+            0x1_0000_0001,
+            0x1234_5678_9ABC_DEF0,
+            -1,
+            -0x1_0000,
+            0xFFFF_FFFF_FFFF_0000_u64 as i64,
+        ] {
+            instructions.push(Instruction::MovImm {
+                rd: Register::X3,
+                imm,
+            });
+        }
+
+        for instr in instructions {
+            let bytes = AArch64Assembler::new()
+                .assemble_instructions(&[instr], 0)
+                .unwrap_or_else(|error| panic!("{instr} failed to assemble: {error}"));
+            assert_eq!(instr.encoded_length(), bytes.len(), "{instr}");
+        }
+    }
+
+    #[test]
+    fn branch_after_wide_mov_resolves_from_its_real_address() {
+        let branch = Instruction::B {
+            target: LabelId(0x1100),
+        };
+        let wide = AArch64Assembler::new()
+            .assemble_instructions(
+                &[
+                    Instruction::MovImm {
+                        rd: Register::X0,
+                        imm: 0x1_2345,
+                    },
+                    branch,
+                ],
+                0x1000,
+            )
+            .expect("wide MOV then B should encode");
+        let split = AArch64Assembler::new()
+            .assemble_instructions(
+                &[
+                    Instruction::MovZ {
+                        rd: Register::X0,
+                        imm: 0x2345,
+                        shift: 0,
+                    },
+                    Instruction::MovK {
+                        rd: Register::X0,
+                        imm: 0x1,
+                        shift: 16,
+                    },
+                    branch,
+                ],
+                0x1000,
+            )
+            .expect("MOVZ/MOVK then B should encode");
+        assert_eq!(wide, split);
+    }
+
     #[test]
     fn test_mov_reg_encoding() {
         let mut assembler = AArch64Assembler::new();
@@ -2585,13 +2703,88 @@ mod tests {
         let mut assembler = AArch64Assembler::new();
         let instructions = vec![Instruction::MovImm {
             rd: Register::X0,
-            imm: 0x10000, // Too large
+            imm: -0x1_2345, // complement spans two halfwords: no single MOVN
         }];
 
         let result = assembler.assemble_instructions(&instructions, 0);
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_mov_imm_minus_one_assembles_to_single_movn() {
+        let mut assembler = AArch64Assembler::new();
+        let mov = Instruction::MovImm {
+            rd: Register::X0,
+            imm: -1,
+        };
+        assert!(mov.is_encodable_aarch64());
+        let bytes = assembler
+            .assemble_instructions(&[mov], 0)
+            .expect("MOV #-1 should lower to MOVN");
+        let movn = assembler
+            .assemble_instructions(
+                &[Instruction::MovN {
+                    rd: Register::X0,
+                    imm: 0,
+                    shift: 0,
+                }],
+                0,
+            )
+            .expect("MOVN should assemble");
+        assert_eq!(bytes, movn);
+        disassemble_and_verify(&bytes, "mov", &["x0", "#-1"]);
+    }
+
+    #[test]
+    fn test_mov_imm_inverted_halfword_assembles_to_shifted_movn() {
+        let mut assembler = AArch64Assembler::new();
+        // !0xFFFF_FFFF_0000_FFFF == 0xFFFF << 16
+        let imm = 0xFFFF_FFFF_0000_FFFF_u64 as i64;
+        let bytes = assembler
+            .assemble_instructions(
+                &[Instruction::MovImm {
+                    rd: Register::X1,
+                    imm,
+                }],
+                0,
+            )
+            .expect("inverted-halfword immediate should lower to MOVN");
+        assert_eq!(bytes.len(), 4);
+        disassemble_and_verify(&bytes, "mov", &["x1", "#-0xffff0001"]);
+    }
+
+    #[test]
+    fn test_wide_mov_imm_lowers_to_movz_movk_chain() {
+        let mut assembler = AArch64Assembler::new();
+        let wide = assembler
+            .assemble_instructions(
+                &[Instruction::MovImm {
+                    rd: Register::X0,
+                    imm: 0x1_2345,
+                }],
+                0,
+            )
+            .expect("wide MOV immediate should lower to MOVZ/MOVK");
+        let chain = assembler
+            .assemble_instructions(
+                &[
+                    Instruction::MovZ {
+                        rd: Register::X0,
+                        imm: 0x2345,
+                        shift: 0,
+                    },
+                    Instruction::MovK {
+                        rd: Register::X0,
+                        imm: 0x1,
+                        shift: 16,
+                    },
+                ],
+                0,
+            )
+            .expect("MOVZ/MOVK chain should assemble");
+        assert_eq!(wide, chain);
+    }
+
     #[test]
     fn test_multiple_instructions() {
         let mut assembler = AArch64Assembler::new();
@@ -2711,6 +2904,56 @@ mod tests {
         disassemble_and_verify(&bytes, "add", &["x0", "x1", "0xa"]);
     }
 
+    #[test]
+    fn add_sub_immediates_above_12_bits_use_lsl_12() {
+        let mut assembler = AArch64Assembler::new();
+        let bytes = assembler
+            .assemble_instructions(
+                &[Instruction::Add {
+                    rd: Register::X0,
+                    rn: Register::X1,
+                    rm: Operand::Immediate(0x1000),
+                }],
+                0,
+            )
+            .expect("ADD #0x1000 should encode as #1, lsl #12");
+        disassemble_and_verify(&bytes, "add", &["x0", "x1", "#1", "lsl #12"]);
+
+        for instr in [
+            Instruction::SubW {
+                rd: Register::X2,
+                rn: Register::SP,
+                rm: Operand::Immediate(0xFFF000),
+            },
+            Instruction::Subs {
+                rd: Register::X0,
+                rn: Register::X1,
+                rm: Operand::Immediate(0x3000),
+            },
+            Instruction::Cmn {
+                rn: Register::X1,
+                rm: Operand::Immediate(0x1000),
+            },
+        ] {
+            let bytes = AArch64Assembler::new()
+                .assemble_instructions(&[instr], 0)
+                .unwrap_or_else(|e| panic!("{instr} should encode: {e}"));
+            assert_eq!(bytes.len(), 4);
+        }
+
+        let err = AArch64Assembler::new()
+            .assemble_instructions(
+                &[Instruction::Add {
+                    rd: Register::X0,
+                    rn: Register::X1,
+                    rm: Operand::Immediate(0x1001),
+                }],
+                0,
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("out of range"), "{err}");
+    }
+
     #[test]
     fn test_sub_reg_correctness() {
         let mut assembler = AArch64Assembler::new();
@@ -4632,7 +4875,7 @@ mod tests {
             Instruction::Add {
                 rd: Register::X0,
                 rn: Register::X1,
-                rm: Operand::Immediate(4096),
+                rm: Operand::Immediate(4097),
             },
             Instruction::Sub {
                 rd: Register::X0,
@@ -4676,7 +4919,7 @@ mod tests {
             },
             Instruction::Cmp {
                 rn: Register::X1,
-                rm: Operand::Immediate(4096),
+                rm: Operand::Immediate(4097),
             },
             Instruction::Cmn {
                 rn: Register::X1,
@@ -4725,7 +4968,7 @@ mod tests {
             Instruction::Adds {
                 rd: Register::X0,
                 rn: Register::X1,
-                rm: Operand::Immediate(4096),
+                rm: Operand::Immediate(4097),
             },
             Instruction::Adds {
                 rd: Register::X0,
@@ -134,6 +134,10 @@ pub fn load_sequence(path: &Path) -> (Vec<Instruction>, LiveOut) {
         match parse_line(line) {
             Ok(LineResult::Instruction(instr)) => sequence.push(instr),
             Ok(LineResult::Skip) => {}
+            Ok(LineResult::Unsupported(mnemonic)) => panic!(
+                "fixture {}: unsupported instruction {mnemonic} on line {line:?}",
+                path.display()
+            ),
             Err(e) => panic!(
                 "fixture {}: parse error on line {line:?}: {e:?}",
                 path.display()
@@ -282,13 +286,15 @@ pub fn run_bench(spec: &BenchSpec) -> BenchRecord {
             let result = search.search(&target, &live_out, &config);
             (result.statistics, result.optimized_sequence)
         }
+        #[cfg(feature = "z3")]
         Algorithm::Symbolic => {
             let mut search = crate::search::SymbolicSearch::<crate::isa::AArch64>::new();
             let result = search.search(&target, &live_out, &config);
             (result.statistics, result.optimized_sequence)
         }
         // Hybrid/LLM not wired into the bench harness — issue #70 keeps
-        // those out of scope. Caller should pre-filter.
+        // those out of scope. Caller should pre-filter. Symbolic lands here
+        // too in a build without the `z3` feature.
         other => panic!("run_bench: unsupported algorithm {other:?}"),
     };
 
@@ -2,7 +2,7 @@
 //!
 //! Capstone renders some AArch64 encodings with alias spellings the GNU-assembler
 //! parser does not accept directly (wide `mov Xd, #imm`, the `cinc`/`cinv`/`cneg`
-//! conditional-select aliases). This module normalizes those spellings and then
+//! conditional-select aliases, the unscaled `ldur`/`stur` loads and stores). This module normalizes those spellings and then
 //! delegates to [`crate::parser::parse_line`], which is the single source of truth
 //! for the supported mnemonic set. Keeping the delegation here is what guarantees
 //! the asm-text path and the ELF/Capstone path support exactly the same mnemonics
@@ -90,7 +90,12 @@ fn normalize_mov_wide_alias(op_str: &str) -> Result<Option<String>, String> {
         return Ok(Some(format_move_wide("movn", rd, imm, shift)));
     }
 
-    Ok(None)
+    // One decoded instruction never needs a MOVZ/MOVK chain: this is the
+    // ORR-bitmask spelling, which must not be widened into a chain.
+    Err(format!(
+        "{} is neither a single MOVZ nor MOVN immediate",
+        operands[1]
+    ))
 }
 
 fn normalize_cond_select_alias(mnemonic: &str, op_str: &str) -> Result<String, String> {
@@ -133,10 +138,49 @@ fn normalize_cond_select_alias(mnemonic: &str, op_str: &str) -> Result<String, S
     ))
 }
 
+/// `mul` and `mneg` are the `madd`/`msub` encodings with a zero-register
+/// accumulator. Capstone already prints those aliases, but a spelled-out
+/// `madd ..., xzr` is folded the same way so both reach the same IR.
+fn normalize_multiply_accumulate_alias(mnemonic: &str, op_str: &str) -> Option<String> {
+    let operands = split_capstone_alias_operands(op_str);
+    let [rd, rn, rm, ra] = operands.as_slice() else {
+        return None;
+    };
+    if !matches!(ra.to_ascii_lowercase().as_str(), "xzr" | "wzr") {
+        return None;
+    }
+    let canonical = if mnemonic == "madd" { "mul" } else { "mneg" };
+    Some(format!("{} {}, {}, {}", canonical, rd, rn, rm))
+}
+
+/// The assembler emits the unscaled LDUR/STUR family for an `ldr`/`str`
+/// offset the scaled form cannot hold, so those read back as that
+/// `ldr`/`str`. The parser keeps rejecting the unscaled spellings in
+/// assembly text (ADR-0007 §9).
+fn normalize_unscaled_memory_alias(mnemonic: &str, op_str: &str) -> Option<String> {
+    let scaled = match mnemonic {
+        "ldur" => "ldr",
+        "ldurb" => "ldrb",
+        "ldurh" => "ldrh",
+        "ldursb" => "ldrsb",
+        "ldursh" => "ldrsh",
+        "ldursw" => "ldrsw",
+        "stur" => "str",
+        "sturb" => "strb",
+        "sturh" => "strh",
+        _ => return None,
+    };
+    Some(format!("{} {}", scaled, op_str))
+}
+
 fn normalize_capstone_alias(mnemonic: &str, op_str: &str) -> Result<Option<String>, String> {
     let mnemonic = mnemonic.to_ascii_lowercase();
+    if let Some(scaled) = normalize_unscaled_memory_alias(&mnemonic, op_str) {
+        return Ok(Some(scaled));
+    }
     match mnemonic.as_str() {
         "mov" => normalize_mov_wide_alias(op_str),
+        "madd" | "msub" => Ok(normalize_multiply_accumulate_alias(&mnemonic, op_str)),
         "cinc" | "cinv" | "cneg" => normalize_cond_select_alias(&mnemonic, op_str).map(Some),
         _ => Ok(None),
     }
@@ -180,7 +224,8 @@ pub fn convert_capstone_op(mnemonic: &str, op_str: &str) -> ConvertOutcome {
     match parser::parse_line(&line) {
         Ok(parser::LineResult::Instruction(instr)) => ConvertOutcome::Instruction(instr),
         Ok(parser::LineResult::Skip) => ConvertOutcome::Skip,
-        Err(parser::ParseLineError::UnknownInstruction(_)) => {
+        Ok(parser::LineResult::Unsupported(_))
+        | Err(parser::ParseLineError::UnknownInstruction(_)) => {
             ConvertOutcome::Unsupported(describe_unsupported_line(&raw_line, &line, None))
         }
         Err(parser::ParseLineError::Other(err)) => {
@@ -192,6 +237,7 @@ pub fn convert_capstone_op(mnemonic: &str, op_str: &str) -> ConvertOutcome {
 #[cfg(test)]
 mod tests {
     use super::{ConvertOutcome, convert_capstone_op};
+    use crate::ir::types::{AccessWidth, AddressOperand, IndexMode};
     use crate::ir::{self, Instruction, Register};
 
     #[test]
@@ -535,6 +581,30 @@ mod tests {
         }
     }
 
+    #[test]
+    fn convert_capstone_op_folds_zero_accumulator_into_mul_and_mneg() {
+        let (rd, rn, rm) = (Register::X0, Register::X1, Register::X2);
+        for (mnemonic, ops, expected) in [
+            ("madd", "x0, x1, x2, xzr", Instruction::Mul { rd, rn, rm }),
+            ("msub", "x0, x1, x2, xzr", Instruction::Mneg { rd, rn, rm }),
+            (
+                "madd",
+                "x0, x1, x2, x3",
+                Instruction::Madd {
+                    rd,
+                    rn,
+                    rm,
+                    ra: Register::X3,
+                },
+            ),
+        ] {
+            match convert_capstone_op(mnemonic, ops) {
+                ConvertOutcome::Instruction(instr) => assert_eq!(instr, expected),
+                other => panic!("expected {expected:?} for `{mnemonic} {ops}`, got {other:?}"),
+            }
+        }
+    }
+
     #[test]
     fn convert_capstone_op_normalizes_cond_select_aliases() {
         for (mnemonic, ops, expected) in [
@@ -626,24 +696,60 @@ mod tests {
 
     #[test]
     fn convert_capstone_op_keeps_related_memory_mnemonics_unsupported() {
-        // ADR-0007 §9 explicitly leaves these out of scope. Lock the outcome
-        // here so a future Capstone-syntax shift cannot silently start
-        // parsing them as supported instructions:
-        //   - LDUR / STUR: unscaled-signed-offset variants Capstone uses
-        //     for negative immediates that LDR-imm cannot encode.
-        //   - LDR (literal): PC-relative pool load — different operand
-        //     grammar than the bracketed forms supported by step 4.
-        for (mnem, ops) in [
-            ("ldur", "x0, [x1, #-1]"),
-            ("stur", "x0, [x1, #-1]"),
-            ("ldr", "x0, #0x1234"),
+        // ADR-0007 §9 explicitly leaves LDR (literal) out of scope: it is a
+        // PC-relative pool load, a different operand grammar than the
+        // bracketed forms supported by step 4. Lock the outcome here so a
+        // future Capstone-syntax shift cannot silently start parsing it.
+        match convert_capstone_op("ldr", "x0, #0x1234") {
+            ConvertOutcome::Unsupported(_) => {}
+            other => panic!(
+                "expected Unsupported for `ldr x0, #0x1234`, got {:?}",
+                other
+            ),
+        }
+    }
+
+    #[test]
+    fn convert_capstone_op_reads_unscaled_memory_back_as_ldr_and_str() {
+        // The assembler emits LDUR / STUR for the negative or unaligned
+        // offsets LDR / STR cannot scale, so the bridge must read them back.
+        let addr = AddressOperand::Imm {
+            base: Register::X1,
+            offset: -1,
+            mode: IndexMode::Offset,
+        };
+        for (mnem, ops, expected) in [
+            (
+                "ldur",
+                "x0, [x1, #-1]",
+                Instruction::Ldr {
+                    rt: Register::X0,
+                    addr,
+                    width: AccessWidth::Extended,
+                },
+            ),
+            (
+                "ldursb",
+                "x0, [x1, #-1]",
+                Instruction::Ldrs {
+                    rt: Register::X0,
+                    addr,
+                    width: AccessWidth::Byte,
+                },
+            ),
+            (
+                "sturh",
+                "w0, [x1, #-1]",
+                Instruction::Str {
+                    rt: Register::X0,
+                    addr,
+                    width: AccessWidth::Half,
+                },
+            ),
         ] {
             match convert_capstone_op(mnem, ops) {
-                ConvertOutcome::Unsupported(_) => {}
-                other => panic!(
-                    "expected Unsupported for `{} {}`, got {:?}",
-                    mnem, ops, other
-                ),
+                ConvertOutcome::Instruction(instr) => assert_eq!(instr, expected),
+                other => panic!("expected `{mnem} {ops}` to convert, got {other:?}"),
             }
         }
     }
@@ -85,6 +85,17 @@ impl DetectedArch {
         }
     }
 
+    /// Number of NOP sequences `nop_sequence` hands out to fill `len` bytes.
+    pub fn nop_padding_count(&self, len: usize) -> usize {
+        let mut remaining = len;
+        let mut count = 0;
+        while remaining > 0 {
+            remaining -= self.nop_sequence(remaining).len();
+            count += 1;
+        }
+        count
+    }
+
     fn from_e_machine(machine: u16) -> Option<Self> {
         match machine {
             elf::abi::EM_AARCH64 => Some(DetectedArch::Aarch64),
@@ -95,17 +106,63 @@ impl DetectedArch {
     }
 }
 
+/// One differing byte: `(file_offset, original_byte, other_byte)`.
+pub type ByteDiff = (usize, u8, u8);
+
 pub struct ElfPatcher {
     file_data: Vec<u8>,
     arch: DetectedArch,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct AddressWindow {
     pub start: u64,
     pub end: u64,
 }
 
+impl AddressWindow {
+    /// Check that the window is non-empty and both ends sit on `arch`'s
+    /// instruction boundary.
+    pub fn validate(&self, arch: DetectedArch) -> Result<(), String> {
+        if self.start >= self.end {
+            return Err("Start address must be less than end address".to_string());
+        }
+
+        let align = arch.instruction_alignment();
+        if align > 1 && (!self.start.is_multiple_of(align) || !self.end.is_multiple_of(align)) {
+            return Err(format!(
+                "Addresses must be {}-byte aligned for {:?} instructions",
+                align, arch
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Parses `START-END` (`0x1000-0x1100`), each half as [`parse_hex_address`]
+/// does. Only the ordering is checked here; alignment depends on the
+/// architecture and is left to [`AddressWindow::validate`].
+impl std::str::FromStr for AddressWindow {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (start, end) = s
+            .split_once('-')
+            .ok_or_else(|| format!("Invalid address window '{}': expected START-END", s))?;
+        let window = AddressWindow {
+            start: parse_hex_address(start.trim())?,
+            end: parse_hex_address(end.trim())?,
+        };
+        if window.start >= window.end {
+            return Err(format!(
+                "Invalid address window '{}': start must be less than end",
+                s
+            ));
+        }
+        Ok(window)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct TextSection {
     pub name: String,
@@ -114,9 +171,88 @@ pub struct TextSection {
     pub size: u64,
 }
 
+/// A sized function symbol and the code bytes it covers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionSymbol {
+    pub name: String,
+    pub window: AddressWindow,
+}
+
+/// Where one instruction of a window came from: the addresses of the original
+/// instructions it derives from. Kept beside the instruction list, by
+/// position, rather than on the IR itself.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InstructionMeta {
+    pub source_addrs: Vec<u64>,
+}
+
+impl InstructionMeta {
+    pub fn from_addr(addr: u64) -> Self {
+        Self {
+            source_addrs: vec![addr],
+        }
+    }
+}
+
+/// Everything one window optimization decided, before anything is written.
+///
+/// `new_bytes` is the assembled replacement; [`apply_plan`] pads it back out
+/// to the window with `nops_added` NOP sequences. Costs are under the metric
+/// the search ran with. Generic over the IR so the x86 pipeline can produce
+/// one too.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatchPlan<I> {
+    pub window: AddressWindow,
+    pub original: Vec<I>,
+    pub optimized: Vec<I>,
+    pub original_bytes: Vec<u8>,
+    pub new_bytes: Vec<u8>,
+    pub nops_added: usize,
+    pub cost_before: u64,
+    pub cost_after: u64,
+    /// Original bytes `[start, end)` the search rewrote: the window less any
+    /// instructions held fixed around it. `None` when the window was kept as
+    /// is or the caller did not track where its instructions came from.
+    pub replaced: Option<AddressWindow>,
+}
+
+impl<I> PatchPlan<I> {
+    /// Bytes of NOP padding the plan needs after `new_bytes`.
+    pub fn padding_bytes(&self) -> usize {
+        self.original_bytes.len() - self.new_bytes.len()
+    }
+}
+
+/// Write `plan` to `output_path` as a patched copy of `patcher`'s input.
+pub fn apply_plan<I>(
+    patcher: &ElfPatcher,
+    plan: &PatchPlan<I>,
+    output_path: &Path,
+) -> Result<(), Box<dyn std::error::Error>> {
+    patcher.create_patched_copy(output_path, &plan.window, &plan.new_bytes)
+}
+
+/// Write every plan in `plans` into one patched copy of `patcher`'s input.
+/// The windows must not overlap.
+pub fn apply_plans<I>(
+    patcher: &ElfPatcher,
+    plans: &[PatchPlan<I>],
+    output_path: &Path,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let patches: Vec<(&AddressWindow, &[u8])> = plans
+        .iter()
+        .map(|plan| (&plan.window, plan.new_bytes.as_slice()))
+        .collect();
+    patcher.create_multi_patched_copy(output_path, &patches)
+}
+
 impl ElfPatcher {
     pub fn new(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
-        let file_data = fs::read(path)?;
+        Self::from_bytes(fs::read(path)?)
+    }
+
+    /// Parse an in-memory ELF image, as [`ElfPatcher::new`] does a file.
+    pub fn from_bytes(file_data: Vec<u8>) -> Result<Self, Box<dyn std::error::Error>> {
         let elf = ElfBytes::<AnyEndian>::minimal_parse(&file_data)?;
         let arch = DetectedArch::from_e_machine(elf.ehdr.e_machine).ok_or_else(|| {
             format!(
@@ -160,6 +296,39 @@ impl ElfPatcher {
         Ok(text_sections)
     }
 
+    /// Every `STT_FUNC` symbol with a non-zero size, in address order. Aliases
+    /// sharing a start address are reported once, under the first name in
+    /// the table. A binary without a symbol table (stripped) has none.
+    pub fn function_symbols(&self) -> Result<Vec<FunctionSymbol>, Box<dyn std::error::Error>> {
+        let elf = ElfBytes::<AnyEndian>::minimal_parse(&self.file_data)?;
+        let Some((symbols, names)) = elf.symbol_table()? else {
+            return Ok(Vec::new());
+        };
+
+        let mut functions = Vec::new();
+        for symbol in symbols.iter() {
+            if symbol.st_symtype() != elf::abi::STT_FUNC || symbol.st_size == 0 {
+                continue;
+            }
+            let end = symbol.st_value.checked_add(symbol.st_size).ok_or_else(|| {
+                format!(
+                    "Function symbol at 0x{:x} with size {} overflows",
+                    symbol.st_value, symbol.st_size
+                )
+            })?;
+            functions.push(FunctionSymbol {
+                name: names.get(symbol.st_name as usize)?.to_string(),
+                window: AddressWindow {
+                    start: symbol.st_value,
+                    end,
+                },
+            });
+        }
+        functions.sort_by_key(|function| function.window.start);
+        functions.dedup_by_key(|function| function.window.start);
+        Ok(functions)
+    }
+
     pub fn validate_address_window(&self, window: &AddressWindow) -> Result<TextSection, String> {
         let text_sections = self
             .get_text_sections()
@@ -168,31 +337,107 @@ impl ElfPatcher {
         // Find which section contains this address window
         for section in text_sections {
             let section_start = section.virtual_addr;
-            let section_end = section.virtual_addr + section.size;
+            let Some(section_end) = section.virtual_addr.checked_add(section.size) else {
+                return Err(format!(
+                    "Section {} address range 0x{:x}+0x{:x} overflows",
+                    section.name, section.virtual_addr, section.size
+                ));
+            };
 
             if window.start >= section_start && window.end <= section_end {
-                if window.start >= window.end {
-                    return Err("Start address must be less than end address".to_string());
-                }
-
-                let align = self.arch.instruction_alignment();
-                if align > 1
-                    && (!window.start.is_multiple_of(align) || !window.end.is_multiple_of(align))
-                {
-                    return Err(format!(
-                        "Addresses must be {}-byte aligned for {:?} instructions",
-                        align, self.arch
-                    ));
-                }
-
+                window.validate(self.arch)?;
                 return Ok(section);
             }
         }
 
-        Err(format!(
+        Err(self.describe_window_miss(window))
+    }
+
+    /// Explain why `window` fits no executable section. Distinguishes a
+    /// window that straddles two sections, one that runs off the end of its
+    /// section, and one that lies in a non-executable section, falling back
+    /// to the generic message when neither end lands in a mapped section.
+    fn describe_window_miss(&self, window: &AddressWindow) -> String {
+        let generic = format!(
             "Address window 0x{:x}-0x{:x} is not within any executable section",
             window.start, window.end
-        ))
+        );
+        let Ok(sections) = self.mapped_sections() else {
+            return generic;
+        };
+
+        // `end` is exclusive: the last byte of the window is `end - 1`.
+        let last = window.end.saturating_sub(1).max(window.start);
+        let containing = |addr: u64| {
+            sections
+                .iter()
+                .find(|(s, _)| addr >= s.virtual_addr && addr - s.virtual_addr < s.size)
+        };
+        let describe = |(section, executable): &(TextSection, bool)| {
+            format!(
+                "{} {} (0x{:x}-0x{:x})",
+                if *executable {
+                    "executable section"
+                } else {
+                    "non-executable section"
+                },
+                section.name,
+                section.virtual_addr,
+                section.virtual_addr.saturating_add(section.size)
+            )
+        };
+
+        match (containing(window.start), containing(last)) {
+            (Some(first), Some(second)) if first.0.name != second.0.name => format!(
+                "{}: it straddles two sections, starting in {} and ending in {}",
+                generic,
+                describe(first),
+                describe(second)
+            ),
+            (Some(section), _) | (None, Some(section)) if !section.1 => {
+                format!("{}: it lies in {}", generic, describe(section))
+            }
+            (Some(section), None) => format!(
+                "{}: it starts in {} but ends outside any section",
+                generic,
+                describe(section)
+            ),
+            (None, Some(section)) => format!(
+                "{}: it ends in {} but starts outside any section",
+                generic,
+                describe(section)
+            ),
+            _ => generic,
+        }
+    }
+
+    /// Every allocated, non-empty section paired with whether it is
+    /// executable. Used only for window diagnostics.
+    fn mapped_sections(&self) -> Result<Vec<(TextSection, bool)>, Box<dyn std::error::Error>> {
+        let elf = ElfBytes::<AnyEndian>::minimal_parse(&self.file_data)?;
+        let (section_headers, string_table) = elf.section_headers_with_strtab()?;
+        let section_headers = section_headers.ok_or("Failed to get section headers")?;
+        let string_table = string_table.ok_or("Failed to get string table")?;
+
+        let mut sections = Vec::new();
+        for section_header in section_headers.iter() {
+            if section_header.sh_flags & elf::abi::SHF_ALLOC as u64 == 0
+                || section_header.sh_size == 0
+            {
+                continue;
+            }
+            let section_name = string_table.get(section_header.sh_name as usize)?;
+            sections.push((
+                TextSection {
+                    name: section_name.to_string(),
+                    file_offset: section_header.sh_offset,
+                    virtual_addr: section_header.sh_addr,
+                    size: section_header.sh_size,
+                },
+                section_header.sh_flags & elf::abi::SHF_EXECINSTR as u64 != 0,
+            ));
+        }
+        Ok(sections)
     }
 
     pub fn get_instructions_in_window(
@@ -203,17 +448,143 @@ impl ElfPatcher {
             .validate_address_window(window)
             .map_err(|e| format!("Invalid address window: {}", e))?;
 
+        let range = self.window_file_range(&section, window)?;
+        Ok(self.file_data[range].to_vec())
+    }
+
+    /// File byte range backing `window`, after the same validation as
+    /// [`ElfPatcher::get_instructions_in_window`].
+    pub fn window_file_span(
+        &self,
+        window: &AddressWindow,
+    ) -> Result<std::ops::Range<usize>, String> {
+        let section = self
+            .validate_address_window(window)
+            .map_err(|e| format!("Invalid address window: {}", e))?;
+        self.window_file_range(&section, window)
+    }
+
+    /// Every [`ByteDiff`] where the file at `other_path` differs from this
+    /// patcher's input, in offset order. A patched copy never changes size,
+    /// so a length mismatch is an error.
+    pub fn diff_against(
+        &self,
+        other_path: &Path,
+    ) -> Result<Vec<ByteDiff>, Box<dyn std::error::Error>> {
+        let other = fs::read(other_path)?;
+        if other.len() != self.file_data.len() {
+            return Err(format!(
+                "{} is {} bytes but the original is {} bytes",
+                other_path.display(),
+                other.len(),
+                self.file_data.len()
+            )
+            .into());
+        }
+        Ok(self
+            .file_data
+            .iter()
+            .zip(&other)
+            .enumerate()
+            .filter(|(_, (original, patched))| original != patched)
+            .map(|(offset, (&original, &patched))| (offset, original, patched))
+            .collect())
+    }
+
+    /// The differences [`ElfPatcher::diff_against`] finds outside the file
+    /// ranges of `windows`: bytes a patch of those windows must not touch,
+    /// such as headers or other sections.
+    pub fn diffs_outside_windows(
+        &self,
+        other_path: &Path,
+        windows: &[&AddressWindow],
+    ) -> Result<Vec<ByteDiff>, Box<dyn std::error::Error>> {
+        let spans = windows
+            .iter()
+            .map(|window| self.window_file_span(window))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(self
+            .diff_against(other_path)?
+            .into_iter()
+            .filter(|(offset, _, _)| !spans.iter().any(|span| span.contains(offset)))
+            .collect())
+    }
+
+    /// File byte range backing `window` inside `section`, which must already
+    /// contain it (see `validate_address_window`). Section headers come from
+    /// the file, so every offset is computed with checked arithmetic and
+    /// bounds-checked against the file size before anyone slices with it.
+    fn window_file_range(
+        &self,
+        section: &TextSection,
+        window: &AddressWindow,
+    ) -> Result<std::ops::Range<usize>, String> {
         let offset_in_section = window.start - section.virtual_addr;
         let length = window.end - window.start;
 
-        let file_start = section.file_offset + offset_in_section;
-        let file_end = file_start + length;
-
-        if file_end > self.file_data.len() as u64 {
-            return Err("Address window extends beyond file".into());
+        let file_start = section
+            .file_offset
+            .checked_add(offset_in_section)
+            .ok_or_else(|| {
+                format!(
+                    "Section {} file offset 0x{:x} overflows at window start 0x{:x}",
+                    section.name, section.file_offset, window.start
+                )
+            })?;
+        let file_len = self.file_data.len() as u64;
+        if file_start > file_len {
+            return Err(format!(
+                "Address window starts at file offset 0x{:x}, beyond the end of the file (0x{:x} bytes)",
+                file_start, file_len
+            ));
+        }
+        let file_end = file_start.checked_add(length).ok_or_else(|| {
+            format!(
+                "Address window file range 0x{:x}+0x{:x} overflows",
+                file_start, length
+            )
+        })?;
+        if file_end > file_len {
+            return Err("Address window extends beyond file".to_string());
         }
 
-        Ok(self.file_data[file_start as usize..file_end as usize].to_vec())
+        Ok(file_start as usize..file_end as usize)
+    }
+
+    /// Build the [`PatchPlan`] replacing `window` with `new_bytes`, which
+    /// must fit in it. Reads the window's current bytes; writes nothing.
+    pub fn plan_patch<I>(
+        &self,
+        window: &AddressWindow,
+        original: Vec<I>,
+        optimized: Vec<I>,
+        new_bytes: Vec<u8>,
+        cost_before: u64,
+        cost_after: u64,
+    ) -> Result<PatchPlan<I>, Box<dyn std::error::Error>> {
+        let original_bytes = self.get_instructions_in_window(window)?;
+        if new_bytes.len() > original_bytes.len() {
+            return Err(format!(
+                "New code ({} bytes) is larger than window size ({} bytes)",
+                new_bytes.len(),
+                original_bytes.len()
+            )
+            .into());
+        }
+        let nops_added = self
+            .arch
+            .nop_padding_count(original_bytes.len() - new_bytes.len());
+        Ok(PatchPlan {
+            window: window.clone(),
+            original,
+            optimized,
+            original_bytes,
+            new_bytes,
+            nops_added,
+            cost_before,
+            cost_after,
+            replaced: None,
+        })
     }
 
     pub fn create_patched_copy(
@@ -221,6 +592,41 @@ impl ElfPatcher {
         output_path: &Path,
         window: &AddressWindow,
         new_code: &[u8],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.create_multi_patched_copy(output_path, &[(window, new_code)])
+    }
+
+    /// Write a copy of the input with each `(window, new_code)` patch
+    /// applied, NOP-padding every window its code does not fill. Rejects
+    /// overlapping windows before writing anything.
+    pub fn create_multi_patched_copy(
+        &self,
+        output_path: &Path,
+        patches: &[(&AddressWindow, &[u8])],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let windows: Vec<AddressWindow> = patches
+            .iter()
+            .map(|(window, _)| (*window).clone())
+            .collect();
+        validate_non_overlapping(&windows)?;
+
+        // Create a copy of the original file data
+        let mut patched_data = self.file_data.clone();
+        for (window, new_code) in patches {
+            self.patch_window(&mut patched_data, window, new_code)?;
+        }
+
+        // Write the patched file
+        fs::write(output_path, patched_data)?;
+
+        Ok(())
+    }
+
+    fn patch_window(
+        &self,
+        patched_data: &mut [u8],
+        window: &AddressWindow,
+        new_code: &[u8],
     ) -> Result<(), Box<dyn std::error::Error>> {
         let section = self
             .validate_address_window(window)
@@ -237,12 +643,8 @@ impl ElfPatcher {
             .into());
         }
 
-        // Create a copy of the original file data
-        let mut patched_data = self.file_data.clone();
-
         // Calculate file offset for the patch
-        let offset_in_section = window.start - section.virtual_addr;
-        let file_offset = (section.file_offset + offset_in_section) as usize;
+        let file_offset = self.window_file_range(&section, window)?.start;
 
         // Apply the patch
         let patch_end = file_offset + new_code.len();
@@ -264,13 +666,29 @@ impl ElfPatcher {
             }
         }
 
-        // Write the patched file
-        fs::write(output_path, patched_data)?;
-
         Ok(())
     }
 }
 
+/// Check that no two of `windows` share a byte. Each patch, NOP padding
+/// included, stays inside its own window, so disjoint windows can never
+/// write into each other. Touching windows (`a.end == b.start`) are fine;
+/// the error names both ranges of the first overlap found.
+pub fn validate_non_overlapping(windows: &[AddressWindow]) -> Result<(), String> {
+    let mut sorted: Vec<&AddressWindow> = windows.iter().collect();
+    sorted.sort_by_key(|window| (window.start, window.end));
+    for pair in sorted.windows(2) {
+        let (first, second) = (pair[0], pair[1]);
+        if first.end > second.start {
+            return Err(format!(
+                "Address windows 0x{:x}-0x{:x} and 0x{:x}-0x{:x} overlap",
+                first.start, first.end, second.start, second.end
+            ));
+        }
+    }
+    Ok(())
+}
+
 pub fn parse_hex_address(addr_str: &str) -> Result<u64, String> {
     let addr_str = if addr_str.starts_with("0x") || addr_str.starts_with("0X") {
         &addr_str[2..]
@@ -292,6 +710,45 @@ mod tests {
         assert_eq!(DetectedArch::X86_32.instruction_alignment(), 1);
     }
 
+    #[test]
+    fn address_window_parses_start_dash_end() {
+        let window: AddressWindow = "0x1000-0x1100".parse().unwrap();
+        assert_eq!(
+            window,
+            AddressWindow {
+                start: 0x1000,
+                end: 0x1100
+            }
+        );
+        assert_eq!(
+            "1000-0X1010".parse::<AddressWindow>(),
+            Ok(AddressWindow {
+                start: 0x1000,
+                end: 0x1010
+            })
+        );
+        assert!("0x1000".parse::<AddressWindow>().is_err());
+        assert!("0x1000-zz".parse::<AddressWindow>().is_err());
+    }
+
+    #[test]
+    fn address_window_rejects_reversed_and_empty_ranges() {
+        let err = "0x1100-0x1000".parse::<AddressWindow>().unwrap_err();
+        assert!(err.contains("start must be less than end"), "{err}");
+        assert!("0x1000-0x1000".parse::<AddressWindow>().is_err());
+    }
+
+    #[test]
+    fn address_window_alignment_is_checked_per_arch() {
+        let window: AddressWindow = "0x1002-0x1010".parse().unwrap();
+        let err = window.validate(DetectedArch::Aarch64).unwrap_err();
+        assert!(err.contains("4-byte aligned"), "{err}");
+        assert_eq!(window.validate(DetectedArch::X86_64), Ok(()));
+
+        let window: AddressWindow = "0x1000-0x1010".parse().unwrap();
+        assert_eq!(window.validate(DetectedArch::Aarch64), Ok(()));
+    }
+
     #[test]
     fn x86_nop_sequence_canonical_five_byte() {
         assert_eq!(
@@ -376,6 +833,15 @@ mod tests {
         }
     }
 
+    #[test]
+    fn nop_padding_count_matches_the_sequences_handed_out() {
+        assert_eq!(DetectedArch::Aarch64.nop_padding_count(0), 0);
+        assert_eq!(DetectedArch::Aarch64.nop_padding_count(12), 3);
+        assert_eq!(DetectedArch::X86_64.nop_padding_count(9), 1);
+        assert_eq!(DetectedArch::X86_64.nop_padding_count(20), 3);
+        assert_eq!(DetectedArch::X86_32.nop_padding_count(5), 5);
+    }
+
     #[test]
     fn detected_arch_from_e_machine() {
         assert_eq!(
@@ -419,17 +885,42 @@ mod tests {
         assert!(invalid_window.start >= invalid_window.end);
     }
 
+    /// One section of a hand-rolled test ELF: name, `sh_flags`, `sh_addr`
+    /// and contents.
+    struct TestSection<'a> {
+        name: &'a str,
+        flags: u64,
+        vaddr: u64,
+        bytes: &'a [u8],
+    }
+
     /// Hand-rolled minimal ELF64 used only by integration tests in this
-    /// module. Layout: header, .text data, .shstrtab data, then a section
-    /// header table with NULL / .text / .shstrtab. Only the fields
-    /// `ElfPatcher` actually reads are populated.
-    fn build_minimal_elf64(text_bytes: &[u8], text_vaddr: u64, machine: u16) -> Vec<u8> {
+    /// module. Layout: header, section data in order, .shstrtab data, then a
+    /// section header table with NULL / `sections` / .shstrtab. Only the
+    /// fields `ElfPatcher` actually reads are populated. The first section's
+    /// data always starts at file offset 64.
+    fn build_elf64(machine: u16, sections: &[TestSection]) -> Vec<u8> {
         let elf_header_size = 64usize;
         let shentsize = 64usize;
-        let shnum = 3usize;
-        let shstrtab: &[u8] = b"\0.text\0.shstrtab\0";
-        let text_offset = elf_header_size;
-        let shstrtab_offset = text_offset + text_bytes.len();
+        let shnum = sections.len() + 2;
+
+        let mut shstrtab = vec![0u8];
+        let mut name_offsets = Vec::new();
+        for section in sections {
+            name_offsets.push(shstrtab.len());
+            shstrtab.extend_from_slice(section.name.as_bytes());
+            shstrtab.push(0);
+        }
+        let shstrtab_name = shstrtab.len();
+        shstrtab.extend_from_slice(b".shstrtab\0");
+
+        let mut data_offsets = Vec::new();
+        let mut cursor = elf_header_size;
+        for section in sections {
+            data_offsets.push(cursor);
+            cursor += section.bytes.len();
+        }
+        let shstrtab_offset = cursor;
         let shoff = shstrtab_offset + shstrtab.len();
         let total_size = shoff + shentsize * shnum;
 
@@ -446,10 +937,12 @@ mod tests {
         buf[52..54].copy_from_slice(&(elf_header_size as u16).to_le_bytes());
         buf[58..60].copy_from_slice(&(shentsize as u16).to_le_bytes());
         buf[60..62].copy_from_slice(&(shnum as u16).to_le_bytes());
-        buf[62..64].copy_from_slice(&2u16.to_le_bytes());
+        buf[62..64].copy_from_slice(&((shnum - 1) as u16).to_le_bytes());
 
-        buf[text_offset..text_offset + text_bytes.len()].copy_from_slice(text_bytes);
-        buf[shstrtab_offset..shstrtab_offset + shstrtab.len()].copy_from_slice(shstrtab);
+        for (section, &offset) in sections.iter().zip(&data_offsets) {
+            buf[offset..offset + section.bytes.len()].copy_from_slice(section.bytes);
+        }
+        buf[shstrtab_offset..shstrtab_offset + shstrtab.len()].copy_from_slice(&shstrtab);
 
         // `fields` follows the Elf64_Shdr layout:
         // fields[0] => sh_name (u32), fields[1] => sh_type (u32),
@@ -471,25 +964,27 @@ mod tests {
             buf[base + 56..base + 64].copy_from_slice(&fields[9].to_le_bytes());
         };
         write_shdr(0, [0; 10]);
+        for (index, section) in sections.iter().enumerate() {
+            write_shdr(
+                index + 1,
+                [
+                    name_offsets[index] as u64,
+                    elf::abi::SHT_PROGBITS as u64,
+                    section.flags,
+                    section.vaddr,
+                    data_offsets[index] as u64,
+                    section.bytes.len() as u64,
+                    0,
+                    0,
+                    1,
+                    0,
+                ],
+            );
+        }
         write_shdr(
-            1,
-            [
-                1,
-                elf::abi::SHT_PROGBITS as u64,
-                (elf::abi::SHF_ALLOC | elf::abi::SHF_EXECINSTR) as u64,
-                text_vaddr,
-                text_offset as u64,
-                text_bytes.len() as u64,
-                0,
-                0,
-                1,
-                0,
-            ],
-        );
-        write_shdr(
-            2,
+            shnum - 1,
             [
-                7,
+                shstrtab_name as u64,
                 elf::abi::SHT_STRTAB as u64,
                 0,
                 0,
@@ -505,6 +1000,18 @@ mod tests {
         buf
     }
 
+    fn build_minimal_elf64(text_bytes: &[u8], text_vaddr: u64, machine: u16) -> Vec<u8> {
+        build_elf64(
+            machine,
+            &[TestSection {
+                name: ".text",
+                flags: (elf::abi::SHF_ALLOC | elf::abi::SHF_EXECINSTR) as u64,
+                vaddr: text_vaddr,
+                bytes: text_bytes,
+            }],
+        )
+    }
+
     fn build_minimal_x86_64_elf(text_bytes: &[u8], text_vaddr: u64) -> Vec<u8> {
         build_minimal_elf64(text_bytes, text_vaddr, elf::abi::EM_X86_64)
     }
@@ -513,6 +1020,52 @@ mod tests {
         build_minimal_elf64(text_bytes, text_vaddr, elf::abi::EM_AARCH64)
     }
 
+    #[test]
+    fn get_instructions_in_window_rejects_overflowing_section_offset() {
+        use crate::test_utils::TempFile;
+
+        let text_vaddr: u64 = 0x1000;
+        let mut elf_bytes = build_minimal_x86_64_elf(&[0x90; 8], text_vaddr);
+        // Point .text's sh_offset (header 1, byte 24) so close to u64::MAX
+        // that adding the window's offset into the section wraps around.
+        let shoff = u64::from_le_bytes(elf_bytes[40..48].try_into().unwrap()) as usize;
+        let sh_offset = shoff + 64 + 24;
+        elf_bytes[sh_offset..sh_offset + 8].copy_from_slice(&(u64::MAX - 3).to_le_bytes());
+
+        let input = TempFile::new_bytes("s11-elf-overflow", "elf", &elf_bytes);
+        let patcher = ElfPatcher::new(input.path()).expect("headers still parse");
+
+        let err = patcher
+            .get_instructions_in_window(&AddressWindow {
+                start: text_vaddr + 4,
+                end: text_vaddr + 8,
+            })
+            .unwrap_err();
+        assert!(err.to_string().contains("overflows"), "{err}");
+
+        let err = patcher
+            .get_instructions_in_window(&AddressWindow {
+                start: text_vaddr,
+                end: text_vaddr + 2,
+            })
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("beyond the end of the file"),
+            "{err}"
+        );
+
+        let output = TempFile::new_bytes("s11-elf-overflow-out", "elf", &[]);
+        let window = AddressWindow {
+            start: text_vaddr + 4,
+            end: text_vaddr + 8,
+        };
+        assert!(
+            patcher
+                .create_patched_copy(output.path(), &window, &[0x90])
+                .is_err()
+        );
+    }
+
     #[test]
     fn create_patched_copy_emits_canonical_x86_nop_padding() {
         use crate::test_utils::TempFile;
@@ -581,6 +1134,143 @@ mod tests {
         );
     }
 
+    #[test]
+    fn apply_plans_writes_two_disjoint_windows_into_one_copy() {
+        use crate::test_utils::TempFile;
+
+        let text_vaddr: u64 = 0x100000;
+        let text_bytes = [0xdeu8; 32];
+        let elf_bytes = build_minimal_aarch64_elf(&text_bytes, text_vaddr);
+        let input = TempFile::new_bytes("s11-elf-multi-patch-in", "elf", &elf_bytes);
+        let output = TempFile::new_bytes("s11-elf-multi-patch-out", "elf", &[]);
+        let patcher = ElfPatcher::new(input.path()).expect("patcher should accept minimal ELF");
+
+        let plan = |start: u64, new_bytes: Vec<u8>| {
+            let window = AddressWindow {
+                start,
+                end: start + 8,
+            };
+            patcher
+                .plan_patch::<()>(&window, vec![], vec![], new_bytes, 2, 1)
+                .expect("plan should fit")
+        };
+        let plans = [
+            plan(text_vaddr + 16, vec![0x11, 0x22, 0x33, 0x44]),
+            plan(text_vaddr, vec![0xaa, 0xbb, 0xcc, 0xdd]),
+        ];
+        apply_plans(&patcher, &plans, output.path()).expect("disjoint windows should apply");
+
+        let patched = std::fs::read(output.path()).expect("output should be readable");
+        let text = &patched[64..64 + 32];
+        let nop = [0x1f, 0x20, 0x03, 0xd5];
+        assert_eq!(text[..4], [0xaa, 0xbb, 0xcc, 0xdd]);
+        assert_eq!(text[4..8], nop);
+        assert_eq!(text[8..16], [0xde; 8], "bytes between windows untouched");
+        assert_eq!(text[16..20], [0x11, 0x22, 0x33, 0x44]);
+        assert_eq!(text[20..24], nop);
+        assert_eq!(text[24..], [0xde; 8]);
+
+        let overlapping = [plan(text_vaddr, vec![]), plan(text_vaddr + 4, vec![])];
+        let err = apply_plans(&patcher, &overlapping, output.path()).unwrap_err();
+        assert!(err.to_string().contains("overlap"), "{err}");
+    }
+
+    #[test]
+    fn patching_the_mov_add_window_changes_only_the_window_bytes() {
+        use crate::test_utils::TempFile;
+
+        let text_vaddr: u64 = 0x100000;
+        // nop; mov x0, x1; add x0, x0, #1; nop
+        let text_bytes = [
+            0x1f, 0x20, 0x03, 0xd5, 0xe0, 0x03, 0x01, 0xaa, 0x00, 0x04, 0x00, 0x91, 0x1f, 0x20,
+            0x03, 0xd5,
+        ];
+        let elf_bytes = build_minimal_aarch64_elf(&text_bytes, text_vaddr);
+        let input = TempFile::new_bytes("s11-elf-diff-in", "elf", &elf_bytes);
+        let output = TempFile::new_bytes("s11-elf-diff-out", "elf", &[]);
+        let patcher = ElfPatcher::new(input.path()).expect("patcher should accept minimal ELF");
+
+        let window = AddressWindow {
+            start: text_vaddr + 4,
+            end: text_vaddr + 12,
+        };
+        // add x0, x1, #1
+        patcher
+            .create_patched_copy(output.path(), &window, &[0x20, 0x04, 0x00, 0x91])
+            .expect("patch should apply");
+
+        let span = patcher.window_file_span(&window).unwrap();
+        assert_eq!(span, 64 + 4..64 + 12);
+        let diffs = patcher.diff_against(output.path()).unwrap();
+        assert!(!diffs.is_empty());
+        assert!(
+            diffs.iter().all(|(offset, _, _)| span.contains(offset)),
+            "{diffs:?}"
+        );
+        assert_eq!(diffs[0], (64 + 4, 0xe0, 0x20));
+        assert!(
+            patcher
+                .diffs_outside_windows(output.path(), &[&window])
+                .unwrap()
+                .is_empty()
+        );
+
+        // A stray write to the ELF header is caught.
+        let mut corrupted = std::fs::read(output.path()).unwrap();
+        corrupted[7] ^= 0xff;
+        std::fs::write(output.path(), &corrupted).unwrap();
+        assert_eq!(
+            patcher
+                .diffs_outside_windows(output.path(), &[&window])
+                .unwrap(),
+            vec![(7, elf_bytes[7], elf_bytes[7] ^ 0xff)]
+        );
+
+        std::fs::write(output.path(), &corrupted[1..]).unwrap();
+        let err = patcher.diff_against(output.path()).unwrap_err();
+        assert!(
+            err.to_string().contains("bytes but the original is"),
+            "{err}"
+        );
+    }
+
+    fn window(start: u64, end: u64) -> AddressWindow {
+        AddressWindow { start, end }
+    }
+
+    #[test]
+    fn validate_non_overlapping_allows_touching_windows() {
+        let windows = [window(0x1008, 0x1010), window(0x1000, 0x1008)];
+        assert_eq!(validate_non_overlapping(&windows), Ok(()));
+    }
+
+    #[test]
+    fn validate_non_overlapping_allows_windows_in_different_sections() {
+        // .text at 0x1000 and .init at 0x2000, as in the two-section fixture.
+        let windows = [window(0x2000, 0x2004), window(0x1000, 0x1010)];
+        assert_eq!(validate_non_overlapping(&windows), Ok(()));
+    }
+
+    #[test]
+    fn validate_non_overlapping_rejects_one_instruction_overlap_naming_both() {
+        let windows = [window(0x1000, 0x100c), window(0x1008, 0x1010)];
+        assert_eq!(
+            validate_non_overlapping(&windows),
+            Err("Address windows 0x1000-0x100c and 0x1008-0x1010 overlap".to_string())
+        );
+    }
+
+    #[test]
+    fn validate_non_overlapping_rejects_nested_and_duplicate_windows() {
+        assert!(
+            validate_non_overlapping(&[window(0x1000, 0x1010), window(0x1004, 0x1008)]).is_err()
+        );
+        assert!(
+            validate_non_overlapping(&[window(0x1000, 0x1004), window(0x1000, 0x1004)]).is_err()
+        );
+        assert_eq!(validate_non_overlapping(&[]), Ok(()));
+    }
+
     #[test]
     fn create_patched_copy_emits_no_aarch64_padding_when_payload_fills_window() {
         use crate::test_utils::TempFile;
@@ -766,4 +1456,92 @@ mod tests {
 
         // TempFile::drop tolerates a missing file (test_utils.rs:33-37).
     }
+
+    fn two_section_patcher(prefix: &str, second_name: &str, second_flags: u64) -> ElfPatcher {
+        use crate::test_utils::TempFile;
+
+        let code = [0x1fu8, 0x20, 0x03, 0xd5].repeat(4);
+        let elf_bytes = build_elf64(
+            elf::abi::EM_AARCH64,
+            &[
+                TestSection {
+                    name: ".text",
+                    flags: (elf::abi::SHF_ALLOC | elf::abi::SHF_EXECINSTR) as u64,
+                    vaddr: 0x1000,
+                    bytes: &code,
+                },
+                TestSection {
+                    name: second_name,
+                    flags: second_flags,
+                    vaddr: 0x1010,
+                    bytes: &code,
+                },
+            ],
+        );
+        let input = TempFile::new_bytes(prefix, "elf", &elf_bytes);
+        ElfPatcher::new(input.path()).expect("patcher should accept two-section ELF")
+    }
+
+    #[test]
+    fn validate_address_window_reports_straddled_sections() {
+        let patcher = two_section_patcher(
+            "s11-elf-straddle",
+            ".text.hot",
+            (elf::abi::SHF_ALLOC | elf::abi::SHF_EXECINSTR) as u64,
+        );
+
+        let err = patcher
+            .validate_address_window(&AddressWindow {
+                start: 0x1008,
+                end: 0x1018,
+            })
+            .expect_err("a window spanning two sections must be rejected");
+        assert!(err.contains("straddles two sections"), "{err}");
+        assert!(
+            err.contains("starting in executable section .text (0x1000-0x1010)"),
+            "{err}"
+        );
+        assert!(
+            err.contains("ending in executable section .text.hot (0x1010-0x1020)"),
+            "{err}"
+        );
+
+        // Each half on its own is fine.
+        assert!(
+            patcher
+                .validate_address_window(&AddressWindow {
+                    start: 0x1010,
+                    end: 0x1018,
+                })
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn validate_address_window_names_non_executable_section() {
+        let patcher = two_section_patcher("s11-elf-rodata", ".rodata", elf::abi::SHF_ALLOC as u64);
+
+        let err = patcher
+            .validate_address_window(&AddressWindow {
+                start: 0x1010,
+                end: 0x1018,
+            })
+            .expect_err("a window in .rodata must be rejected");
+        assert!(err.contains("not within any executable section"), "{err}");
+        assert!(
+            err.contains("lies in non-executable section .rodata (0x1010-0x1020)"),
+            "{err}"
+        );
+
+        let err = patcher
+            .validate_address_window(&AddressWindow {
+                start: 0x2000,
+                end: 0x2004,
+            })
+            .expect_err("a window outside every section must be rejected");
+        assert_eq!(
+            err,
+            "Address window 0x2000-0x2004 is not within any executable section"
+        );
+    }
 }
@@ -0,0 +1,149 @@
+//! `proptest` generators for the IR.
+//!
+//! Compiled for the crate's own tests and, for downstream property tests,
+//! behind the `proptest` feature. [`Instruction`] draws from the same pool
+//! the enumerative search walks (`generate_all_instructions`), keeping only
+//! encodable, non-terminator forms, so a property never sees an instruction
+//! the assembler would reject.
+
+use proptest::prelude::*;
+use proptest::sample::select;
+
+use crate::ir::types::NORMAL_CONDITIONS;
+use crate::ir::{Condition, Instruction, Operand, Register, ShiftKind};
+use crate::search::candidate::generate_all_instructions;
+use crate::search::config::DEFAULT_IMMEDIATES;
+
+/// The register and immediate pool an arbitrary [`Instruction`] is built
+/// from. The default is x0-x3 and [`DEFAULT_IMMEDIATES`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstructionPool {
+    pub registers: Vec<Register>,
+    pub immediates: Vec<i64>,
+}
+
+impl Default for InstructionPool {
+    fn default() -> Self {
+        Self {
+            registers: vec![Register::X0, Register::X1, Register::X2, Register::X3],
+            immediates: DEFAULT_IMMEDIATES.to_vec(),
+        }
+    }
+}
+
+/// X0-X30 and XZR; SP is only legal in a few operand slots.
+impl Arbitrary for Register {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        select(
+            (0..=31)
+                .filter_map(Register::from_index)
+                .collect::<Vec<_>>(),
+        )
+        .boxed()
+    }
+}
+
+/// The fourteen conditions other than AL and NV.
+impl Arbitrary for Condition {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        select(NORMAL_CONDITIONS.to_vec()).boxed()
+    }
+}
+
+/// A register, a 12-bit immediate, or a register shifted by 0-63.
+impl Arbitrary for Operand {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        let kinds = vec![
+            ShiftKind::Lsl,
+            ShiftKind::Lsr,
+            ShiftKind::Asr,
+            ShiftKind::Ror,
+        ];
+        prop_oneof![
+            any::<Register>().prop_map(Operand::Register),
+            (0i64..=0xfff).prop_map(Operand::Immediate),
+            (any::<Register>(), select(kinds), 0u8..64)
+                .prop_map(|(reg, kind, amount)| Operand::ShiftedRegister { reg, kind, amount }),
+        ]
+        .boxed()
+    }
+}
+
+impl Arbitrary for Instruction {
+    type Parameters = InstructionPool;
+    type Strategy = BoxedStrategy<Self>;
+
+    /// # Panics
+    ///
+    /// Panics if `pool` yields no encodable instruction (e.g. no registers).
+    fn arbitrary_with(pool: InstructionPool) -> Self::Strategy {
+        let instructions: Vec<Instruction> =
+            generate_all_instructions(&pool.registers, &pool.immediates)
+                .into_iter()
+                .filter(|instr| instr.is_encodable_aarch64() && !instr.is_terminator())
+                .collect();
+        select(instructions).boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assembler::AArch64Assembler;
+    use crate::capstone_bridge::{ConvertOutcome, convert_capstone_op};
+    use capstone::prelude::*;
+
+    fn assemble(instruction: Instruction) -> Vec<u8> {
+        AArch64Assembler::new()
+            .assemble_instructions(&[instruction], 0x1000)
+            .expect("encodable instruction should assemble")
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(256))]
+
+        // Disassembling an arbitrary instruction and converting it back may
+        // land on an alias spelling, so compare the re-assembled bytes.
+        #[test]
+        fn encodable_instructions_round_trip_through_capstone(instruction in any::<Instruction>()) {
+            let cs = Capstone::new()
+                .arm64()
+                .mode(capstone::arch::arm64::ArchMode::Arm)
+                .build()
+                .unwrap();
+            let bytes = assemble(instruction);
+            let disassembled = cs.disasm_all(&bytes, 0x1000).unwrap();
+            prop_assert_eq!(disassembled.len(), 1);
+            let insn = disassembled.iter().next().unwrap();
+            let (mnemonic, op_str) = (insn.mnemonic().unwrap_or(""), insn.op_str().unwrap_or(""));
+            let converted = match convert_capstone_op(mnemonic, op_str) {
+                ConvertOutcome::Instruction(converted) => converted,
+                other => panic!("{instruction} did not convert back: {other:?}"),
+            };
+            prop_assert_eq!(assemble(converted), bytes, "{} -> {}", instruction, converted);
+        }
+
+        #[test]
+        fn arbitrary_instructions_stay_within_the_pool(
+            instruction in any_with::<Instruction>(InstructionPool {
+                registers: vec![Register::X5, Register::X6],
+                immediates: vec![3],
+            })
+        ) {
+            prop_assert!(instruction
+                .source_registers()
+                .into_iter()
+                .chain(instruction.destinations())
+                .all(|reg| matches!(reg, Register::X5 | Register::X6 | Register::XZR)));
+        }
+    }
+}
@@ -5,6 +5,7 @@ use crate::ir::types::{
     AccessWidth, AddressOperand, Condition, ExtendKind, IndexMode, LabelId, Operand,
     PairAccessWidth, Register, RegisterWidth, ShiftKind, VectorArrangement, VectorRegister,
 };
+use smallvec::{SmallVec, smallvec};
 use std::fmt;
 
 /// Legal `lsl` amounts for the move-wide immediate family (MOVN / MOVZ / MOVK).
@@ -17,6 +18,10 @@ pub const MOVW_LEGAL_SHIFTS: [u8; 4] = [0, 16, 32, 48];
 /// mutation helpers for LSL/LSR/ASR/ROR.
 pub(crate) const AARCH64_RANDOM_SHIFT_IMMEDIATES: [i64; 6] = [1, 2, 4, 8, 16, 32];
 
+/// [`AARCH64_RANDOM_SHIFT_IMMEDIATES`] plus the trivial `#0`, sampled when a
+/// search sets `SearchConfig::include_trivial_shifts`.
+pub(crate) const AARCH64_RANDOM_SHIFT_IMMEDIATES_WITH_ZERO: [i64; 7] = [0, 1, 2, 4, 8, 16, 32];
+
 pub(crate) fn logical_imm32_value(imm: i64) -> Option<u32> {
     if imm >= 0 {
         u32::try_from(imm).ok()
@@ -749,6 +754,60 @@ impl Instruction {
                 | Instruction::Br { .. }
         )
     }
+
+    /// Bytes `AArch64Assembler` emits for this instruction: one 4-byte word,
+    /// except a `MovImm` wider than 16 bits that no single MOVN covers, which
+    /// lowers to one MOVZ/MOVK word per nonzero halfword. Must stay in sync
+    /// with the assembler's `MovImm` lowering.
+    pub fn encoded_length(&self) -> usize {
+        match self {
+            Instruction::MovImm { imm, .. } if *imm > 0xFFFF && movn_chunk(*imm).is_none() => {
+                4 * move_wide_chunks(*imm).len()
+            }
+            _ => 4,
+        }
+    }
+}
+
+/// The `(imm16, shift)` halfwords a MOVZ/MOVK chain needs to materialize
+/// `imm`, lowest first. Only nonzero chunks are listed (zero yields a single
+/// `(0, 0)`), so the length is the instruction count of the chain.
+pub fn move_wide_chunks(imm: i64) -> Vec<(u16, u8)> {
+    let bits = imm as u64;
+    let chunks: Vec<(u16, u8)> = (0..4u8)
+        .map(|i| ((bits >> (16 * i)) as u16, 16 * i))
+        .filter(|(chunk, _)| *chunk != 0)
+        .collect();
+    if chunks.is_empty() {
+        vec![(0, 0)]
+    } else {
+        chunks
+    }
+}
+
+/// The `(imm16, shift)` of the single MOVN that materializes `imm`, when
+/// `!imm` is one 16-bit chunk at a halfword shift (e.g. `-1` is
+/// `movn #0`). `None` otherwise.
+pub fn movn_chunk(imm: i64) -> Option<(u16, u8)> {
+    let inverted = !(imm as u64);
+    (0..4u8)
+        .map(|i| 16 * i)
+        .find(|shift| inverted & !(0xFFFF_u64 << shift) == 0)
+        .map(|shift| ((inverted >> shift) as u16, shift))
+}
+
+/// The `(imm12, shifted)` encoding of an ADD/SUB-family immediate: `imm`
+/// itself when it fits 12 bits, else `imm >> 12` under `lsl #12` when the
+/// low 12 bits are clear (`#0x1000` is `#1, lsl #12`). `None` when neither
+/// form reaches it, e.g. `#0x1001`.
+pub fn add_sub_imm12(imm: i64) -> Option<(u32, bool)> {
+    if (0..=0xFFF).contains(&imm) {
+        Some((imm as u32, false))
+    } else if imm & 0xFFF == 0 && (0..=0xFFF << 12).contains(&imm) {
+        Some(((imm >> 12) as u32, true))
+    } else {
+        None
+    }
 }
 
 /// Split an instruction sequence into `(prefix, terminator)`. Returns the
@@ -827,9 +886,11 @@ impl Instruction {
     /// Check if this instruction can be encoded in AArch64 machine code.
     ///
     /// This validates immediate operand ranges against AArch64 encoding constraints:
-    /// - MOV immediate: 0 to 0xFFFF (16-bit)
-    /// - ADD/SUB immediate: 0 to 0xFFF (12-bit unsigned); rd/rn ≠ XZR (Xn|SP slot, SP allowed)
-    /// - CMP/CMN immediate: 0 to 0xFFF (12-bit unsigned); rn ≠ XZR (Xn|SP slot, SP allowed)
+    /// - MOV immediate: a single MOVZ (0 to 0xFFFF) or MOVN, or any other
+    ///   non-negative value as a MOVZ/MOVK chain (see [`move_wide_chunks`])
+    /// - ADD/SUB immediate: 0 to 0xFFF, or a multiple of 0x1000 up to 0xFFF000
+    ///   (`lsl #12`; see [`add_sub_imm12`]); rd/rn ≠ XZR (Xn|SP slot, SP allowed)
+    /// - CMP/CMN immediate: as ADD/SUB; rn ≠ XZR (Xn|SP slot, SP allowed)
     /// - LSL/LSR/ASR immediate: 0 to 63
     /// - AND/ORR/EOR immediate: register, or encodable bitmask immediate
     ///   (rd ≠ XZR for the imm form — Xn|SP slot rejects the zero register)
@@ -841,13 +902,18 @@ impl Instruction {
                 is_x_or_xzr(*rd) && is_x_or_xzr(*rn)
             }
 
-            // MOV immediate: 16-bit range
-            Instruction::MovImm { rd, imm } => is_x_or_xzr(*rd) && *imm >= 0 && *imm <= 0xFFFF,
+            // MOV immediate: what the assembler emits — one MOVN for a value
+            // whose complement is a single halfword, else a MOVZ/MOVK chain,
+            // which only reaches non-negative values.
+            Instruction::MovImm { rd, imm } => {
+                is_x_or_xzr(*rd) && (*imm >= 0 || movn_chunk(*imm).is_some())
+            }
             Instruction::Movi { imm, .. } => *imm == 0,
             Instruction::MovFromVectorLane { rd, lane, .. } => is_x_or_xzr(*rd) && *lane < 2,
             Instruction::VectorAdd { .. } => true,
 
-            // ADD/SUB: register or immediate (12-bit unsigned), or shifted-register
+            // ADD/SUB: register or immediate (12-bit unsigned, optionally
+            // `lsl #12`), or shifted-register
             // (LSL/LSR/ASR only — ROR not encodable for arithmetic shifted-register form).
             // Shifted-register form forbids SP for any operand (ARM v8 spec).
             Instruction::Add { rd, rn, rm } | Instruction::Sub { rd, rn, rm } => match rm {
@@ -856,7 +922,9 @@ impl Instruction {
                 // permitted but XZR (also reg 31) must be rejected — it would
                 // alias to SP. Mirrors the assembler's register_to_dynasm_xsp
                 // so can_assemble() stays consistent with the real encoder.
-                Operand::Immediate(imm) => *imm >= 0 && *imm <= 0xFFF && is_xsp(*rd) && is_xsp(*rn),
+                Operand::Immediate(imm) => {
+                    add_sub_imm12(*imm).is_some() && is_xsp(*rd) && is_xsp(*rn)
+                }
                 Operand::ShiftedRegister { reg, kind, amount } => {
                     *kind != ShiftKind::Ror
                         && *amount <= 63
@@ -872,7 +940,9 @@ impl Instruction {
             },
             Instruction::AddW { rd, rn, rm } | Instruction::SubW { rd, rn, rm } => match rm {
                 Operand::Register(reg) => is_x_or_xzr(*rd) && is_x_or_xzr(*rn) && is_x_or_xzr(*reg),
-                Operand::Immediate(imm) => *imm >= 0 && *imm <= 0xFFF && is_xsp(*rd) && is_xsp(*rn),
+                Operand::Immediate(imm) => {
+                    add_sub_imm12(*imm).is_some() && is_xsp(*rd) && is_xsp(*rn)
+                }
                 Operand::ShiftedRegister { reg, kind, amount } => {
                     *kind != ShiftKind::Ror
                         && *amount <= 31
@@ -947,11 +1017,11 @@ impl Instruction {
                 is_x_or_xzr(*rd) && is_x_or_xzr(*rn) && is_x_or_xzr(*rm) && is_x_or_xzr(*ra)
             }
 
-            // CMP/CMN: register, immediate (12-bit unsigned), or shifted-register
+            // CMP/CMN: register, immediate (as ADD/SUB), or shifted-register
             // (LSL/LSR/ASR only — ROR not encodable for arithmetic shifted-register form).
             Instruction::Cmp { rn, rm } | Instruction::Cmn { rn, rm } => match rm {
                 Operand::Register(reg) => is_x_or_xzr(*rn) && is_x_or_xzr(*reg),
-                Operand::Immediate(imm) => *imm >= 0 && *imm <= 0xFFF && is_xsp(*rn),
+                Operand::Immediate(imm) => add_sub_imm12(*imm).is_some() && is_xsp(*rn),
                 Operand::ShiftedRegister { reg, kind, amount } => {
                     *kind != ShiftKind::Ror
                         && *amount <= 63
@@ -1018,12 +1088,12 @@ impl Instruction {
                 | Operand::ExtendedRegister { .. } => false,
             },
 
-            // ADDS/SUBS: register, imm 0..=0xFFF, or shifted-register
+            // ADDS/SUBS: register, immediate (as ADD/SUB), or shifted-register
             // (LSL/LSR/ASR only — ROR not encodable for arithmetic shifted-register form).
             Instruction::Adds { rd, rn, rm } | Instruction::Subs { rd, rn, rm } => match rm {
                 Operand::Register(reg) => is_x_or_xzr(*rd) && is_x_or_xzr(*rn) && is_x_or_xzr(*reg),
                 Operand::Immediate(imm) => {
-                    *imm >= 0 && *imm <= 0xFFF && is_x_or_xzr(*rd) && is_xsp(*rn)
+                    add_sub_imm12(*imm).is_some() && is_x_or_xzr(*rd) && is_xsp(*rn)
                 }
                 Operand::ShiftedRegister { reg, kind, amount } => {
                     *kind != ShiftKind::Ror
@@ -1219,6 +1289,105 @@ impl Instruction {
         }
     }
 
+    /// The literal immediate this instruction carries, if any: a `mov`
+    /// value or an immediate operand (shift amounts included). Bitfield
+    /// positions, move-wide chunks, condition-flag literals and branch
+    /// offsets are encoding fields rather than values and are not reported.
+    pub fn immediate(&self) -> Option<i64> {
+        match self {
+            Instruction::MovImm { imm, .. } => Some(*imm),
+            Instruction::Add { rm, .. }
+            | Instruction::AddW { rm, .. }
+            | Instruction::Sub { rm, .. }
+            | Instruction::SubW { rm, .. }
+            | Instruction::And { rm, .. }
+            | Instruction::Orr { rm, .. }
+            | Instruction::Eor { rm, .. }
+            | Instruction::Cmp { rm, .. }
+            | Instruction::Cmn { rm, .. }
+            | Instruction::Tst { rm, .. }
+            | Instruction::Ccmp { rm, .. }
+            | Instruction::Ccmn { rm, .. }
+            | Instruction::Bic { rm, .. }
+            | Instruction::Bics { rm, .. }
+            | Instruction::Orn { rm, .. }
+            | Instruction::Eon { rm, .. }
+            | Instruction::Adds { rm, .. }
+            | Instruction::Subs { rm, .. }
+            | Instruction::Ands { rm, .. }
+            | Instruction::Lsl { shift: rm, .. }
+            | Instruction::Lsr { shift: rm, .. }
+            | Instruction::Asr { shift: rm, .. }
+            | Instruction::Ror { shift: rm, .. } => match rm {
+                Operand::Immediate(imm) => Some(*imm),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// The same instruction with its two source operands exchanged, for the
+    /// commutative operations (`add`, `and`, `orr`, `eor`, `mul`, `cmn`).
+    /// `None` for everything else, when `rm` is not a plain register (an
+    /// immediate, shifted or extended operand cannot move into `rn`), and
+    /// when the swapped form has no encoding (SP is only legal in `rn`).
+    pub fn swap_operands(&self) -> Option<Instruction> {
+        let swapped = match *self {
+            Instruction::Add {
+                rd,
+                rn,
+                rm: Operand::Register(rm),
+            } => Instruction::Add {
+                rd,
+                rn: rm,
+                rm: Operand::Register(rn),
+            },
+            Instruction::And {
+                rd,
+                rn,
+                rm: Operand::Register(rm),
+                width,
+            } => Instruction::And {
+                rd,
+                rn: rm,
+                rm: Operand::Register(rn),
+                width,
+            },
+            Instruction::Orr {
+                rd,
+                rn,
+                rm: Operand::Register(rm),
+                width,
+            } => Instruction::Orr {
+                rd,
+                rn: rm,
+                rm: Operand::Register(rn),
+                width,
+            },
+            Instruction::Eor {
+                rd,
+                rn,
+                rm: Operand::Register(rm),
+                width,
+            } => Instruction::Eor {
+                rd,
+                rn: rm,
+                rm: Operand::Register(rn),
+                width,
+            },
+            Instruction::Mul { rd, rn, rm } => Instruction::Mul { rd, rn: rm, rm: rn },
+            Instruction::Cmn {
+                rn,
+                rm: Operand::Register(rm),
+            } => Instruction::Cmn {
+                rn: rm,
+                rm: Operand::Register(rn),
+            },
+            _ => return None,
+        };
+        swapped.is_encodable_aarch64().then_some(swapped)
+    }
+
     /// Get all source registers used by this instruction
     #[allow(dead_code)]
     pub fn source_registers(&self) -> Vec<Register> {
@@ -1381,6 +1550,349 @@ fn address_source_registers(addr: &AddressOperand) -> Vec<Register> {
     }
 }
 
+impl Instruction {
+    /// Return a copy of this instruction with every scalar register slot
+    /// (destinations, sources, shifted/extended operands, and address
+    /// base/index registers) rewritten through `f`. Vector registers are
+    /// left untouched. `MovK` / `Bfi` / `Bfxil` read and write the same
+    /// `rd` slot, so `f` sees it once.
+    #[must_use]
+    pub fn map_registers(&self, mut f: impl FnMut(Register) -> Register) -> Instruction {
+        let mut out = *self;
+        for slot in out.register_slots_mut() {
+            *slot = f(*slot);
+        }
+        out
+    }
+
+    fn register_slots_mut(&mut self) -> Vec<&mut Register> {
+        match self {
+            Instruction::MovReg { rd, rn }
+            | Instruction::MovRegW { rd, rn }
+            | Instruction::Clz { rd, rn }
+            | Instruction::Cls { rd, rn }
+            | Instruction::Rbit { rd, rn }
+            | Instruction::Rev { rd, rn }
+            | Instruction::Rev32 { rd, rn }
+            | Instruction::Rev16 { rd, rn }
+            | Instruction::Sxtb { rd, rn }
+            | Instruction::Sxth { rd, rn }
+            | Instruction::Sxtw { rd, rn }
+            | Instruction::Uxtb { rd, rn }
+            | Instruction::Uxth { rd, rn }
+            | Instruction::Ubfx { rd, rn, .. }
+            | Instruction::Sbfx { rd, rn, .. }
+            | Instruction::Bfi { rd, rn, .. }
+            | Instruction::Bfxil { rd, rn, .. }
+            | Instruction::Ubfiz { rd, rn, .. }
+            | Instruction::Sbfiz { rd, rn, .. } => vec![rd, rn],
+            Instruction::MovImm { rd, .. }
+            | Instruction::MovFromVectorLane { rd, .. }
+            | Instruction::MovN { rd, .. }
+            | Instruction::MovZ { rd, .. }
+            | Instruction::MovK { rd, .. }
+            | Instruction::Cset { rd, .. }
+            | Instruction::Csetm { rd, .. } => vec![rd],
+            Instruction::Movi { .. } | Instruction::VectorAdd { .. } => vec![],
+            Instruction::Add { rd, rn, rm }
+            | Instruction::AddW { rd, rn, rm }
+            | Instruction::Sub { rd, rn, rm }
+            | Instruction::SubW { rd, rn, rm }
+            | Instruction::And { rd, rn, rm, .. }
+            | Instruction::Orr { rd, rn, rm, .. }
+            | Instruction::Eor { rd, rn, rm, .. }
+            | Instruction::Bic { rd, rn, rm }
+            | Instruction::Bics { rd, rn, rm }
+            | Instruction::Orn { rd, rn, rm }
+            | Instruction::Eon { rd, rn, rm }
+            | Instruction::Adds { rd, rn, rm }
+            | Instruction::Subs { rd, rn, rm }
+            | Instruction::Ands { rd, rn, rm, .. }
+            | Instruction::Lsl {
+                rd, rn, shift: rm, ..
+            }
+            | Instruction::Lsr {
+                rd, rn, shift: rm, ..
+            }
+            | Instruction::Asr {
+                rd, rn, shift: rm, ..
+            }
+            | Instruction::Ror {
+                rd, rn, shift: rm, ..
+            } => {
+                let mut slots = vec![rd, rn];
+                slots.extend(operand_register_mut(rm));
+                slots
+            }
+            Instruction::Mul { rd, rn, rm }
+            | Instruction::Sdiv { rd, rn, rm }
+            | Instruction::Udiv { rd, rn, rm }
+            | Instruction::Mneg { rd, rn, rm }
+            | Instruction::Smulh { rd, rn, rm }
+            | Instruction::Umulh { rd, rn, rm }
+            | Instruction::Csel { rd, rn, rm, .. }
+            | Instruction::Csinc { rd, rn, rm, .. }
+            | Instruction::Csinv { rd, rn, rm, .. }
+            | Instruction::Csneg { rd, rn, rm, .. }
+            | Instruction::Adc { rd, rn, rm }
+            | Instruction::Adcs { rd, rn, rm }
+            | Instruction::Sbc { rd, rn, rm }
+            | Instruction::Sbcs { rd, rn, rm } => vec![rd, rn, rm],
+            Instruction::Madd { rd, rn, rm, ra } | Instruction::Msub { rd, rn, rm, ra } => {
+                vec![rd, rn, rm, ra]
+            }
+            Instruction::Cmp { rn, rm }
+            | Instruction::Cmn { rn, rm }
+            | Instruction::Tst { rn, rm, .. }
+            | Instruction::Ccmp { rn, rm, .. }
+            | Instruction::Ccmn { rn, rm, .. } => {
+                let mut slots = vec![rn];
+                slots.extend(operand_register_mut(rm));
+                slots
+            }
+            Instruction::Mvn { rd, rm }
+            | Instruction::Neg { rd, rm }
+            | Instruction::Negs { rd, rm } => {
+                vec![rd, rm]
+            }
+            Instruction::B { .. } | Instruction::BCond { .. } | Instruction::Bl { .. } => vec![],
+            Instruction::Cbz { rn, .. }
+            | Instruction::Cbnz { rn, .. }
+            | Instruction::Ret { rn }
+            | Instruction::Br { rn } => vec![rn],
+            Instruction::Tbz { rt, .. } | Instruction::Tbnz { rt, .. } => vec![rt],
+            Instruction::Ldr { rt, addr, .. }
+            | Instruction::Ldrs { rt, addr, .. }
+            | Instruction::Str { rt, addr, .. } => {
+                let mut slots = vec![rt];
+                slots.extend(address_registers_mut(addr));
+                slots
+            }
+            Instruction::Ldp { rt1, rt2, addr, .. } | Instruction::Stp { rt1, rt2, addr, .. } => {
+                let mut slots = vec![rt1, rt2];
+                slots.extend(address_registers_mut(addr));
+                slots
+            }
+        }
+    }
+}
+
+/// One operand of an [`Instruction`], as listed by [`Instruction::operands`].
+///
+/// Vector registers appear as `Register::Vector`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OperandSlot {
+    /// A register the instruction writes (`rd`, a load's `rt`).
+    Dest(Register),
+    /// A register the instruction reads (`rn`, a plain `rm`, a store's `rt`).
+    Src(Register),
+    /// A flexible second source: register, immediate, shifted or extended
+    /// register.
+    Operand(Operand),
+    /// The amount operand of `lsl`/`lsr`/`asr`/`ror`.
+    Shift(Operand),
+    /// The condition of a conditional select, compare, set or branch.
+    Cond(Condition),
+}
+
+/// A mutable view of one operand slot; the single per-variant table behind
+/// `operands` and `with_operands`.
+enum OperandRef<'a> {
+    Dest(&'a mut Register),
+    DestVector(&'a mut VectorRegister),
+    Src(&'a mut Register),
+    SrcVector(&'a mut VectorRegister),
+    Operand(&'a mut Operand),
+    Shift(&'a mut Operand),
+    Cond(&'a mut Condition),
+}
+
+impl OperandRef<'_> {
+    fn get(&self) -> OperandSlot {
+        match self {
+            OperandRef::Dest(reg) => OperandSlot::Dest(**reg),
+            OperandRef::DestVector(reg) => OperandSlot::Dest(Register::Vector(**reg)),
+            OperandRef::Src(reg) => OperandSlot::Src(**reg),
+            OperandRef::SrcVector(reg) => OperandSlot::Src(Register::Vector(**reg)),
+            OperandRef::Operand(operand) => OperandSlot::Operand(**operand),
+            OperandRef::Shift(operand) => OperandSlot::Shift(**operand),
+            OperandRef::Cond(cond) => OperandSlot::Cond(**cond),
+        }
+    }
+
+    /// Store `slot` here, or `false` when it is the wrong kind of operand
+    /// (including a scalar register in a vector slot and vice versa).
+    fn set(self, slot: OperandSlot) -> bool {
+        match (self, slot) {
+            (OperandRef::Dest(reg), OperandSlot::Dest(new))
+            | (OperandRef::Src(reg), OperandSlot::Src(new))
+                if new.vector().is_none() =>
+            {
+                *reg = new;
+            }
+            (OperandRef::DestVector(reg), OperandSlot::Dest(Register::Vector(new)))
+            | (OperandRef::SrcVector(reg), OperandSlot::Src(Register::Vector(new))) => {
+                *reg = new;
+            }
+            (OperandRef::Operand(operand), OperandSlot::Operand(new))
+            | (OperandRef::Shift(operand), OperandSlot::Shift(new)) => *operand = new,
+            (OperandRef::Cond(cond), OperandSlot::Cond(new)) => *cond = new,
+            _ => return false,
+        }
+        true
+    }
+}
+
+impl Instruction {
+    /// The instruction's operands in assembly order: destinations, source
+    /// registers, the flexible `rm` or shift operand, and the condition.
+    ///
+    /// Immediates held in dedicated fields (`mov` values, move-wide chunks,
+    /// bit-field positions, `nzcv`), widths, memory addresses and branch
+    /// labels are encoding details rather than operands and are not listed;
+    /// [`Self::with_operands`] keeps them as they are.
+    pub fn operands(&self) -> SmallVec<[OperandSlot; 4]> {
+        let mut copy = *self;
+        copy.operand_refs().iter().map(OperandRef::get).collect()
+    }
+
+    /// Rebuild this instruction with `operands` in place of
+    /// [`Self::operands`]. `None` when `operands` has a different length or
+    /// a slot of the wrong kind.
+    #[must_use]
+    pub fn with_operands(&self, operands: &[OperandSlot]) -> Option<Instruction> {
+        let mut out = *self;
+        let refs = out.operand_refs();
+        if refs.len() != operands.len() {
+            return None;
+        }
+        let all_set = refs
+            .into_iter()
+            .zip(operands)
+            .all(|(slot, operand)| slot.set(*operand));
+        all_set.then_some(out)
+    }
+
+    fn operand_refs(&mut self) -> SmallVec<[OperandRef<'_>; 4]> {
+        use OperandRef::{Cond, Dest, DestVector, Shift, Src, SrcVector};
+        match self {
+            Instruction::MovReg { rd, rn }
+            | Instruction::MovRegW { rd, rn }
+            | Instruction::Clz { rd, rn }
+            | Instruction::Cls { rd, rn }
+            | Instruction::Rbit { rd, rn }
+            | Instruction::Rev { rd, rn }
+            | Instruction::Rev32 { rd, rn }
+            | Instruction::Rev16 { rd, rn }
+            | Instruction::Sxtb { rd, rn }
+            | Instruction::Sxth { rd, rn }
+            | Instruction::Sxtw { rd, rn }
+            | Instruction::Uxtb { rd, rn }
+            | Instruction::Uxth { rd, rn }
+            | Instruction::Ubfx { rd, rn, .. }
+            | Instruction::Sbfx { rd, rn, .. }
+            | Instruction::Bfi { rd, rn, .. }
+            | Instruction::Bfxil { rd, rn, .. }
+            | Instruction::Ubfiz { rd, rn, .. }
+            | Instruction::Sbfiz { rd, rn, .. }
+            | Instruction::Mvn { rd, rm: rn }
+            | Instruction::Neg { rd, rm: rn }
+            | Instruction::Negs { rd, rm: rn } => smallvec![Dest(rd), Src(rn)],
+            Instruction::MovImm { rd, .. }
+            | Instruction::MovN { rd, .. }
+            | Instruction::MovZ { rd, .. }
+            | Instruction::MovK { rd, .. } => smallvec![Dest(rd)],
+            Instruction::Movi { vd, .. } => smallvec![DestVector(vd)],
+            Instruction::MovFromVectorLane { rd, vn, .. } => smallvec![Dest(rd), SrcVector(vn)],
+            Instruction::VectorAdd { vd, vn, vm, .. } => {
+                smallvec![DestVector(vd), SrcVector(vn), SrcVector(vm)]
+            }
+            Instruction::Add { rd, rn, rm }
+            | Instruction::AddW { rd, rn, rm }
+            | Instruction::Sub { rd, rn, rm }
+            | Instruction::SubW { rd, rn, rm }
+            | Instruction::And { rd, rn, rm, .. }
+            | Instruction::Orr { rd, rn, rm, .. }
+            | Instruction::Eor { rd, rn, rm, .. }
+            | Instruction::Bic { rd, rn, rm }
+            | Instruction::Bics { rd, rn, rm }
+            | Instruction::Orn { rd, rn, rm }
+            | Instruction::Eon { rd, rn, rm }
+            | Instruction::Adds { rd, rn, rm }
+            | Instruction::Subs { rd, rn, rm }
+            | Instruction::Ands { rd, rn, rm, .. } => {
+                smallvec![Dest(rd), Src(rn), OperandRef::Operand(rm)]
+            }
+            Instruction::Lsl { rd, rn, shift }
+            | Instruction::Lsr { rd, rn, shift }
+            | Instruction::Asr { rd, rn, shift }
+            | Instruction::Ror { rd, rn, shift } => smallvec![Dest(rd), Src(rn), Shift(shift)],
+            Instruction::Mul { rd, rn, rm }
+            | Instruction::Sdiv { rd, rn, rm }
+            | Instruction::Udiv { rd, rn, rm }
+            | Instruction::Mneg { rd, rn, rm }
+            | Instruction::Smulh { rd, rn, rm }
+            | Instruction::Umulh { rd, rn, rm }
+            | Instruction::Adc { rd, rn, rm }
+            | Instruction::Adcs { rd, rn, rm }
+            | Instruction::Sbc { rd, rn, rm }
+            | Instruction::Sbcs { rd, rn, rm } => smallvec![Dest(rd), Src(rn), Src(rm)],
+            Instruction::Madd { rd, rn, rm, ra } | Instruction::Msub { rd, rn, rm, ra } => {
+                smallvec![Dest(rd), Src(rn), Src(rm), Src(ra)]
+            }
+            Instruction::Cmp { rn, rm }
+            | Instruction::Cmn { rn, rm }
+            | Instruction::Tst { rn, rm, .. } => smallvec![Src(rn), OperandRef::Operand(rm)],
+            Instruction::Csel { rd, rn, rm, cond }
+            | Instruction::Csinc { rd, rn, rm, cond }
+            | Instruction::Csinv { rd, rn, rm, cond }
+            | Instruction::Csneg { rd, rn, rm, cond } => {
+                smallvec![Dest(rd), Src(rn), Src(rm), Cond(cond)]
+            }
+            Instruction::Ccmp { rn, rm, cond, .. } | Instruction::Ccmn { rn, rm, cond, .. } => {
+                smallvec![Src(rn), OperandRef::Operand(rm), Cond(cond)]
+            }
+            Instruction::Cset { rd, cond } | Instruction::Csetm { rd, cond } => {
+                smallvec![Dest(rd), Cond(cond)]
+            }
+            Instruction::B { .. } | Instruction::Bl { .. } => smallvec![],
+            Instruction::BCond { cond, .. } => smallvec![Cond(cond)],
+            Instruction::Ret { rn }
+            | Instruction::Br { rn }
+            | Instruction::Cbz { rn, .. }
+            | Instruction::Cbnz { rn, .. }
+            | Instruction::Tbz { rt: rn, .. }
+            | Instruction::Tbnz { rt: rn, .. }
+            | Instruction::Str { rt: rn, .. } => smallvec![Src(rn)],
+            Instruction::Ldr { rt, .. } | Instruction::Ldrs { rt, .. } => smallvec![Dest(rt)],
+            Instruction::Ldp { rt1, rt2, .. } => smallvec![Dest(rt1), Dest(rt2)],
+            Instruction::Stp { rt1, rt2, .. } => smallvec![Src(rt1), Src(rt2)],
+        }
+    }
+}
+
+/// Helper for `Instruction::map_registers`: the register slot inside an
+/// rm/shift operand, if any.
+fn operand_register_mut(operand: &mut Operand) -> Option<&mut Register> {
+    match operand {
+        Operand::Register(reg)
+        | Operand::ShiftedRegister { reg, .. }
+        | Operand::ExtendedRegister { reg, .. } => Some(reg),
+        Operand::Immediate(_) => None,
+    }
+}
+
+/// Helper for `Instruction::map_registers`: the base register plus the
+/// index register for Reg/Ext address modes.
+fn address_registers_mut(addr: &mut AddressOperand) -> Vec<&mut Register> {
+    match addr {
+        AddressOperand::Imm { base, .. } => vec![base],
+        AddressOperand::Reg { base, idx, .. } | AddressOperand::Ext { base, idx, .. } => {
+            vec![base, idx]
+        }
+    }
+}
+
 /// Encodability gate for the LDR / LDRS / STR family. Rules per ADR-0007:
 /// (a) base register cannot be XZR (the XSP slot rejects the zero register
 /// — SP is accepted); (b) `rt` cannot be SP (loads/stores use Xt/Wt
@@ -1551,6 +2063,181 @@ fn address_base(addr: &AddressOperand) -> Register {
     }
 }
 
+/// What a single instruction computes, up to spelling. Two instructions
+/// with the same key have the same effect on every register, flag and memory
+/// location; see [`Instruction::semantic_key`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SemanticKey {
+    /// Changes nothing ([`Instruction::is_nop`]).
+    Nop,
+    /// Writes zero to the register and leaves the flags alone: `mov x0, #0`,
+    /// `eor x0, x1, x1`, `sub x0, x1, x1`, `and x0, x1, xzr`, `mov x0, xzr`.
+    Zero(Register),
+    /// Anything else, keyed by its [`Instruction::simplified`] form.
+    Instruction(Instruction),
+}
+
+impl Instruction {
+    /// Cheap single-instruction equivalence: syntactic zeroing idioms share
+    /// a [`SemanticKey::Zero`], every nop shares [`SemanticKey::Nop`], and
+    /// disguised moves share the key of the `mov` they simplify to. Equal
+    /// keys imply equal semantics; different keys imply nothing, since this
+    /// recognises idioms rather than proving equivalence.
+    pub fn semantic_key(&self) -> SemanticKey {
+        if self.is_nop() {
+            return SemanticKey::Nop;
+        }
+        let simplified = self.simplified();
+        match simplified.zeroed_register() {
+            Some(Register::XZR) => SemanticKey::Nop,
+            Some(rd) => SemanticKey::Zero(rd),
+            None => SemanticKey::Instruction(simplified),
+        }
+    }
+
+    /// The register a zeroing idiom clears, if this is one. Flag-setting
+    /// forms and SP destinations are never idioms.
+    fn zeroed_register(&self) -> Option<Register> {
+        let rd = match *self {
+            Instruction::MovImm { rd, imm: 0 } => rd,
+            Instruction::MovReg {
+                rd,
+                rn: Register::XZR,
+            }
+            | Instruction::MovRegW {
+                rd,
+                rn: Register::XZR,
+            } => rd,
+            Instruction::Eor {
+                rd,
+                rn,
+                rm: Operand::Register(rm),
+                ..
+            }
+            | Instruction::Sub {
+                rd,
+                rn,
+                rm: Operand::Register(rm),
+            }
+            | Instruction::SubW {
+                rd,
+                rn,
+                rm: Operand::Register(rm),
+            }
+            | Instruction::Bic {
+                rd,
+                rn,
+                rm: Operand::Register(rm),
+            } if rn == rm => rd,
+            Instruction::And { rd, rn, rm, .. } if rn == Register::XZR || operand_is_zero(&rm) => {
+                rd
+            }
+            _ => return None,
+        };
+        (rd != Register::SP).then_some(rd)
+    }
+
+    /// True if executing this instruction leaves every register and flag
+    /// unchanged: self-moves (`mov x0, x0`), moves into XZR, and arithmetic
+    /// that adds/ORs/shifts by zero back into its own source
+    /// (`add x0, x0, #0`). `mov w0, w0` is *not* a nop — it clears the upper
+    /// 32 bits.
+    pub fn is_nop(&self) -> bool {
+        match self.simplified() {
+            Instruction::MovReg { rd, rn } => rd == rn || rd == Register::XZR,
+            Instruction::MovRegW { rd, .. } => rd == Register::XZR,
+            // `add sp, sp, #0` has no MOV form, so it survives `simplified`.
+            Instruction::Add { rd, rn, rm } | Instruction::Sub { rd, rn, rm } => {
+                rd == rn && operand_is_zero(&rm)
+            }
+            _ => false,
+        }
+    }
+
+    /// Canonical cheaper form of a disguised move: `add/sub rd, rn, #0`,
+    /// `orr/eor rd, rn, xzr`, `orr rd, xzr, rm`, `orr/and rd, rn, rn`,
+    /// `bic rd, rn, xzr` and shifts by zero all become `mov rd, rn` (or the
+    /// W-form move for 32-bit variants). Flag-setting variants and anything
+    /// that is not a disguised move are returned unchanged, as are forms whose
+    /// operands include SP (the register MOV alias cannot encode SP).
+    #[must_use]
+    pub fn simplified(&self) -> Instruction {
+        let to_move = |rd: Register, rn: Register, width: RegisterWidth| {
+            if rd == Register::SP || rn == Register::SP {
+                return *self;
+            }
+            match width {
+                RegisterWidth::X64 => Instruction::MovReg { rd, rn },
+                RegisterWidth::W32 => Instruction::MovRegW { rd, rn },
+            }
+        };
+        match *self {
+            Instruction::Add { rd, rn, rm } | Instruction::Sub { rd, rn, rm }
+                if operand_is_zero(&rm) =>
+            {
+                to_move(rd, rn, RegisterWidth::X64)
+            }
+            Instruction::AddW { rd, rn, rm } | Instruction::SubW { rd, rn, rm }
+                if operand_is_zero(&rm) =>
+            {
+                to_move(rd, rn, RegisterWidth::W32)
+            }
+            Instruction::Add {
+                rd,
+                rn: Register::XZR,
+                rm: Operand::Register(rm),
+            } => to_move(rd, rm, RegisterWidth::X64),
+            Instruction::Orr { rd, rn, rm, width } | Instruction::Eor { rd, rn, rm, width }
+                if operand_is_zero(&rm) =>
+            {
+                to_move(rd, rn, width)
+            }
+            Instruction::Orr {
+                rd,
+                rn: Register::XZR,
+                rm: Operand::Register(rm),
+                width,
+            } => to_move(rd, rm, width),
+            Instruction::Orr {
+                rd,
+                rn,
+                rm: Operand::Register(rm),
+                width,
+            }
+            | Instruction::And {
+                rd,
+                rn,
+                rm: Operand::Register(rm),
+                width,
+            } if rn == rm => to_move(rd, rn, width),
+            Instruction::Bic { rd, rn, rm } if operand_is_zero(&rm) => {
+                to_move(rd, rn, RegisterWidth::X64)
+            }
+            Instruction::Lsl { rd, rn, shift }
+            | Instruction::Lsr { rd, rn, shift }
+            | Instruction::Asr { rd, rn, shift }
+            | Instruction::Ror { rd, rn, shift }
+                if operand_is_zero(&shift) =>
+            {
+                to_move(rd, rn, RegisterWidth::X64)
+            }
+            other => other,
+        }
+    }
+}
+
+/// True if `operand` always reads as zero: `#0`, or XZR in any register form
+/// (a shifted or extended zero register is still zero).
+fn operand_is_zero(operand: &Operand) -> bool {
+    match operand {
+        Operand::Immediate(imm) => *imm == 0,
+        other => other.source_register() == Some(Register::XZR),
+    }
+}
+
+/// Prints GNU-style AArch64 assembly that `parser::parse_line` accepts and
+/// maps back to the same instruction (aliases such as `movz #imm` → `mov`
+/// re-parse to an identically encoded form).
 impl fmt::Display for Instruction {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -2897,6 +3584,71 @@ mod tests {
         assert_eq!(add_imm.source_registers(), vec![Register::X1]);
     }
 
+    #[test]
+    fn swap_operands_exchanges_commutative_sources() {
+        let add = Instruction::Add {
+            rd: Register::X0,
+            rn: Register::X1,
+            rm: Operand::Register(Register::X2),
+        };
+        assert_eq!(
+            add.swap_operands(),
+            Some(Instruction::Add {
+                rd: Register::X0,
+                rn: Register::X2,
+                rm: Operand::Register(Register::X1),
+            })
+        );
+
+        let sub = Instruction::Sub {
+            rd: Register::X0,
+            rn: Register::X1,
+            rm: Operand::Register(Register::X2),
+        };
+        assert_eq!(sub.swap_operands(), None);
+
+        // An immediate cannot move into rn, and SP cannot move into rm.
+        let add_imm = Instruction::Add {
+            rd: Register::X0,
+            rn: Register::X1,
+            rm: Operand::Immediate(1),
+        };
+        assert_eq!(add_imm.swap_operands(), None);
+        let add_sp = Instruction::Add {
+            rd: Register::X0,
+            rn: Register::SP,
+            rm: Operand::Register(Register::X1),
+        };
+        assert_eq!(add_sp.swap_operands(), None);
+    }
+
+    #[test]
+    fn immediate_reports_values_but_not_encoding_fields() {
+        let mov = Instruction::MovImm {
+            rd: Register::X0,
+            imm: -7,
+        };
+        assert_eq!(mov.immediate(), Some(-7));
+        let lsr = Instruction::Lsr {
+            rd: Register::X0,
+            rn: Register::X1,
+            shift: Operand::Immediate(3),
+        };
+        assert_eq!(lsr.immediate(), Some(3));
+        let add_reg = Instruction::Add {
+            rd: Register::X0,
+            rn: Register::X1,
+            rm: Operand::Register(Register::X2),
+        };
+        assert_eq!(add_reg.immediate(), None);
+        let movz = Instruction::MovZ {
+            rd: Register::X0,
+            imm: 1,
+            shift: 16,
+        };
+        assert_eq!(movz.immediate(), None);
+    }
+
     #[test]
     fn test_source_registers_shifted_register() {
         // Add/Sub/And/Orr/Eor and Cmp/Cmn/Tst must extract the inner register
@@ -3189,18 +3941,38 @@ mod tests {
             .is_encodable_aarch64()
         );
 
-        // MovImm out of range
+        // MovImm whose complement is one halfword is a single MOVN
         assert!(
-            !Instruction::MovImm {
+            Instruction::MovImm {
                 rd: Register::X0,
                 imm: -1
             }
             .is_encodable_aarch64()
         );
+        assert!(
+            Instruction::MovImm {
+                rd: Register::X0,
+                imm: !(0x1234_i64 << 32)
+            }
+            .is_encodable_aarch64()
+        );
+
+        // A wider non-negative MovImm assembles to a MOVZ/MOVK chain
+        for imm in [0x10000, 0x1234_5678_9ABC_DEF0] {
+            assert!(
+                Instruction::MovImm {
+                    rd: Register::X0,
+                    imm
+                }
+                .is_encodable_aarch64()
+            );
+        }
+
+        // A negative MovImm that is not a single MOVN has no encoding
         assert!(
             !Instruction::MovImm {
                 rd: Register::X0,
-                imm: 0x10000
+                imm: -0x1_2345
             }
             .is_encodable_aarch64()
         );
@@ -3736,12 +4508,46 @@ mod tests {
             .is_encodable_aarch64()
         );
 
-        // Invalid: immediate too large
+        // Invalid: too large, and not a 12-bit value shifted by 12
         assert!(
             !Instruction::Add {
                 rd: Register::X0,
                 rn: Register::X1,
-                rm: Operand::Immediate(0x1000)
+                rm: Operand::Immediate(0x1001)
+            }
+            .is_encodable_aarch64()
+        );
+    }
+
+    #[test]
+    fn add_sub_immediates_reach_the_lsl_12_range() {
+        assert_eq!(add_sub_imm12(0xFFF), Some((0xFFF, false)));
+        assert_eq!(add_sub_imm12(0x1000), Some((1, true)));
+        assert_eq!(add_sub_imm12(0xFFF000), Some((0xFFF, true)));
+        assert_eq!(add_sub_imm12(0x1001), None);
+        assert_eq!(add_sub_imm12(0x100_0000), None);
+        assert_eq!(add_sub_imm12(-0x1000), None);
+
+        let add = |imm| Instruction::Add {
+            rd: Register::X0,
+            rn: Register::X1,
+            rm: Operand::Immediate(imm),
+        };
+        assert!(add(0x1000).is_encodable_aarch64());
+        assert!(add(0xFFF000).is_encodable_aarch64());
+        assert!(!add(0x1001).is_encodable_aarch64());
+        assert!(
+            Instruction::SubW {
+                rd: Register::X0,
+                rn: Register::X1,
+                rm: Operand::Immediate(0x2000),
+            }
+            .is_encodable_aarch64()
+        );
+        assert!(
+            Instruction::Cmp {
+                rn: Register::X1,
+                rm: Operand::Immediate(0x7000),
             }
             .is_encodable_aarch64()
         );
@@ -4796,6 +5602,160 @@ mod tests {
         );
     }
 
+    #[test]
+    fn operands_round_trip_through_with_operands() {
+        use Register::{X0, X1, X2};
+        let target = LabelId(0x1000);
+        let addr = AddressOperand::Imm {
+            base: X1,
+            offset: 8,
+            mode: IndexMode::Offset,
+        };
+        // Every variant the shared fixtures do not cover.
+        let extra = [
+            Instruction::MovRegW { rd: X0, rn: X1 },
+            Instruction::AddW {
+                rd: X0,
+                rn: X1,
+                rm: Operand::Register(X2),
+            },
+            Instruction::SubW {
+                rd: X0,
+                rn: X1,
+                rm: Operand::Immediate(3),
+            },
+            Instruction::Adc {
+                rd: X0,
+                rn: X1,
+                rm: X2,
+            },
+            Instruction::Adcs {
+                rd: X0,
+                rn: X1,
+                rm: X2,
+            },
+            Instruction::Sbc {
+                rd: X0,
+                rn: X1,
+                rm: X2,
+            },
+            Instruction::Sbcs {
+                rd: X0,
+                rn: X1,
+                rm: X2,
+            },
+            Instruction::B { target },
+            Instruction::Bl { target },
+            Instruction::BCond {
+                target,
+                cond: Condition::NE,
+            },
+            Instruction::Ret { rn: Register::X30 },
+            Instruction::Br { rn: X1 },
+            Instruction::Cbz { rn: X1, target },
+            Instruction::Cbnz { rn: X1, target },
+            Instruction::Tbz {
+                rt: X1,
+                bit: 3,
+                target,
+            },
+            Instruction::Tbnz {
+                rt: X1,
+                bit: 3,
+                target,
+            },
+            Instruction::Ldr {
+                rt: X0,
+                addr,
+                width: AccessWidth::Extended,
+            },
+            Instruction::Ldrs {
+                rt: X0,
+                addr,
+                width: AccessWidth::Byte,
+            },
+            Instruction::Str {
+                rt: X0,
+                addr,
+                width: AccessWidth::Word,
+            },
+            Instruction::Ldp {
+                rt1: X0,
+                rt2: X2,
+                addr,
+                width: PairAccessWidth::Extended,
+                signed: false,
+            },
+            Instruction::Stp {
+                rt1: X0,
+                rt2: X2,
+                addr,
+                width: PairAccessWidth::Extended,
+            },
+        ];
+        let all = aarch64_instruction_families()
+            .into_iter()
+            .map(|fixture| fixture.instruction)
+            .chain(extra);
+        for instruction in all {
+            let operands = instruction.operands();
+            assert_eq!(
+                instruction.with_operands(&operands),
+                Some(instruction),
+                "{instruction:?} -> {operands:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn with_operands_rebuilds_and_rejects_mismatched_slots() {
+        let add = Instruction::Add {
+            rd: Register::X0,
+            rn: Register::X1,
+            rm: Operand::Register(Register::X2),
+        };
+        assert_eq!(
+            add.operands().as_slice(),
+            [
+                OperandSlot::Dest(Register::X0),
+                OperandSlot::Src(Register::X1),
+                OperandSlot::Operand(Operand::Register(Register::X2)),
+            ]
+        );
+        assert_eq!(
+            add.with_operands(&[
+                OperandSlot::Dest(Register::X3),
+                OperandSlot::Src(Register::X4),
+                OperandSlot::Operand(Operand::Immediate(1)),
+            ]),
+            Some(Instruction::Add {
+                rd: Register::X3,
+                rn: Register::X4,
+                rm: Operand::Immediate(1),
+            })
+        );
+
+        // Too few slots, a source where the destination goes, and a vector
+        // register in a scalar slot.
+        assert_eq!(add.with_operands(&add.operands()[..2]), None);
+        assert_eq!(
+            add.with_operands(&[
+                OperandSlot::Src(Register::X3),
+                OperandSlot::Src(Register::X4),
+                OperandSlot::Operand(Operand::Immediate(1)),
+            ]),
+            None
+        );
+        assert_eq!(
+            add.with_operands(&[
+                OperandSlot::Dest(Register::Vector(VectorRegister::V0)),
+                OperandSlot::Src(Register::X4),
+                OperandSlot::Operand(Operand::Immediate(1)),
+            ]),
+            None
+        );
+    }
+
     #[test]
     fn representative_instruction_variants_match_helper_contracts() {
         for fixture in aarch64_instruction_families() {
@@ -4839,6 +5799,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::single_element_loop)]
     fn test_extended_register_arith_encodability() {
         use crate::ir::ExtendKind;
         // ADD x0, x1, x2, UXTB #2 — within shift range, not SP, accepted.
@@ -5644,4 +6605,300 @@ mod tests {
         assert!(prefix.is_empty());
         assert!(term.is_none());
     }
+
+    fn disguised_moves() -> Vec<(Instruction, Instruction)> {
+        use Register::{X0, X1, X2, XZR};
+        let mov = Instruction::MovReg { rd: X0, rn: X1 };
+        let mov_w = Instruction::MovRegW { rd: X0, rn: X1 };
+        vec![
+            (
+                Instruction::Add {
+                    rd: X0,
+                    rn: X1,
+                    rm: Operand::Immediate(0),
+                },
+                mov,
+            ),
+            (
+                Instruction::Sub {
+                    rd: X0,
+                    rn: X1,
+                    rm: Operand::Register(XZR),
+                },
+                mov,
+            ),
+            (
+                Instruction::AddW {
+                    rd: X0,
+                    rn: X1,
+                    rm: Operand::Immediate(0),
+                },
+                mov_w,
+            ),
+            (
+                Instruction::Add {
+                    rd: X0,
+                    rn: XZR,
+                    rm: Operand::Register(X1),
+                },
+                mov,
+            ),
+            (
+                Instruction::Orr {
+                    rd: X0,
+                    rn: X1,
+                    rm: Operand::Register(XZR),
+                    width: RegisterWidth::X64,
+                },
+                mov,
+            ),
+            (
+                Instruction::Orr {
+                    rd: X0,
+                    rn: XZR,
+                    rm: Operand::Register(X1),
+                    width: RegisterWidth::W32,
+                },
+                mov_w,
+            ),
+            (
+                Instruction::Eor {
+                    rd: X0,
+                    rn: X1,
+                    rm: Operand::ShiftedRegister {
+                        reg: XZR,
+                        kind: ShiftKind::Lsl,
+                        amount: 3,
+                    },
+                    width: RegisterWidth::X64,
+                },
+                mov,
+            ),
+            (
+                Instruction::And {
+                    rd: X0,
+                    rn: X1,
+                    rm: Operand::Register(X1),
+                    width: RegisterWidth::X64,
+                },
+                mov,
+            ),
+            (
+                Instruction::Bic {
+                    rd: X0,
+                    rn: X1,
+                    rm: Operand::Register(XZR),
+                },
+                mov,
+            ),
+            (
+                Instruction::Lsl {
+                    rd: X0,
+                    rn: X1,
+                    shift: Operand::Immediate(0),
+                },
+                mov,
+            ),
+            (
+                Instruction::Ror {
+                    rd: X0,
+                    rn: X1,
+                    shift: Operand::Register(XZR),
+                },
+                mov,
+            ),
+            (
+                Instruction::Add {
+                    rd: X0,
+                    rn: X1,
+                    rm: Operand::Register(X2),
+                },
+                Instruction::Add {
+                    rd: X0,
+                    rn: X1,
+                    rm: Operand::Register(X2),
+                },
+            ),
+        ]
+    }
+
+    #[test]
+    fn simplified_maps_disguised_moves_to_mov() {
+        for (instr, expected) in disguised_moves() {
+            assert_eq!(instr.simplified(), expected, "simplifying {}", instr);
+        }
+    }
+
+    #[test]
+    fn simplified_preserves_concrete_semantics() {
+        use crate::semantics::{ConcreteMachineState, ConcreteValue, apply_sequence_concrete};
+        let mut state = ConcreteMachineState::new_zeroed();
+        state.set_register(Register::X0, ConcreteValue::new(0xdead_beef));
+        state.set_register(Register::X1, ConcreteValue::new(0xffff_0000_1234_5678));
+        state.set_register(Register::X2, ConcreteValue::new(7));
+        for (instr, _) in disguised_moves() {
+            let original = apply_sequence_concrete(state.clone(), &[instr]);
+            let simplified = apply_sequence_concrete(state.clone(), &[instr.simplified()]);
+            assert_eq!(
+                original.get_register(Register::X0),
+                simplified.get_register(Register::X0),
+                "{} vs {}",
+                instr,
+                instr.simplified()
+            );
+        }
+    }
+
+    #[test]
+    fn simplified_leaves_flag_setters_and_sp_forms_alone() {
+        let adds = Instruction::Adds {
+            rd: Register::X0,
+            rn: Register::X1,
+            rm: Operand::Immediate(0),
+        };
+        assert_eq!(adds.simplified(), adds);
+        let from_sp = Instruction::Add {
+            rd: Register::X0,
+            rn: Register::SP,
+            rm: Operand::Immediate(0),
+        };
+        assert_eq!(from_sp.simplified(), from_sp);
+        assert!(!from_sp.is_nop());
+    }
+
+    #[test]
+    fn zeroing_idioms_share_a_semantic_key() {
+        use Register::{X0, X1, XZR};
+        let zeroing = [
+            Instruction::MovImm { rd: X0, imm: 0 },
+            Instruction::Eor {
+                rd: X0,
+                rn: X0,
+                rm: Operand::Register(X0),
+                width: RegisterWidth::X64,
+            },
+            Instruction::Eor {
+                rd: X0,
+                rn: X1,
+                rm: Operand::Register(X1),
+                width: RegisterWidth::W32,
+            },
+            Instruction::Sub {
+                rd: X0,
+                rn: X1,
+                rm: Operand::Register(X1),
+            },
+            Instruction::And {
+                rd: X0,
+                rn: X1,
+                rm: Operand::Register(XZR),
+                width: RegisterWidth::X64,
+            },
+            Instruction::MovReg { rd: X0, rn: XZR },
+            Instruction::MovRegW { rd: X0, rn: XZR },
+        ];
+        for instr in zeroing {
+            assert_eq!(instr.semantic_key(), SemanticKey::Zero(X0), "{}", instr);
+        }
+
+        let one = Instruction::MovImm { rd: X0, imm: 1 };
+        assert_eq!(one.semantic_key(), SemanticKey::Instruction(one));
+        assert_ne!(
+            Instruction::MovImm { rd: X1, imm: 0 }.semantic_key(),
+            SemanticKey::Zero(X0)
+        );
+        // The flag-setting form also writes NZCV, so it is not an idiom.
+        let subs = Instruction::Subs {
+            rd: X0,
+            rn: X1,
+            rm: Operand::Register(X1),
+        };
+        assert_eq!(subs.semantic_key(), SemanticKey::Instruction(subs));
+    }
+
+    #[test]
+    fn semantic_key_merges_disguised_moves_and_nops() {
+        for (instr, expected) in disguised_moves() {
+            assert_eq!(instr.semantic_key(), expected.semantic_key(), "{}", instr);
+        }
+        let self_move = Instruction::MovReg {
+            rd: Register::X2,
+            rn: Register::X2,
+        };
+        let add_zero = Instruction::Add {
+            rd: Register::X4,
+            rn: Register::X4,
+            rm: Operand::Immediate(0),
+        };
+        let zero_into_xzr = Instruction::MovImm {
+            rd: Register::XZR,
+            imm: 0,
+        };
+        assert_eq!(self_move.semantic_key(), SemanticKey::Nop);
+        assert_eq!(add_zero.semantic_key(), SemanticKey::Nop);
+        assert_eq!(zero_into_xzr.semantic_key(), SemanticKey::Nop);
+    }
+
+    #[test]
+    fn is_nop_detects_self_moves_and_zero_arithmetic() {
+        let nops = [
+            Instruction::MovReg {
+                rd: Register::X0,
+                rn: Register::X0,
+            },
+            Instruction::MovReg {
+                rd: Register::XZR,
+                rn: Register::X3,
+            },
+            Instruction::Add {
+                rd: Register::X4,
+                rn: Register::X4,
+                rm: Operand::Immediate(0),
+            },
+            Instruction::Add {
+                rd: Register::SP,
+                rn: Register::SP,
+                rm: Operand::Immediate(0),
+            },
+            Instruction::Orr {
+                rd: Register::X2,
+                rn: Register::X2,
+                rm: Operand::Register(Register::XZR),
+                width: RegisterWidth::X64,
+            },
+            Instruction::Lsr {
+                rd: Register::X1,
+                rn: Register::X1,
+                shift: Operand::Immediate(0),
+            },
+        ];
+        for instr in nops {
+            assert!(instr.is_nop(), "{} should be a nop", instr);
+        }
+
+        let not_nops = [
+            // W-form self-move zero-extends, so it changes the register.
+            Instruction::MovRegW {
+                rd: Register::X0,
+                rn: Register::X0,
+            },
+            Instruction::MovReg {
+                rd: Register::X0,
+                rn: Register::X1,
+            },
+            Instruction::Add {
+                rd: Register::X0,
+                rn: Register::X0,
+                rm: Operand::Immediate(1),
+            },
+            Instruction::Adds {
+                rd: Register::X0,
+                rn: Register::X0,
+                rm: Operand::Immediate(0),
+            },
+        ];
+        for instr in not_nops {
+            assert!(!instr.is_nop(), "{} should not be a nop", instr);
+        }
+    }
 }
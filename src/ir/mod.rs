@@ -1,11 +1,13 @@
 //! Intermediate Representation (IR) for AArch64 instructions
 
 pub(crate) mod aarch64_encoding;
+#[cfg(any(test, feature = "proptest"))]
+pub mod arbitrary;
 pub mod instructions;
 pub mod types;
 
 // Re-export commonly used types
-pub use instructions::Instruction;
+pub use instructions::{Instruction, OperandSlot, SemanticKey};
 pub use types::{
     Condition, ExtendKind, LabelId, Operand, Register, RegisterWidth, ShiftKind, VectorArrangement,
     VectorRegister,
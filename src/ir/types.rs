@@ -144,8 +144,9 @@ pub enum Register {
 }
 
 impl Register {
-    /// Get register index for X0-X30
-    #[allow(dead_code)]
+    /// Encoding index: 0-30 for X0-X30 and 31 for XZR. SP also encodes as
+    /// 31 in the operand slots that accept it, but it has no index of its
+    /// own, so `from_index(31)` is always XZR.
     pub const fn index(&self) -> Option<u8> {
         match self {
             Register::X0 => Some(0),
@@ -184,7 +185,8 @@ impl Register {
         }
     }
 
-    /// Create register from index (0-30 for X registers, 31 for XZR)
+    /// Inverse of [`Self::index`]: 0-30 for X0-X30, 31 for XZR. SP and the
+    /// vector registers are never produced.
     pub fn from_index(index: u8) -> Option<Self> {
         match index {
             0 => Some(Register::X0),
@@ -223,6 +225,17 @@ impl Register {
         }
     }
 
+    /// Up to `count` of X0-X30, lowest first, that are not in `exclude`.
+    /// XZR and SP are never returned; when fewer than `count` remain the
+    /// result is simply shorter.
+    pub fn scratch_registers(count: usize, exclude: &[Register]) -> Vec<Register> {
+        (0..=30)
+            .filter_map(Register::from_index)
+            .filter(|register| !exclude.contains(register))
+            .take(count)
+            .collect()
+    }
+
     #[must_use]
     pub const fn vector(self) -> Option<VectorRegister> {
         match self {
@@ -821,6 +834,34 @@ mod tests {
         assert_eq!(Register::from_index(32), None);
     }
 
+    #[test]
+    fn from_index_inverts_index() {
+        for index in 0..=31 {
+            let reg = Register::from_index(index).unwrap();
+            assert_eq!(reg.index(), Some(index));
+        }
+    }
+
+    #[test]
+    fn scratch_registers_skip_excluded_and_special_registers() {
+        let exclude = [Register::X0, Register::X2, Register::SP];
+        assert_eq!(
+            Register::scratch_registers(3, &exclude),
+            vec![Register::X1, Register::X3, Register::X4]
+        );
+
+        let all = Register::scratch_registers(usize::MAX, &exclude);
+        assert_eq!(all.len(), 29);
+        assert!(all.iter().all(|register| !exclude.contains(register)
+            && *register != Register::XZR
+            && *register != Register::SP));
+
+        // Not enough left: clamp rather than invent registers.
+        let taken: Vec<Register> = (0..30).filter_map(Register::from_index).collect();
+        assert_eq!(Register::scratch_registers(4, &taken), vec![Register::X30]);
+        assert!(Register::scratch_registers(0, &[]).is_empty());
+    }
+
     #[test]
     fn test_register_display() {
         assert_eq!(format!("{}", Register::X0), "x0");
@@ -117,6 +117,14 @@ pub trait InstructionType:
     /// Get the mnemonic string for this instruction
     fn mnemonic(&self) -> &'static str;
 
+    /// The mnemonic with the condition code appended (`csel.eq`) for
+    /// instructions that take one, so that differently conditioned
+    /// instructions stay distinct in counts and logs. Defaults to
+    /// [`Self::mnemonic`].
+    fn suffixed_mnemonic(&self) -> String {
+        self.mnemonic().to_string()
+    }
+
     /// Returns true if this instruction has side effects beyond register writes
     /// (e.g., memory access, branches, condition code updates)
     fn has_side_effects(&self) -> bool {
@@ -145,7 +153,8 @@ pub trait ISA: Send + Sync + Clone {
     /// Name of this ISA (e.g., "AArch64", "RISC-V")
     fn name(&self) -> &'static str;
 
-    /// Number of general-purpose registers
+    /// Number of general-purpose registers, the length of
+    /// [`Self::general_registers`]
     fn register_count(&self) -> usize;
 
     /// Register bit width (e.g., 64 for AArch64, 32 for ARM).
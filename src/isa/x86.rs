@@ -1207,6 +1207,7 @@ impl crate::isa::traits::ConcreteExecutor<X86Instruction> for X86_32 {
     }
 }
 
+#[cfg(feature = "z3")]
 impl crate::isa::traits::SymbolicExecutor<X86Instruction> for X86_64 {
     type State = crate::semantics::smt_x86::MachineStateX86;
 
@@ -1219,6 +1220,7 @@ impl crate::isa::traits::SymbolicExecutor<X86Instruction> for X86_64 {
     }
 }
 
+#[cfg(feature = "z3")]
 impl crate::isa::traits::SymbolicExecutor<X86Instruction> for X86_32 {
     type State = crate::semantics::smt_x86::MachineStateX86;
 
@@ -3970,6 +3972,7 @@ mod tests {
                 opcode: 1.0,
                 swap: 0.0,
                 instruction: 0.0,
+                canonicalize: 0.0,
             },
             crate::assembler::x86::X86Mode::Mode64,
         );
@@ -4008,6 +4011,7 @@ mod tests {
                 opcode: 1.0,
                 swap: 0.0,
                 instruction: 0.0,
+                canonicalize: 0.0,
             },
             crate::assembler::x86::X86Mode::Mode64,
         );
@@ -4092,6 +4096,7 @@ mod tests {
                 opcode: 0.0,
                 swap: 0.0,
                 instruction: 1.0,
+                canonicalize: 0.0,
             },
             crate::assembler::x86::X86Mode::Mode64,
         );
@@ -4119,6 +4124,7 @@ mod tests {
                 opcode: 0.0,
                 swap: 0.0,
                 instruction: 0.0,
+                canonicalize: 0.0,
             },
             crate::assembler::x86::X86Mode::Mode64,
         );
@@ -4168,6 +4174,7 @@ mod tests {
                 opcode: 0.0,
                 swap: 0.0,
                 instruction: 0.0,
+                canonicalize: 0.0,
             },
             crate::assembler::x86::X86Mode::Mode64,
         );
@@ -4480,6 +4487,7 @@ mod tests {
                 opcode: 0.0,
                 swap: 0.0,
                 instruction: 1.0,
+                canonicalize: 0.0,
             },
             crate::assembler::x86::X86Mode::Mode64,
         );
@@ -4516,6 +4524,7 @@ mod tests {
                 opcode: 0.0,
                 swap: 0.0,
                 instruction: 0.0,
+                canonicalize: 0.0,
             },
             crate::assembler::x86::X86Mode::Mode64,
         );
@@ -4565,6 +4574,7 @@ mod tests {
                     opcode: 0.0,
                     swap: 0.0,
                     instruction: 1.0,
+                    canonicalize: 0.0,
                 },
                 crate::assembler::x86::X86Mode::Mode64,
             );
@@ -4589,6 +4599,7 @@ mod tests {
                     opcode: 0.0,
                     swap: 0.0,
                     instruction: 0.0,
+                    canonicalize: 0.0,
                 },
                 crate::assembler::x86::X86Mode::Mode64,
             );
@@ -4682,6 +4693,7 @@ mod tests {
                 opcode: 0.0,
                 swap: 0.0,
                 instruction: 0.0,
+                canonicalize: 0.0,
             },
             crate::assembler::x86::X86Mode::Mode32,
         );
@@ -4731,6 +4743,7 @@ mod tests {
                 opcode: 0.0,
                 swap: 0.0,
                 instruction: 0.0,
+                canonicalize: 0.0,
             },
             crate::assembler::x86::X86Mode::Mode64,
         );
@@ -4821,6 +4834,7 @@ mod tests {
                 opcode: 0.0,
                 swap: 0.0,
                 instruction: 0.5,
+                canonicalize: 0.0,
             },
             crate::assembler::x86::X86Mode::Mode64,
         );
@@ -4919,6 +4933,7 @@ mod tests {
                 opcode: 1.0,
                 swap: 0.0,
                 instruction: 0.0,
+                canonicalize: 0.0,
             },
             crate::assembler::x86::X86Mode::Mode64,
         );
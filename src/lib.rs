@@ -10,6 +10,8 @@ pub mod search;
 pub mod semantics;
 pub mod validation;
 
+pub use search::{optimize_sequence, optimize_sequence_parallel};
+
 #[cfg(test)]
 #[path = "test_utils.rs"]
 mod test_utils;
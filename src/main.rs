@@ -3,6 +3,8 @@ use clap::{Parser, Subcommand, ValueEnum};
 use elf::{ElfBytes, endian::AnyEndian};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
 use std::time::Duration;
 
 #[cfg(test)]
@@ -11,14 +13,20 @@ mod test_utils;
 
 use s11::assembler::AArch64Assembler;
 use s11::capstone_bridge::{ConvertOutcome, convert_capstone_op};
-use s11::elf_patcher::{AddressWindow, DetectedArch, ElfPatcher, TextSection, parse_hex_address};
+use s11::elf_patcher::{
+    AddressWindow, DetectedArch, ElfPatcher, InstructionMeta, TextSection, parse_hex_address,
+};
 use s11::ir::instructions::split_terminator;
 use s11::ir::{Instruction, Register};
+use s11::isa::InstructionType;
+#[cfg(feature = "z3")]
+use s11::search::SymbolicSearch;
 use s11::search::config::{
     Algorithm, LlmConfig, SearchConfig, SearchMode, StochasticConfig, SymbolicConfig,
 };
+use s11::search::optimize::search_without_overwritten_writes;
 use s11::search::parallel::{ParallelConfig, run_parallel_search};
-use s11::search::{EnumerativeSearch, SearchAlgorithm, StochasticSearch, SymbolicSearch};
+use s11::search::{EnumerativeSearch, SearchAlgorithm, StochasticSearch};
 use s11::semantics::LiveOut;
 use s11::semantics::cost::CostMetric;
 #[allow(unused_imports)]
@@ -33,10 +41,26 @@ use s11::{assembler, elf_patcher, ir, isa, parser, search, semantics, validation
 #[command(subcommand_required = true)]
 #[command(arg_required_else_help = true)]
 struct Args {
+    /// Suppress progress and diagnostic output; results still go to stdout
+    #[arg(long, short = 'q', global = true)]
+    quiet: bool,
     #[command(subcommand)]
     command: Commands,
 }
 
+/// Print a progress / diagnostic line to stderr unless `--quiet` is set.
+///
+/// Convention: stdout carries only results (optimized code, reports, JSON) so
+/// it stays machine-readable; everything describing *how* we got there goes
+/// through this macro to stderr.
+macro_rules! progress {
+    ($quiet:expr, $($arg:tt)*) => {
+        if !$quiet {
+            eprintln!($($arg)*);
+        }
+    };
+}
+
 /// CLI algorithm selection
 #[derive(Clone, Copy, Debug, ValueEnum, PartialEq, Eq)]
 enum CliAlgorithm {
@@ -64,15 +88,29 @@ impl From<CliAlgorithm> for Algorithm {
     }
 }
 
+/// Output format for command results
+#[derive(Clone, Copy, Debug, Default, ValueEnum, PartialEq, Eq)]
+enum OutputFormat {
+    /// Human-readable text
+    #[default]
+    Text,
+    /// A single JSON object on stdout
+    Json,
+}
+
 /// CLI cost metric selection
 #[derive(Clone, Copy, Debug, ValueEnum)]
 enum CliCostMetric {
     /// Count number of instructions
+    #[value(alias = "count")]
     InstructionCount,
     /// Estimate latency cycles
     Latency,
     /// Estimate code size in bytes
+    #[value(alias = "size")]
     CodeSize,
+    /// Instruction count, ties broken by fewer clobbered registers
+    Clobbers,
 }
 
 impl From<CliCostMetric> for CostMetric {
@@ -81,6 +119,9 @@ impl From<CliCostMetric> for CostMetric {
             CliCostMetric::InstructionCount => CostMetric::InstructionCount,
             CliCostMetric::Latency => CostMetric::Latency,
             CliCostMetric::CodeSize => CostMetric::CodeSize,
+            CliCostMetric::Clobbers => {
+                CostMetric::Clobbers(semantics::cost::LiveRegisterMask::EMPTY)
+            }
         }
     }
 }
@@ -218,6 +259,8 @@ impl From<DetectedArch> for CliArch {
     }
 }
 
+// Parsed once per process, so the size of the `opt` variant does not matter.
+#[allow(clippy::large_enum_variant)]
 #[derive(Subcommand)]
 enum Commands {
     /// Disassemble an ELF binary showing addresses and machine code
@@ -227,6 +270,14 @@ enum Commands {
         /// Target architecture (auto-detected from ELF if not specified)
         #[arg(long, value_enum)]
         arch: Option<CliArch>,
+        /// List the executable sections with their address ranges, file
+        /// offsets and sizes instead of disassembling
+        #[arg(long)]
+        list_sections: bool,
+        /// Count how many instructions across all executable sections s11 can
+        /// model and rank the unsupported mnemonics by frequency
+        #[arg(long, conflicts_with = "list_sections")]
+        report_unsupported: bool,
     },
     /// Optimize a window of instructions in an ELF binary
     #[command(
@@ -244,19 +295,109 @@ enum Commands {
     Opt {
         /// Path to ELF binary to optimize
         binary: PathBuf,
-        /// Start address of optimization window (hex, e.g., 0x1000). Required unless --auto is set.
-        #[arg(long, required_unless_present = "auto")]
+        /// Start address of optimization window (hex, e.g., 0x1000). Required unless --window, --auto, --windows or --optimize-all is set.
+        #[arg(
+            long,
+            required_unless_present_any = ["window", "auto", "windows", "optimize_all", "compare_binary"]
+        )]
         start_addr: Option<String>,
-        /// End address of optimization window (hex, e.g., 0x1100). Required unless --auto is set.
-        #[arg(long, required_unless_present = "auto")]
+        /// End address of optimization window (hex, e.g., 0x1100). Required unless --window, --auto, --windows or --optimize-all is set.
+        #[arg(
+            long,
+            required_unless_present_any = ["window", "auto", "windows", "optimize_all", "compare_binary"]
+        )]
         end_addr: Option<String>,
+        /// Optimization window as one START-END range (hex, e.g., 0x1000-0x1100);
+        /// replaces --start-addr/--end-addr
+        #[arg(
+            long,
+            conflicts_with_all = ["start_addr", "end_addr", "auto", "windows", "optimize_all"]
+        )]
+        window: Option<AddressWindow>,
 
         /// Superoptimize the whole binary (mutually exclusive with --start-addr/--end-addr)
         #[arg(long, conflicts_with_all = ["start_addr", "end_addr"])]
         auto: bool,
+        /// Optimize every window listed in a JSON manifest
+        /// (`[{"start": "0x1000", "end": "0x1010", "live_out": "x0"}]`) and
+        /// write all patches to one output copy
+        #[arg(long, conflicts_with_all = ["start_addr", "end_addr", "auto"])]
+        windows: Option<PathBuf>,
+        /// Optimize every sized function in the symbol table, keep going past
+        /// functions that cannot be optimized, and write all improvements to
+        /// one output copy
+        #[arg(long, conflicts_with_all = ["start_addr", "end_addr", "auto", "windows"])]
+        optimize_all: bool,
+        /// Optimize the --function symbol in this binary and in OTHER (e.g. a
+        /// build from another compiler) and print the savings side by side;
+        /// nothing is written
+        #[arg(
+            long,
+            value_name = "OTHER",
+            requires = "function",
+            conflicts_with_all = ["start_addr", "end_addr", "window", "auto", "windows", "optimize_all"]
+        )]
+        compare_binary: Option<PathBuf>,
+        /// Function symbol to optimize with --compare-binary
+        #[arg(long, requires = "compare_binary")]
+        function: Option<String>,
+        /// With --optimize-all, skip functions smaller than this many bytes
+        #[arg(
+            long,
+            default_value = "8",
+            requires = "optimize_all",
+            conflicts_with_all = ["start_addr", "end_addr", "window", "auto", "windows", "compare_binary"]
+        )]
+        min_size: u64,
         /// Write the optimized binary to PATH (defaults to <stem>_optimized.<ext>)
         #[arg(long, short = 'o')]
         output: Option<PathBuf>,
+        /// Replace an existing -o/--output file instead of refusing to write
+        #[arg(long, requires = "output")]
+        overwrite: bool,
+        /// Hold the first N instructions of the window fixed; only the rest is searched
+        #[arg(long, default_value = "0", conflicts_with = "auto")]
+        fixed_prefix: usize,
+        /// Hold the last M instructions of the window fixed; only the rest is searched
+        #[arg(long, default_value = "0", conflicts_with = "auto")]
+        fixed_suffix: usize,
+        /// Cross-check the optimized window against the original under
+        /// qemu-aarch64 on random inputs before patching (needs the `qemu` feature)
+        #[arg(long)]
+        qemu_verify: bool,
+        /// After an optimization is found, print why it is valid: the live-out
+        /// set, the concrete tests it passed and the SMT verdict (AArch64)
+        #[arg(long)]
+        explain: bool,
+        /// Result format written to stdout; `json` prints one object per
+        /// optimized window, with the byte range the search replaced
+        #[arg(
+            long,
+            value_enum,
+            default_value = "text",
+            conflicts_with_all = ["auto", "optimize_all", "compare_binary", "explain", "bench_search"]
+        )]
+        format: OutputFormat,
+        /// Write the output binary even when the search finds no improvement
+        /// (by default the input is left as the only copy)
+        #[arg(long)]
+        write_unchanged: bool,
+        /// Require the rewrite to preserve every register and the flags, not
+        /// only what the window's live-out analysis keeps: a drop-in
+        /// replacement of the whole machine state, at the cost of fewer
+        /// optimizations
+        #[arg(long)]
+        strict: bool,
+        /// Optimize windows containing PC-relative address generation or
+        /// literal loads (adr, adrp, ldr literal; RIP-relative operands on
+        /// x86), which are refused by default
+        #[arg(long)]
+        allow_pcrel: bool,
+        /// Run the search for a fixed wall-clock budget (--timeout, default 10s)
+        /// and print candidates/sec, fast-passes/sec, SMT queries/sec and the
+        /// acceptance rate; --iterations no longer bounds the run
+        #[arg(long)]
+        bench_search: bool,
 
         // --- Architecture selection ---
         /// Target architecture (auto-detected from ELF if not specified)
@@ -272,9 +413,27 @@ enum Commands {
         /// Timeout in seconds for the search
         #[arg(long)]
         timeout: Option<u64>,
+        /// Stop the enumerative or symbolic search after evaluating this many
+        /// candidates, for runs that are reproducible across machines
+        #[arg(long)]
+        max_candidates: Option<u64>,
         /// Cost metric to optimize
-        #[arg(long, value_enum, default_value = "instruction-count")]
+        #[arg(
+            long,
+            visible_alias = "metric",
+            value_enum,
+            default_value = "instruction-count"
+        )]
         cost_metric: CliCostMetric,
+        /// JSON object of per-opcode latencies (`{"10": 1}`: opcode id to
+        /// cycles) overriding the built-in table; needs `--cost-metric latency`
+        #[arg(long)]
+        latency_table: Option<PathBuf>,
+        /// Search with the fixed register (x0-x7) and immediate pools instead
+        /// of the window's own registers, two scratch registers and its
+        /// immediates (AArch64)
+        #[arg(long)]
+        no_seed_from_window: bool,
         /// Enable verbose output
         #[arg(long, short)]
         verbose: bool,
@@ -289,6 +448,10 @@ enum Commands {
         /// Random seed for reproducibility
         #[arg(long)]
         seed: Option<u64>,
+        /// Assembly file whose sequence the MCMC chain starts from instead of
+        /// the target (stochastic and hybrid, AArch64); `-` reads stdin
+        #[arg(long)]
+        initial_program: Option<PathBuf>,
 
         // --- Symbolic search options ---
         /// Search mode for symbolic synthesis
@@ -316,7 +479,7 @@ enum Commands {
     },
     /// Run LLM-assisted optimization on a single assembly file (demo entry point)
     LlmOpt {
-        /// Path to an .s file containing the target sequence (GAS syntax)
+        /// Path to an .s file containing the target sequence (GAS syntax); `-` reads stdin
         #[arg(long)]
         asm: PathBuf,
         /// Live-out contract (comma-separated regs; ';nzcv' suffix is accepted for syntax compatibility with `equiv` but has no effect here — the LLM verifier always treats NZCV as live; see ADR-0006)
@@ -335,11 +498,39 @@ enum Commands {
         #[arg(short, long)]
         verbose: bool,
     },
+    /// Read AArch64 instructions from stdin and optimize each blank-line
+    /// separated block (`:help` lists the settings commands)
+    Repl {
+        /// Search algorithm for each block (`:algo` changes it)
+        #[arg(long, value_enum, default_value = "symbolic")]
+        algorithm: CliAlgorithm,
+        /// Initial live-out contract (`:live-out` changes it)
+        #[arg(long, default_value = "x0")]
+        live_out: String,
+        /// Per-block search timeout in seconds (`:timeout` changes it)
+        #[arg(long, default_value = "10")]
+        timeout: u64,
+    },
+    /// Run enumerative, stochastic and symbolic search on one assembly file
+    /// and tabulate how each did
+    CompareAlgorithms {
+        /// Assembly file containing the target sequence; `-` reads stdin
+        asm: PathBuf,
+        /// Live-out contract (comma-separated regs; optional ';nzcv' suffix declares flags live)
+        #[arg(long, default_value = "x0")]
+        live_out: String,
+        /// Search timeout in seconds, applied to each algorithm
+        #[arg(long, default_value = "10")]
+        timeout: u64,
+        /// Random seed for the stochastic run
+        #[arg(long)]
+        seed: Option<u64>,
+    },
     /// Check semantic equivalence of two assembly files
     Equiv {
-        /// First assembly file
+        /// First assembly file; `-` reads stdin
         file1: PathBuf,
-        /// Second assembly file
+        /// Second assembly file; `-` reads stdin
         file2: PathBuf,
         /// Live-out contract (comma-separated regs; optional ';nzcv' suffix declares flags live, e.g. "x0,x1;nzcv")
         #[arg(long, default_value = "x0,x1,x2,x3,x4,x5,x6,x7")]
@@ -350,6 +541,9 @@ enum Commands {
         /// Use fast path only (random testing, no SMT)
         #[arg(long)]
         fast_only: bool,
+        /// Result format written to stdout
+        #[arg(long, value_enum, default_value = "text")]
+        format: OutputFormat,
         /// Enable verbose output
         #[arg(short, long)]
         verbose: bool,
@@ -383,8 +577,14 @@ enum OptTargetError {
         arch: CliArch,
         algorithm: CliAlgorithm,
     },
+    /// Symbolic search was requested from a build without the `z3` feature.
+    SymbolicWithoutZ3,
 }
 
+/// Why a build without the `z3` feature cannot run symbolic search.
+const SYMBOLIC_WITHOUT_Z3: &str =
+    "--algorithm symbolic needs an SMT solver; rebuild s11 with the `z3` feature";
+
 impl std::fmt::Display for OptTargetError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -402,6 +602,7 @@ impl std::fmt::Display for OptTargetError {
                 "x86 supports --algorithm enumerative / stochastic / symbolic in this release; \
                  hybrid and llm remain AArch64-only.",
             ),
+            OptTargetError::SymbolicWithoutZ3 => f.write_str(SYMBOLIC_WITHOUT_Z3),
         }
     }
 }
@@ -417,11 +618,16 @@ impl std::error::Error for OptTargetError {}
 /// the chosen search algorithm. The rules are applied in the same order the
 /// CLI has always used: reject an `--arch` that disagrees with the ELF, then
 /// reject RISC-V, then reject x86 paired with an AArch64-only algorithm.
+/// A build without the `z3` feature rejects symbolic search up front.
 fn resolve_opt_target(
     requested: Option<CliArch>,
     detected: CliArch,
     algorithm: CliAlgorithm,
 ) -> Result<SupportedArch, OptTargetError> {
+    if !cfg!(feature = "z3") && algorithm == CliAlgorithm::Symbolic {
+        return Err(OptTargetError::SymbolicWithoutZ3);
+    }
+
     let arch = match requested {
         Some(a) if a != detected => {
             return Err(OptTargetError::ArchMismatch {
@@ -448,6 +654,207 @@ fn resolve_opt_target(
     Ok(supported)
 }
 
+/// The `opt` cost metric: `metric`, or `CostMetric::CustomLatency` loaded
+/// from the `--latency-table` JSON file when one is given.
+fn resolve_cost_metric(
+    metric: CliCostMetric,
+    latency_table: Option<&Path>,
+) -> Result<CostMetric, Box<dyn std::error::Error>> {
+    let Some(path) = latency_table else {
+        return Ok(metric.into());
+    };
+    if !matches!(metric, CliCostMetric::Latency) {
+        return Err("--latency-table requires --cost-metric latency".into());
+    }
+    let overrides: std::collections::HashMap<u8, u64> =
+        serde_json::from_str(&fs::read_to_string(path)?)
+            .map_err(|e| format!("invalid latency table {}: {}", path.display(), e))?;
+    let table = semantics::cost::LatencyTable::from_overrides(&overrides)
+        .map_err(|e| format!("invalid latency table {}: {}", path.display(), e))?;
+    Ok(CostMetric::CustomLatency(table))
+}
+
+/// Whether `--qemu-verify` can run for `target`: it needs the `qemu` cargo
+/// feature and an AArch64 window. Checked before search so an unusable flag
+/// fails fast instead of after a long search.
+fn qemu_verify_supported(target: SupportedArch) -> Result<(), String> {
+    if !cfg!(feature = "qemu") {
+        return Err("--qemu-verify requires s11 to be built with `--features qemu`".to_string());
+    }
+    if target != SupportedArch::Aarch64 {
+        return Err("--qemu-verify is only supported for AArch64 binaries".to_string());
+    }
+    Ok(())
+}
+
+/// Whether `metric` can score `target` windows: only the AArch64 cost model
+/// counts clobbered registers or reads a latency table, so either is refused
+/// elsewhere rather than silently scored as plain count or latency.
+fn cost_metric_supported(target: SupportedArch, metric: &CostMetric) -> Result<(), String> {
+    if metric.counts_clobbers() && target != SupportedArch::Aarch64 {
+        return Err(format!(
+            "--cost-metric {} is only supported for AArch64 binaries",
+            metric
+        ));
+    }
+    if matches!(metric, CostMetric::CustomLatency(_)) && target != SupportedArch::Aarch64 {
+        return Err("--latency-table is only supported for AArch64 binaries".to_string());
+    }
+    Ok(())
+}
+
+/// Lines printed by `disasm --list-sections`: one per executable section,
+/// with its virtual address range (end exclusive), file offset and size.
+fn list_sections(path: &Path) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let sections = ElfPatcher::new(path)?.get_text_sections()?;
+    let mut lines = vec!["Executable sections:".to_string()];
+    if sections.is_empty() {
+        lines.push("  (none)".to_string());
+    }
+    for section in &sections {
+        let end = section
+            .virtual_addr
+            .checked_add(section.size)
+            .ok_or_else(|| {
+                format!(
+                    "Section {} address range 0x{:x}+0x{:x} overflows",
+                    section.name, section.virtual_addr, section.size
+                )
+            })?;
+        lines.push(format!(
+            "  {:<16} 0x{:08x}-0x{:08x}  offset 0x{:x}  size {} bytes",
+            section.name, section.virtual_addr, end, section.file_offset, section.size
+        ));
+    }
+    Ok(lines)
+}
+
+/// How many unsupported mnemonics `disasm --report-unsupported` lists.
+const UNSUPPORTED_REPORT_TOP: usize = 20;
+
+/// Instruction-support census over every executable section of a binary.
+#[derive(Debug, Default)]
+struct SupportCensus {
+    supported: usize,
+    /// Supported instructions by IR opcode, condition code included
+    /// (`csel.eq`). Instructions the conversion drops have no entry.
+    supported_opcodes: search::llm::ledger::UnsupportedMnemonicLedger,
+    unsupported: search::llm::ledger::UnsupportedMnemonicLedger,
+}
+
+impl SupportCensus {
+    fn unsupported_count(&self) -> usize {
+        self.unsupported
+            .sorted_entries()
+            .iter()
+            .map(|(_, count)| *count as usize)
+            .sum()
+    }
+}
+
+/// Classify every decoded instruction with the same recognition the
+/// optimizer uses for window IR. Instructions the AArch64 conversion drops
+/// (NOPs) count as supported, since they never block a window there.
+fn support_census(patcher: &ElfPatcher) -> Result<SupportCensus, Box<dyn std::error::Error>> {
+    match patcher.arch() {
+        DetectedArch::Aarch64 => support_census_with_backend(AArch64OptimizationBackend, patcher),
+        DetectedArch::X86_64 | DetectedArch::X86_32 => support_census_with_backend(
+            X86OptimizationBackend::new(X86Arch::try_from(patcher.arch())?),
+            patcher,
+        ),
+    }
+}
+
+fn support_census_with_backend<B: ElfOptimizationBackend>(
+    backend: B,
+    patcher: &ElfPatcher,
+) -> Result<SupportCensus, Box<dyn std::error::Error>> {
+    let cs = backend.disassembler()?;
+    let mut census = SupportCensus::default();
+    for section in patcher.get_text_sections()? {
+        let end = section
+            .virtual_addr
+            .checked_add(section.size)
+            .ok_or_else(|| {
+                format!(
+                    "Section {} address range 0x{:x}+0x{:x} overflows",
+                    section.name, section.virtual_addr, section.size
+                )
+            })?;
+        let window = AddressWindow {
+            start: section.virtual_addr,
+            end,
+        };
+        let bytes = patcher.get_instructions_in_window(&window)?;
+        let instructions = cs.disasm_all(&bytes, section.virtual_addr)?;
+        for instruction in instructions.iter() {
+            match backend.census_opcode(instruction) {
+                Ok(opcode) => {
+                    census.supported += 1;
+                    if let Some(opcode) = opcode {
+                        census.supported_opcodes.record(&opcode);
+                    }
+                }
+                Err(_) => census
+                    .unsupported
+                    .record(instruction.mnemonic().unwrap_or("???")),
+            }
+        }
+    }
+    Ok(census)
+}
+
+/// Lines printed by `disasm --report-unsupported`: the supported/unsupported
+/// split, then the `top` most frequent unsupported mnemonics and supported
+/// opcodes.
+fn format_support_census(census: &SupportCensus, top: usize) -> Vec<String> {
+    let unsupported = census.unsupported_count();
+    let total = census.supported + unsupported;
+    let percent = |count: usize| {
+        if total == 0 {
+            0.0
+        } else {
+            100.0 * count as f64 / total as f64
+        }
+    };
+    let mut lines = vec![
+        format!("Instructions: {}", total),
+        format!(
+            "  Supported:   {:>7}  ({:.1}%)",
+            census.supported,
+            percent(census.supported)
+        ),
+        format!(
+            "  Unsupported: {:>7}  ({:.1}%)",
+            unsupported,
+            percent(unsupported)
+        ),
+    ];
+    let entries = census.unsupported.sorted_entries();
+    if !entries.is_empty() {
+        lines.push(format!(
+            "Top unsupported mnemonics ({} of {}):",
+            entries.len().min(top),
+            entries.len()
+        ));
+        for (mnemonic, count) in entries.into_iter().take(top) {
+            lines.push(format!("  {:>7}  {}", count, mnemonic));
+        }
+    }
+    let opcodes = census.supported_opcodes.sorted_entries();
+    if !opcodes.is_empty() {
+        lines.push(format!(
+            "Top supported opcodes ({} of {}):",
+            opcodes.len().min(top),
+            opcodes.len()
+        ));
+        for (opcode, count) in opcodes.into_iter().take(top) {
+            lines.push(format!("  {:>7}  {}", count, opcode));
+        }
+    }
+    lines
+}
+
 fn analyze_elf_binary(
     path: &Path,
     disasm_mode: bool,
@@ -570,11 +977,44 @@ fn analyze_elf_binary(
 struct OptimizationOptions {
     algorithm: Algorithm,
     timeout: Option<Duration>,
+    /// Candidate budget for the enumerative and symbolic searches
+    /// (`--max-candidates`).
+    max_candidates: Option<u64>,
     cost_metric: CostMetric,
     verbose: bool,
+    /// Suppress progress output (`--quiet`); results are still printed.
+    quiet: bool,
+    /// Leading / trailing window instructions held fixed (`--fixed-prefix`,
+    /// `--fixed-suffix`), counted in IR instructions.
+    fixed_prefix: usize,
+    fixed_suffix: usize,
+    /// Run the QEMU runtime cross-check on an optimized window
+    /// (`--qemu-verify`); a mismatch aborts before the binary is patched.
+    qemu_verify: bool,
+    /// Re-check an optimized window and print the justification
+    /// (`--explain`).
+    explain: bool,
+    /// How each planned window is reported on stdout (`--format`).
+    format: OutputFormat,
+    /// Write the output even when no improvement was found
+    /// (`--write-unchanged`); by default nothing is written.
+    write_unchanged: bool,
+    /// Search under every register and NZCV live (`--strict`) instead of
+    /// the per-window live-out.
+    strict: bool,
+    /// Let windows with PC-relative instructions through (`--allow-pcrel`).
+    allow_pcrel: bool,
+    /// Print the throughput report after each search (`--bench-search`).
+    bench_search: bool,
+    /// Keep the fixed AArch64 register/immediate pools rather than deriving
+    /// them from the window (`--no-seed-from-window`).
+    no_seed_from_window: bool,
     beta: f64,
-    iterations: u64,
+    /// MCMC iteration cap; `None` runs until the timeout.
+    iterations: Option<u64>,
     seed: Option<u64>,
+    /// Starting sequence for the MCMC chain (`--initial-program`).
+    initial_program: Option<Vec<Instruction>>,
     search_mode: SearchMode,
     solver_timeout: Duration,
     // Parallel/Hybrid options
@@ -583,6 +1023,37 @@ struct OptimizationOptions {
     // LLM options
     llm_max_calls: u32,
     llm_model: String,
+    /// Raised by the Ctrl-C handler (`ctrlc` feature); every search polls
+    /// it and returns its best result so far.
+    stop_flag: Option<Arc<AtomicBool>>,
+}
+
+/// Route Ctrl-C to a stop flag the searches poll, so an interrupted `opt`
+/// unwinds and reports the best rewrite found so far. A second Ctrl-C exits
+/// at once.
+#[cfg(feature = "ctrlc")]
+fn install_interrupt_handler() -> Option<Arc<AtomicBool>> {
+    let flag = Arc::new(AtomicBool::new(false));
+    let handler_flag = Arc::clone(&flag);
+    let installed = ctrlc::set_handler(move || {
+        if handler_flag.swap(true, std::sync::atomic::Ordering::SeqCst) {
+            std::process::exit(130);
+        }
+        eprintln!("\nInterrupted: stopping the search (Ctrl-C again to abort)");
+    });
+    match installed {
+        Ok(()) => Some(flag),
+        Err(e) => {
+            eprintln!("Warning: Ctrl-C will abort the search: {e}");
+            None
+        }
+    }
+}
+
+/// Without the `ctrlc` feature Ctrl-C keeps its default, killing the run.
+#[cfg(not(feature = "ctrlc"))]
+fn install_interrupt_handler() -> Option<Arc<AtomicBool>> {
+    None
 }
 
 // --- Optimization Function ---
@@ -612,6 +1083,10 @@ struct OptimizationContext {
     /// window's live-out set is narrowed to (written ∩ this) when available;
     /// `Unknown` keeps every written register live (issue #621).
     downstream_live_regs: DownstreamLiveRegs,
+    /// Byte budget for the searched region: the window size less the
+    /// encodings of any held-fixed instructions. `None` leaves rewrites
+    /// unbounded.
+    max_bytes: Option<usize>,
 }
 
 impl Default for OptimizationContext {
@@ -619,6 +1094,7 @@ impl Default for OptimizationContext {
         Self {
             downstream_flags_live: true,
             downstream_live_regs: DownstreamLiveRegs::Unknown,
+            max_bytes: None,
         }
     }
 }
@@ -633,7 +1109,7 @@ enum CandidateInstructionDisposition {
 }
 
 trait ElfOptimizationBackend {
-    type Instruction: std::fmt::Display;
+    type Instruction: std::fmt::Display + Clone + PartialEq;
 
     fn arch(&self) -> DetectedArch;
 
@@ -652,12 +1128,32 @@ trait ElfOptimizationBackend {
         instructions: &capstone::Instructions,
     ) -> Result<Vec<Self::Instruction>, String>;
 
+    /// [`Self::convert_ir`] plus the source address of each IR instruction.
+    /// The default assumes one IR instruction per decoded instruction.
+    fn convert_ir_with_provenance(
+        &self,
+        instructions: &capstone::Instructions,
+    ) -> Result<(Vec<Self::Instruction>, Vec<InstructionMeta>), String> {
+        let ir = self.convert_ir(instructions)?;
+        let provenance = instructions
+            .iter()
+            .map(|instruction| InstructionMeta::from_addr(instruction.address()))
+            .collect();
+        Ok((ir, provenance))
+    }
+
     #[cfg_attr(not(test), allow(dead_code))]
     fn classify_candidate_instruction(
         &self,
         instruction: &capstone::Insn<'_>,
     ) -> Result<CandidateInstructionDisposition, String>;
 
+    /// The opcode `disasm --report-unsupported` counts `instruction` under:
+    /// the [`InstructionType::suffixed_mnemonic`] of its IR, so differently
+    /// conditioned forms stay apart. `Ok(None)` when the conversion drops
+    /// the instruction (a NOP); `Err` when it is unsupported.
+    fn census_opcode(&self, instruction: &capstone::Insn<'_>) -> Result<Option<String>, String>;
+
     fn validate_window_ir(&self, ir: &[Self::Instruction]) -> Result<(), String>;
 
     /// Build the per-window `OptimizationContext`, deriving the downstream
@@ -676,6 +1172,60 @@ trait ElfOptimizationBackend {
         optimization_context_for_backend(self.arch(), patcher, section, end_addr, cs)
     }
 
+    /// Build the context for a caller-supplied live-out contract (a window
+    /// manifest's `live_out`) in place of the downstream scan.
+    fn context_from_live_out(&self, spec: &str) -> Result<OptimizationContext, String> {
+        Err(format!(
+            "a window live-out ({}) is not supported for {}",
+            spec,
+            self.arch_description()
+        ))
+    }
+
+    /// Adjust `context` for a search over the instructions that precede a
+    /// held-fixed suffix: everything the suffix reads must stay live-out of
+    /// the searched region. The default drops the downstream narrowing and
+    /// keeps flags live, which is always sound.
+    fn context_before_fixed_suffix(
+        &self,
+        _context: OptimizationContext,
+        _suffix: &[Self::Instruction],
+    ) -> OptimizationContext {
+        OptimizationContext::default()
+    }
+
+    /// Cross-check `optimized` against `original` under QEMU for
+    /// `--qemu-verify`. Only the AArch64 backend implements it;
+    /// `qemu_verify_supported` rejects the flag for other targets up front.
+    fn qemu_verify(
+        &self,
+        _original: &[Self::Instruction],
+        _optimized: &[Self::Instruction],
+        _context: &OptimizationContext,
+        _options: &OptimizationOptions,
+    ) -> Result<(), String> {
+        Err(format!(
+            "--qemu-verify is not supported for {}",
+            self.arch_description()
+        ))
+    }
+
+    /// Re-check `optimized` against `original` and describe the verdict for
+    /// `--explain`. Only the AArch64 backend implements it; the CLI rejects
+    /// the flag for other targets up front.
+    fn explain(
+        &self,
+        _original: &[Self::Instruction],
+        _optimized: &[Self::Instruction],
+        _context: &OptimizationContext,
+        _options: &OptimizationOptions,
+    ) -> Result<Vec<String>, String> {
+        Err(format!(
+            "--explain is not supported for {}",
+            self.arch_description()
+        ))
+    }
+
     /// Run the selected search. `capstone_instructions` preserves the original
     /// instruction bytes for backends that need encoding metadata; backends
     /// that do not need it can ignore the argument.
@@ -689,6 +1239,27 @@ trait ElfOptimizationBackend {
 
     fn no_optimization_message(&self) -> &'static str;
 
+    /// Cost of `ir` under `metric`, as the backend's search scores it.
+    fn sequence_cost(&self, ir: &[Self::Instruction], metric: &CostMetric) -> u64;
+
+    /// `metric` with the live-out of the window `original` bound in, as
+    /// the search binds it (see [`CostMetric::with_live_out`]).
+    fn bind_live_out(
+        &self,
+        metric: CostMetric,
+        _original: &[Self::Instruction],
+        _context: &OptimizationContext,
+        _options: &OptimizationOptions,
+    ) -> CostMetric {
+        metric
+    }
+
+    /// Bytes `ir` assembles to, used to give the search the window's byte
+    /// budget. `None` (the default) leaves the search unbounded.
+    fn encoded_length(&self, _ir: &[Self::Instruction]) -> Option<usize> {
+        None
+    }
+
     fn assemble_window(
         &self,
         original_ir: &[Self::Instruction],
@@ -709,6 +1280,16 @@ impl ElfOptimizationBackend for AArch64OptimizationBackend {
         DetectedArch::Aarch64
     }
 
+    fn context_from_live_out(&self, spec: &str) -> Result<OptimizationContext, String> {
+        let live_out =
+            validation::live_out::parse_live_out_contract(spec).map_err(|e| e.to_string())?;
+        Ok(OptimizationContext {
+            downstream_flags_live: live_out.flags_live(),
+            downstream_live_regs: DownstreamLiveRegs::Aarch64(live_out),
+            max_bytes: None,
+        })
+    }
+
     fn disassembler(&self) -> Result<Capstone, Box<dyn std::error::Error>> {
         Ok(Capstone::new()
             .arm64()
@@ -724,6 +1305,13 @@ impl ElfOptimizationBackend for AArch64OptimizationBackend {
         convert_to_ir(instructions)
     }
 
+    fn convert_ir_with_provenance(
+        &self,
+        instructions: &capstone::Instructions,
+    ) -> Result<(Vec<Self::Instruction>, Vec<InstructionMeta>), String> {
+        convert_to_ir_with_provenance(instructions)
+    }
+
     fn classify_candidate_instruction(
         &self,
         instruction: &capstone::Insn<'_>,
@@ -739,6 +1327,15 @@ impl ElfOptimizationBackend for AArch64OptimizationBackend {
         })
     }
 
+    fn census_opcode(&self, instruction: &capstone::Insn<'_>) -> Result<Option<String>, String> {
+        let converted = convert_capstone_op_for_optimization(
+            instruction.mnemonic().unwrap_or(""),
+            instruction.op_str().unwrap_or(""),
+            instruction.address(),
+        )?;
+        Ok(converted.map(|ir| ir.suffixed_mnemonic()))
+    }
+
     fn validate_window_ir(&self, ir: &[Self::Instruction]) -> Result<(), String> {
         validate_basic_block(ir)
     }
@@ -781,6 +1378,7 @@ impl ElfOptimizationBackend for AArch64OptimizationBackend {
                 patcher, section, end_addr, cs,
             ),
             downstream_live_regs,
+            max_bytes: None,
         }
     }
 
@@ -795,13 +1393,121 @@ impl ElfOptimizationBackend for AArch64OptimizationBackend {
             DownstreamLiveRegs::Aarch64(set) => Some(set.clone()),
             _ => None,
         };
-        run_optimization(ir, options, context.downstream_flags_live, downstream_live)
+        run_optimization(
+            ir,
+            options,
+            context.downstream_flags_live,
+            downstream_live,
+            context.max_bytes,
+        )
+    }
+
+    fn context_before_fixed_suffix(
+        &self,
+        context: OptimizationContext,
+        suffix: &[Self::Instruction],
+    ) -> OptimizationContext {
+        // The suffix sits between the searched region and the downstream
+        // code, so its reads join the downstream-live set. Registers the
+        // suffix overwrites stay in the set too: over-approximating liveness
+        // only costs narrowing, never soundness.
+        let downstream_live_regs = match context.downstream_live_regs {
+            DownstreamLiveRegs::Aarch64(mut live) => {
+                for reg in suffix.iter().flat_map(|instr| instr.source_registers()) {
+                    live.add(reg);
+                }
+                DownstreamLiveRegs::Aarch64(live)
+            }
+            other => other,
+        };
+        OptimizationContext {
+            downstream_flags_live: context.downstream_flags_live
+                || validation::live_out::reads_flags_before_writing(suffix),
+            downstream_live_regs,
+            max_bytes: context.max_bytes,
+        }
+    }
+
+    #[cfg(feature = "qemu")]
+    fn qemu_verify(
+        &self,
+        original: &[Self::Instruction],
+        optimized: &[Self::Instruction],
+        context: &OptimizationContext,
+        options: &OptimizationOptions,
+    ) -> Result<(), String> {
+        use validation::qemu::{QemuVerdict, QemuVerifyConfig, qemu_verify};
+
+        // The terminator is held fixed by every search, so only the prefixes
+        // run under QEMU; its reads are pinned in the live-out contract.
+        let (original_prefix, _) = split_terminator(original);
+        let (optimized_prefix, _) = split_terminator(optimized);
+        let live_out = window_live_out(original, context, options);
+        let config = QemuVerifyConfig {
+            seed: options.seed,
+            ..QemuVerifyConfig::default()
+        };
+        match qemu_verify(original_prefix, optimized_prefix, &live_out, &config)? {
+            QemuVerdict::Agree { inputs } => {
+                progress!(
+                    options.quiet,
+                    "QEMU cross-check passed on {} random inputs",
+                    inputs
+                );
+                Ok(())
+            }
+            QemuVerdict::Mismatch(mismatch) => {
+                Err(format!("QEMU cross-check failed: {}", mismatch))
+            }
+        }
+    }
+
+    fn explain(
+        &self,
+        original: &[Self::Instruction],
+        optimized: &[Self::Instruction],
+        context: &OptimizationContext,
+        options: &OptimizationOptions,
+    ) -> Result<Vec<String>, String> {
+        // Same contract the search and `--qemu-verify` use: the terminator is
+        // held fixed and its reads are pinned live.
+        let (original_prefix, _) = split_terminator(original);
+        let (optimized_prefix, _) = split_terminator(optimized);
+        let live_out = window_live_out(original, context, options);
+        let config = semantics::EquivalenceConfig {
+            smt_timeout: Some(options.solver_timeout),
+            fast_only: options.solver_timeout.is_zero(),
+            ..semantics::EquivalenceConfig::with_live_out(live_out)
+        };
+        Ok(explanation_lines(
+            original_prefix,
+            optimized_prefix,
+            &config,
+        ))
     }
 
     fn no_optimization_message(&self) -> &'static str {
         "No optimization found, using original instructions."
     }
 
+    fn sequence_cost(&self, ir: &[Self::Instruction], metric: &CostMetric) -> u64 {
+        semantics::cost::sequence_cost(ir, metric)
+    }
+
+    fn bind_live_out(
+        &self,
+        metric: CostMetric,
+        original: &[Self::Instruction],
+        context: &OptimizationContext,
+        options: &OptimizationOptions,
+    ) -> CostMetric {
+        metric.with_live_out(&window_live_out(original, context, options))
+    }
+
+    fn encoded_length(&self, ir: &[Self::Instruction]) -> Option<usize> {
+        Some(ir.iter().map(Instruction::encoded_length).sum())
+    }
+
     fn assemble_window(
         &self,
         _original_ir: &[Self::Instruction],
@@ -930,6 +1636,16 @@ impl ElfOptimizationBackend for X86OptimizationBackend {
         })
     }
 
+    fn census_opcode(&self, instruction: &capstone::Insn<'_>) -> Result<Option<String>, String> {
+        let ir = convert_x86_capstone_op_for_optimization(
+            instruction.mnemonic().unwrap_or(""),
+            instruction.op_str().unwrap_or(""),
+            instruction.address(),
+            self.parse_mode(),
+        )?;
+        Ok(Some(ir.suffixed_mnemonic()))
+    }
+
     fn validate_window_ir(&self, ir: &[Self::Instruction]) -> Result<(), String> {
         validate_x86_window_terminator_placement(ir)
     }
@@ -975,6 +1691,7 @@ impl ElfOptimizationBackend for X86OptimizationBackend {
                 cs,
             ),
             downstream_live_regs,
+            max_bytes: None,
         }
     }
 
@@ -1005,6 +1722,7 @@ impl ElfOptimizationBackend for X86OptimizationBackend {
                 context.downstream_flags_live,
                 downstream_live.as_ref(),
             ),
+            #[cfg(feature = "z3")]
             Algorithm::Symbolic => run_x86_symbolic(
                 ir,
                 width,
@@ -1013,6 +1731,8 @@ impl ElfOptimizationBackend for X86OptimizationBackend {
                 downstream_live.as_ref(),
                 true,
             ),
+            #[cfg(not(feature = "z3"))]
+            Algorithm::Symbolic => return Err(SYMBOLIC_WITHOUT_Z3.into()),
             Algorithm::Hybrid | Algorithm::Llm => {
                 // Rejected upstream at the CLI layer; defensive check here
                 // in case a programmatic caller bypasses it.
@@ -1026,6 +1746,10 @@ impl ElfOptimizationBackend for X86OptimizationBackend {
         "No optimization found; not patching (input binary left untouched)."
     }
 
+    fn sequence_cost(&self, ir: &[Self::Instruction], metric: &CostMetric) -> u64 {
+        semantics::cost_x86::sequence_cost(ir, metric, self.arch.width())
+    }
+
     fn assemble_window(
         &self,
         original_ir: &[Self::Instruction],
@@ -1399,8 +2123,14 @@ fn optimized_output_path(path: &Path) -> PathBuf {
 /// is preserved verbatim (the pre-#616 single-window behaviour). An explicit
 /// output is honoured, except when it resolves to the input binary itself: the
 /// driver never rewrites the input in place, so that request is rejected rather
-/// than silently clobbering the source.
-fn resolve_output_path(input: &Path, output: Option<&Path>) -> Result<PathBuf, String> {
+/// than silently clobbering the source. An explicit output that already exists
+/// is likewise refused unless `overwrite` (`--overwrite`) is set, so a scripted
+/// run cannot quietly replace the result of an earlier one.
+fn resolve_output_path(
+    input: &Path,
+    output: Option<&Path>,
+    overwrite: bool,
+) -> Result<PathBuf, String> {
     match output {
         Some(out) => {
             if paths_point_to_same_file(input, out) {
@@ -1408,6 +2138,11 @@ fn resolve_output_path(input: &Path, output: Option<&Path>) -> Result<PathBuf, S
                     "output path '{}' resolves to the input binary; refusing to optimize in place (choose a different -o/--output)",
                     out.display()
                 ))
+            } else if !overwrite && out.exists() {
+                Err(format!(
+                    "output path '{}' already exists; pass --overwrite to replace it",
+                    out.display()
+                ))
             } else {
                 Ok(out.to_path_buf())
             }
@@ -1462,6 +2197,32 @@ fn run_auto_optimization(
     Err("whole-binary auto optimization (--auto) is not yet implemented".into())
 }
 
+/// A window's IR as (held-fixed prefix, searched region, held-fixed suffix).
+type FixedRegions<'a, T> = (&'a [T], &'a [T], &'a [T]);
+
+/// Split a window's IR into the held-fixed prefix, the searched region and
+/// the held-fixed suffix. Errors when the fixed regions leave nothing to
+/// search.
+fn split_fixed_regions<T>(
+    ir: &[T],
+    fixed_prefix: usize,
+    fixed_suffix: usize,
+) -> Result<FixedRegions<'_, T>, String> {
+    let fixed = fixed_prefix.saturating_add(fixed_suffix);
+    if fixed > 0 && fixed >= ir.len() {
+        return Err(format!(
+            "--fixed-prefix {} and --fixed-suffix {} leave nothing to optimize in a \
+             {}-instruction window",
+            fixed_prefix,
+            fixed_suffix,
+            ir.len()
+        ));
+    }
+    let (prefix, rest) = ir.split_at(fixed_prefix);
+    let (searched, suffix) = rest.split_at(rest.len() - fixed_suffix);
+    Ok((prefix, searched, suffix))
+}
+
 fn decode_arch_label(arch: DetectedArch) -> &'static str {
     match arch {
         DetectedArch::Aarch64 => "AArch64",
@@ -1510,41 +2271,625 @@ fn optimize_elf_binary_with_backend<B: ElfOptimizationBackend>(
     output_path: &Path,
     options: &OptimizationOptions,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    println!("Optimizing ELF binary: {}", path.display());
-    println!("Detected: {}", backend.arch_description());
-    println!("Address window: 0x{:x} - 0x{:x}", start_addr, end_addr);
-    println!("Algorithm: {:?}", options.algorithm);
-
-    // Create address window
-    let window = AddressWindow {
-        start: start_addr,
-        end: end_addr,
+    progress!(options.quiet, "Optimizing ELF binary: {}", path.display());
+    let Some(plan) = plan_elf_optimization(&backend, patcher, start_addr, end_addr, None, options)?
+    else {
+        return Ok(());
     };
+    print_patch_plan(&plan, options);
 
-    let section = patcher.validate_address_window(&window)?;
-    println!("Window is within section: {}", section.name);
+    // Create patched ELF file at the caller-resolved output path.
+    elf_patcher::apply_plan(patcher, &plan, output_path)?;
+    check_patch_confined(patcher, output_path, &[&plan.window])?;
+    print_opt_outcome(
+        options,
+        &format!("Created optimized binary: {}", output_path.display()),
+    );
 
-    // Get the original instructions in the window
-    let original_bytes = patcher.get_instructions_in_window(&window)?;
-    println!("Original code: {} bytes", original_bytes.len());
+    Ok(())
+}
 
-    // Initialize Capstone disassembler
-    let cs = backend.disassembler()?;
+/// Re-read the patched copy at `output_path` and fail if any byte outside
+/// `windows` differs from the input, i.e. a header or another section was
+/// corrupted on the way out.
+fn check_patch_confined(
+    patcher: &ElfPatcher,
+    output_path: &Path,
+    windows: &[&AddressWindow],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let stray = patcher.diffs_outside_windows(output_path, windows)?;
+    if let Some((offset, original, patched)) = stray.first() {
+        return Err(format!(
+            "{} changed {} byte(s) outside the optimized window(s); first at file offset 0x{:x} (0x{:02x} -> 0x{:02x})",
+            output_path.display(),
+            stray.len(),
+            offset,
+            original,
+            patched
+        )
+        .into());
+    }
+    Ok(())
+}
 
-    // Disassemble instructions in the window
-    let instructions = cs.disasm_all(&original_bytes, start_addr)?;
-    println!("Disassembled {} instructions:", instructions.len());
+/// One entry of an `opt --windows` manifest.
+#[derive(Debug, serde::Deserialize)]
+struct ManifestEntry {
+    start: String,
+    end: String,
+    #[serde(default)]
+    live_out: Option<String>,
+}
 
-    for instruction in instructions.iter() {
-        println!(
-            "  0x{:x}: {} {}",
-            instruction.address(),
-            instruction.mnemonic().unwrap_or("???"),
-            instruction.op_str().unwrap_or("")
-        );
-    }
+/// A manifest window with its addresses parsed.
+#[derive(Debug, PartialEq)]
+struct ManifestWindow {
+    window: AddressWindow,
+    live_out: Option<String>,
+}
 
-    let decoded_bytes: usize = instructions.iter().map(|i| i.bytes().len()).sum();
+/// Read an `opt --windows` manifest: a JSON array of `{start, end,
+/// live_out?}` objects with hex addresses.
+fn load_window_manifest(path: &Path) -> Result<Vec<ManifestWindow>, Box<dyn std::error::Error>> {
+    let entries: Vec<ManifestEntry> = serde_json::from_str(&fs::read_to_string(path)?)
+        .map_err(|e| format!("invalid window manifest {}: {}", path.display(), e))?;
+    entries
+        .into_iter()
+        .map(|entry| {
+            Ok(ManifestWindow {
+                window: AddressWindow {
+                    start: parse_hex_address(&entry.start)?,
+                    end: parse_hex_address(&entry.end)?,
+                },
+                live_out: entry.live_out,
+            })
+        })
+        .collect::<Result<_, String>>()
+        .map_err(Into::into)
+}
+
+fn optimize_window_manifest(
+    patcher: &ElfPatcher,
+    path: &Path,
+    windows: &[ManifestWindow],
+    output_path: &Path,
+    options: &OptimizationOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match patcher.arch() {
+        DetectedArch::Aarch64 => optimize_window_manifest_with_backend(
+            AArch64OptimizationBackend,
+            patcher,
+            path,
+            windows,
+            output_path,
+            options,
+        ),
+        DetectedArch::X86_64 | DetectedArch::X86_32 => optimize_window_manifest_with_backend(
+            X86OptimizationBackend::new(X86Arch::try_from(patcher.arch())?),
+            patcher,
+            path,
+            windows,
+            output_path,
+            options,
+        ),
+    }
+}
+
+/// Plan every manifest window in order, then write all the patches to one
+/// copy of the input.
+fn optimize_window_manifest_with_backend<B: ElfOptimizationBackend>(
+    backend: B,
+    patcher: &ElfPatcher,
+    path: &Path,
+    windows: &[ManifestWindow],
+    output_path: &Path,
+    options: &OptimizationOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    progress!(
+        options.quiet,
+        "Optimizing {} windows of ELF binary: {}",
+        windows.len(),
+        path.display()
+    );
+    let mut plans = Vec::new();
+    for entry in windows {
+        let plan = plan_elf_optimization(
+            &backend,
+            patcher,
+            entry.window.start,
+            entry.window.end,
+            entry.live_out.as_deref(),
+            options,
+        )?;
+        if let Some(plan) = plan {
+            print_patch_plan(&plan, options);
+            plans.push(plan);
+        }
+    }
+    if plans.is_empty() {
+        print_opt_outcome(options, "No window improved; binary unchanged.");
+        return Ok(());
+    }
+
+    elf_patcher::apply_plans(patcher, &plans, output_path)?;
+    let windows: Vec<&AddressWindow> = plans.iter().map(|plan| &plan.window).collect();
+    check_patch_confined(patcher, output_path, &windows)?;
+    print_opt_outcome(
+        options,
+        &format!(
+            "Created optimized binary: {} ({} windows patched)",
+            output_path.display(),
+            plans.len()
+        ),
+    );
+
+    Ok(())
+}
+
+/// What `opt --optimize-all` did with one function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum FunctionOutcome {
+    /// Rewritten; the patch frees this many bytes to NOP padding.
+    Improved {
+        bytes_saved: usize,
+    },
+    Unchanged,
+    /// Holds an instruction s11 cannot model, named by its first mnemonic.
+    Unsupported(String),
+    /// Planning failed for another reason, e.g. a branch inside the body.
+    Failed(String),
+}
+
+fn optimize_all_functions(
+    patcher: &ElfPatcher,
+    path: &Path,
+    min_size: u64,
+    output_path: &Path,
+    options: &OptimizationOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match patcher.arch() {
+        DetectedArch::Aarch64 => optimize_all_functions_with_backend(
+            AArch64OptimizationBackend,
+            patcher,
+            path,
+            min_size,
+            output_path,
+            options,
+        ),
+        DetectedArch::X86_64 | DetectedArch::X86_32 => optimize_all_functions_with_backend(
+            X86OptimizationBackend::new(X86Arch::try_from(patcher.arch())?),
+            patcher,
+            path,
+            min_size,
+            output_path,
+            options,
+        ),
+    }
+}
+
+/// Plan every function of at least `min_size` bytes as its own window, then
+/// write all the improvements to one copy of the input. A function that
+/// cannot be optimized is reported and skipped rather than failing the run.
+fn optimize_all_functions_with_backend<B: ElfOptimizationBackend>(
+    backend: B,
+    patcher: &ElfPatcher,
+    path: &Path,
+    min_size: u64,
+    output_path: &Path,
+    options: &OptimizationOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (functions, stubs): (Vec<_>, Vec<_>) = patcher
+        .function_symbols()?
+        .into_iter()
+        .partition(|function| function.window.end - function.window.start >= min_size);
+    if functions.is_empty() {
+        return Err(format!(
+            "no function symbols of at least {} bytes in {}",
+            min_size,
+            path.display()
+        )
+        .into());
+    }
+    progress!(
+        options.quiet,
+        "Optimizing {} functions of ELF binary: {} ({} below --min-size {} skipped)",
+        functions.len(),
+        path.display(),
+        stubs.len(),
+        min_size
+    );
+
+    let cs = backend.disassembler()?;
+    let mut plans = Vec::new();
+    let mut outcomes = Vec::new();
+    for function in &functions {
+        progress!(options.quiet, "\nFunction {}:", function.name);
+        let (outcome, plan) = plan_function(&backend, &cs, patcher, &function.window, options);
+        if let (FunctionOutcome::Improved { .. }, Some(plan)) = (&outcome, plan) {
+            plans.push(plan);
+        }
+        outcomes.push((function, outcome));
+    }
+
+    for line in format_function_summary(&outcomes) {
+        println!("{}", line);
+    }
+    if plans.is_empty() {
+        println!("No function improved; binary unchanged.");
+        return Ok(());
+    }
+
+    elf_patcher::apply_plans(patcher, &plans, output_path)?;
+    let windows: Vec<&AddressWindow> = plans.iter().map(|plan| &plan.window).collect();
+    check_patch_confined(patcher, output_path, &windows)?;
+    println!(
+        "Created optimized binary: {} ({} functions patched)",
+        output_path.display(),
+        plans.len()
+    );
+
+    Ok(())
+}
+
+/// Optimize one function's `window`, returning what happened to it and the
+/// plan the search produced, if it got that far.
+fn plan_function<B: ElfOptimizationBackend>(
+    backend: &B,
+    cs: &Capstone,
+    patcher: &ElfPatcher,
+    window: &AddressWindow,
+    options: &OptimizationOptions,
+) -> (
+    FunctionOutcome,
+    Option<elf_patcher::PatchPlan<B::Instruction>>,
+) {
+    match first_unsupported_mnemonic(backend, cs, patcher, window) {
+        Ok(Some(mnemonic)) => (FunctionOutcome::Unsupported(mnemonic), None),
+        Err(e) => (FunctionOutcome::Failed(e.to_string()), None),
+        Ok(None) => {
+            match plan_elf_optimization(backend, patcher, window.start, window.end, None, options) {
+                Ok(Some(plan)) if plan.optimized != plan.original => (
+                    FunctionOutcome::Improved {
+                        bytes_saved: plan.padding_bytes(),
+                    },
+                    Some(plan),
+                ),
+                Ok(plan) => (FunctionOutcome::Unchanged, plan),
+                Err(e) => (FunctionOutcome::Failed(e.to_string()), None),
+            }
+        }
+    }
+}
+
+/// One column of the `opt --compare-binary` report.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ComparedFunction {
+    binary: String,
+    window: AddressWindow,
+    /// Search cost before and after, when the search ran.
+    costs: Option<(u64, u64)>,
+    outcome: FunctionOutcome,
+}
+
+/// Optimize the function `name` in both `patcher`'s binary and `other`, and
+/// print the two results side by side. Neither binary is written.
+fn compare_function_across_binaries(
+    patcher: &ElfPatcher,
+    path: &Path,
+    other: &Path,
+    name: &str,
+    options: &OptimizationOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let other_patcher = ElfPatcher::new(other)?;
+    if other_patcher.arch() != patcher.arch() {
+        return Err(format!(
+            "{} is {} but {} is {}",
+            path.display(),
+            CliArch::from(patcher.arch()),
+            other.display(),
+            CliArch::from(other_patcher.arch())
+        )
+        .into());
+    }
+    let binaries = [(path, patcher), (other, &other_patcher)];
+    let columns = match patcher.arch() {
+        DetectedArch::Aarch64 => {
+            compare_function_with_backend(AArch64OptimizationBackend, binaries, name, options)?
+        }
+        DetectedArch::X86_64 | DetectedArch::X86_32 => compare_function_with_backend(
+            X86OptimizationBackend::new(X86Arch::try_from(patcher.arch())?),
+            binaries,
+            name,
+            options,
+        )?,
+    };
+    for line in format_binary_comparison(name, &columns) {
+        println!("{}", line);
+    }
+    Ok(())
+}
+
+fn compare_function_with_backend<B: ElfOptimizationBackend>(
+    backend: B,
+    binaries: [(&Path, &ElfPatcher); 2],
+    name: &str,
+    options: &OptimizationOptions,
+) -> Result<Vec<ComparedFunction>, Box<dyn std::error::Error>> {
+    let cs = backend.disassembler()?;
+    let mut columns = Vec::new();
+    for (path, patcher) in binaries {
+        let function = patcher
+            .function_symbols()?
+            .into_iter()
+            .find(|function| function.name == name)
+            .ok_or_else(|| format!("no function symbol {} in {}", name, path.display()))?;
+        progress!(options.quiet, "\nFunction {} in {}:", name, path.display());
+        let (outcome, plan) = plan_function(&backend, &cs, patcher, &function.window, options);
+        columns.push(ComparedFunction {
+            binary: path.display().to_string(),
+            window: function.window,
+            costs: plan.map(|plan| (plan.cost_before, plan.cost_after)),
+            outcome,
+        });
+    }
+    Ok(columns)
+}
+
+/// The `opt --compare-binary` report: one column per binary.
+fn format_binary_comparison(name: &str, columns: &[ComparedFunction]) -> Vec<String> {
+    let rows: Vec<(&str, Vec<String>)> = vec![
+        (
+            "",
+            columns.iter().map(|column| column.binary.clone()).collect(),
+        ),
+        (
+            "window",
+            columns
+                .iter()
+                .map(|column| format!("0x{:08x}-0x{:08x}", column.window.start, column.window.end))
+                .collect(),
+        ),
+        (
+            "cost",
+            columns
+                .iter()
+                .map(|column| match column.costs {
+                    Some((before, after)) => format!("{} -> {}", before, after),
+                    None => "-".to_string(),
+                })
+                .collect(),
+        ),
+        (
+            "result",
+            columns
+                .iter()
+                .map(|column| function_status(&column.outcome))
+                .collect(),
+        ),
+    ];
+    let width = rows
+        .iter()
+        .flat_map(|(_, cells)| cells.iter().map(String::len))
+        .max()
+        .unwrap_or(0);
+    let mut lines = vec![format!("\nComparison of {}:", name)];
+    for (label, cells) in rows {
+        let mut line = format!("  {:<8}", label);
+        for cell in cells {
+            line.push_str(&format!("  {:<width$}", cell));
+        }
+        lines.push(line.trim_end().to_string());
+    }
+    lines
+}
+
+/// The mnemonic of the first instruction in `window` the backend cannot
+/// model, if any.
+fn first_unsupported_mnemonic<B: ElfOptimizationBackend>(
+    backend: &B,
+    cs: &Capstone,
+    patcher: &ElfPatcher,
+    window: &AddressWindow,
+) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    patcher.validate_address_window(window)?;
+    let bytes = patcher.get_instructions_in_window(window)?;
+    let instructions = cs.disasm_all(&bytes, window.start)?;
+    Ok(instructions
+        .iter()
+        .find(|instruction| backend.classify_candidate_instruction(instruction).is_err())
+        .map(|instruction| instruction.mnemonic().unwrap_or("???").to_string()))
+}
+
+/// The `opt --optimize-all` report: one line per function, then the totals.
+fn format_function_summary(
+    outcomes: &[(&elf_patcher::FunctionSymbol, FunctionOutcome)],
+) -> Vec<String> {
+    let mut lines = vec!["\nFunction summary:".to_string()];
+    let (mut improved, mut unchanged, mut skipped, mut saved) = (0, 0, 0, 0);
+    for (function, outcome) in outcomes {
+        match outcome {
+            FunctionOutcome::Improved { bytes_saved } => {
+                improved += 1;
+                saved += bytes_saved;
+            }
+            FunctionOutcome::Unchanged => unchanged += 1,
+            FunctionOutcome::Unsupported(_) | FunctionOutcome::Failed(_) => skipped += 1,
+        }
+        lines.push(format!(
+            "  {:<24} 0x{:08x}-0x{:08x}  {}",
+            function.name,
+            function.window.start,
+            function.window.end,
+            function_status(outcome)
+        ));
+    }
+    lines.push(format!(
+        "Functions: {} improved, {} unchanged, {} skipped; {} bytes saved",
+        improved, unchanged, skipped, saved
+    ));
+    lines
+}
+
+/// How a function fared, as printed in the per-function reports.
+fn function_status(outcome: &FunctionOutcome) -> String {
+    match outcome {
+        FunctionOutcome::Improved { bytes_saved } => format!("saved {} bytes", bytes_saved),
+        FunctionOutcome::Unchanged => "unchanged".to_string(),
+        FunctionOutcome::Unsupported(mnemonic) => {
+            format!("skipped: unsupported instruction {}", mnemonic)
+        }
+        FunctionOutcome::Failed(reason) => format!("skipped: {}", reason),
+    }
+}
+
+/// Print the `opt` report for `plan`: the listing (or, under `--format
+/// json`, the whole plan) on stdout, the cost and byte footprint as progress
+/// on stderr.
+fn print_patch_plan<I: std::fmt::Display + PartialEq>(
+    plan: &elf_patcher::PatchPlan<I>,
+    options: &OptimizationOptions,
+) {
+    match options.format {
+        OutputFormat::Text => {
+            for line in patch_plan_lines(plan) {
+                println!("{}", line);
+            }
+        }
+        OutputFormat::Json => println!("{}", patch_plan_json(plan)),
+    }
+    for line in patch_plan_progress_lines(plan) {
+        progress!(options.quiet, "{}", line);
+    }
+}
+
+/// Print an `opt` outcome line: on stdout as text, but as progress under
+/// `--format json` so stdout holds only the JSON.
+fn print_opt_outcome(options: &OptimizationOptions, line: &str) {
+    match options.format {
+        OutputFormat::Text => println!("{}", line),
+        OutputFormat::Json => progress!(options.quiet, "{}", line),
+    }
+}
+
+/// The new listing for `plan`, empty when the search left the window as is.
+fn patch_plan_lines<I: std::fmt::Display + PartialEq>(
+    plan: &elf_patcher::PatchPlan<I>,
+) -> Vec<String> {
+    let mut lines = Vec::new();
+    if plan.optimized != plan.original {
+        lines.push(format!(
+            "Optimized to {} instructions:",
+            plan.optimized.len()
+        ));
+        lines.extend(plan.optimized.iter().map(|instr| format!("  {}", instr)));
+        if let Some(replaced) = &plan.replaced {
+            lines.push(format!(
+                "Replaced bytes [0x{:x}, 0x{:x})",
+                replaced.start, replaced.end
+            ));
+        }
+    }
+    lines
+}
+
+/// `opt --format json`: one object per planned window, with the listings as
+/// assembly text, addresses as hex strings and `replaced` null when the
+/// window was kept as is.
+fn patch_plan_json<I: std::fmt::Display>(plan: &elf_patcher::PatchPlan<I>) -> serde_json::Value {
+    let range = |window: &AddressWindow| {
+        serde_json::json!({
+            "start": format!("0x{:x}", window.start),
+            "end": format!("0x{:x}", window.end),
+        })
+    };
+    let listing =
+        |instructions: &[I]| -> Vec<String> { instructions.iter().map(I::to_string).collect() };
+    serde_json::json!({
+        "window": range(&plan.window),
+        "replaced": plan.replaced.as_ref().map(range),
+        "original": listing(&plan.original),
+        "optimized": listing(&plan.optimized),
+        "cost_before": plan.cost_before,
+        "cost_after": plan.cost_after,
+        "original_bytes": plan.original_bytes.len(),
+        "new_bytes": plan.new_bytes.len(),
+        "nops_added": plan.nops_added,
+    })
+}
+
+/// The cost and byte footprint of `plan`.
+fn patch_plan_progress_lines<I>(plan: &elf_patcher::PatchPlan<I>) -> Vec<String> {
+    vec![
+        format!("Cost: {} -> {}", plan.cost_before, plan.cost_after),
+        format!(
+            "Reassembled to {} of {} bytes ({} NOP padding)",
+            plan.new_bytes.len(),
+            plan.original_bytes.len(),
+            plan.nops_added
+        ),
+    ]
+}
+
+/// Run the `opt` pipeline on one window up to, but not including, writing
+/// the output: disassemble, search, verify and reassemble. `live_out`
+/// replaces the downstream liveness scan with a caller-supplied contract.
+/// `None` means the backend chose to leave the input untouched.
+fn plan_elf_optimization<B: ElfOptimizationBackend>(
+    backend: &B,
+    patcher: &ElfPatcher,
+    start_addr: u64,
+    end_addr: u64,
+    live_out: Option<&str>,
+    options: &OptimizationOptions,
+) -> Result<Option<elf_patcher::PatchPlan<B::Instruction>>, Box<dyn std::error::Error>> {
+    progress!(options.quiet, "Detected: {}", backend.arch_description());
+    progress!(
+        options.quiet,
+        "Address window: 0x{:x} - 0x{:x}",
+        start_addr,
+        end_addr
+    );
+    progress!(options.quiet, "Algorithm: {:?}", options.algorithm);
+
+    // Create address window
+    let window = AddressWindow {
+        start: start_addr,
+        end: end_addr,
+    };
+
+    let section = patcher.validate_address_window(&window)?;
+    progress!(options.quiet, "Window is within section: {}", section.name);
+
+    // Get the original instructions in the window
+    let original_bytes = patcher.get_instructions_in_window(&window)?;
+    progress!(
+        options.quiet,
+        "Original code: {} bytes",
+        original_bytes.len()
+    );
+
+    // Initialize Capstone disassembler
+    let cs = backend.disassembler()?;
+
+    // Disassemble instructions in the window
+    let instructions = cs.disasm_all(&original_bytes, start_addr)?;
+    progress!(
+        options.quiet,
+        "Disassembled {} instructions:",
+        instructions.len()
+    );
+
+    for instruction in instructions.iter() {
+        progress!(
+            options.quiet,
+            "  0x{:x}: {} {}",
+            instruction.address(),
+            instruction.mnemonic().unwrap_or("???"),
+            instruction.op_str().unwrap_or("")
+        );
+    }
+
+    let decoded_bytes: usize = instructions.iter().map(|i| i.bytes().len()).sum();
     ensure_window_fully_decoded_for_arch(
         decode_arch_label(backend.arch()),
         decoded_bytes,
@@ -1553,32 +2898,59 @@ fn optimize_elf_binary_with_backend<B: ElfOptimizationBackend>(
         end_addr,
     )?;
 
+    if !options.allow_pcrel {
+        reject_pc_relative_window(backend.arch(), &instructions)?;
+    }
+
     // Convert to IR
-    let ir_instructions = backend.convert_ir(&instructions)?;
+    let (ir_instructions, source_provenance) = backend.convert_ir_with_provenance(&instructions)?;
     // An all-NOP AArch64 window can legitimately convert to empty IR: NOPs are
     // skipped and the patcher pads the original byte window back out with NOPs.
-    println!(
+    progress!(
+        options.quiet,
         "Converted {} instructions to {}:",
         ir_instructions.len(),
         backend.ir_label()
     );
 
     for instr in &ir_instructions {
-        println!("  {}", instr);
+        progress!(options.quiet, "  {}", instr);
     }
 
     backend.validate_window_ir(&ir_instructions)?;
 
-    let optimization_context =
-        backend.optimization_context(&ir_instructions, patcher, &section, end_addr, &cs);
+    let (fixed_prefix, searched, fixed_suffix) =
+        split_fixed_regions(&ir_instructions, options.fixed_prefix, options.fixed_suffix)?;
+    if !fixed_prefix.is_empty() || !fixed_suffix.is_empty() {
+        progress!(
+            options.quiet,
+            "Holding {} leading and {} trailing instructions fixed; searching {}",
+            fixed_prefix.len(),
+            fixed_suffix.len(),
+            searched.len()
+        );
+    }
 
-    // Run optimization based on selected algorithm
-    let optimized_instructions = backend.run_search(
-        &ir_instructions,
-        &instructions,
-        options,
-        optimization_context,
-    )?;
+    let window_context = match live_out {
+        Some(spec) => backend.context_from_live_out(spec)?,
+        None => backend.optimization_context(&ir_instructions, patcher, &section, end_addr, &cs),
+    };
+    let mut optimization_context = window_context.clone();
+    if !fixed_suffix.is_empty() {
+        optimization_context =
+            backend.context_before_fixed_suffix(optimization_context, fixed_suffix);
+    }
+    // A rewrite must fit the bytes left once the fixed regions are placed.
+    optimization_context.max_bytes = backend
+        .encoded_length(fixed_prefix)
+        .zip(backend.encoded_length(fixed_suffix))
+        .map(|(prefix, suffix)| original_bytes.len().saturating_sub(prefix + suffix));
+
+    // Run optimization based on selected algorithm, then re-stitch the
+    // untouched prefix and suffix around the searched region.
+    let optimized_instructions = backend
+        .run_search(searched, &instructions, options, optimization_context)?
+        .map(|optimized| [fixed_prefix, &optimized, fixed_suffix].concat());
 
     // Use optimized instructions if found, otherwise use original
     let final_instructions = optimized_instructions
@@ -1586,12 +2958,29 @@ fn optimize_elf_binary_with_backend<B: ElfOptimizationBackend>(
         .unwrap_or(&ir_instructions);
 
     if optimized_instructions.is_some() {
-        println!("Optimized to {} instructions:", final_instructions.len());
-        for instr in final_instructions {
-            println!("  {}", instr);
+        if options.qemu_verify {
+            backend.qemu_verify(
+                &ir_instructions,
+                final_instructions,
+                &window_context,
+                options,
+            )?;
+        }
+        if options.explain {
+            for line in backend.explain(
+                &ir_instructions,
+                final_instructions,
+                &window_context,
+                options,
+            )? {
+                println!("{}", line);
+            }
         }
+    } else if !options.write_unchanged {
+        progress!(options.quiet, "No improvement found; binary unchanged.");
+        return Ok(None);
     } else {
-        println!("{}", backend.no_optimization_message());
+        progress!(options.quiet, "{}", backend.no_optimization_message());
     }
 
     // Reassemble the instructions
@@ -1604,15 +2993,33 @@ fn optimize_elf_binary_with_backend<B: ElfOptimizationBackend>(
         start_addr,
     )?;
     let OptimizedWindowBytes::Patch(assembled_bytes) = assembled_bytes else {
-        return Ok(());
+        return Ok(None);
     };
-    println!("Reassembled to {} bytes", assembled_bytes.len());
 
-    // Create patched ELF file at the caller-resolved output path.
-    patcher.create_patched_copy(output_path, &window, &assembled_bytes)?;
-    println!("Created optimized binary: {}", output_path.display());
-
-    Ok(())
+    let replaced = optimized_instructions.as_ref().and_then(|_| {
+        replaced_range(
+            &source_provenance,
+            fixed_prefix.len(),
+            fixed_suffix.len(),
+            end_addr,
+        )
+    });
+    let cost_metric = backend.bind_live_out(
+        options.cost_metric,
+        &ir_instructions,
+        &window_context,
+        options,
+    );
+    let mut plan = patcher.plan_patch(
+        &window,
+        ir_instructions.clone(),
+        final_instructions.to_vec(),
+        assembled_bytes,
+        backend.sequence_cost(&ir_instructions, &cost_metric),
+        backend.sequence_cost(final_instructions, &cost_metric),
+    )?;
+    plan.replaced = replaced;
+    Ok(Some(plan))
 }
 
 /// Build the per-window AArch64 live-out contract.
@@ -1676,10 +3083,54 @@ fn live_out_for_optimization_prefix(
     LiveOut::from_registers(live_registers).with_flags(flags_live)
 }
 
+/// The live-out contract the search, `--qemu-verify` and `--explain` share
+/// for the AArch64 window `original`: the terminator is held fixed and its
+/// reads are pinned live, or everything is live under `--strict`.
+fn window_live_out(
+    original: &[Instruction],
+    context: &OptimizationContext,
+    options: &OptimizationOptions,
+) -> LiveOut {
+    let (original_prefix, terminator) = split_terminator(original);
+    let downstream_live = match &context.downstream_live_regs {
+        DownstreamLiveRegs::Aarch64(set) => Some(set),
+        _ => None,
+    };
+    prefix_live_out(
+        options,
+        original_prefix,
+        terminator,
+        context.downstream_flags_live,
+        downstream_live,
+    )
+}
+
+/// [`window_live_out`] for a window already split into its searched
+/// `prefix` and fixed `terminator`.
+fn prefix_live_out(
+    options: &OptimizationOptions,
+    prefix: &[Instruction],
+    terminator: Option<&Instruction>,
+    downstream_flags_live: bool,
+    downstream_live: Option<&semantics::live_out::RegisterSet<Register>>,
+) -> LiveOut {
+    if options.strict {
+        return strict_live_out();
+    }
+    live_out_for_optimization_prefix(prefix, terminator, downstream_flags_live, downstream_live)
+}
+
+/// The contract `--strict` substitutes for the per-window live-out: every
+/// register and NZCV, so the rewrite must reproduce the whole machine state
+/// rather than what the window's consumers are known to read.
+fn strict_live_out() -> LiveOut {
+    semantics::EquivalenceConfig::drop_in_replacement().live_out
+}
+
 /// Shared base `SearchConfig` for the AArch64 stochastic/enumerative/hybrid/
 /// symbolic/LLM builders. Sets the fields every AArch64 algorithm configures
-/// identically — cost metric, overall and SMT solver timeouts, verbosity, and
-/// the register + immediate pools — so each builder only layers on its
+/// identically — cost metric, overall and SMT solver timeouts, the candidate
+/// budget, verbosity, and the register + immediate pools — so each builder only layers on its
 /// algorithm-specific pieces. Mirrors `build_x86_base_search_config`.
 ///
 /// Issue #243 was exactly the failure this base prevents: a per-algorithm
@@ -1696,7 +3147,9 @@ fn build_aarch64_base_search_config(
         .with_cost_metric(options.cost_metric)
         .with_solver_timeout(options.solver_timeout)
         .with_timeout_option(options.timeout)
+        .with_max_candidates_option(options.max_candidates)
         .with_verbose(options.verbose)
+        .with_stop_flag_option(options.stop_flag.clone())
         .with_registers(available_registers)
         .with_immediates(available_immediates)
 }
@@ -1708,8 +3161,9 @@ fn build_stochastic_search_config(
 ) -> SearchConfig {
     let stochastic_config = StochasticConfig::default()
         .with_beta(options.beta)
-        .with_iterations(options.iterations)
-        .with_seed_option(options.seed);
+        .with_iterations_option(options.iterations)
+        .with_seed_option(options.seed)
+        .with_initial_program_option(options.initial_program.clone());
 
     build_aarch64_base_search_config(options, available_registers, available_immediates)
         .with_stochastic(stochastic_config)
@@ -1741,7 +3195,8 @@ fn build_hybrid_search_config(
 ) -> SearchConfig {
     let stochastic_config = StochasticConfig::default()
         .with_beta(options.beta)
-        .with_iterations(options.iterations);
+        .with_iterations_option(options.iterations)
+        .with_initial_program_option(options.initial_program.clone());
 
     let symbolic_config = SymbolicConfig::default().with_search_mode(options.search_mode);
 
@@ -1752,6 +3207,7 @@ fn build_hybrid_search_config(
 
 /// Build the `SearchConfig` for AArch64 symbolic (SMT) search: the shared base
 /// plus the symbolic search mode.
+#[cfg(feature = "z3")]
 fn build_symbolic_search_config(
     options: &OptimizationOptions,
     available_registers: Vec<Register>,
@@ -1792,7 +3248,9 @@ fn build_x86_base_search_config(
         .with_cost_metric(options.cost_metric)
         .with_solver_timeout(options.solver_timeout)
         .with_timeout_option(options.timeout)
+        .with_max_candidates_option(options.max_candidates)
         .with_verbose(options.verbose)
+        .with_stop_flag_option(options.stop_flag.clone())
         .with_x86_registers(x86_registers_from_target(target))
         .with_immediates(isa::x86::default_x86_immediates())
 }
@@ -1803,12 +3261,13 @@ fn build_x86_stochastic_search_config(
 ) -> SearchConfig {
     let stochastic_config = StochasticConfig::default()
         .with_beta(options.beta)
-        .with_iterations(options.iterations)
+        .with_iterations_option(options.iterations)
         .with_seed_option(options.seed);
 
     build_x86_base_search_config(target, options).with_stochastic(stochastic_config)
 }
 
+#[cfg(feature = "z3")]
 fn build_x86_symbolic_search_config(
     target: &[isa::x86::X86Instruction],
     options: &OptimizationOptions,
@@ -1858,6 +3317,59 @@ fn aarch64_search_registers(target: &[Instruction]) -> Vec<Register> {
     registers
 }
 
+/// Immediate pool for AArch64 searches not seeded from the window.
+const DEFAULT_AARCH64_IMMEDIATES: [i64; 20] = [
+    0, 1, 2, 3, 4, 5, 7, 8, 10, 15, 16, 31, 32, 63, 64, 100, 255, 256, 1000, 4095,
+];
+
+/// Unused X registers added to a window-seeded register pool, so rewrites
+/// that need a temporary the window never touched stay reachable.
+const WINDOW_SCRATCH_REGISTERS: usize = 2;
+
+/// Register and immediate pools for searching `target`.
+///
+/// Seeded from the window, the registers are the ones `target` reads or
+/// writes plus the `WINDOW_SCRATCH_REGISTERS` lowest-numbered X registers it
+/// leaves unused, and every literal `target` carries joins
+/// `DEFAULT_AARCH64_IMMEDIATES` (which stay, since folding the window's
+/// constants needs values it does not spell out). SP and XZR stay out of
+/// the register pool either way. Otherwise the historical fixed pools
+/// apply: x0-x7 plus the target's vector registers (see
+/// `aarch64_search_registers`) and `DEFAULT_AARCH64_IMMEDIATES`.
+fn aarch64_search_pools(
+    target: &[Instruction],
+    seed_from_window: bool,
+) -> (Vec<Register>, Vec<i64>) {
+    if !seed_from_window {
+        return (
+            aarch64_search_registers(target),
+            DEFAULT_AARCH64_IMMEDIATES.to_vec(),
+        );
+    }
+
+    let mut registers: Vec<Register> = target
+        .iter()
+        .flat_map(|instruction| {
+            let mut used = instruction.source_registers();
+            used.extend(instruction.destinations());
+            used
+        })
+        .filter(|register| *register != Register::XZR && *register != Register::SP)
+        .collect();
+    let scratch = Register::scratch_registers(WINDOW_SCRATCH_REGISTERS, &registers);
+    registers.extend(scratch);
+    registers.sort_by_key(|register| register.sort_key());
+    registers.dedup();
+
+    let mut immediates = DEFAULT_AARCH64_IMMEDIATES.to_vec();
+    for imm in target.iter().filter_map(Instruction::immediate) {
+        if !immediates.contains(&imm) {
+            immediates.push(imm);
+        }
+    }
+    (registers, immediates)
+}
+
 /// Run optimization using the selected algorithm.
 ///
 /// Issue #69: if `target` ends in a terminator (branch / control-flow
@@ -1868,6 +3380,7 @@ fn run_optimization(
     options: &OptimizationOptions,
     downstream_flags_live: bool,
     downstream_live: Option<semantics::live_out::RegisterSet<Register>>,
+    max_bytes: Option<usize>,
 ) -> Result<Option<Vec<Instruction>>, Box<dyn std::error::Error>> {
     if target.is_empty() {
         return Ok(None);
@@ -1882,23 +3395,24 @@ fn run_optimization(
         return Ok(None);
     }
 
-    // Keep the historical scalar pool and add the target's vector registers
-    // so every search backend can generate NEON candidates for NEON windows.
-    let available_registers = aarch64_search_registers(prefix);
-    let available_immediates = vec![
-        0, 1, 2, 3, 4, 5, 7, 8, 10, 15, 16, 31, 32, 63, 64, 100, 255, 256, 1000, 4095,
-    ];
+    let (available_registers, available_immediates) =
+        aarch64_search_pools(prefix, !options.no_seed_from_window);
 
     // Create live-out contract over the prefix (assume all modified registers
     // are live-out), plus any registers the fixed terminator reads after the
     // optimized prefix runs. NZCV liveness comes from the fixed terminator or
     // the known downstream fall-through context.
-    let live_out = live_out_for_optimization_prefix(
+    let live_out = prefix_live_out(
+        options,
         prefix,
         terminator,
         downstream_flags_live,
         downstream_live.as_ref(),
     );
+    // The terminator is re-attached after the search, so its word comes out
+    // of the prefix's byte budget.
+    let max_bytes = max_bytes
+        .map(|bytes| bytes.saturating_sub(terminator.map_or(0, Instruction::encoded_length)));
 
     // Reattach the terminator (if any) to a successfully optimized prefix.
     let reattach = |opt: Option<Vec<Instruction>>| -> Option<Vec<Instruction>> {
@@ -1912,18 +3426,21 @@ fn run_optimization(
 
     match options.algorithm {
         Algorithm::Enumerative => {
-            println!("\nRunning enumerative search...");
+            progress!(options.quiet, "\nRunning enumerative search...");
             if let Some(n) = options.cores {
-                println!("  Cores: {}", n);
+                progress!(options.quiet, "  Cores: {}", n);
             }
 
             let config =
-                build_enumerative_search_config(options, available_registers, available_immediates);
+                build_enumerative_search_config(options, available_registers, available_immediates)
+                    .with_max_bytes_option(max_bytes);
 
             let mut search = EnumerativeSearch::<isa::AArch64>::new();
-            let result = search.search(prefix, &live_out, &config);
+            let result = search_without_overwritten_writes(prefix, &live_out, &config, |reduced| {
+                search.search(reduced, &live_out, &config).into()
+            });
 
-            print_search_statistics(&result.statistics);
+            report_search_statistics(&result.statistics, options);
 
             if result.found_optimization {
                 Ok(reattach(result.optimized_sequence))
@@ -1932,21 +3449,26 @@ fn run_optimization(
             }
         }
         Algorithm::Stochastic => {
-            println!("\nRunning stochastic (MCMC) search...");
-            println!("  Beta: {}", options.beta);
-            println!("  Iterations: {}", options.iterations);
+            progress!(options.quiet, "\nRunning stochastic (MCMC) search...");
+            progress!(options.quiet, "  Beta: {}", options.beta);
+            match options.iterations {
+                Some(iterations) => progress!(options.quiet, "  Iterations: {}", iterations),
+                None => progress!(options.quiet, "  Iterations: until timeout"),
+            }
             if let Some(seed) = options.seed {
-                println!("  Seed: {}", seed);
+                progress!(options.quiet, "  Seed: {}", seed);
             }
 
             let config =
-                build_stochastic_search_config(options, available_registers, available_immediates);
+                build_stochastic_search_config(options, available_registers, available_immediates)
+                    .with_max_bytes_option(max_bytes);
 
             let mut search: StochasticSearch<isa::AArch64> = StochasticSearch::new();
-            let result: search::result::SearchResult =
-                search.search(prefix, &live_out, &config).into();
+            let result = search_without_overwritten_writes(prefix, &live_out, &config, |reduced| {
+                search.search(reduced, &live_out, &config).into()
+            });
 
-            print_search_statistics(&result.statistics);
+            report_search_statistics(&result.statistics, options);
 
             if result.found_optimization {
                 Ok(reattach(result.optimized_sequence))
@@ -1954,19 +3476,28 @@ fn run_optimization(
                 Ok(None)
             }
         }
+        #[cfg(not(feature = "z3"))]
+        Algorithm::Symbolic => Err(SYMBOLIC_WITHOUT_Z3.into()),
+        #[cfg(feature = "z3")]
         Algorithm::Symbolic => {
-            println!("\nRunning symbolic (SMT) search...");
-            println!("  Search mode: {:?}", options.search_mode);
-            println!("  Solver timeout: {:?}", options.solver_timeout);
+            progress!(options.quiet, "\nRunning symbolic (SMT) search...");
+            progress!(options.quiet, "  Search mode: {:?}", options.search_mode);
+            progress!(
+                options.quiet,
+                "  Solver timeout: {:?}",
+                options.solver_timeout
+            );
 
             let config =
-                build_symbolic_search_config(options, available_registers, available_immediates);
+                build_symbolic_search_config(options, available_registers, available_immediates)
+                    .with_max_bytes_option(max_bytes);
 
             let mut search: SymbolicSearch<isa::AArch64> = SymbolicSearch::new();
-            let result: search::result::SearchResult =
-                search.search(prefix, &live_out, &config).into();
+            let result = search_without_overwritten_writes(prefix, &live_out, &config, |reduced| {
+                search.search(reduced, &live_out, &config).into()
+            });
 
-            print_search_statistics(&result.statistics);
+            report_search_statistics(&result.statistics, options);
 
             if result.found_optimization {
                 Ok(reattach(result.optimized_sequence))
@@ -1975,19 +3506,30 @@ fn run_optimization(
             }
         }
         Algorithm::Llm => {
-            println!("\nRunning LLM-assisted (Codex) search...");
-            println!("  Model: {}", options.llm_model);
-            println!("  Max codex calls: {}", options.llm_max_calls);
+            progress!(options.quiet, "\nRunning LLM-assisted (Codex) search...");
+            progress!(options.quiet, "  Model: {}", options.llm_model);
+            progress!(
+                options.quiet,
+                "  Max codex calls: {}",
+                options.llm_max_calls
+            );
 
             let config =
-                build_llm_search_config(options, available_registers, available_immediates);
+                build_llm_search_config(options, available_registers, available_immediates)
+                    .with_max_bytes_option(max_bytes);
 
             let mut search = search::llm::LlmSearch::new();
-            let result = search.search(prefix, &live_out, &config);
+            let result = search_without_overwritten_writes(prefix, &live_out, &config, |reduced| {
+                search.search(reduced, &live_out, &config)
+            });
 
-            print_search_statistics(&result.statistics);
-            print_llm_timings(search.timings(), result.statistics.elapsed_time);
-            print_unsupported_mnemonic_ledger(search.ledger());
+            report_search_statistics(&result.statistics, options);
+            print_llm_timings(
+                search.timings(),
+                result.statistics.elapsed_time,
+                options.quiet,
+            );
+            print_unsupported_mnemonic_ledger(search.ledger(), options.quiet);
 
             if result.found_optimization {
                 Ok(reattach(result.optimized_sequence))
@@ -1997,15 +3539,16 @@ fn run_optimization(
         }
         Algorithm::Hybrid => {
             let num_cores = options.cores.unwrap_or_else(num_cpus::get);
-            println!("\nRunning hybrid parallel search...");
-            println!("  Workers: {}", num_cores);
-            println!("  Symbolic worker: {}", !options.no_symbolic);
+            progress!(options.quiet, "\nRunning hybrid parallel search...");
+            progress!(options.quiet, "  Workers: {}", num_cores);
+            progress!(options.quiet, "  Symbolic worker: {}", !options.no_symbolic);
             if let Some(seed) = options.seed {
-                println!("  Base seed: {}", seed);
+                progress!(options.quiet, "  Base seed: {}", seed);
             }
 
             let config =
-                build_hybrid_search_config(options, available_registers, available_immediates);
+                build_hybrid_search_config(options, available_registers, available_immediates)
+                    .with_max_bytes_option(max_bytes);
 
             let parallel_config = ParallelConfig::default()
                 .with_workers(num_cores)
@@ -2013,12 +3556,20 @@ fn run_optimization(
                 .with_seed_option(options.seed)
                 .with_timeout_option(options.timeout);
 
-            let result = run_parallel_search(prefix, &live_out, &config, &parallel_config);
+            let mut total_statistics = None;
+            let result = search_without_overwritten_writes(prefix, &live_out, &config, |reduced| {
+                let result = run_parallel_search(reduced, &live_out, &config, &parallel_config);
+                total_statistics = Some(result.total_statistics);
+                result.best_result
+            });
+            let mut total_statistics =
+                total_statistics.unwrap_or_else(|| result.statistics.clone());
+            total_statistics.original_cost = result.statistics.original_cost;
 
-            print_search_statistics(&result.total_statistics);
+            report_search_statistics(&total_statistics, options);
 
-            if result.best_result.found_optimization {
-                Ok(reattach(result.best_result.optimized_sequence))
+            if result.found_optimization {
+                Ok(reattach(result.optimized_sequence))
             } else {
                 Ok(None)
             }
@@ -2104,9 +3655,9 @@ fn format_llm_timings(timings: &search::llm::LlmTimings, total: Duration) -> Vec
 }
 
 /// Print the per-phase timing breakdown from an LLM-assisted run.
-fn print_llm_timings(timings: &search::llm::LlmTimings, total: Duration) {
+fn print_llm_timings(timings: &search::llm::LlmTimings, total: Duration, quiet: bool) {
     for line in format_llm_timings(timings, total) {
-        println!("{}", line);
+        progress!(quiet, "{}", line);
     }
 }
 
@@ -2129,9 +3680,12 @@ fn format_unsupported_mnemonic_ledger(
 }
 
 /// Print the unsupported-mnemonic ledger from an LLM-assisted run.
-fn print_unsupported_mnemonic_ledger(ledger: &search::llm::ledger::UnsupportedMnemonicLedger) {
+fn print_unsupported_mnemonic_ledger(
+    ledger: &search::llm::ledger::UnsupportedMnemonicLedger,
+    quiet: bool,
+) {
     for line in format_unsupported_mnemonic_ledger(ledger) {
-        println!("{}", line);
+        progress!(quiet, "{}", line);
     }
 }
 
@@ -2160,6 +3714,9 @@ fn format_search_statistics(stats: &search::result::SearchStatistics) -> Vec<Str
         format!("  Original cost: {}", stats.original_cost),
         format!("  Best cost found: {}", stats.best_cost_found),
     ];
+    if stats.smt_unknowns > 0 {
+        lines.push(format!("  SMT unknown: {}", stats.smt_unknowns));
+    }
     if stats.iterations > 0 {
         lines.push(format!("  Iterations: {}", stats.iterations));
         lines.push(format!(
@@ -2170,10 +3727,42 @@ fn format_search_statistics(stats: &search::result::SearchStatistics) -> Vec<Str
     lines
 }
 
-/// Print search statistics
-fn print_search_statistics(stats: &search::result::SearchStatistics) {
+/// Print search statistics to stderr (they are diagnostics, not results).
+fn print_search_statistics(stats: &search::result::SearchStatistics, quiet: bool) {
     for line in format_search_statistics(stats) {
-        println!("{}", line);
+        progress!(quiet, "{}", line);
+    }
+}
+
+/// Wall-clock budget for `--bench-search` when no `--timeout` is given.
+const DEFAULT_BENCH_SEARCH_SECS: u64 = 10;
+
+/// Render the `--bench-search` report from
+/// `SearchStatistics::throughput_summary`: every rate is the matching counter
+/// divided by the elapsed wall-clock time.
+fn format_search_rates(stats: &search::result::SearchStatistics) -> Vec<String> {
+    let rates = stats.throughput_summary();
+    vec![
+        "\nSearch Throughput:".to_string(),
+        format!("  Elapsed time: {:.2?}", stats.elapsed_time),
+        format!("  Candidates/sec: {:.1}", rates.candidates_per_sec),
+        format!("  Fast passes/sec: {:.1}", rates.fast_passes_per_sec),
+        format!("  SMT queries/sec: {:.1}", rates.smt_per_sec),
+        format!("  Acceptance rate: {:.2}%", rates.acceptance_rate * 100.0),
+    ]
+}
+
+/// Print the search statistics, plus the throughput report on stdout when
+/// `--bench-search` is set (the rates are the result of a bench run).
+fn report_search_statistics(
+    stats: &search::result::SearchStatistics,
+    options: &OptimizationOptions,
+) {
+    print_search_statistics(stats, options.quiet);
+    if options.bench_search {
+        for line in format_search_rates(stats) {
+            println!("{}", line);
+        }
     }
 }
 
@@ -2245,8 +3834,59 @@ fn convert_capstone_op_for_optimization(
     }
 }
 
+/// Whether a disassembled instruction computes an address from, or loads a
+/// literal relative to, its own PC. Rewriting a window can move such an
+/// instruction, silently retargeting it. Branches are excluded: their targets
+/// are re-resolved when the window is assembled.
+fn is_pc_relative(arch: DetectedArch, mnemonic: &str, op_str: &str) -> bool {
+    match arch {
+        DetectedArch::Aarch64 => match mnemonic {
+            "adr" | "adrp" => true,
+            // The literal forms print a bare `#<address>` instead of a
+            // bracketed base register.
+            "ldr" | "ldrsw" | "prfm" => !op_str.contains('['),
+            _ => false,
+        },
+        DetectedArch::X86_64 | DetectedArch::X86_32 => op_str.contains("rip"),
+    }
+}
+
+/// Refuse a window holding a PC-relative instruction unless `--allow-pcrel`
+/// was given.
+fn reject_pc_relative_window(
+    arch: DetectedArch,
+    instructions: &capstone::Instructions,
+) -> Result<(), String> {
+    match instructions.iter().find(|instruction| {
+        is_pc_relative(
+            arch,
+            instruction.mnemonic().unwrap_or(""),
+            instruction.op_str().unwrap_or(""),
+        )
+    }) {
+        Some(instruction) => Err(format!(
+            "window contains PC-relative instruction '{} {}' at 0x{:x}; rewriting the window \
+             may move it and corrupt its target. Narrow the window to exclude it, or pass \
+             --allow-pcrel to optimize anyway.",
+            instruction.mnemonic().unwrap_or(""),
+            instruction.op_str().unwrap_or(""),
+            instruction.address()
+        )),
+        None => Ok(()),
+    }
+}
+
 fn convert_to_ir(instructions: &capstone::Instructions) -> Result<Vec<Instruction>, String> {
+    convert_to_ir_with_provenance(instructions).map(|(ir, _)| ir)
+}
+
+/// [`convert_to_ir`] plus the address each IR instruction was decoded from.
+/// Skipped NOPs leave no IR and so no entry.
+fn convert_to_ir_with_provenance(
+    instructions: &capstone::Instructions,
+) -> Result<(Vec<Instruction>, Vec<InstructionMeta>), String> {
     let mut ir_instructions = Vec::new();
+    let mut provenance = Vec::new();
 
     for instruction in instructions.iter() {
         let mnemonic = instruction.mnemonic().unwrap_or("");
@@ -2256,10 +3896,33 @@ fn convert_to_ir(instructions: &capstone::Instructions) -> Result<Vec<Instructio
             convert_capstone_op_for_optimization(mnemonic, op_str, instruction.address())?
         {
             ir_instructions.push(instr);
+            provenance.push(InstructionMeta::from_addr(instruction.address()));
         }
     }
 
-    Ok(ir_instructions)
+    Ok((ir_instructions, provenance))
+}
+
+/// The bytes a search over the window's middle rewrote, from the first
+/// searched instruction up to the first one of the fixed suffix (or
+/// `window_end`). Coarse on purpose: a rewrite need not map instruction by
+/// instruction. `None` when nothing was searched.
+fn replaced_range(
+    source: &[InstructionMeta],
+    prefix_len: usize,
+    suffix_len: usize,
+    window_end: u64,
+) -> Option<AddressWindow> {
+    let searched_end = source.len() - suffix_len;
+    let start = *source[prefix_len..searched_end]
+        .first()?
+        .source_addrs
+        .first()?;
+    let end = source
+        .get(searched_end)
+        .and_then(|meta| meta.source_addrs.first().copied())
+        .unwrap_or(window_end);
+    Some(AddressWindow { start, end })
 }
 
 /// Flags-only context derivation, used as the trait default and by callers
@@ -2280,6 +3943,7 @@ fn optimization_context_for_backend(
                 patcher, section, end_addr, cs,
             ),
             downstream_live_regs: DownstreamLiveRegs::Unknown,
+            max_bytes: None,
         };
     }
 
@@ -2289,6 +3953,7 @@ fn optimization_context_for_backend(
                 arch, patcher, section, end_addr, cs,
             ),
             downstream_live_regs: DownstreamLiveRegs::Unknown,
+            max_bytes: None,
         };
     }
 
@@ -2451,6 +4116,33 @@ fn x86_enumerative_immediates_from_target(target: &[isa::x86::X86Instruction]) -
     imms
 }
 
+/// The x86 `--strict` contract: every general register of the `width`-bit
+/// mode and EFLAGS.
+fn x86_strict_live_out(width: u32) -> semantics::live_out::X86LiveOut {
+    let count = if width == 32 { 8 } else { 16 };
+    semantics::live_out::RegisterSet::from_registers(
+        (0..count)
+            .filter_map(isa::x86::X86Register::from_index)
+            .collect(),
+    )
+    .with_flags(true)
+}
+
+/// The x86 counterpart of [`window_live_out`]: the per-window contract, or
+/// [`x86_strict_live_out`] under `--strict`.
+fn x86_window_live_out(
+    options: &OptimizationOptions,
+    target: &[isa::x86::X86Instruction],
+    width: u32,
+    downstream_flags_live: bool,
+    downstream_live: Option<&semantics::live_out::RegisterSet<isa::x86::X86Register>>,
+) -> semantics::live_out::X86LiveOut {
+    if options.strict {
+        return x86_strict_live_out(width);
+    }
+    x86_live_out_for_optimization(target, downstream_flags_live, downstream_live)
+}
+
 /// Build the per-window x86 live-out contract.
 ///
 /// EFLAGS liveness folds in the downstream flags scan (pre-existing). For
@@ -2512,7 +4204,13 @@ fn run_x86_enumerative(
     use search::SearchAlgorithm;
 
     let config = build_x86_enumerative_search_config(target, options);
-    let live_out = x86_live_out_for_optimization(target, downstream_flags_live, downstream_live);
+    let live_out = x86_window_live_out(
+        options,
+        target,
+        width,
+        downstream_flags_live,
+        downstream_live,
+    );
 
     let (optimized, statistics) = if width == 32 {
         let mut search: EnumerativeSearch<isa::X86_32> = EnumerativeSearch::new();
@@ -2535,7 +4233,7 @@ fn run_x86_enumerative(
             result.statistics,
         )
     };
-    print_search_statistics(&statistics);
+    report_search_statistics(&statistics, options);
     optimized
 }
 
@@ -2558,7 +4256,13 @@ fn run_x86_stochastic(
     if config.x86_available_registers.is_empty() {
         return None;
     }
-    let live_out = x86_live_out_for_optimization(target, downstream_flags_live, downstream_live);
+    let live_out = x86_window_live_out(
+        options,
+        target,
+        width,
+        downstream_flags_live,
+        downstream_live,
+    );
 
     // Extract (optimized, statistics) in each width branch separately:
     // the two `SearchResultFor<X86_64>` / `SearchResultFor<X86_32>`
@@ -2585,12 +4289,13 @@ fn run_x86_stochastic(
             result.statistics,
         )
     };
-    print_search_statistics(&statistics);
+    report_search_statistics(&statistics, options);
     optimized
 }
 
 /// Run x86 symbolic (SMT) search and return the optimized sequence if
 /// any. Same width / live-out handling as `run_x86_stochastic`.
+#[cfg(feature = "z3")]
 fn run_x86_symbolic(
     target: &[isa::x86::X86Instruction],
     width: u32,
@@ -2603,7 +4308,13 @@ fn run_x86_symbolic(
     use search::symbolic::SymbolicSearch;
 
     let config = build_x86_symbolic_search_config(target, options, same_count_code_size_allowed);
-    let live_out = x86_live_out_for_optimization(target, downstream_flags_live, downstream_live);
+    let live_out = x86_window_live_out(
+        options,
+        target,
+        width,
+        downstream_flags_live,
+        downstream_live,
+    );
 
     let (optimized, statistics) = if width == 32 {
         let mut search: SymbolicSearch<isa::X86_32> = SymbolicSearch::new();
@@ -2626,7 +4337,7 @@ fn run_x86_symbolic(
             result.statistics,
         )
     };
-    print_search_statistics(&statistics);
+    report_search_statistics(&statistics, options);
     optimized
 }
 
@@ -2720,12 +4431,13 @@ fn run_llm_opt(
     model: &str,
     timeout_secs: u64,
     verbose: bool,
+    quiet: bool,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let target = parser::parse_assembly_file(asm)?;
+    let target = parser::parse_assembly_path(asm)?;
     if verbose {
-        println!("Target ({} instructions):", target.len());
+        progress!(quiet, "Target ({} instructions):", target.len());
         for instr in &target {
-            println!("  {}", instr);
+            progress!(quiet, "  {}", instr);
         }
     }
 
@@ -2749,18 +4461,18 @@ fn run_llm_opt(
     let config = SearchConfig::default()
         .with_algorithm(Algorithm::Llm)
         .with_timeout(Duration::from_secs(timeout_secs))
-        .with_verbose(verbose)
+        .with_verbose(verbose && !quiet)
         .with_llm(llm);
 
     let mut searcher = search::llm::LlmSearch::new();
     let result = searcher.search(&target, &live_out, &config);
 
-    print_search_statistics(&result.statistics);
-    print_llm_timings(searcher.timings(), result.statistics.elapsed_time);
-    print_unsupported_mnemonic_ledger(searcher.ledger());
+    print_search_statistics(&result.statistics, quiet);
+    print_llm_timings(searcher.timings(), result.statistics.elapsed_time, quiet);
+    print_unsupported_mnemonic_ledger(searcher.ledger(), quiet);
 
-    println!();
-    println!("{}", result);
+    progress!(quiet, "");
+    print!("{}", result.diff_summary(&config.cost_metric));
 
     Ok(())
 }
@@ -2779,6 +4491,25 @@ struct EquivReport {
     exit_code: i32,
 }
 
+/// `state`'s live-out registers paired with their hex rendering, sorted by
+/// register index for deterministic output.
+fn live_out_register_values(
+    state: &semantics::ConcreteMachineState,
+    live_out: &LiveOut,
+) -> Vec<(Register, String)> {
+    let mut regs: Vec<_> = live_out.iter().copied().collect();
+    regs.sort_by_key(|reg| reg.sort_key());
+    regs.into_iter()
+        .map(|reg| {
+            let value = match reg {
+                Register::Vector(vector) => format!("0x{:032x}", state.get_vector(vector)),
+                _ => format!("0x{:016x}", state.get_register(reg).as_u64()),
+            };
+            (reg, value)
+        })
+        .collect()
+}
+
 /// Append `state`'s live-out registers to `lines`, one `    <reg> = 0x…` entry
 /// each, sorted by register index for deterministic output. Shared by the
 /// input / output-1 / output-2 sections of a counterexample so the three
@@ -2788,20 +4519,88 @@ fn push_live_out_registers(
     state: &semantics::ConcreteMachineState,
     live_out: &LiveOut,
 ) {
+    for (reg, value) in live_out_register_values(state, live_out) {
+        lines.push(format!("    {} = {}", reg, value));
+    }
+}
+
+/// `live_out` as `opt --explain` prints it: the registers in index order,
+/// then `nzcv` when the flags are live.
+fn describe_live_out(live_out: &LiveOut) -> String {
     let mut regs: Vec<_> = live_out.iter().copied().collect();
     regs.sort_by_key(|reg| reg.sort_key());
-    for reg in regs {
-        match reg {
-            Register::Vector(vector) => {
-                lines.push(format!("    {} = 0x{:032x}", reg, state.get_vector(vector)));
-            }
-            _ => lines.push(format!(
-                "    {} = 0x{:016x}",
-                reg,
-                state.get_register(reg).as_u64()
-            )),
+    let mut names: Vec<String> = regs.iter().map(|reg| reg.to_string()).collect();
+    if live_out.flags_live() {
+        names.push("nzcv".to_string());
+    }
+    if names.is_empty() {
+        "(nothing)".to_string()
+    } else {
+        names.join(", ")
+    }
+}
+
+/// The `opt --explain` justification for replacing `original` with
+/// `optimized`: the live-out contract, how many concrete inputs the pair
+/// agreed on, and the SMT verdict. A refutation lists the input and both
+/// outputs the same way `equiv` does.
+fn explanation_lines(
+    original: &[Instruction],
+    optimized: &[Instruction],
+    config: &semantics::EquivalenceConfig,
+) -> Vec<String> {
+    use semantics::EquivalenceResult;
+
+    let (result, metrics) =
+        semantics::check_equivalence_with_config_metrics(original, optimized, config);
+    let live_out = &config.live_out;
+    let live_regs: Vec<Register> = live_out.iter().copied().collect();
+    let edge_cases = validation::random::generate_edge_case_inputs(&live_regs).len();
+
+    let mut lines = vec![
+        "Explanation:".to_string(),
+        format!("  Live-out: {}", describe_live_out(live_out)),
+    ];
+    let counterexample = match &result {
+        EquivalenceResult::NotEquivalentFast(counterexample) => {
+            lines.push("  Concrete tests: failed".to_string());
+            Some(counterexample.input.clone())
+        }
+        _ if metrics.flag_guard_rejected => {
+            lines.push("  Concrete tests: not run (the flag writes differ)".to_string());
+            None
+        }
+        _ => {
+            lines.push(format!(
+                "  Concrete tests passed: {} random, {} edge-case",
+                config.random_test_count, edge_cases
+            ));
+            metrics.smt_counterexample.clone()
         }
+    };
+    lines.push(match &result {
+        EquivalenceResult::Equivalent if metrics.smt_called => {
+            "  Verdict: SMT-proven equivalent".to_string()
+        }
+        EquivalenceResult::Equivalent => {
+            "  Verdict: not SMT-checked (--solver-timeout 0); concrete tests only".to_string()
+        }
+        EquivalenceResult::NotEquivalent | EquivalenceResult::NotEquivalentFast(_) => {
+            "  Verdict: NOT equivalent".to_string()
+        }
+        EquivalenceResult::Unknown(reason) => format!("  Verdict: SMT inconclusive ({})", reason),
+    });
+    if let Some(input) = counterexample {
+        let original_output = semantics::apply_sequence_concrete(input.clone(), original);
+        let optimized_output = semantics::apply_sequence_concrete(input.clone(), optimized);
+        lines.push("  Counterexample input:".to_string());
+        push_live_out_registers(&mut lines, &input, live_out);
+        lines.push("  Output from original:".to_string());
+        push_live_out_registers(&mut lines, &original_output, live_out);
+        lines.push("  Output from optimized:".to_string());
+        push_live_out_registers(&mut lines, &optimized_output, live_out);
     }
+    lines
 }
 
 /// Turn an [`EquivalenceResult`] into the lines to print and the exit code to
@@ -2830,7 +4629,10 @@ fn build_equiv_report(
             ],
             exit_code: 1,
         },
-        EquivalenceResult::NotEquivalentFast(input_state) => {
+        EquivalenceResult::NotEquivalentFast(semantics::FastCounterexample {
+            input: input_state,
+            ..
+        }) => {
             // Issue #69: strip terminators before re-running on the
             // counterexample. The B1/B2 stubs panic if a branch reaches the
             // concrete interpreter; the equivalence layer already excluded the
@@ -2867,36 +4669,81 @@ fn build_equiv_report(
     }
 }
 
+/// Machine-readable counterpart of [`build_equiv_report`] for
+/// `equiv --format json`: a single object carrying the verdict, the exit code,
+/// and — when the fast path found one — the counterexample input restricted to
+/// the live-out registers.
+fn build_equiv_json(
+    result: &semantics::EquivalenceResult,
+    exit_code: i32,
+    live_out: &LiveOut,
+) -> serde_json::Value {
+    use semantics::EquivalenceResult;
+
+    let verdict = match result {
+        EquivalenceResult::Equivalent => "equivalent",
+        EquivalenceResult::NotEquivalent | EquivalenceResult::NotEquivalentFast(_) => {
+            "not-equivalent"
+        }
+        EquivalenceResult::Unknown(_) => "unknown",
+    };
+    let mut json = serde_json::json!({
+        "verdict": verdict,
+        "exit_code": exit_code,
+    });
+    match result {
+        EquivalenceResult::NotEquivalentFast(semantics::FastCounterexample {
+            input: input_state,
+            ..
+        }) => {
+            let input: serde_json::Map<String, serde_json::Value> =
+                live_out_register_values(input_state, live_out)
+                    .into_iter()
+                    .map(|(reg, value)| (reg.to_string(), value.into()))
+                    .collect();
+            json["counterexample"] = serde_json::json!({ "input": input });
+        }
+        EquivalenceResult::Unknown(reason) => json["reason"] = reason.as_str().into(),
+        EquivalenceResult::Equivalent | EquivalenceResult::NotEquivalent => {}
+    }
+    json
+}
+
 fn run_equiv(
     file1: &Path,
     file2: &Path,
     live_out_str: &str,
     timeout: u64,
     fast_only: bool,
+    format: OutputFormat,
     verbose: bool,
 ) -> Result<i32, Box<dyn std::error::Error>> {
     use semantics::{EquivalenceConfig, check_equivalence_with_config};
 
+    if file1 == Path::new("-") && file2 == Path::new("-") {
+        return Err("only one of the two assembly files can be `-` (stdin)".into());
+    }
+
     // Parse assembly files
     if verbose {
-        println!("Parsing {}...", file1.display());
+        eprintln!("Parsing {}...", file1.display());
     }
-    let seq1 = parser::parse_assembly_file(file1)?;
+    let seq1 = parser::parse_assembly_path(file1)?;
     if verbose {
-        println!("  Parsed {} instructions:", seq1.len());
+        eprintln!("  Parsed {} instructions:", seq1.len());
         for instr in &seq1 {
-            println!("    {}", instr);
+            eprintln!("    {}", instr);
         }
     }
 
     if verbose {
-        println!("Parsing {}...", file2.display());
+        eprintln!("Parsing {}...", file2.display());
     }
-    let seq2 = parser::parse_assembly_file(file2)?;
+    let seq2 = parser::parse_assembly_path(file2)?;
     if verbose {
-        println!("  Parsed {} instructions:", seq2.len());
+        eprintln!("  Parsed {} instructions:", seq2.len());
         for instr in &seq2 {
-            println!("    {}", instr);
+            eprintln!("    {}", instr);
         }
     }
 
@@ -2907,9 +4754,9 @@ fn run_equiv(
         let mut regs: Vec<_> = live_out.iter().collect();
         regs.sort_by_key(|r| r.sort_key());
         let names: Vec<String> = regs.iter().map(|r| format!("{}", r)).collect();
-        println!("Live-out registers: {}", names.join(", "));
+        eprintln!("Live-out registers: {}", names.join(", "));
         if live_out.flags_live() {
-            println!("Live-out flags: nzcv");
+            eprintln!("Live-out flags: nzcv");
         }
     }
 
@@ -2919,12 +4766,12 @@ fn run_equiv(
         .set_fast_only(fast_only);
 
     if verbose {
-        println!("\nChecking equivalence...");
+        eprintln!("\nChecking equivalence...");
         if fast_only {
-            println!("  Mode: fast path only (random testing)");
+            eprintln!("  Mode: fast path only (random testing)");
         } else {
-            println!("  Mode: random testing + SMT verification");
-            println!("  Timeout: {}s", timeout);
+            eprintln!("  Mode: random testing + SMT verification");
+            eprintln!("  Timeout: {}s", timeout);
         }
     }
 
@@ -2932,18 +4779,323 @@ fn run_equiv(
     // and which exit code to surface. `main` performs the actual `process::exit`.
     let result = check_equivalence_with_config(&seq1, &seq2, &config);
     let report = build_equiv_report(&result, &seq1, &seq2, &config.live_out);
-    for line in &report.lines {
-        println!("{}", line);
+    match format {
+        OutputFormat::Text => {
+            for line in &report.lines {
+                println!("{}", line);
+            }
+        }
+        OutputFormat::Json => {
+            let json = build_equiv_json(&result, report.exit_code, &config.live_out);
+            println!("{}", json);
+        }
     }
     Ok(report.exit_code)
 }
 
+// --- Algorithm Comparison ---
+
+/// Algorithms `compare-algorithms` runs, in table order.
+const COMPARED_ALGORITHMS: [Algorithm; 3] = [
+    Algorithm::Enumerative,
+    Algorithm::Stochastic,
+    Algorithm::Symbolic,
+];
+
+/// How one algorithm did on the shared `compare-algorithms` target.
+#[derive(Debug)]
+struct ComparisonRow {
+    algorithm: Algorithm,
+    found: bool,
+    /// Cost of the optimized sequence, or of the target when none was found.
+    best_cost: u64,
+    candidates_evaluated: u64,
+    smt_queries: u64,
+    elapsed: Duration,
+}
+
+/// Run each of [`COMPARED_ALGORITHMS`] through `optimize_sequence` on
+/// `target` with `base` as the shared config. A trailing branch is held
+/// fixed, as `opt` does for windows. Symbolic search is left out of a build
+/// without the `z3` feature.
+fn compare_algorithms(
+    target: &[Instruction],
+    live_out: &LiveOut,
+    base: &SearchConfig,
+) -> Result<Vec<ComparisonRow>, String> {
+    validate_basic_block(target)?;
+    let (prefix, _terminator) = split_terminator(target);
+    // Price both sides with the live-out bound, as the searches do.
+    let metric = base.cost_metric.with_live_out(live_out);
+    let original_cost = semantics::cost::sequence_cost(prefix, &metric);
+    Ok(COMPARED_ALGORITHMS
+        .iter()
+        .filter(|&&algorithm| cfg!(feature = "z3") || algorithm != Algorithm::Symbolic)
+        .map(|&algorithm| {
+            let config = base.clone().with_algorithm(algorithm);
+            let result = s11::optimize_sequence(prefix, live_out, &config);
+            let best_cost = match &result.optimized_sequence {
+                Some(optimized) if result.found_optimization => {
+                    semantics::cost::sequence_cost(optimized, &metric)
+                }
+                _ => original_cost,
+            };
+            ComparisonRow {
+                algorithm,
+                found: result.found_optimization,
+                best_cost,
+                candidates_evaluated: result.statistics.candidates_evaluated,
+                smt_queries: result.statistics.smt_queries,
+                elapsed: result.statistics.elapsed_time,
+            }
+        })
+        .collect())
+}
+
+/// Render `rows` as the aligned table `compare-algorithms` prints.
+fn comparison_table(rows: &[ComparisonRow]) -> Vec<String> {
+    let mut lines = vec![format!(
+        "{:<12} {:<6} {:>9} {:>11} {:>11} {:>9}",
+        "algorithm", "found", "best cost", "candidates", "SMT queries", "elapsed"
+    )];
+    lines.extend(rows.iter().map(|row| {
+        format!(
+            "{:<12} {:<6} {:>9} {:>11} {:>11} {:>8.2}s",
+            row.algorithm.to_string(),
+            if row.found { "yes" } else { "no" },
+            row.best_cost,
+            row.candidates_evaluated,
+            row.smt_queries,
+            row.elapsed.as_secs_f64()
+        )
+    }));
+    lines
+}
+
+fn run_compare_algorithms(
+    asm: &Path,
+    live_out_str: &str,
+    timeout: Duration,
+    seed: Option<u64>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let target = parser::parse_assembly_path(asm)?;
+    let live_out = validation::live_out::parse_live_out_contract(live_out_str)
+        .map_err(|e| format!("invalid live-out: {}", e))?;
+    let (prefix, _) = split_terminator(&target);
+    let base = SearchConfig::default()
+        .with_timeout(timeout)
+        .with_registers(aarch64_search_registers(prefix))
+        .with_stochastic(StochasticConfig::default().with_seed_option(seed));
+    for line in comparison_table(&compare_algorithms(&target, &live_out, &base)?) {
+        println!("{}", line);
+    }
+    Ok(())
+}
+
+// --- Interactive REPL ---
+
+const REPL_HELP: &[&str] = &[
+    "Enter AArch64 instructions, one per line; a blank line optimizes them.",
+    "  :live-out <regs>   set the live-out contract (e.g. x0,x1;nzcv)",
+    "  :algo <name>       enumerative | stochastic | symbolic | hybrid | llm",
+    "  :timeout <secs>    set the per-block search timeout",
+    "  :show              print the buffered instructions and settings",
+    "  :clear             discard the buffered instructions",
+    "  :quit              leave the REPL",
+];
+
+/// Settings and pending instructions of an `s11 repl` session.
+struct ReplSession {
+    algorithm: Algorithm,
+    live_out_spec: String,
+    live_out: LiveOut,
+    timeout: Duration,
+    buffer: Vec<Instruction>,
+}
+
+impl ReplSession {
+    fn new(algorithm: Algorithm, live_out_spec: &str, timeout: Duration) -> Result<Self, String> {
+        let live_out = validation::live_out::parse_live_out_contract(live_out_spec)
+            .map_err(|e| format!("invalid live-out: {}", e))?;
+        Ok(Self {
+            algorithm,
+            live_out_spec: live_out_spec.to_string(),
+            live_out,
+            timeout,
+            buffer: Vec::new(),
+        })
+    }
+
+    /// Handle one input line and return the lines to print, or `None` when
+    /// the session should end.
+    fn handle_line(&mut self, line: &str) -> Option<Vec<String>> {
+        let trimmed = line.trim();
+        if let Some(command) = trimmed.strip_prefix(':') {
+            return self.handle_command(command);
+        }
+        if trimmed.is_empty() {
+            return Some(self.optimize_buffer());
+        }
+        Some(match parser::parse_line(trimmed) {
+            Ok(parser::LineResult::Instruction(instr)) => {
+                self.buffer.push(instr);
+                Vec::new()
+            }
+            Ok(parser::LineResult::Skip) => Vec::new(),
+            Ok(parser::LineResult::Unsupported(mnemonic)) => {
+                vec![format!("error: unsupported instruction: {}", mnemonic)]
+            }
+            Err(e) => vec![format!("error: {}", e)],
+        })
+    }
+
+    fn handle_command(&mut self, command: &str) -> Option<Vec<String>> {
+        let (name, arg) = command
+            .split_once(char::is_whitespace)
+            .map_or((command, ""), |(name, arg)| (name, arg.trim()));
+        let reply = match name {
+            "live-out" => match validation::live_out::parse_live_out_contract(arg) {
+                Ok(live_out) => {
+                    self.live_out = live_out;
+                    self.live_out_spec = arg.to_string();
+                    format!("live-out: {}", arg)
+                }
+                Err(e) => format!("error: invalid live-out: {}", e),
+            },
+            "algo" => match arg.parse::<Algorithm>() {
+                Ok(algorithm) => {
+                    self.algorithm = algorithm;
+                    format!("algorithm: {}", algorithm)
+                }
+                Err(e) => format!("error: {}", e),
+            },
+            "timeout" => match arg.parse::<u64>() {
+                Ok(secs) => {
+                    self.timeout = Duration::from_secs(secs);
+                    format!("timeout: {}s", secs)
+                }
+                Err(_) => format!("error: invalid timeout '{}'", arg),
+            },
+            "clear" => {
+                self.buffer.clear();
+                "buffer cleared".to_string()
+            }
+            "show" => {
+                let mut lines = vec![format!(
+                    "algorithm: {}, live-out: {}, timeout: {}s",
+                    self.algorithm,
+                    self.live_out_spec,
+                    self.timeout.as_secs()
+                )];
+                lines.extend(self.buffer.iter().map(|instr| format!("  {}", instr)));
+                return Some(lines);
+            }
+            "help" => return Some(REPL_HELP.iter().map(|line| line.to_string()).collect()),
+            "quit" | "q" | "exit" => return None,
+            _ => format!("error: unknown command ':{}' (try :help)", name),
+        };
+        Some(vec![reply])
+    }
+
+    /// Search the buffered block and report the result, emptying the buffer.
+    /// A trailing branch is held fixed, as `opt` does for windows.
+    fn optimize_buffer(&mut self) -> Vec<String> {
+        if self.buffer.is_empty() {
+            return Vec::new();
+        }
+        let target = std::mem::take(&mut self.buffer);
+        if let Err(e) = validate_basic_block(&target) {
+            return vec![format!("error: {}", e)];
+        }
+        let (prefix, terminator) = split_terminator(&target);
+        let config = SearchConfig::default()
+            .with_algorithm(self.algorithm)
+            .with_timeout(self.timeout)
+            .with_registers(aarch64_search_registers(prefix));
+        let result = s11::optimize_sequence(prefix, &self.live_out, &config);
+
+        let metric = config.cost_metric.with_live_out(&self.live_out);
+        let original_cost = semantics::cost::sequence_cost(prefix, &metric);
+        let mut lines = Vec::new();
+        match result.optimized_sequence {
+            Some(mut optimized) if result.found_optimization => {
+                let cost = semantics::cost::sequence_cost(&optimized, &metric);
+                optimized.extend(terminator.copied());
+                lines.push(format!("Optimized (cost {} -> {}):", original_cost, cost));
+                if optimized.is_empty() {
+                    lines.push("  (empty sequence)".to_string());
+                }
+                lines.extend(optimized.iter().map(|instr| format!("  {}", instr)));
+            }
+            _ => lines.push(format!("No improvement found (cost {})", original_cost)),
+        }
+        lines.push(format!(
+            "  [{} search, {:.2?}]",
+            self.algorithm, result.statistics.elapsed_time
+        ));
+        lines
+    }
+}
+
+/// Drive a REPL session over `input`, writing replies to `output`. Anything
+/// still buffered at end of input is optimized before returning.
+fn run_repl(
+    session: &mut ReplSession,
+    input: impl std::io::BufRead,
+    mut output: impl std::io::Write,
+) -> std::io::Result<()> {
+    writeln!(output, "s11 repl (:help for commands)")?;
+    for line in input.lines() {
+        let Some(replies) = session.handle_line(&line?) else {
+            return Ok(());
+        };
+        for reply in replies {
+            writeln!(output, "{}", reply)?;
+        }
+    }
+    for reply in session.optimize_buffer() {
+        writeln!(output, "{}", reply)?;
+    }
+    Ok(())
+}
+
 // --- Main Function ---
 fn main() {
     let args = Args::parse();
+    let quiet = args.quiet;
 
     match args.command {
-        Commands::Disasm { binary, arch } => {
+        Commands::Disasm {
+            binary,
+            list_sections: true,
+            ..
+        } => match list_sections(&binary) {
+            Ok(lines) => {
+                for line in lines {
+                    println!("{}", line);
+                }
+            }
+            Err(e) => {
+                eprintln!("Error reading sections: {}", e);
+                std::process::exit(1);
+            }
+        },
+        Commands::Disasm {
+            binary,
+            report_unsupported: true,
+            ..
+        } => match ElfPatcher::new(&binary).and_then(|patcher| support_census(&patcher)) {
+            Ok(census) => {
+                for line in format_support_census(&census, UNSUPPORTED_REPORT_TOP) {
+                    println!("{}", line);
+                }
+            }
+            Err(e) => {
+                eprintln!("Error scanning binary: {}", e);
+                std::process::exit(1);
+            }
+        },
+        Commands::Disasm { binary, arch, .. } => {
             // Disassemble mode. `analyze_elf_binary` auto-detects the
             // architecture from e_machine and picks the right Capstone
             // backend. The optional `--arch` still early-rejects RISC-V, but
@@ -2973,16 +5125,36 @@ fn main() {
             binary,
             start_addr,
             end_addr,
+            window,
             auto,
+            windows,
+            optimize_all,
+            compare_binary,
+            function,
+            min_size,
             output,
+            overwrite,
+            fixed_prefix,
+            fixed_suffix,
+            qemu_verify,
+            explain,
+            format,
+            write_unchanged,
+            strict,
+            allow_pcrel,
+            bench_search,
             arch,
             algorithm,
             timeout,
+            max_candidates,
             cost_metric,
+            latency_table,
+            no_seed_from_window,
             verbose,
             beta,
             iterations,
             seed,
+            initial_program,
             search_mode,
             solver_timeout,
             cores,
@@ -3036,25 +5208,80 @@ fn main() {
             // Every pre-dispatch policy rule (arch cross-check, RISC-V refusal,
             // x86-only-algorithm refusal) lives behind resolve_opt_target so it
             // is exercised by table tests rather than only through this CLI arm.
-            if let Err(e) = resolve_opt_target(arch, detected_arch, algorithm) {
-                eprintln!("{e}");
+            let target = match resolve_opt_target(arch, detected_arch, algorithm) {
+                Ok(target) => target,
+                Err(e) => {
+                    eprintln!("{e}");
+                    std::process::exit(1);
+                }
+            };
+            if qemu_verify && let Err(e) = qemu_verify_supported(target) {
+                eprintln!("Error: {e}");
+                std::process::exit(1);
+            }
+            if explain && target != SupportedArch::Aarch64 {
+                eprintln!("Error: --explain is only supported for AArch64 binaries");
+                std::process::exit(1);
+            }
+            let initial_program = match initial_program {
+                Some(_) if target != SupportedArch::Aarch64 => {
+                    eprintln!("Error: --initial-program is only supported for AArch64 binaries");
+                    std::process::exit(1);
+                }
+                Some(path) => match parser::parse_assembly_path(&path) {
+                    Ok(program) => Some(program),
+                    Err(e) => {
+                        eprintln!("Error reading initial program: {}", e);
+                        std::process::exit(1);
+                    }
+                },
+                None => None,
+            };
+            let cost_metric = resolve_cost_metric(cost_metric, latency_table.as_deref())
+                .unwrap_or_else(|e| {
+                    eprintln!("Error: {e}");
+                    std::process::exit(1);
+                });
+            if let Err(e) = cost_metric_supported(target, &cost_metric) {
+                eprintln!("Error: {e}");
                 std::process::exit(1);
             }
 
+            // A bench run is bounded by wall-clock time alone, so the MCMC
+            // iteration cap is lifted and a missing --timeout gets a default.
+            let (timeout, iterations) = if bench_search {
+                (Some(timeout.unwrap_or(DEFAULT_BENCH_SEARCH_SECS)), None)
+            } else {
+                (timeout, Some(iterations))
+            };
             let options = OptimizationOptions {
                 algorithm: algorithm.into(),
                 timeout: timeout.map(Duration::from_secs),
-                cost_metric: cost_metric.into(),
-                verbose,
+                max_candidates,
+                cost_metric,
+                verbose: verbose && !quiet,
+                quiet,
+                fixed_prefix,
+                fixed_suffix,
+                qemu_verify,
+                explain,
+                format,
+                write_unchanged,
+                strict,
+                allow_pcrel,
+                bench_search,
+                no_seed_from_window,
                 beta,
                 iterations,
                 seed,
+                initial_program,
                 search_mode: search_mode.into(),
                 solver_timeout: Duration::from_secs(solver_timeout),
                 cores,
                 no_symbolic,
                 llm_max_calls,
                 llm_model,
+                stop_flag: install_interrupt_handler(),
             };
 
             let result = if auto {
@@ -3062,31 +5289,66 @@ fn main() {
                 // --end-addr are absent (conflicts_with_all); the driver loop
                 // itself is a later #615 slice, so this dispatches to a guard.
                 run_auto_optimization(&patcher, &binary, output.as_deref(), &options)
-            } else {
-                // Single-window path. clap's required_unless_present guarantees
-                // both addresses are present here; guard defensively rather than
-                // unwrap so a future clap change fails loudly, not with a panic.
-                let (Some(start_addr), Some(end_addr)) = (start_addr, end_addr) else {
-                    eprintln!(
-                        "Error: --start-addr and --end-addr are required unless --auto is set"
-                    );
-                    std::process::exit(1);
-                };
-                let start_addr = match parse_hex_address(&start_addr) {
-                    Ok(addr) => addr,
+            } else if let Some(manifest) = windows {
+                let output_path = match resolve_output_path(&binary, output.as_deref(), overwrite) {
+                    Ok(path) => path,
                     Err(e) => {
-                        eprintln!("Error parsing start address: {}", e);
+                        eprintln!("Error: {e}");
                         std::process::exit(1);
                     }
                 };
-                let end_addr = match parse_hex_address(&end_addr) {
-                    Ok(addr) => addr,
+                load_window_manifest(&manifest).and_then(|windows| {
+                    optimize_window_manifest(&patcher, &binary, &windows, &output_path, &options)
+                })
+            } else if optimize_all {
+                let output_path = match resolve_output_path(&binary, output.as_deref(), overwrite) {
+                    Ok(path) => path,
                     Err(e) => {
-                        eprintln!("Error parsing end address: {}", e);
+                        eprintln!("Error: {e}");
                         std::process::exit(1);
                     }
                 };
-                let output_path = match resolve_output_path(&binary, output.as_deref()) {
+                optimize_all_functions(&patcher, &binary, min_size, &output_path, &options)
+            } else if let (Some(other), Some(function)) = (compare_binary, function) {
+                compare_function_across_binaries(&patcher, &binary, &other, &function, &options)
+            } else {
+                let (start_addr, end_addr) = match window {
+                    Some(window) => {
+                        if let Err(e) = window.validate(patcher.arch()) {
+                            eprintln!("Error in --window: {}", e);
+                            std::process::exit(1);
+                        }
+                        (window.start, window.end)
+                    }
+                    None => {
+                        // Single-window path. clap's required_unless_present
+                        // guarantees both addresses are present here; guard
+                        // defensively rather than unwrap so a future clap
+                        // change fails loudly, not with a panic.
+                        let (Some(start_addr), Some(end_addr)) = (start_addr, end_addr) else {
+                            eprintln!(
+                                "Error: --start-addr and --end-addr are required unless --window or --auto is set"
+                            );
+                            std::process::exit(1);
+                        };
+                        let start_addr = match parse_hex_address(&start_addr) {
+                            Ok(addr) => addr,
+                            Err(e) => {
+                                eprintln!("Error parsing start address: {}", e);
+                                std::process::exit(1);
+                            }
+                        };
+                        let end_addr = match parse_hex_address(&end_addr) {
+                            Ok(addr) => addr,
+                            Err(e) => {
+                                eprintln!("Error parsing end address: {}", e);
+                                std::process::exit(1);
+                            }
+                        };
+                        (start_addr, end_addr)
+                    }
+                };
+                let output_path = match resolve_output_path(&binary, output.as_deref(), overwrite) {
                     Ok(path) => path,
                     Err(e) => {
                         eprintln!("Error: {e}");
@@ -3104,7 +5366,7 @@ fn main() {
             };
 
             match result {
-                Ok(()) => println!("\nOptimization completed successfully."),
+                Ok(()) => progress!(quiet, "\nOptimization completed successfully."),
                 Err(e) => {
                     eprintln!("Error during optimization: {}", e);
                     std::process::exit(1);
@@ -3118,21 +5380,61 @@ fn main() {
             model,
             timeout,
             verbose,
-        } => match run_llm_opt(&asm, &live_out, max_calls, &model, timeout, verbose) {
+        } => match run_llm_opt(&asm, &live_out, max_calls, &model, timeout, verbose, quiet) {
             Ok(()) => {}
             Err(e) => {
                 eprintln!("llm-opt: {}", e);
                 std::process::exit(1);
             }
         },
+        Commands::Repl {
+            algorithm,
+            live_out,
+            timeout,
+        } => {
+            let mut session =
+                match ReplSession::new(algorithm.into(), &live_out, Duration::from_secs(timeout)) {
+                    Ok(session) => session,
+                    Err(e) => {
+                        eprintln!("repl: {}", e);
+                        std::process::exit(1);
+                    }
+                };
+            if let Err(e) = run_repl(&mut session, std::io::stdin().lock(), std::io::stdout()) {
+                eprintln!("repl: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Commands::CompareAlgorithms {
+            asm,
+            live_out,
+            timeout,
+            seed,
+        } => {
+            if let Err(e) =
+                run_compare_algorithms(&asm, &live_out, Duration::from_secs(timeout), seed)
+            {
+                eprintln!("compare-algorithms: {}", e);
+                std::process::exit(1);
+            }
+        }
         Commands::Equiv {
             file1,
             file2,
             live_out,
             timeout,
             fast_only,
+            format,
             verbose,
-        } => match run_equiv(&file1, &file2, &live_out, timeout, fast_only, verbose) {
+        } => match run_equiv(
+            &file1,
+            &file2,
+            &live_out,
+            timeout,
+            fast_only,
+            format,
+            verbose && !quiet,
+        ) {
             Ok(code) => {
                 if code != 0 {
                     std::process::exit(code);
@@ -3161,17 +5463,31 @@ mod cli_helper_tests {
         OptimizationOptions {
             algorithm,
             timeout: Some(Duration::from_millis(1)),
+            max_candidates: None,
             cost_metric: CostMetric::InstructionCount,
             verbose: false,
+            quiet: true,
+            fixed_prefix: 0,
+            fixed_suffix: 0,
+            qemu_verify: false,
+            explain: false,
+            format: OutputFormat::Text,
+            write_unchanged: false,
+            strict: false,
+            allow_pcrel: false,
+            bench_search: false,
+            no_seed_from_window: false,
             beta: 1.0,
-            iterations: 0,
+            iterations: Some(0),
             seed: Some(1),
+            initial_program: None,
             search_mode: SearchMode::Linear,
             solver_timeout: Duration::from_millis(1),
             cores: Some(1),
             no_symbolic: true,
             llm_max_calls: 0,
             llm_model: "test-model".to_string(),
+            stop_flag: None,
         }
     }
 
@@ -3316,11 +5632,270 @@ mod cli_helper_tests {
             ],
         );
 
-        buf
+        buf
+    }
+
+    fn build_minimal_elf64(text_bytes: &[u8], text_vaddr: u64, machine: u16) -> Vec<u8> {
+        build_elf64_with_executable_sections(&[(".text", text_bytes, text_vaddr)], machine)
+    }
+
+    #[test]
+    fn assembly_piped_through_stdin_parses_and_optimizes() {
+        // `cc -S -o - | s11 ... -` in miniature: the stdin reader sees the
+        // compiler's directives and labels around the two instructions.
+        let piped = ".text\n.globl f\nf:\n    mov x0, x1\n    add x0, x0, #1\n";
+        let target = parser::parse_assembly_reader(piped.as_bytes(), parser::STDIN_SOURCE)
+            .expect("stdin assembly parses");
+        let config = SearchConfig::default()
+            .with_algorithm(Algorithm::Symbolic)
+            .with_registers(vec![Register::X0, Register::X1])
+            .with_immediates(vec![0, 1]);
+        let live_out = LiveOut::from_registers(vec![Register::X0]);
+
+        let result = s11::optimize_sequence(&target, &live_out, &config);
+
+        assert!(result.found_optimization);
+        assert_eq!(result.optimized_sequence.map(|seq| seq.len()), Some(1));
+    }
+
+    #[test]
+    fn equiv_rejects_stdin_for_both_files() {
+        let dash = Path::new("-");
+        let err = run_equiv(dash, dash, "x0", 1, true, OutputFormat::Text, false).unwrap_err();
+        assert!(err.to_string().contains("only one of the two"), "{err}");
+    }
+
+    #[test]
+    fn compare_algorithms_reports_every_algorithm_on_mov_add_fusion() {
+        let target = parser::parse_assembly_string("mov x0, x1\nadd x0, x0, #1\n", "t".into())
+            .expect("parse failed");
+        let base = SearchConfig::default()
+            .with_timeout(Duration::from_secs(10))
+            .with_registers(vec![Register::X0, Register::X1])
+            .with_immediates(vec![0, 1])
+            .with_stochastic(
+                StochasticConfig::default()
+                    .with_iterations(20_000)
+                    .with_seed(1),
+            );
+        let live_out = LiveOut::from_registers(vec![Register::X0]);
+
+        let rows = compare_algorithms(&target, &live_out, &base).expect("valid block");
+
+        let algorithms: Vec<_> = rows.iter().map(|row| row.algorithm).collect();
+        assert_eq!(algorithms, COMPARED_ALGORITHMS);
+        let found = |algorithm| rows.iter().find(|row| row.algorithm == algorithm).unwrap();
+        assert_eq!(
+            found(Algorithm::Stochastic).found,
+            found(Algorithm::Symbolic).found
+        );
+        assert!(found(Algorithm::Symbolic).found);
+        assert_eq!(found(Algorithm::Symbolic).best_cost, 1);
+
+        let table = comparison_table(&rows);
+        assert_eq!(table.len(), 4);
+        assert!(table[0].starts_with("algorithm"));
+        assert!(table[2].starts_with("stochastic   yes"), "{}", table[2]);
+    }
+
+    #[test]
+    fn compare_algorithms_prices_clobbers_against_the_live_out() {
+        let target = parser::parse_assembly_string("mov x0, x1\nadd x0, x0, #1\n", "t".into())
+            .expect("parse failed");
+        let fused =
+            parser::parse_assembly_string("add x0, x1, #1\n", "t".into()).expect("parse failed");
+        let base = SearchConfig::default()
+            .with_timeout(Duration::from_secs(10))
+            .with_registers(vec![Register::X0, Register::X1])
+            .with_immediates(vec![0, 1])
+            .with_cost_metric(CostMetric::Clobbers(
+                semantics::cost::LiveRegisterMask::EMPTY,
+            ))
+            .with_stochastic(StochasticConfig::default().with_iterations(2_000));
+        let live_out = LiveOut::from_registers(vec![Register::X0]);
+
+        let rows = compare_algorithms(&target, &live_out, &base).expect("valid block");
+
+        // Writing the live-out x0 is the result, not a clobber.
+        let symbolic = rows
+            .iter()
+            .find(|row| row.algorithm == Algorithm::Symbolic)
+            .unwrap();
+        assert!(symbolic.found);
+        assert_eq!(
+            symbolic.best_cost,
+            semantics::cost::sequence_cost(&fused, &base.cost_metric.with_live_out(&live_out))
+        );
+        assert!(symbolic.best_cost < semantics::cost::sequence_cost(&fused, &base.cost_metric));
+    }
+
+    #[test]
+    fn repl_commands_update_settings_and_report_errors() {
+        let mut session =
+            ReplSession::new(Algorithm::Symbolic, "x0", Duration::from_secs(10)).unwrap();
+
+        assert_eq!(
+            session.handle_line(":algo enumerative"),
+            Some(vec!["algorithm: enumerative".to_string()])
+        );
+        assert_eq!(session.algorithm, Algorithm::Enumerative);
+        assert_eq!(
+            session.handle_line(":live-out x0,x1;nzcv"),
+            Some(vec!["live-out: x0,x1;nzcv".to_string()])
+        );
+        assert!(session.live_out.flags_live());
+        assert_eq!(
+            session.handle_line(":timeout 5"),
+            Some(vec!["timeout: 5s".to_string()])
+        );
+        assert_eq!(session.timeout, Duration::from_secs(5));
+
+        for bad in [":live-out q9", ":algo fastest", ":timeout soon", ":frob"] {
+            let reply = session.handle_line(bad).unwrap();
+            assert!(reply[0].starts_with("error:"), "{bad}: {reply:?}");
+        }
+        let reply = session.handle_line("frob x0, x1").unwrap();
+        assert_eq!(reply, vec!["error: unknown instruction: frob".to_string()]);
+
+        assert_eq!(session.handle_line("mov x0, x1"), Some(Vec::new()));
+        let shown = session.handle_line(":show").unwrap();
+        assert_eq!(shown.last().map(String::as_str), Some("  mov x0, x1"));
+        session.handle_line(":clear");
+        assert!(session.buffer.is_empty());
+        assert_eq!(session.handle_line(":quit"), None);
+    }
+
+    #[test]
+    fn run_repl_optimizes_each_blank_line_separated_block() {
+        let mut session =
+            ReplSession::new(Algorithm::Symbolic, "x0", Duration::from_secs(10)).unwrap();
+        let input = "mov x1, #7\n\nadd x0, x1, #1\n";
+        let mut output = Vec::new();
+
+        run_repl(&mut session, input.as_bytes(), &mut output).unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines[1], "Optimized (cost 1 -> 0):");
+        assert_eq!(lines[2], "  (empty sequence)");
+        assert_eq!(lines[4], "No improvement found (cost 1)");
+        assert!(session.buffer.is_empty());
+    }
+
+    #[test]
+    fn list_sections_reports_each_executable_section_with_its_bounds() {
+        let text = [0x90; 9];
+        let init = [0xc3];
+        let elf_bytes = build_elf64_with_executable_sections(
+            &[(".text", &text, 0x1000), (".init", &init, 0x2000)],
+            elf::abi::EM_X86_64,
+        );
+        let input = TempFile::new_bytes("s11-list-sections", "elf", &elf_bytes);
+
+        let lines = list_sections(input.path()).expect("sections should list");
+
+        assert_eq!(
+            lines,
+            vec![
+                "Executable sections:".to_string(),
+                "  .text            0x00001000-0x00001009  offset 0x40  size 9 bytes".to_string(),
+                "  .init            0x00002000-0x00002001  offset 0x49  size 1 bytes".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn list_sections_rejects_a_section_whose_range_overflows() {
+        let text = [0x90; 9];
+        let elf_bytes = build_elf64_with_executable_sections(
+            &[(".text", &text, u64::MAX - 4)],
+            elf::abi::EM_X86_64,
+        );
+        let input = TempFile::new_bytes("s11-list-sections-overflow", "elf", &elf_bytes);
+
+        let err = list_sections(input.path()).unwrap_err();
+
+        assert!(err.to_string().contains("overflows"), "{err}");
+    }
+
+    #[test]
+    fn support_census_rejects_a_section_whose_range_overflows() {
+        let text = [0x90; 9];
+        let elf_bytes = build_elf64_with_executable_sections(
+            &[(".text", &text, u64::MAX - 4)],
+            elf::abi::EM_X86_64,
+        );
+        let input = TempFile::new_bytes("s11-support-census-overflow", "elf", &elf_bytes);
+        let patcher = ElfPatcher::new(input.path()).expect("x86-64 ELF should parse");
+
+        let err = support_census(&patcher).unwrap_err();
+
+        assert!(err.to_string().contains("overflows"), "{err}");
+    }
+
+    #[test]
+    fn support_census_counts_supported_and_unsupported_mnemonics() {
+        // push rax; mov rax, rbx; add rax, 1; pop rax; push rbx
+        let text = [0x50, 0x48, 0x89, 0xd8, 0x48, 0x83, 0xc0, 0x01, 0x58, 0x53];
+        // nop; push rax
+        let init = [0x90, 0x50];
+        let elf_bytes = build_elf64_with_executable_sections(
+            &[(".text", &text, 0x1000), (".init", &init, 0x2000)],
+            elf::abi::EM_X86_64,
+        );
+        let input = TempFile::new_bytes("s11-support-census", "elf", &elf_bytes);
+        let patcher = ElfPatcher::new(input.path()).expect("x86-64 ELF should parse");
+
+        let census = support_census(&patcher).expect("census should succeed");
+
+        assert_eq!(census.supported, 2);
+        assert_eq!(census.unsupported_count(), 5);
+        assert_eq!(
+            census.unsupported.sorted_entries(),
+            vec![
+                ("push".to_string(), 3),
+                ("nop".to_string(), 1),
+                ("pop".to_string(), 1)
+            ]
+        );
+        assert_eq!(
+            format_support_census(&census, 1),
+            vec![
+                "Instructions: 7".to_string(),
+                "  Supported:         2  (28.6%)".to_string(),
+                "  Unsupported:       5  (71.4%)".to_string(),
+                "Top unsupported mnemonics (1 of 3):".to_string(),
+                "        3  push".to_string(),
+                "Top supported opcodes (1 of 2):".to_string(),
+                "        1  add".to_string(),
+            ]
+        );
     }
 
-    fn build_minimal_elf64(text_bytes: &[u8], text_vaddr: u64, machine: u16) -> Vec<u8> {
-        build_elf64_with_executable_sections(&[(".text", text_bytes, text_vaddr)], machine)
+    #[test]
+    fn support_census_counts_supported_opcodes_by_condition() {
+        let csel = |cond| Instruction::Csel {
+            rd: Register::X0,
+            rn: Register::X1,
+            rm: Register::X2,
+            cond,
+        };
+        let text = assemble_aarch64_test_bytes(&[
+            csel(ir::Condition::EQ),
+            csel(ir::Condition::NE),
+            csel(ir::Condition::EQ),
+        ]);
+        let elf_bytes =
+            build_elf64_with_executable_sections(&[(".text", &text, 0x1000)], elf::abi::EM_AARCH64);
+        let input = TempFile::new_bytes("s11-support-census-conditions", "elf", &elf_bytes);
+        let patcher = ElfPatcher::new(input.path()).expect("AArch64 ELF should parse");
+
+        let census = support_census(&patcher).expect("census should succeed");
+
+        assert_eq!(
+            census.supported_opcodes.sorted_entries(),
+            vec![("csel.eq".to_string(), 2), ("csel.ne".to_string(), 1)]
+        );
     }
 
     #[test]
@@ -3402,6 +5977,47 @@ mod cli_helper_tests {
         );
     }
 
+    #[test]
+    fn clobbers_cost_metric_is_rejected_for_x86() {
+        let clobbers: CostMetric = "clobbers".parse().unwrap();
+        let err = cost_metric_supported(SupportedArch::X86_64, &clobbers).unwrap_err();
+        assert!(err.contains("only supported for AArch64"), "{err}");
+        assert_eq!(
+            cost_metric_supported(SupportedArch::Aarch64, &clobbers),
+            Ok(())
+        );
+        assert_eq!(
+            cost_metric_supported(SupportedArch::X86_32, &CostMetric::CodeSize),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn latency_table_is_rejected_for_x86() {
+        let custom = CostMetric::CustomLatency(semantics::cost::LatencyTable::default());
+        for target in [SupportedArch::X86_64, SupportedArch::X86_32] {
+            let err = cost_metric_supported(target, &custom).unwrap_err();
+            assert!(err.contains("--latency-table"), "{err}");
+        }
+        assert_eq!(
+            cost_metric_supported(SupportedArch::Aarch64, &custom),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn qemu_verify_is_rejected_for_x86_and_without_the_feature() {
+        let x86 = qemu_verify_supported(SupportedArch::X86_64).unwrap_err();
+        let aarch64 = qemu_verify_supported(SupportedArch::Aarch64);
+        if cfg!(feature = "qemu") {
+            assert!(x86.contains("only supported for AArch64"), "{x86}");
+            assert_eq!(aarch64, Ok(()));
+        } else {
+            assert!(x86.contains("--features qemu"), "{x86}");
+            assert!(aarch64.unwrap_err().contains("--features qemu"));
+        }
+    }
+
     #[test]
     fn supported_arch_try_from_cli_arch_rejects_riscv() {
         assert_eq!(
@@ -3739,6 +6355,41 @@ mod cli_helper_tests {
         assert_eq!(end_addr, None);
     }
 
+    #[test]
+    fn opt_window_parses_and_conflicts_with_separate_addresses() {
+        let Commands::Opt {
+            window,
+            start_addr,
+            end_addr,
+            ..
+        } = parse_opt(&["s11", "opt", "prog.elf", "--window", "0x1000-0x1100"])
+        else {
+            panic!("expected the opt subcommand");
+        };
+        assert_eq!(
+            window,
+            Some(AddressWindow {
+                start: 0x1000,
+                end: 0x1100
+            })
+        );
+        assert_eq!(start_addr, None);
+        assert_eq!(end_addr, None);
+
+        let err = parse_opt_err(&[
+            "s11",
+            "opt",
+            "prog.elf",
+            "--window",
+            "0x1000-0x1100",
+            "--start-addr",
+            "0x1000",
+        ]);
+        assert_eq!(err.kind(), clap::error::ErrorKind::ArgumentConflict);
+        let err = parse_opt_err(&["s11", "opt", "prog.elf", "--window", "0x1100-0x1000"]);
+        assert_eq!(err.kind(), clap::error::ErrorKind::ValueValidation);
+    }
+
     #[test]
     fn opt_auto_without_output_parses() {
         // The driver falls back to the derived path when -o is omitted, so
@@ -3752,6 +6403,73 @@ mod cli_helper_tests {
         assert_eq!(output, None);
     }
 
+    #[test]
+    fn opt_json_format_is_limited_to_window_plans() {
+        let Commands::Opt { format, .. } = parse_opt(&[
+            "s11",
+            "opt",
+            "prog.elf",
+            "--window",
+            "0x1000-0x1100",
+            "--format",
+            "json",
+        ]) else {
+            panic!("expected the opt subcommand");
+        };
+        assert_eq!(format, OutputFormat::Json);
+
+        for mode in ["--auto", "--optimize-all", "--explain", "--bench-search"] {
+            let err = parse_opt_err(&["s11", "opt", "prog.elf", mode, "--format", "json"]);
+            assert_eq!(
+                err.kind(),
+                clap::error::ErrorKind::ArgumentConflict,
+                "{mode}"
+            );
+        }
+        // The default text format leaves every mode available.
+        let Commands::Opt { auto, .. } = parse_opt(&["s11", "opt", "prog.elf", "--auto"]) else {
+            panic!("expected the opt subcommand");
+        };
+        assert!(auto);
+    }
+
+    #[test]
+    fn opt_min_size_requires_optimize_all() {
+        let err = parse_opt_err(&["s11", "opt", "prog.elf", "--min-size", "16"]);
+        assert_eq!(err.kind(), clap::error::ErrorKind::MissingRequiredArgument);
+        // clap waives `requires` when a conflicting mode is present, so the
+        // window modes are rejected as conflicts instead.
+        let err = parse_opt_err(&[
+            "s11",
+            "opt",
+            "prog.elf",
+            "--window",
+            "0x1000-0x1100",
+            "--min-size",
+            "16",
+        ]);
+        assert_eq!(err.kind(), clap::error::ErrorKind::ArgumentConflict);
+
+        let Commands::Opt { min_size, .. } = parse_opt(&[
+            "s11",
+            "opt",
+            "prog.elf",
+            "--optimize-all",
+            "--min-size",
+            "16",
+        ]) else {
+            panic!("expected the opt subcommand");
+        };
+        assert_eq!(min_size, 16);
+        // The default does not drag in --optimize-all.
+        let Commands::Opt { min_size, .. } =
+            parse_opt(&["s11", "opt", "prog.elf", "--window", "0x1000-0x1100"])
+        else {
+            panic!("expected the opt subcommand");
+        };
+        assert_eq!(min_size, 8);
+    }
+
     #[test]
     fn opt_auto_conflicts_with_start_addr() {
         let err = parse_opt_err(&["s11", "opt", "prog.elf", "--auto", "--start-addr", "0x1000"]);
@@ -3858,7 +6576,7 @@ mod cli_helper_tests {
     fn resolve_output_path_falls_back_to_derived_path() {
         let input = Path::new("/some/dir/prog.elf");
         assert_eq!(
-            resolve_output_path(input, None).unwrap(),
+            resolve_output_path(input, None, false).unwrap(),
             optimized_output_path(input)
         );
     }
@@ -3868,11 +6586,24 @@ mod cli_helper_tests {
         let input = Path::new("/some/dir/prog.elf");
         let out = Path::new("/other/place/out.bin");
         assert_eq!(
-            resolve_output_path(input, Some(out)).unwrap(),
+            resolve_output_path(input, Some(out), false).unwrap(),
             out.to_path_buf()
         );
     }
 
+    #[test]
+    fn resolve_output_path_refuses_an_existing_output_without_overwrite() {
+        let input = Path::new("/some/dir/prog.elf");
+        let existing = TempFile::new_bytes("s11-resolve-existing", "bin", &[0u8; 4]);
+        let err = resolve_output_path(input, Some(existing.path()), false)
+            .expect_err("an existing -o target must not be replaced silently");
+        assert!(err.contains("--overwrite"), "unexpected error: {err}");
+        assert_eq!(
+            resolve_output_path(input, Some(existing.path()), true).unwrap(),
+            existing.path().to_path_buf()
+        );
+    }
+
     #[test]
     fn resolve_output_path_rejects_in_place_output() {
         // The same existing file addressed two ways (a `.` component): on Unix
@@ -3885,7 +6616,7 @@ mod cli_helper_tests {
             .unwrap()
             .join(".")
             .join(input.path().file_name().unwrap());
-        let err = resolve_output_path(input.path(), Some(&aliased))
+        let err = resolve_output_path(input.path(), Some(&aliased), true)
             .expect_err("output resolving to the input binary must be rejected");
         assert!(
             err.contains("refusing to optimize in place"),
@@ -3901,7 +6632,7 @@ mod cli_helper_tests {
         let input = TempFile::new_bytes("s11-resolve-hardlink", "elf", &[0u8; 8]);
         let link = input.path().with_extension("hardlink");
         std::fs::hard_link(input.path(), &link).expect("create hard link to input");
-        let result = resolve_output_path(input.path(), Some(&link));
+        let result = resolve_output_path(input.path(), Some(&link), true);
         let _ = std::fs::remove_file(&link);
         let err = result.expect_err("a hard link to the input binary must be rejected");
         assert!(
@@ -3971,6 +6702,20 @@ mod cli_helper_tests {
         assert!(ir.is_empty(), "pure-NOP windows should produce empty IR");
     }
 
+    #[test]
+    fn reject_pc_relative_window_flags_literal_loads_but_not_register_loads() {
+        let cs = aarch64_test_capstone();
+        let register_load = [0x20, 0x00, 0x40, 0xf9]; // ldr x0, [x1]
+        let instructions = cs.disasm_all(&register_load, 0x1000).unwrap();
+        assert!(reject_pc_relative_window(DetectedArch::Aarch64, &instructions).is_ok());
+
+        let literal_load = [0x40, 0x00, 0x00, 0x58]; // ldr x0, #0x1008
+        let instructions = cs.disasm_all(&literal_load, 0x1000).unwrap();
+        let err = reject_pc_relative_window(DetectedArch::Aarch64, &instructions)
+            .expect_err("a literal load must be refused by default");
+        assert!(err.contains("0x1000"), "unexpected error: {err}");
+    }
+
     #[test]
     fn convert_to_ir_treats_nop_add_nop_as_add() {
         let cs = aarch64_test_capstone();
@@ -3997,6 +6742,52 @@ mod cli_helper_tests {
         );
     }
 
+    #[test]
+    fn convert_to_ir_maps_multiply_accumulate_encodings() {
+        let cs = aarch64_test_capstone();
+        let bytes = [
+            0x20, 0x0c, 0x02, 0x9b, // madd x0, x1, x2, x3
+            0xa4, 0x9c, 0x06, 0x9b, // msub x4, x5, x6, x7
+            0x20, 0x7c, 0x02, 0x9b, // madd x0, x1, x2, xzr (mul)
+            0x20, 0xfc, 0x02, 0x9b, // msub x0, x1, x2, xzr (mneg)
+        ];
+        let instructions = cs
+            .disasm_all(&bytes, 0x1000)
+            .expect("multiply-accumulate bytes should disassemble");
+
+        let ir = convert_to_ir(&instructions).expect("multiply-accumulate window should convert");
+
+        let (x0, x1, x2) = (Register::X0, Register::X1, Register::X2);
+        assert_eq!(
+            ir,
+            vec![
+                Instruction::Madd {
+                    rd: x0,
+                    rn: x1,
+                    rm: x2,
+                    ra: Register::X3,
+                },
+                Instruction::Msub {
+                    rd: Register::X4,
+                    rn: Register::X5,
+                    rm: Register::X6,
+                    ra: Register::X7,
+                },
+                Instruction::Mul {
+                    rd: x0,
+                    rn: x1,
+                    rm: x2
+                },
+                Instruction::Mneg {
+                    rd: x0,
+                    rn: x1,
+                    rm: x2
+                },
+            ]
+        );
+        assert_eq!(assemble_aarch64_test_bytes(&ir), bytes);
+    }
+
     #[test]
     fn first_neon_slice_round_trips_through_assembler_capstone_and_parser() {
         let original = vec![
@@ -4673,61 +7464,347 @@ mod cli_helper_tests {
             }]
         );
 
-        let mov_al = cs
-            .disasm_all(&[0xb0, 0x7f], 0x1000)
-            .expect("disassemble mov al, 0x7f");
-        let insn = mov_al.iter().next().expect("one instruction");
-        assert_eq!(insn.mnemonic(), Some("mov"));
-        assert_eq!(insn.op_str(), Some("al, 0x7f"));
+        let mov_al = cs
+            .disasm_all(&[0xb0, 0x7f], 0x1000)
+            .expect("disassemble mov al, 0x7f");
+        let insn = mov_al.iter().next().expect("one instruction");
+        assert_eq!(insn.mnemonic(), Some("mov"));
+        assert_eq!(insn.op_str(), Some("al, 0x7f"));
+        assert_eq!(
+            convert_to_x86_ir(&mov_al, parser::x86::X86ParseMode::Mode64).unwrap(),
+            vec![X86Instruction::MovImm {
+                rd: X86Register::AL,
+                imm: 0x7f,
+            }]
+        );
+    }
+
+    #[test]
+    fn x86_capstone_bridge_accepts_mode_width_register_aliases() {
+        let cs64 = capstone::Capstone::new()
+            .x86()
+            .mode(capstone::arch::x86::ArchMode::Mode64)
+            .syntax(capstone::arch::x86::ArchSyntax::Intel)
+            .build()
+            .expect("capstone x86-64 init");
+        let add_rax = cs64
+            .disasm_all(&[0x48, 0x83, 0xc0, 0x00], 0x1000)
+            .expect("disassemble add rax, 0");
+        let insn = add_rax.iter().next().expect("one instruction");
+        assert_eq!(insn.mnemonic(), Some("add"));
+        assert_eq!(insn.op_str(), Some("rax, 0"));
+        assert_eq!(
+            convert_to_x86_ir(&add_rax, parser::x86::X86ParseMode::Mode64).unwrap(),
+            vec![X86Instruction::AddImm {
+                rd: X86Register::RAX,
+                imm: 0,
+            }]
+        );
+
+        let cs32 = capstone::Capstone::new()
+            .x86()
+            .mode(capstone::arch::x86::ArchMode::Mode32)
+            .syntax(capstone::arch::x86::ArchSyntax::Intel)
+            .build()
+            .expect("capstone x86-32 init");
+        let add_eax = cs32
+            .disasm_all(&[0x83, 0xc0, 0x00], 0x1000)
+            .expect("disassemble add eax, 0");
+        let insn = add_eax.iter().next().expect("one instruction");
+        assert_eq!(insn.mnemonic(), Some("add"));
+        assert_eq!(insn.op_str(), Some("eax, 0"));
+        assert_eq!(
+            convert_to_x86_ir(&add_eax, parser::x86::X86ParseMode::Mode32).unwrap(),
+            vec![X86Instruction::AddImm {
+                rd: X86Register::EAX,
+                imm: 0,
+            }]
+        );
+    }
+
+    #[test]
+    fn split_fixed_regions_rejects_windows_with_nothing_left_to_search() {
+        let ir = [1, 2, 3, 4];
+        assert_eq!(
+            split_fixed_regions(&ir, 1, 2).unwrap(),
+            (&ir[..1], &ir[1..2], &ir[2..])
+        );
+        assert_eq!(
+            split_fixed_regions(&ir, 0, 0).unwrap(),
+            (&ir[..0], &ir[..], &ir[4..])
+        );
+        assert!(split_fixed_regions(&ir, 2, 2).is_err());
+        assert!(split_fixed_regions(&ir, 5, 0).is_err());
+        assert!(split_fixed_regions::<u8>(&[], 0, 0).is_ok());
+    }
+
+    #[test]
+    fn fixed_suffix_reads_stay_live_out_of_searched_region() {
+        let context = OptimizationContext {
+            downstream_flags_live: false,
+            downstream_live_regs: DownstreamLiveRegs::Aarch64(
+                semantics::live_out::RegisterSet::from_registers(vec![Register::X0]),
+            ),
+            max_bytes: None,
+        };
+        let suffix = [Instruction::Csel {
+            rd: Register::X0,
+            rn: Register::X1,
+            rm: Register::X2,
+            cond: s11::ir::Condition::EQ,
+        }];
+        let context = AArch64OptimizationBackend.context_before_fixed_suffix(context, &suffix);
+        assert!(context.downstream_flags_live, "the suffix reads NZCV");
+        let DownstreamLiveRegs::Aarch64(live) = context.downstream_live_regs else {
+            panic!("AArch64 narrowing should survive the fixed suffix");
+        };
+        for reg in [Register::X0, Register::X1, Register::X2] {
+            assert!(live.contains(reg), "{reg} should be live");
+        }
+    }
+
+    #[test]
+    fn plan_elf_optimization_describes_the_mov_add_window_without_writing() {
+        let target = [
+            Instruction::MovReg {
+                rd: Register::X0,
+                rn: Register::X1,
+            },
+            Instruction::Add {
+                rd: Register::X0,
+                rn: Register::X0,
+                rm: Operand::Immediate(1),
+            },
+        ];
+        let bytes = assemble_aarch64_test_bytes(&target);
+        let elf_bytes = build_minimal_elf64(&bytes, 0x1000, elf::abi::EM_AARCH64);
+        let patcher = ElfPatcher::from_bytes(elf_bytes).expect("AArch64 ELF should parse");
+        let mut opts = options_for(Algorithm::Enumerative);
+        opts.timeout = Some(Duration::from_secs(10));
+        opts.solver_timeout = Duration::from_secs(5);
+
+        let plan = plan_elf_optimization(
+            &AArch64OptimizationBackend,
+            &patcher,
+            0x1000,
+            0x1008,
+            None,
+            &opts,
+        )
+        .expect("planning should succeed")
+        .expect("AArch64 always produces a patch");
+
+        let fused = Instruction::Add {
+            rd: Register::X0,
+            rn: Register::X1,
+            rm: Operand::Immediate(1),
+        };
+        assert_eq!(
+            plan.window,
+            AddressWindow {
+                start: 0x1000,
+                end: 0x1008
+            }
+        );
+        assert_eq!(plan.original, target);
+        assert_eq!(plan.optimized, vec![fused]);
+        assert_eq!(plan.original_bytes, bytes);
+        assert_eq!(plan.new_bytes, assemble_aarch64_test_bytes(&[fused]));
+        assert_eq!(plan.nops_added, 1);
+        assert_eq!((plan.cost_before, plan.cost_after), (2, 1));
+        // The fused instruction replaced both original instructions.
+        assert_eq!(
+            plan.replaced,
+            Some(AddressWindow {
+                start: 0x1000,
+                end: 0x1008
+            })
+        );
+        assert_eq!(
+            patch_plan_lines(&plan),
+            vec![
+                "Optimized to 1 instructions:".to_string(),
+                format!("  {}", fused),
+                "Replaced bytes [0x1000, 0x1008)".to_string(),
+            ]
+        );
+        assert_eq!(
+            patch_plan_json(&plan),
+            serde_json::json!({
+                "window": { "start": "0x1000", "end": "0x1008" },
+                "replaced": { "start": "0x1000", "end": "0x1008" },
+                "original": ["mov x0, x1", "add x0, x0, #1"],
+                "optimized": ["add x0, x1, #1"],
+                "cost_before": 2,
+                "cost_after": 1,
+                "original_bytes": 8,
+                "new_bytes": 4,
+                "nops_added": 1,
+            })
+        );
+        assert_eq!(
+            patch_plan_progress_lines(&plan),
+            vec![
+                "Cost: 2 -> 1".to_string(),
+                "Reassembled to 4 of 8 bytes (1 NOP padding)".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn replaced_range_spans_the_searched_instructions() {
+        let source: Vec<InstructionMeta> = [0x1000, 0x1004, 0x1008]
+            .into_iter()
+            .map(InstructionMeta::from_addr)
+            .collect();
+        let range = |start, end| Some(AddressWindow { start, end });
+
+        assert_eq!(replaced_range(&source, 0, 0, 0x100c), range(0x1000, 0x100c));
+        // Fixed instructions on either side are not part of the rewrite.
+        assert_eq!(replaced_range(&source, 1, 1, 0x100c), range(0x1004, 0x1008));
+        assert_eq!(replaced_range(&source, 2, 1, 0x100c), None);
+    }
+
+    #[test]
+    fn resolve_cost_metric_loads_a_latency_table_for_latency_only() {
         assert_eq!(
-            convert_to_x86_ir(&mov_al, parser::x86::X86ParseMode::Mode64).unwrap(),
-            vec![X86Instruction::MovImm {
-                rd: X86Register::AL,
-                imm: 0x7f,
-            }]
+            resolve_cost_metric(CliCostMetric::CodeSize, None).unwrap(),
+            CostMetric::CodeSize
         );
+        let table = TempFile::new("s11-latency-table", "json", r#"{"10": 1}"#);
+        let metric = resolve_cost_metric(CliCostMetric::Latency, Some(table.path())).unwrap();
+        let expected = semantics::cost::LatencyTable::from_overrides(
+            &std::collections::HashMap::from([(10, 1)]),
+        )
+        .unwrap();
+        assert_eq!(metric, CostMetric::CustomLatency(expected));
+
+        assert!(resolve_cost_metric(CliCostMetric::InstructionCount, Some(table.path())).is_err());
+        let bad = TempFile::new("s11-latency-table-bad", "json", r#"{"200": 1}"#);
+        assert!(resolve_cost_metric(CliCostMetric::Latency, Some(bad.path())).is_err());
     }
 
     #[test]
-    fn x86_capstone_bridge_accepts_mode_width_register_aliases() {
-        let cs64 = capstone::Capstone::new()
-            .x86()
-            .mode(capstone::arch::x86::ArchMode::Mode64)
-            .syntax(capstone::arch::x86::ArchSyntax::Intel)
-            .build()
-            .expect("capstone x86-64 init");
-        let add_rax = cs64
-            .disasm_all(&[0x48, 0x83, 0xc0, 0x00], 0x1000)
-            .expect("disassemble add rax, 0");
-        let insn = add_rax.iter().next().expect("one instruction");
-        assert_eq!(insn.mnemonic(), Some("add"));
-        assert_eq!(insn.op_str(), Some("rax, 0"));
+    fn window_manifest_patches_every_listed_window_into_one_copy() {
+        let mov_add = [
+            Instruction::MovReg {
+                rd: Register::X0,
+                rn: Register::X1,
+            },
+            Instruction::Add {
+                rd: Register::X0,
+                rn: Register::X0,
+                rm: Operand::Immediate(1),
+            },
+        ];
+        let between = Instruction::MovImm {
+            rd: Register::X5,
+            imm: 7,
+        };
+        let bytes = assemble_aarch64_test_bytes(&[&mov_add[..], &[between], &mov_add[..]].concat());
+        let elf_bytes = build_minimal_elf64(&bytes, 0x1000, elf::abi::EM_AARCH64);
+        let input = TempFile::new_bytes("s11-manifest-in", "elf", &elf_bytes);
+        let output = TempFile::new_bytes("s11-manifest-out", "elf", &[]);
+        let manifest = TempFile::new(
+            "s11-manifest",
+            "json",
+            // The second window overwrites x0, so without the first entry's
+            // explicit contract the downstream scan would find it dead.
+            r#"[{"start": "0x1000", "end": "0x1008", "live_out": "x0"},
+                {"start": "0x100c", "end": "0x1014"}]"#,
+        );
+        let patcher = ElfPatcher::new(input.path()).expect("AArch64 ELF should parse");
+        let mut opts = options_for(Algorithm::Enumerative);
+        opts.timeout = Some(Duration::from_secs(10));
+        opts.solver_timeout = Duration::from_secs(5);
+
+        let windows = load_window_manifest(manifest.path()).expect("manifest should parse");
+        assert_eq!(windows[0].live_out.as_deref(), Some("x0"));
+        optimize_window_manifest(&patcher, input.path(), &windows, output.path(), &opts)
+            .expect("both windows should optimize");
+
+        let patched = ElfPatcher::new(output.path()).expect("output should parse");
+        let window = AddressWindow {
+            start: 0x1000,
+            end: 0x1014,
+        };
+        let text = patched.get_instructions_in_window(&window).unwrap();
+        let cs = AArch64OptimizationBackend.disassembler().unwrap();
+        let disassembly = cs.disasm_all(&text, 0x1000).unwrap();
+        let fused = Instruction::Add {
+            rd: Register::X0,
+            rn: Register::X1,
+            rm: Operand::Immediate(1),
+        };
+        // NOP padding is dropped by the IR conversion.
+        assert_eq!(disassembly.len(), 5, "each window keeps one NOP of padding");
         assert_eq!(
-            convert_to_x86_ir(&add_rax, parser::x86::X86ParseMode::Mode64).unwrap(),
-            vec![X86Instruction::AddImm {
-                rd: X86Register::RAX,
-                imm: 0,
-            }]
+            convert_to_ir(&disassembly).unwrap(),
+            vec![fused, between, fused]
         );
+    }
 
-        let cs32 = capstone::Capstone::new()
-            .x86()
-            .mode(capstone::arch::x86::ArchMode::Mode32)
-            .syntax(capstone::arch::x86::ArchSyntax::Intel)
-            .build()
-            .expect("capstone x86-32 init");
-        let add_eax = cs32
-            .disasm_all(&[0x83, 0xc0, 0x00], 0x1000)
-            .expect("disassemble add eax, 0");
-        let insn = add_eax.iter().next().expect("one instruction");
-        assert_eq!(insn.mnemonic(), Some("add"));
-        assert_eq!(insn.op_str(), Some("eax, 0"));
+    #[test]
+    fn already_optimal_window_writes_no_output_unless_asked() {
+        let add = Instruction::Add {
+            rd: Register::X0,
+            rn: Register::X1,
+            rm: Operand::Immediate(1),
+        };
+        let bytes = assemble_aarch64_test_bytes(&[add]);
+        let elf_bytes = build_minimal_elf64(&bytes, 0x1000, elf::abi::EM_AARCH64);
+        let input = TempFile::new_bytes("s11-already-optimal", "elf", &elf_bytes);
+        let output = TempFile::new_bytes("s11-already-optimal-out", "elf", &[]);
+        std::fs::remove_file(output.path()).unwrap();
+        let patcher = ElfPatcher::new(input.path()).expect("AArch64 ELF should parse");
+        let mut opts = options_for(Algorithm::Enumerative);
+
+        optimize_elf_binary(&patcher, input.path(), 0x1000, 0x1004, output.path(), &opts)
+            .expect("an unimprovable window is not an error");
+        assert!(!output.path().exists(), "no improvement: nothing written");
+
+        opts.write_unchanged = true;
+        optimize_elf_binary(&patcher, input.path(), 0x1000, 0x1004, output.path(), &opts)
+            .expect("--write-unchanged should write the copy");
+        assert_eq!(std::fs::read(output.path()).unwrap(), elf_bytes);
+    }
+
+    #[test]
+    fn aarch64_fixed_suffix_optimizes_only_the_interior() {
+        let add_one = Instruction::Add {
+            rd: Register::X0,
+            rn: Register::X0,
+            rm: Operand::Immediate(1),
+        };
+        let bytes = assemble_aarch64_test_bytes(&[add_one, add_one, add_one]);
+        let elf_bytes = build_minimal_elf64(&bytes, 0x1000, elf::abi::EM_AARCH64);
+        let input = TempFile::new_bytes("s11-fixed-suffix-a64", "elf", &elf_bytes);
+        let output = TempFile::new_bytes("s11-fixed-suffix-a64-out", "elf", &[]);
+        let patcher = ElfPatcher::new(input.path()).expect("AArch64 ELF should parse");
+        let mut opts = options_for(Algorithm::Enumerative);
+        opts.timeout = Some(Duration::from_secs(10));
+        opts.solver_timeout = Duration::from_secs(5);
+        opts.fixed_suffix = 1;
+
+        optimize_elf_binary(&patcher, input.path(), 0x1000, 0x100c, output.path(), &opts)
+            .expect("fixed-suffix optimization should succeed");
+
+        let patched = fs::read(output.path()).expect("patched binary should exist");
+        let window = &patched[64..64 + bytes.len()];
+        let cs = aarch64_test_capstone();
+        let disassembly = cs.disasm_all(window, 0x1000).expect("patched window");
+        // The two searchable adds fold into one; the fixed trailing add is
+        // kept verbatim rather than folded in as well.
         assert_eq!(
-            convert_to_x86_ir(&add_eax, parser::x86::X86ParseMode::Mode32).unwrap(),
-            vec![X86Instruction::AddImm {
-                rd: X86Register::EAX,
-                imm: 0,
-            }]
+            convert_to_ir(&disassembly).unwrap(),
+            vec![
+                Instruction::Add {
+                    rd: Register::X0,
+                    rn: Register::X0,
+                    rm: Operand::Immediate(2),
+                },
+                add_one,
+            ]
         );
     }
 
@@ -5136,6 +8213,7 @@ mod cli_helper_tests {
         let context = OptimizationContext {
             downstream_flags_live: false,
             downstream_live_regs: DownstreamLiveRegs::Unknown,
+            max_bytes: None,
         };
 
         // 66 b8 00 00 = mov ax, 0. The register view survives conversion.
@@ -5217,11 +8295,12 @@ mod cli_helper_tests {
         let mut opts = options_for(Algorithm::Stochastic);
         opts.timeout = Some(Duration::from_millis(200));
         opts.solver_timeout = Duration::from_millis(200);
-        opts.iterations = 50;
+        opts.iterations = Some(50);
         opts.cost_metric = CostMetric::CodeSize;
         let context = OptimizationContext {
             downstream_flags_live: false,
             downstream_live_regs: DownstreamLiveRegs::Unknown,
+            max_bytes: None,
         };
 
         // 48 c7 c0 00 00 00 00 = mov rax, 0 (full-width source operand).
@@ -5254,6 +8333,7 @@ mod cli_helper_tests {
         let context = OptimizationContext {
             downstream_flags_live: false,
             downstream_live_regs: DownstreamLiveRegs::Unknown,
+            max_bytes: None,
         };
 
         // 48 c7 c0 00 00 00 00 = mov rax, 0, written twice. The first write is
@@ -5288,6 +8368,7 @@ mod cli_helper_tests {
         let context = OptimizationContext {
             downstream_flags_live: false,
             downstream_live_regs: DownstreamLiveRegs::Unknown,
+            max_bytes: None,
         };
 
         // 48 c7 c0 00 00 00 00 = mov rax, 0.
@@ -5451,6 +8532,52 @@ mod cli_helper_tests {
         }
     }
 
+    /// `mov rax, rbx; lea rax, [rax + 1]` costs 11 bytes but only one cycle
+    /// (the register move is free), so `mov rax, rbx; inc rax` is an
+    /// improvement under code size and not under latency: the metric must
+    /// steer the search. The trailing `mov rbx, rbx` only brings RBX into the
+    /// destination-derived register pool.
+    #[test]
+    fn x86_enumerative_result_depends_on_the_cost_metric() {
+        let target = [
+            X86Instruction::MovReg {
+                rd: X86Register::RAX,
+                rs: X86Register::RBX,
+            },
+            X86Instruction::Lea {
+                rd: X86Register::RAX,
+                base: X86Register::RAX,
+                disp: 1,
+            },
+            X86Instruction::MovReg {
+                rd: X86Register::RBX,
+                rs: X86Register::RBX,
+            },
+        ];
+        let mut opts = options_for(Algorithm::Enumerative);
+        opts.timeout = None;
+        opts.solver_timeout = Duration::from_secs(30);
+
+        opts.cost_metric = "size".parse().unwrap();
+        let optimized =
+            run_x86_enumerative(&target, 64, &opts, false, None).expect("inc is shorter than lea");
+        assert_eq!(
+            optimized,
+            vec![
+                X86Instruction::MovReg {
+                    rd: X86Register::RAX,
+                    rs: X86Register::RBX,
+                },
+                X86Instruction::Inc {
+                    rd: X86Register::RAX,
+                },
+            ]
+        );
+
+        opts.cost_metric = "latency".parse().unwrap();
+        assert_eq!(run_x86_enumerative(&target, 64, &opts, false, None), None);
+    }
+
     /// Regression (PR #384): the trait-backed enumerative path must draw
     /// candidates from the target's own registers/immediates. R10 is outside
     /// `default_x86_registers()` and `-1` outside `default_x86_immediates()`,
@@ -5496,7 +8623,7 @@ mod cli_helper_tests {
         opts.timeout = None;
         opts.solver_timeout = Duration::from_secs(30);
         opts.cost_metric = CostMetric::InstructionCount;
-        opts.iterations = 50_000;
+        opts.iterations = Some(50_000);
         opts.seed = Some(7);
 
         let target = r10_zeroing_target();
@@ -5632,7 +8759,7 @@ mod cli_helper_tests {
         opts.timeout = Some(Duration::from_millis(31));
         opts.solver_timeout = Duration::from_millis(37);
         opts.beta = 7.25;
-        opts.iterations = 987;
+        opts.iterations = Some(987);
         opts.seed = Some(123);
         opts.cost_metric = CostMetric::Latency;
         opts.verbose = true;
@@ -5673,7 +8800,7 @@ mod cli_helper_tests {
         // shared base means the config still honors --solver-timeout for SMT
         // verification queries.
         assert_eq!(config.stochastic.beta, 7.25);
-        assert_eq!(config.stochastic.iterations, 987);
+        assert_eq!(config.stochastic.iterations, Some(987));
         assert_eq!(config.stochastic.seed, Some(123));
         assert_eq!(config.solver_timeout, Some(Duration::from_millis(37)));
     }
@@ -5696,18 +8823,18 @@ mod cli_helper_tests {
             smt_formula_bytes_total: 2_048,
             smt_formula_bytes_max: 1_536,
         };
-        print_llm_timings(&timings, Duration::from_millis(10));
+        print_llm_timings(&timings, Duration::from_millis(10), false);
 
         let mut ledger = UnsupportedMnemonicLedger::new();
-        print_unsupported_mnemonic_ledger(&ledger);
+        print_unsupported_mnemonic_ledger(&ledger, false);
         ledger.record("ldr");
-        print_unsupported_mnemonic_ledger(&ledger);
+        print_unsupported_mnemonic_ledger(&ledger, false);
 
         let mut stats = SearchStatistics::new(Algorithm::Stochastic);
         stats.candidates_pruned_by_cost = 3;
         stats.iterations = 10;
         stats.accepted_proposals = 5;
-        print_search_statistics(&stats);
+        print_search_statistics(&stats, false);
     }
 
     #[test]
@@ -5851,6 +8978,29 @@ mod cli_helper_tests {
         assert!(lines.iter().any(|l| l.contains("Total:")));
     }
 
+    #[test]
+    fn format_search_rates_divides_counters_by_elapsed_time() {
+        let mut stats = SearchStatistics::new(Algorithm::Stochastic);
+        stats.elapsed_time = Duration::from_secs(2);
+        stats.candidates_evaluated = 1000;
+        stats.candidates_passed_fast = 50;
+        stats.smt_queries = 4;
+        stats.iterations = 1000;
+        stats.accepted_proposals = 250;
+
+        assert_eq!(
+            format_search_rates(&stats),
+            vec![
+                "\nSearch Throughput:",
+                "  Elapsed time: 2.00s",
+                "  Candidates/sec: 500.0",
+                "  Fast passes/sec: 25.0",
+                "  SMT queries/sec: 2.0",
+                "  Acceptance rate: 25.00%",
+            ],
+        );
+    }
+
     #[test]
     fn format_search_statistics_omits_iteration_lines_when_no_iterations() {
         let stats = SearchStatistics::new(Algorithm::Enumerative);
@@ -5868,6 +9018,21 @@ mod cli_helper_tests {
     /// default 60 s timeout and the per-worker search loop is unbounded
     /// (the coordinator-level deadline is now the primary cancel path, but
     /// this stays as a backstop).
+    #[test]
+    fn stochastic_and_hybrid_configs_carry_the_initial_program() {
+        let program = vec![Instruction::MovReg {
+            rd: Register::X0,
+            rn: Register::X1,
+        }];
+        let mut opts = options_for(Algorithm::Stochastic);
+        opts.initial_program = Some(program.clone());
+
+        let stochastic = build_stochastic_search_config(&opts, vec![Register::X0], vec![0]);
+        assert_eq!(stochastic.stochastic.initial_program, Some(program.clone()));
+        let hybrid = build_hybrid_search_config(&opts, vec![Register::X0], vec![0]);
+        assert_eq!(hybrid.stochastic.initial_program, Some(program));
+    }
+
     #[test]
     fn build_hybrid_search_config_propagates_timeout() {
         let mut opts = options_for(Algorithm::Hybrid);
@@ -5915,7 +9080,7 @@ mod cli_helper_tests {
         opts.timeout = Some(Duration::from_millis(11));
         opts.solver_timeout = Duration::from_millis(17);
         opts.beta = 2.5;
-        opts.iterations = 123;
+        opts.iterations = Some(123);
         opts.seed = Some(99);
         opts.cost_metric = CostMetric::Latency;
         opts.verbose = true;
@@ -5932,11 +9097,13 @@ mod cli_helper_tests {
     #[test]
     fn build_aarch64_base_search_config_sets_shared_fields_only() {
         // The base seam sets exactly the fields every AArch64 algorithm shares
-        // — cost metric, overall + SMT solver timeouts, verbosity, and the
-        // register/immediate pools — and applies no algorithm-specific layer,
-        // so `cores` (the enumerative layer) stays at its default.
+        // — cost metric, overall + SMT solver timeouts, candidate budget,
+        // verbosity, and the register/immediate pools — and applies no
+        // algorithm-specific layer, so `cores` (the enumerative layer) stays
+        // at its default.
         let mut opts = options_for(Algorithm::Enumerative);
         opts.timeout = Some(Duration::from_millis(8));
+        opts.max_candidates = Some(500);
         opts.solver_timeout = Duration::from_millis(12);
         opts.cost_metric = CostMetric::CodeSize;
         opts.verbose = true;
@@ -5947,6 +9114,7 @@ mod cli_helper_tests {
 
         assert_eq!(config.timeout, Some(Duration::from_millis(8)));
         assert_eq!(config.solver_timeout, Some(Duration::from_millis(12)));
+        assert_eq!(config.max_candidates, Some(500));
         assert_eq!(config.cost_metric, CostMetric::CodeSize);
         assert!(config.verbose);
         assert_eq!(config.available_registers, regs);
@@ -5985,6 +9153,39 @@ mod cli_helper_tests {
         );
     }
 
+    #[test]
+    fn window_seeded_pools_carry_the_window_registers_and_immediates() {
+        // mov x5, #42; add x6, x5, x6
+        let window = [
+            Instruction::MovImm {
+                rd: Register::X5,
+                imm: 42,
+            },
+            Instruction::Add {
+                rd: Register::X6,
+                rn: Register::X5,
+                rm: Operand::Register(Register::X6),
+            },
+        ];
+        let options = options_for(Algorithm::Enumerative);
+
+        let (registers, immediates) = aarch64_search_pools(&window, true);
+        let config = build_enumerative_search_config(&options, registers, immediates);
+        assert_eq!(
+            config.available_registers,
+            vec![Register::X0, Register::X1, Register::X5, Register::X6]
+        );
+        assert!(config.available_immediates.contains(&42));
+
+        let (registers, immediates) = aarch64_search_pools(&window, false);
+        let config = build_enumerative_search_config(&options, registers, immediates);
+        assert_eq!(
+            config.available_registers,
+            (0..8).filter_map(Register::from_index).collect::<Vec<_>>()
+        );
+        assert!(!config.available_immediates.contains(&42));
+    }
+
     /// Regression for issue #243, generalised: every AArch64 algorithm builder
     /// must propagate the shared base fields (`--timeout`, `--solver-timeout`,
     /// cost metric, verbosity, register/immediate pools) identically. They all
@@ -6044,7 +9245,7 @@ mod cli_helper_tests {
         opts.timeout = Some(Duration::from_millis(13));
         opts.solver_timeout = Duration::from_millis(19);
         opts.beta = 3.5;
-        opts.iterations = 456;
+        opts.iterations = Some(456);
         opts.seed = Some(101);
         opts.cost_metric = CostMetric::CodeSize;
         opts.verbose = true;
@@ -6218,15 +9419,80 @@ mod cli_helper_tests {
             Algorithm::Llm,
         ] {
             let options = options_for(algorithm);
-            let _ = run_optimization(&target, &options, true, None).unwrap();
+            let _ = run_optimization(&target, &options, true, None, None).unwrap();
         }
         assert!(
-            run_optimization(&[], &options_for(Algorithm::Enumerative), true, None)
+            run_optimization(&[], &options_for(Algorithm::Enumerative), true, None, None)
                 .unwrap()
                 .is_none()
         );
     }
 
+    #[test]
+    fn run_optimization_drops_an_overwritten_write() {
+        let target = [
+            Instruction::MovImm {
+                rd: Register::X0,
+                imm: 1,
+            },
+            Instruction::MovImm {
+                rd: Register::X0,
+                imm: 2,
+            },
+        ];
+        let x0_live = semantics::live_out::RegisterSet::from_registers(vec![Register::X0]);
+        let mut options = options_for(Algorithm::Enumerative);
+        options.solver_timeout = Duration::from_secs(10);
+
+        let optimized = run_optimization(&target, &options, false, Some(x0_live), None).unwrap();
+
+        assert_eq!(optimized, Some(vec![target[1]]));
+    }
+
+    #[test]
+    fn strict_keeps_writes_the_downstream_analysis_calls_dead() {
+        // x1 is written but proven dead downstream, so the default contract
+        // drops its write; --strict must keep it.
+        let target = [
+            Instruction::MovImm {
+                rd: Register::X1,
+                imm: 5,
+            },
+            Instruction::MovImm {
+                rd: Register::X0,
+                imm: 1,
+            },
+        ];
+        let x0_live = semantics::live_out::RegisterSet::from_registers(vec![Register::X0]);
+        // The budget covers the `mov x0, #1` candidate, which the unstrict
+        // run finds, without enumerating the whole pool under --strict.
+        let mut options = options_for(Algorithm::Enumerative);
+        options.timeout = Some(Duration::from_secs(10));
+        options.solver_timeout = Duration::from_secs(10);
+        options.max_candidates = Some(2_000);
+
+        let narrowed = run_optimization(&target, &options, false, Some(x0_live.clone()), None)
+            .unwrap()
+            .expect("the dead x1 write should be dropped");
+        assert_eq!(
+            narrowed,
+            vec![Instruction::MovImm {
+                rd: Register::X0,
+                imm: 1,
+            }]
+        );
+
+        options.strict = true;
+        assert_eq!(
+            run_optimization(&target, &options, false, Some(x0_live), None).unwrap(),
+            None
+        );
+        assert!(strict_live_out().contains(Register::X1));
+        assert!(strict_live_out().flags_live());
+        assert_eq!(x86_strict_live_out(32).len(), 8);
+        assert_eq!(x86_strict_live_out(64).len(), 16);
+    }
+
     #[test]
     fn run_optimization_uses_downstream_flags_dead_context() {
         let target = [
@@ -6243,7 +9509,7 @@ mod cli_helper_tests {
         options.timeout = Some(Duration::from_secs(10));
         options.solver_timeout = Duration::from_secs(10);
 
-        let flags_dead = run_optimization(&target, &options, false, None)
+        let flags_dead = run_optimization(&target, &options, false, None, None)
             .expect("symbolic search should run with flags dead")
             .expect("flags-dead window should drop redundant cmp");
         assert_eq!(flags_dead.len(), 1);
@@ -6259,13 +9525,22 @@ mod cli_helper_tests {
         let asm1 = TempFile::new("s11-equiv-a", "s", "mov x0, x1\n");
         let asm2 = TempFile::new("s11-equiv-b", "s", "mov x0, x1\n");
         assert_eq!(
-            run_equiv(asm1.path(), asm2.path(), "x0", 1, true, true).unwrap(),
+            run_equiv(
+                asm1.path(),
+                asm2.path(),
+                "x0",
+                1,
+                true,
+                OutputFormat::Text,
+                true
+            )
+            .unwrap(),
             0,
             "equivalent sequences must map to exit code 0"
         );
 
         let llm_asm = TempFile::new("s11-llm", "s", "mov x0, x1\n");
-        run_llm_opt(llm_asm.path(), "x0", 0, "test-model", 0, true).unwrap();
+        run_llm_opt(llm_asm.path(), "x0", 0, "test-model", 0, true, false).unwrap();
     }
 
     // ===== `equiv` report builder (extracted seam) =====
@@ -6326,6 +9601,60 @@ mod cli_helper_tests {
         );
     }
 
+    #[test]
+    fn explanation_names_live_out_tests_and_verdict() {
+        let original = vec![
+            Instruction::MovReg {
+                rd: Register::X0,
+                rn: Register::X1,
+            },
+            Instruction::Add {
+                rd: Register::X0,
+                rn: Register::X0,
+                rm: Operand::Immediate(1),
+            },
+        ];
+        let fused = vec![Instruction::Add {
+            rd: Register::X0,
+            rn: Register::X1,
+            rm: Operand::Immediate(1),
+        }];
+        let config = semantics::EquivalenceConfig::with_live_out(LiveOut::from_registers(vec![
+            Register::X0,
+        ]));
+
+        let lines = explanation_lines(&original, &fused, &config);
+        assert_eq!(lines[0], "Explanation:");
+        assert_eq!(lines[1], "  Live-out: x0");
+        assert!(
+            lines[2].starts_with("  Concrete tests passed: 10 random, "),
+            "{lines:?}"
+        );
+        assert_eq!(lines[3], "  Verdict: SMT-proven equivalent");
+        assert_eq!(lines.len(), 4);
+
+        let off_by_one = vec![Instruction::Add {
+            rd: Register::X0,
+            rn: Register::X1,
+            rm: Operand::Immediate(2),
+        }];
+        let lines = explanation_lines(&original, &off_by_one, &config);
+        assert_eq!(lines[2], "  Concrete tests: failed");
+        assert_eq!(lines[3], "  Verdict: NOT equivalent");
+        assert_eq!(lines[4], "  Counterexample input:");
+        assert!(lines.iter().any(|line| line == "  Output from optimized:"));
+    }
+
+    #[test]
+    fn describe_live_out_lists_registers_then_flags() {
+        let live = LiveOut::from_registers(vec![Register::X2, Register::X0]).with_flags(true);
+        assert_eq!(describe_live_out(&live), "x0, x2, nzcv");
+        assert_eq!(
+            describe_live_out(&LiveOut::from_registers(vec![])),
+            "(nothing)"
+        );
+    }
+
     #[test]
     fn equiv_report_counterexample_reruns_sequences_and_formats_live_registers() {
         // seq1 computes x0 = x1 + 1; seq2 computes x0 = x1 + 2. With x1 = 5 in
@@ -6349,7 +9678,10 @@ mod cli_helper_tests {
         let live_out = LiveOut::from_registers(vec![Register::X0, Register::X1]);
 
         let report = build_equiv_report(
-            &semantics::EquivalenceResult::NotEquivalentFast(input),
+            &semantics::EquivalenceResult::NotEquivalentFast(semantics::FastCounterexample {
+                input,
+                first_difference: Some(Register::X0),
+            }),
             &seq1,
             &seq2,
             &live_out,
@@ -6374,6 +9706,39 @@ mod cli_helper_tests {
         );
     }
 
+    #[test]
+    fn equiv_json_carries_verdict_reason_and_counterexample() {
+        let unknown = build_equiv_json(
+            &semantics::EquivalenceResult::Unknown("solver timeout".to_string()),
+            2,
+            &LiveOut::from_registers(vec![]),
+        );
+        assert_eq!(
+            unknown,
+            serde_json::json!({
+                "verdict": "unknown",
+                "exit_code": 2,
+                "reason": "solver timeout",
+            })
+        );
+
+        let mut input = semantics::ConcreteMachineState::new_zeroed();
+        input.set_register(Register::X1, semantics::ConcreteValue::new(5));
+        let counterexample = build_equiv_json(
+            &semantics::EquivalenceResult::NotEquivalentFast(semantics::FastCounterexample {
+                input,
+                first_difference: Some(Register::X0),
+            }),
+            1,
+            &LiveOut::from_registers(vec![Register::X1]),
+        );
+        assert_eq!(counterexample["verdict"], "not-equivalent");
+        assert_eq!(
+            counterexample["counterexample"]["input"]["x1"],
+            "0x0000000000000005"
+        );
+    }
+
     // ===== Issue #69: validate_basic_block =====
 
     #[test]
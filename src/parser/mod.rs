@@ -13,6 +13,7 @@
 //! semantics work.
 
 use std::fmt;
+use std::io::Read;
 use std::path::Path;
 
 use crate::ir::instructions::MOVW_LEGAL_SHIFTS;
@@ -85,8 +86,22 @@ pub enum LineResult {
     Instruction(Instruction),
     /// Line was empty, a comment, label, or directive (skip it)
     Skip,
+    /// A real AArch64 instruction the IR does not model, named by its
+    /// lowercased mnemonic; see [`UNSUPPORTED_MNEMONICS`]. Unlike
+    /// [`ParseLineError::UnknownInstruction`] this is not a typo, but the
+    /// line still cannot be optimized across.
+    Unsupported(String),
 }
 
+/// Mnemonics `parse_line` recognizes as AArch64 but reports as
+/// [`LineResult::Unsupported`]: indirect calls, PC-relative address
+/// generation, exclusive and acquire/release memory, barriers, system
+/// instructions and unscaled loads/stores. Sorted for `binary_search`.
+pub const UNSUPPORTED_MNEMONICS: &[&str] = &[
+    "adr", "adrp", "blr", "brk", "dmb", "dsb", "isb", "ldar", "ldaxr", "ldur", "ldxr", "mrs",
+    "msr", "nop", "stlr", "stlxr", "stur", "stxr", "svc",
+];
+
 /// Structured failure mode for `parse_line`. Distinguishes "the parser doesn't
 /// recognise this opcode" from any other parse failure (operand parsing,
 /// encoding-range violations, etc.) so consumers can act on the unknown
@@ -249,7 +264,9 @@ fn parse_sized_operand(mnem: &str, operand: &str, width: RegisterWidth) -> Resul
     Ok(Operand::Register(reg))
 }
 
-/// Parse an immediate value (with or without # prefix, hex or decimal)
+/// Parse an immediate value (with or without # prefix; decimal, `0x` hex or
+/// `0b` binary). `_` digit separators are accepted between digits in every
+/// radix (`0x1000_0000`), but not before the first or after the last digit.
 pub fn parse_immediate(s: &str) -> Result<i64, String> {
     let s = s.trim();
     let s = s.strip_prefix('#').unwrap_or(s);
@@ -259,25 +276,64 @@ pub fn parse_immediate(s: &str) -> Result<i64, String> {
         return Err("empty immediate value".to_string());
     }
 
-    // Handle hex. Positive hex parses as u64 then reinterprets as i64 so that
-    // high-bit logical-immediate masks (e.g., 0x8000_0000_0000_0000) round-trip
-    // from Capstone text into the IR — i64::from_str_radix would reject them as
-    // overflow.
-    if let Some(hex) = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
-        u64::from_str_radix(hex, 16)
-            .map(|v| v as i64)
-            .map_err(|e| format!("invalid hex immediate '{}': {}", s, e))
-    } else if let Some(hex) = s.strip_prefix("-0x").or_else(|| s.strip_prefix("-0X")) {
-        i64::from_str_radix(hex, 16)
-            .map(|v| -v)
-            .map_err(|e| format!("invalid hex immediate '{}': {}", s, e))
+    let (negative, unsigned) = match s.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, s),
+    };
+    let (radix, kind, digits) = if let Some(hex) = strip_radix_prefix(unsigned, 'x') {
+        (16, "hex ", hex)
+    } else if let Some(bin) = strip_radix_prefix(unsigned, 'b') {
+        (2, "binary ", bin)
     } else {
-        // Decimal
-        s.parse::<i64>()
-            .map_err(|e| format!("invalid immediate '{}': {}", s, e))
+        (10, "", unsigned)
+    };
+    if digits.starts_with('_') || digits.ends_with('_') {
+        return Err(format!(
+            "invalid {}immediate '{}': misplaced '_' separator",
+            kind, s
+        ));
+    }
+    let digits = digits.replace('_', "");
+
+    if radix == 10 {
+        // Decimal keeps i64 parsing (including its own sign handling).
+        let decimal = if negative {
+            format!("-{}", digits)
+        } else {
+            digits
+        };
+        return decimal
+            .parse::<i64>()
+            .map_err(|e| format!("invalid immediate '{}': {}", s, e));
+    }
+
+    // Hex/binary magnitudes parse as u64. A positive one reinterprets as i64
+    // so that high-bit logical-immediate masks (e.g., 0x8000_0000_0000_0000)
+    // round-trip from Capstone text into the IR — i64::from_str_radix would
+    // reject them as overflow. A negative one is negated with checked
+    // arithmetic, so -0x8000_0000_0000_0000 is i64::MIN and anything larger
+    // is rejected.
+    let magnitude = u64::from_str_radix(&digits, radix)
+        .map_err(|e| format!("invalid {}immediate '{}': {}", kind, s, e))?;
+    if negative {
+        0i64.checked_sub_unsigned(magnitude).ok_or_else(|| {
+            format!(
+                "invalid {}immediate '{}': number too small to fit in target type",
+                kind, s
+            )
+        })
+    } else {
+        Ok(magnitude as i64)
     }
 }
 
+/// Strip a `0<letter>` radix prefix (either case) from `s`.
+fn strip_radix_prefix(s: &str, letter: char) -> Option<&str> {
+    let rest = s.strip_prefix('0')?;
+    rest.strip_prefix(letter)
+        .or_else(|| rest.strip_prefix(letter.to_ascii_uppercase()))
+}
+
 /// Parse an operand (register or immediate)
 pub fn parse_operand(s: &str) -> Result<Operand, String> {
     let s = s.trim();
@@ -323,15 +379,34 @@ pub fn parse_condition(s: &str) -> Result<Condition, String> {
     }
 }
 
+/// How `;` is read on an assembly line.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CommentStyle {
+    /// `;` starts a comment, like `//` and `@` (the default).
+    #[default]
+    SemicolonComment,
+    /// `;` separates instructions, as in `mov x0, #1; add x0, x0, #2`;
+    /// `//` and `@` still start comments.
+    SemicolonSeparator,
+}
+
 /// Strip comments from a line (handles //, ;, and @)
 fn strip_comments(line: &str) -> &str {
+    strip_comments_with_style(line, CommentStyle::SemicolonComment)
+}
+
+/// Strip comments from a line; `;` is a marker only under
+/// [`CommentStyle::SemicolonComment`].
+fn strip_comments_with_style(line: &str, style: CommentStyle) -> &str {
     // Find the first comment marker
     let mut end = line.len();
 
     if let Some(pos) = line.find("//") {
         end = end.min(pos);
     }
-    if let Some(pos) = line.find(';') {
+    if style == CommentStyle::SemicolonComment
+        && let Some(pos) = line.find(';')
+    {
         end = end.min(pos);
     }
     if let Some(pos) = line.find('@') {
@@ -521,9 +596,10 @@ where
 ///   semantics on AArch64), so X-spelling for rd is also accepted.
 /// - `SXTB Xd, Wn` / `SXTH Xd, Wn` / `SXTW Xd, Wn` — X-dest, W-src. There is
 ///   also a 32-bit `SXTB Wd, Wn` architectural form which writes only Wd and
-///   zeroes the upper half of Xd — that is *not* what the IR models, so
-///   `sxtb w0, w1` is rejected by this parser to avoid silent semantic
-///   erasure (codex P1 on #144).
+///   zeroes the upper half of Xd — that is *not* what the `Sxtb`/`Sxth` IR
+///   models, so this helper rejects a W-form rd to avoid silent semantic
+///   erasure (codex P1 on #144). `parse_line` routes the W-form SXTB/SXTH to
+///   [`parse_w_sign_extend`] first.
 ///
 /// The IR stores everything as 64-bit X-registers; the semantics layer masks
 /// to the architectural width. The W-form acceptance is scoped to this
@@ -570,6 +646,37 @@ where
     Ok(build(rd, rn))
 }
 
+/// Parse the 32-bit `SXTB Wd, Wn` / `SXTH Wd, Wn`: SBFM aliases that the IR
+/// spells as a W-form `sbfx` of the low `width` bits. `None` when rd is not
+/// W-form, leaving the X-dest spelling to [`parse_unary_extend`].
+fn parse_w_sign_extend(
+    mnemonic: &str,
+    operands: &[&str],
+    width: u8,
+) -> Option<Result<Instruction, String>> {
+    let (_, RegisterWidth::W32) = parse_sized_register(operands.first()?).ok()? else {
+        return None;
+    };
+    if operands.len() != 2 {
+        return Some(Err(format!(
+            "{} requires 2 operands, got {}",
+            mnemonic,
+            operands.len()
+        )));
+    }
+    Some(
+        parse_same_width_registers(mnemonic, operands).map(|(rd, rn, reg_width)| {
+            Instruction::Sbfx {
+                rd,
+                rn,
+                lsb: 0,
+                width,
+                reg_width,
+            }
+        }),
+    )
+}
+
 /// Parse NEG instruction
 fn parse_neg(operands: &[&str]) -> Result<Instruction, String> {
     if operands.len() != 2 {
@@ -1303,6 +1410,39 @@ fn parse_pair_mem(
     }
 }
 
+/// `#imm, lsl #12`: the ADD/SUB-family spelling of a shifted 12-bit immediate,
+/// which is how capstone prints `add x0, x1, #0x1000`. Folds to the plain
+/// value, so the IR holds one form. `None` unless `mnem` is in that family
+/// and `operand` is an immediate.
+fn parse_lsl12_immediate(mnem: &str, operand: &str, tail: &str) -> Option<Result<Operand, String>> {
+    if !matches!(mnem, "add" | "sub" | "adds" | "subs" | "cmp" | "cmn")
+        || !operand.trim().starts_with('#')
+    {
+        return None;
+    }
+    let parse = || {
+        let imm = parse_immediate(operand)?;
+        let mut parts = tail.trim().splitn(2, char::is_whitespace);
+        let kw = parts.next().unwrap_or("");
+        let amount = parse_immediate(parts.next().unwrap_or(""))?;
+        if !kw.eq_ignore_ascii_case("lsl") || !matches!(amount, 0 | 12) {
+            return Err(format!(
+                "{} immediate shift must be `lsl #0` or `lsl #12`, got `{}`",
+                mnem,
+                tail.trim()
+            ));
+        }
+        if !(0..=0xFFF).contains(&imm) {
+            return Err(format!(
+                "{} shifted immediate {} out of range (0..=4095)",
+                mnem, imm
+            ));
+        }
+        Ok(Operand::Immediate(imm << amount))
+    };
+    Some(parse())
+}
+
 /// Parse the rm slot for the 3-operand arith/logical instructions
 /// (Add/Sub/And/Orr/Eor). Returns the register/immediate form, a
 /// shifted-register operand, or an extended-register operand based on the
@@ -1312,6 +1452,9 @@ fn parse_rm_3op(mnem: &str, operands: &[&str]) -> Result<Operand, String> {
         parse_operand(operands[2])
     } else if operands.len() == 4 {
         let tail = operands[3].trim();
+        if let Some(imm) = parse_lsl12_immediate(mnem, operands[2], tail) {
+            return imm;
+        }
         let kw = tail.split_whitespace().next().unwrap_or("");
         if is_extend_keyword(kw) {
             // Extended-register form: the inner register may be W-form for
@@ -1340,6 +1483,9 @@ fn parse_rm_3op_with_width(
         parse_sized_operand(mnem, operands[2], width)
     } else if operands.len() == 4 {
         let tail = operands[3].trim();
+        if let Some(imm) = parse_lsl12_immediate(mnem, operands[2], tail) {
+            return imm;
+        }
         let kw = tail.split_whitespace().next().unwrap_or("");
         if is_extend_keyword(kw) {
             let reg = parse_w_or_x_register(operands[2])?;
@@ -1487,6 +1633,57 @@ fn parse_asr(operands: &[&str]) -> Result<Instruction, String> {
     Ok(Instruction::Asr { rd, rn, shift })
 }
 
+/// Parse a 32-bit `LSL`/`LSR`/`ASR Wd, Wn, #amount`. These are UBFM/SBFM
+/// aliases, and the IR's `Lsl`/`Lsr`/`Asr` are 64-bit only, so they parse to
+/// the W-form `ubfiz`/`ubfx`/`sbfx` with the same encoding. `None` when rd is
+/// not W-form, leaving the X-form spelling to `parse_lsl` and friends.
+fn parse_w_immediate_shift(mnem: &str, operands: &[&str]) -> Option<Result<Instruction, String>> {
+    let (_, RegisterWidth::W32) = parse_sized_register(operands.first()?).ok()? else {
+        return None;
+    };
+    if operands.len() != 3 {
+        return Some(Err(format!(
+            "{} requires 3 operands, got {}",
+            mnem,
+            operands.len()
+        )));
+    }
+    Some(
+        parse_same_width_registers(mnem, operands).and_then(|(rd, rn, reg_width)| {
+            let amount = parse_immediate(operands[2])
+                .map_err(|_| format!("{} by a W register is not supported", mnem))?;
+            if !(0..32).contains(&amount) {
+                return Err(format!("{} amount {} out of range (0..=31)", mnem, amount));
+            }
+            let lsb = amount as u8;
+            let width = 32 - lsb;
+            Ok(match mnem {
+                "lsl" => Instruction::Ubfiz {
+                    rd,
+                    rn,
+                    lsb,
+                    width,
+                    reg_width,
+                },
+                "lsr" => Instruction::Ubfx {
+                    rd,
+                    rn,
+                    lsb,
+                    width,
+                    reg_width,
+                },
+                _ => Instruction::Sbfx {
+                    rd,
+                    rn,
+                    lsb,
+                    width,
+                    reg_width,
+                },
+            })
+        }),
+    )
+}
+
 /// Parse MUL instruction
 fn parse_mul(operands: &[&str]) -> Result<Instruction, String> {
     if operands.len() != 3 {
@@ -1495,7 +1692,7 @@ fn parse_mul(operands: &[&str]) -> Result<Instruction, String> {
 
     let rd = parse_register(operands[0])?;
     let rn = parse_register(operands[1])?;
-    let rm = parse_register(operands[2])?;
+    let rm = parse_register_only_rm("mul", operands)?;
 
     Ok(Instruction::Mul { rd, rn, rm })
 }
@@ -1575,7 +1772,7 @@ fn parse_sdiv(operands: &[&str]) -> Result<Instruction, String> {
 
     let rd = parse_register(operands[0])?;
     let rn = parse_register(operands[1])?;
-    let rm = parse_register(operands[2])?;
+    let rm = parse_register_only_rm("sdiv", operands)?;
 
     Ok(Instruction::Sdiv { rd, rn, rm })
 }
@@ -1588,11 +1785,42 @@ fn parse_udiv(operands: &[&str]) -> Result<Instruction, String> {
 
     let rd = parse_register(operands[0])?;
     let rn = parse_register(operands[1])?;
-    let rm = parse_register(operands[2])?;
+    let rm = parse_register_only_rm("udiv", operands)?;
 
     Ok(Instruction::Udiv { rd, rn, rm })
 }
 
+/// Parse the third operand of `mul`/`sdiv`/`udiv`, which AArch64 only has in
+/// register form. An immediate gets an error naming the rewrite instead of
+/// "unknown register": a shift for a power-of-two `mul`/`udiv`, otherwise a
+/// `mov` into a scratch register.
+fn parse_register_only_rm(mnemonic: &str, operands: &[&str]) -> Result<Register, String> {
+    let operand = operands[2].trim();
+    if !operand.starts_with('#') {
+        return parse_register(operand);
+    }
+    let imm = parse_immediate(operand)?;
+    let shift = match mnemonic {
+        "mul" => Some("lsl"),
+        "udiv" => Some("lsr"),
+        _ => None,
+    };
+    let suggestion = match shift {
+        Some(shift) if imm > 0 && (imm as u64).is_power_of_two() => format!(
+            "use `{} {}, {}, #{}` for a power of two",
+            shift,
+            operands[0].trim(),
+            operands[1].trim(),
+            imm.trailing_zeros()
+        ),
+        _ => format!("`mov` #{} into a scratch register first", imm),
+    };
+    Err(format!(
+        "{} requires register operands; {}",
+        mnemonic, suggestion
+    ))
+}
+
 /// Parse the rm slot for the 2-operand comparison instructions
 /// (Cmp/Cmn/Tst). Returns either the existing register/immediate form or a new
 /// shifted-register operand if a 3rd comma-separated token is present.
@@ -1601,6 +1829,9 @@ fn parse_rm_2op(mnem: &str, operands: &[&str]) -> Result<Operand, String> {
         parse_operand(operands[1])
     } else if operands.len() == 3 {
         let tail = operands[2].trim();
+        if let Some(imm) = parse_lsl12_immediate(mnem, operands[1], tail) {
+            return imm;
+        }
         let kw = tail.split_whitespace().next().unwrap_or("");
         if is_extend_keyword(kw) {
             // Extended-register form: the inner register may be W-form for
@@ -1998,9 +2229,15 @@ pub fn parse_line(line: &str) -> Result<LineResult, ParseLineError> {
         "and" => parse_and(&operands).map_err(ParseLineError::Other)?,
         "orr" => parse_orr(&operands).map_err(ParseLineError::Other)?,
         "eor" => parse_eor(&operands).map_err(ParseLineError::Other)?,
-        "lsl" => parse_lsl(&operands).map_err(ParseLineError::Other)?,
-        "lsr" => parse_lsr(&operands).map_err(ParseLineError::Other)?,
-        "asr" => parse_asr(&operands).map_err(ParseLineError::Other)?,
+        "lsl" => parse_w_immediate_shift("lsl", &operands)
+            .unwrap_or_else(|| parse_lsl(&operands))
+            .map_err(ParseLineError::Other)?,
+        "lsr" => parse_w_immediate_shift("lsr", &operands)
+            .unwrap_or_else(|| parse_lsr(&operands))
+            .map_err(ParseLineError::Other)?,
+        "asr" => parse_w_immediate_shift("asr", &operands)
+            .unwrap_or_else(|| parse_asr(&operands))
+            .map_err(ParseLineError::Other)?,
         "mul" => parse_mul(&operands).map_err(ParseLineError::Other)?,
         "madd" => parse_madd(&operands).map_err(ParseLineError::Other)?,
         "msub" => parse_msub(&operands).map_err(ParseLineError::Other)?,
@@ -2116,18 +2353,24 @@ pub fn parse_line(line: &str) -> Result<LineResult, ParseLineError> {
             rn,
         })
         .map_err(ParseLineError::Other)?,
-        // SXTB/SXTH/SXTW: X-dest only. The 32-bit `Wd` form is a distinct
-        // architectural instruction that this IR does not model.
-        "sxtb" => parse_unary_extend("sxtb", &operands, false, |rd, rn| Instruction::Sxtb {
-            rd,
-            rn,
-        })
-        .map_err(ParseLineError::Other)?,
-        "sxth" => parse_unary_extend("sxth", &operands, false, |rd, rn| Instruction::Sxth {
-            rd,
-            rn,
-        })
-        .map_err(ParseLineError::Other)?,
+        // SXTB/SXTH: the X-dest form is its own IR instruction; the 32-bit
+        // `Wd` form is a W-form SBFX. SXTW has no `Wd` form.
+        "sxtb" => parse_w_sign_extend("sxtb", &operands, 8)
+            .unwrap_or_else(|| {
+                parse_unary_extend("sxtb", &operands, false, |rd, rn| Instruction::Sxtb {
+                    rd,
+                    rn,
+                })
+            })
+            .map_err(ParseLineError::Other)?,
+        "sxth" => parse_w_sign_extend("sxth", &operands, 16)
+            .unwrap_or_else(|| {
+                parse_unary_extend("sxth", &operands, false, |rd, rn| Instruction::Sxth {
+                    rd,
+                    rn,
+                })
+            })
+            .map_err(ParseLineError::Other)?,
         "sxtw" => parse_unary_extend("sxtw", &operands, false, |rd, rn| Instruction::Sxtw {
             rd,
             rn,
@@ -2204,6 +2447,9 @@ pub fn parse_line(line: &str) -> Result<LineResult, ParseLineError> {
                 .map_err(|_| ParseLineError::UnknownInstruction(opcode.clone()))?;
             parse_b_cond(cond, &operands).map_err(ParseLineError::Other)?
         }
+        op if UNSUPPORTED_MNEMONICS.binary_search(&op).is_ok() => {
+            return Ok(LineResult::Unsupported(opcode));
+        }
         _ => return Err(ParseLineError::UnknownInstruction(opcode)),
     };
 
@@ -2218,6 +2464,57 @@ pub fn parse_line(line: &str) -> Result<LineResult, ParseLineError> {
     Ok(LineResult::Instruction(instruction))
 }
 
+/// Parse a line under `style`, one result per instruction on it.
+///
+/// Under [`CommentStyle::SemicolonSeparator`] the line is split on `;` and
+/// each piece goes through [`parse_line`]; under the default style this is
+/// [`parse_line`] itself, so anything after a `;` is dropped. Skipped pieces
+/// (empty, labels, directives) are left out, so a blank or comment-only
+/// line yields an empty vector.
+pub fn parse_line_with_style(
+    line: &str,
+    style: CommentStyle,
+) -> Result<Vec<LineResult>, ParseLineError> {
+    let mut results = Vec::new();
+    for piece in strip_comments_with_style(line, style).split(';') {
+        match parse_line(piece)? {
+            LineResult::Skip => {}
+            result => results.push(result),
+        }
+    }
+    Ok(results)
+}
+
+/// Source name reported for assembly read from standard input.
+pub const STDIN_SOURCE: &str = "<stdin>";
+
+/// Parse assembly from `path`, or from standard input when `path` is `-`,
+/// so the CLI can sit at the end of a pipe (`cc -S foo.c -o - | s11 ...`).
+pub fn parse_assembly_path(path: &Path) -> Result<Vec<Instruction>, ParseError> {
+    if path == Path::new("-") {
+        parse_assembly_reader(std::io::stdin().lock(), STDIN_SOURCE)
+    } else {
+        parse_assembly_file(path)
+    }
+}
+
+/// Parse everything `reader` yields; errors name `source_name`.
+pub fn parse_assembly_reader(
+    mut reader: impl Read,
+    source_name: &str,
+) -> Result<Vec<Instruction>, ParseError> {
+    let mut content = String::new();
+    reader.read_to_string(&mut content).map_err(|e| {
+        ParseError::new(
+            0,
+            format!("failed to read {}: {}", source_name, e),
+            source_name,
+        )
+    })?;
+
+    parse_assembly_string(&content, source_name.to_string())
+}
+
 /// Parse an assembly file into a sequence of instructions
 pub fn parse_assembly_file(path: &Path) -> Result<Vec<Instruction>, ParseError> {
     let content = std::fs::read_to_string(path).map_err(|e| {
@@ -2235,21 +2532,41 @@ pub fn parse_assembly_file(path: &Path) -> Result<Vec<Instruction>, ParseError>
 pub fn parse_assembly_string(
     content: &str,
     source_name: String,
+) -> Result<Vec<Instruction>, ParseError> {
+    parse_assembly_string_with_style(content, source_name, CommentStyle::default())
+}
+
+/// [`parse_assembly_string`] reading `;` according to `style`.
+pub fn parse_assembly_string_with_style(
+    content: &str,
+    source_name: String,
+    style: CommentStyle,
 ) -> Result<Vec<Instruction>, ParseError> {
     let mut instructions = Vec::new();
 
     for (line_num, line) in content.lines().enumerate() {
         let line_number = line_num + 1; // 1-indexed
 
-        match parse_line(line) {
-            Ok(LineResult::Instruction(instr)) => {
-                instructions.push(instr);
-            }
-            Ok(LineResult::Skip) => {
-                // Nothing to do
-            }
-            Err(err) => {
-                return Err(ParseError::new(line_number, err.to_string(), line));
+        let results = parse_line_with_style(line, style)
+            .map_err(|err| ParseError::new(line_number, err.to_string(), line))?;
+        for result in results {
+            match result {
+                LineResult::Instruction(instr) => {
+                    instructions.push(instr);
+                }
+                LineResult::Skip => {
+                    // Nothing to do
+                }
+                // Dropping the line would splice the code on either side of it
+                // into one sequence, so an unmodelled instruction still stops
+                // the parse, just with a clearer message than a typo gets.
+                LineResult::Unsupported(mnemonic) => {
+                    return Err(ParseError::new(
+                        line_number,
+                        format!("unsupported instruction: {}", mnemonic),
+                        line,
+                    ));
+                }
             }
         }
     }
@@ -2257,7 +2574,7 @@ pub fn parse_assembly_string(
     if instructions.is_empty() {
         return Err(ParseError::new(
             1,
-            "no instructions found in file",
+            no_instructions_message(content),
             source_name,
         ));
     }
@@ -2265,6 +2582,43 @@ pub fn parse_assembly_string(
     Ok(instructions)
 }
 
+/// Diagnostic for a file that parsed without yielding an instruction: either
+/// it is empty (or whitespace only), or it names which kinds of
+/// non-executable lines it held, so a file of labels and directives is not
+/// mistaken for an empty one.
+fn no_instructions_message(content: &str) -> String {
+    if content.trim().is_empty() {
+        return "file is empty".to_string();
+    }
+
+    let (mut labels, mut directives, mut comments) = (false, false, false);
+    for line in content.lines() {
+        let code = strip_comments(line).trim();
+        comments |= code.len() < line.trim().len();
+        if code.is_empty() {
+            continue;
+        }
+        let rest = strip_leading_labels(code);
+        labels |= is_label(code) || rest.len() < code.len();
+        directives |= is_directive(rest);
+    }
+
+    let kinds: Vec<&str> = [
+        (labels, "labels"),
+        (directives, "directives"),
+        (comments, "comments"),
+    ]
+    .into_iter()
+    .filter_map(|(present, kind)| present.then_some(kind))
+    .collect();
+    let only = match kinds.as_slice() {
+        [] => String::new(),
+        [kind] => (*kind).to_string(),
+        [init @ .., last] => format!("{} and {}", init.join(", "), last),
+    };
+    format!("no executable instructions found in file (only {only})")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2534,6 +2888,49 @@ mod tests {
         assert!(parse_immediate("abc").is_err());
     }
 
+    #[test]
+    fn test_parse_immediate_binary_and_digit_separators() {
+        assert_eq!(parse_immediate("#0b1111").unwrap(), 15);
+        assert_eq!(parse_immediate("0B101").unwrap(), 5);
+        assert_eq!(parse_immediate("-0b10").unwrap(), -2);
+        assert_eq!(parse_immediate("0x1_00").unwrap(), 256);
+        assert_eq!(parse_immediate("#0x1000_0000").unwrap(), 0x1000_0000);
+        assert_eq!(parse_immediate("1_000").unwrap(), 1000);
+        assert_eq!(parse_immediate("0b1111_0000").unwrap(), 0xF0);
+        assert_eq!(
+            parse_immediate("0x8000_0000_0000_0000").unwrap(),
+            i64::MIN,
+            "high-bit masks still reinterpret as u64"
+        );
+        assert_eq!(parse_immediate("-0x8000000000000000").unwrap(), i64::MIN);
+        assert_eq!(
+            parse_immediate("-0x7fff_ffff_ffff_ffff").unwrap(),
+            -i64::MAX
+        );
+        assert_eq!(
+            parse_immediate(&format!("-0b1{}", "0".repeat(63))).unwrap(),
+            i64::MIN
+        );
+
+        for bad in [
+            "0b",
+            "#0b",
+            "0b2",
+            "0x",
+            "_1",
+            "1_",
+            "0x_10",
+            "0x10_",
+            "0b_1",
+            "-_1",
+            "-0x8000000000000001",
+            "-0xffff_ffff_ffff_ffff",
+            "--0x1",
+        ] {
+            assert!(parse_immediate(bad).is_err(), "{bad} should be rejected");
+        }
+    }
+
     // Operand parsing tests
     #[test]
     fn test_parse_operand() {
@@ -2767,6 +3164,57 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn semicolon_separates_instructions_only_in_separator_mode() {
+        let line = "mov x0, #1; add x0, x0, #2";
+        let instructions = |style| -> Vec<Instruction> {
+            parse_line_with_style(line, style)
+                .unwrap()
+                .into_iter()
+                .map(|result| match result {
+                    LineResult::Instruction(instr) => instr,
+                    other => panic!("expected an instruction, got {other:?}"),
+                })
+                .collect()
+        };
+        let mov = Instruction::MovImm {
+            rd: Register::X0,
+            imm: 1,
+        };
+
+        assert_eq!(
+            instructions(CommentStyle::SemicolonSeparator),
+            vec![
+                mov,
+                Instruction::Add {
+                    rd: Register::X0,
+                    rn: Register::X0,
+                    rm: Operand::Immediate(2),
+                },
+            ]
+        );
+        assert_eq!(instructions(CommentStyle::SemicolonComment), vec![mov]);
+        assert!(
+            parse_line_with_style("; only a comment", CommentStyle::SemicolonComment)
+                .unwrap()
+                .is_empty()
+        );
+        let labelled = parse_line_with_style(
+            "label: ; mov x1, x2 // trailing",
+            CommentStyle::SemicolonSeparator,
+        )
+        .unwrap();
+        assert_eq!(labelled.len(), 1);
+
+        let program = parse_assembly_string_with_style(
+            "mov x0, #1; add x0, x0, #2\nret\n",
+            "t".into(),
+            CommentStyle::SemicolonSeparator,
+        )
+        .unwrap();
+        assert_eq!(program.len(), 3);
+    }
+
     #[test]
     fn test_parse_line_with_comment() {
         match parse_line("add x0, x1, #1 // increment").unwrap() {
@@ -2779,13 +3227,46 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parses_add_sub_immediates_shifted_by_12() {
+        let parsed = |line: &str| match parse_line(line) {
+            Ok(LineResult::Instruction(instr)) => instr,
+            other => panic!("`{line}` should parse, got {other:?}"),
+        };
+        let add = Instruction::Add {
+            rd: Register::X0,
+            rn: Register::X1,
+            rm: Operand::Immediate(0x1000),
+        };
+        assert_eq!(parsed("add x0, x1, #0x1000"), add);
+        assert_eq!(parsed("add x0, x1, #1, lsl #12"), add);
+        assert_eq!(
+            parsed("cmp x1, #3, lsl #12"),
+            Instruction::Cmp {
+                rn: Register::X1,
+                rm: Operand::Immediate(0x3000),
+            }
+        );
+        assert_eq!(
+            parsed("subs x0, x1, #2, lsl #0"),
+            Instruction::Subs {
+                rd: Register::X0,
+                rn: Register::X1,
+                rm: Operand::Immediate(2),
+            }
+        );
+        assert!(parse_line("add x0, x1, #1, lsl #8").is_err());
+        assert!(parse_line("add x0, x1, #0x1000, lsl #12").is_err());
+        assert!(parse_line("and x0, x1, #1, lsl #12").is_err());
+    }
+
     #[test]
     fn test_parse_line_encoding_validation() {
         // Valid ADD immediate
         assert!(parse_line("add x0, x1, #4095").is_ok());
 
-        // Invalid ADD immediate (out of range)
-        assert!(parse_line("add x0, x1, #4096").is_err());
+        // Invalid ADD immediate (out of range even under lsl #12)
+        assert!(parse_line("add x0, x1, #4097").is_err());
 
         // AND with a non-bitmask immediate (e.g., #5 = 0b101) is rejected by
         // the encodability check. Valid bitmask values (e.g., #1) are accepted.
@@ -2803,6 +3284,33 @@ mod tests {
         assert!(parse_line("tst x1, #0x8000000000000000").is_ok());
     }
 
+    #[test]
+    fn parse_line_suggests_a_rewrite_for_immediate_mul_and_div() {
+        for (line, expected) in [
+            (
+                "mul x0, x1, #4",
+                "mul requires register operands; use `lsl x0, x1, #2` for a power of two",
+            ),
+            (
+                "udiv x0, x1, #8",
+                "udiv requires register operands; use `lsr x0, x1, #3` for a power of two",
+            ),
+            (
+                "mul x0, x1, #3",
+                "mul requires register operands; `mov` #3 into a scratch register first",
+            ),
+            (
+                "sdiv x0, x1, #4",
+                "sdiv requires register operands; `mov` #4 into a scratch register first",
+            ),
+        ] {
+            match parse_line(line) {
+                Err(ParseLineError::Other(msg)) => assert_eq!(msg, expected, "{line}"),
+                other => panic!("expected a rewrite suggestion for {line}, got {other:?}"),
+            }
+        }
+    }
+
     #[test]
     fn parse_line_rejects_sp_in_multiply_family() {
         // The IR-level test `test_is_encodable_multiply_family_rejects_sp_all_slots`
@@ -2968,16 +3476,57 @@ mod tests {
     fn test_parse_assembly_string_empty() {
         let empty_err = parse_assembly_string("", "test".to_string()).unwrap_err();
         assert_eq!(empty_err.line_number, 1);
-        assert_eq!(empty_err.message, "no instructions found in file");
+        assert_eq!(empty_err.message, "file is empty");
         assert_eq!(empty_err.line_content, "test");
 
+        let blank_err = parse_assembly_string("  \n\t\n", "test".to_string()).unwrap_err();
+        assert_eq!(blank_err.message, "file is empty");
+
         let skipped_err =
             parse_assembly_string("// just a comment\n.text\n", "test".to_string()).unwrap_err();
         assert_eq!(skipped_err.line_number, 1);
-        assert_eq!(skipped_err.message, "no instructions found in file");
+        assert_eq!(
+            skipped_err.message,
+            "no executable instructions found in file (only directives and comments)"
+        );
         assert_eq!(skipped_err.line_content, "test");
     }
 
+    #[test]
+    fn instruction_free_files_name_what_they_contain() {
+        let message = |content: &str| {
+            parse_assembly_string(content, "test".to_string())
+                .unwrap_err()
+                .message
+        };
+        assert_eq!(
+            message("// header\n; more\n@ and more\n"),
+            "no executable instructions found in file (only comments)"
+        );
+        assert_eq!(
+            message(".text\n.globl f\n"),
+            "no executable instructions found in file (only directives)"
+        );
+        assert_eq!(
+            message("f:\n  .cfi_startproc // prologue\n"),
+            "no executable instructions found in file (only labels, directives and comments)"
+        );
+    }
+
+    #[test]
+    fn parse_assembly_reader_names_the_source_in_errors() {
+        let instructions =
+            parse_assembly_reader("mov x0, x1\nadd x0, x0, #1\n".as_bytes(), STDIN_SOURCE).unwrap();
+        assert_eq!(instructions.len(), 2);
+
+        let err = parse_assembly_reader(".text\n".as_bytes(), STDIN_SOURCE).unwrap_err();
+        assert_eq!(
+            err.message,
+            "no executable instructions found in file (only directives)"
+        );
+        assert_eq!(err.line_content, "<stdin>");
+    }
+
     /// Round-trip Display → parser for every Tier 1 mnemonic.
     #[test]
     fn test_tier1_display_parser_roundtrip() {
@@ -3093,6 +3642,72 @@ mod tests {
         }
     }
 
+    /// `parse_line(&instr.to_string())` must give back `instr` for every
+    /// encodable instruction the generators can emit, so printed output
+    /// (e.g. `--emit-asm`) can be fed back in. The one sanctioned
+    /// difference is an alias whose Display is the preferred disassembly
+    /// (`movz x0, #1` prints as `mov x0, #1` and re-parses as `MovImm`):
+    /// the re-parsed form must then print and assemble identically.
+    #[test]
+    fn display_round_trips_through_parser_for_generated_instructions() {
+        use crate::assembler::AArch64Assembler;
+        use crate::ir::VectorRegister;
+        use crate::search::candidate::{
+            generate_all_encodable_instructions, generate_random_instruction,
+        };
+        use crate::test_utils::instruction_fixtures::aarch64_instruction_families;
+        use rand::SeedableRng;
+
+        let registers = [
+            Register::X0,
+            Register::X1,
+            Register::XZR,
+            Register::SP,
+            Register::Vector(VectorRegister::V0),
+            Register::Vector(VectorRegister::V1),
+        ];
+        let immediates = [0, 1, 5, 63, 255, 4095, -1, 0x5555_5555_5555_5555];
+
+        let mut instructions = generate_all_encodable_instructions(&registers, &immediates);
+        instructions.extend(
+            aarch64_instruction_families()
+                .into_iter()
+                .map(|family| family.instruction),
+        );
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(603);
+        for _ in 0..20_000 {
+            instructions.push(generate_random_instruction(
+                &mut rng,
+                &registers,
+                &immediates,
+            ));
+        }
+
+        let mut assembler = AArch64Assembler::new();
+        for instr in instructions
+            .into_iter()
+            .filter(Instruction::is_encodable_aarch64)
+        {
+            let printed = instr.to_string();
+            let parsed = match parse_line(&printed) {
+                Ok(LineResult::Instruction(parsed)) => parsed,
+                other => panic!("`{}` ({:?}) does not re-parse: {:?}", printed, instr, other),
+            };
+            if parsed == instr {
+                continue;
+            }
+            assert_eq!(parsed.to_string(), printed, "alias of {:?}", instr);
+            assert_eq!(
+                assembler.assemble_instructions(&[parsed], 0),
+                assembler.assemble_instructions(&[instr], 0),
+                "`{}` re-parsed as {:?}, which encodes differently from {:?}",
+                printed,
+                parsed,
+                instr
+            );
+        }
+    }
+
     #[test]
     fn parse_all_aarch64_register_names() {
         for idx in 0..=30 {
@@ -3191,7 +3806,7 @@ mod tests {
     fn parse_ubfx_roundtrip() {
         let parsed = match parse_line("ubfx x0, x1, #5, #10").unwrap() {
             LineResult::Instruction(instr) => instr,
-            LineResult::Skip => panic!("unexpected skip"),
+            other => panic!("expected an instruction, got {other:?}"),
         };
         assert_eq!(
             parsed,
@@ -3214,7 +3829,7 @@ mod tests {
             let text = format!("{mnem} w0, w1, #5, #10");
             let parsed = match parse_line(&text).unwrap() {
                 LineResult::Instruction(instr) => instr,
-                LineResult::Skip => panic!("unexpected skip"),
+                other => panic!("expected an instruction, got {other:?}"),
             };
             assert_eq!(
                 parsed.destination(),
@@ -3229,7 +3844,7 @@ mod tests {
     fn parse_bitfield_w_builds_w32_variant() {
         let parsed = match parse_line("ubfx w0, w1, #5, #10").unwrap() {
             LineResult::Instruction(instr) => instr,
-            LineResult::Skip => panic!("unexpected skip"),
+            other => panic!("expected an instruction, got {other:?}"),
         };
         assert_eq!(
             parsed,
@@ -3263,7 +3878,7 @@ mod tests {
     fn parse_sbfx_roundtrip() {
         let parsed = match parse_line("sbfx x0, x1, #5, #10").unwrap() {
             LineResult::Instruction(instr) => instr,
-            LineResult::Skip => panic!("unexpected skip"),
+            other => panic!("expected an instruction, got {other:?}"),
         };
         assert_eq!(
             parsed,
@@ -3282,7 +3897,7 @@ mod tests {
     fn parse_bfi_roundtrip() {
         let parsed = match parse_line("bfi x0, x1, #5, #10").unwrap() {
             LineResult::Instruction(instr) => instr,
-            LineResult::Skip => panic!("unexpected skip"),
+            other => panic!("expected an instruction, got {other:?}"),
         };
         assert_eq!(
             parsed,
@@ -3301,7 +3916,7 @@ mod tests {
     fn parse_bfxil_roundtrip() {
         let parsed = match parse_line("bfxil x0, x1, #5, #10").unwrap() {
             LineResult::Instruction(instr) => instr,
-            LineResult::Skip => panic!("unexpected skip"),
+            other => panic!("expected an instruction, got {other:?}"),
         };
         assert_eq!(
             parsed,
@@ -3320,7 +3935,7 @@ mod tests {
     fn parse_ubfiz_roundtrip() {
         let parsed = match parse_line("ubfiz x0, x1, #5, #10").unwrap() {
             LineResult::Instruction(instr) => instr,
-            LineResult::Skip => panic!("unexpected skip"),
+            other => panic!("expected an instruction, got {other:?}"),
         };
         assert_eq!(
             parsed,
@@ -3339,7 +3954,7 @@ mod tests {
     fn parse_sbfiz_roundtrip() {
         let parsed = match parse_line("sbfiz x0, x1, #5, #10").unwrap() {
             LineResult::Instruction(instr) => instr,
-            LineResult::Skip => panic!("unexpected skip"),
+            other => panic!("expected an instruction, got {other:?}"),
         };
         assert_eq!(
             parsed,
@@ -3373,6 +3988,50 @@ mod tests {
         assert!(result.is_err(), "lsb + width > 64 must be rejected");
     }
 
+    #[test]
+    fn parse_line_separates_unsupported_mnemonics_from_typos() {
+        assert!(matches!(
+            parse_line("blr x1"),
+            Ok(LineResult::Unsupported(m)) if m == "blr"
+        ));
+        assert!(matches!(
+            parse_line("  ADRP x0, table"),
+            Ok(LineResult::Unsupported(m)) if m == "adrp"
+        ));
+        assert_eq!(
+            parse_line("xyz x0, x1").unwrap_err(),
+            ParseLineError::UnknownInstruction("xyz".to_string())
+        );
+        // Terminators the IR models still parse as instructions.
+        assert!(matches!(
+            parse_line("ret"),
+            Ok(LineResult::Instruction(Instruction::Ret { .. }))
+        ));
+    }
+
+    #[test]
+    fn unsupported_mnemonics_are_sorted_and_unparsed() {
+        assert!(UNSUPPORTED_MNEMONICS.is_sorted());
+        for mnemonic in UNSUPPORTED_MNEMONICS {
+            assert!(
+                matches!(parse_line(mnemonic), Ok(LineResult::Unsupported(_))),
+                "{mnemonic} should be reported as unsupported"
+            );
+        }
+    }
+
+    #[test]
+    fn parse_assembly_string_names_unsupported_instructions() {
+        let err =
+            parse_assembly_string("add x0, x0, #1\nblr x2\n", "call.s".to_string()).unwrap_err();
+        assert_eq!(err.line_number, 2);
+        assert!(
+            err.message.contains("unsupported instruction: blr"),
+            "{}",
+            err.message
+        );
+    }
+
     #[test]
     fn parse_line_covers_all_core_mnemonics() {
         let cases = [
@@ -3417,7 +4076,7 @@ mod tests {
         for (line, display) in cases {
             let parsed = match parse_line(line).unwrap() {
                 LineResult::Instruction(instr) => instr,
-                LineResult::Skip => panic!("unexpected skip for {}", line),
+                other => panic!("unexpected {other:?} for {}", line),
             };
             assert_eq!(format!("{}", parsed), display);
         }
@@ -3469,7 +4128,7 @@ mod tests {
         for (line, display) in cases {
             let parsed = match parse_line(line).unwrap() {
                 LineResult::Instruction(instr) => instr,
-                LineResult::Skip => panic!("unexpected skip for {}", line),
+                other => panic!("unexpected {other:?} for {}", line),
             };
             assert_eq!(format!("{}", parsed), display);
         }
@@ -3479,7 +4138,7 @@ mod tests {
     fn parse_movz_shift0_display_normalizes_to_mov_alias() {
         let parsed = match parse_line("movz x0, #0x1234").unwrap() {
             LineResult::Instruction(instr) => instr,
-            LineResult::Skip => panic!("unexpected skip for movz"),
+            other => panic!("unexpected {other:?} for movz"),
         };
         assert_eq!(
             parsed,
@@ -3495,7 +4154,7 @@ mod tests {
 
         let reparsed = match parse_line(&printed).unwrap() {
             LineResult::Instruction(instr) => instr,
-            LineResult::Skip => panic!("unexpected skip for {}", printed),
+            other => panic!("unexpected {other:?} for {}", printed),
         };
         assert_eq!(
             reparsed,
@@ -3553,7 +4212,7 @@ mod tests {
         // ExtendedRegister rm. The inner register parses as w2 (alias of x2).
         let parsed = match parse_line("add x0, x1, w2, uxtb #2").unwrap() {
             LineResult::Instruction(instr) => instr,
-            LineResult::Skip => panic!("unexpected skip"),
+            other => panic!("expected an instruction, got {other:?}"),
         };
         assert_eq!(
             parsed,
@@ -3574,7 +4233,7 @@ mod tests {
         use crate::ir::ExtendKind;
         let parsed = match parse_line("cmp x1, w2, sxth #1").unwrap() {
             LineResult::Instruction(instr) => instr,
-            LineResult::Skip => panic!("unexpected skip"),
+            other => panic!("expected an instruction, got {other:?}"),
         };
         assert_eq!(
             parsed,
@@ -3714,7 +4373,7 @@ mod tests {
         for (line, expected) in cases {
             let parsed = match parse_line(line).unwrap_or_else(|e| panic!("{}: {:?}", line, e)) {
                 LineResult::Instruction(instr) => instr,
-                LineResult::Skip => panic!("unexpected skip for {}", line),
+                other => panic!("unexpected {other:?} for {}", line),
             };
             assert_eq!(parsed, expected, "round-trip failed for {}", line);
         }
@@ -3773,7 +4432,7 @@ mod tests {
                 .unwrap_or_else(|e| panic!("{expected_text}: {e}"))
             {
                 LineResult::Instruction(instr) => instr,
-                LineResult::Skip => panic!("unexpected skip for {expected_text}"),
+                other => panic!("unexpected {other:?} for {expected_text}"),
             };
             assert_eq!(parsed, expected, "parse mismatch for {expected_text}");
             assert_eq!(parsed.to_string(), expected_text);
@@ -3781,19 +4440,26 @@ mod tests {
     }
 
     #[test]
-    fn parse_sxt_rejects_w_destination() {
+    fn parse_sxt_keeps_the_w_destination_width() {
         // Issue #60 follow-up (Codex P1 on the rebased branch): `sxtb w0, w1`
         // is the 32-bit-Wd-write form architecturally — distinct from the
-        // X-dest SXTB the IR models. Accepting it would silently erase the
-        // width and let the optimizer emit Xd-write bytes against W-dest
-        // input. The parser must refuse.
-        for line in ["sxtb w0, w1", "sxth w0, w1", "sxtw w0, w1"] {
-            assert!(
-                parse_line(line).is_err(),
-                "{} should reject W-form destination",
-                line
+        // X-dest SXTB. It must not parse to `Sxtb`, which would silently
+        // erase the width; it is the W-form SBFX of the low byte/half.
+        for (line, width) in [("sxtb w0, w1", 8), ("sxth w0, w1", 16)] {
+            assert_eq!(
+                parse_one(line),
+                Instruction::Sbfx {
+                    rd: Register::X0,
+                    rn: Register::X1,
+                    lsb: 0,
+                    width,
+                    reg_width: RegisterWidth::W32,
+                },
+                "{line}"
             );
         }
+        // There is no 32-bit SXTW.
+        assert!(parse_line("sxtw w0, w1").is_err());
         // The X-form destination is the correct spelling for the IR's model.
         assert!(parse_line("sxtb x0, w1").is_ok());
         assert!(parse_line("sxth x0, w1").is_ok());
@@ -3804,6 +4470,48 @@ mod tests {
         assert!(parse_line("uxtb x0, w1").is_ok());
     }
 
+    #[test]
+    fn parse_w_form_immediate_shifts_as_bitfield_moves() {
+        let w32 = RegisterWidth::W32;
+        for (line, expected) in [
+            (
+                "lsl w1, w2, #16",
+                Instruction::Ubfiz {
+                    rd: Register::X1,
+                    rn: Register::X2,
+                    lsb: 16,
+                    width: 16,
+                    reg_width: w32,
+                },
+            ),
+            (
+                "lsr w0, w0, #0",
+                Instruction::Ubfx {
+                    rd: Register::X0,
+                    rn: Register::X0,
+                    lsb: 0,
+                    width: 32,
+                    reg_width: w32,
+                },
+            ),
+            (
+                "asr w3, w4, #31",
+                Instruction::Sbfx {
+                    rd: Register::X3,
+                    rn: Register::X4,
+                    lsb: 31,
+                    width: 1,
+                    reg_width: w32,
+                },
+            ),
+        ] {
+            assert_eq!(parse_one(line), expected, "{line}");
+        }
+        assert!(parse_line("lsl w0, w1, #32").is_err());
+        assert!(parse_line("lsl w0, w1, w2").is_err());
+        assert!(parse_line("lsl w0, x1, #1").is_err());
+    }
+
     #[test]
     fn parse_extended_register_rejects_oversized_shift() {
         // Shift > 4 must be rejected at the parser (the encodability gate
@@ -3817,7 +4525,7 @@ mod tests {
     fn parse_sxtw_standalone() {
         let parsed = match parse_line("sxtw x0, x1").unwrap() {
             LineResult::Instruction(instr) => instr,
-            LineResult::Skip => panic!("unexpected skip"),
+            other => panic!("expected an instruction, got {other:?}"),
         };
         assert_eq!(
             parsed,
@@ -3833,7 +4541,7 @@ mod tests {
     fn parse_sxth_standalone() {
         let parsed = match parse_line("sxth x0, x1").unwrap() {
             LineResult::Instruction(instr) => instr,
-            LineResult::Skip => panic!("unexpected skip"),
+            other => panic!("expected an instruction, got {other:?}"),
         };
         assert_eq!(
             parsed,
@@ -3849,7 +4557,7 @@ mod tests {
     fn parse_uxth_standalone() {
         let parsed = match parse_line("uxth x0, x1").unwrap() {
             LineResult::Instruction(instr) => instr,
-            LineResult::Skip => panic!("unexpected skip"),
+            other => panic!("expected an instruction, got {other:?}"),
         };
         assert_eq!(
             parsed,
@@ -3865,7 +4573,7 @@ mod tests {
     fn parse_sxtb_standalone() {
         let parsed = match parse_line("sxtb x0, x1").unwrap() {
             LineResult::Instruction(instr) => instr,
-            LineResult::Skip => panic!("unexpected skip"),
+            other => panic!("expected an instruction, got {other:?}"),
         };
         assert_eq!(
             parsed,
@@ -3882,7 +4590,7 @@ mod tests {
         // Issue #60: the standalone UXTB mnemonic produces Instruction::Uxtb.
         let parsed = match parse_line("uxtb x0, x1").unwrap() {
             LineResult::Instruction(instr) => instr,
-            LineResult::Skip => panic!("unexpected skip"),
+            other => panic!("expected an instruction, got {other:?}"),
         };
         assert_eq!(
             parsed,
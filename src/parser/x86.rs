@@ -1330,6 +1330,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::manual_split_once)]
     fn test_mnemonic_parses_reg_and_imm_forms_and_round_trips_display() {
         // `test rax, rbx` and `test rax, 5` parse to TestReg/TestImm, and the
         // Display output round-trips back through the parser to the same IR.
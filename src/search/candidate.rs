@@ -1,8 +1,18 @@
 //! Instruction generation utilities for search algorithms
 
-use crate::ir::instructions::{AARCH64_RANDOM_SHIFT_IMMEDIATES, MOVW_LEGAL_SHIFTS};
-use crate::ir::{Instruction, Operand, Register, RegisterWidth, ShiftKind, VectorArrangement};
+use crate::ir::instructions::{
+    AARCH64_RANDOM_SHIFT_IMMEDIATES, AARCH64_RANDOM_SHIFT_IMMEDIATES_WITH_ZERO, MOVW_LEGAL_SHIFTS,
+    movn_chunk,
+};
+use crate::ir::types::Condition;
+use crate::ir::{
+    Instruction, Operand, OperandSlot, Register, RegisterWidth, ShiftKind, VectorArrangement,
+};
+use crate::isa::aarch64::normalized_immediate_pool;
 use crate::isa::{AArch64, Assembler, InstructionType};
+use crate::search::config::SearchConfig;
+use smallvec::SmallVec;
+use std::collections::HashSet;
 
 /// Generic encodability check: for any `<I: InstructionType, A: Assembler<I>>`,
 /// returns true iff every instruction passes `A::can_assemble`.
@@ -39,6 +49,110 @@ pub fn generate_all_encodable_instructions(
         .collect()
 }
 
+/// The AArch64 candidate pool a search under `config` draws from: the
+/// encodable forms up to `config.max_immediate_bits`, or every modelled form
+/// when `config.allow_unencodable` is set, collapsed by
+/// [`dedup_by_semantic_key`], plus the shifts by zero when
+/// `config.include_trivial_shifts` is set.
+pub fn generate_candidates_for_config(
+    config: &SearchConfig,
+    registers: &[Register],
+    immediates: &[i64],
+) -> Vec<Instruction> {
+    let mut instrs = dedup_by_semantic_key(if config.allow_unencodable {
+        generate_all_instructions(registers, immediates)
+    } else {
+        generate_all_encodable_instructions_with_max_immediate_bits(
+            registers,
+            immediates,
+            config.max_immediate_bits,
+        )
+    });
+    if config.include_trivial_shifts {
+        instrs.extend(generate_trivial_shifts(registers));
+    }
+    instrs
+}
+
+/// Keep the first instruction of each [`Instruction::semantic_key`], so
+/// idiom variants (`eor x0, x1, x1` next to `mov x0, #0`) are not each
+/// evaluated and verified. Order is otherwise preserved.
+pub fn dedup_by_semantic_key(instrs: Vec<Instruction>) -> Vec<Instruction> {
+    let mut seen = HashSet::new();
+    instrs
+        .into_iter()
+        .filter(|instr| seen.insert(instr.semantic_key()))
+        .collect()
+}
+
+/// `lsl`/`lsr`/`asr`/`ror rd, rn, #0` over the scalar registers: each is a
+/// plain `mov rd, rn`, so `generate_all_instructions` leaves them out.
+pub fn generate_trivial_shifts(registers: &[Register]) -> Vec<Instruction> {
+    let scalar = || {
+        registers
+            .iter()
+            .copied()
+            .filter(|reg| reg.vector().is_none())
+    };
+    let zero = Operand::Immediate(0);
+    let mut instrs = Vec::new();
+    for rd in scalar() {
+        for rn in scalar() {
+            instrs.push(Instruction::Lsl {
+                rd,
+                rn,
+                shift: zero,
+            });
+            instrs.push(Instruction::Lsr {
+                rd,
+                rn,
+                shift: zero,
+            });
+            instrs.push(Instruction::Asr {
+                rd,
+                rn,
+                shift: zero,
+            });
+            instrs.push(Instruction::Ror {
+                rd,
+                rn,
+                shift: zero,
+            });
+        }
+    }
+    instrs
+}
+
+/// `generate_all_encodable_instructions` without the wide `MovImm` forms
+/// whose immediate does not fit in `max_immediate_bits` (see
+/// [`fits_immediate_bits`]).
+pub fn generate_all_encodable_instructions_with_max_immediate_bits(
+    registers: &[Register],
+    immediates: &[i64],
+    max_immediate_bits: u32,
+) -> Vec<Instruction> {
+    generate_all_encodable_instructions(registers, immediates)
+        .into_iter()
+        .filter(|instr| fits_immediate_bits(instr, max_immediate_bits))
+        .collect()
+}
+
+/// Whether `instr` respects `SearchConfig::max_immediate_bits`. A `MovImm`
+/// that a single MOVZ or MOVN materializes always does; a wider one, which
+/// assembles to a MOVZ/MOVK chain, must be a non-negative value that fits in
+/// `max_immediate_bits`. Every other instruction does.
+pub fn fits_immediate_bits(instr: &Instruction, max_immediate_bits: u32) -> bool {
+    match *instr {
+        Instruction::MovImm { imm, .. } => {
+            (0..=0xFFFF).contains(&imm)
+                || movn_chunk(imm).is_some()
+                || (imm >= 0
+                    && (max_immediate_bits >= 64 || (imm as u64) >> max_immediate_bits == 0))
+        }
+        _ => true,
+    }
+}
+
 /// Generate all possible instructions using the given registers and immediates
 /// Curated shift amounts enumerated for shifted-register operands (issue #59).
 /// 0 is intentionally excluded: `<op> rd, rn, rm, lsl #0` is identical to the
@@ -241,8 +355,10 @@ pub fn generate_all_instructions(registers: &[Register], immediates: &[i64]) ->
                 }
             }
 
-            // Shift operations with immediate shift amount (0-63 is valid, but we use small values)
-            for shift in [0i64, 1, 2, 4, 8, 16, 32] {
+            // Shift operations with immediate shift amount (0-63 is valid, but
+            // we use small values). 0 is left out: it is `mov rd, rn`; see
+            // `generate_trivial_shifts`.
+            for shift in [1i64, 2, 4, 8, 16, 32] {
                 let shift_op = Operand::Immediate(shift);
                 instrs.push(Instruction::Lsl {
                     rd,
@@ -751,11 +867,38 @@ pub fn generate_all_instructions(registers: &[Register], immediates: &[i64]) ->
     instrs
 }
 
+/// The immediate amounts random LSL/LSR/ASR/ROR draw from: the nonzero
+/// representatives, plus `#0` when `include_trivial_shifts` is set (see
+/// [`generate_candidates_for_config`]).
+pub fn random_shift_immediates(include_trivial_shifts: bool) -> &'static [i64] {
+    if include_trivial_shifts {
+        &AARCH64_RANDOM_SHIFT_IMMEDIATES_WITH_ZERO
+    } else {
+        &AARCH64_RANDOM_SHIFT_IMMEDIATES
+    }
+}
+
 /// Generate a random instruction using the given registers and immediates
 pub fn generate_random_instruction<R: rand::RngExt>(
     rng: &mut R,
     registers: &[Register],
     immediates: &[i64],
+) -> Instruction {
+    generate_random_instruction_with_shifts(
+        rng,
+        registers,
+        immediates,
+        &AARCH64_RANDOM_SHIFT_IMMEDIATES,
+    )
+}
+
+/// [`generate_random_instruction`] with immediate shift amounts drawn from
+/// `shift_immediates` (see [`random_shift_immediates`]).
+pub fn generate_random_instruction_with_shifts<R: rand::RngExt>(
+    rng: &mut R,
+    registers: &[Register],
+    immediates: &[i64],
+    shift_immediates: &[i64],
 ) -> Instruction {
     let scalar_registers: Vec<_> = registers
         .iter()
@@ -812,9 +955,8 @@ pub fn generate_random_instruction<R: rand::RngExt>(
     let rd = registers[rng.random_range(0..registers.len())];
     let pick_reg = |rng: &mut R| registers[rng.random_range(0..registers.len())];
 
-    // See also `src/isa/aarch64.rs::AArch64InstructionGenerator::generate_random`:
-    // this is a parallel 48-slot sampler, but its slot numbers differ
-    // (notably, ROR is slot 37 there and slot 23 here).
+    // This is the only AArch64 random sampler:
+    // `AArch64InstructionGenerator::generate_random` delegates here.
     match rng.random_range(0..48) {
         0 => {
             let imm = if immediates.is_empty() {
@@ -824,10 +966,7 @@ pub fn generate_random_instruction<R: rand::RngExt>(
             };
             Instruction::MovImm { rd, imm }
         }
-        1 => Instruction::MovReg {
-            rd,
-            rn: pick_reg(rng),
-        },
+        1 => with_random_slots(rng, MOV_REG_TEMPLATE, registers, rd),
         2 => {
             let rn = pick_reg(rng);
             let rm = random_arith_rm_operand(rng, registers, immediates);
@@ -846,90 +985,31 @@ pub fn generate_random_instruction<R: rand::RngExt>(
         // iterations. Wiring a curated bitmask-immediate table for these
         // opcodes is left to a follow-up; for now stochastic search keeps
         // emitting register-only AND/ORR/EOR candidates.
-        4 => {
-            let rn = pick_reg(rng);
-            let rm = Operand::Register(pick_reg(rng));
-            Instruction::And {
-                rd,
-                rn,
-                rm,
-                width: RegisterWidth::X64,
-            }
-        }
-        5 => {
-            let rn = pick_reg(rng);
-            let rm = Operand::Register(pick_reg(rng));
-            Instruction::Orr {
-                rd,
-                rn,
-                rm,
-                width: RegisterWidth::X64,
-            }
-        }
-        6 => {
-            let rn = pick_reg(rng);
-            let rm = Operand::Register(pick_reg(rng));
-            Instruction::Eor {
-                rd,
-                rn,
-                rm,
-                width: RegisterWidth::X64,
-            }
-        }
+        n @ 4..=6 => with_random_slots(rng, LOGICAL_REGISTER_TEMPLATES[n - 4], registers, rd),
         7 => {
             let rn = pick_reg(rng);
-            let shift = random_shift_operand(rng, registers);
+            let shift = random_shift_operand(rng, registers, shift_immediates);
             Instruction::Lsl { rd, rn, shift }
         }
         8 => {
             let rn = pick_reg(rng);
-            let shift = random_shift_operand(rng, registers);
+            let shift = random_shift_operand(rng, registers, shift_immediates);
             Instruction::Lsr { rd, rn, shift }
         }
         9 => {
             let rn = pick_reg(rng);
-            let shift = random_shift_operand(rng, registers);
+            let shift = random_shift_operand(rng, registers, shift_immediates);
             Instruction::Asr { rd, rn, shift }
         }
         // New: unary / inverted-logical / flag-setting / cond-set / ror
-        10 => Instruction::Mvn {
-            rd,
-            rm: pick_reg(rng),
-        },
-        11 => Instruction::Neg {
-            rd,
-            rm: pick_reg(rng),
-        },
-        12 => Instruction::Negs {
-            rd,
-            rm: pick_reg(rng),
-        },
+        n @ 10..=12 => with_random_slots(rng, UNARY_TEMPLATES[n - 10], registers, rd),
         13 => {
             let imm = (rng.random::<u32>() & 0xFFFF) as u16;
             let shifts = MOVW_LEGAL_SHIFTS;
             let shift = shifts[rng.random_range(0..shifts.len())];
             Instruction::MovN { rd, imm, shift }
         }
-        14 => {
-            let rn = pick_reg(rng);
-            let rm = Operand::Register(pick_reg(rng));
-            Instruction::Bic { rd, rn, rm }
-        }
-        15 => {
-            let rn = pick_reg(rng);
-            let rm = Operand::Register(pick_reg(rng));
-            Instruction::Bics { rd, rn, rm }
-        }
-        16 => {
-            let rn = pick_reg(rng);
-            let rm = Operand::Register(pick_reg(rng));
-            Instruction::Orn { rd, rn, rm }
-        }
-        17 => {
-            let rn = pick_reg(rng);
-            let rm = Operand::Register(pick_reg(rng));
-            Instruction::Eon { rd, rn, rm }
-        }
+        n @ 14..=17 => with_random_slots(rng, INVERTED_LOGICAL_TEMPLATES[n - 14], registers, rd),
         18 => {
             let rn = pick_reg(rng);
             let rm = random_arith_rm_operand(rng, registers, immediates);
@@ -940,27 +1020,11 @@ pub fn generate_random_instruction<R: rand::RngExt>(
             let rm = random_arith_rm_operand(rng, registers, immediates);
             Instruction::Subs { rd, rn, rm }
         }
-        20 => {
-            let rn = pick_reg(rng);
-            let rm = Operand::Register(pick_reg(rng));
-            Instruction::Ands {
-                rd,
-                rn,
-                rm,
-                width: RegisterWidth::X64,
-            }
-        }
-        21 => Instruction::Cset {
-            rd,
-            cond: crate::ir::types::Condition::random_normal(rng),
-        },
-        22 => Instruction::Csetm {
-            rd,
-            cond: crate::ir::types::Condition::random_normal(rng),
-        },
+        20 => with_random_slots(rng, ANDS_REGISTER_TEMPLATE, registers, rd),
+        n @ 21..=22 => with_random_slots(rng, COND_SET_TEMPLATES[n - 21], registers, rd),
         23 => {
             let rn = pick_reg(rng);
-            let shift = random_shift_operand(rng, registers);
+            let shift = random_shift_operand(rng, registers, shift_immediates);
             Instruction::Ror { rd, rn, shift }
         }
         24 => {
@@ -977,30 +1041,7 @@ pub fn generate_random_instruction<R: rand::RngExt>(
         }
         // Single-source bit-manipulation opcodes each keep a top-level slot
         // so stochastic search does not starve CLZ/RBIT/REV-shaped targets.
-        26 => Instruction::Clz {
-            rd,
-            rn: pick_reg(rng),
-        },
-        27 => Instruction::Cls {
-            rd,
-            rn: pick_reg(rng),
-        },
-        28 => Instruction::Rbit {
-            rd,
-            rn: pick_reg(rng),
-        },
-        29 => Instruction::Rev {
-            rd,
-            rn: pick_reg(rng),
-        },
-        30 => Instruction::Rev32 {
-            rd,
-            rn: pick_reg(rng),
-        },
-        31 => Instruction::Rev16 {
-            rd,
-            rn: pick_reg(rng),
-        },
+        n @ 26..=31 => with_random_slots(rng, SINGLE_SOURCE_TEMPLATES[n - 26], registers, rd),
         // CCMP: conditional compare. CCMP/CCMN forbid SP in `rn` and in
         // the register form of `rm`; the helper mirrors the enumeration filter
         // and falls back finitely on SP-only pools.
@@ -1011,16 +1052,11 @@ pub fn generate_random_instruction<R: rand::RngExt>(
         33 => random_bitfield_instruction(rng, registers, rd, 0),
         // MADD representative slot; the rest of the multiply-accumulate
         // variants are appended as top-level slots below.
-        34 => random_madd_like_instruction(rng, registers, rd, 0),
+        34 => with_random_slots(rng, MULTIPLY_TEMPLATES[0], registers, rd),
         // Issue #66 multiply / divide: MUL/SDIV/UDIV. All register-only.
         35 => {
-            let rn = pick_reg(rng);
-            let rm = pick_reg(rng);
-            match rng.random_range(0..3) {
-                0 => Instruction::Mul { rd, rn, rm },
-                1 => Instruction::Sdiv { rd, rn, rm },
-                _ => Instruction::Udiv { rd, rn, rm },
-            }
+            let template = MUL_DIV_TEMPLATES[rng.random_range(0..MUL_DIV_TEMPLATES.len())];
+            with_random_slots(rng, template, registers, rd)
         }
         // Issue #66 compares/tests. CMP/CMN sample register, clamped
         // immediate, and arithmetic shifted-register forms. TST samples
@@ -1031,15 +1067,8 @@ pub fn generate_random_instruction<R: rand::RngExt>(
         // condition sampled from NORMAL_CONDITIONS (AL/NV excluded — AL
         // collapses to MOV rd,rn and NV is reserved).
         37 => {
-            let rn = pick_reg(rng);
-            let rm = pick_reg(rng);
-            let cond = crate::ir::types::Condition::random_normal(rng);
-            match rng.random_range(0..4) {
-                0 => Instruction::Csel { rd, rn, rm, cond },
-                1 => Instruction::Csinc { rd, rn, rm, cond },
-                2 => Instruction::Csinv { rd, rn, rm, cond },
-                _ => Instruction::Csneg { rd, rn, rm, cond },
-            }
+            let template = COND_SELECT_TEMPLATES[rng.random_range(0..COND_SELECT_TEMPLATES.len())];
+            with_random_slots(rng, template, registers, rd)
         }
         38 => random_cond_compare_instruction(rng, registers, immediates, rd, true),
         39 => random_bitfield_instruction(rng, registers, rd, 1),
@@ -1047,14 +1076,226 @@ pub fn generate_random_instruction<R: rand::RngExt>(
         41 => random_bitfield_instruction(rng, registers, rd, 3),
         42 => random_bitfield_instruction(rng, registers, rd, 4),
         43 => random_bitfield_instruction(rng, registers, rd, 5),
-        44 => random_madd_like_instruction(rng, registers, rd, 1),
-        45 => random_madd_like_instruction(rng, registers, rd, 2),
-        46 => random_madd_like_instruction(rng, registers, rd, 3),
-        47 => random_madd_like_instruction(rng, registers, rd, 4),
+        n @ 44..=47 => with_random_slots(rng, MULTIPLY_TEMPLATES[n - 43], registers, rd),
         _ => unreachable!(),
     }
 }
 
+/// `template` with `rd` in its first destination slot, a register from
+/// `registers` in every other register slot (a register-form `rm` included)
+/// and a normal condition in its condition slot. The templates below fix
+/// everything else; their `X0` registers and `EQ` conditions are placeholders.
+fn with_random_slots<R: rand::RngExt>(
+    rng: &mut R,
+    template: Instruction,
+    registers: &[Register],
+    rd: Register,
+) -> Instruction {
+    let mut rd = Some(rd);
+    let slots: SmallVec<[OperandSlot; 4]> = template
+        .operands()
+        .into_iter()
+        .map(|slot| match slot {
+            OperandSlot::Dest(_) => {
+                OperandSlot::Dest(rd.take().unwrap_or_else(|| pick_register(rng, registers)))
+            }
+            OperandSlot::Src(_) => OperandSlot::Src(pick_register(rng, registers)),
+            OperandSlot::Operand(Operand::Register(_)) => {
+                OperandSlot::Operand(Operand::Register(pick_register(rng, registers)))
+            }
+            OperandSlot::Cond(_) => OperandSlot::Cond(Condition::random_normal(rng)),
+            fixed => fixed,
+        })
+        .collect();
+    template
+        .with_operands(&slots)
+        .expect("random slots keep the template's slot kinds")
+}
+
+const MOV_REG_TEMPLATE: Instruction = Instruction::MovReg {
+    rd: Register::X0,
+    rn: Register::X0,
+};
+
+const ANDS_REGISTER_TEMPLATE: Instruction = Instruction::Ands {
+    rd: Register::X0,
+    rn: Register::X0,
+    rm: Operand::Register(Register::X0),
+    width: RegisterWidth::X64,
+};
+
+const LOGICAL_REGISTER_TEMPLATES: [Instruction; 3] = [
+    Instruction::And {
+        rd: Register::X0,
+        rn: Register::X0,
+        rm: Operand::Register(Register::X0),
+        width: RegisterWidth::X64,
+    },
+    Instruction::Orr {
+        rd: Register::X0,
+        rn: Register::X0,
+        rm: Operand::Register(Register::X0),
+        width: RegisterWidth::X64,
+    },
+    Instruction::Eor {
+        rd: Register::X0,
+        rn: Register::X0,
+        rm: Operand::Register(Register::X0),
+        width: RegisterWidth::X64,
+    },
+];
+
+const UNARY_TEMPLATES: [Instruction; 3] = [
+    Instruction::Mvn {
+        rd: Register::X0,
+        rm: Register::X0,
+    },
+    Instruction::Neg {
+        rd: Register::X0,
+        rm: Register::X0,
+    },
+    Instruction::Negs {
+        rd: Register::X0,
+        rm: Register::X0,
+    },
+];
+
+const INVERTED_LOGICAL_TEMPLATES: [Instruction; 4] = [
+    Instruction::Bic {
+        rd: Register::X0,
+        rn: Register::X0,
+        rm: Operand::Register(Register::X0),
+    },
+    Instruction::Bics {
+        rd: Register::X0,
+        rn: Register::X0,
+        rm: Operand::Register(Register::X0),
+    },
+    Instruction::Orn {
+        rd: Register::X0,
+        rn: Register::X0,
+        rm: Operand::Register(Register::X0),
+    },
+    Instruction::Eon {
+        rd: Register::X0,
+        rn: Register::X0,
+        rm: Operand::Register(Register::X0),
+    },
+];
+
+const COND_SET_TEMPLATES: [Instruction; 2] = [
+    Instruction::Cset {
+        rd: Register::X0,
+        cond: Condition::EQ,
+    },
+    Instruction::Csetm {
+        rd: Register::X0,
+        cond: Condition::EQ,
+    },
+];
+
+const SINGLE_SOURCE_TEMPLATES: [Instruction; 6] = [
+    Instruction::Clz {
+        rd: Register::X0,
+        rn: Register::X0,
+    },
+    Instruction::Cls {
+        rd: Register::X0,
+        rn: Register::X0,
+    },
+    Instruction::Rbit {
+        rd: Register::X0,
+        rn: Register::X0,
+    },
+    Instruction::Rev {
+        rd: Register::X0,
+        rn: Register::X0,
+    },
+    Instruction::Rev32 {
+        rd: Register::X0,
+        rn: Register::X0,
+    },
+    Instruction::Rev16 {
+        rd: Register::X0,
+        rn: Register::X0,
+    },
+];
+
+const MUL_DIV_TEMPLATES: [Instruction; 3] = [
+    Instruction::Mul {
+        rd: Register::X0,
+        rn: Register::X0,
+        rm: Register::X0,
+    },
+    Instruction::Sdiv {
+        rd: Register::X0,
+        rn: Register::X0,
+        rm: Register::X0,
+    },
+    Instruction::Udiv {
+        rd: Register::X0,
+        rn: Register::X0,
+        rm: Register::X0,
+    },
+];
+
+const COND_SELECT_TEMPLATES: [Instruction; 4] = [
+    Instruction::Csel {
+        rd: Register::X0,
+        rn: Register::X0,
+        rm: Register::X0,
+        cond: Condition::EQ,
+    },
+    Instruction::Csinc {
+        rd: Register::X0,
+        rn: Register::X0,
+        rm: Register::X0,
+        cond: Condition::EQ,
+    },
+    Instruction::Csinv {
+        rd: Register::X0,
+        rn: Register::X0,
+        rm: Register::X0,
+        cond: Condition::EQ,
+    },
+    Instruction::Csneg {
+        rd: Register::X0,
+        rn: Register::X0,
+        rm: Register::X0,
+        cond: Condition::EQ,
+    },
+];
+
+const MULTIPLY_TEMPLATES: [Instruction; 5] = [
+    Instruction::Madd {
+        rd: Register::X0,
+        rn: Register::X0,
+        rm: Register::X0,
+        ra: Register::X0,
+    },
+    Instruction::Msub {
+        rd: Register::X0,
+        rn: Register::X0,
+        rm: Register::X0,
+        ra: Register::X0,
+    },
+    Instruction::Mneg {
+        rd: Register::X0,
+        rn: Register::X0,
+        rm: Register::X0,
+    },
+    Instruction::Smulh {
+        rd: Register::X0,
+        rn: Register::X0,
+        rm: Register::X0,
+    },
+    Instruction::Umulh {
+        rd: Register::X0,
+        rn: Register::X0,
+        rm: Register::X0,
+    },
+];
+
 fn random_cond_compare_instruction<R: rand::RngExt>(
     rng: &mut R,
     registers: &[Register],
@@ -1072,21 +1313,13 @@ fn random_cond_compare_instruction<R: rand::RngExt>(
     }
     let pick_non_sp = |rng: &mut R| non_sp[rng.random_range(0..non_sp.len())];
     let rn = pick_non_sp(rng);
-    let rm = match random_imm12_operand(rng, registers, immediates) {
-        Operand::Register(Register::SP) => Operand::Register(pick_non_sp(rng)),
-        Operand::Register(r) => Operand::Register(r),
-        Operand::Immediate(v) => Operand::Immediate(v.rem_euclid(32)),
-        // random_imm12_operand only returns Register/Immediate, but the compiler can't
-        // prove that. Drop shifted/extended forms to a plain register; CCMP and
-        // CCMN reject both forms.
-        Operand::ShiftedRegister { reg, .. } | Operand::ExtendedRegister { reg, .. }
-            if reg != Register::SP =>
-        {
-            Operand::Register(reg)
-        }
-        Operand::ShiftedRegister { .. } | Operand::ExtendedRegister { .. } => {
-            Operand::Register(pick_non_sp(rng))
-        }
+    // Immediates are drawn from the distinct imm5 residues so congruent
+    // table entries (0, 32, 64) do not outweigh the others.
+    let rm = if rng.random_bool(0.5) {
+        Operand::Register(pick_non_sp(rng))
+    } else {
+        let imm5_immediates = normalized_immediate_pool(immediates, 32);
+        Operand::Immediate(imm5_immediates[rng.random_range(0..imm5_immediates.len())])
     };
     let nzcv = (rng.random::<u32>() & 0x0F) as u8;
     let cond = crate::ir::types::Condition::random_normal(rng);
@@ -1170,30 +1403,6 @@ fn random_bitfield_instruction<R: rand::RngExt>(
     }
 }
 
-fn random_madd_like_instruction<R: rand::RngExt>(
-    rng: &mut R,
-    registers: &[Register],
-    rd: Register,
-    variant: u8,
-) -> Instruction {
-    let rn = pick_register(rng, registers);
-    let rm = pick_register(rng, registers);
-    match variant {
-        0 => {
-            let ra = pick_register(rng, registers);
-            Instruction::Madd { rd, rn, rm, ra }
-        }
-        1 => {
-            let ra = pick_register(rng, registers);
-            Instruction::Msub { rd, rn, rm, ra }
-        }
-        2 => Instruction::Mneg { rd, rn, rm },
-        3 => Instruction::Smulh { rd, rn, rm },
-        4 => Instruction::Umulh { rd, rn, rm },
-        _ => unreachable!(),
-    }
-}
-
 fn random_compare_or_test_instruction<R: rand::RngExt>(
     rng: &mut R,
     registers: &[Register],
@@ -1369,10 +1578,13 @@ fn random_arith_rm_operand<R: rand::RngExt>(
     }
 }
 
-fn random_shift_operand<R: rand::RngExt>(rng: &mut R, registers: &[Register]) -> Operand {
+fn random_shift_operand<R: rand::RngExt>(
+    rng: &mut R,
+    registers: &[Register],
+    shifts: &[i64],
+) -> Operand {
     if rng.random_bool(0.7) {
         // Prefer immediate shifts
-        let shifts = AARCH64_RANDOM_SHIFT_IMMEDIATES;
         Operand::Immediate(shifts[rng.random_range(0..shifts.len())])
     } else if !registers.is_empty() {
         Operand::Register(registers[rng.random_range(0..registers.len())])
@@ -1387,12 +1599,94 @@ pub fn generate_random_sequence<R: rand::RngExt>(
     length: usize,
     registers: &[Register],
     immediates: &[i64],
+) -> Vec<Instruction> {
+    generate_random_sequence_with_shifts(
+        rng,
+        length,
+        registers,
+        immediates,
+        &AARCH64_RANDOM_SHIFT_IMMEDIATES,
+    )
+}
+
+/// [`generate_random_sequence`] with immediate shift amounts drawn from
+/// `shift_immediates` (see [`random_shift_immediates`]).
+pub fn generate_random_sequence_with_shifts<R: rand::RngExt>(
+    rng: &mut R,
+    length: usize,
+    registers: &[Register],
+    immediates: &[i64],
+    shift_immediates: &[i64],
 ) -> Vec<Instruction> {
     (0..length)
-        .map(|_| generate_random_instruction(rng, registers, immediates))
+        .map(|_| {
+            generate_random_instruction_with_shifts(rng, registers, immediates, shift_immediates)
+        })
         .collect()
 }
 
+/// Lazy iterator over every length-`k` sequence drawn from a candidate pool.
+///
+/// Yields the `pool.len()^k` cartesian product in lexicographic order of pool
+/// indices while holding only a `k`-element index cursor, so callers can walk
+/// length-2+ search spaces without materialising the product. Length 0 yields
+/// the single empty sequence; an empty pool with `k > 0` yields nothing.
+#[derive(Debug, Clone)]
+pub struct CandidateIterator<'a, T = Instruction> {
+    pool: &'a [T],
+    cursor: Vec<usize>,
+    remaining: Option<usize>,
+    exhausted: bool,
+}
+
+impl<'a, T: Copy> CandidateIterator<'a, T> {
+    pub fn new(pool: &'a [T], length: usize) -> Self {
+        Self {
+            pool,
+            cursor: vec![0; length],
+            remaining: u32::try_from(length)
+                .ok()
+                .and_then(|length| pool.len().checked_pow(length)),
+            exhausted: length > 0 && pool.is_empty(),
+        }
+    }
+
+    /// Step the index cursor like an odometer; returns false once it wraps.
+    fn advance(&mut self) -> bool {
+        for slot in self.cursor.iter_mut().rev() {
+            *slot += 1;
+            if *slot < self.pool.len() {
+                return true;
+            }
+            *slot = 0;
+        }
+        false
+    }
+}
+
+impl<T: Copy> Iterator for CandidateIterator<'_, T> {
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.exhausted {
+            return None;
+        }
+        let sequence = self.cursor.iter().map(|&index| self.pool[index]).collect();
+        self.exhausted = !self.advance();
+        if let Some(remaining) = self.remaining.as_mut() {
+            *remaining = remaining.saturating_sub(1);
+        }
+        Some(sequence)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.remaining {
+            Some(remaining) => (remaining, Some(remaining)),
+            None => (usize::MAX, None),
+        }
+    }
+}
+
 /// Check if an instruction has immediate operand support
 #[allow(dead_code)]
 pub fn supports_immediate(instr: &Instruction) -> bool {
@@ -1464,6 +1758,7 @@ pub fn is_move_op(instr: &Instruction) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ir::SemanticKey;
     use crate::isa::InstructionGenerator;
     use crate::isa::aarch64::AArch64InstructionGenerator;
     use crate::test_utils::instruction_fixtures::aarch64_instruction_families;
@@ -1596,6 +1891,77 @@ mod tests {
         }
     }
 
+    #[test]
+    fn config_pool_collapses_zeroing_idioms() {
+        let registers = default_registers();
+        let immediates = default_immediates();
+        let full = generate_all_encodable_instructions(&registers, &immediates);
+        let eor_zero = Instruction::Eor {
+            rd: Register::X0,
+            rn: Register::X1,
+            rm: Operand::Register(Register::X1),
+            width: RegisterWidth::X64,
+        };
+        assert!(full.contains(&eor_zero));
+
+        let pool =
+            generate_candidates_for_config(&SearchConfig::default(), &registers, &immediates);
+        assert!(!pool.contains(&eor_zero));
+        assert!(pool.contains(&Instruction::MovImm {
+            rd: Register::X0,
+            imm: 0
+        }));
+        let zeroing_x0 = pool
+            .iter()
+            .filter(|instr| instr.semantic_key() == SemanticKey::Zero(Register::X0))
+            .count();
+        assert_eq!(zeroing_x0, 1);
+        assert!(pool.len() < full.len());
+    }
+
+    #[test]
+    fn shifts_by_zero_are_generated_only_on_request() {
+        let is_trivial_shift = |instr: &Instruction| {
+            matches!(
+                instr,
+                Instruction::Lsl {
+                    shift: Operand::Immediate(0),
+                    ..
+                } | Instruction::Lsr {
+                    shift: Operand::Immediate(0),
+                    ..
+                } | Instruction::Asr {
+                    shift: Operand::Immediate(0),
+                    ..
+                } | Instruction::Ror {
+                    shift: Operand::Immediate(0),
+                    ..
+                }
+            )
+        };
+        let registers = default_registers();
+        let immediates = default_immediates();
+
+        let config = SearchConfig::default();
+        let pool = generate_candidates_for_config(&config, &registers, &immediates);
+        assert!(!pool.iter().any(is_trivial_shift));
+        assert!(
+            !generate_all_instructions(&registers, &immediates)
+                .iter()
+                .any(is_trivial_shift)
+        );
+
+        let config = config.with_include_trivial_shifts(true);
+        let pool = generate_candidates_for_config(&config, &registers, &immediates);
+        let trivial = pool.iter().filter(|instr| is_trivial_shift(instr)).count();
+        assert_eq!(trivial, 4 * registers.len() * registers.len());
+        assert!(pool.contains(&Instruction::Lsl {
+            rd: Register::X0,
+            rn: Register::X1,
+            shift: Operand::Immediate(0),
+        }));
+    }
+
     #[test]
     fn test_generate_all_instructions_not_empty() {
         let instrs = generate_all_instructions(&default_registers(), &default_immediates());
@@ -1611,6 +1977,42 @@ mod tests {
         assert!(has_mov_imm);
     }
 
+    #[test]
+    fn max_immediate_bits_admits_two_word_mov_imm_costed_per_chunk() {
+        use crate::semantics::cost::{CostMetric, instruction_cost};
+
+        let registers = [Register::X0, Register::X1];
+        let immediates = [1, 0x1_2345];
+        let wide = Instruction::MovImm {
+            rd: Register::X0,
+            imm: 0x1_2345,
+        };
+
+        let default_pool = generate_all_encodable_instructions_with_max_immediate_bits(
+            &registers,
+            &immediates,
+            16,
+        );
+        assert!(!default_pool.contains(&wide));
+
+        let pool = generate_all_encodable_instructions_with_max_immediate_bits(
+            &registers,
+            &immediates,
+            32,
+        );
+        assert!(pool.contains(&wide));
+        assert_eq!(instruction_cost(&wide, &CostMetric::InstructionCount), 2);
+        assert_eq!(instruction_cost(&wide, &CostMetric::CodeSize), 8);
+
+        let too_wide =
+            generate_all_encodable_instructions_with_max_immediate_bits(&registers, &[1 << 40], 32);
+        assert!(
+            !too_wide
+                .iter()
+                .any(|i| matches!(i, Instruction::MovImm { imm, .. } if *imm == 1 << 40))
+        );
+    }
+
     #[test]
     fn test_generate_all_instructions_contains_add() {
         let instrs = generate_all_instructions(&default_registers(), &default_immediates());
@@ -2866,16 +3268,19 @@ mod tests {
     fn generate_random_instruction_keeps_ccmp_immediates_in_imm5_range() {
         let regs = [Register::X0, Register::X1, Register::X2];
         let imms = [0, 31, 32, 0xFFF, 0x1000, 1_000_000, -1];
-        let imm_count = imms.len() as u32;
+        // Immediates are drawn from the distinct imm5 residues of the table.
+        let residues = normalized_immediate_pool(&imms, 32);
+        assert_eq!(residues, vec![0, 31]);
+        let residue_count = residues.len() as u32;
 
-        for (imm_index, &raw_imm) in imms.iter().enumerate() {
+        for (residue_index, &residue) in residues.iter().enumerate() {
             let mut rng = BudgetedRng::new(vec![
                 word_for_range(3, 0),
                 word_for_range(48, 32),
                 word_for_range(3, 0),
                 u32::MAX,
                 u32::MAX,
-                word_for_range(imm_count, imm_index as u32),
+                word_for_range(residue_count, residue_index as u32),
             ]);
             let instr = generate_random_instruction(&mut rng, &regs, &imms);
             let imm = match instr {
@@ -2890,7 +3295,7 @@ mod tests {
                 other => panic!("expected immediate-form CCMP/CCMN, got {other:?}"),
             };
 
-            assert_eq!(imm, raw_imm.rem_euclid(32));
+            assert_eq!(imm, residue);
             assert!(
                 instr.is_encodable_aarch64(),
                 "random CCMP/CCMN instruction must be encodable: {instr}"
@@ -3310,4 +3715,49 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn candidate_iterator_matches_eager_product() {
+        let pool = generate_all_encodable_instructions(&[Register::X0, Register::X1], &[0, 1]);
+        let pool = &pool[..6];
+
+        let mut eager = Vec::new();
+        for &a in pool {
+            for &b in pool {
+                eager.push(vec![a, b]);
+            }
+        }
+
+        let iter = CandidateIterator::new(pool, 2);
+        assert_eq!(iter.size_hint(), (36, Some(36)));
+        let mut lazy: Vec<_> = iter.collect();
+        let key = |seq: &Vec<Instruction>| format!("{:?}", seq);
+        eager.sort_by_key(key);
+        lazy.sort_by_key(key);
+        assert_eq!(lazy, eager);
+    }
+
+    #[test]
+    fn candidate_iterator_is_lazy_over_huge_products() {
+        // pool^4 sequences would never fit in memory; the iterator must still
+        // hand out the first few immediately and only track a 4-slot cursor.
+        let pool = generate_all_encodable_instructions(&default_registers(), &default_immediates());
+        let mut iter = CandidateIterator::new(&pool, 4);
+        let first: Vec<_> = iter.by_ref().take(3).collect();
+        assert_eq!(first.len(), 3);
+        assert_eq!(first[0], vec![pool[0]; 4]);
+        assert_eq!(first[2], vec![pool[0], pool[0], pool[0], pool[2]]);
+        assert_eq!(iter.cursor.len(), 4);
+    }
+
+    #[test]
+    fn candidate_iterator_edge_lengths() {
+        let pool = [Register::X0, Register::X1];
+        assert_eq!(
+            CandidateIterator::new(&pool, 0).collect::<Vec<_>>(),
+            vec![Vec::<Register>::new()]
+        );
+        assert_eq!(CandidateIterator::new(&[] as &[Register], 2).count(), 0);
+        assert_eq!(CandidateIterator::new(&pool, 3).count(), 8);
+    }
 }
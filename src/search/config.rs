@@ -2,7 +2,7 @@
 
 #![allow(dead_code)]
 
-use crate::ir::Register;
+use crate::ir::{Instruction, Register};
 use crate::semantics::cost::CostMetric;
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
@@ -60,11 +60,7 @@ pub struct CostMetricConfig(pub CostMetric);
 
 impl std::fmt::Display for CostMetricConfig {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self.0 {
-            CostMetric::InstructionCount => write!(f, "instruction-count"),
-            CostMetric::Latency => write!(f, "latency"),
-            CostMetric::CodeSize => write!(f, "code-size"),
-        }
+        self.0.fmt(f)
     }
 }
 
@@ -72,17 +68,7 @@ impl std::str::FromStr for CostMetricConfig {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.to_lowercase().replace('_', "-").as_str() {
-            "instruction-count" | "count" | "instructions" => {
-                Ok(CostMetricConfig(CostMetric::InstructionCount))
-            }
-            "latency" => Ok(CostMetricConfig(CostMetric::Latency)),
-            "code-size" | "size" | "bytes" => Ok(CostMetricConfig(CostMetric::CodeSize)),
-            _ => Err(format!(
-                "Unknown cost metric: '{}'. Valid options: instruction-count, latency, code-size",
-                s
-            )),
-        }
+        s.parse().map(CostMetricConfig)
     }
 }
 
@@ -91,24 +77,71 @@ impl std::str::FromStr for CostMetricConfig {
 pub struct StochasticConfig {
     /// Inverse temperature parameter for Metropolis acceptance (higher = more greedy)
     pub beta: f64,
-    /// Maximum number of MCMC iterations
-    pub iterations: u64,
-    /// Number of random test cases for fast validation
-    pub test_count: usize,
+    /// Maximum number of MCMC iterations (None = run until `SearchConfig::timeout`
+    /// or the stop flag ends the search; the timeout is then required, see
+    /// [`SearchConfig::validate_stochastic`]). When both an iteration cap and a
+    /// timeout are set, whichever is reached first stops the search.
+    pub iterations: Option<u64>,
+    /// Number of random test cases for fast validation. `None` (the default)
+    /// uses [`SearchConfig::prefilter_tests`], which the symbolic prefilter
+    /// shares; `Some` overrides it for the stochastic search only.
+    pub test_count: Option<usize>,
+    /// Upper bound on the solver counterexamples added to the concrete test
+    /// set, which grows by one input each time the solver refutes a proposal
+    /// that passed every concrete test. The random and edge-case inputs the
+    /// set starts with do not count against it.
+    pub max_counterexamples: usize,
     /// Mutation operator weights [operand, opcode, swap, instruction]
     pub mutation_weights: MutationWeights,
     /// Seed for random number generator (None = random seed)
     pub seed: Option<u64>,
+    /// Verify improvements with the SMT solver. When false, improvements are
+    /// accepted on `check_equivalence_approx` alone (concrete tests only),
+    /// trading soundness for throughput.
+    pub smt_verification: bool,
+    /// What to do with a cheaper proposal whose SMT check comes back
+    /// `Unknown` (typically a solver timeout).
+    pub on_unknown: UnknownPolicy,
+    /// Every this many iterations, reset the chain's current sequence to the
+    /// best proven rewrite so far (the target until one is found). `None`
+    /// (and `Some(0)`) never restarts.
+    pub restart_interval: Option<u64>,
+    /// AArch64 sequence to start the chain from instead of the target or a
+    /// random sequence, e.g. a hand optimization to refine. Its length must
+    /// fit the chain's bounds (see [`Self::validate_initial_program`]);
+    /// otherwise it is ignored, with a warning under `verbose`.
+    pub initial_program: Option<Vec<Instruction>>,
+}
+
+/// Policy for a cheaper stochastic proposal the solver could neither prove
+/// nor refute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UnknownPolicy {
+    /// Drop the improvement; the proposal still takes part in the
+    /// Metropolis step since it was not refuted.
+    #[default]
+    Discard,
+    /// Re-run the SMT check up to `attempts` more times, doubling the solver
+    /// timeout each time (still clamped to the remaining search budget).
+    Retry { attempts: u32 },
+    /// Record the proposal as an improvement anyway and print a warning.
+    /// Unsound: the result is only as good as the concrete tests.
+    AcceptWithWarning,
 }
 
 impl Default for StochasticConfig {
     fn default() -> Self {
         Self {
             beta: 1.0,
-            iterations: 1_000_000,
-            test_count: 16,
+            iterations: Some(1_000_000),
+            test_count: None,
+            max_counterexamples: 64,
             mutation_weights: MutationWeights::default(),
             seed: None,
+            smt_verification: true,
+            on_unknown: UnknownPolicy::Discard,
+            restart_interval: None,
+            initial_program: None,
         }
     }
 }
@@ -120,12 +153,22 @@ impl StochasticConfig {
     }
 
     pub fn with_iterations(mut self, iterations: u64) -> Self {
+        self.iterations = Some(iterations);
+        self
+    }
+
+    pub fn with_iterations_option(mut self, iterations: Option<u64>) -> Self {
         self.iterations = iterations;
         self
     }
 
     pub fn with_test_count(mut self, count: usize) -> Self {
-        self.test_count = count;
+        self.test_count = Some(count);
+        self
+    }
+
+    pub fn with_max_counterexamples(mut self, max: usize) -> Self {
+        self.max_counterexamples = max;
         self
     }
 
@@ -138,6 +181,50 @@ impl StochasticConfig {
         self.seed = seed;
         self
     }
+
+    pub fn with_smt_verification(mut self, enabled: bool) -> Self {
+        self.smt_verification = enabled;
+        self
+    }
+
+    pub fn with_on_unknown(mut self, policy: UnknownPolicy) -> Self {
+        self.on_unknown = policy;
+        self
+    }
+
+    pub fn with_restart_interval(mut self, interval: u64) -> Self {
+        self.restart_interval = Some(interval);
+        self
+    }
+
+    pub fn with_initial_program(mut self, program: Vec<Instruction>) -> Self {
+        self.initial_program = Some(program);
+        self
+    }
+
+    pub fn with_initial_program_option(mut self, program: Option<Vec<Instruction>>) -> Self {
+        self.initial_program = program;
+        self
+    }
+
+    /// Check that [`Self::initial_program`], if set, has between
+    /// `min_length` and `max_length` instructions, the lengths the chain can
+    /// occupy for the current target.
+    pub fn validate_initial_program(
+        &self,
+        min_length: usize,
+        max_length: usize,
+    ) -> Result<(), String> {
+        match &self.initial_program {
+            Some(program) if !(min_length..=max_length).contains(&program.len()) => Err(format!(
+                "initial program has {} instruction(s); expected {}..={}",
+                program.len(),
+                min_length,
+                max_length
+            )),
+            _ => Ok(()),
+        }
+    }
 }
 
 /// Weights for mutation operators in stochastic search
@@ -151,6 +238,11 @@ pub struct MutationWeights {
     pub swap: f64,
     /// Weight for instruction mutation (replace entire instruction)
     pub instruction: f64,
+    /// Weight for the canonicalize macro-mutation: rewrite the sequence to
+    /// its peephole canonical form, then apply one ordinary mutation. Off by
+    /// default. x86 has no canonicalization pass and treats this bucket as an
+    /// instruction mutation.
+    pub canonicalize: f64,
 }
 
 impl Default for MutationWeights {
@@ -160,34 +252,111 @@ impl Default for MutationWeights {
             opcode: 0.16,
             swap: 0.16,
             instruction: 0.18,
+            canonicalize: 0.0,
         }
     }
 }
 
 impl MutationWeights {
-    /// Bucket a uniform draw `r ∈ [0, 1)` into one of the four mutation
+    /// Bucket a uniform draw `r ∈ [0, 1)` into one of the five mutation
     /// categories, indexed in `MutationType` order: 0 = operand, 1 = opcode,
-    /// 2 = swap, 3 = instruction. A category is chosen with probability
-    /// proportional to its weight.
+    /// 2 = swap, 3 = instruction, 4 = canonicalize. A category is chosen with
+    /// probability proportional to its weight.
     ///
     /// Degenerate all-zero weights (total ≤ 0) collapse the whole interval
-    /// onto the last bucket instead of dividing by zero into NaN thresholds.
+    /// onto the instruction bucket instead of dividing by zero into NaN
+    /// thresholds; [`Self::validate`] rejects such weights up front.
     pub fn select_index(&self, r: f64) -> usize {
-        let total = self.operand + self.opcode + self.swap + self.instruction;
+        let total = self.operand + self.opcode + self.swap + self.instruction + self.canonicalize;
         if total <= 0.0 {
             return 3;
         }
         let t0 = self.operand / total;
         let t1 = (self.operand + self.opcode) / total;
         let t2 = (self.operand + self.opcode + self.swap) / total;
+        let t3 = (self.operand + self.opcode + self.swap + self.instruction) / total;
         if r < t0 {
             0
         } else if r < t1 {
             1
         } else if r < t2 {
             2
-        } else {
+        } else if r < t3 || self.canonicalize <= 0.0 {
             3
+        } else {
+            4
+        }
+    }
+
+    /// Build weights from relative values, scaled so they sum to 1.0.
+    /// Fails under the same conditions as [`Self::validate`].
+    pub fn new(
+        operand: f64,
+        opcode: f64,
+        swap: f64,
+        instruction: f64,
+        canonicalize: f64,
+    ) -> Result<Self, String> {
+        Self {
+            operand,
+            opcode,
+            swap,
+            instruction,
+            canonicalize,
+        }
+        .normalized()
+    }
+
+    /// Check that every weight is finite and non-negative and that at least
+    /// one is positive, so `select_index` describes a real distribution.
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, weight) in self.named() {
+            if !weight.is_finite() || weight < 0.0 {
+                return Err(format!(
+                    "mutation weight `{}` must be finite and non-negative, got {}",
+                    name, weight
+                ));
+            }
+        }
+        if self.total() <= 0.0 {
+            return Err("mutation weights must not all be zero".to_string());
+        }
+        Ok(())
+    }
+
+    /// These weights scaled to sum to 1.0, after [`Self::validate`].
+    pub fn normalized(&self) -> Result<Self, String> {
+        self.validate()?;
+        let total = self.total();
+        Ok(Self {
+            operand: self.operand / total,
+            opcode: self.opcode / total,
+            swap: self.swap / total,
+            instruction: self.instruction / total,
+            canonicalize: self.canonicalize / total,
+        })
+    }
+
+    fn total(&self) -> f64 {
+        self.named().iter().map(|(_, weight)| weight).sum()
+    }
+
+    fn named(&self) -> [(&'static str, f64); 5] {
+        [
+            ("operand", self.operand),
+            ("opcode", self.opcode),
+            ("swap", self.swap),
+            ("instruction", self.instruction),
+            ("canonicalize", self.canonicalize),
+        ]
+    }
+
+    /// These weights with the canonicalize bucket removed: the distribution
+    /// over the four single-step mutations.
+    pub fn without_canonicalize(&self) -> Self {
+        Self {
+            canonicalize: 0.0,
+            ..self.clone()
         }
     }
 }
@@ -226,9 +395,27 @@ impl std::str::FromStr for SearchMode {
     }
 }
 
+/// How the symbolic search picks candidates at lengths of three or more (and
+/// at length two past `SymbolicConfig::exhaustive_pair_limit`), where
+/// enumerating every sequence is out of reach.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Sampling {
+    /// The first `sample_size` sequences in enumeration order: each slot
+    /// counts through the instruction pool, the last slot fastest.
+    #[default]
+    FirstN,
+    /// `sample_size` sequences whose every slot is drawn uniformly from the
+    /// instruction pool by a generator seeded with `seed`.
+    Random { seed: u64 },
+}
+
 /// Default timeout for each SMT solver query used by verification/synthesis.
 pub const DEFAULT_SYMBOLIC_SOLVER_TIMEOUT: Duration = Duration::from_secs(30);
 
+/// Default [`SymbolicConfig::exhaustive_pair_limit`]: every pair over a pool of
+/// up to a thousand instructions.
+pub const DEFAULT_EXHAUSTIVE_PAIR_LIMIT: usize = 1_000_000;
+
 /// Configuration for symbolic (SMT) search
 #[derive(Debug, Clone)]
 pub struct SymbolicConfig {
@@ -250,6 +437,21 @@ pub struct SymbolicConfig {
     pub cost_bound: Option<u64>,
     /// Search mode (linear or binary)
     pub search_mode: SearchMode,
+    /// Number of candidates tried at each sampled length.
+    ///
+    /// Length one is always enumerated exhaustively, and length two is too
+    /// unless `exhaustive_pair_limit` says otherwise; longer lengths are
+    /// sampled, not exhaustive, so a miss there does not prove that no
+    /// cheaper sequence of that length exists.
+    pub sample_size: usize,
+    /// Which candidates the `sample_size` budget is spent on.
+    pub sampling: Sampling,
+    /// Largest number of length-two sequences enumerated exhaustively
+    /// (default [`DEFAULT_EXHAUSTIVE_PAIR_LIMIT`]).
+    ///
+    /// When the instruction pool squared exceeds this, length two is sampled
+    /// like the longer lengths. `None` always enumerates length two.
+    pub exhaustive_pair_limit: Option<usize>,
 }
 
 impl Default for SymbolicConfig {
@@ -258,6 +460,9 @@ impl Default for SymbolicConfig {
             window_size: 3,
             cost_bound: None,
             search_mode: SearchMode::Linear,
+            sample_size: 10_000,
+            sampling: Sampling::FirstN,
+            exhaustive_pair_limit: Some(DEFAULT_EXHAUSTIVE_PAIR_LIMIT),
         }
     }
 }
@@ -285,6 +490,21 @@ impl SymbolicConfig {
         self.search_mode = mode;
         self
     }
+
+    pub fn with_sample_size(mut self, sample_size: usize) -> Self {
+        self.sample_size = sample_size;
+        self
+    }
+
+    pub fn with_sampling(mut self, sampling: Sampling) -> Self {
+        self.sampling = sampling;
+        self
+    }
+
+    pub fn with_exhaustive_pair_limit(mut self, limit: usize) -> Self {
+        self.exhaustive_pair_limit = Some(limit);
+        self
+    }
 }
 
 /// Default Codex model identifier used by the LLM-assisted search flow.
@@ -355,16 +575,54 @@ pub struct SearchConfig {
     /// this field through [`Self::solver_timeout_within_budget`] rather than
     /// pass zero to Z3, where it would mean an unbounded query.
     pub solver_timeout: Option<Duration>,
+    /// Work budget: the enumerative and symbolic searches stop cleanly, with
+    /// the best result so far, once they have evaluated this many
+    /// candidates. Unlike `timeout` it does not depend on machine speed, so
+    /// capped runs are reproducible. `None` (the default) sets no cap.
+    pub max_candidates: Option<u64>,
     /// Number of worker threads (rayon) for algorithms that parallelise.
     /// `None` lets rayon pick its default (typically logical-core count).
     /// `Some(0)` is coerced to 1 thread (rayon rejects zero-thread pools).
     /// Currently consumed by `EnumerativeSearch`; ignored by single-threaded
     /// algorithms.
     pub cores: Option<usize>,
-    /// Registers available for use in synthesized code
+    /// Registers available for use in synthesized code. Defaults to
+    /// `x0`-`x5`: enough for the usual two-source window plus scratch.
+    /// [`Self::minimal`] narrows it to `x0`-`x2`.
     pub available_registers: Vec<Register>,
-    /// Immediate values to consider in synthesis
+    /// Immediate values to consider in synthesis. Defaults to
+    /// [`DEFAULT_IMMEDIATES`]; [`Self::minimal`] narrows it to `-1..=2`.
     pub available_immediates: Vec<i64>,
+    /// Widest `MovImm` immediate, in bits, the candidate generators may
+    /// emit. The default of 16 keeps every `mov` a single MOVZ; wider
+    /// values admit immediates that assemble to a MOVZ/MOVK chain and are
+    /// costed per 16-bit chunk.
+    pub max_immediate_bits: u32,
+    /// Whether synthesized code may write the stack pointer. Defaults to
+    /// false: the AArch64 generators then never see `SP` in their register
+    /// pool (see [`Self::search_registers`]), so no candidate can clobber
+    /// it.
+    pub allow_sp_writes: bool,
+    /// Whether the AArch64 enumerative and symbolic searches may consider
+    /// candidates that have no machine encoding (`and x0, x1, #5`). Defaults
+    /// to false, since such a result can never be patched back; set it to
+    /// explore the IR semantics alone.
+    pub allow_unencodable: bool,
+    /// Whether the AArch64 candidate pool keeps shifts and rotates by an
+    /// immediate 0 (`lsl x0, x1, #0`). Defaults to false: each is just a
+    /// `mov`, which the pool already holds.
+    pub include_trivial_shifts: bool,
+    /// Byte budget for a rewrite, typically the size of the window it must
+    /// be patched into. Candidates whose encoding is longer are pruned
+    /// before verification. `None` (the default) sets no limit; backends
+    /// that cannot size their candidates ignore it.
+    pub max_bytes: Option<usize>,
+    /// Random concrete tests a candidate must pass before it reaches the
+    /// solver: the stochastic search's test set and the symbolic search's
+    /// `EquivalenceConfig::random_tests`. More tests refute more
+    /// non-equivalent candidates without an SMT query, at a higher cost per
+    /// candidate. Zero skips the concrete prefilter.
+    pub prefilter_tests: usize,
     /// x86 register pool (issue #73). Consumed by
     /// `<X86_64 as StochasticBackend>::registers_from_config` and the
     /// x86 symbolic / LLM backends. Defaults to the same 8 GPRs the
@@ -391,6 +649,12 @@ pub struct SearchConfig {
     pub stop_flag: Option<Arc<AtomicBool>>,
 }
 
+/// The immediates [`SearchConfig::default`] offers the synthesizer: small
+/// counts, shift amounts, byte/halfword masks and the 12-bit `add` limit.
+pub const DEFAULT_IMMEDIATES: &[i64] = &[
+    0, 1, 2, 3, 4, 5, 7, 8, 10, 15, 16, 31, 32, 63, 64, 100, 255, 256, 1000, 4095,
+];
+
 impl Default for SearchConfig {
     fn default() -> Self {
         Self {
@@ -398,6 +662,7 @@ impl Default for SearchConfig {
             cost_metric: CostMetric::default(),
             timeout: Some(Duration::from_secs(60)),
             solver_timeout: Some(DEFAULT_SYMBOLIC_SOLVER_TIMEOUT),
+            max_candidates: None,
             cores: None,
             available_registers: vec![
                 Register::X0,
@@ -407,9 +672,13 @@ impl Default for SearchConfig {
                 Register::X4,
                 Register::X5,
             ],
-            available_immediates: vec![
-                0, 1, 2, 3, 4, 5, 7, 8, 10, 15, 16, 31, 32, 63, 64, 100, 255, 256, 1000, 4095,
-            ],
+            available_immediates: DEFAULT_IMMEDIATES.to_vec(),
+            max_immediate_bits: 16,
+            allow_sp_writes: false,
+            allow_unencodable: false,
+            include_trivial_shifts: false,
+            max_bytes: None,
+            prefilter_tests: 16,
             x86_available_registers: crate::isa::x86::default_x86_registers(),
             x86_same_count_code_size_allowed: true,
             stochastic: StochasticConfig::default(),
@@ -422,6 +691,15 @@ impl Default for SearchConfig {
 }
 
 impl SearchConfig {
+    /// The default configuration over a small pool, `x0`-`x2` and the
+    /// immediates `-1..=2`, for tests and quick experiments whose targets
+    /// need nothing wider.
+    pub fn minimal() -> Self {
+        Self::default()
+            .with_registers(vec![Register::X0, Register::X1, Register::X2])
+            .with_immediates(vec![-1, 0, 1, 2])
+    }
+
     pub fn with_algorithm(mut self, algorithm: Algorithm) -> Self {
         self.algorithm = algorithm;
         self
@@ -466,6 +744,89 @@ impl SearchConfig {
         self
     }
 
+    pub fn with_allow_sp_writes(mut self, allow: bool) -> Self {
+        self.allow_sp_writes = allow;
+        self
+    }
+
+    pub fn with_allow_unencodable(mut self, allow: bool) -> Self {
+        self.allow_unencodable = allow;
+        self
+    }
+
+    pub fn with_include_trivial_shifts(mut self, include: bool) -> Self {
+        self.include_trivial_shifts = include;
+        self
+    }
+
+    pub fn with_max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+
+    pub fn with_max_bytes_option(mut self, max_bytes: Option<usize>) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    pub fn with_prefilter_tests(mut self, count: usize) -> Self {
+        self.prefilter_tests = count;
+        self
+    }
+
+    /// Whether a candidate encoding to `encoded_length` bytes overruns
+    /// [`Self::max_bytes`]. An unknown length never does.
+    pub fn exceeds_max_bytes(&self, encoded_length: Option<usize>) -> bool {
+        matches!((self.max_bytes, encoded_length), (Some(max), Some(len)) if len > max)
+    }
+
+    /// Reject settings [`Self::algorithm`] cannot run with. Only the
+    /// stochastic and hybrid searches read [`Self::stochastic`], so the other
+    /// algorithms never fail on it. Symbolic search needs a solver, so it
+    /// fails outright in a build without the `z3` feature.
+    pub fn validate(&self) -> Result<(), String> {
+        match self.algorithm {
+            Algorithm::Stochastic | Algorithm::Hybrid => self.validate_stochastic(),
+            Algorithm::Symbolic if !cfg!(feature = "z3") => {
+                Err("symbolic search requires s11 built with the `z3` feature".to_string())
+            }
+            Algorithm::Enumerative | Algorithm::Symbolic | Algorithm::Llm => Ok(()),
+        }
+    }
+
+    /// Reject settings the stochastic search cannot run with: invalid
+    /// mutation weights (see [`MutationWeights::validate`]), or neither an
+    /// iteration cap nor a [`Self::timeout`] to end the chain.
+    pub fn validate_stochastic(&self) -> Result<(), String> {
+        self.stochastic
+            .mutation_weights
+            .validate()
+            .map_err(|e| format!("stochastic: {}", e))?;
+        if self.stochastic.iterations.is_none() && self.timeout.is_none() {
+            return Err("stochastic: needs an iteration cap or a timeout".to_string());
+        }
+        Ok(())
+    }
+
+    /// The AArch64 register pool handed to the candidate generators and
+    /// mutators: `available_registers`, minus `SP` unless
+    /// [`allow_sp_writes`](Self::allow_sp_writes) is set. The generators do
+    /// not distinguish source from destination operands, so withholding `SP`
+    /// from the pool is what keeps it out of every destination.
+    pub fn search_registers(&self) -> Vec<Register> {
+        self.available_registers
+            .iter()
+            .copied()
+            .filter(|reg| self.allow_sp_writes || *reg != Register::SP)
+            .collect()
+    }
+
+    /// Set the widest `MovImm` immediate (in bits) the generators may emit.
+    pub fn with_max_immediate_bits(mut self, bits: u32) -> Self {
+        self.max_immediate_bits = bits;
+        self
+    }
+
     pub fn with_stochastic(mut self, stochastic: StochasticConfig) -> Self {
         self.stochastic = stochastic;
         self
@@ -496,6 +857,16 @@ impl SearchConfig {
         self
     }
 
+    pub fn with_max_candidates(mut self, max_candidates: u64) -> Self {
+        self.max_candidates = Some(max_candidates);
+        self
+    }
+
+    pub fn with_max_candidates_option(mut self, max_candidates: Option<u64>) -> Self {
+        self.max_candidates = max_candidates;
+        self
+    }
+
     pub fn with_solver_timeout_option(mut self, timeout: Option<Duration>) -> Self {
         self.solver_timeout = timeout;
         self
@@ -518,6 +889,11 @@ impl SearchConfig {
         self
     }
 
+    pub fn with_stop_flag_option(mut self, flag: Option<Arc<AtomicBool>>) -> Self {
+        self.stop_flag = flag;
+        self
+    }
+
     pub fn with_x86_same_count_code_size_allowed(mut self, allowed: bool) -> Self {
         self.x86_same_count_code_size_allowed = allowed;
         self
@@ -572,6 +948,25 @@ impl SearchConfig {
         Self::solver_query_timeout(self.solver_timeout().min(remaining))
     }
 
+    /// Per-query SMT timeout for retrying a query that came back `Unknown`
+    /// after `previous`: double it, capped at the time left in the search.
+    ///
+    /// Returns `None` under the same conditions as
+    /// [`Self::solver_timeout_within_budget`].
+    pub(crate) fn solver_retry_timeout(
+        &self,
+        previous: Duration,
+        elapsed: Duration,
+    ) -> Option<Duration> {
+        let doubled = previous.saturating_mul(2);
+        match self.timeout {
+            Some(search_timeout) => {
+                Self::solver_query_timeout(doubled.min(search_timeout.checked_sub(elapsed)?))
+            }
+            None => Self::solver_query_timeout(doubled),
+        }
+    }
+
     fn solver_query_timeout(timeout: Duration) -> Option<Duration> {
         // Z3 interprets a zero timeout as unbounded. SearchConfig deliberately
         // reserves zero for "skip SMT", and Z3 cannot represent positive
@@ -598,10 +993,60 @@ mod tests {
             "symbolic".parse::<Algorithm>().unwrap(),
             Algorithm::Symbolic
         );
+        assert_eq!("hybrid".parse::<Algorithm>().unwrap(), Algorithm::Hybrid);
+        assert_eq!("HYBRID".parse::<Algorithm>().unwrap(), Algorithm::Hybrid);
         assert_eq!("mcmc".parse::<Algorithm>().unwrap(), Algorithm::Stochastic);
         assert_eq!("smt".parse::<Algorithm>().unwrap(), Algorithm::Symbolic);
     }
 
+    #[test]
+    fn algorithm_display_round_trips_through_from_str() {
+        for algorithm in [
+            Algorithm::Enumerative,
+            Algorithm::Stochastic,
+            Algorithm::Symbolic,
+            Algorithm::Hybrid,
+            Algorithm::Llm,
+        ] {
+            assert_eq!(algorithm.to_string().parse::<Algorithm>(), Ok(algorithm));
+        }
+    }
+
+    #[test]
+    fn exceeds_max_bytes_only_when_both_sides_are_known() {
+        let unbounded = SearchConfig::default();
+        assert!(!unbounded.exceeds_max_bytes(Some(usize::MAX)));
+
+        let window = SearchConfig::default().with_max_bytes(8);
+        assert!(!window.exceeds_max_bytes(Some(8)));
+        assert!(window.exceeds_max_bytes(Some(12)));
+        assert!(!window.exceeds_max_bytes(None));
+    }
+
+    #[test]
+    fn validate_initial_program_checks_the_length_bounds() {
+        let mov = Instruction::MovImm {
+            rd: Register::X0,
+            imm: 0,
+        };
+        assert_eq!(
+            StochasticConfig::default().validate_initial_program(1, 2),
+            Ok(())
+        );
+        let two = StochasticConfig::default().with_initial_program(vec![mov; 2]);
+        assert_eq!(two.validate_initial_program(1, 2), Ok(()));
+        assert_eq!(
+            two.validate_initial_program(1, 1),
+            Err("initial program has 2 instruction(s); expected 1..=1".to_string())
+        );
+        assert!(
+            StochasticConfig::default()
+                .with_initial_program(Vec::new())
+                .validate_initial_program(1, 2)
+                .is_err()
+        );
+    }
+
     #[test]
     fn test_algorithm_display() {
         assert_eq!(format!("{}", Algorithm::Enumerative), "enumerative");
@@ -633,6 +1078,10 @@ mod tests {
             "bytes".parse::<CostMetricConfig>().unwrap().0,
             CostMetric::CodeSize
         );
+        assert_eq!(
+            "clobbers".parse::<CostMetricConfig>().unwrap().0,
+            CostMetric::Clobbers(crate::semantics::cost::LiveRegisterMask::EMPTY)
+        );
         assert!("bogus".parse::<CostMetricConfig>().is_err());
         assert_eq!(
             format!("{}", CostMetricConfig(CostMetric::InstructionCount)),
@@ -657,6 +1106,7 @@ mod tests {
             opcode: 1.0,
             swap: 1.0,
             instruction: 1.0,
+            canonicalize: 0.0,
         };
         assert_eq!(weights.select_index(0.0), 0);
         assert_eq!(weights.select_index(0.24), 0);
@@ -666,6 +1116,19 @@ mod tests {
         assert_eq!(weights.select_index(0.74), 2);
         assert_eq!(weights.select_index(0.75), 3);
         assert_eq!(weights.select_index(0.999), 3);
+
+        let with_canonicalize = MutationWeights {
+            instruction: 0.5,
+            canonicalize: 0.5,
+            ..weights
+        };
+        assert_eq!(with_canonicalize.select_index(0.74), 2);
+        assert_eq!(with_canonicalize.select_index(0.80), 3);
+        assert_eq!(with_canonicalize.select_index(0.90), 4);
+        assert_eq!(
+            with_canonicalize.without_canonicalize().select_index(0.999),
+            3
+        );
     }
 
     #[test]
@@ -688,6 +1151,7 @@ mod tests {
             opcode: 0.0,
             swap: 0.0,
             instruction: 0.0,
+            canonicalize: 0.0,
         };
         assert_eq!(weights.select_index(0.0), 0);
         assert_eq!(weights.select_index(0.5), 0);
@@ -699,11 +1163,92 @@ mod tests {
             opcode: 0.0,
             swap: 1.0,
             instruction: 0.0,
+            canonicalize: 0.0,
         };
         assert_eq!(swap_only.select_index(0.0), 2);
         assert_eq!(swap_only.select_index(0.999), 2);
     }
 
+    #[test]
+    fn all_zero_mutation_weights_are_rejected() {
+        let zero = MutationWeights::new(0.0, 0.0, 0.0, 0.0, 0.0);
+        assert_eq!(
+            zero.unwrap_err(),
+            "mutation weights must not all be zero".to_string()
+        );
+
+        let mut config = SearchConfig::default().with_algorithm(Algorithm::Stochastic);
+        assert_eq!(config.validate(), Ok(()));
+        config.stochastic.mutation_weights = MutationWeights {
+            operand: 0.0,
+            opcode: 0.0,
+            swap: 0.0,
+            instruction: 0.0,
+            canonicalize: 0.0,
+        };
+        assert!(config.validate().unwrap_err().starts_with("stochastic: "));
+
+        // The weights are stochastic-only: the other searches still run.
+        for algorithm in [Algorithm::Enumerative, Algorithm::Symbolic] {
+            assert_eq!(config.clone().with_algorithm(algorithm).validate(), Ok(()));
+        }
+    }
+
+    #[test]
+    fn unbounded_stochastic_search_is_rejected() {
+        let unbounded = SearchConfig::default()
+            .with_algorithm(Algorithm::Stochastic)
+            .with_timeout_option(None)
+            .with_stochastic(StochasticConfig::default().with_iterations_option(None));
+        assert_eq!(
+            unbounded.validate(),
+            Err("stochastic: needs an iteration cap or a timeout".to_string())
+        );
+
+        let capped = unbounded
+            .clone()
+            .with_stochastic(StochasticConfig::default());
+        assert_eq!(capped.validate(), Ok(()));
+        let timed = unbounded.with_timeout(Duration::from_secs(1));
+        assert_eq!(timed.validate(), Ok(()));
+    }
+
+    #[test]
+    fn negative_or_non_finite_mutation_weights_are_rejected() {
+        for bad in [-1.0, f64::NAN, f64::INFINITY] {
+            let weights = MutationWeights {
+                swap: bad,
+                ..MutationWeights::default()
+            };
+            let err = weights.validate().unwrap_err();
+            assert!(err.contains("`swap`"), "{err}");
+        }
+    }
+
+    #[test]
+    fn positive_mutation_weights_are_normalized_to_unit_sum() {
+        let weights = MutationWeights::new(2.0, 1.0, 1.0, 0.0, 4.0).unwrap();
+        assert_eq!(weights.operand, 0.25);
+        assert_eq!(weights.opcode, 0.125);
+        assert_eq!(weights.swap, 0.125);
+        assert_eq!(weights.instruction, 0.0);
+        assert_eq!(weights.canonicalize, 0.5);
+
+        // Scaling leaves the bucket boundaries where they were.
+        let raw = MutationWeights {
+            operand: 2.0,
+            opcode: 1.0,
+            swap: 1.0,
+            instruction: 0.0,
+            canonicalize: 4.0,
+        };
+        for r in [0.0, 0.2, 0.3, 0.45, 0.5, 0.99] {
+            assert_eq!(weights.select_index(r), raw.select_index(r), "r = {r}");
+        }
+        let default = MutationWeights::default().normalized().unwrap();
+        assert!((default.total() - 1.0).abs() < 1e-12);
+    }
+
     #[test]
     fn select_index_with_zero_total_is_defined() {
         // Degenerate all-zero weights must stay well-defined (no NaN from a
@@ -713,6 +1258,7 @@ mod tests {
             opcode: 0.0,
             swap: 0.0,
             instruction: 0.0,
+            canonicalize: 0.0,
         };
         assert_eq!(weights.select_index(0.0), 3);
         assert_eq!(weights.select_index(0.5), 3);
@@ -834,6 +1380,41 @@ mod tests {
         );
     }
 
+    #[test]
+    fn search_registers_withholds_sp_unless_sp_writes_are_allowed() {
+        let config =
+            SearchConfig::default().with_registers(vec![Register::X0, Register::SP, Register::X1]);
+        assert!(!config.allow_sp_writes);
+        assert_eq!(config.search_registers(), vec![Register::X0, Register::X1]);
+        assert_eq!(
+            config.with_allow_sp_writes(true).search_registers(),
+            vec![Register::X0, Register::SP, Register::X1]
+        );
+    }
+
+    #[test]
+    fn solver_retry_timeout_doubles_within_remaining_budget() {
+        let config = SearchConfig::default().with_timeout(Duration::from_secs(1));
+        assert_eq!(
+            config.solver_retry_timeout(Duration::from_millis(100), Duration::from_millis(500)),
+            Some(Duration::from_millis(200))
+        );
+        assert_eq!(
+            config.solver_retry_timeout(Duration::from_millis(400), Duration::from_millis(500)),
+            Some(Duration::from_millis(500))
+        );
+        assert_eq!(
+            config.solver_retry_timeout(Duration::from_millis(400), Duration::from_secs(1)),
+            None
+        );
+
+        let unbounded = SearchConfig::default().with_timeout_option(None);
+        assert_eq!(
+            unbounded.solver_retry_timeout(Duration::from_secs(5), Duration::from_secs(999)),
+            Some(Duration::from_secs(10))
+        );
+    }
+
     #[test]
     fn solver_timeout_with_remaining_budget_uses_the_shared_zero_policy() {
         let disabled = SearchConfig::default().with_solver_timeout(Duration::ZERO);
@@ -871,13 +1452,16 @@ mod tests {
             .with_beta(2.0)
             .with_iterations(500_000)
             .with_test_count(99)
+            .with_max_counterexamples(128)
             .with_seed(42)
             .with_seed_option(None);
 
         assert_eq!(config.beta, 2.0);
-        assert_eq!(config.iterations, 500_000);
-        assert_eq!(config.test_count, 99);
+        assert_eq!(config.iterations, Some(500_000));
+        assert_eq!(config.test_count, Some(99));
+        assert_eq!(config.max_counterexamples, 128);
         assert_eq!(config.seed, None);
+        assert_eq!(config.with_iterations_option(None).iterations, None);
     }
 
     #[test]
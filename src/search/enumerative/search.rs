@@ -5,6 +5,7 @@
 //! register/immediate sets (shared with the symbolic path) and verifies each
 //! against the target with the live-out/flag-aware equivalence checker.
 
+use std::collections::HashSet;
 use std::marker::PhantomData;
 use std::sync::Mutex;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
@@ -14,10 +15,14 @@ use rayon::prelude::*;
 
 use crate::isa::{AArch64, CostModel, ISA, InstructionGenerator};
 use crate::search::SearchAlgorithm;
-use crate::search::candidate::generate_all_encodable_instructions;
+use crate::search::candidate::{
+    CandidateIterator, generate_all_encodable_instructions, generate_candidates_for_config,
+};
 use crate::search::config::{Algorithm, SearchConfig};
+use crate::search::normalize::{canonicalize, normalize_registers};
 use crate::search::result::{SearchResultFor, SearchStatistics};
-use crate::semantics::cost::CostMetric;
+use crate::search::trace::{opcode_summary, search_event};
+use crate::semantics::cost::{CLOBBER_INSTRUCTION_WEIGHT, CostMetric};
 use crate::semantics::equivalence::{
     EquivalenceConfigFor, check_equivalence_for_metrics, check_equivalence_with_config_metrics,
 };
@@ -38,11 +43,14 @@ struct SharedState<I: ISA> {
     candidates_passed_fast: AtomicU64,
     improvements_found: AtomicU64,
     best: Mutex<Option<Vec<I::Instruction>>>,
+    /// `dedup_key`s of candidates already sent to verification.
+    verified: Mutex<HashSet<Vec<I::Instruction>>>,
 }
 
 struct CandidatePool<I: ISA> {
     registers: Vec<I::Register>,
     immediates: Vec<i64>,
+    max_immediate_bits: u32,
     instructions: Vec<I::Instruction>,
 }
 
@@ -59,6 +67,7 @@ impl<I: ISA> SharedState<I> {
             candidates_passed_fast: AtomicU64::new(0),
             improvements_found: AtomicU64::new(0),
             best: Mutex::new(None),
+            verified: Mutex::new(HashSet::new()),
         }
     }
 
@@ -92,10 +101,39 @@ pub trait EnumerativeBackend<I: ISA>: Sized {
     fn registers_from_config(config: &SearchConfig) -> Vec<I::Register>;
     fn immediates_from_config(config: &SearchConfig) -> Vec<i64>;
     fn enumerate_all(regs: &[I::Register], imms: &[i64]) -> Vec<I::Instruction>;
+    /// Candidate pool for `config`. Defaults to `enumerate_all`; backends
+    /// override it to honour config knobs such as `max_immediate_bits`.
+    fn enumerate_for_config(
+        config: &SearchConfig,
+        regs: &[I::Register],
+        imms: &[i64],
+    ) -> Vec<I::Instruction> {
+        let _ = config;
+        Self::enumerate_all(regs, imms)
+    }
     fn sequence_cost(seq: &[I::Instruction], config: &SearchConfig) -> u64;
+    /// `metric` with `live_out` bound into its clobber term (see
+    /// [`CostMetric::with_live_out`]). The default leaves it unchanged, for
+    /// backends without a clobber model.
+    fn bind_live_out(metric: CostMetric, _live_out: &Self::LiveOut) -> CostMetric {
+        metric
+    }
+    /// Bytes `seq` assembles to, for `SearchConfig::max_bytes`. `None` (the
+    /// default) when the backend cannot size it; such candidates are never
+    /// pruned by size.
+    fn encoded_length(_seq: &[I::Instruction]) -> Option<usize> {
+        None
+    }
     fn target_terminator(_target: &[I::Instruction]) -> Option<I::Instruction> {
         None
     }
+    /// Key under which `seq` is deduplicated before verification: candidates
+    /// with equal keys share one equivalence verdict. Defaults to `seq`
+    /// itself; backends with a scratch-register renaming collapse
+    /// alpha-equivalent candidates onto one key.
+    fn dedup_key(seq: &[I::Instruction], _live_out: &Self::LiveOut) -> Vec<I::Instruction> {
+        seq.to_vec()
+    }
     fn check_equivalence(
         target: &[I::Instruction],
         candidate: &[I::Instruction],
@@ -108,7 +146,7 @@ impl EnumerativeBackend<AArch64> for AArch64 {
     type LiveOut = LiveOut;
 
     fn registers_from_config(config: &SearchConfig) -> Vec<crate::ir::Register> {
-        config.available_registers.clone()
+        config.search_registers()
     }
 
     fn immediates_from_config(config: &SearchConfig) -> Vec<i64> {
@@ -119,6 +157,18 @@ impl EnumerativeBackend<AArch64> for AArch64 {
         generate_all_encodable_instructions(regs, imms)
     }
 
+    fn enumerate_for_config(
+        config: &SearchConfig,
+        regs: &[crate::ir::Register],
+        imms: &[i64],
+    ) -> Vec<crate::ir::Instruction> {
+        generate_candidates_for_config(config, regs, imms)
+    }
+
+    fn bind_live_out(metric: CostMetric, live_out: &Self::LiveOut) -> CostMetric {
+        metric.with_live_out(live_out)
+    }
+
     fn sequence_cost(seq: &[crate::ir::Instruction], config: &SearchConfig) -> u64 {
         <AArch64 as CostModel<crate::ir::Instruction>>::sequence_cost(
             &AArch64,
@@ -127,6 +177,16 @@ impl EnumerativeBackend<AArch64> for AArch64 {
         )
     }
 
+    fn dedup_key(
+        seq: &[crate::ir::Instruction],
+        live_out: &Self::LiveOut,
+    ) -> Vec<crate::ir::Instruction> {
+        canonicalize(&normalize_registers(seq, live_out))
+    }
+    fn encoded_length(seq: &[crate::ir::Instruction]) -> Option<usize> {
+        Some(seq.iter().map(crate::ir::Instruction::encoded_length).sum())
+    }
+
     fn check_equivalence(
         target: &[crate::ir::Instruction],
         candidate: &[crate::ir::Instruction],
@@ -332,8 +392,15 @@ impl<I: ISA> EnumerativeSearch<I> {
         }
     }
 
-    fn timed_out(start: Instant, timeout: Option<Duration>) -> bool {
-        timeout.is_some_and(|t| start.elapsed() >= t)
+    /// True once `config.timeout` has elapsed or an external driver (the
+    /// parallel coordinator, the CLI's Ctrl-C handler) has raised
+    /// `config.stop_flag`.
+    fn should_stop(start: Instant, config: &SearchConfig) -> bool {
+        config.timeout.is_some_and(|t| start.elapsed() >= t)
+            || config
+                .stop_flag
+                .as_ref()
+                .is_some_and(|f| f.load(Ordering::Relaxed))
     }
 
     fn cached_private_pool(
@@ -395,15 +462,21 @@ where
         let registers = <I as EnumerativeBackend<I>>::registers_from_config(config);
         let immediates = <I as EnumerativeBackend<I>>::immediates_from_config(config);
         let regenerate = match &self.candidate_pool {
-            Some(pool) => pool.registers != registers || pool.immediates != immediates,
+            Some(pool) => {
+                pool.registers != registers
+                    || pool.immediates != immediates
+                    || pool.max_immediate_bits != config.max_immediate_bits
+            }
             None => true,
         };
 
         if regenerate {
-            let instructions = <I as EnumerativeBackend<I>>::enumerate_all(&registers, &immediates);
+            let instructions =
+                <I as EnumerativeBackend<I>>::enumerate_for_config(config, &registers, &immediates);
             self.candidate_pool = Some(CandidatePool {
                 registers,
                 immediates,
+                max_immediate_bits: config.max_immediate_bits,
                 instructions,
             });
         }
@@ -431,14 +504,42 @@ fn evaluate_candidate<I>(
         candidate.push(t);
     }
     let candidate_cost = <I as EnumerativeBackend<I>>::sequence_cost(&candidate, config);
-    shared.candidates_evaluated.fetch_add(1, Ordering::Relaxed);
-    if candidate_cost >= shared.best_cost.load(Ordering::Acquire) {
+    // Claim a slot in the `max_candidates` budget; once it is spent, stop
+    // every worker without counting this candidate.
+    let within_budget = shared
+        .candidates_evaluated
+        .try_update(Ordering::Relaxed, Ordering::Relaxed, |evaluated| {
+            config
+                .max_candidates
+                .is_none_or(|max| evaluated < max)
+                .then_some(evaluated + 1)
+        })
+        .is_ok();
+    if !within_budget {
+        shared.stop.store(true, Ordering::Relaxed);
+        return;
+    }
+    if candidate_cost >= shared.best_cost.load(Ordering::Acquire)
+        || config.exceeds_max_bytes(<I as EnumerativeBackend<I>>::encoded_length(&candidate))
+    {
         shared
             .candidates_pruned_by_cost
             .fetch_add(1, Ordering::Relaxed);
         return;
     }
+    // A renaming of an already verified candidate gets the same verdict; a
+    // renaming of an improvement is cost-pruned above anyway.
+    let key = <I as EnumerativeBackend<I>>::dedup_key(&candidate, live_out);
+    if !shared
+        .verified
+        .lock()
+        .expect("verified mutex poisoned")
+        .insert(key)
+    {
+        return;
+    }
     if verify_candidate::<I>(target, &candidate, live_out, config, shared, start) {
+        search_event!(info; "Found improvement: {} (cost {})", opcode_summary(&candidate), candidate_cost);
         shared.record_improvement(candidate, candidate_cost);
     }
 }
@@ -482,7 +583,10 @@ fn length_cost_lower_bound(
     terminator_cost: u64,
 ) -> u64 {
     match metric {
-        CostMetric::Latency => {
+        // `Composite` has a latency term, and any non-empty sequence costs at
+        // least as much as its cheapest member would alone, so the latency
+        // bound holds for it too.
+        CostMetric::Latency | CostMetric::CustomLatency(_) | CostMetric::Composite(..) => {
             // Critical-path cost: the cheapest non-empty sequence's critical
             // path is the minimum single-instruction latency over the pool and
             // the pinned terminator. Never grows with `length` and never
@@ -496,6 +600,16 @@ fn length_cost_lower_bound(
         CostMetric::InstructionCount | CostMetric::CodeSize => min_instruction_cost
             .saturating_mul(length as u64)
             .saturating_add(terminator_cost),
+        // `Clobbers` is a per-instruction sum plus a sequence-level register
+        // term that singleton costs over-count (a register written twice
+        // counts once). Flooring to the per-instruction weight drops that
+        // term and keeps the bound valid.
+        CostMetric::Clobbers(_) => {
+            let floor = |cost: u64| cost - cost % CLOBBER_INSTRUCTION_WEIGHT;
+            floor(min_instruction_cost)
+                .saturating_mul(length as u64)
+                .saturating_add(floor(terminator_cost))
+        }
     }
 }
 
@@ -514,7 +628,7 @@ fn run_length_one<I>(
         if shared.stop.load(Ordering::Relaxed) {
             return;
         }
-        if EnumerativeSearch::<I>::timed_out(start, config.timeout) {
+        if EnumerativeSearch::<I>::should_stop(start, config) {
             shared.stop.store(true, Ordering::Relaxed);
             return;
         }
@@ -549,7 +663,7 @@ fn run_length_two<I>(
             return;
         }
         // Let idle workers stop before claiming a new outer-loop item.
-        if EnumerativeSearch::<I>::timed_out(start, config.timeout) {
+        if EnumerativeSearch::<I>::should_stop(start, config) {
             shared.stop.store(true, Ordering::Relaxed);
             return;
         }
@@ -557,7 +671,7 @@ fn run_length_two<I>(
             if shared.stop.load(Ordering::Relaxed) {
                 return;
             }
-            if EnumerativeSearch::<I>::timed_out(start, config.timeout) {
+            if EnumerativeSearch::<I>::should_stop(start, config) {
                 shared.stop.store(true, Ordering::Relaxed);
                 return;
             }
@@ -596,38 +710,33 @@ where
         if self.shared.stop.load(Ordering::Relaxed) {
             return true;
         }
-        if EnumerativeSearch::<I>::timed_out(self.start, self.config.timeout) {
+        if EnumerativeSearch::<I>::should_stop(self.start, self.config) {
             self.shared.stop.store(true, Ordering::Relaxed);
             return true;
         }
         false
     }
 
-    fn enumerate_suffix(&self, candidate: &mut Vec<I::Instruction>) {
-        if self.stop_if_timed_out() {
-            return;
-        }
-
-        if candidate.len() == self.length {
+    fn enumerate_suffixes(&self, first: I::Instruction) {
+        // Stream the suffixes rather than materialising the pool^(length-1)
+        // product; only one candidate is alive per worker at a time.
+        for suffix in CandidateIterator::new(self.all_instructions, self.length - 1) {
+            if self.stop_if_timed_out() {
+                return;
+            }
+            let mut candidate =
+                Vec::with_capacity(self.length + usize::from(self.terminator.is_some()));
+            candidate.push(first);
+            candidate.extend(suffix);
             evaluate_candidate::<I>(
                 self.target,
                 self.live_out,
                 self.config,
-                candidate.clone(),
+                candidate,
                 self.terminator,
                 self.shared,
                 self.start,
             );
-            return;
-        }
-
-        for instr in self.all_instructions {
-            if self.stop_if_timed_out() {
-                return;
-            }
-            candidate.push(*instr);
-            self.enumerate_suffix(candidate);
-            candidate.pop();
         }
     }
 }
@@ -645,10 +754,7 @@ where
             return;
         }
 
-        let mut candidate =
-            Vec::with_capacity(context.length + usize::from(context.terminator.is_some()));
-        candidate.push(*instr);
-        context.enumerate_suffix(&mut candidate);
+        context.enumerate_suffixes(*instr);
     });
 }
 
@@ -673,10 +779,17 @@ where
     ) -> Self::Result {
         self.reset();
         let start = Instant::now();
+        let config = &config
+            .clone()
+            .with_cost_metric(<I as EnumerativeBackend<I>>::bind_live_out(
+                config.cost_metric,
+                live_out,
+            ));
 
         let original_cost = <I as EnumerativeBackend<I>>::sequence_cost(target, config);
         self.statistics.original_cost = original_cost;
         self.statistics.best_cost_found = original_cost;
+        self.statistics.cost_metric = config.cost_metric;
 
         if target.len() < 2 {
             self.statistics.elapsed_time = start.elapsed();
@@ -705,7 +818,7 @@ where
             // once a length cannot beat the current best no longer length can
             // either — break out instead of scanning the rest.
             for length in 1..target.len() {
-                if Self::timed_out(start, config.timeout) || s.stop.load(Ordering::Relaxed) {
+                if Self::should_stop(start, config) || s.stop.load(Ordering::Relaxed) {
                     break;
                 }
                 let Some(min_instruction_cost) = min_instruction_cost else {
@@ -766,7 +879,9 @@ where
                     // `search()`'s non-`Result` return type. The user gets
                     // unbounded parallelism instead of zero — better than a
                     // panic for a CLI tool.
-                    eprintln!(
+                    search_event!(
+                        warn,
+                        true;
                         "warning: failed to build private rayon pool with {} thread(s) ({}); falling back to global pool",
                         n, e
                     );
@@ -796,7 +911,12 @@ where
             Some(seq) => {
                 self.statistics.best_cost_found =
                     <I as EnumerativeBackend<I>>::sequence_cost(&seq, config);
-                SearchResultFor::with_optimization(target.to_vec(), seq, self.statistics.clone())
+                SearchResultFor::with_optimization(
+                    target.to_vec(),
+                    seq,
+                    self.statistics.clone(),
+                    |seq| <I as EnumerativeBackend<I>>::sequence_cost(seq, config),
+                )
             }
             None => SearchResultFor::no_optimization(target.to_vec(), self.statistics.clone()),
         }
@@ -820,6 +940,7 @@ mod tests {
 
     use crate::ir::{Instruction, Operand, Register};
     use crate::isa::{ISA, ISAMutator, InstructionType, OperandType, RegisterType, U64};
+    use crate::search::config::MutationWeights;
 
     std::thread_local! {
         static LENGTH_TWO_COST_CALLS: std::cell::Cell<u64> =
@@ -1508,6 +1629,58 @@ mod tests {
         assert_eq!(shared.candidates_passed_fast.load(Ordering::Relaxed), 0);
     }
 
+    #[test]
+    fn run_length_one_verifies_each_dedup_key_once() {
+        let _guard = set_verify_stats_result(VERIFY_STATS_NOT_EQUIVALENT, true);
+        let target = [VerifyStatsInstruction(1), VerifyStatsInstruction(2)];
+        let all_instructions = [VerifyStatsInstruction(0), VerifyStatsInstruction(0)];
+        let config = SearchConfig::default().with_timeout_option(None);
+        let shared = SharedState::<VerifyStatsIsa>::new(u64::MAX);
+
+        run_length_one::<VerifyStatsIsa>(
+            &target,
+            &(),
+            &config,
+            &all_instructions,
+            None,
+            &shared,
+            Instant::now(),
+        );
+
+        assert_eq!(shared.candidates_evaluated.load(Ordering::Relaxed), 2);
+        assert_eq!(VERIFY_STATS_CHECKS.load(AtomicOrdering::SeqCst), 1);
+    }
+
+    #[test]
+    fn aarch64_dedup_key_collapses_scratch_register_renamings() {
+        // mov xN, #1; add x0, x0, xN for two scratch choices of xN.
+        let with_scratch = |scratch| {
+            vec![
+                Instruction::MovImm {
+                    rd: scratch,
+                    imm: 1,
+                },
+                Instruction::Add {
+                    rd: Register::X0,
+                    rn: Register::X0,
+                    rm: Operand::Register(scratch),
+                },
+            ]
+        };
+        let live_out = LiveOut::from_registers(vec![Register::X0]);
+
+        assert_eq!(
+            <AArch64 as EnumerativeBackend<AArch64>>::dedup_key(
+                &with_scratch(Register::X1),
+                &live_out
+            ),
+            <AArch64 as EnumerativeBackend<AArch64>>::dedup_key(
+                &with_scratch(Register::X2),
+                &live_out
+            ),
+        );
+    }
+
     #[test]
     fn search_drains_cost_pruned_candidate_count() {
         let _guard = set_verify_stats_result(VERIFY_STATS_NOT_EQUIVALENT, false);
@@ -1527,6 +1700,71 @@ mod tests {
         assert_eq!(result.statistics.candidates_passed_fast, 0);
     }
 
+    #[test]
+    fn max_candidates_caps_the_evaluated_count() {
+        // mov x0, x1; add x0, x0, #1
+        let target = vec![
+            Instruction::MovReg {
+                rd: Register::X0,
+                rn: Register::X1,
+            },
+            Instruction::Add {
+                rd: Register::X0,
+                rn: Register::X0,
+                rm: Operand::Immediate(1),
+            },
+        ];
+        let live_out = LiveOut::from_registers(vec![Register::X0]);
+        let mut unbounded = EnumerativeSearch::<AArch64>::new();
+        let full = unbounded.search(&target, &live_out, &small_config());
+        assert!(full.statistics.candidates_evaluated > 1_000);
+
+        // `add x0, x1, #1` comes up within the first thousand candidates.
+        let config = small_config().with_max_candidates(1_000);
+        let mut search = EnumerativeSearch::<AArch64>::new();
+        let result = search.search(&target, &live_out, &config);
+
+        assert_eq!(result.statistics.candidates_evaluated, 1_000);
+        let optimized = result
+            .optimized_sequence
+            .expect("the capped run keeps the rewrite it found");
+        assert_eq!(
+            optimized,
+            vec![Instruction::Add {
+                rd: Register::X0,
+                rn: Register::X1,
+                rm: Operand::Immediate(1),
+            }]
+        );
+        assert_eq!(result.statistics.best_cost_found, 1);
+    }
+
+    #[test]
+    fn stochastic_mutation_weights_do_not_block_enumeration() {
+        // mov x0, x1; mov x0, x1 collapses to one mov.
+        let target = vec![
+            Instruction::MovReg {
+                rd: Register::X0,
+                rn: Register::X1,
+            };
+            2
+        ];
+        let live_out = LiveOut::from_registers(vec![Register::X0]);
+        let mut config = small_config();
+        config.stochastic.mutation_weights = MutationWeights {
+            operand: 0.0,
+            opcode: 0.0,
+            swap: 0.0,
+            instruction: 0.0,
+            canonicalize: 0.0,
+        };
+
+        let mut search = EnumerativeSearch::<AArch64>::new();
+        let result = search.search(&target, &live_out, &config);
+
+        assert!(result.found_optimization);
+    }
+
     fn small_config() -> SearchConfig {
         // Tight register/immediate pool so unit tests run fast.
         //
@@ -2539,6 +2777,55 @@ mod tests {
         );
     }
 
+    #[test]
+    fn max_bytes_prunes_multi_word_mov_before_verification() {
+        // movz x0, #0x2345; movk x0, #1, lsl #16; add x0, x0, #1 is 12 bytes.
+        // `mov x0, #0x12346` is cheaper but still a two-word MOVZ/MOVK chain,
+        // so it cannot land in a 4-byte budget.
+        let target = vec![
+            Instruction::MovZ {
+                rd: Register::X0,
+                imm: 0x2345,
+                shift: 0,
+            },
+            Instruction::MovK {
+                rd: Register::X0,
+                imm: 1,
+                shift: 16,
+            },
+            Instruction::Add {
+                rd: Register::X0,
+                rn: Register::X0,
+                rm: Operand::Immediate(1),
+            },
+        ];
+        let live_out = LiveOut::from_registers(vec![Register::X0]);
+        let config = SearchConfig::default()
+            .with_registers(vec![Register::X0])
+            .with_immediates(vec![0x1_2346])
+            .with_max_immediate_bits(32)
+            .with_timeout(Duration::from_secs(30));
+
+        let unbounded =
+            EnumerativeSearch::<crate::isa::AArch64>::new().search(&target, &live_out, &config);
+        assert_eq!(
+            unbounded.optimized_sequence,
+            Some(vec![Instruction::MovImm {
+                rd: Register::X0,
+                imm: 0x1_2346,
+            }])
+        );
+
+        let tight = EnumerativeSearch::<crate::isa::AArch64>::new().search(
+            &target,
+            &live_out,
+            &config.with_max_bytes(4),
+        );
+        assert!(!tight.found_optimization);
+        assert_eq!(tight.statistics.smt_queries, 0);
+        assert!(tight.statistics.candidates_pruned_by_cost > 0);
+    }
+
     #[test]
     fn with_config_preserves_aarch64_single_add_collapse() {
         let target = vec![
@@ -2757,6 +3044,17 @@ mod tests {
             length_cost_lower_bound(&CostMetric::InstructionCount, 5, 1, 1),
             6
         );
+        // Clobbers drops the register term singleton costs carry: three
+        // writes to one register cost 3 * 128 + 1, below 3 * (128 + 1).
+        assert_eq!(
+            length_cost_lower_bound(
+                &CostMetric::Clobbers(crate::semantics::cost::LiveRegisterMask::EMPTY),
+                3,
+                129,
+                0
+            ),
+            3 * CLOBBER_INSTRUCTION_WEIGHT
+        );
     }
 
     proptest::proptest! {
@@ -16,6 +16,8 @@ use crate::ir::Instruction;
 use crate::search::SearchAlgorithm;
 use crate::search::config::SearchConfig;
 use crate::search::result::{SearchResult, SearchStatistics};
+use crate::search::trace::search_event;
+use crate::semantics::cost::CostMetric;
 use crate::semantics::live_out::LiveOut;
 use crate::validation::live_out::compute_live_in_registers;
 
@@ -127,20 +129,14 @@ impl SearchAlgorithm<crate::isa::AArch64> for LlmSearch {
 
         for call_idx in 0..max_calls {
             let Some(remaining) = remaining_until(started, timeout, deadline) else {
-                if config.verbose {
-                    eprintln!("llm-search: timeout after {} calls", call_idx);
-                }
+                search_event!(info, config.verbose; "llm-search: timeout after {} calls", call_idx);
                 break;
             };
 
-            if config.verbose {
-                eprintln!(
-                    "llm-search: [{:>2}/{}] calling codex (elapsed {:.2}s)",
+            search_event!(debug, config.verbose; "llm-search: [{:>2}/{}] calling codex (elapsed {:.2}s)",
                     call_idx + 1,
                     max_calls,
-                    started.elapsed().as_secs_f64()
-                );
-            }
+                    started.elapsed().as_secs_f64());
             let call_start = Instant::now();
             let codex_result = invoke_codex(&config.llm, &prompt, OUTPUT_SCHEMA, remaining);
             let codex_elapsed = call_start.elapsed();
@@ -148,29 +144,21 @@ impl SearchAlgorithm<crate::isa::AArch64> for LlmSearch {
             timings.codex_time += codex_elapsed;
             let raw = match codex_result {
                 Ok(s) => {
-                    if config.verbose {
-                        eprintln!(
-                            "llm-search: [{:>2}/{}]   ← codex returned in {:.2}s",
+                    search_event!(debug, config.verbose; "llm-search: [{:>2}/{}]   ← codex returned in {:.2}s",
                             call_idx + 1,
                             max_calls,
-                            codex_elapsed.as_secs_f64()
-                        );
-                    }
+                            codex_elapsed.as_secs_f64());
                     // Codex produced a candidate; this is the moment we count
                     // it as "evaluated" — Codex IO errors above don't.
                     stats.candidates_evaluated += 1;
                     s
                 }
                 Err(e) => {
-                    if config.verbose {
-                        eprintln!(
-                            "llm-search: [{:>2}/{}]   ✗ codex error after {:.2}s: {}",
+                    search_event!(debug, config.verbose; "llm-search: [{:>2}/{}]   ✗ codex error after {:.2}s: {}",
                             call_idx + 1,
                             max_calls,
                             codex_elapsed.as_secs_f64(),
-                            e
-                        );
-                    }
+                            e);
                     continue;
                 }
             };
@@ -178,12 +166,8 @@ impl SearchAlgorithm<crate::isa::AArch64> for LlmSearch {
             let Some(verify_remaining) = remaining_until(started, timeout, deadline)
                 .and_then(|remaining| verification_timeout_for_remaining(config, remaining))
             else {
-                if config.verbose {
-                    eprintln!(
-                        "llm-search: SMT disabled or budget exhausted before verifying candidate on call {}",
-                        call_idx
-                    );
-                }
+                search_event!(info, config.verbose; "llm-search: SMT disabled or budget exhausted before verifying candidate on call {}",
+                        call_idx);
                 break;
             };
             let verify_start = Instant::now();
@@ -207,14 +191,10 @@ impl SearchAlgorithm<crate::isa::AArch64> for LlmSearch {
             }
             match outcome {
                 IterationOutcome::Success(seq) => {
-                    if config.verbose {
-                        eprintln!(
-                            "llm-search: success on call {} ({} -> {} instructions)",
+                    search_event!(info, config.verbose; "llm-search: success on call {} ({} -> {} instructions)",
                             call_idx,
                             target.len(),
-                            seq.len()
-                        );
-                    }
+                            seq.len());
                     stats.smt_equivalent += 1;
                     stats.candidates_passed_fast += 1;
                     stats.improvements_found += 1;
@@ -228,45 +208,39 @@ impl SearchAlgorithm<crate::isa::AArch64> for LlmSearch {
                     for m in &unsupported_mnemonics {
                         ledger.record(m);
                     }
-                    if config.verbose {
-                        if unsupported_mnemonics.is_empty() {
-                            eprintln!(
-                                "llm-search: parse-fail on call {} (operand or encoding error; \
-                                 no unknown mnemonics)",
-                                call_idx
-                            );
-                        } else {
-                            eprintln!(
-                                "llm-search: parse-fail on call {} ({} unsupported mnemonic{})",
-                                call_idx,
-                                unsupported_mnemonics.len(),
-                                if unsupported_mnemonics.len() == 1 {
-                                    ""
-                                } else {
-                                    "s"
-                                }
-                            );
-                        }
+                    if unsupported_mnemonics.is_empty() {
+                        search_event!(
+                            debug,
+                            config.verbose;
+                            "llm-search: parse-fail on call {} (operand or encoding error; \
+                             no unknown mnemonics)",
+                            call_idx
+                        );
+                    } else {
+                        search_event!(
+                            debug,
+                            config.verbose;
+                            "llm-search: parse-fail on call {} ({} unsupported mnemonic{})",
+                            call_idx,
+                            unsupported_mnemonics.len(),
+                            if unsupported_mnemonics.len() == 1 {
+                                ""
+                            } else {
+                                "s"
+                            }
+                        );
                     }
                 }
                 IterationOutcome::NotShorter { candidate_len } => {
                     stats.candidates_pruned_by_cost += 1;
-                    if config.verbose {
-                        eprintln!(
-                            "llm-search: not-shorter on call {} (got {} instructions)",
-                            call_idx, candidate_len
-                        );
-                    }
+                    search_event!(debug, config.verbose; "llm-search: not-shorter on call {} (got {} instructions)",
+                            call_idx, candidate_len);
                 }
                 IterationOutcome::EquivFail => {
-                    if config.verbose {
-                        eprintln!("llm-search: equiv-fail on call {}", call_idx);
-                    }
+                    search_event!(debug, config.verbose; "llm-search: equiv-fail on call {}", call_idx);
                 }
                 IterationOutcome::EquivUnknown => {
-                    if config.verbose {
-                        eprintln!("llm-search: equiv-unknown on call {}", call_idx);
-                    }
+                    search_event!(debug, config.verbose; "llm-search: equiv-unknown on call {}", call_idx);
                 }
             }
         }
@@ -277,7 +251,12 @@ impl SearchAlgorithm<crate::isa::AArch64> for LlmSearch {
         self.last_timings = timings;
 
         match found {
-            Some(seq) => SearchResult::with_optimization(target.to_vec(), seq, stats),
+            Some(seq) => SearchResult::with_optimization(
+                target.to_vec(),
+                seq,
+                stats,
+                &CostMetric::InstructionCount,
+            ),
             None => SearchResult::no_optimization(target.to_vec(), stats),
         }
     }
@@ -10,19 +10,27 @@ pub mod candidate;
 pub mod config;
 pub mod enumerative;
 pub mod llm;
+pub mod normalize;
+pub mod optimize;
 pub mod parallel;
 pub mod result;
 pub mod stochastic;
+#[cfg(feature = "z3")]
 pub mod symbolic;
+mod trace;
 
 #[allow(unused_imports)]
-pub use config::{Algorithm, SearchConfig, StochasticConfig, SymbolicConfig};
+pub use config::{
+    Algorithm, Sampling, SearchConfig, StochasticConfig, SymbolicConfig, UnknownPolicy,
+};
 pub use enumerative::EnumerativeSearch;
+pub use optimize::{optimize_sequence, optimize_sequence_parallel};
 #[allow(unused_imports)]
 pub use parallel::{ParallelConfig, ParallelResult, run_parallel_search};
 #[allow(unused_imports)]
-pub use result::{SearchResult, SearchStatistics};
+pub use result::{SearchResult, SearchStatistics, ThroughputSummary};
 pub use stochastic::StochasticSearch;
+#[cfg(feature = "z3")]
 pub use symbolic::SymbolicSearch;
 
 use crate::isa::ISA;
@@ -70,6 +78,7 @@ mod tests {
         fn assert_impl<I: ISA, A: SearchAlgorithm<I>>() {}
         assert_impl::<crate::isa::AArch64, enumerative::EnumerativeSearch>();
         assert_impl::<crate::isa::AArch64, stochastic::StochasticSearch>();
+        #[cfg(feature = "z3")]
         assert_impl::<crate::isa::AArch64, symbolic::SymbolicSearch>();
         assert_impl::<crate::isa::AArch64, llm::LlmSearch>();
         // x86_64 and x86_32 instantiations land via this PR (issue #73).
@@ -77,8 +86,10 @@ mod tests {
         assert_impl::<crate::isa::X86_64, enumerative::EnumerativeSearch<crate::isa::X86_64>>();
         assert_impl::<crate::isa::X86_32, enumerative::EnumerativeSearch<crate::isa::X86_32>>();
         assert_impl::<crate::isa::X86_64, stochastic::StochasticSearch<crate::isa::X86_64>>();
+        #[cfg(feature = "z3")]
         assert_impl::<crate::isa::X86_64, symbolic::SymbolicSearch<crate::isa::X86_64>>();
         assert_impl::<crate::isa::X86_32, stochastic::StochasticSearch<crate::isa::X86_32>>();
+        #[cfg(feature = "z3")]
         assert_impl::<crate::isa::X86_32, symbolic::SymbolicSearch<crate::isa::X86_32>>();
 
         fn assert_register_set_live_out<I, A>()
@@ -91,6 +102,7 @@ mod tests {
             crate::isa::X86_64,
             stochastic::StochasticSearch<crate::isa::X86_64>,
         >();
+        #[cfg(feature = "z3")]
         assert_register_set_live_out::<
             crate::isa::X86_64,
             symbolic::SymbolicSearch<crate::isa::X86_64>,
@@ -99,6 +111,7 @@ mod tests {
             crate::isa::X86_32,
             stochastic::StochasticSearch<crate::isa::X86_32>,
         >();
+        #[cfg(feature = "z3")]
         assert_register_set_live_out::<
             crate::isa::X86_32,
             symbolic::SymbolicSearch<crate::isa::X86_32>,
@@ -0,0 +1,545 @@
+//! Register-renaming normalization for alpha-equivalent sequences.
+//!
+//! Two candidate sequences that differ only in which scratch register
+//! carries an intermediate value compute the same live-out state, yet the
+//! search treats them as distinct points. `normalize_registers` rewrites
+//! every scratch register to a canonical name so such sequences collapse to
+//! one representative that can be used as a dedup / cache key.
+//!
+//! A register is *scratch* when it is a general-purpose X register, it is
+//! not live-out, and its first appearance in the sequence is a write. Any
+//! register read before it is written is a live-in input and keeps its name,
+//! as do live-out registers, SP, XZR and vector registers.
+//!
+//! `canonicalize` is the per-instruction counterpart: it rewrites
+//! instructions that have several equivalent spellings (currently the
+//! conditional-select family) to one preferred form, so the search reports
+//! the same answer regardless of which spelling it happened to reach. A
+//! select on an always-true condition (`al`/`nv`) is really a register move
+//! and is spelled `mov`, and a commutative operation lists its lower-numbered
+//! source register first.
+//!
+//! `drop_overwritten_writes` is the one rewrite here that shortens a
+//! sequence: it deletes an instruction whose every result the very next
+//! instruction overwrites without reading (`mov x0, #1; mov x0, #2`). It is
+//! kept apart from `canonicalize` because the stochastic canonicalize move
+//! relies on the sequence length staying fixed; the library entry points and
+//! the `opt` CLI run it on the target before searching, through
+//! `optimize::search_without_overwritten_writes`, which reports the shorter
+//! sequence only once SMT proves it equivalent.
+
+use crate::ir::{Condition, Instruction, Register};
+use crate::semantics::live_out::LiveOut;
+use std::collections::{HashMap, HashSet};
+
+/// Rename the scratch registers of `seq` to canonical names.
+///
+/// Scratch registers are renamed in first-definition order onto the
+/// lowest-numbered X registers that the sequence does not otherwise use and
+/// that are not live-out. The result is semantically equivalent to `seq`
+/// under `live_out`, and two sequences that differ only by a consistent
+/// renaming of scratch registers normalize to the same sequence.
+pub fn normalize_registers(seq: &[Instruction], live_out: &LiveOut) -> Vec<Instruction> {
+    let mut seen = HashSet::new();
+    let mut scratch = Vec::new();
+    for instr in seq {
+        for reg in instr.source_registers() {
+            seen.insert(reg);
+        }
+        for reg in instr.destinations() {
+            if seen.insert(reg) && is_renamable(reg) && !live_out.contains(reg) {
+                scratch.push(reg);
+            }
+        }
+    }
+    if scratch.is_empty() {
+        return seq.to_vec();
+    }
+
+    let pinned: HashSet<Register> = seen
+        .into_iter()
+        .filter(|reg| !scratch.contains(reg))
+        .collect();
+    let pool = (0..=30)
+        .filter_map(Register::from_index)
+        .filter(|reg| !pinned.contains(reg) && !live_out.contains(*reg));
+    let renaming: HashMap<Register, Register> = scratch.into_iter().zip(pool).collect();
+
+    seq.iter()
+        .map(|instr| instr.map_registers(|reg| renaming.get(&reg).copied().unwrap_or(reg)))
+        .collect()
+}
+
+/// Rewrite every instruction of `seq` to its canonical spelling. The result
+/// computes the same state as `seq` instruction by instruction, so it is
+/// equivalent under any live-out contract.
+pub fn canonicalize(seq: &[Instruction]) -> Vec<Instruction> {
+    seq.iter().map(canonicalize_instruction).collect()
+}
+
+/// Canonical spelling of a single instruction.
+///
+/// Any conditional select on `al`/`nv` becomes `mov rd, rn`: both
+/// conditions always hold, so the else-arm (and whatever it would do to `rm`)
+/// is dead. Otherwise conditional selects prefer `csel` over
+/// `csinc`/`csinv`/`csneg` whenever the else-arm transform cannot matter, and
+/// prefer the un-inverted member of each condition pair (`eq` over `ne`, `cs`
+/// over `cc`, ...) by swapping the `csel` operands. Commutative operations
+/// ([`Instruction::swap_operands`]) put the source register with the lower
+/// [`Register::sort_key`] in `rn`.
+pub fn canonicalize_instruction(instr: &Instruction) -> Instruction {
+    match *instr {
+        Instruction::Csel { rd, rn, cond, .. }
+        | Instruction::Csinc { rd, rn, cond, .. }
+        | Instruction::Csinv { rd, rn, cond, .. }
+        | Instruction::Csneg { rd, rn, cond, .. }
+            if is_always(cond) =>
+        {
+            Instruction::MovReg { rd, rn }
+        }
+        // -0 == 0: negating XZR is a plain select of XZR.
+        Instruction::Csneg {
+            rd,
+            rn,
+            rm: Register::XZR,
+            cond,
+        } => canonical_csel(rd, rn, Register::XZR, cond),
+        Instruction::Csel { rd, rn, rm, cond } => canonical_csel(rd, rn, rm, cond),
+        other => match other.swap_operands() {
+            Some(swapped) if first_source_key(&swapped) < first_source_key(&other) => swapped,
+            _ => other,
+        },
+    }
+}
+
+/// Delete every instruction whose results are all overwritten by the
+/// instruction right after it, with no read in between.
+///
+/// Unlike the backward liveness sweep of the equivalence checker this needs
+/// no live-out contract: the dropped write is unobservable in the final
+/// state, so the result is equivalent to `seq` under any live-out. Only
+/// adjacent pairs are considered, repeatedly, so `mov x0, #1; mov x0, #2;
+/// mov x0, #3` collapses to its last instruction. Memory operations,
+/// terminators and instructions without a register destination are never
+/// dropped, and a flag write is only dropped when the next instruction
+/// overwrites the flags without reading them.
+pub fn drop_overwritten_writes(seq: &[Instruction]) -> Vec<Instruction> {
+    let mut kept: Vec<Instruction> = Vec::with_capacity(seq.len());
+    for instr in seq {
+        while kept
+            .last()
+            .is_some_and(|previous| is_overwritten_by(previous, instr))
+        {
+            kept.pop();
+        }
+        kept.push(*instr);
+    }
+    kept
+}
+
+/// Whether `next` overwrites every result of `previous` without reading it.
+fn is_overwritten_by(previous: &Instruction, next: &Instruction) -> bool {
+    let written = previous.destinations();
+    if written.is_empty() || previous.is_memory_op() || previous.is_terminator() {
+        return false;
+    }
+    let overwritten = next.destinations();
+    let sources = next.source_registers();
+    written
+        .iter()
+        .all(|reg| overwritten.contains(reg) && !sources.contains(reg))
+        && (!previous.modifies_flags() || (next.modifies_flags() && !next.reads_flags()))
+}
+
+/// Sort key of the `rn` source, the register [`Instruction::swap_operands`]
+/// moves.
+fn first_source_key(instr: &Instruction) -> Option<u16> {
+    instr.source_registers().first().map(|reg| reg.sort_key())
+}
+
+fn canonical_csel(rd: Register, rn: Register, rm: Register, cond: Condition) -> Instruction {
+    if is_inverted(cond) {
+        Instruction::Csel {
+            rd,
+            rn: rm,
+            rm: rn,
+            cond: cond.invert(),
+        }
+    } else {
+        Instruction::Csel { rd, rn, rm, cond }
+    }
+}
+
+fn is_always(cond: Condition) -> bool {
+    matches!(cond, Condition::AL | Condition::NV)
+}
+
+/// The odd-encoded member of each complementary condition pair.
+fn is_inverted(cond: Condition) -> bool {
+    matches!(
+        cond,
+        Condition::NE
+            | Condition::CC
+            | Condition::PL
+            | Condition::VC
+            | Condition::LS
+            | Condition::LT
+            | Condition::LE
+    )
+}
+
+/// X0..X30 only: XZR, SP and vector registers are never renamed.
+fn is_renamable(reg: Register) -> bool {
+    matches!(reg.index(), Some(index) if index <= 30)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::Operand;
+    use crate::test_utils::sequence_fixtures::x0_live;
+
+    fn copy_then_increment(scratch: Register) -> Vec<Instruction> {
+        vec![
+            Instruction::MovReg {
+                rd: scratch,
+                rn: Register::X1,
+            },
+            Instruction::Add {
+                rd: Register::X0,
+                rn: scratch,
+                rm: Operand::Immediate(1),
+            },
+        ]
+    }
+
+    #[test]
+    fn alpha_equivalent_sequences_normalize_identically() {
+        let with_x3 = copy_then_increment(Register::X3);
+        let with_x2 = copy_then_increment(Register::X2);
+        assert_ne!(with_x3, with_x2);
+
+        let live_out = x0_live();
+        assert_eq!(
+            normalize_registers(&with_x3, &live_out),
+            normalize_registers(&with_x2, &live_out)
+        );
+        // X0 is live-out and X1 is live-in, so the scratch lands on X2.
+        assert_eq!(normalize_registers(&with_x3, &live_out), with_x2);
+    }
+
+    #[test]
+    fn live_out_scratch_is_not_renamed() {
+        let seq = copy_then_increment(Register::X3);
+        let live_out = LiveOut::from_registers(vec![Register::X0, Register::X3]);
+        assert_eq!(normalize_registers(&seq, &live_out), seq);
+    }
+
+    #[test]
+    fn live_in_registers_keep_their_names() {
+        // X4 is read before any write, so it is an input and must not move.
+        let seq = vec![Instruction::Add {
+            rd: Register::X0,
+            rn: Register::X4,
+            rm: Operand::Register(Register::X5),
+        }];
+        assert_eq!(normalize_registers(&seq, &x0_live()), seq);
+    }
+
+    #[test]
+    fn scratch_never_lands_on_unwritten_live_out_register() {
+        // X1 is live-out but untouched; renaming X7 onto X1 would clobber it.
+        let seq = vec![
+            Instruction::MovImm {
+                rd: Register::X7,
+                imm: 5,
+            },
+            Instruction::Add {
+                rd: Register::X0,
+                rn: Register::X7,
+                rm: Operand::Immediate(1),
+            },
+        ];
+        let live_out = LiveOut::from_registers(vec![Register::X0, Register::X1]);
+        let normalized = normalize_registers(&seq, &live_out);
+        assert_eq!(normalized[0].destination(), Some(Register::X2));
+    }
+
+    #[test]
+    fn multiple_scratches_follow_first_definition_order() {
+        let seq = vec![
+            Instruction::MovImm {
+                rd: Register::X9,
+                imm: 1,
+            },
+            Instruction::MovImm {
+                rd: Register::X4,
+                imm: 2,
+            },
+            Instruction::Add {
+                rd: Register::X0,
+                rn: Register::X9,
+                rm: Operand::Register(Register::X4),
+            },
+        ];
+        let normalized = normalize_registers(&seq, &x0_live());
+        assert_eq!(normalized[0].destination(), Some(Register::X1));
+        assert_eq!(normalized[1].destination(), Some(Register::X2));
+        assert_eq!(
+            normalized[2],
+            Instruction::Add {
+                rd: Register::X0,
+                rn: Register::X1,
+                rm: Operand::Register(Register::X2),
+            }
+        );
+        assert_eq!(normalize_registers(&normalized, &x0_live()), normalized);
+    }
+
+    fn assert_canonical_form_verifies(original: Instruction, expected: Instruction) {
+        use crate::semantics::{
+            EquivalenceConfig, EquivalenceResult, check_equivalence_with_config,
+        };
+
+        let canonical = canonicalize(&[original]);
+        assert_eq!(canonical, vec![expected], "canonicalizing {original}");
+        assert_eq!(canonicalize(&canonical), canonical, "not idempotent");
+        let config = EquivalenceConfig::with_live_out(x0_live());
+        assert_eq!(
+            check_equivalence_with_config(&[original], &canonical, &config),
+            EquivalenceResult::Equivalent,
+            "{original} vs {}",
+            canonical[0]
+        );
+    }
+
+    #[test]
+    fn always_true_conditional_selects_canonicalize_to_mov() {
+        let mov = Instruction::MovReg {
+            rd: Register::X0,
+            rn: Register::X1,
+        };
+        for cond in [Condition::AL, Condition::NV] {
+            for original in [
+                Instruction::Csel {
+                    rd: Register::X0,
+                    rn: Register::X1,
+                    rm: Register::X2,
+                    cond,
+                },
+                Instruction::Csinc {
+                    rd: Register::X0,
+                    rn: Register::X1,
+                    rm: Register::X2,
+                    cond,
+                },
+                Instruction::Csinv {
+                    rd: Register::X0,
+                    rn: Register::X1,
+                    rm: Register::X2,
+                    cond,
+                },
+                Instruction::Csneg {
+                    rd: Register::X0,
+                    rn: Register::X1,
+                    rm: Register::XZR,
+                    cond,
+                },
+            ] {
+                assert_canonical_form_verifies(original, mov);
+            }
+        }
+    }
+
+    #[test]
+    fn always_true_csel_of_xzr_canonicalizes_to_mov_of_xzr() {
+        assert_canonical_form_verifies(
+            Instruction::Csel {
+                rd: Register::X0,
+                rn: Register::XZR,
+                rm: Register::X2,
+                cond: Condition::AL,
+            },
+            Instruction::MovReg {
+                rd: Register::X0,
+                rn: Register::XZR,
+            },
+        );
+    }
+
+    #[test]
+    fn csneg_of_xzr_canonicalizes_to_csel() {
+        assert_canonical_form_verifies(
+            Instruction::Csneg {
+                rd: Register::X0,
+                rn: Register::X1,
+                rm: Register::XZR,
+                cond: Condition::GT,
+            },
+            Instruction::Csel {
+                rd: Register::X0,
+                rn: Register::X1,
+                rm: Register::XZR,
+                cond: Condition::GT,
+            },
+        );
+    }
+
+    #[test]
+    fn csel_prefers_uninverted_condition() {
+        assert_canonical_form_verifies(
+            Instruction::Csel {
+                rd: Register::X0,
+                rn: Register::X1,
+                rm: Register::X2,
+                cond: Condition::NE,
+            },
+            Instruction::Csel {
+                rd: Register::X0,
+                rn: Register::X2,
+                rm: Register::X1,
+                cond: Condition::EQ,
+            },
+        );
+    }
+
+    #[test]
+    fn non_trivial_conditional_selects_are_untouched() {
+        let seq = vec![
+            Instruction::Csinc {
+                rd: Register::X0,
+                rn: Register::X1,
+                rm: Register::X2,
+                cond: Condition::NE,
+            },
+            Instruction::Csinv {
+                rd: Register::X0,
+                rn: Register::X1,
+                rm: Register::XZR,
+                cond: Condition::EQ,
+            },
+            Instruction::Csel {
+                rd: Register::X0,
+                rn: Register::X1,
+                rm: Register::X2,
+                cond: Condition::GT,
+            },
+        ];
+        assert_eq!(canonicalize(&seq), seq);
+    }
+
+    #[test]
+    fn commutative_operations_put_the_lower_register_first() {
+        let reversed = vec![
+            Instruction::Add {
+                rd: Register::X0,
+                rn: Register::X2,
+                rm: Operand::Register(Register::X1),
+            },
+            Instruction::Mul {
+                rd: Register::X0,
+                rn: Register::X3,
+                rm: Register::X0,
+            },
+        ];
+        assert_eq!(
+            canonicalize(&reversed),
+            vec![
+                Instruction::Add {
+                    rd: Register::X0,
+                    rn: Register::X1,
+                    rm: Operand::Register(Register::X2),
+                },
+                Instruction::Mul {
+                    rd: Register::X0,
+                    rn: Register::X0,
+                    rm: Register::X3,
+                },
+            ]
+        );
+
+        // Non-commutative operations keep their operand order.
+        let sub = vec![Instruction::Sub {
+            rd: Register::X0,
+            rn: Register::X2,
+            rm: Operand::Register(Register::X1),
+        }];
+        assert_eq!(canonicalize(&sub), sub);
+    }
+
+    fn mov_imm(rd: Register, imm: i64) -> Instruction {
+        Instruction::MovImm { rd, imm }
+    }
+
+    #[test]
+    fn adjacent_overwritten_writes_are_dropped() {
+        use crate::semantics::{
+            EquivalenceConfig, EquivalenceResult, check_equivalence_with_config,
+        };
+
+        let seq = vec![
+            mov_imm(Register::X0, 1),
+            mov_imm(Register::X0, 2),
+            Instruction::Add {
+                rd: Register::X1,
+                rn: Register::X0,
+                rm: Operand::Immediate(3),
+            },
+        ];
+        let collapsed = drop_overwritten_writes(&seq);
+        assert_eq!(collapsed, seq[1..]);
+        // The dropped write is unobservable even with every register live.
+        let config = EquivalenceConfig::drop_in_replacement();
+        assert_eq!(
+            check_equivalence_with_config(&seq, &collapsed, &config),
+            EquivalenceResult::Equivalent
+        );
+
+        let chain = vec![
+            mov_imm(Register::X0, 1),
+            mov_imm(Register::X0, 2),
+            mov_imm(Register::X0, 3),
+        ];
+        assert_eq!(drop_overwritten_writes(&chain), chain[2..]);
+    }
+
+    #[test]
+    fn writes_that_are_read_or_only_partly_overwritten_are_kept() {
+        // The add reads x0 before overwriting it.
+        let read_between = vec![
+            mov_imm(Register::X0, 1),
+            Instruction::Add {
+                rd: Register::X0,
+                rn: Register::X0,
+                rm: Operand::Immediate(2),
+            },
+        ];
+        assert_eq!(drop_overwritten_writes(&read_between), read_between);
+
+        // movk merges into the old value, so it reads x0 too.
+        let merge = vec![
+            mov_imm(Register::X0, 1),
+            Instruction::MovK {
+                rd: Register::X0,
+                imm: 2,
+                shift: 16,
+            },
+        ];
+        assert_eq!(drop_overwritten_writes(&merge), merge);
+
+        // A flag write survives when the next instruction leaves NZCV alone.
+        let flags = vec![
+            Instruction::Adds {
+                rd: Register::X0,
+                rn: Register::X1,
+                rm: Operand::Immediate(1),
+            },
+            mov_imm(Register::X0, 2),
+        ];
+        assert_eq!(drop_overwritten_writes(&flags), flags);
+
+        // Writes to different registers are independent.
+        let different = vec![mov_imm(Register::X0, 1), mov_imm(Register::X1, 2)];
+        assert_eq!(drop_overwritten_writes(&different), different);
+    }
+}
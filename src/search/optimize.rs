@@ -0,0 +1,436 @@
+//! Library front door: one call from a target sequence to a search result.
+//!
+//! [`optimize_sequence`] dispatches on [`SearchConfig::algorithm`] to the
+//! AArch64 search backends, the same way the `opt` CLI does, so embedders do
+//! not have to pick and drive a `SearchAlgorithm` implementation themselves.
+//! [`optimize_sequence_parallel`] runs the hybrid coordinator with an explicit
+//! [`ParallelConfig`].
+//!
+//! Both entry points first drop writes that the next instruction overwrites
+//! ([`drop_overwritten_writes`]), so `mov x0, #1; mov x0, #2` reaches the
+//! backend as `mov x0, #2`, and report the result against the caller's
+//! target once SMT proves it equivalent (see
+//! [`search_without_overwritten_writes`], which the `opt` CLI shares). They
+//! then answer two trivial cases without starting a search: an empty target,
+//! and a single unit-cost instruction under the instruction-count metric,
+//! whose only possible improvement is deleting it.
+
+use crate::ir::Instruction;
+use crate::isa::AArch64;
+#[cfg(feature = "z3")]
+use crate::search::SymbolicSearch;
+use crate::search::config::{Algorithm, SearchConfig};
+use crate::search::llm::LlmSearch;
+use crate::search::normalize::drop_overwritten_writes;
+use crate::search::parallel::{ParallelConfig, ParallelResult, run_parallel_search};
+use crate::search::result::{SearchResult, SearchStatistics};
+use crate::search::{EnumerativeSearch, SearchAlgorithm, StochasticSearch};
+use crate::semantics::cost::{CostMetric, sequence_cost};
+use crate::semantics::live_out::LiveOut;
+use crate::semantics::{EquivalenceConfig, EquivalenceResult, check_equivalence_with_config};
+use std::time::{Duration, Instant};
+
+/// Search for a cheaper sequence equivalent to `target` under `live_out`.
+///
+/// The backend is chosen by `config.algorithm`. `Hybrid` runs the parallel
+/// coordinator with a [`ParallelConfig`] derived from `config` (`cores`,
+/// `timeout` and the stochastic seed); the returned statistics are the
+/// cross-worker totals. Use [`optimize_sequence_parallel`] to control the
+/// coordinator directly.
+///
+/// ```
+/// use s11::ir::{Instruction, Operand, Register};
+/// use s11::search::{Algorithm, SearchConfig};
+/// use s11::semantics::LiveOut;
+/// use std::time::Duration;
+///
+/// // mov x0, x1; add x0, x0, #1  ==>  add x0, x1, #1
+/// let target = vec![
+///     Instruction::MovReg { rd: Register::X0, rn: Register::X1 },
+///     Instruction::Add {
+///         rd: Register::X0,
+///         rn: Register::X0,
+///         rm: Operand::Immediate(1),
+///     },
+/// ];
+/// let config = SearchConfig::default()
+///     .with_algorithm(Algorithm::Enumerative)
+///     .with_registers(vec![Register::X0, Register::X1])
+///     .with_immediates(vec![0, 1])
+///     .with_timeout(Duration::from_secs(30));
+///
+/// let live_out = LiveOut::from_registers(vec![Register::X0]);
+///
+/// let result = s11::optimize_sequence(&target, &live_out, &config);
+///
+/// assert!(result.found_optimization);
+/// assert_eq!(
+///     result.optimized_sequence,
+///     Some(vec![Instruction::Add {
+///         rd: Register::X0,
+///         rn: Register::X1,
+///         rm: Operand::Immediate(1),
+///     }])
+/// );
+/// ```
+pub fn optimize_sequence(
+    target: &[Instruction],
+    live_out: &LiveOut,
+    config: &SearchConfig,
+) -> SearchResult {
+    search_without_overwritten_writes(target, live_out, config, |reduced| {
+        search_reduced(reduced, live_out, config)
+    })
+}
+
+/// [`optimize_sequence`] on a target that has already been through the
+/// overwritten-write pre-pass.
+fn search_reduced(
+    target: &[Instruction],
+    live_out: &LiveOut,
+    config: &SearchConfig,
+) -> SearchResult {
+    if let Some(result) = trivial_result(target, live_out, config) {
+        return result;
+    }
+
+    match config.algorithm {
+        Algorithm::Enumerative => EnumerativeSearch::<AArch64>::new()
+            .search(target, live_out, config)
+            .into(),
+        Algorithm::Stochastic => StochasticSearch::<AArch64>::new()
+            .search(target, live_out, config)
+            .into(),
+        #[cfg(feature = "z3")]
+        Algorithm::Symbolic => SymbolicSearch::<AArch64>::new()
+            .search(target, live_out, config)
+            .into(),
+        // `SearchConfig::validate` rejects this combination; a caller that
+        // skips validation gets the target back unchanged.
+        #[cfg(not(feature = "z3"))]
+        Algorithm::Symbolic => SearchResult::no_optimization(
+            target.to_vec(),
+            SearchStatistics::new(Algorithm::Symbolic),
+        ),
+        Algorithm::Llm => LlmSearch::new().search(target, live_out, config),
+        Algorithm::Hybrid => {
+            let parallel = ParallelConfig::default()
+                .with_timeout_option(config.timeout)
+                .with_seed_option(config.stochastic.seed);
+            let parallel = match config.cores {
+                Some(cores) => parallel.with_workers(cores),
+                None => parallel,
+            };
+            let result = run_parallel_search(target, live_out, config, &parallel);
+            SearchResult {
+                statistics: result.total_statistics,
+                ..result.best_result
+            }
+        }
+    }
+}
+
+/// Run the parallel (hybrid) coordinator on `target` with an explicit
+/// `parallel` configuration, after the same trivial-case shortcuts as
+/// [`optimize_sequence`]. A shortcut result reports no per-worker statistics.
+pub fn optimize_sequence_parallel(
+    target: &[Instruction],
+    live_out: &LiveOut,
+    config: &SearchConfig,
+    parallel: &ParallelConfig,
+) -> ParallelResult {
+    let mut total_statistics = None;
+    let mut worker_statistics = Vec::new();
+    let best_result =
+        search_without_overwritten_writes(
+            target,
+            live_out,
+            config,
+            |reduced| match trivial_result(reduced, live_out, config) {
+                Some(result) => result,
+                None => {
+                    let result = run_parallel_search(reduced, live_out, config, parallel);
+                    total_statistics = Some(result.total_statistics);
+                    worker_statistics = result.worker_statistics;
+                    result.best_result
+                }
+            },
+        );
+    let mut total_statistics = total_statistics.unwrap_or_else(|| best_result.statistics.clone());
+    total_statistics.original_cost = best_result.statistics.original_cost;
+    ParallelResult {
+        best_result,
+        total_statistics,
+        worker_statistics,
+    }
+}
+
+/// Run `search` on `target` with every write the next instruction overwrites
+/// dropped ([`drop_overwritten_writes`]), and restate its result against
+/// `target`.
+///
+/// The backend only proved its answer against the shortened sequence, and
+/// the shortened sequence itself is a candidate even when the search could
+/// not improve on it. Either is reported as an optimization of `target` only
+/// after SMT proves it equivalent to `target` under `live_out`; with SMT
+/// disabled (`solver_timeout` of zero) or an inconclusive check the result is
+/// no optimization.
+pub fn search_without_overwritten_writes(
+    target: &[Instruction],
+    live_out: &LiveOut,
+    config: &SearchConfig,
+    search: impl FnOnce(&[Instruction]) -> SearchResult,
+) -> SearchResult {
+    let reduced = drop_overwritten_writes(target);
+    let result = search(&reduced);
+    if reduced.len() == target.len() {
+        return result;
+    }
+
+    let mut statistics = result.statistics;
+    statistics.original_cost = sequence_cost(target, &config.cost_metric);
+    let candidate = match result.optimized_sequence {
+        Some(optimized) if result.found_optimization => optimized,
+        _ => reduced,
+    };
+    let proven = config
+        .solver_timeout_within_budget(Duration::ZERO)
+        .is_some_and(|smt_timeout| {
+            let equivalence = EquivalenceConfig {
+                smt_timeout: Some(smt_timeout),
+                ..EquivalenceConfig::with_live_out(live_out.clone())
+            };
+            check_equivalence_with_config(target, &candidate, &equivalence)
+                == EquivalenceResult::Equivalent
+        });
+    if !proven {
+        return SearchResult::no_optimization(target.to_vec(), statistics);
+    }
+    SearchResult::with_optimization(target.to_vec(), candidate, statistics, &config.cost_metric)
+}
+
+/// Answer the cases no search can improve on, or `None` to run the search.
+///
+/// An empty target has nothing to optimize. A single instruction costing 1
+/// under `InstructionCount` can only be beaten by the empty sequence, so one
+/// equivalence check against `[]` decides it. When SMT is disabled
+/// (`solver_timeout` of zero) the backend's own policy applies instead.
+fn trivial_result(
+    target: &[Instruction],
+    live_out: &LiveOut,
+    config: &SearchConfig,
+) -> Option<SearchResult> {
+    let mut statistics = SearchStatistics::new(config.algorithm);
+    if target.is_empty() {
+        return Some(SearchResult::no_optimization(Vec::new(), statistics));
+    }
+    if target.len() != 1 || config.cost_metric != CostMetric::InstructionCount {
+        return None;
+    }
+    let original_cost = sequence_cost(target, &config.cost_metric);
+    if original_cost != 1 {
+        return None;
+    }
+    let smt_timeout = config.solver_timeout_within_budget(Duration::ZERO)?;
+
+    let start = Instant::now();
+    let equivalence = EquivalenceConfig {
+        smt_timeout: Some(smt_timeout),
+        ..EquivalenceConfig::with_live_out(live_out.clone())
+    };
+    let verdict = check_equivalence_with_config(target, &[], &equivalence);
+    statistics.elapsed_time = start.elapsed();
+    statistics.candidates_evaluated = 1;
+    statistics.original_cost = original_cost;
+
+    if verdict == EquivalenceResult::Equivalent {
+        statistics.improvements_found = 1;
+        statistics.best_cost_found = 0;
+        Some(SearchResult::with_optimization(
+            target.to_vec(),
+            Vec::new(),
+            statistics,
+            &config.cost_metric,
+        ))
+    } else {
+        statistics.best_cost_found = original_cost;
+        Some(SearchResult::no_optimization(target.to_vec(), statistics))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{Operand, Register};
+    use crate::search::config::StochasticConfig;
+    use crate::test_utils::sequence_fixtures::x0_live;
+
+    #[test]
+    fn empty_target_short_circuits() {
+        let config = SearchConfig::default().with_algorithm(Algorithm::Stochastic);
+        let result = optimize_sequence(&[], &x0_live(), &config);
+        assert!(!result.found_optimization);
+        assert_eq!(result.statistics.candidates_evaluated, 0);
+        assert_eq!(result.statistics.algorithm, Algorithm::Stochastic);
+    }
+
+    #[test]
+    fn dead_single_instruction_is_deleted_without_search() {
+        let target = [Instruction::MovImm {
+            rd: Register::X1,
+            imm: 7,
+        }];
+        let result = optimize_sequence(&target, &x0_live(), &SearchConfig::default());
+        assert!(result.found_optimization);
+        assert_eq!(result.optimized_sequence, Some(Vec::new()));
+        assert_eq!(result.statistics.candidates_evaluated, 1);
+    }
+
+    #[test]
+    fn live_single_instruction_is_kept_without_search() {
+        let target = [Instruction::Add {
+            rd: Register::X0,
+            rn: Register::X1,
+            rm: Operand::Immediate(1),
+        }];
+        let result = optimize_sequence(&target, &x0_live(), &SearchConfig::default());
+        assert!(!result.found_optimization);
+        assert_eq!(result.statistics.best_cost_found, 1);
+        assert_eq!(result.statistics.candidates_evaluated, 1);
+    }
+
+    #[test]
+    fn parallel_variant_shares_the_shortcuts() {
+        let result = optimize_sequence_parallel(
+            &[],
+            &x0_live(),
+            &SearchConfig::default(),
+            &ParallelConfig::default().with_workers(2),
+        );
+        assert!(!result.best_result.found_optimization);
+        assert!(result.worker_statistics.is_empty());
+    }
+
+    #[test]
+    fn overwritten_write_is_dropped_before_the_search() {
+        let target = [
+            Instruction::MovImm {
+                rd: Register::X0,
+                imm: 1,
+            },
+            Instruction::MovImm {
+                rd: Register::X0,
+                imm: 2,
+            },
+        ];
+        let collapsed = vec![target[1]];
+        let result = optimize_sequence(&target, &x0_live(), &SearchConfig::default());
+        assert!(result.found_optimization);
+        assert_eq!(result.original_sequence, target);
+        assert_eq!(result.optimized_sequence, Some(collapsed.clone()));
+        assert_eq!(result.statistics.original_cost, 2);
+        assert_eq!(
+            check_equivalence_with_config(
+                &target,
+                &collapsed,
+                &EquivalenceConfig::with_live_out(x0_live())
+            ),
+            EquivalenceResult::Equivalent
+        );
+
+        let parallel = optimize_sequence_parallel(
+            &target,
+            &x0_live(),
+            &SearchConfig::default(),
+            &ParallelConfig::default().with_workers(2),
+        );
+        assert_eq!(parallel.best_result.optimized_sequence, Some(collapsed));
+        assert_eq!(parallel.total_statistics.original_cost, 2);
+    }
+
+    /// The collapse is only reported with an SMT proof, so it is not
+    /// reported at all when SMT is disabled.
+    #[test]
+    fn overwritten_write_is_not_reported_without_a_proof() {
+        let target = [
+            Instruction::MovImm {
+                rd: Register::X0,
+                imm: 1,
+            },
+            Instruction::MovImm {
+                rd: Register::X0,
+                imm: 2,
+            },
+        ];
+        let config = SearchConfig::default().with_solver_timeout(Duration::ZERO);
+        let result = optimize_sequence(&target, &x0_live(), &config);
+        assert!(!result.found_optimization);
+        assert_eq!(result.original_sequence, target);
+    }
+
+    #[test]
+    fn symbolic_dispatch_fuses_mov_into_add() {
+        let target = [
+            Instruction::MovReg {
+                rd: Register::X0,
+                rn: Register::X1,
+            },
+            Instruction::Add {
+                rd: Register::X0,
+                rn: Register::X0,
+                rm: Operand::Immediate(1),
+            },
+        ];
+        let config = SearchConfig::default()
+            .with_algorithm(Algorithm::Symbolic)
+            .with_registers(vec![Register::X0, Register::X1])
+            .with_immediates(vec![0, 1]);
+        let result = optimize_sequence(&target, &x0_live(), &config);
+        assert!(result.found_optimization);
+        assert_eq!(result.statistics.algorithm, Algorithm::Symbolic);
+        assert_eq!(result.optimized_sequence.map(|seq| seq.len()), Some(1));
+    }
+
+    /// With this seed the stochastic chain lands on `add x0, x2, x1`; the
+    /// result the caller gets back lists the lower source register first.
+    #[test]
+    fn returned_sequence_takes_its_canonical_spelling() {
+        let add = |rn, rm| Instruction::Add {
+            rd: Register::X0,
+            rn,
+            rm: Operand::Register(rm),
+        };
+        let target = [
+            Instruction::Add {
+                rd: Register::X3,
+                rn: Register::X2,
+                rm: Operand::Register(Register::X1),
+            },
+            Instruction::MovReg {
+                rd: Register::X0,
+                rn: Register::X3,
+            },
+        ];
+        let config = SearchConfig::default()
+            .with_algorithm(Algorithm::Stochastic)
+            .with_registers(vec![Register::X0, Register::X1, Register::X2, Register::X3])
+            .with_immediates(vec![0, 1])
+            .with_stochastic(
+                StochasticConfig::default()
+                    .with_seed(6)
+                    .with_iterations(20_000),
+            );
+
+        let found = StochasticSearch::<AArch64>::new().search(&target, &x0_live(), &config);
+        assert_eq!(
+            found.optimized_sequence,
+            Some(vec![add(Register::X2, Register::X1)])
+        );
+
+        let result = optimize_sequence(&target, &x0_live(), &config);
+        assert_eq!(
+            result.optimized_sequence,
+            Some(vec![add(Register::X1, Register::X2)])
+        );
+    }
+}
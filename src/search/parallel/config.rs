@@ -4,12 +4,46 @@
 
 use std::time::Duration;
 
+use crate::search::config::SearchMode;
+
+/// What one parallel worker runs. A `None` field keeps the value from the
+/// shared `SearchConfig`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WorkerSpec {
+    /// Symbolic synthesis, optionally in a different search mode.
+    Symbolic { mode: Option<SearchMode> },
+    /// An MCMC chain. An unset seed falls back to
+    /// [`ParallelConfig::worker_seed`], so chains still differ by worker id.
+    Stochastic {
+        seed: Option<u64>,
+        beta: Option<f64>,
+        iterations: Option<u64>,
+    },
+}
+
+impl WorkerSpec {
+    /// A symbolic worker with the shared symbolic settings.
+    pub fn symbolic() -> Self {
+        WorkerSpec::Symbolic { mode: None }
+    }
+
+    /// A stochastic worker with the shared stochastic settings.
+    pub fn stochastic() -> Self {
+        WorkerSpec::Stochastic {
+            seed: None,
+            beta: None,
+            iterations: None,
+        }
+    }
+}
+
 /// Configuration for parallel search execution.
 #[derive(Debug, Clone)]
 pub struct ParallelConfig {
     /// Number of worker threads to spawn.
     pub num_workers: usize,
-    /// Whether to include a symbolic search worker (in hybrid mode).
+    /// Whether to include a symbolic search worker (in hybrid mode). Off by
+    /// default in a build without the `z3` feature.
     pub include_symbolic: bool,
     /// Whether workers should share solutions with each other.
     pub solution_sharing: bool,
@@ -17,16 +51,25 @@ pub struct ParallelConfig {
     pub timeout: Option<Duration>,
     /// Base random seed (workers get seed + worker_id).
     pub base_seed: Option<u64>,
+    /// Pick the winner from every worker's report with a fixed tie-break
+    /// instead of taking the first reporter; see [`Self::with_deterministic`].
+    pub deterministic: bool,
+    /// Explicit per-worker specs, indexed by worker id. Empty means the
+    /// layout implied by `num_workers` and `include_symbolic`; see
+    /// [`Self::with_worker_specs`].
+    pub workers: Vec<WorkerSpec>,
 }
 
 impl Default for ParallelConfig {
     fn default() -> Self {
         Self {
             num_workers: num_cpus::get(),
-            include_symbolic: true,
+            include_symbolic: cfg!(feature = "z3"),
             solution_sharing: true,
             timeout: None,
             base_seed: None,
+            deterministic: false,
+            workers: Vec::new(),
         }
     }
 }
@@ -68,6 +111,31 @@ impl ParallelConfig {
         self
     }
 
+    /// Make the parallel result independent of thread scheduling.
+    ///
+    /// The coordinator waits for every worker and picks the lowest-cost
+    /// sequence, breaking ties by canonical sequence order and then by the
+    /// lowest worker id; per-worker statistics are reported in worker-id
+    /// order. Stochastic workers fall back to a base seed of 0 when none is
+    /// set. Runs are only reproducible when the workers are bounded by
+    /// iterations rather than wall-clock timeouts.
+    pub fn with_deterministic(mut self, deterministic: bool) -> Self {
+        self.deterministic = deterministic;
+        self
+    }
+
+    /// Run exactly these workers, e.g. one symbolic plus several stochastic
+    /// chains at different betas. Sets `num_workers` to the number of specs
+    /// and takes precedence over `include_symbolic`; an empty list restores
+    /// the implied layout.
+    pub fn with_worker_specs(mut self, workers: Vec<WorkerSpec>) -> Self {
+        if !workers.is_empty() {
+            self.num_workers = workers.len();
+        }
+        self.workers = workers;
+        self
+    }
+
     /// Set the overall timeout from an Option.
     pub fn with_timeout_option(mut self, timeout: Option<Duration>) -> Self {
         self.timeout = timeout;
@@ -76,22 +144,53 @@ impl ParallelConfig {
 
     /// Get the number of stochastic workers.
     ///
-    /// Stochastic workers occupy the trailing worker-id suffix. Any symbolic
-    /// worker occupies the leading prefix.
+    /// Without explicit specs, stochastic workers occupy the trailing
+    /// worker-id suffix and any symbolic worker the leading prefix.
     pub fn num_stochastic_workers(&self) -> usize {
-        if self.include_symbolic && self.num_workers > 1 {
+        if !self.workers.is_empty() {
+            (0..self.num_workers)
+                .filter(|&worker_id| self.is_stochastic_worker(worker_id))
+                .count()
+        } else if self.include_symbolic && self.num_workers > 1 {
             self.num_workers - 1
         } else {
             self.num_workers
         }
     }
 
-    /// Return whether the worker id belongs to the stochastic suffix.
-    pub(crate) fn is_stochastic_worker(&self, worker_id: usize) -> bool {
+    /// The seed for stochastic worker `worker_id`: the base seed plus the id,
+    /// with the base defaulting to 0 in deterministic mode. `None` lets the
+    /// worker seed itself from entropy.
+    pub(crate) fn worker_seed(&self, worker_id: usize) -> Option<u64> {
+        self.base_seed
+            .or(self.deterministic.then_some(0))
+            .map(|seed| seed.wrapping_add(worker_id as u64))
+    }
+
+    /// The spec worker `worker_id` runs: its entry in `workers` when that
+    /// is set (ids past the end run a default stochastic chain), otherwise
+    /// symbolic for the leading prefix and stochastic for the suffix.
+    pub fn worker_spec(&self, worker_id: usize) -> WorkerSpec {
+        if !self.workers.is_empty() {
+            return self
+                .workers
+                .get(worker_id)
+                .copied()
+                .unwrap_or_else(WorkerSpec::stochastic);
+        }
         let first_stochastic_worker = self
             .num_workers
             .saturating_sub(self.num_stochastic_workers());
-        worker_id >= first_stochastic_worker
+        if worker_id >= first_stochastic_worker {
+            WorkerSpec::stochastic()
+        } else {
+            WorkerSpec::symbolic()
+        }
+    }
+
+    /// Return whether the worker runs a stochastic chain.
+    pub(crate) fn is_stochastic_worker(&self, worker_id: usize) -> bool {
+        matches!(self.worker_spec(worker_id), WorkerSpec::Stochastic { .. })
     }
 }
 
@@ -107,6 +206,52 @@ mod tests {
         assert!(config.solution_sharing);
         assert!(config.timeout.is_none());
         assert!(config.base_seed.is_none());
+        assert!(!config.deterministic);
+        assert!(config.workers.is_empty());
+    }
+
+    #[test]
+    fn worker_specs_set_the_worker_count_and_override_the_layout() {
+        let specs = vec![
+            WorkerSpec::stochastic(),
+            WorkerSpec::Symbolic {
+                mode: Some(SearchMode::Binary),
+            },
+            WorkerSpec::Stochastic {
+                seed: Some(7),
+                beta: Some(0.5),
+                iterations: None,
+            },
+        ];
+        let config = ParallelConfig::default()
+            .with_workers(8)
+            .with_symbolic(false)
+            .with_worker_specs(specs.clone());
+
+        assert_eq!(config.num_workers, 3);
+        assert_eq!(
+            (0..3).map(|id| config.worker_spec(id)).collect::<Vec<_>>(),
+            specs
+        );
+        assert_eq!(config.num_stochastic_workers(), 2);
+        assert!(!config.is_stochastic_worker(1));
+
+        let config = config.with_worker_specs(Vec::new()).with_symbolic(true);
+        assert_eq!(config.worker_spec(0), WorkerSpec::symbolic());
+        assert_eq!(config.worker_spec(1), WorkerSpec::stochastic());
+    }
+
+    #[test]
+    fn worker_seed_offsets_the_base_and_defaults_to_zero_when_deterministic() {
+        let config = ParallelConfig::default();
+        assert_eq!(config.worker_seed(3), None);
+
+        let config = config.with_deterministic(true);
+        assert_eq!(config.worker_seed(3), Some(3));
+
+        let config = config.with_seed(u64::MAX);
+        assert_eq!(config.worker_seed(0), Some(u64::MAX));
+        assert_eq!(config.worker_seed(1), Some(0));
     }
 
     #[test]
@@ -5,16 +5,21 @@
 use crate::ir::Instruction;
 use crate::search::SearchAlgorithm;
 use crate::search::config::{Algorithm, SearchConfig};
+use crate::search::normalize::canonicalize_instruction;
 use crate::search::parallel::channel::{
     CoordinatorChannels, CoordinatorMessage, WorkerChannels, WorkerMessage, create_channels,
 };
-use crate::search::parallel::config::ParallelConfig;
+use crate::search::parallel::config::{ParallelConfig, WorkerSpec};
 use crate::search::result::{SearchResult, SearchStatistics};
 use crate::search::stochastic::StochasticSearch;
+#[cfg(feature = "z3")]
 use crate::search::symbolic::SymbolicSearch;
+use crate::search::trace::search_event;
+use crate::semantics::cost::CostMetric;
 use crate::semantics::live_out::LiveOut;
 use crossbeam_channel::RecvTimeoutError;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::{Duration, Instant};
 
 /// Result from parallel search execution.
@@ -29,7 +34,8 @@ use std::time::{Duration, Instant};
 /// it), and `best_cost_found` is the minimum nonzero across workers
 /// (falling back to `original_cost` when no worker recorded one).
 /// `worker_statistics` carries the per-worker statistics in arrival
-/// order; each entry's algorithm lives in `SearchStatistics::algorithm`.
+/// order (worker-id order under [`ParallelConfig::deterministic`]); each
+/// entry's algorithm lives in `SearchStatistics::algorithm`.
 /// Each entry's `elapsed_time` is the coordinator wall-clock at message
 /// arrival (`start_time.elapsed()`), not the worker's own driver-reported
 /// duration; this gives every entry a common time origin.
@@ -95,6 +101,8 @@ pub fn run_parallel_search(
         live_out.as_ref(),
         coordinator_channels,
         parallel_config.as_ref(),
+        &search_config.cost_metric,
+        search_config.stop_flag.as_deref(),
         start_time,
     );
 
@@ -112,20 +120,28 @@ fn run_coordinator(
     _live_out: &LiveOut,
     channels: CoordinatorChannels,
     config: &ParallelConfig,
+    metric: &CostMetric,
+    external_stop: Option<&AtomicBool>,
     start_time: Instant,
 ) -> ParallelResult {
     let mut best_result: Option<SearchResult> = None;
     let mut worker_stats: Vec<(usize, SearchStatistics)> = Vec::new();
     let mut finished_count = 0;
     let mut winning_worker_id: Option<usize> = None;
+    // Every improvement reported, kept only in deterministic mode where the
+    // winner is chosen after all workers finish.
+    let mut improvements: Vec<(usize, Vec<Instruction>, u64, Algorithm)> = Vec::new();
     let total_workers = config.num_workers;
 
     // Calculate timeout
     let deadline = config.timeout.map(|t| start_time + t);
 
     loop {
-        // Check if we've exceeded timeout
-        if deadline.is_some_and(|d| Instant::now() >= d) {
+        // Check if we've exceeded timeout or the caller asked us to stop
+        // (the workers run on their own flag, so forward it)
+        if deadline.is_some_and(|d| Instant::now() >= d)
+            || external_stop.is_some_and(|f| f.load(Ordering::Relaxed))
+        {
             channels.shared.signal_stop();
             // Broadcast stop to all workers. `try_send` is intentional: the
             // per-worker bounded(8) channel may already be full of advisory
@@ -148,40 +164,47 @@ fn run_coordinator(
                     algorithm,
                 } => {
                     // Check if this is actually better than current best
-                    if channels.shared.try_update(cost) {
-                        if config.solution_sharing {
-                            // Broadcast to other workers. `try_send` is
-                            // intentional: workers do not currently consume
-                            // `BetterSolution`, so the bounded(8) channel
-                            // can backfill. The blocking variant would stall
-                            // the coordinator while workers are still in
-                            // their inner search loop. Dropping a missed
-                            // advisory broadcast is safe.
-                            for (i, tx) in channels.to_workers.iter().enumerate() {
-                                if i != worker_id {
-                                    let _ = tx.try_send(CoordinatorMessage::BetterSolution {
-                                        sequence: sequence.clone(),
-                                        cost,
-                                    });
-                                }
+                    let is_new_best = channels.shared.try_update(cost);
+                    if is_new_best && config.solution_sharing {
+                        // Broadcast to other workers. `try_send` is
+                        // intentional: workers do not currently consume
+                        // `BetterSolution`, so the bounded(8) channel
+                        // can backfill. The blocking variant would stall
+                        // the coordinator while workers are still in
+                        // their inner search loop. Dropping a missed
+                        // advisory broadcast is safe.
+                        for (i, tx) in channels.to_workers.iter().enumerate() {
+                            if i != worker_id {
+                                let _ = tx.try_send(CoordinatorMessage::BetterSolution {
+                                    sequence: sequence.clone(),
+                                    cost,
+                                });
                             }
                         }
+                    }
 
+                    // On a cost tie the symbolic worker's rewrite replaces a
+                    // stochastic one: it is the first rewrite in length
+                    // order, so the winner does not hinge on which thread
+                    // reported first.
+                    let symbolic_tie = !is_new_best
+                        && algorithm == Algorithm::Symbolic
+                        && cost == channels.shared.current_best()
+                        && best_result
+                            .as_ref()
+                            .is_some_and(|best| best.statistics.algorithm != Algorithm::Symbolic);
+                    if config.deterministic {
+                        improvements.push((worker_id, sequence, cost, algorithm));
+                    } else if is_new_best || symbolic_tie {
                         // Update best result. statistics is a placeholder
                         // here; it is finalised after every worker has
                         // reported, see post-loop block below.
                         // winning_worker_id is overwritten on each
                         // accepted Improvement: `try_update` only succeeds
                         // when `cost` is strictly less than the prior
-                        // best, so the last accepted improvement is the
-                        // overall winner.
-                        let result = SearchResult {
-                            found_optimization: true,
-                            original_sequence: target.to_vec(),
-                            optimized_sequence: Some(sequence),
-                            statistics: SearchStatistics::new(algorithm),
-                        };
-                        best_result = Some(result);
+                        // best (a symbolic tie keeps the cost), so the last
+                        // accepted improvement is the overall winner.
+                        best_result = Some(improvement_result(target, sequence, algorithm, metric));
                         winning_worker_id = Some(worker_id);
                     }
                 }
@@ -190,6 +213,13 @@ fn run_coordinator(
                     statistics,
                 } => {
                     finished_count += 1;
+                    search_event!(
+                        debug;
+                        "Worker {} finished ({:?}, {} candidates evaluated)",
+                        worker_id,
+                        statistics.algorithm,
+                        statistics.candidates_evaluated
+                    );
                     let mut stats = statistics;
                     // Use coordinator wall-clock for per-worker elapsed_time
                     // so all entries share a common time origin (start_time).
@@ -205,7 +235,7 @@ fn run_coordinator(
                     }
                 }
                 WorkerMessage::Error { worker_id, message } => {
-                    eprintln!("Worker {} error: {}", worker_id, message);
+                    search_event!(error, true; "Worker {} error: {}", worker_id, message);
                     finished_count += 1;
                     if finished_count >= total_workers {
                         break;
@@ -225,6 +255,20 @@ fn run_coordinator(
         }
     }
 
+    if config.deterministic {
+        worker_stats.sort_by_key(|(worker_id, _)| *worker_id);
+        if let Some((worker_id, sequence, _, algorithm)) =
+            improvements
+                .into_iter()
+                .min_by_key(|(worker_id, sequence, cost, _)| {
+                    deterministic_rank(*cost, sequence, *worker_id)
+                })
+        {
+            best_result = Some(improvement_result(target, sequence, algorithm, metric));
+            winning_worker_id = Some(worker_id);
+        }
+    }
+
     // Fold the per-worker statistics into the cross-worker aggregate. The
     // reduce rules (counter sums, max original_cost, min-nonzero best_cost
     // with fallback) live on SearchStatistics so they stay tested in one place
@@ -264,17 +308,45 @@ fn run_coordinator(
     }
 }
 
+/// A found-optimization result for `sequence`, priced under the workers'
+/// `metric`, whose statistics are a placeholder until every worker has
+/// reported (see `run_coordinator`).
+fn improvement_result(
+    target: &[Instruction],
+    sequence: Vec<Instruction>,
+    algorithm: Algorithm,
+    metric: &CostMetric,
+) -> SearchResult {
+    SearchResult::with_optimization(
+        target.to_vec(),
+        sequence,
+        SearchStatistics::new(algorithm),
+        metric,
+    )
+}
+
+/// Ordering key for the deterministic winner: lowest cost, then the
+/// canonical assembly of the sequence, then the lowest worker id.
+fn deterministic_rank(
+    cost: u64,
+    sequence: &[Instruction],
+    worker_id: usize,
+) -> (u64, Vec<String>, usize) {
+    let canonical = sequence
+        .iter()
+        .map(|instr| canonicalize_instruction(instr).to_string())
+        .collect();
+    (cost, canonical, worker_id)
+}
+
 /// Map the config-owned worker placement to the algorithm a worker runs.
 ///
-/// [`ParallelConfig::num_stochastic_workers`] defines the stochastic suffix
-/// length, and [`ParallelConfig::is_stochastic_worker`] owns worker-id
-/// placement. This function only maps that placement to the enum used by
-/// worker execution and statistics.
+/// [`ParallelConfig::worker_spec`] owns worker-id placement. This function
+/// only maps that spec to the enum used by statistics.
 fn worker_algorithm(worker_id: usize, parallel_config: &ParallelConfig) -> Algorithm {
-    if parallel_config.is_stochastic_worker(worker_id) {
-        Algorithm::Stochastic
-    } else {
-        Algorithm::Symbolic
+    match parallel_config.worker_spec(worker_id) {
+        WorkerSpec::Symbolic { .. } => Algorithm::Symbolic,
+        WorkerSpec::Stochastic { .. } => Algorithm::Stochastic,
     }
 }
 
@@ -287,11 +359,6 @@ fn run_worker(
     parallel_config: &ParallelConfig,
     channels: WorkerChannels,
 ) {
-    let is_symbolic_worker = matches!(
-        worker_algorithm(worker_id, parallel_config),
-        Algorithm::Symbolic
-    );
-
     // Build worker-specific config. Inject the coordinator's cooperative-
     // cancel flag so the inner search loops (`StochasticSearch::search`,
     // `SymbolicSearch::search`) honour `SharedBest::signal_stop` even when
@@ -300,26 +367,41 @@ fn run_worker(
         .clone()
         .with_stop_flag(channels.shared.stop_flag());
 
-    if is_symbolic_worker {
-        // Run symbolic search
-        run_symbolic_worker(worker_id, target, live_out, &config, channels);
-    } else {
-        // Run stochastic search with unique seed
-        let seed = parallel_config
-            .base_seed
-            .map(|s| s.wrapping_add(worker_id as u64));
+    match parallel_config.worker_spec(worker_id) {
+        WorkerSpec::Symbolic { mode } => {
+            if let Some(mode) = mode {
+                let mut symbolic_config = config.symbolic.clone();
+                symbolic_config.search_mode = mode;
+                config = config.with_symbolic(symbolic_config);
+            }
 
-        if let Some(seed) = seed {
+            run_symbolic_worker(worker_id, target, live_out, &config, channels);
+        }
+        WorkerSpec::Stochastic {
+            seed,
+            beta,
+            iterations,
+        } => {
+            // Run stochastic search with unique seed unless the spec pins one
             let mut stochastic_config = config.stochastic.clone();
-            stochastic_config.seed = Some(seed);
+            if let Some(seed) = seed.or_else(|| parallel_config.worker_seed(worker_id)) {
+                stochastic_config.seed = Some(seed);
+            }
+            if let Some(beta) = beta {
+                stochastic_config.beta = beta;
+            }
+            if iterations.is_some() {
+                stochastic_config.iterations = iterations;
+            }
             config = config.with_stochastic(stochastic_config);
-        }
 
-        run_stochastic_worker(worker_id, target, live_out, &config, channels);
+            run_stochastic_worker(worker_id, target, live_out, &config, channels);
+        }
     }
 }
 
 /// Run a symbolic search worker.
+#[cfg(feature = "z3")]
 fn run_symbolic_worker(
     worker_id: usize,
     target: &[Instruction],
@@ -353,6 +435,22 @@ fn run_symbolic_worker(
     });
 }
 
+/// Without the `z3` feature there is no symbolic search: an explicitly
+/// requested symbolic worker finishes immediately without a rewrite.
+#[cfg(not(feature = "z3"))]
+fn run_symbolic_worker(
+    worker_id: usize,
+    _target: &[Instruction],
+    _live_out: &LiveOut,
+    _config: &SearchConfig,
+    channels: WorkerChannels,
+) {
+    let _ = channels.to_coordinator.send(WorkerMessage::Finished {
+        worker_id,
+        statistics: SearchStatistics::new(Algorithm::Symbolic),
+    });
+}
+
 /// Run a stochastic search worker with periodic checks for better solutions.
 fn run_stochastic_worker(
     worker_id: usize,
@@ -394,31 +492,16 @@ fn run_stochastic_worker(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::ir::{Operand, Register};
+    use crate::ir::Register;
     use crate::search::config::{SearchConfig, StochasticConfig};
-
-    fn mov_add_sequence() -> Vec<Instruction> {
-        vec![
-            Instruction::MovReg {
-                rd: Register::X0,
-                rn: Register::X1,
-            },
-            Instruction::Add {
-                rd: Register::X0,
-                rn: Register::X0,
-                rm: Operand::Immediate(1),
-            },
-        ]
-    }
+    use crate::test_utils::sequence_fixtures::{mov_add_sequence, small_search_config, x0_live};
 
     #[test]
     fn test_parallel_search_single_worker() {
         let target = mov_add_sequence();
-        let live_out = LiveOut::from_registers(vec![Register::X0]);
+        let live_out = x0_live();
 
-        let search_config = SearchConfig::default()
-            .with_registers(vec![Register::X0, Register::X1])
-            .with_immediates(vec![0, 1, 2])
+        let search_config = small_search_config()
             .with_stochastic(StochasticConfig::default().with_iterations(1000));
 
         let parallel_config = ParallelConfig::default()
@@ -501,13 +584,11 @@ mod tests {
     #[test]
     fn test_two_workers_with_symbolic_reports_one_symbolic_one_stochastic() {
         let target = mov_add_sequence();
-        let live_out = LiveOut::from_registers(vec![Register::X0]);
+        let live_out = x0_live();
 
         // Keep the symbolic worker's solver budget tight so it terminates
         // quickly under Z3 on this trivial target.
-        let search_config = SearchConfig::default()
-            .with_registers(vec![Register::X0, Register::X1])
-            .with_immediates(vec![0, 1, 2])
+        let search_config = small_search_config()
             .with_stochastic(StochasticConfig::default().with_iterations(200))
             .with_solver_timeout(Duration::from_millis(250));
 
@@ -534,16 +615,58 @@ mod tests {
         );
     }
 
+    #[test]
+    fn mixed_worker_specs_spawn_the_requested_worker_types() {
+        let target = mov_add_sequence();
+        let live_out = x0_live();
+
+        let search_config = small_search_config()
+            .with_stochastic(StochasticConfig::default().with_iterations(200))
+            .with_solver_timeout(Duration::from_millis(250));
+
+        let stochastic = |beta| WorkerSpec::Stochastic {
+            seed: None,
+            beta: Some(beta),
+            iterations: Some(100),
+        };
+        // Symbolic in the middle, which the implied layout never produces.
+        let parallel_config = ParallelConfig::default()
+            .with_worker_specs(vec![
+                stochastic(0.5),
+                WorkerSpec::symbolic(),
+                stochastic(1.0),
+                stochastic(2.0),
+            ])
+            .with_seed(42)
+            .with_timeout(Duration::from_secs(10));
+
+        let result = run_parallel_search(&target, &live_out, &search_config, &parallel_config);
+
+        let mut pairs: Vec<(usize, Algorithm)> = result
+            .worker_statistics
+            .iter()
+            .map(|(id, stats)| (*id, stats.algorithm))
+            .collect();
+        pairs.sort_by_key(|(id, _)| *id);
+        assert_eq!(
+            pairs,
+            vec![
+                (0, Algorithm::Stochastic),
+                (1, Algorithm::Symbolic),
+                (2, Algorithm::Stochastic),
+                (3, Algorithm::Stochastic),
+            ]
+        );
+    }
+
     #[test]
     fn test_four_workers_with_symbolic_reports_one_symbolic_three_stochastic() {
         let target = mov_add_sequence();
-        let live_out = LiveOut::from_registers(vec![Register::X0]);
+        let live_out = x0_live();
 
         // Keep the symbolic worker's solver budget tight so it terminates
         // quickly under Z3 on this trivial target.
-        let search_config = SearchConfig::default()
-            .with_registers(vec![Register::X0, Register::X1])
-            .with_immediates(vec![0, 1, 2])
+        let search_config = small_search_config()
             .with_stochastic(StochasticConfig::default().with_iterations(200))
             .with_solver_timeout(Duration::from_millis(250));
 
@@ -578,12 +701,10 @@ mod tests {
     #[test]
     fn test_single_worker_with_symbolic_is_stochastic() {
         let target = mov_add_sequence();
-        let live_out = LiveOut::from_registers(vec![Register::X0]);
+        let live_out = x0_live();
 
-        let search_config = SearchConfig::default()
-            .with_registers(vec![Register::X0, Register::X1])
-            .with_immediates(vec![0, 1, 2])
-            .with_stochastic(StochasticConfig::default().with_iterations(200));
+        let search_config =
+            small_search_config().with_stochastic(StochasticConfig::default().with_iterations(200));
 
         // include_symbolic = true but num_workers = 1: per the config-owned
         // stochastic suffix placement, the lone worker must be stochastic.
@@ -610,12 +731,10 @@ mod tests {
     #[test]
     fn test_single_worker_without_symbolic_stays_stochastic() {
         let target = mov_add_sequence();
-        let live_out = LiveOut::from_registers(vec![Register::X0]);
+        let live_out = x0_live();
 
-        let search_config = SearchConfig::default()
-            .with_registers(vec![Register::X0, Register::X1])
-            .with_immediates(vec![0, 1, 2])
-            .with_stochastic(StochasticConfig::default().with_iterations(200));
+        let search_config =
+            small_search_config().with_stochastic(StochasticConfig::default().with_iterations(200));
 
         let parallel_config = ParallelConfig::default()
             .with_workers(1)
@@ -635,12 +754,10 @@ mod tests {
     #[test]
     fn test_parallel_search_multiple_workers() {
         let target = mov_add_sequence();
-        let live_out = LiveOut::from_registers(vec![Register::X0]);
+        let live_out = x0_live();
 
-        let search_config = SearchConfig::default()
-            .with_registers(vec![Register::X0, Register::X1])
-            .with_immediates(vec![0, 1, 2])
-            .with_stochastic(StochasticConfig::default().with_iterations(500));
+        let search_config =
+            small_search_config().with_stochastic(StochasticConfig::default().with_iterations(500));
 
         let parallel_config = ParallelConfig::default()
             .with_workers(2)
@@ -719,7 +836,7 @@ mod tests {
     #[test]
     fn coordinator_timeout_zero_returns_promptly_with_no_search_timeout() {
         let target = mov_add_sequence();
-        let live_out = LiveOut::from_registers(vec![Register::X0]);
+        let live_out = x0_live();
 
         let search_config = SearchConfig::default()
             .with_timeout_option(None)
@@ -755,12 +872,10 @@ mod tests {
     #[test]
     fn test_parallel_search_no_dropped_finished_messages() {
         let target = mov_add_sequence();
-        let live_out = LiveOut::from_registers(vec![Register::X0]);
+        let live_out = x0_live();
 
-        let search_config = SearchConfig::default()
-            .with_registers(vec![Register::X0, Register::X1])
-            .with_immediates(vec![0, 1, 2])
-            .with_stochastic(StochasticConfig::default().with_iterations(200));
+        let search_config =
+            small_search_config().with_stochastic(StochasticConfig::default().with_iterations(200));
 
         let num_workers = 4;
         let parallel_config = ParallelConfig::default()
@@ -806,7 +921,7 @@ mod tests {
     #[test]
     fn test_parallel_search_symbolic_worker_statistics_are_propagated() {
         let target = mov_add_sequence();
-        let live_out = LiveOut::from_registers(vec![Register::X0]);
+        let live_out = x0_live();
         let ci_timeout = Duration::from_secs(30);
 
         let search_config = SearchConfig::default()
@@ -919,4 +1034,41 @@ mod tests {
             result.best_result.statistics.algorithm,
         );
     }
+
+    #[test]
+    fn deterministic_rank_orders_by_cost_then_sequence_then_worker() {
+        let add = mov_add_sequence()[1..].to_vec();
+        let mov = mov_add_sequence()[..1].to_vec();
+
+        assert!(deterministic_rank(1, &add, 3) < deterministic_rank(2, &mov, 0));
+        // `add ...` sorts before `mov ...` at equal cost, whichever worker.
+        assert!(deterministic_rank(1, &add, 3) < deterministic_rank(1, &mov, 0));
+        assert!(deterministic_rank(1, &add, 0) < deterministic_rank(1, &add, 1));
+    }
+
+    #[test]
+    fn deterministic_runs_with_the_same_seed_agree() {
+        let target = mov_add_sequence();
+        let live_out = x0_live();
+        let search_config = SearchConfig::default()
+            .with_registers(vec![Register::X0, Register::X1])
+            .with_immediates(vec![0, 1])
+            .with_stochastic(StochasticConfig::default().with_iterations(20_000));
+        let parallel_config = ParallelConfig::default()
+            .with_workers(3)
+            .with_symbolic(false)
+            .with_seed(644)
+            .with_deterministic(true);
+
+        let first = run_parallel_search(&target, &live_out, &search_config, &parallel_config);
+        let second = run_parallel_search(&target, &live_out, &search_config, &parallel_config);
+
+        assert!(first.best_result.found_optimization);
+        assert_eq!(
+            first.best_result.optimized_sequence,
+            second.best_result.optimized_sequence
+        );
+        let worker_ids: Vec<usize> = first.worker_statistics.iter().map(|(id, _)| *id).collect();
+        assert_eq!(worker_ids, vec![0, 1, 2]);
+    }
 }
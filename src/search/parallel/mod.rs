@@ -28,5 +28,5 @@ pub mod channel;
 pub mod config;
 pub mod coordinator;
 
-pub use config::ParallelConfig;
+pub use config::{ParallelConfig, WorkerSpec};
 pub use coordinator::{ParallelResult, run_parallel_search};
@@ -5,7 +5,13 @@
 use crate::ir::Instruction;
 use crate::isa::ISA;
 use crate::search::config::Algorithm;
-use crate::semantics::{EquivalenceMetrics, EquivalenceResult};
+use crate::search::normalize::canonicalize;
+use crate::search::trace::search_event;
+use crate::semantics::cost::{CostMetric, sequence_cost};
+use crate::semantics::live_out::LiveOut;
+use crate::semantics::{
+    EquivalenceConfig, EquivalenceMetrics, EquivalenceResult, check_equivalence_with_config,
+};
 use std::time::Duration;
 
 /// Result of a search operation
@@ -32,14 +38,27 @@ impl SearchResult {
         }
     }
 
-    /// Create a new search result with an optimization found
+    /// Create a new search result with an optimization found.
+    ///
+    /// Both sequences are priced under `metric`, the one the search scored
+    /// with. An optimization that is not strictly cheaper than the original
+    /// is a backend bug: it panics in debug builds, and in release builds is
+    /// logged and reported as no optimization. An accepted optimization is
+    /// stored in its canonical spelling (see [`canonical_spelling`]).
     pub fn with_optimization(
         original: Vec<Instruction>,
         optimized: Vec<Instruction>,
         statistics: SearchStatistics,
+        metric: &CostMetric,
     ) -> Self {
+        if reject_non_improving(
+            sequence_cost(&original, metric),
+            sequence_cost(&optimized, metric),
+        ) {
+            return Self::no_optimization(original, statistics);
+        }
         Self {
-            optimized_sequence: Some(optimized),
+            optimized_sequence: Some(canonical_spelling(optimized, metric)),
             original_sequence: original,
             found_optimization: true,
             statistics,
@@ -54,6 +73,59 @@ impl SearchResult {
             0
         }
     }
+
+    /// The original and optimized sequences side by side, one instruction
+    /// per row, followed by both costs under `metric` and the savings.
+    /// Without an optimization only the original and its cost are listed.
+    pub fn diff_summary(&self, metric: &CostMetric) -> String {
+        let original_cost = sequence_cost(&self.original_sequence, metric);
+        let original: Vec<String> = self
+            .original_sequence
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        let optimized = match &self.optimized_sequence {
+            Some(optimized) if self.found_optimization => optimized,
+            _ => {
+                let mut s = format!("No optimization found (cost {}):\n", original_cost);
+                for line in &original {
+                    s.push_str(&format!("  {}\n", line));
+                }
+                return s;
+            }
+        };
+        let optimized_cost = sequence_cost(optimized, metric);
+        let optimized_lines: Vec<String> = optimized.iter().map(ToString::to_string).collect();
+
+        let left_header = format!("Original (cost {})", original_cost);
+        let width = original
+            .iter()
+            .map(|line| line.len() + 2)
+            .chain([left_header.len()])
+            .max()
+            .unwrap_or(0);
+        let mut s = format!(
+            "{:<width$} | Optimized (cost {})\n",
+            left_header, optimized_cost
+        );
+        for row in 0..original.len().max(optimized_lines.len()) {
+            let left = original
+                .get(row)
+                .map_or(String::new(), |l| format!("  {}", l));
+            let right = optimized_lines
+                .get(row)
+                .map_or(String::new(), |l| format!("  {}", l));
+            s.push_str(format!("{:<width$} | {}", left, right).trim_end());
+            s.push('\n');
+        }
+        s.push_str(&format!(
+            "Cost: {} -> {} (saves {})\n",
+            original_cost,
+            optimized_cost,
+            original_cost as i64 - optimized_cost as i64
+        ));
+        s
+    }
 }
 
 /// Generic search-result type. For AArch64, callers can ignore the
@@ -91,11 +163,18 @@ impl<I: ISA> SearchResultFor<I> {
         }
     }
 
+    /// Mirrors `SearchResult::with_optimization`, including the rejection
+    /// of non-improving optimizations. `cost` prices a sequence the way the
+    /// backend's search does.
     pub fn with_optimization(
         original: Vec<I::Instruction>,
         optimized: Vec<I::Instruction>,
         statistics: SearchStatistics,
+        cost: impl Fn(&[I::Instruction]) -> u64,
     ) -> Self {
+        if reject_non_improving(cost(&original), cost(&optimized)) {
+            return Self::no_optimization(original, statistics);
+        }
         Self {
             optimized_sequence: Some(optimized),
             original_sequence: original,
@@ -105,16 +184,66 @@ impl<I: ISA> SearchResultFor<I> {
     }
 }
 
+/// `optimized` respelled for reporting and patching: disguised moves become
+/// the `mov` they are ([`Instruction::simplified`]) and every instruction
+/// takes its canonical spelling ([`canonicalize`]). Both rewrites preserve
+/// each instruction's effect, but the respelling is only kept when it costs
+/// no more than `optimized` under `metric` and the solver proves it matches
+/// `optimized` on every register and the flags.
+fn canonical_spelling(optimized: Vec<Instruction>, metric: &CostMetric) -> Vec<Instruction> {
+    let simplified: Vec<Instruction> = optimized.iter().map(Instruction::simplified).collect();
+    let respelled = canonicalize(&simplified);
+    if respelled == optimized
+        || sequence_cost(&respelled, metric) > sequence_cost(&optimized, metric)
+    {
+        return optimized;
+    }
+    let every_register =
+        EquivalenceConfig::with_live_out(LiveOut::all_registers().with_flags(true));
+    match check_equivalence_with_config(&optimized, &respelled, &every_register) {
+        EquivalenceResult::Equivalent => respelled,
+        _ => optimized,
+    }
+}
+
+/// Shared guard for both `with_optimization` constructors; `true` means the
+/// optimization is not strictly cheaper and the caller must fall back to a
+/// no-optimization result.
+fn reject_non_improving(original_cost: u64, optimized_cost: u64) -> bool {
+    if optimized_cost < original_cost {
+        return false;
+    }
+    debug_assert!(
+        false,
+        "non-improving optimization reported: cost {optimized_cost} vs original {original_cost}"
+    );
+    search_event!(
+        warn,
+        true;
+        "Warning: discarding a reported optimization that costs {} against an original cost of {}",
+        optimized_cost,
+        original_cost
+    );
+    true
+}
+
 /// Backward-compatible conversion from the generic result type into the
 /// AArch64-specific `SearchResult`. Used by the parallel coordinator
 /// (still AArch64-typed) and any consumer that hasn't been migrated to
 /// the generic shape.
 impl From<SearchResultFor<crate::isa::AArch64>> for SearchResult {
     fn from(r: SearchResultFor<crate::isa::AArch64>) -> Self {
-        if let Some(opt) = r.optimized_sequence {
-            SearchResult::with_optimization(r.original_sequence, opt, r.statistics)
-        } else {
-            SearchResult::no_optimization(r.original_sequence, r.statistics)
+        match r.optimized_sequence {
+            Some(optimized) if r.found_optimization => {
+                let metric = r.statistics.cost_metric;
+                SearchResult::with_optimization(
+                    r.original_sequence,
+                    optimized,
+                    r.statistics,
+                    &metric,
+                )
+            }
+            _ => SearchResult::no_optimization(r.original_sequence, r.statistics),
         }
     }
 }
@@ -173,6 +302,23 @@ impl VerificationTally {
     }
 }
 
+/// Rates derived from a [`SearchStatistics`], as data for dashboards and
+/// regression thresholds rather than log text. See
+/// [`SearchStatistics::throughput_summary`].
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+pub struct ThroughputSummary {
+    /// Candidates evaluated per second of `elapsed_time`
+    pub candidates_per_sec: f64,
+    /// Candidates passing fast validation per second of `elapsed_time`
+    pub fast_passes_per_sec: f64,
+    /// SMT queries issued per second of `elapsed_time`
+    pub smt_per_sec: f64,
+    /// Fraction of evaluated candidates that passed fast validation
+    pub fast_pass_rate: f64,
+    /// Fraction of stochastic proposals accepted
+    pub acceptance_rate: f64,
+}
+
 /// Statistics from a search operation
 #[derive(Debug, Clone, Default)]
 pub struct SearchStatistics {
@@ -184,7 +330,8 @@ pub struct SearchStatistics {
     /// This includes candidates later rejected by a cost/best-bound gate.
     pub candidates_evaluated: u64,
     /// Number of evaluated candidates rejected before verification because
-    /// they were not cheaper than the current best solution.
+    /// they were not cheaper than the current best solution or did not fit
+    /// `SearchConfig::max_bytes`.
     pub candidates_pruned_by_cost: u64,
     /// Number of candidates that passed fast (concrete) validation
     pub candidates_passed_fast: u64,
@@ -197,6 +344,9 @@ pub struct SearchStatistics {
     pub smt_elapsed: Duration,
     /// Number of SMT queries that proved equivalence
     pub smt_equivalent: u64,
+    /// Number of SMT queries that ended `Unknown` (solver timeout or an
+    /// inconclusive result). Only the stochastic search tallies these.
+    pub smt_unknowns: u64,
     /// Number of iterations (for stochastic search)
     pub iterations: u64,
     /// Number of accepted proposals (for stochastic search)
@@ -205,6 +355,8 @@ pub struct SearchStatistics {
     pub best_cost_found: u64,
     /// Original sequence cost
     pub original_cost: u64,
+    /// Metric `original_cost` and `best_cost_found` are priced under
+    pub cost_metric: CostMetric,
     /// Number of times the search improved the current best
     pub improvements_found: u64,
 }
@@ -259,25 +411,44 @@ impl SearchStatistics {
         tally.proved_equivalent
     }
 
+    /// Fold `other` into `self`.
+    ///
+    /// Every counter field **sums**; `elapsed_time` and `original_cost` take
+    /// the **max**; `best_cost_found` takes the **minimum nonzero** value (0
+    /// means "never recorded", not a zero-cost sequence). `algorithm` and
+    /// `cost_metric` are left untouched.
+    pub fn merge(&mut self, other: &SearchStatistics) {
+        self.candidates_evaluated += other.candidates_evaluated;
+        self.candidates_pruned_by_cost += other.candidates_pruned_by_cost;
+        self.candidates_passed_fast += other.candidates_passed_fast;
+        self.smt_queries += other.smt_queries;
+        self.smt_elapsed += other.smt_elapsed;
+        self.smt_equivalent += other.smt_equivalent;
+        self.smt_unknowns += other.smt_unknowns;
+        self.iterations += other.iterations;
+        self.accepted_proposals += other.accepted_proposals;
+        self.improvements_found += other.improvements_found;
+        self.elapsed_time = self.elapsed_time.max(other.elapsed_time);
+        self.original_cost = self.original_cost.max(other.original_cost);
+        self.best_cost_found = match (self.best_cost_found, other.best_cost_found) {
+            (0, theirs) => theirs,
+            (ours, 0) => ours,
+            (ours, theirs) => ours.min(theirs),
+        };
+    }
+
     /// Combine the per-worker statistics collected by the parallel coordinator
     /// into a single cross-worker aggregate for a hybrid search.
     ///
-    /// This is the single home for the reduce rules the parallel coordinator
-    /// used to inline into its receive loop. Keeping the field list next to
-    /// [`SearchStatistics`] means adding a counter can no longer silently
-    /// under-report in the aggregate.
-    ///
-    /// The rules, matching the [`ParallelResult`](crate::search::parallel::coordinator)
-    /// contract:
-    /// * every counter field **sums** across workers;
-    /// * `original_cost` takes the **max** — workers see the same target, so the
-    ///   max is robust against a worker that exited before recording it;
-    /// * `best_cost_found` takes the **minimum nonzero** value, falling back to
-    ///   the aggregated `original_cost` so the CLI never reports a best cost of 0
-    ///   when some worker verified a candidate;
+    /// The per-field rules are those of [`Self::merge`], applied across every
+    /// worker, plus:
+    /// * `best_cost_found` falls back to the aggregated `original_cost` when no
+    ///   worker recorded one, so the CLI never reports a best cost of 0;
     /// * `algorithm` is always [`Algorithm::Hybrid`] (the parallel coordinator's
     ///   identity) and `elapsed_time` is the coordinator wall-clock, passed in so
-    ///   every aggregate shares one time origin.
+    ///   every aggregate shares one time origin;
+    /// * `cost_metric` is the first worker's, as every worker prices under the
+    ///   same search configuration.
     ///
     /// The worker id in each entry is not part of the aggregation; it is accepted
     /// so callers can pass the coordinator's `worker_stats` vector directly.
@@ -286,29 +457,16 @@ impl SearchStatistics {
         elapsed: Duration,
     ) -> SearchStatistics {
         let mut total = SearchStatistics::new(Algorithm::Hybrid);
-        total.elapsed_time = elapsed;
         for (_, s) in worker_stats {
-            total.candidates_evaluated += s.candidates_evaluated;
-            total.candidates_pruned_by_cost += s.candidates_pruned_by_cost;
-            total.candidates_passed_fast += s.candidates_passed_fast;
-            total.smt_queries += s.smt_queries;
-            total.smt_elapsed += s.smt_elapsed;
-            total.smt_equivalent += s.smt_equivalent;
-            total.iterations += s.iterations;
-            total.accepted_proposals += s.accepted_proposals;
-            total.improvements_found += s.improvements_found;
+            total.merge(s);
+        }
+        total.elapsed_time = elapsed;
+        if let Some((_, first)) = worker_stats.first() {
+            total.cost_metric = first.cost_metric;
+        }
+        if total.best_cost_found == 0 {
+            total.best_cost_found = total.original_cost;
         }
-        total.original_cost = worker_stats
-            .iter()
-            .map(|(_, s)| s.original_cost)
-            .max()
-            .unwrap_or(0);
-        total.best_cost_found = worker_stats
-            .iter()
-            .map(|(_, s)| s.best_cost_found)
-            .filter(|&c| c > 0)
-            .min()
-            .unwrap_or(total.original_cost);
         total
     }
 
@@ -346,11 +504,37 @@ impl SearchStatistics {
 
     /// Get candidates evaluated per second
     pub fn throughput(&self) -> f64 {
+        self.per_second(self.candidates_evaluated)
+    }
+
+    /// Get candidates passing fast validation per second
+    pub fn fast_passes_per_second(&self) -> f64 {
+        self.per_second(self.candidates_passed_fast)
+    }
+
+    /// Get SMT queries issued per second
+    pub fn smt_queries_per_second(&self) -> f64 {
+        self.per_second(self.smt_queries)
+    }
+
+    /// The derived rates in one value; every field is 0.0 when its
+    /// denominator is zero, like the individual rate methods.
+    pub fn throughput_summary(&self) -> ThroughputSummary {
+        ThroughputSummary {
+            candidates_per_sec: self.throughput(),
+            fast_passes_per_sec: self.fast_passes_per_second(),
+            smt_per_sec: self.smt_queries_per_second(),
+            fast_pass_rate: self.fast_pass_rate(),
+            acceptance_rate: self.acceptance_rate(),
+        }
+    }
+
+    fn per_second(&self, count: u64) -> f64 {
         let secs = self.elapsed_time.as_secs_f64();
         if secs == 0.0 {
             0.0
         } else {
-            self.candidates_evaluated as f64 / secs
+            count as f64 / secs
         }
     }
 
@@ -388,6 +572,9 @@ impl SearchStatistics {
                 self.smt_success_rate() * 100.0
             ));
         }
+        if self.smt_unknowns > 0 {
+            s.push_str(&format!("SMT unknown: {}\n", self.smt_unknowns));
+        }
 
         if self.algorithm == Algorithm::Stochastic && self.iterations > 0 {
             s.push_str(&format!("Iterations: {}\n", self.iterations));
@@ -445,7 +632,7 @@ impl std::fmt::Display for SearchResult {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::ir::{Operand, Register};
+    use crate::ir::{Condition, Operand, Register};
 
     fn sample_sequence() -> Vec<Instruction> {
         vec![
@@ -469,6 +656,30 @@ mod tests {
         }]
     }
 
+    #[test]
+    fn diff_summary_lists_both_sequences_and_the_savings() {
+        let result = SearchResult::with_optimization(
+            sample_sequence(),
+            optimized_sequence(),
+            SearchStatistics::default(),
+            &CostMetric::InstructionCount,
+        );
+
+        assert_eq!(
+            result.diff_summary(&CostMetric::InstructionCount),
+            "Original (cost 2) | Optimized (cost 1)\n\
+             \x20 mov x0, x1      |   add x0, x1, #1\n\
+             \x20 add x0, x0, #1  |\n\
+             Cost: 2 -> 1 (saves 1)\n"
+        );
+
+        let none = SearchResult::no_optimization(sample_sequence(), SearchStatistics::default());
+        assert_eq!(
+            none.diff_summary(&CostMetric::InstructionCount),
+            "No optimization found (cost 2):\n  mov x0, x1\n  add x0, x0, #1\n"
+        );
+    }
+
     #[test]
     fn test_search_result_no_optimization() {
         let stats = SearchStatistics::default();
@@ -482,14 +693,74 @@ mod tests {
     #[test]
     fn test_search_result_with_optimization() {
         let stats = SearchStatistics::default();
-        let result =
-            SearchResult::with_optimization(sample_sequence(), optimized_sequence(), stats);
+        let result = SearchResult::with_optimization(
+            sample_sequence(),
+            optimized_sequence(),
+            stats,
+            &CostMetric::InstructionCount,
+        );
 
         assert!(result.found_optimization);
         assert!(result.optimized_sequence.is_some());
         assert_eq!(result.cost_savings(), 1);
     }
 
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "non-improving optimization reported: cost 2 vs original 2")]
+    fn with_optimization_rejects_a_same_cost_sequence_in_debug() {
+        let _ = SearchResult::with_optimization(
+            sample_sequence(),
+            sample_sequence(),
+            SearchStatistics::default(),
+            &CostMetric::InstructionCount,
+        );
+    }
+
+    /// A sequence that does not beat the original is rejected from its own
+    /// cost, whatever the statistics claim.
+    #[test]
+    #[cfg(not(debug_assertions))]
+    fn with_optimization_normalizes_a_non_improving_sequence_in_release() {
+        let result = SearchResult::with_optimization(
+            sample_sequence(),
+            sample_sequence(),
+            SearchStatistics::default(),
+            &CostMetric::InstructionCount,
+        );
+        assert!(!result.found_optimization);
+        assert!(result.optimized_sequence.is_none());
+
+        let costlier = [sample_sequence(), optimized_sequence()].concat();
+        let generic = SearchResultFor::<crate::isa::AArch64>::with_optimization(
+            sample_sequence(),
+            costlier,
+            SearchStatistics {
+                original_cost: 3,
+                best_cost_found: 1,
+                ..SearchStatistics::default()
+            },
+            |seq| sequence_cost(seq, &CostMetric::InstructionCount),
+        );
+        assert!(!generic.found_optimization);
+        assert!(generic.optimized_sequence.is_none());
+    }
+
+    /// The AArch64 conversion re-vets a generic result under the metric its
+    /// statistics record, so a hand-built result cannot skip the check.
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "non-improving optimization reported: cost 3 vs original 2")]
+    fn aarch64_conversion_rejects_a_non_improving_generic_result() {
+        let generic = SearchResultFor::<crate::isa::AArch64> {
+            optimized_sequence: Some([sample_sequence(), optimized_sequence()].concat()),
+            original_sequence: sample_sequence(),
+            found_optimization: true,
+            statistics: SearchStatistics::default(),
+        };
+        let _ = SearchResult::from(generic);
+    }
+
     #[test]
     fn test_statistics_acceptance_rate() {
         let mut stats = SearchStatistics::new(Algorithm::Stochastic);
@@ -500,6 +771,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::field_reassign_with_default)]
     fn test_statistics_fast_pass_rate() {
         let mut stats = SearchStatistics::default();
         stats.candidates_evaluated = 100;
@@ -509,6 +781,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::field_reassign_with_default)]
     fn test_statistics_smt_success_rate() {
         let mut stats = SearchStatistics::default();
         stats.smt_queries = 50;
@@ -518,6 +791,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::field_reassign_with_default)]
     fn test_statistics_throughput() {
         let mut stats = SearchStatistics::default();
         stats.candidates_evaluated = 10000;
@@ -526,6 +800,45 @@ mod tests {
         assert!((stats.throughput() - 1000.0).abs() < 1e-10);
     }
 
+    #[test]
+    fn test_statistics_fast_pass_and_smt_rates_per_second() {
+        let stats = SearchStatistics {
+            candidates_passed_fast: 500,
+            smt_queries: 40,
+            elapsed_time: Duration::from_secs(4),
+            ..Default::default()
+        };
+
+        assert!((stats.fast_passes_per_second() - 125.0).abs() < 1e-10);
+        assert!((stats.smt_queries_per_second() - 10.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_throughput_summary_derives_rates_from_counters() {
+        let stats = SearchStatistics {
+            candidates_evaluated: 2000,
+            candidates_passed_fast: 500,
+            smt_queries: 40,
+            iterations: 1000,
+            accepted_proposals: 250,
+            elapsed_time: Duration::from_secs(4),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            stats.throughput_summary(),
+            ThroughputSummary {
+                candidates_per_sec: 500.0,
+                fast_passes_per_sec: 125.0,
+                smt_per_sec: 10.0,
+                fast_pass_rate: 0.25,
+                acceptance_rate: 0.25,
+            }
+        );
+        let json = serde_json::to_value(stats.throughput_summary()).unwrap();
+        assert_eq!(json["candidates_per_sec"], 500.0);
+    }
+
     #[test]
     fn test_statistics_zero_division() {
         let stats = SearchStatistics::default();
@@ -533,6 +846,8 @@ mod tests {
         assert_eq!(stats.fast_pass_rate(), 0.0);
         assert_eq!(stats.smt_success_rate(), 0.0);
         assert_eq!(stats.throughput(), 0.0);
+        assert_eq!(stats.fast_passes_per_second(), 0.0);
+        assert_eq!(stats.smt_queries_per_second(), 0.0);
     }
 
     #[test]
@@ -569,14 +884,61 @@ mod tests {
         assert!(no_opt_text.contains("Original sequence"));
         assert!(no_opt_text.contains("mov x0, x1"));
 
-        let with_opt =
-            SearchResult::with_optimization(sample_sequence(), optimized_sequence(), stats);
+        let with_opt = SearchResult::with_optimization(
+            sample_sequence(),
+            optimized_sequence(),
+            stats,
+            &CostMetric::InstructionCount,
+        );
         let with_opt_text = format!("{}", with_opt);
         assert!(with_opt_text.contains("Optimization found!"));
         assert!(with_opt_text.contains("Optimized sequence"));
         assert!(with_opt_text.contains("Savings: 1 instructions"));
     }
 
+    /// The returned sequence is the canonical spelling of what the backend
+    /// found, and the report shows exactly those instructions.
+    #[test]
+    fn test_with_optimization_returns_and_prints_the_canonical_spelling() {
+        let optimized = vec![
+            Instruction::Add {
+                rd: Register::X0,
+                rn: Register::X1,
+                rm: Operand::Immediate(0),
+            },
+            Instruction::Csel {
+                rd: Register::X0,
+                rn: Register::X1,
+                rm: Register::X2,
+                cond: Condition::NE,
+            },
+        ];
+        let result = SearchResult::with_optimization(
+            [sample_sequence(), sample_sequence()].concat(),
+            optimized,
+            SearchStatistics::default(),
+            &CostMetric::InstructionCount,
+        );
+        assert_eq!(
+            result.optimized_sequence,
+            Some(vec![
+                Instruction::MovReg {
+                    rd: Register::X0,
+                    rn: Register::X1,
+                },
+                Instruction::Csel {
+                    rd: Register::X0,
+                    rn: Register::X2,
+                    rm: Register::X1,
+                    cond: Condition::EQ,
+                },
+            ])
+        );
+        let text = format!("{}", result);
+        assert!(text.contains("  mov x0, x1\n"), "{}", text);
+        assert!(text.contains("  csel x0, x2, x1, eq\n"), "{}", text);
+    }
+
     // --- Verification accounting seam (verification_tally / record_verification) ---
     //
     // These pin the canonical policy for folding one candidate verification into
@@ -738,10 +1100,12 @@ mod tests {
             smt_queries: 3,
             smt_elapsed: Duration::from_millis(4),
             smt_equivalent: 1,
+            smt_unknowns: 1,
             iterations: 100,
             accepted_proposals: 20,
             improvements_found: 2,
             original_cost: 6,
+            cost_metric: CostMetric::Latency,
             best_cost_found: 4,
             elapsed_time: Duration::from_millis(900),
         };
@@ -753,16 +1117,46 @@ mod tests {
             smt_queries: 2,
             smt_elapsed: Duration::from_millis(6),
             smt_equivalent: 1,
+            smt_unknowns: 0,
             iterations: 50,
             accepted_proposals: 10,
             improvements_found: 1,
             original_cost: 6,
+            cost_metric: CostMetric::Latency,
             best_cost_found: 3,
             elapsed_time: Duration::from_millis(500),
         };
         vec![(0, a), (1, b)]
     }
 
+    #[test]
+    fn merge_sums_counters_and_keeps_extremes() {
+        let workers = two_worker_stats();
+        let mut merged = workers[0].1.clone();
+        merged.merge(&workers[1].1);
+
+        assert_eq!(merged.candidates_evaluated, 17);
+        assert_eq!(merged.candidates_pruned_by_cost, 3);
+        assert_eq!(merged.candidates_passed_fast, 8);
+        assert_eq!(merged.smt_queries, 5);
+        assert_eq!(merged.smt_elapsed, Duration::from_millis(10));
+        assert_eq!(merged.smt_equivalent, 2);
+        assert_eq!(merged.smt_unknowns, 1);
+        assert_eq!(merged.iterations, 150);
+        assert_eq!(merged.accepted_proposals, 30);
+        assert_eq!(merged.improvements_found, 3);
+        assert_eq!(merged.best_cost_found, 3);
+        assert_eq!(merged.original_cost, 6);
+        assert_eq!(merged.elapsed_time, Duration::from_millis(900));
+        // The receiver's identity is kept.
+        assert_eq!(merged.algorithm, Algorithm::Stochastic);
+
+        // An unrecorded (zero) best cost never wins the minimum.
+        let mut fresh = SearchStatistics::new(Algorithm::Hybrid);
+        fresh.merge(&workers[1].1);
+        assert_eq!(fresh.best_cost_found, 3);
+    }
+
     #[test]
     fn aggregate_workers_sums_counters_and_labels_hybrid() {
         let total =
@@ -782,6 +1176,7 @@ mod tests {
         // The aggregate is labelled Hybrid and carries the passed-in wall-clock,
         // regardless of the per-worker algorithms or elapsed times.
         assert_eq!(total.algorithm, Algorithm::Hybrid);
+        assert_eq!(total.cost_metric, CostMetric::Latency);
         assert_eq!(total.elapsed_time, Duration::from_millis(250));
     }
 
@@ -1,11 +1,14 @@
 //! Metropolis acceptance criterion for stochastic search
 //!
-//! Implements the cost-only acceptance decision for stochastic search. This
-//! module does not receive proposal probabilities and therefore does not apply
-//! a Hastings correction for asymmetric mutation proposals.
-//! A proposal is accepted if:
+//! Implements the acceptance decision for stochastic search. `accept` is the
+//! cost-only Metropolis rule: a proposal is accepted if
 //!   proposal_cost < current_cost - ln(random) / beta
 //!
+//! `accept_with_proposal_ratio` adds the Hastings correction for asymmetric
+//! proposals (e.g. insert vs. delete moves that change the sequence length),
+//! accepting with probability
+//!   min(1, exp(-beta * (proposal_cost - current_cost)) * q_backward / q_forward)
+//!
 //! Where beta is the inverse temperature parameter.
 //! Higher beta = more greedy (less likely to accept worse solutions)
 //! Lower beta = more exploration (more likely to accept worse solutions)
@@ -87,6 +90,51 @@ impl AcceptanceCriterion {
         (cost_delta as f64) < threshold
     }
 
+    /// Metropolis-Hastings acceptance for asymmetric proposals
+    ///
+    /// # Arguments
+    /// * `rng` - Random number generator
+    /// * `current_cost` - Cost of current solution
+    /// * `proposal_cost` - Cost of proposed solution
+    /// * `forward_prob` - Probability of proposing this move from the current state
+    /// * `backward_prob` - Probability of proposing the reverse move from the proposal
+    ///
+    /// With `forward_prob == backward_prob` this is equivalent to `accept`.
+    pub fn accept_with_proposal_ratio<R: RngExt>(
+        &self,
+        rng: &mut R,
+        current_cost: u64,
+        proposal_cost: u64,
+        forward_prob: f64,
+        backward_prob: f64,
+    ) -> bool {
+        let p = self.acceptance_probability_with_proposal_ratio(
+            proposal_cost as i64 - current_cost as i64,
+            forward_prob,
+            backward_prob,
+        );
+        p >= 1.0 || rng.random::<f64>() < p
+    }
+
+    /// Calculate the Metropolis-Hastings acceptance probability
+    ///
+    /// P(accept) = min(1, exp(-beta * delta) * backward_prob / forward_prob)
+    ///
+    /// A move that could not have been proposed (`forward_prob` not positive)
+    /// is rejected outright.
+    pub fn acceptance_probability_with_proposal_ratio(
+        &self,
+        cost_delta: i64,
+        forward_prob: f64,
+        backward_prob: f64,
+    ) -> f64 {
+        if forward_prob.is_nan() || forward_prob <= 0.0 {
+            return 0.0;
+        }
+        let log_ratio = -self.beta * cost_delta as f64 + backward_prob.ln() - forward_prob.ln();
+        log_ratio.exp().min(1.0)
+    }
+
     /// Calculate acceptance probability for a cost difference
     ///
     /// P(accept) = min(1, exp(-beta * delta)) for delta >= 0
@@ -191,6 +239,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::manual_range_contains)]
     fn test_acceptance_probability_worse() {
         let criterion = AcceptanceCriterion::new(1.0);
 
@@ -248,6 +297,70 @@ mod tests {
         assert!(max > min + 0.1);
     }
 
+    #[test]
+    fn test_proposal_ratio_reduces_to_metropolis_when_symmetric() {
+        let criterion = AcceptanceCriterion::new(0.5);
+        for delta in [-3, 0, 1, 4] {
+            let mh = criterion.acceptance_probability_with_proposal_ratio(delta, 0.3, 0.3);
+            assert!((mh - criterion.acceptance_probability(delta)).abs() < 1e-12);
+        }
+        // A reverse move that is four times rarer scales acceptance by 1/4.
+        let p = criterion.acceptance_probability_with_proposal_ratio(0, 0.8, 0.2);
+        assert!((p - 0.25).abs() < 1e-12);
+        // An impossible forward move is rejected instead of panicking.
+        assert_eq!(
+            criterion.acceptance_probability_with_proposal_ratio(-3, 0.0, 0.5),
+            0.0
+        );
+    }
+
+    /// Two-state chain over sequence lengths 1 and 2 on a flat cost
+    /// landscape: from length 1 an insert is proposed with probability 0.8,
+    /// from length 2 a delete with probability 0.2. Uncorrected Metropolis
+    /// settles where the insert/delete ratio puts it (4:1 in favour of
+    /// length 2); the Hastings-corrected rule recovers the flat target.
+    #[test]
+    fn test_flat_landscape_length_distribution() {
+        use rand::SeedableRng;
+
+        const P_INSERT: f64 = 0.8;
+        const P_DELETE: f64 = 0.2;
+        const STEPS: usize = 200_000;
+
+        let criterion = AcceptanceCriterion::new(1.0);
+        let run = |corrected: bool| -> f64 {
+            let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(604);
+            let mut len = 1;
+            let mut at_two = 0usize;
+            for _ in 0..STEPS {
+                let (propose, forward, backward) = if len == 1 {
+                    (P_INSERT, P_INSERT, P_DELETE)
+                } else {
+                    (P_DELETE, P_DELETE, P_INSERT)
+                };
+                if rng.random::<f64>() < propose {
+                    let accepted = if corrected {
+                        criterion.accept_with_proposal_ratio(&mut rng, 5, 5, forward, backward)
+                    } else {
+                        criterion.accept(&mut rng, 5, 5)
+                    };
+                    if accepted {
+                        len = 3 - len;
+                    }
+                }
+                at_two += usize::from(len == 2);
+            }
+            at_two as f64 / STEPS as f64
+        };
+
+        let uncorrected = run(false);
+        let expected = P_INSERT / (P_INSERT + P_DELETE);
+        assert!((uncorrected - expected).abs() < 0.02, "{uncorrected}");
+
+        let corrected = run(true);
+        assert!((corrected - 0.5).abs() < 0.02, "{corrected}");
+    }
+
     #[test]
     #[should_panic(expected = "beta must be positive")]
     fn test_invalid_beta_zero() {
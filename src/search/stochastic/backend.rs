@@ -71,8 +71,26 @@ pub trait StochasticBackend<I: ISA>: Sized {
 
     /// Sum the cost of every instruction in the sequence.
     fn sequence_cost(seq: &[I::Instruction], metric: &CostMetric, width: u32) -> u64;
+    /// As [`EnumerativeBackend::bind_live_out`].
+    ///
+    /// [`EnumerativeBackend::bind_live_out`]: crate::search::enumerative::search::EnumerativeBackend::bind_live_out
+    fn bind_live_out(metric: CostMetric, _live_out: &Self::LiveOut) -> CostMetric {
+        metric
+    }
     /// Sequence-level encodability against the ISA's assembler.
     fn is_encodable(seq: &[I::Instruction]) -> bool;
+    /// Whether `seq` respects the per-instruction limits of `config` beyond
+    /// encodability, such as `SearchConfig::max_immediate_bits`. The default
+    /// accepts every sequence.
+    fn within_config_limits(_seq: &[I::Instruction], _config: &SearchConfig) -> bool {
+        true
+    }
+    /// Bytes `seq` assembles to, for `SearchConfig::max_bytes`. `None` (the
+    /// default) when the backend cannot size it; such candidates are never
+    /// pruned by size.
+    fn encoded_length(_seq: &[I::Instruction]) -> Option<usize> {
+        None
+    }
 
     /// Run the full equivalence check.
     fn check_equivalence(
@@ -83,6 +101,23 @@ pub trait StochasticBackend<I: ISA>: Sized {
         timeout: Duration,
     ) -> (EquivalenceResult, EquivalenceMetrics);
 
+    /// Solver counterexample carried by `check_equivalence`'s metrics, as an
+    /// input state the concrete tests can replay. Default returns `None`;
+    /// AArch64 overrides to surface the SMT model.
+    fn counterexample_input(_metrics: &EquivalenceMetrics) -> Option<Self::State> {
+        None
+    }
+
+    /// Run the concrete-only approximate equivalence check with
+    /// `num_tests` random inputs. Never invokes the SMT solver.
+    fn check_equivalence_approx(
+        target: &[I::Instruction],
+        proposal: &[I::Instruction],
+        live_out: &Self::LiveOut,
+        width: u32,
+        num_tests: usize,
+    ) -> EquivalenceResult;
+
     /// Generate a random sequence of length `len` from the supplied
     /// pools.
     fn random_sequence<R: RngExt>(
@@ -93,6 +128,12 @@ pub trait StochasticBackend<I: ISA>: Sized {
         config: &SearchConfig,
     ) -> Vec<I::Instruction>;
 
+    /// The configured starting sequence, if any. Default returns `None`;
+    /// AArch64 overrides to read `StochasticConfig::initial_program`.
+    fn initial_program(_config: &SearchConfig) -> Option<Vec<I::Instruction>> {
+        None
+    }
+
     /// Return the target's trailing terminator if any. MCMC appends it
     /// to every `random_sequence` result so the equivalence check's
     /// terminator-equality precheck doesn't reject every random
@@ -102,6 +143,13 @@ pub trait StochasticBackend<I: ISA>: Sized {
         None
     }
 
+    /// Key under which MCMC caches refuted proposals: proposals with equal
+    /// keys share one equivalence verdict. Default is `seq` itself; AArch64
+    /// renames scratch registers so alpha-equivalent proposals share a key.
+    fn dedup_key(seq: &[I::Instruction], _live_out: &Self::LiveOut) -> Vec<I::Instruction> {
+        seq.to_vec()
+    }
+
     /// Width parameter for cost + state masking. Architecture markers own
     /// this width so a mismatched config cannot silently change semantics;
     /// implementations return an architectural constant.
@@ -115,7 +163,7 @@ impl StochasticBackend<crate::isa::AArch64> for crate::isa::AArch64 {
     type LiveOut = crate::semantics::live_out::LiveOut;
 
     fn registers_from_config(config: &SearchConfig) -> Vec<crate::ir::Register> {
-        config.available_registers.clone()
+        config.search_registers()
     }
 
     fn immediates_from_config(config: &SearchConfig) -> Vec<i64> {
@@ -124,10 +172,11 @@ impl StochasticBackend<crate::isa::AArch64> for crate::isa::AArch64 {
 
     fn make_mutator(config: &SearchConfig) -> crate::search::stochastic::mutation::AArch64Mutator {
         crate::search::stochastic::mutation::AArch64Mutator::new(
-            config.available_registers.clone(),
+            config.search_registers(),
             config.available_immediates.clone(),
             config.stochastic.mutation_weights.clone(),
         )
+        .with_trivial_shifts(config.include_trivial_shifts)
     }
 
     fn validation_registers(
@@ -182,7 +231,16 @@ impl StochasticBackend<crate::isa::AArch64> for crate::isa::AArch64 {
         // a register/flag prefilter; the full equivalence path force-enables
         // memory comparison when either sequence touches memory, so
         // `memory_live = false` here.
-        crate::semantics::concrete::states_equal_for_live_out(s1, s2, live_out, false)
+        crate::semantics::concrete::states_equal_for_live_out(
+            s1,
+            s2,
+            live_out,
+            &crate::semantics::live_out::MemLiveOut::None,
+        )
+    }
+
+    fn bind_live_out(metric: CostMetric, live_out: &Self::LiveOut) -> CostMetric {
+        metric.with_live_out(live_out)
     }
 
     fn sequence_cost(seq: &[crate::ir::Instruction], metric: &CostMetric, _width: u32) -> u64 {
@@ -197,6 +255,29 @@ impl StochasticBackend<crate::isa::AArch64> for crate::isa::AArch64 {
         crate::search::candidate::is_sequence_encodable(seq)
     }
 
+    fn within_config_limits(seq: &[crate::ir::Instruction], config: &SearchConfig) -> bool {
+        seq.iter().all(|instr| {
+            crate::search::candidate::fits_immediate_bits(instr, config.max_immediate_bits)
+        })
+    }
+
+    fn encoded_length(seq: &[crate::ir::Instruction]) -> Option<usize> {
+        Some(seq.iter().map(crate::ir::Instruction::encoded_length).sum())
+    }
+
+    fn dedup_key(
+        seq: &[crate::ir::Instruction],
+        live_out: &Self::LiveOut,
+    ) -> Vec<crate::ir::Instruction> {
+        crate::search::normalize::canonicalize(&crate::search::normalize::normalize_registers(
+            seq, live_out,
+        ))
+    }
+
+    fn initial_program(config: &SearchConfig) -> Option<Vec<crate::ir::Instruction>> {
+        config.stochastic.initial_program.clone()
+    }
+
     fn check_equivalence(
         target: &[crate::ir::Instruction],
         proposal: &[crate::ir::Instruction],
@@ -214,14 +295,37 @@ impl StochasticBackend<crate::isa::AArch64> for crate::isa::AArch64 {
         crate::semantics::equivalence::check_equivalence_with_config_metrics(target, proposal, &cfg)
     }
 
+    fn counterexample_input(metrics: &EquivalenceMetrics) -> Option<Self::State> {
+        metrics.smt_counterexample.clone()
+    }
+
+    fn check_equivalence_approx(
+        target: &[crate::ir::Instruction],
+        proposal: &[crate::ir::Instruction],
+        live_out: &Self::LiveOut,
+        _width: u32,
+        num_tests: usize,
+    ) -> EquivalenceResult {
+        crate::semantics::equivalence::check_equivalence_approx(
+            target, proposal, live_out, num_tests,
+        )
+    }
+
     fn random_sequence<R: RngExt>(
         rng: &mut R,
         len: usize,
         regs: &[crate::ir::Register],
         imms: &[i64],
-        _config: &SearchConfig,
+        config: &SearchConfig,
     ) -> Vec<crate::ir::Instruction> {
-        crate::search::candidate::generate_random_sequence(rng, len, regs, imms)
+        let shifts =
+            crate::search::candidate::random_shift_immediates(config.include_trivial_shifts);
+        (0..len)
+            .map(|_| {
+                crate::isa::aarch64::AArch64InstructionGenerator
+                    .generate_random_with_shifts(rng, regs, imms, shifts)
+            })
+            .collect()
     }
 
     fn width() -> u32 {
@@ -390,6 +494,22 @@ impl StochasticBackend<crate::isa::X86_64> for crate::isa::X86_64 {
         )
     }
 
+    fn check_equivalence_approx(
+        target: &[crate::isa::x86::X86Instruction],
+        proposal: &[crate::isa::x86::X86Instruction],
+        live_out: &Self::LiveOut,
+        _width: u32,
+        num_tests: usize,
+    ) -> EquivalenceResult {
+        let cfg =
+            crate::semantics::equivalence::EquivalenceConfigFor::<crate::isa::X86_64>::default()
+                .live_out(live_out.clone())
+                .random_tests(num_tests);
+        crate::semantics::equivalence::check_equivalence_approx_for::<crate::isa::X86_64>(
+            target, proposal, &cfg,
+        )
+    }
+
     fn random_sequence<R: RngExt>(
         rng: &mut R,
         len: usize,
@@ -489,6 +609,22 @@ impl StochasticBackend<crate::isa::X86_32> for crate::isa::X86_32 {
         )
     }
 
+    fn check_equivalence_approx(
+        target: &[crate::isa::x86::X86Instruction],
+        proposal: &[crate::isa::x86::X86Instruction],
+        live_out: &Self::LiveOut,
+        _width: u32,
+        num_tests: usize,
+    ) -> EquivalenceResult {
+        let cfg =
+            crate::semantics::equivalence::EquivalenceConfigFor::<crate::isa::X86_32>::default()
+                .live_out(live_out.clone())
+                .random_tests(num_tests);
+        crate::semantics::equivalence::check_equivalence_approx_for::<crate::isa::X86_32>(
+            target, proposal, &cfg,
+        )
+    }
+
     fn random_sequence<R: RngExt>(
         rng: &mut R,
         len: usize,
@@ -676,6 +812,59 @@ mod tests {
         );
     }
 
+    #[test]
+    fn aarch64_generated_and_mutated_instructions_never_write_sp_by_default() {
+        use crate::isa::ISAMutator;
+        use rand::SeedableRng;
+        use rand_chacha::ChaCha8Rng;
+
+        type B = AArch64;
+        let writes_sp = |seq: &[Instruction]| {
+            seq.iter()
+                .any(|instr| instr.destinations().contains(&Register::SP))
+        };
+        let config = SearchConfig::default()
+            .with_registers(vec![Register::X0, Register::X1, Register::SP])
+            .with_immediates(vec![0, 1, 16]);
+        let regs = <B as StochasticBackend<B>>::registers_from_config(&config);
+        let imms = <B as StochasticBackend<B>>::immediates_from_config(&config);
+        let mutator = <B as StochasticBackend<B>>::make_mutator(&config);
+        let mut rng = ChaCha8Rng::seed_from_u64(11);
+
+        let mut current = vec![Instruction::Add {
+            rd: Register::X0,
+            rn: Register::X1,
+            rm: Operand::Immediate(1),
+        }];
+        for _ in 0..2_000 {
+            let random =
+                <B as StochasticBackend<B>>::random_sequence(&mut rng, 2, &regs, &imms, &config);
+            assert!(!writes_sp(&random), "random sequence writes SP: {random:?}");
+            current = mutator.mutate(&mut rng, &current);
+            assert!(!writes_sp(&current), "mutation writes SP: {current:?}");
+        }
+
+        // The enumerative candidate pool draws on the same filtered registers.
+        use crate::search::enumerative::search::EnumerativeBackend;
+        let pool = <B as EnumerativeBackend<B>>::enumerate_for_config(
+            &config,
+            &<B as EnumerativeBackend<B>>::registers_from_config(&config),
+            &imms,
+        );
+        assert!(!pool.is_empty());
+        assert!(!writes_sp(&pool), "enumerative pool writes SP");
+
+        // Opting in hands SP back to the generators.
+        let config = config.with_allow_sp_writes(true);
+        let regs = <B as StochasticBackend<B>>::registers_from_config(&config);
+        assert!(regs.contains(&Register::SP));
+        assert!(
+            (0..2_000).any(|_| writes_sp(&<B as StochasticBackend<B>>::random_sequence(
+                &mut rng, 2, &regs, &imms, &config
+            )))
+        );
+    }
+
     #[test]
     fn x86_random_sequence_respects_empty_register_pool() {
         use rand::SeedableRng;
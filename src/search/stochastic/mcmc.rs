@@ -1,32 +1,50 @@
 //! Markov Chain Monte Carlo (MCMC) search implementation
 //!
 //! Implements stochastic superoptimization using MCMC-style mutation plus
-//! Metropolis cost acceptance. Proposal probabilities are heuristic and no
-//! Hastings ratio is computed.
+//! Metropolis cost acceptance. Same-length proposal probabilities are
+//! heuristic; only the length-changing insert/delete moves carry a Hastings
+//! correction.
 //!
 //! The algorithm:
 //! 1. Generate test cases for fast validation
-//! 2. Start with a random initial program (or copy of target)
+//! 2. Start with the configured initial program, or else a random program
+//!    or a copy of the target
 //! 3. Loop for N iterations:
-//!    a. Mutate current program
+//!    a. Mutate current program, or occasionally insert or delete an
+//!    instruction
 //!    b. Evaluate on tests (fast rejection if fails)
-//!    c. If passes tests with zero cost → verify with SMT
+//!    c. If passes tests with zero cost → verify with SMT (or, with
+//!    `smt_verification` off, with a larger concrete-only test batch); an
+//!    inconclusive SMT result is handled per `StochasticConfig::on_unknown`
 //!    d. Accept/reject based on Metropolis cost acceptance
 //! 4. Return best found optimization
 
 use crate::isa::{ISA, ISAMutator};
-use crate::search::config::SearchConfig;
+use crate::search::config::{SearchConfig, UnknownPolicy};
 use crate::search::result::{SearchResultFor, SearchStatistics};
 use crate::search::stochastic::acceptance::AcceptanceCriterion;
 use crate::search::stochastic::backend::StochasticBackend;
+use crate::search::stochastic::mutation::propose_length_change;
+use crate::search::trace::{opcode_summary, search_event};
 use crate::search::{Algorithm, SearchAlgorithm};
-use crate::semantics::EquivalenceResult;
+use crate::semantics::{EquivalenceMetrics, EquivalenceResult};
 use rand::{RngExt, SeedableRng};
 use rand_chacha::ChaCha8Rng;
+use std::collections::HashSet;
 use std::marker::PhantomData;
 use std::sync::atomic::Ordering;
 use std::time::Instant;
 
+/// Concrete test inputs used to accept an improvement when
+/// `StochasticConfig::smt_verification` is off. Far more than the prefilter's
+/// `SearchConfig::prefilter_tests` (or `StochasticConfig::test_count`), since
+/// these tests are the only evidence of equivalence.
+const APPROX_VERIFICATION_TESTS: usize = 256;
+
+/// Share of iterations that propose an insert/delete move instead of a
+/// same-length mutation.
+const LENGTH_MOVE_PROBABILITY: f64 = 0.1;
+
 /// Stochastic search using MCMC-style proposals and Metropolis cost
 /// acceptance, generic over ISA.
 ///
@@ -74,11 +92,24 @@ where
         self.reset();
         let start_time = Instant::now();
         let width = <I as StochasticBackend<I>>::width();
+        let config = &config
+            .clone()
+            .with_cost_metric(<I as StochasticBackend<I>>::bind_live_out(
+                config.cost_metric,
+                live_out,
+            ));
 
         let original_cost =
             <I as StochasticBackend<I>>::sequence_cost(target, &config.cost_metric, width);
         self.statistics.original_cost = original_cost;
         self.statistics.best_cost_found = original_cost;
+        self.statistics.cost_metric = config.cost_metric;
+
+        if let Err(e) = config.validate_stochastic() {
+            search_event!(warn, true; "warning: not searching: {}", e);
+            self.statistics.elapsed_time = start_time.elapsed();
+            return SearchResultFor::no_optimization(target.to_vec(), self.statistics.clone());
+        }
 
         if target.is_empty() {
             self.statistics.elapsed_time = start_time.elapsed();
@@ -100,20 +131,22 @@ where
             <I as StochasticBackend<I>>::validation_registers(&regs, target, live_out);
 
         // Generate test cases: random + edge.
-        let test_inputs = <I as StochasticBackend<I>>::make_test_inputs(
-            &validation_regs,
-            width,
-            config.stochastic.test_count,
-        );
+        let test_count = config
+            .stochastic
+            .test_count
+            .unwrap_or(config.prefilter_tests);
+        let test_inputs =
+            <I as StochasticBackend<I>>::make_test_inputs(&validation_regs, width, test_count);
         let edge_inputs = <I as StochasticBackend<I>>::make_edge_inputs(&validation_regs, width);
 
         // Precompute target outputs.
-        let target_outputs: Vec<_> = test_inputs
+        let mut target_outputs: Vec<_> = test_inputs
             .iter()
             .chain(edge_inputs.iter())
             .map(|input| <I as StochasticBackend<I>>::apply_sequence(input.clone(), target))
             .collect();
-        let all_inputs: Vec<_> = test_inputs.into_iter().chain(edge_inputs).collect();
+        let mut all_inputs: Vec<_> = test_inputs.into_iter().chain(edge_inputs).collect();
+        let mut counterexample_budget = config.stochastic.max_counterexamples;
 
         let mutator = <I as StochasticBackend<I>>::make_mutator(config);
         let acceptance = AcceptanceCriterion::new(config.stochastic.beta);
@@ -131,8 +164,28 @@ where
             seq
         };
 
-        // Start with target sequence or random sequence of same length
-        let mut current = if rng.random_bool(0.5) {
+        // Length bounds: the terminator (if any) is always pinned at the
+        // tail, so length-change proposals only vary the prefix length.
+        let min_length = 1 + terminator_len;
+        let max_length = target.len();
+
+        // Start with the configured initial program if it fits the length
+        // bounds, else the target sequence or a random one of the same length.
+        let initial_program = <I as StochasticBackend<I>>::initial_program(config).filter(|_| {
+            match config
+                .stochastic
+                .validate_initial_program(min_length, max_length)
+            {
+                Ok(()) => true,
+                Err(e) => {
+                    search_event!(warn, config.verbose; "warning: ignoring initial program: {}", e);
+                    false
+                }
+            }
+        });
+        let mut current = if let Some(program) = initial_program {
+            program
+        } else if rng.random_bool(0.5) {
             target.to_vec()
         } else {
             loop {
@@ -140,29 +193,34 @@ where
                 let seq = with_term(<I as StochasticBackend<I>>::random_sequence(
                     &mut rng, prefix_len, &regs, &imms, config,
                 ));
-                if <I as StochasticBackend<I>>::is_encodable(&seq) {
+                if <I as StochasticBackend<I>>::is_encodable(&seq)
+                    && <I as StochasticBackend<I>>::within_config_limits(&seq, config)
+                {
                     break seq;
                 }
             }
         };
         let mut current_cost =
             <I as StochasticBackend<I>>::sequence_cost(&current, &config.cost_metric, width);
+        // A chain started from a random program has no test-passing
+        // neighbourhood to climb in yet: until it first reaches a program
+        // that passes the concrete tests it accepts every proposal.
+        let mut current_passes =
+            passes_concrete_tests::<I>(&current, &all_inputs, &target_outputs, live_out);
 
         let mut best_equivalent: Option<Vec<I::Instruction>> = None;
         let mut best_cost = original_cost;
+        // `dedup_key`s of proposals already refuted, so a chain revisiting a
+        // proposal (or a register renaming of one) skips the solver.
+        let mut refuted: HashSet<Vec<I::Instruction>> = HashSet::new();
 
-        // Length bounds: the terminator (if any) is always pinned at the
-        // tail, so length-change proposals only vary the prefix length.
-        let min_length = 1 + terminator_len;
-        let max_length = target.len();
-
-        for iteration in 0..config.stochastic.iterations {
+        // With no iteration cap, `validate_stochastic` guaranteed a timeout.
+        let max_iterations = config.stochastic.iterations.unwrap_or(u64::MAX);
+        for iteration in 0..max_iterations {
             self.statistics.iterations = iteration + 1;
 
             if config.timeout.is_some_and(|t| start_time.elapsed() >= t) {
-                if config.verbose {
-                    println!("Search timed out after {} iterations", iteration);
-                }
+                search_event!(info, config.verbose; "Search timed out after {} iterations", iteration);
                 break;
             }
 
@@ -179,31 +237,48 @@ where
                 break;
             }
 
-            // Occasionally try a different length
-            if rng.random_bool(0.1) && max_length > min_length {
-                let new_len = rng.random_range(min_length..=max_length);
-                if new_len != current.len() {
-                    loop {
-                        let prefix_len = new_len.saturating_sub(terminator_len);
-                        let seq = with_term(<I as StochasticBackend<I>>::random_sequence(
-                            &mut rng, prefix_len, &regs, &imms, config,
-                        ));
-                        if <I as StochasticBackend<I>>::is_encodable(&seq) {
-                            current = seq;
-                            break;
-                        }
-                    }
-                    current_cost = <I as StochasticBackend<I>>::sequence_cost(
-                        &current,
-                        &config.cost_metric,
-                        width,
-                    );
-                }
+            // Restart-from-best: pull the chain back to the best proven
+            // sequence so exploration intensifies around it.
+            if let Some(interval) = config.stochastic.restart_interval
+                && interval > 0
+                && iteration > 0
+                && iteration % interval == 0
+            {
+                current = best_equivalent.clone().unwrap_or_else(|| target.to_vec());
+                current_passes = true;
+                current_cost = <I as StochasticBackend<I>>::sequence_cost(
+                    &current,
+                    &config.cost_metric,
+                    width,
+                );
             }
 
-            let proposal = mutator.mutate(&mut rng, &current);
+            // Occasionally insert or delete an instruction; those moves are
+            // asymmetric at the length bounds, so they carry the proposal
+            // probabilities for the Hastings-corrected acceptance below.
+            let length_move = if rng.random_bool(LENGTH_MOVE_PROBABILITY) {
+                propose_length_change(
+                    &mut rng,
+                    &current,
+                    current.len() - terminator_len,
+                    min_length - terminator_len,
+                    max_length - terminator_len,
+                    |rng| {
+                        <I as StochasticBackend<I>>::random_sequence(rng, 1, &regs, &imms, config)
+                            .remove(0)
+                    },
+                )
+            } else {
+                None
+            };
+            let (proposal, forward_prob, backward_prob) = match length_move {
+                Some(m) => (m.sequence, m.forward_prob, m.backward_prob),
+                None => (mutator.mutate(&mut rng, &current), 1.0, 1.0),
+            };
 
-            if !<I as StochasticBackend<I>>::is_encodable(&proposal) {
+            if !<I as StochasticBackend<I>>::is_encodable(&proposal)
+                || !<I as StochasticBackend<I>>::within_config_limits(&proposal, config)
+            {
                 continue;
             }
 
@@ -213,6 +288,11 @@ where
             self.statistics.candidates_evaluated += 1;
 
             if !passes_concrete_tests::<I>(&proposal, &all_inputs, &target_outputs, live_out) {
+                if !current_passes {
+                    current = proposal;
+                    current_cost = proposal_cost;
+                    self.statistics.accepted_proposals += 1;
+                }
                 continue;
             }
 
@@ -221,52 +301,124 @@ where
             // Only cheaper proposals enter verification below and can set this
             // veto; cost-pruned proposals receive no equivalence verdict.
             let mut smt_refuted = false;
-            if proposal_cost < best_cost {
-                let Some(smt_timeout) = config.solver_timeout_within_budget(start_time.elapsed())
-                else {
-                    // SMT is disabled or no millisecond-granularity budget
-                    // remains. Stop rather than hand Z3 its unbounded zero
-                    // sentinel or a timeout it cannot honour. Mirrors the
-                    // enumerative path.
-                    break;
-                };
-                let (verdict, metrics) = <I as StochasticBackend<I>>::check_equivalence(
-                    target,
-                    &proposal,
-                    live_out,
-                    width,
-                    smt_timeout,
-                );
+            let fits =
+                !config.exceeds_max_bytes(<I as StochasticBackend<I>>::encoded_length(&proposal));
+            let refuted_key = (proposal_cost < best_cost && fits)
+                .then(|| <I as StochasticBackend<I>>::dedup_key(&proposal, live_out));
+            if refuted_key
+                .as_ref()
+                .is_some_and(|key| refuted.contains(key))
+            {
+                smt_refuted = true;
+            } else if let Some(key) = refuted_key {
                 // Fold the SMT counters through the canonical accounting seam so
                 // this path cannot drift from the symbolic/enumerative ones.
                 // `candidates_passed_fast` is counted separately above (at the
                 // concrete-test stage), which is why we apply the tally directly
                 // rather than calling `record_verification`.
-                let tally = SearchStatistics::verification_tally(&metrics, &verdict);
-                tally.fold_into(&mut self.statistics);
-                if tally.proved_equivalent {
+                let mut counterexample = None;
+                let verdict = if config.stochastic.smt_verification {
+                    let Some(mut smt_timeout) =
+                        config.solver_timeout_within_budget(start_time.elapsed())
+                    else {
+                        // SMT is disabled or no millisecond-granularity budget
+                        // remains. Stop rather than hand Z3 its unbounded zero
+                        // sentinel or a timeout it cannot honour. Mirrors the
+                        // enumerative path.
+                        break;
+                    };
+                    let mut retries_left = match config.stochastic.on_unknown {
+                        UnknownPolicy::Retry { attempts } => attempts,
+                        UnknownPolicy::Discard | UnknownPolicy::AcceptWithWarning => 0,
+                    };
+                    loop {
+                        let (verdict, metrics) = <I as StochasticBackend<I>>::check_equivalence(
+                            target,
+                            &proposal,
+                            live_out,
+                            width,
+                            smt_timeout,
+                        );
+                        SearchStatistics::verification_tally(&metrics, &verdict)
+                            .fold_into(&mut self.statistics);
+                        counterexample =
+                            <I as StochasticBackend<I>>::counterexample_input(&metrics);
+                        if !matches!(verdict, EquivalenceResult::Unknown(_)) {
+                            break verdict;
+                        }
+                        self.statistics.smt_unknowns += 1;
+                        if retries_left == 0 {
+                            break verdict;
+                        }
+                        retries_left -= 1;
+                        match config.solver_retry_timeout(smt_timeout, start_time.elapsed()) {
+                            Some(next) => smt_timeout = next,
+                            None => break verdict,
+                        }
+                    }
+                } else {
+                    let verdict = <I as StochasticBackend<I>>::check_equivalence_approx(
+                        target,
+                        &proposal,
+                        live_out,
+                        width,
+                        APPROX_VERIFICATION_TESTS,
+                    );
+                    SearchStatistics::verification_tally(&EquivalenceMetrics::default(), &verdict)
+                        .fold_into(&mut self.statistics);
+                    verdict
+                };
+                let accepted_unknown = match &verdict {
+                    EquivalenceResult::Unknown(reason)
+                        if config.stochastic.on_unknown == UnknownPolicy::AcceptWithWarning =>
+                    {
+                        search_event!(
+                            warn,
+                            true;
+                            "warning: accepting unverified improvement at iteration {} \
+                             (SMT result unknown: {})",
+                            iteration, reason
+                        );
+                        true
+                    }
+                    _ => false,
+                };
+                if matches!(verdict, EquivalenceResult::Equivalent) || accepted_unknown {
                     self.statistics.improvements_found += 1;
 
                     best_equivalent = Some(proposal.clone());
                     best_cost = proposal_cost;
                     self.statistics.best_cost_found = best_cost;
 
-                    if config.verbose {
-                        println!(
-                            "Found improvement at iteration {}: cost {} -> {}",
-                            iteration, original_cost, best_cost
-                        );
-                    }
+                    search_event!(info, config.verbose; "Found improvement at iteration {}: {} (cost {} -> {})",
+                            iteration, opcode_summary(&proposal), original_cost, best_cost);
                 } else if matches!(
                     verdict,
                     EquivalenceResult::NotEquivalent | EquivalenceResult::NotEquivalentFast(_)
                 ) {
                     smt_refuted = true;
+                    refuted.insert(key);
+                    if let Some(input) = counterexample {
+                        add_counterexample_input::<I>(
+                            input,
+                            target,
+                            &proposal,
+                            live_out,
+                            &mut all_inputs,
+                            &mut target_outputs,
+                            &mut counterexample_budget,
+                        );
+                    }
+                } else if let EquivalenceResult::Unknown(reason) = &verdict {
+                    search_event!(debug; "SMT result unknown at iteration {}: {}", iteration, reason);
                 }
-                // SMT timeout / inconclusive (`Unknown`): we cannot prove the
-                // proposal incorrect, so leave the Metropolis decision below
-                // intact rather than vetoing exploration.
+                // SMT timeout / inconclusive (`Unknown`) under `Discard` or an
+                // exhausted `Retry`: we cannot prove the proposal incorrect, so
+                // leave the Metropolis decision below intact rather than
+                // vetoing exploration.
             } else {
+                // Not cheaper, or cheaper but overflowing `max_bytes`: either
+                // way it is pruned without an equivalence check.
                 self.statistics.candidates_pruned_by_cost += 1;
             }
 
@@ -274,14 +426,23 @@ where
                 continue;
             }
 
-            if acceptance.accept(&mut rng, current_cost, proposal_cost) {
+            if acceptance.accept_with_proposal_ratio(
+                &mut rng,
+                current_cost,
+                proposal_cost,
+                forward_prob,
+                backward_prob,
+            ) {
                 current = proposal;
                 current_cost = proposal_cost;
+                current_passes = true;
                 self.statistics.accepted_proposals += 1;
             }
 
-            if config.verbose && iteration > 0 && iteration % 100_000 == 0 {
-                println!(
+            if iteration > 0 && iteration % 100_000 == 0 {
+                search_event!(
+                    debug,
+                    config.verbose;
                     "Iteration {}: current_cost={}, best_cost={}, acceptance_rate={:.2}%",
                     iteration,
                     current_cost,
@@ -294,7 +455,12 @@ where
         self.statistics.elapsed_time = start_time.elapsed();
 
         if let Some(optimized) = best_equivalent {
-            SearchResultFor::with_optimization(target.to_vec(), optimized, self.statistics.clone())
+            SearchResultFor::with_optimization(
+                target.to_vec(),
+                optimized,
+                self.statistics.clone(),
+                |seq| <I as StochasticBackend<I>>::sequence_cost(seq, &config.cost_metric, width),
+            )
         } else {
             SearchResultFor::no_optimization(target.to_vec(), self.statistics.clone())
         }
@@ -338,6 +504,42 @@ where
         })
 }
 
+/// Counterexample-guided test growth: append `input`, a solver
+/// counterexample for `proposal`, and the target's output on it to the
+/// concrete test set so the same divergence is fast-rejected from then on.
+///
+/// Each added input spends one unit of `budget`; the inputs the set started
+/// with cost nothing. Returns `false` without growing the set when `budget`
+/// is spent, or when `input` does not separate `proposal` from `target`
+/// concretely (e.g. a memory divergence, which solver models do not carry
+/// back).
+fn add_counterexample_input<I>(
+    input: <I as StochasticBackend<I>>::State,
+    target: &[I::Instruction],
+    proposal: &[I::Instruction],
+    live_out: &<I as StochasticBackend<I>>::LiveOut,
+    inputs: &mut Vec<<I as StochasticBackend<I>>::State>,
+    target_outputs: &mut Vec<<I as StochasticBackend<I>>::State>,
+    budget: &mut usize,
+) -> bool
+where
+    I: ISA + StochasticBackend<I>,
+    <I as StochasticBackend<I>>::State: Clone,
+{
+    if *budget == 0 {
+        return false;
+    }
+    let target_output = <I as StochasticBackend<I>>::apply_sequence(input.clone(), target);
+    let proposal_output = <I as StochasticBackend<I>>::apply_sequence(input.clone(), proposal);
+    if <I as StochasticBackend<I>>::states_equal(&proposal_output, &target_output, live_out) {
+        return false;
+    }
+    inputs.push(input);
+    target_outputs.push(target_output);
+    *budget -= 1;
+    true
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -346,33 +548,13 @@ mod tests {
     use crate::search::config::StochasticConfig;
     use crate::semantics::concrete::apply_sequence_concrete;
     use crate::semantics::cost::CostMetric;
-    use crate::semantics::live_out::LiveOut;
+    use crate::semantics::equivalence::FastCounterexample;
     use crate::semantics::state::{ConcreteMachineState, ConcreteValue, ConditionFlags};
+    use crate::test_utils::sequence_fixtures::{mov_add_sequence, mov_zero_sequence, x0_live};
     use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering as AtomicOrdering};
     use std::sync::{Mutex as TestMutex, MutexGuard};
     use std::time::Duration;
 
-    fn mov_add_sequence() -> Vec<Instruction> {
-        vec![
-            Instruction::MovReg {
-                rd: Register::X0,
-                rn: Register::X1,
-            },
-            Instruction::Add {
-                rd: Register::X0,
-                rn: Register::X0,
-                rm: Operand::Immediate(1),
-            },
-        ]
-    }
-
-    fn mov_zero_sequence() -> Vec<Instruction> {
-        vec![Instruction::MovImm {
-            rd: Register::X0,
-            imm: 0,
-        }]
-    }
-
     #[test]
     fn test_stochastic_search_creation() {
         let search: StochasticSearch<AArch64> = StochasticSearch::new();
@@ -385,12 +567,55 @@ mod tests {
     fn test_stochastic_search_empty_sequence() {
         let mut search: StochasticSearch<AArch64> = StochasticSearch::new();
         let config = SearchConfig::default();
-        let live_out = LiveOut::from_registers(vec![Register::X0]);
+        let live_out = x0_live();
 
         let result = search.search(&[], &live_out, &config);
         assert!(!result.found_optimization);
     }
 
+    #[test]
+    fn stochastic_search_refuses_an_invalid_config() {
+        let mut search: StochasticSearch<AArch64> = StochasticSearch::new();
+        let mut config = SearchConfig::default().with_stochastic(
+            StochasticConfig::default()
+                .with_iterations(100)
+                .with_seed(1),
+        );
+        config.stochastic.mutation_weights.operand = f64::NAN;
+
+        let result = search.search(&mov_add_sequence(), &x0_live(), &config);
+
+        assert!(!result.found_optimization);
+        assert_eq!(result.statistics.candidates_evaluated, 0);
+    }
+
+    #[test]
+    fn stochastic_search_counts_oversized_proposals_as_pruned() {
+        let mut search: StochasticSearch<AArch64> = StochasticSearch::new();
+        let config = SearchConfig::default()
+            .with_registers(vec![Register::X0, Register::X1])
+            .with_immediates(vec![0, 1])
+            .with_max_bytes(0)
+            .with_stochastic(
+                StochasticConfig::default()
+                    .with_iterations(500)
+                    .with_seed(1),
+            );
+
+        let result = search.search(&mov_add_sequence(), &x0_live(), &config);
+
+        // Nothing fits in zero bytes, so every proposal that passes the
+        // concrete tests is pruned before the solver.
+        let stats = &result.statistics;
+        assert!(!result.found_optimization);
+        assert_eq!(stats.smt_queries, 0);
+        assert!(stats.candidates_passed_fast > 0);
+        assert_eq!(
+            stats.candidates_pruned_by_cost,
+            stats.candidates_passed_fast
+        );
+    }
+
     #[derive(Clone)]
     struct TimeoutProbeIsa;
 
@@ -400,8 +625,11 @@ mod tests {
         fn mutate<R: rand::RngExt>(
             &self,
             _rng: &mut R,
-            _sequence: &[Instruction],
+            sequence: &[Instruction],
         ) -> Vec<Instruction> {
+            FIRST_MUTATION_INPUT.with(|first| {
+                first.borrow_mut().get_or_insert_with(|| sequence.to_vec());
+            });
             mov_zero_sequence()
         }
     }
@@ -438,6 +666,13 @@ mod tests {
     std::thread_local! {
         static RECORDED_SMT_TIMEOUT_MS: std::cell::Cell<Option<u128>> =
             const { std::cell::Cell::new(None) };
+        /// The chain's `current` at its first mutation, i.e. where it started.
+        static FIRST_MUTATION_INPUT: std::cell::RefCell<Option<Vec<Instruction>>> =
+            const { std::cell::RefCell::new(None) };
+        /// Raised by the probe's SMT check, i.e. once the chain has found
+        /// its first improvement.
+        static STOP_AT_SMT_CHECK: std::cell::RefCell<Option<std::sync::Arc<AtomicBool>>> =
+            const { std::cell::RefCell::new(None) };
     }
 
     const TIMEOUT_PROBE_NOT_EQUIVALENT: usize = 0;
@@ -456,6 +691,8 @@ mod tests {
         TIMEOUT_PROBE_VERDICT.store(verdict, AtomicOrdering::SeqCst);
         TIMEOUT_PROBE_SMT_CALLED.store(smt_called, AtomicOrdering::SeqCst);
         RECORDED_SMT_TIMEOUT_MS.with(|recorded| recorded.set(None));
+        FIRST_MUTATION_INPUT.with(|first| first.borrow_mut().take());
+        STOP_AT_SMT_CHECK.with(|flag| flag.borrow_mut().take());
         guard
     }
 
@@ -507,6 +744,11 @@ mod tests {
             timeout: Duration,
         ) -> (EquivalenceResult, crate::semantics::EquivalenceMetrics) {
             RECORDED_SMT_TIMEOUT_MS.with(|recorded| recorded.set(Some(timeout.as_millis())));
+            STOP_AT_SMT_CHECK.with(|flag| {
+                if let Some(flag) = flag.borrow().as_ref() {
+                    flag.store(true, AtomicOrdering::SeqCst);
+                }
+            });
             let metrics = crate::semantics::EquivalenceMetrics {
                 smt_called: TIMEOUT_PROBE_SMT_CALLED.load(AtomicOrdering::SeqCst),
                 ..crate::semantics::EquivalenceMetrics::default()
@@ -516,7 +758,7 @@ mod tests {
                     TIMEOUT_PROBE_EQUIVALENT => EquivalenceResult::Equivalent,
                     TIMEOUT_PROBE_UNKNOWN => EquivalenceResult::Unknown("solver timeout".into()),
                     TIMEOUT_PROBE_NOT_EQUIVALENT_FAST => {
-                        EquivalenceResult::NotEquivalentFast(ConcreteMachineState::new_zeroed())
+                        EquivalenceResult::NotEquivalentFast(FastCounterexample::structural())
                     }
                     _ => EquivalenceResult::NotEquivalent,
                 },
@@ -524,6 +766,19 @@ mod tests {
             )
         }
 
+        fn check_equivalence_approx(
+            _target: &[Instruction],
+            _proposal: &[Instruction],
+            _live_out: &Self::LiveOut,
+            _width: u32,
+            _num_tests: usize,
+        ) -> EquivalenceResult {
+            match TIMEOUT_PROBE_VERDICT.load(AtomicOrdering::SeqCst) {
+                TIMEOUT_PROBE_EQUIVALENT => EquivalenceResult::Equivalent,
+                _ => EquivalenceResult::NotEquivalentFast(FastCounterexample::structural()),
+            }
+        }
+
         fn random_sequence<R: rand::RngExt>(
             _rng: &mut R,
             len: usize,
@@ -540,6 +795,10 @@ mod tests {
             ]
         }
 
+        fn initial_program(config: &SearchConfig) -> Option<Vec<Instruction>> {
+            config.stochastic.initial_program.clone()
+        }
+
         fn width() -> u32 {
             64
         }
@@ -578,12 +837,9 @@ mod tests {
         let _guard = set_timeout_probe_result(TIMEOUT_PROBE_EQUIVALENT, true);
 
         let mut search: StochasticSearch<TimeoutProbeIsa> = StochasticSearch::new();
-        let config = SearchConfig::default().with_stochastic(
-            StochasticConfig::default()
-                .with_iterations(1)
-                .with_test_count(0)
-                .with_seed(1),
-        );
+        let config = SearchConfig::default()
+            .with_prefilter_tests(0)
+            .with_stochastic(StochasticConfig::default().with_iterations(1).with_seed(1));
         let target = mov_add_sequence();
 
         let result = search.search(&target, &(), &config);
@@ -595,16 +851,65 @@ mod tests {
     }
 
     #[test]
-    fn stochastic_search_does_not_accept_smt_refuted_cheaper_proposal() {
+    fn stochastic_search_does_not_requery_refuted_proposals() {
+        // Every proposal is cheaper than the target, but only three are
+        // distinct: the probe mutator's `mov x0, #0` and the two one-instruction
+        // deletions of the target. Revisits must hit the refuted cache.
         let _guard = set_timeout_probe_result(TIMEOUT_PROBE_NOT_EQUIVALENT, true);
 
         let mut search: StochasticSearch<TimeoutProbeIsa> = StochasticSearch::new();
-        let config = SearchConfig::default().with_stochastic(
-            StochasticConfig::default()
-                .with_iterations(1)
-                .with_test_count(0)
-                .with_seed(1),
+        let config = SearchConfig::default()
+            .with_prefilter_tests(0)
+            .with_stochastic(
+                StochasticConfig::default()
+                    .with_initial_program(mov_add_sequence())
+                    .with_iterations(20)
+                    .with_seed(1),
+            );
+        let target = mov_add_sequence();
+
+        let result = search.search(&target, &(), &config);
+
+        assert_eq!(result.statistics.candidates_passed_fast, 20);
+        assert_eq!(result.statistics.smt_queries, 3);
+        assert!(!result.found_optimization);
+    }
+
+    #[test]
+    fn stochastic_search_without_smt_verification_accepts_on_concrete_tests() {
+        let _guard = set_timeout_probe_result(TIMEOUT_PROBE_EQUIVALENT, true);
+
+        let mut search: StochasticSearch<TimeoutProbeIsa> = StochasticSearch::new();
+        let config = SearchConfig::default()
+            .with_prefilter_tests(0)
+            .with_stochastic(
+                StochasticConfig::default()
+                    .with_iterations(1)
+                    .with_seed(1)
+                    .with_smt_verification(false),
+            );
+        let target = mov_add_sequence();
+
+        let result = search.search(&target, &(), &config);
+
+        assert_eq!(result.statistics.smt_queries, 0);
+        assert_eq!(
+            RECORDED_SMT_TIMEOUT_MS.with(|recorded| recorded.get()),
+            None,
+            "approximate mode must not reach the solver"
         );
+        assert_eq!(result.statistics.improvements_found, 1);
+        assert!(result.found_optimization);
+    }
+
+    #[test]
+    fn stochastic_search_does_not_accept_smt_refuted_cheaper_proposal() {
+        let _guard = set_timeout_probe_result(TIMEOUT_PROBE_NOT_EQUIVALENT, true);
+
+        let mut search: StochasticSearch<TimeoutProbeIsa> = StochasticSearch::new();
+        let config = SearchConfig::default()
+            .with_prefilter_tests(0)
+            .with_stochastic(StochasticConfig::default().with_iterations(1).with_seed(1));
         let target = mov_add_sequence();
 
         let result = search.search(&target, &(), &config);
@@ -622,12 +927,9 @@ mod tests {
         let _guard = set_timeout_probe_result(TIMEOUT_PROBE_UNKNOWN, true);
 
         let mut search: StochasticSearch<TimeoutProbeIsa> = StochasticSearch::new();
-        let config = SearchConfig::default().with_stochastic(
-            StochasticConfig::default()
-                .with_iterations(1)
-                .with_test_count(0)
-                .with_seed(1),
-        );
+        let config = SearchConfig::default()
+            .with_prefilter_tests(0)
+            .with_stochastic(StochasticConfig::default().with_iterations(1).with_seed(1));
         let target = mov_add_sequence();
 
         let result = search.search(&target, &(), &config);
@@ -640,17 +942,82 @@ mod tests {
         assert_eq!(result.statistics.accepted_proposals, 1);
     }
 
+    fn unknown_policy_config(policy: UnknownPolicy) -> SearchConfig {
+        SearchConfig::default()
+            .with_prefilter_tests(0)
+            .with_solver_timeout(Duration::from_millis(1))
+            .with_stochastic(
+                StochasticConfig::default()
+                    .with_iterations(1)
+                    .with_seed(1)
+                    .with_on_unknown(policy),
+            )
+    }
+
+    #[test]
+    fn stochastic_search_discards_unknown_improvement_by_default() {
+        assert_eq!(
+            StochasticConfig::default().on_unknown,
+            UnknownPolicy::Discard
+        );
+        let _guard = set_timeout_probe_result(TIMEOUT_PROBE_UNKNOWN, true);
+
+        let mut search: StochasticSearch<TimeoutProbeIsa> = StochasticSearch::new();
+        let config = unknown_policy_config(UnknownPolicy::Discard);
+        let result = search.search(&mov_add_sequence(), &(), &config);
+
+        assert_eq!(result.statistics.smt_queries, 1);
+        assert_eq!(result.statistics.smt_unknowns, 1);
+        assert_eq!(result.statistics.improvements_found, 0);
+        assert!(!result.found_optimization);
+    }
+
+    #[test]
+    fn stochastic_search_retries_unknown_with_doubled_solver_timeout() {
+        let config = unknown_policy_config(UnknownPolicy::Retry { attempts: 2 });
+        let (statistics, last_timeout) =
+            run_timeout_probe_search_with(config, TIMEOUT_PROBE_UNKNOWN, true);
+
+        // The first query plus two retries, each still inconclusive.
+        assert_eq!(statistics.smt_queries, 3);
+        assert_eq!(statistics.smt_unknowns, 3);
+        assert_eq!(statistics.improvements_found, 0);
+        assert_eq!(last_timeout, Some(4));
+    }
+
+    #[test]
+    fn stochastic_search_retry_stops_once_the_solver_decides() {
+        let config = unknown_policy_config(UnknownPolicy::Retry { attempts: 2 });
+        let (statistics, _) = run_timeout_probe_search_with(config, TIMEOUT_PROBE_EQUIVALENT, true);
+
+        assert_eq!(statistics.smt_queries, 1);
+        assert_eq!(statistics.smt_unknowns, 0);
+        assert_eq!(statistics.improvements_found, 1);
+    }
+
+    #[test]
+    fn stochastic_search_accepts_unknown_improvement_with_warning_policy() {
+        let _guard = set_timeout_probe_result(TIMEOUT_PROBE_UNKNOWN, true);
+
+        let mut search: StochasticSearch<TimeoutProbeIsa> = StochasticSearch::new();
+        let config = unknown_policy_config(UnknownPolicy::AcceptWithWarning);
+        let result = search.search(&mov_add_sequence(), &(), &config);
+
+        assert_eq!(result.statistics.smt_queries, 1);
+        assert_eq!(result.statistics.smt_unknowns, 1);
+        assert_eq!(result.statistics.smt_equivalent, 0);
+        assert_eq!(result.statistics.improvements_found, 1);
+        assert!(result.found_optimization);
+    }
+
     #[test]
     fn stochastic_search_does_not_accept_fast_refuted_cheaper_proposal_after_concrete_tests() {
         let _guard = set_timeout_probe_result(TIMEOUT_PROBE_NOT_EQUIVALENT_FAST, false);
 
         let mut search: StochasticSearch<TimeoutProbeIsa> = StochasticSearch::new();
-        let config = SearchConfig::default().with_stochastic(
-            StochasticConfig::default()
-                .with_iterations(1)
-                .with_test_count(1)
-                .with_seed(1),
-        );
+        let config = SearchConfig::default()
+            .with_prefilter_tests(1)
+            .with_stochastic(StochasticConfig::default().with_iterations(1).with_seed(1));
         let target = mov_add_sequence();
 
         let result = search.search(&target, &(), &config);
@@ -667,12 +1034,8 @@ mod tests {
     fn stochastic_search_uses_top_level_solver_timeout_for_smt() {
         let recorded_timeout = run_timeout_probe_search(
             SearchConfig::default()
-                .with_stochastic(
-                    StochasticConfig::default()
-                        .with_iterations(1)
-                        .with_test_count(0)
-                        .with_seed(1),
-                )
+                .with_prefilter_tests(0)
+                .with_stochastic(StochasticConfig::default().with_iterations(1).with_seed(1))
                 .with_solver_timeout(Duration::from_millis(17)),
         );
 
@@ -683,12 +1046,8 @@ mod tests {
     fn timeout_probe_preserves_full_millisecond_value() {
         let recorded_timeout = run_timeout_probe_search(
             SearchConfig::default()
-                .with_stochastic(
-                    StochasticConfig::default()
-                        .with_iterations(1)
-                        .with_test_count(0)
-                        .with_seed(1),
-                )
+                .with_prefilter_tests(0)
+                .with_stochastic(StochasticConfig::default().with_iterations(1).with_seed(1))
                 .with_timeout_option(None)
                 .with_solver_timeout(Duration::MAX),
         );
@@ -707,12 +1066,8 @@ mod tests {
         // timing.
         let (statistics, recorded_timeout) = run_timeout_probe_search_with(
             SearchConfig::default()
-                .with_stochastic(
-                    StochasticConfig::default()
-                        .with_iterations(1)
-                        .with_test_count(0)
-                        .with_seed(1),
-                )
+                .with_prefilter_tests(0)
+                .with_stochastic(StochasticConfig::default().with_iterations(1).with_seed(1))
                 .with_timeout(Duration::from_millis(50))
                 .with_solver_timeout(Duration::from_secs(30)),
             TIMEOUT_PROBE_EQUIVALENT,
@@ -735,14 +1090,10 @@ mod tests {
     fn stochastic_zero_solver_timeout_skips_smt() {
         let (statistics, recorded_timeout) = run_timeout_probe_search_with(
             SearchConfig::default()
+                .with_prefilter_tests(0)
                 .with_timeout_option(None)
                 .with_solver_timeout(Duration::ZERO)
-                .with_stochastic(
-                    StochasticConfig::default()
-                        .with_iterations(1)
-                        .with_test_count(0)
-                        .with_seed(1),
-                ),
+                .with_stochastic(StochasticConfig::default().with_iterations(1).with_seed(1)),
             TIMEOUT_PROBE_EQUIVALENT,
             true,
         );
@@ -757,12 +1108,8 @@ mod tests {
     fn stochastic_search_falls_back_to_five_seconds_when_solver_timeout_unset() {
         let recorded_timeout = run_timeout_probe_search(
             SearchConfig::default()
-                .with_stochastic(
-                    StochasticConfig::default()
-                        .with_iterations(1)
-                        .with_test_count(0)
-                        .with_seed(1),
-                )
+                .with_prefilter_tests(0)
+                .with_stochastic(StochasticConfig::default().with_iterations(1).with_seed(1))
                 .with_solver_timeout_option(None),
         );
 
@@ -772,12 +1119,9 @@ mod tests {
     #[test]
     fn stochastic_counts_fast_passing_non_improving_proposal_as_cost_pruned() {
         let mut search: StochasticSearch<TimeoutProbeIsa> = StochasticSearch::new();
-        let config = SearchConfig::default().with_stochastic(
-            StochasticConfig::default()
-                .with_iterations(1)
-                .with_test_count(0)
-                .with_seed(1),
-        );
+        let config = SearchConfig::default()
+            .with_prefilter_tests(0)
+            .with_stochastic(StochasticConfig::default().with_iterations(1).with_seed(1));
         let target = mov_zero_sequence();
 
         let result = search.search(&target, &(), &config);
@@ -793,12 +1137,8 @@ mod tests {
     fn stochastic_search_does_not_count_pre_smt_refutation_as_smt_query() {
         let (statistics, recorded_timeout) = run_timeout_probe_search_with(
             SearchConfig::default()
-                .with_stochastic(
-                    StochasticConfig::default()
-                        .with_iterations(1)
-                        .with_test_count(0)
-                        .with_seed(1),
-                )
+                .with_prefilter_tests(0)
+                .with_stochastic(StochasticConfig::default().with_iterations(1).with_seed(1))
                 .with_solver_timeout(Duration::from_millis(17)),
             TIMEOUT_PROBE_NOT_EQUIVALENT,
             false,
@@ -818,7 +1158,7 @@ mod tests {
                 .with_seed(42)
                 .with_iterations(1000),
         );
-        let live_out = LiveOut::from_registers(vec![Register::X0]);
+        let live_out = x0_live();
 
         let result = search.search(&mov_zero_sequence(), &live_out, &config);
         let stats = result.statistics;
@@ -837,7 +1177,7 @@ mod tests {
             .with_registers(vec![Register::X0, Register::X1, Register::X2])
             .with_immediates(vec![-1, 0, 1]);
 
-        let live_out = LiveOut::from_registers(vec![Register::X0]);
+        let live_out = x0_live();
 
         // Target: MOV X0, #0 - can be replaced with EOR X0, X0, X0
         // But since both are 1 instruction, no optimization expected
@@ -857,7 +1197,7 @@ mod tests {
             .with_registers(vec![Register::X0, Register::X1, Register::X2])
             .with_immediates(vec![-1, 0, 1, 2]);
 
-        let live_out = LiveOut::from_registers(vec![Register::X0]);
+        let live_out = x0_live();
 
         // Target: MOV X0, X1; ADD X0, X0, #1 (2 instructions)
         // Can be optimized to: ADD X0, X1, #1 (1 instruction)
@@ -875,6 +1215,72 @@ mod tests {
         }
     }
 
+    /// Start the probe chain on a one-instruction target with `initial` as
+    /// the configured initial program and return the sequence it first
+    /// mutated.
+    fn first_current_with_initial_program(initial: Vec<Instruction>) -> Vec<Instruction> {
+        let _guard = set_timeout_probe_result(TIMEOUT_PROBE_NOT_EQUIVALENT, true);
+        let config = SearchConfig::default().with_stochastic(
+            StochasticConfig::default()
+                .with_iterations(1)
+                .with_seed(7)
+                .with_initial_program(initial),
+        );
+        StochasticSearch::<TimeoutProbeIsa>::new().search(&mov_zero_sequence(), &(), &config);
+        FIRST_MUTATION_INPUT
+            .with(|first| first.borrow_mut().take())
+            .expect("the chain mutated at least once")
+    }
+
+    #[test]
+    fn initial_program_is_the_chains_first_current() {
+        let initial = vec![Instruction::Add {
+            rd: Register::X0,
+            rn: Register::X1,
+            rm: Operand::Immediate(1),
+        }];
+        assert_eq!(first_current_with_initial_program(initial.clone()), initial);
+    }
+
+    #[test]
+    fn initial_program_longer_than_the_target_is_ignored() {
+        let initial = mov_add_sequence();
+        let first = first_current_with_initial_program(initial);
+        assert_eq!(first.len(), 1);
+    }
+
+    /// Over a fixed set of seeds, restarting from the best sequence every
+    /// few iterations finds the mov/add fusion at least as often as a free
+    /// chain with the same budget.
+    #[test]
+    fn restart_interval_finds_mov_add_fusion_at_least_as_often() {
+        let successes = |restart: Option<u64>| {
+            (0..16u64)
+                .filter(|&seed| {
+                    let mut stochastic = StochasticConfig::default()
+                        .with_iterations(1_000)
+                        .with_seed(seed);
+                    stochastic.restart_interval = restart;
+                    let config = SearchConfig::default()
+                        .with_stochastic(stochastic)
+                        .with_registers(vec![Register::X0, Register::X1])
+                        .with_immediates(vec![0, 1]);
+                    let live_out = x0_live();
+                    StochasticSearch::<AArch64>::new()
+                        .search(&mov_add_sequence(), &live_out, &config)
+                        .found_optimization
+                })
+                .count()
+        };
+
+        let with_restart = successes(Some(100));
+        let without_restart = successes(None);
+        assert!(
+            with_restart >= without_restart,
+            "restart found {with_restart}/16, free chain {without_restart}/16"
+        );
+    }
+
     #[test]
     fn passes_concrete_tests_accepts_equivalent_proposal() {
         // EOR X0, X0, X0 zeroes X0, matching MOV X0, #0 on the live-out {X0}.
@@ -888,7 +1294,7 @@ mod tests {
 
         let input = ConcreteMachineState::new_zeroed();
         let target_output = apply_sequence_concrete(input.clone(), &target);
-        let live_out = LiveOut::from_registers(vec![Register::X0]);
+        let live_out = x0_live();
 
         assert!(passes_concrete_tests::<AArch64>(
             &proposal,
@@ -908,7 +1314,7 @@ mod tests {
 
         let input = ConcreteMachineState::new_zeroed();
         let target_output = apply_sequence_concrete(input.clone(), &target);
-        let live_out = LiveOut::from_registers(vec![Register::X0]);
+        let live_out = x0_live();
 
         assert!(!passes_concrete_tests::<AArch64>(
             &proposal,
@@ -942,7 +1348,7 @@ mod tests {
         });
 
         // Flags dead: divergent NZCV is ignored, so the register-only match passes.
-        let live_out_flags_dead = LiveOut::from_registers(vec![Register::X0]);
+        let live_out_flags_dead = x0_live();
         assert!(passes_concrete_tests::<AArch64>(
             &proposal,
             &[input.clone()],
@@ -966,7 +1372,7 @@ mod tests {
             rd: Register::X0,
             imm: 7,
         }];
-        let live_out = LiveOut::from_registers(vec![Register::X0]);
+        let live_out = x0_live();
         assert!(passes_concrete_tests::<AArch64>(
             &proposal,
             &[],
@@ -975,6 +1381,147 @@ mod tests {
         ));
     }
 
+    /// `cmp x1, #1234; csel x0, x2, x1, eq` only differs from `mov x0, x1`
+    /// when x1 == 1234, which neither random nor edge inputs hit: it passes
+    /// the concrete tests until the solver's counterexample joins them.
+    #[test]
+    fn smt_counterexample_grows_test_set_and_fast_rejects_reproposal() {
+        use crate::ir::Condition;
+
+        let target = vec![Instruction::MovReg {
+            rd: Register::X0,
+            rn: Register::X1,
+        }];
+        let proposal = vec![
+            Instruction::Cmp {
+                rn: Register::X1,
+                rm: Operand::Immediate(1234),
+            },
+            Instruction::Csel {
+                rd: Register::X0,
+                rn: Register::X2,
+                rm: Register::X1,
+                cond: Condition::EQ,
+            },
+        ];
+        let live_out = x0_live();
+        let regs = [Register::X0, Register::X1, Register::X2];
+        let mut inputs = <AArch64 as StochasticBackend<AArch64>>::make_test_inputs(&regs, 64, 16);
+        inputs.extend(<AArch64 as StochasticBackend<AArch64>>::make_edge_inputs(
+            &regs, 64,
+        ));
+        let mut target_outputs: Vec<_> = inputs
+            .iter()
+            .map(|input| apply_sequence_concrete(input.clone(), &target))
+            .collect();
+        assert!(passes_concrete_tests::<AArch64>(
+            &proposal,
+            &inputs,
+            &target_outputs,
+            &live_out
+        ));
+
+        let (verdict, metrics) = <AArch64 as StochasticBackend<AArch64>>::check_equivalence(
+            &target,
+            &proposal,
+            &live_out,
+            64,
+            Duration::from_secs(5),
+        );
+        assert_eq!(verdict, EquivalenceResult::NotEquivalent);
+        let counterexample =
+            <AArch64 as StochasticBackend<AArch64>>::counterexample_input(&metrics)
+                .expect("SMT disproof should carry a model");
+        assert_eq!(counterexample.get_register(Register::X1).as_u64(), 1234);
+
+        let before = inputs.len();
+        assert!(!add_counterexample_input::<AArch64>(
+            counterexample.clone(),
+            &target,
+            &proposal,
+            &live_out,
+            &mut inputs,
+            &mut target_outputs,
+            &mut 0,
+        ));
+        let mut budget = 1;
+        assert!(add_counterexample_input::<AArch64>(
+            counterexample,
+            &target,
+            &proposal,
+            &live_out,
+            &mut inputs,
+            &mut target_outputs,
+            &mut budget,
+        ));
+        assert_eq!(budget, 0);
+        assert_eq!(inputs.len(), before + 1);
+        assert_eq!(target_outputs.len(), before + 1);
+
+        // The search loop only reaches the solver for proposals that pass
+        // these tests, so a re-proposal now stops before any SMT call.
+        assert!(!passes_concrete_tests::<AArch64>(
+            &proposal,
+            &inputs,
+            &target_outputs,
+            &live_out
+        ));
+    }
+
+    /// The counterexample budget is separate from the base test set: a
+    /// prefilter larger than the budget still leaves room to grow.
+    #[test]
+    fn counterexample_budget_ignores_a_large_base_test_set() {
+        use crate::ir::Condition;
+
+        let target = vec![Instruction::MovReg {
+            rd: Register::X0,
+            rn: Register::X1,
+        }];
+        let proposal = vec![
+            Instruction::Cmp {
+                rn: Register::X1,
+                rm: Operand::Immediate(1234),
+            },
+            Instruction::Csel {
+                rd: Register::X0,
+                rn: Register::X2,
+                rm: Register::X1,
+                cond: Condition::EQ,
+            },
+        ];
+        let live_out = x0_live();
+        let regs = [Register::X0, Register::X1, Register::X2];
+        let config = StochasticConfig::default();
+        let mut inputs = <AArch64 as StochasticBackend<AArch64>>::make_test_inputs(
+            &regs,
+            64,
+            2 * config.max_counterexamples,
+        );
+        inputs.extend(<AArch64 as StochasticBackend<AArch64>>::make_edge_inputs(
+            &regs, 64,
+        ));
+        let mut target_outputs: Vec<_> = inputs
+            .iter()
+            .map(|input| apply_sequence_concrete(input.clone(), &target))
+            .collect();
+        let mut counterexample = inputs[0].clone();
+        counterexample.set_register(Register::X1, ConcreteValue::new(1234));
+        counterexample.set_register(Register::X2, ConcreteValue::new(0));
+
+        let mut budget = config.max_counterexamples;
+        assert!(add_counterexample_input::<AArch64>(
+            counterexample,
+            &target,
+            &proposal,
+            &live_out,
+            &mut inputs,
+            &mut target_outputs,
+            &mut budget,
+        ));
+        assert_eq!(budget, config.max_counterexamples - 1);
+    }
+
     #[test]
     fn test_statistics_tracking() {
         let mut search: StochasticSearch<AArch64> = StochasticSearch::new();
@@ -983,7 +1530,7 @@ mod tests {
             .with_stochastic(StochasticConfig::default().with_iterations(1000))
             .with_registers(vec![Register::X0, Register::X1]);
 
-        let live_out = LiveOut::from_registers(vec![Register::X0]);
+        let live_out = x0_live();
         let target = mov_zero_sequence();
 
         let result = search.search(&target, &live_out, &config);
@@ -1021,7 +1568,7 @@ mod tests {
                         .with_iterations(configured_iterations)
                         .with_seed(7),
                 );
-            let live_out = LiveOut::from_registers(vec![Register::X0]);
+            let live_out = x0_live();
             let target = mov_add_sequence();
             search.search(&target, &live_out, &config)
         });
@@ -1046,6 +1593,93 @@ mod tests {
         );
     }
 
+    /// An interrupted run (the CLI's Ctrl-C handler raises the same flag)
+    /// still reports the best rewrite it had proven before the stop.
+    #[test]
+    fn stochastic_search_returns_best_so_far_when_stopped() {
+        use std::sync::Arc;
+
+        // The probe proposes `mov x0, #0` (cost 1 < 2) and proves it on the
+        // first iteration, raising the flag inside that SMT check; the caps
+        // only bound the run should the flag ever go unread.
+        let _guard = set_timeout_probe_result(TIMEOUT_PROBE_EQUIVALENT, true);
+        let flag = Arc::new(AtomicBool::new(false));
+        STOP_AT_SMT_CHECK.with(|stop| *stop.borrow_mut() = Some(Arc::clone(&flag)));
+        let config = SearchConfig::default()
+            .with_timeout(Duration::from_secs(30))
+            .with_prefilter_tests(0)
+            .with_stop_flag(flag)
+            .with_stochastic(
+                StochasticConfig::default()
+                    .with_iterations(1_000)
+                    .with_seed(7),
+            );
+
+        let result =
+            StochasticSearch::<TimeoutProbeIsa>::new().search(&mov_add_sequence(), &(), &config);
+
+        assert!(result.found_optimization, "{:?}", result.statistics);
+        assert_eq!(result.optimized_sequence, Some(mov_zero_sequence()));
+        assert_eq!(result.statistics.best_cost_found, 1);
+        assert_eq!(result.statistics.iterations, 2);
+        assert_eq!(result.statistics.candidates_evaluated, 1);
+    }
+
+    /// Neither an iteration cap nor a timeout would never end the chain, so
+    /// the search refuses to start.
+    #[test]
+    fn stochastic_search_without_any_limit_does_not_run() {
+        let config = SearchConfig::default()
+            .with_timeout_option(None)
+            .with_stochastic(
+                StochasticConfig::default()
+                    .with_iterations_option(None)
+                    .with_seed(1),
+            );
+
+        let result =
+            StochasticSearch::<AArch64>::new().search(&mov_add_sequence(), &x0_live(), &config);
+
+        assert!(!result.found_optimization);
+        assert_eq!(result.statistics.iterations, 0);
+        assert_eq!(result.statistics.candidates_evaluated, 0);
+    }
+
+    /// With no iteration cap the timeout alone bounds the run.
+    #[test]
+    fn stochastic_search_without_iteration_cap_runs_until_timeout() {
+        use std::time::{Duration, Instant};
+
+        let mut search: StochasticSearch<AArch64> = StochasticSearch::new();
+        let timeout = Duration::from_millis(200);
+        let config = SearchConfig::default()
+            .with_timeout(timeout)
+            .with_registers(vec![Register::X0, Register::X1])
+            .with_immediates(vec![-1, 0, 1])
+            .with_stochastic(
+                StochasticConfig::default()
+                    .with_iterations_option(None)
+                    .with_seed(11),
+            );
+        let live_out = x0_live();
+        let target = mov_add_sequence();
+
+        let started = Instant::now();
+        let result = search.search(&target, &live_out, &config);
+        let elapsed = started.elapsed();
+
+        assert!(
+            result.statistics.iterations > 10,
+            "expected more than a handful of iterations; got {}",
+            result.statistics.iterations,
+        );
+        assert!(
+            elapsed >= timeout && elapsed < Duration::from_secs(2),
+            "search should stop near the 200ms deadline; took {:?}",
+            elapsed,
+        );
+    }
+
     #[test]
     fn test_acceptance_rate_tracking() {
         let mut search: StochasticSearch<AArch64> = StochasticSearch::new();
@@ -1058,7 +1692,7 @@ mod tests {
             )
             .with_registers(vec![Register::X0, Register::X1, Register::X2]);
 
-        let live_out = LiveOut::from_registers(vec![Register::X0]);
+        let live_out = x0_live();
         let target = mov_zero_sequence();
 
         let result = search.search(&target, &live_out, &config);
@@ -1163,4 +1797,63 @@ mod tests {
         assert_eq!(result.statistics.iterations, 200);
         assert!(result.statistics.candidates_evaluated > 0);
     }
+
+    /// A constant wider than 16 bits is only proposed once
+    /// `max_immediate_bits` admits it.
+    #[test]
+    fn stochastic_limits_honour_max_immediate_bits() {
+        let wide = [Instruction::MovImm {
+            rd: Register::X0,
+            imm: 0x1_2345,
+        }];
+        let capped = SearchConfig::default().with_max_immediate_bits(16);
+        let widened = SearchConfig::default().with_max_immediate_bits(32);
+
+        assert!(!<AArch64 as StochasticBackend<AArch64>>::within_config_limits(&wide, &capped));
+        assert!(<AArch64 as StochasticBackend<AArch64>>::within_config_limits(&wide, &widened));
+    }
+
+    /// Random starting sequences include the shifts by `#0` exactly when
+    /// `include_trivial_shifts` admits them into the candidate pools.
+    #[test]
+    fn random_sequences_honour_include_trivial_shifts() {
+        use rand::SeedableRng;
+
+        let regs = [Register::X0, Register::X1];
+        let is_trivial_shift = |instr: &Instruction| {
+            matches!(
+                instr,
+                Instruction::Lsl {
+                    shift: Operand::Immediate(0),
+                    ..
+                } | Instruction::Lsr {
+                    shift: Operand::Immediate(0),
+                    ..
+                } | Instruction::Asr {
+                    shift: Operand::Immediate(0),
+                    ..
+                } | Instruction::Ror {
+                    shift: Operand::Immediate(0),
+                    ..
+                }
+            )
+        };
+        let trivial_shifts_drawn = |include| {
+            let config = SearchConfig::default().with_include_trivial_shifts(include);
+            let mut rng = ChaCha8Rng::seed_from_u64(666);
+            <AArch64 as StochasticBackend<AArch64>>::random_sequence(
+                &mut rng,
+                20_000,
+                &regs,
+                &[0, 1],
+                &config,
+            )
+            .iter()
+            .filter(|instr| is_trivial_shift(instr))
+            .count()
+        };
+
+        assert_eq!(trivial_shifts_drawn(false), 0);
+        assert!(trivial_shifts_drawn(true) > 0);
+    }
 }
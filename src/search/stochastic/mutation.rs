@@ -11,14 +11,23 @@
 //! transition probabilities, and the stochastic search does not apply a
 //! Hastings ratio to correct that asymmetry. The search is intended as an
 //! optimization heuristic, not as a detailed-balance sampler.
+//!
+//! Length-changing insert/delete moves ([`propose_length_change`]) are the
+//! exception: they report their forward and backward proposal probabilities
+//! so MCMC can apply the Hastings correction at the length bounds.
 
-use crate::ir::instructions::{AARCH64_RANDOM_SHIFT_IMMEDIATES, MOVW_LEGAL_SHIFTS};
+use crate::ir::instructions::{
+    AARCH64_RANDOM_SHIFT_IMMEDIATES, MOVW_LEGAL_SHIFTS, move_wide_chunks, movn_chunk,
+};
 use crate::ir::types::Condition;
 use crate::ir::{
-    ExtendKind, Instruction, Operand, Register, RegisterWidth, VectorArrangement, VectorRegister,
+    ExtendKind, Instruction, Operand, OperandSlot, Register, RegisterWidth, VectorArrangement,
+    VectorRegister,
 };
-use crate::search::candidate::generate_random_instruction;
+use crate::isa::aarch64::{AArch64InstructionGenerator, normalized_immediate_pool};
+use crate::search::candidate::random_shift_immediates;
 use crate::search::config::MutationWeights;
+use crate::search::normalize::canonicalize;
 use rand::RngExt;
 
 const ADDRESS_OFFSET_POOL: [i64; 8] = [0, 8, 16, 24, 32, 64, -8, -256];
@@ -103,23 +112,6 @@ fn single_source_opcode_peer<R: RngExt>(rng: &mut R, rd: Register, rn: Register)
     }
 }
 
-fn normalized_immediate_pool(immediates: &[i64], modulus: i64) -> Vec<i64> {
-    debug_assert!(modulus > 0, "immediate modulus must be positive");
-
-    if immediates.is_empty() {
-        return vec![0];
-    }
-
-    let mut normalized = Vec::new();
-    for imm in immediates {
-        let residue = imm.rem_euclid(modulus);
-        if !normalized.contains(&residue) {
-            normalized.push(residue);
-        }
-    }
-    normalized
-}
-
 /// Mutation operator types
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MutationType {
@@ -131,6 +123,9 @@ pub enum MutationType {
     Swap,
     /// Replace entire instruction
     Instruction,
+    /// Macro-mutation: rewrite the sequence to its peephole canonical form
+    /// (`normalize::canonicalize`), then apply one of the other four
+    Canonicalize,
 }
 
 /// Mutator for instruction sequences
@@ -138,37 +133,103 @@ pub enum MutationType {
 pub struct Mutator {
     registers: Vec<Register>,
     immediates: Vec<i64>,
+    mov_immediates: Vec<i64>,
     imm12_immediates: Vec<i64>,
     imm5_immediates: Vec<i64>,
+    /// Immediate shift amounts; see [`Mutator::with_trivial_shifts`].
+    shift_immediates: &'static [i64],
     weights: MutationWeights,
 }
 
+/// The configured immediates a single `mov rd, #imm` can encode (one MOVZ of
+/// a non-negative value, shifted forms such as `#1, lsl #16` included, or one
+/// MOVN), in first-seen order, or `[0]` if none qualify. Wider values lower to
+/// a MOVZ/MOVK chain, which would turn an operand mutation into a multi-word
+/// instruction.
+fn mov_immediate_pool(immediates: &[i64]) -> Vec<i64> {
+    let mut pool = Vec::new();
+    for &imm in immediates {
+        let single_movz = imm >= 0 && move_wide_chunks(imm).len() == 1;
+        if (single_movz || movn_chunk(imm).is_some()) && !pool.contains(&imm) {
+            pool.push(imm);
+        }
+    }
+    if pool.is_empty() {
+        pool.push(0);
+    }
+    pool
+}
+
+/// How many fields of `instr` that [`Instruction::operands`] leaves out
+/// operand mutation also rewrites; see `Mutator::mutate_extra_field`.
+fn extra_field_count(instr: &Instruction) -> usize {
+    match instr {
+        Instruction::MovN { .. }
+        | Instruction::MovZ { .. }
+        | Instruction::MovK { .. }
+        | Instruction::Ubfx { .. }
+        | Instruction::Sbfx { .. }
+        | Instruction::Bfi { .. }
+        | Instruction::Bfxil { .. }
+        | Instruction::Ubfiz { .. }
+        | Instruction::Sbfiz { .. } => 2,
+        Instruction::MovImm { .. }
+        | Instruction::Movi { .. }
+        | Instruction::MovFromVectorLane { .. }
+        | Instruction::VectorAdd { .. }
+        | Instruction::Ccmp { .. }
+        | Instruction::Ccmn { .. }
+        | Instruction::Ldr { .. }
+        | Instruction::Ldrs { .. }
+        | Instruction::Str { .. }
+        | Instruction::Ldp { .. }
+        | Instruction::Stp { .. } => 1,
+        _ => 0,
+    }
+}
+
 impl Mutator {
     pub fn new(registers: Vec<Register>, immediates: Vec<i64>, weights: MutationWeights) -> Self {
-        // Keep the raw table for unrestricted immediates (MOV, move-wide,
-        // instruction replacement) but precompute per-opcode-class pools for
-        // bounded forms. The pools deduplicate after normalization so
-        // congruent configured values do not get extra proposal weight.
+        // Keep the raw table for instruction replacement but precompute
+        // per-opcode-class pools for bounded forms. The pools deduplicate
+        // after normalization so congruent configured values do not get
+        // extra proposal weight. MOV drops, rather than wraps, the values it
+        // cannot encode: a residue of a wide constant is a different constant.
+        let mov_immediates = mov_immediate_pool(&immediates);
         let imm12_immediates = normalized_immediate_pool(&immediates, 0x1000);
         let imm5_immediates = normalized_immediate_pool(&immediates, 32);
 
         Self {
             registers,
             immediates,
+            mov_immediates,
             imm12_immediates,
             imm5_immediates,
+            shift_immediates: &AARCH64_RANDOM_SHIFT_IMMEDIATES,
             weights,
         }
     }
 
+    /// Also draw the trivial shift by `#0` (a plain `mov rd, rn`), as
+    /// `SearchConfig::include_trivial_shifts` asks of every generator.
+    pub fn with_trivial_shifts(mut self, include: bool) -> Self {
+        self.shift_immediates = random_shift_immediates(include);
+        self
+    }
+
     /// Select a mutation type based on weights
     pub fn select_mutation_type<R: RngExt>(&self, rng: &mut R) -> MutationType {
         let r: f64 = rng.random();
-        match self.weights.select_index(r) {
+        Self::mutation_type_at(self.weights.select_index(r))
+    }
+
+    fn mutation_type_at(index: usize) -> MutationType {
+        match index {
             0 => MutationType::Operand,
             1 => MutationType::Opcode,
             2 => MutationType::Swap,
-            _ => MutationType::Instruction,
+            3 => MutationType::Instruction,
+            _ => MutationType::Canonicalize,
         }
     }
 
@@ -186,11 +247,30 @@ impl Mutator {
             MutationType::Opcode => self.mutate_opcode(rng, &mut result),
             MutationType::Swap => self.mutate_swap(rng, &mut result),
             MutationType::Instruction => self.mutate_instruction(rng, &mut result),
+            MutationType::Canonicalize => self.mutate_canonicalize(rng, &mut result),
         }
 
         result
     }
 
+    /// Canonicalize macro-mutation: jump to the sequence's canonical
+    /// spelling, then take one ordinary step from there, so a chain stuck
+    /// on a non-canonical form can reach that form's neighbours in one
+    /// proposal. Canonicalization is per-instruction, so length and any
+    /// trailing terminator are preserved.
+    fn mutate_canonicalize<R: RngExt>(&self, rng: &mut R, sequence: &mut Vec<Instruction>) {
+        *sequence = canonicalize(sequence);
+        let r: f64 = rng.random();
+        match Self::mutation_type_at(self.weights.without_canonicalize().select_index(r)) {
+            MutationType::Operand => self.mutate_operand(rng, sequence),
+            MutationType::Opcode => self.mutate_opcode(rng, sequence),
+            MutationType::Swap => self.mutate_swap(rng, sequence),
+            MutationType::Instruction | MutationType::Canonicalize => {
+                self.mutate_instruction(rng, sequence)
+            }
+        }
+    }
+
     /// Operand mutation: change a register or immediate in a random instruction
     fn mutate_operand<R: RngExt>(&self, rng: &mut R, sequence: &mut [Instruction]) {
         if sequence.is_empty() || self.registers.is_empty() {
@@ -203,421 +283,209 @@ impl Mutator {
         }
         let idx = rng.random_range(0..rewritable);
         let instr = &mut sequence[idx];
+        // Branches / terminators are never mutated. rewritable_len() above
+        // excludes the terminator slot; this is defense in depth.
+        if instr.is_terminator() {
+            return;
+        }
 
-        match instr {
-            Instruction::MovReg { rd, rn } | Instruction::MovRegW { rd, rn } => {
-                if rng.random_bool(0.5) {
-                    *rd = self.random_register(rng);
-                } else {
-                    *rn = self.random_register(rng);
-                }
-            }
-            Instruction::MovImm { rd, imm } => {
-                if rng.random_bool(0.5) {
-                    *rd = self.random_register(rng);
-                } else {
-                    *imm = self.random_immediate(rng);
-                }
-            }
-            Instruction::Movi {
-                vd, arrangement, ..
-            } => {
-                if rng.random_bool(0.5) {
-                    if let Some(register) = self.random_vector_register(rng) {
-                        *vd = register;
-                    }
-                } else {
-                    *arrangement = alternate_arrangement(*arrangement);
-                }
-            }
-            Instruction::MovFromVectorLane { rd, vn, lane } => match rng.random_range(0..3) {
-                0 => *rd = self.random_register(rng),
-                1 => {
-                    if let Some(register) = self.random_vector_register(rng) {
-                        *vn = register;
-                    }
-                }
-                _ => *lane ^= 1,
-            },
-            Instruction::VectorAdd {
-                vd,
-                vn,
-                vm,
-                arrangement,
-            } => match rng.random_range(0..4) {
-                0 => {
-                    if let Some(register) = self.random_vector_register(rng) {
-                        *vd = register;
-                    }
-                }
-                1 => {
-                    if let Some(register) = self.random_vector_register(rng) {
-                        *vn = register;
-                    }
-                }
-                2 => {
-                    if let Some(register) = self.random_vector_register(rng) {
-                        *vm = register;
-                    }
-                }
-                _ => *arrangement = alternate_arrangement(*arrangement),
-            },
-            Instruction::Add { rd, rn, rm } | Instruction::Sub { rd, rn, rm } => {
-                let choice = rng.random_range(0..3);
-                match choice {
-                    0 => *rd = self.random_register(rng),
-                    1 => *rn = self.random_register(rng),
-                    // Add/Sub do not allow ROR in the shifted-register form.
-                    // Immediate proposals draw from the deduplicated imm12
-                    // pool so congruent configured immediates do not carry
-                    // extra proposal weight.
-                    _ => {
-                        *rm = self.random_operand_3op_from_pool(
-                            rng,
-                            false,
-                            RegisterWidth::X64,
-                            &self.imm12_immediates,
-                        );
-                    }
-                }
-            }
-            Instruction::AddW { rd, rn, rm } | Instruction::SubW { rd, rn, rm } => {
-                let choice = rng.random_range(0..3);
-                match choice {
-                    0 => *rd = self.random_register(rng),
-                    1 => *rn = self.random_register(rng),
-                    // W-form shifted-register amounts are limited to 0..=31.
-                    // Keep the same proposal heat as X-form Add/Sub while
-                    // using a W-safe amount pool.
-                    _ => {
-                        *rm = self.random_operand_3op_from_pool(
-                            rng,
-                            false,
-                            RegisterWidth::W32,
-                            &self.imm12_immediates,
-                        );
-                    }
-                }
-            }
-            Instruction::And { rd, rn, rm, width }
-            | Instruction::Orr { rd, rn, rm, width }
-            | Instruction::Eor { rd, rn, rm, width } => {
-                let choice = rng.random_range(0..3);
-                match choice {
-                    0 => *rd = self.random_register(rng),
-                    1 => *rn = self.random_register(rng),
-                    _ => *rm = self.random_logical_operand(rng, *width, true, true),
-                }
-            }
-            Instruction::Lsl { rd, rn, shift }
-            | Instruction::Lsr { rd, rn, shift }
-            | Instruction::Asr { rd, rn, shift } => {
-                let choice = rng.random_range(0..3);
-                match choice {
-                    0 => *rd = self.random_register(rng),
-                    1 => *rn = self.random_register(rng),
-                    _ => *shift = self.random_shift_operand(rng),
-                }
-            }
-            Instruction::Mul { rd, rn, rm }
-            | Instruction::Sdiv { rd, rn, rm }
-            | Instruction::Udiv { rd, rn, rm } => {
-                let choice = rng.random_range(0..3);
-                match choice {
-                    0 => *rd = self.random_register(rng),
-                    1 => *rn = self.random_register(rng),
-                    _ => *rm = self.random_register(rng),
-                }
-            }
-            // Multiply-accumulate: 4 register slots so 4-way pick.
-            Instruction::Madd { rd, rn, rm, ra } | Instruction::Msub { rd, rn, rm, ra } => {
-                let choice = rng.random_range(0..4);
-                match choice {
-                    0 => *rd = self.random_register(rng),
-                    1 => *rn = self.random_register(rng),
-                    2 => *rm = self.random_register(rng),
-                    _ => *ra = self.random_register(rng),
-                }
-            }
-            // MNEG / SMULH / UMULH: 3 register slots like MUL.
-            Instruction::Mneg { rd, rn, rm }
-            | Instruction::Smulh { rd, rn, rm }
-            | Instruction::Umulh { rd, rn, rm } => {
-                let choice = rng.random_range(0..3);
-                match choice {
-                    0 => *rd = self.random_register(rng),
-                    1 => *rn = self.random_register(rng),
-                    _ => *rm = self.random_register(rng),
-                }
-            }
-            // Comparison instructions (no destination). Cmp/Cmn forbid ROR;
-            // Tst allows it.
-            Instruction::Cmp { rn, rm } | Instruction::Cmn { rn, rm } => {
-                if rng.random_bool(0.5) {
-                    *rn = self.random_register(rng);
-                } else {
-                    *rm = self.random_operand_3op_from_pool(
-                        rng,
-                        false,
-                        RegisterWidth::X64,
-                        &self.imm12_immediates,
-                    );
-                }
+        // Pick uniformly among the operand slots and the fields that
+        // `operands` leaves out (immediates, positions, addresses).
+        let mut slots = instr.operands();
+        let choice = rng.random_range(0..slots.len() + extra_field_count(instr));
+        if choice < slots.len() {
+            slots[choice] = self.random_slot(rng, instr, slots[choice]);
+            *instr = instr
+                .with_operands(&slots)
+                .expect("a random slot keeps the kind of the slot it replaces");
+        } else {
+            self.mutate_extra_field(rng, instr, choice - slots.len());
+        }
+    }
+
+    /// A random replacement for `slot` of `instr`, of the same kind.
+    fn random_slot<R: RngExt>(
+        &self,
+        rng: &mut R,
+        instr: &Instruction,
+        slot: OperandSlot,
+    ) -> OperandSlot {
+        match slot {
+            OperandSlot::Dest(reg) => OperandSlot::Dest(self.random_register_like(rng, reg)),
+            OperandSlot::Src(reg) => OperandSlot::Src(self.random_register_like(rng, reg)),
+            OperandSlot::Operand(_) => {
+                OperandSlot::Operand(self.random_flexible_operand(rng, instr))
             }
-            Instruction::Tst { rn, rm, width } => {
-                if rng.random_bool(0.5) {
-                    *rn = self.random_register(rng);
-                } else {
-                    *rm = self.random_logical_operand(rng, *width, true, false);
-                }
+            OperandSlot::Shift(_) => OperandSlot::Shift(self.random_shift_operand(rng)),
+            OperandSlot::Cond(_) => OperandSlot::Cond(Condition::random_normal(rng)),
+        }
+    }
+
+    /// A random register of the same class (scalar or vector) as `reg`, or
+    /// `reg` itself when no vector register is configured.
+    fn random_register_like<R: RngExt>(&self, rng: &mut R, reg: Register) -> Register {
+        if reg.vector().is_some() {
+            self.random_vector_register(rng)
+                .map_or(reg, Register::Vector)
+        } else {
+            self.random_register(rng)
+        }
+    }
+
+    /// A random flexible `rm` operand for `instr`, drawn from the forms and
+    /// immediate pool its opcode encodes.
+    fn random_flexible_operand<R: RngExt>(&self, rng: &mut R, instr: &Instruction) -> Operand {
+        match *instr {
+            // Add/Sub do not allow ROR in the shifted-register form.
+            // Immediate proposals draw from the deduplicated imm12 pool so
+            // congruent configured immediates do not carry extra proposal
+            // weight. Cmp/Cmn forbid ROR the same way.
+            Instruction::Add { .. }
+            | Instruction::Sub { .. }
+            | Instruction::Cmp { .. }
+            | Instruction::Cmn { .. } => self.random_operand_3op_from_pool(
+                rng,
+                false,
+                RegisterWidth::X64,
+                &self.imm12_immediates,
+            ),
+            // W-form shifted-register amounts are limited to 0..=31. Keep the
+            // same proposal heat as X-form Add/Sub while using a W-safe
+            // amount pool.
+            Instruction::AddW { .. } | Instruction::SubW { .. } => self
+                .random_operand_3op_from_pool(
+                    rng,
+                    false,
+                    RegisterWidth::W32,
+                    &self.imm12_immediates,
+                ),
+            // Same non-ROR shifted-register coverage and deduplicated imm12
+            // immediate pool as Add/Sub, but without the extended-register
+            // branch: ADDS/SUBS do not encode an extended-register form
+            // (issue #279).
+            Instruction::Adds { .. } | Instruction::Subs { .. } => self
+                .random_arith_operand_no_extended(
+                    rng,
+                    false,
+                    RegisterWidth::X64,
+                    &self.imm12_immediates,
+                ),
+            Instruction::And { width, .. }
+            | Instruction::Orr { width, .. }
+            | Instruction::Eor { width, .. } => self.random_logical_operand(rng, width, true, true),
+            // Tst allows ROR.
+            Instruction::Tst { width, .. } => self.random_logical_operand(rng, width, true, false),
+            Instruction::Ands { width, .. } => {
+                self.random_logical_operand(rng, width, false, false)
             }
-            // CCMP / CCMN: rn (register), rm (operand), nzcv (0..=15), cond.
-            // Uniform pick among the four mutable fields. Immediate `rm`
-            // operands draw from a deduplicated imm5 pool so configured
-            // immediates congruent modulo 32 do not become overweighted.
-            Instruction::Ccmp { rn, rm, nzcv, cond } | Instruction::Ccmn { rn, rm, nzcv, cond } => {
-                match rng.random_range(0..4) {
-                    0 => *rn = self.random_register(rng),
-                    1 => {
-                        *rm = match self.random_operand_from_pool(rng, &self.imm5_immediates) {
-                            Operand::Register(r) => Operand::Register(r),
-                            Operand::Immediate(v) => Operand::Immediate(v),
-                            // CCMP/CCMN reject shifted-register or extended-
-                            // register operands; collapse to a plain register
-                            // (consistent with candidate::generate_random_-
-                            // instruction's conditional-compare arm).
-                            Operand::ShiftedRegister { reg, .. }
-                            | Operand::ExtendedRegister { reg, .. } => Operand::Register(reg),
-                        };
-                    }
-                    2 => *nzcv = (rng.random::<u32>() & 0x0F) as u8,
-                    _ => *cond = Condition::random_normal(rng),
+            // CCMP/CCMN immediates draw from a deduplicated imm5 pool so
+            // configured immediates congruent modulo 32 do not become
+            // overweighted. They reject shifted-register or extended-register
+            // operands; collapse to a plain register (consistent with
+            // candidate::generate_random_instruction's conditional-compare
+            // arm).
+            Instruction::Ccmp { .. } | Instruction::Ccmn { .. } => {
+                match self.random_operand_from_pool(rng, &self.imm5_immediates) {
+                    Operand::ShiftedRegister { reg, .. }
+                    | Operand::ExtendedRegister { reg, .. } => Operand::Register(reg),
+                    operand => operand,
                 }
             }
-            // Conditional select instructions
-            Instruction::Csel { rd, rn, rm, .. }
-            | Instruction::Csinc { rd, rn, rm, .. }
-            | Instruction::Csinv { rd, rn, rm, .. }
-            | Instruction::Csneg { rd, rn, rm, .. } => {
-                let choice = rng.random_range(0..3);
-                match choice {
-                    0 => *rd = self.random_register(rng),
-                    1 => *rn = self.random_register(rng),
-                    _ => *rm = self.random_register(rng),
-                }
+            // Inverted-logical: BIC / BICS / ORN / EON — register-only rm.
+            _ => Operand::Register(self.random_register(rng)),
+        }
+    }
+
+    /// Mutate the `field`-th of the fields [`Instruction::operands`] leaves
+    /// out of `instr` (see [`extra_field_count`]).
+    fn mutate_extra_field<R: RngExt>(&self, rng: &mut R, instr: &mut Instruction, field: usize) {
+        match instr {
+            Instruction::MovImm { imm, .. } => *imm = self.random_mov_immediate(rng),
+            Instruction::Movi { arrangement, .. } | Instruction::VectorAdd { arrangement, .. } => {
+                *arrangement = alternate_arrangement(*arrangement);
             }
-            // Unary: MVN, NEG, NEGS
-            Instruction::Mvn { rd, rm }
-            | Instruction::Neg { rd, rm }
-            | Instruction::Negs { rd, rm } => {
-                if rng.random_bool(0.5) {
-                    *rd = self.random_register(rng);
-                } else {
-                    *rm = self.random_register(rng);
-                }
+            Instruction::MovFromVectorLane { lane, .. } => *lane ^= 1,
+            Instruction::Ccmp { nzcv, .. } | Instruction::Ccmn { nzcv, .. } => {
+                *nzcv = (rng.random::<u32>() & 0x0F) as u8;
             }
-            // Single-source bit-manipulation: CLZ, CLS, RBIT, REV, REV32, REV16,
-            // plus the standalone extends SXTB/SXTH/SXTW/UXTB/UXTH (issue #60).
-            Instruction::Clz { rd, rn }
-            | Instruction::Cls { rd, rn }
-            | Instruction::Rbit { rd, rn }
-            | Instruction::Rev { rd, rn }
-            | Instruction::Rev32 { rd, rn }
-            | Instruction::Rev16 { rd, rn }
-            | Instruction::Sxtb { rd, rn }
-            | Instruction::Sxth { rd, rn }
-            | Instruction::Sxtw { rd, rn }
-            | Instruction::Uxtb { rd, rn }
-            | Instruction::Uxth { rd, rn } => {
-                if rng.random_bool(0.5) {
-                    *rd = self.random_register(rng);
+            // MOVK reads rd, so mutating its rd slot additionally changes the
+            // upper-lanes source — that's intentional and matches the other
+            // dest-mutating slots.
+            Instruction::MovN { imm, shift, .. }
+            | Instruction::MovZ { imm, shift, .. }
+            | Instruction::MovK { imm, shift, .. } => {
+                if field == 0 {
+                    *imm = (rng.random::<u32>() & 0xFFFF) as u16;
                 } else {
-                    *rn = self.random_register(rng);
-                }
-            }
-            // MOVN / MOVZ / MOVK: mutate rd, imm, or shift. MOVK reads rd, so
-            // mutating rd here additionally changes the upper-lanes source —
-            // that's intentional and matches the other dest-mutating arms.
-            Instruction::MovN { rd, imm, shift }
-            | Instruction::MovZ { rd, imm, shift }
-            | Instruction::MovK { rd, imm, shift } => match rng.random_range(0..3) {
-                0 => *rd = self.random_register(rng),
-                1 => *imm = (rng.random::<u32>() & 0xFFFF) as u16,
-                _ => {
                     *shift = MOVW_LEGAL_SHIFTS[rng.random_range(0..MOVW_LEGAL_SHIFTS.len())];
                 }
-            },
-            // Inverted-logical: BIC / BICS / ORN / EON — register-only rm
-            Instruction::Bic { rd, rn, rm }
-            | Instruction::Bics { rd, rn, rm }
-            | Instruction::Orn { rd, rn, rm }
-            | Instruction::Eon { rd, rn, rm } => {
-                let choice = rng.random_range(0..3);
-                match choice {
-                    0 => *rd = self.random_register(rng),
-                    1 => *rn = self.random_register(rng),
-                    _ => *rm = Operand::Register(self.random_register(rng)),
-                }
             }
-            // Flag-setting arith/logical: ADDS/SUBS allow 12-bit imm; ANDS
-            // also accepts bitmask imms but we keep the mutator's `rm` table
-            // tuned to the 12-bit form (see candidate.rs notes).
-            Instruction::Adds { rd, rn, rm } | Instruction::Subs { rd, rn, rm } => {
-                let choice = rng.random_range(0..3);
-                match choice {
-                    0 => *rd = self.random_register(rng),
-                    1 => *rn = self.random_register(rng),
-                    // Same non-ROR shifted-register coverage and deduplicated
-                    // imm12 immediate pool as Add/Sub, but without the
-                    // extended-register branch: ADDS/SUBS do not encode an
-                    // extended-register form (issue #279).
-                    _ => {
-                        *rm = self.random_arith_operand_no_extended(
-                            rng,
-                            false,
-                            RegisterWidth::X64,
-                            &self.imm12_immediates,
-                        );
-                    }
-                }
-            }
-            // ADC/ADCS/SBC/SBCS are register-only (rd, rn, rm all registers).
-            Instruction::Adc { rd, rn, rm }
-            | Instruction::Adcs { rd, rn, rm }
-            | Instruction::Sbc { rd, rn, rm }
-            | Instruction::Sbcs { rd, rn, rm } => match rng.random_range(0..3) {
-                0 => *rd = self.random_register(rng),
-                1 => *rn = self.random_register(rng),
-                _ => *rm = self.random_register(rng),
-            },
-            Instruction::Ands { rd, rn, rm, width } => {
-                let choice = rng.random_range(0..3);
-                match choice {
-                    0 => *rd = self.random_register(rng),
-                    1 => *rn = self.random_register(rng),
-                    _ => *rm = self.random_logical_operand(rng, *width, false, false),
-                }
-            }
-            // CSET / CSETM: only rd and cond can change; cond from the 14
-            // non-AL/NV options.
-            Instruction::Cset { rd, cond } | Instruction::Csetm { rd, cond } => {
-                if rng.random_bool(0.5) {
-                    *rd = self.random_register(rng);
-                } else {
-                    *cond = Condition::random_normal(rng);
-                }
-            }
-            // ROR: same operand shape as LSL/LSR/ASR
-            Instruction::Ror { rd, rn, shift } => {
-                let choice = rng.random_range(0..3);
-                match choice {
-                    0 => *rd = self.random_register(rng),
-                    1 => *rn = self.random_register(rng),
-                    _ => *shift = self.random_shift_operand(rng),
-                }
-            }
-            // Bit-field manipulation: 4-way operand mutation (rd, rn, lsb, width)
-            // with 2D clamping so the (lsb + width <= bound) constraint is always
-            // preserved, where `bound` is 32 for the W form and 64 for X. The
-            // register width form itself is never changed here (that would be a
-            // cross-width opcode bridge, which we deliberately avoid).
+            // Bit-field manipulation: clamp in 2D so the (lsb + width <=
+            // bound) constraint is always preserved, where `bound` is 32 for
+            // the W form and 64 for X. The register width form itself is
+            // never changed here (that would be a cross-width opcode bridge,
+            // which we deliberately avoid).
             Instruction::Ubfx {
-                rd,
-                rn,
                 lsb,
                 width,
                 reg_width,
+                ..
             }
             | Instruction::Sbfx {
-                rd,
-                rn,
                 lsb,
                 width,
                 reg_width,
+                ..
             }
             | Instruction::Bfi {
-                rd,
-                rn,
                 lsb,
                 width,
                 reg_width,
+                ..
             }
             | Instruction::Bfxil {
-                rd,
-                rn,
                 lsb,
                 width,
                 reg_width,
+                ..
             }
             | Instruction::Ubfiz {
-                rd,
-                rn,
                 lsb,
                 width,
                 reg_width,
+                ..
             }
             | Instruction::Sbfiz {
-                rd,
-                rn,
                 lsb,
                 width,
                 reg_width,
+                ..
             } => {
                 let bound = reg_width.bit_width() as u32;
-                match rng.random_range(0..4) {
-                    0 => *rd = self.random_register(rng),
-                    1 => *rn = self.random_register(rng),
-                    2 => {
-                        // Mutate width: bound by current lsb so the pair stays valid.
-                        let max_w = (bound - *lsb as u32).max(1);
-                        *width = ((rng.random::<u32>() % max_w) + 1) as u8;
-                    }
-                    _ => {
-                        // Mutate lsb; clamp width down if the new lsb would
-                        // overflow the (lsb + width <= bound) constraint.
-                        *lsb = (rng.random::<u32>() % bound) as u8;
-                        if (*lsb as u16 + *width as u16) > bound as u16 {
-                            *width = bound as u8 - *lsb;
-                        }
-                    }
-                }
-            }
-            // Branches / terminators: never mutated. The rewritable_len()
-            // helper above excludes the terminator slot before this fires;
-            // arm is a no-op for defense in depth.
-            Instruction::B { .. }
-            | Instruction::BCond { .. }
-            | Instruction::Ret { .. }
-            | Instruction::Cbz { .. }
-            | Instruction::Cbnz { .. }
-            | Instruction::Tbz { .. }
-            | Instruction::Tbnz { .. }
-            | Instruction::Bl { .. }
-            | Instruction::Br { .. } => {}
-
-            // Memory ops (issue #68 step 16). Rotate over the small set of
-            // mutable fields per variant: data register, base, optional
-            // index register, optional offset. Keep address-mode and width
-            // unchanged here (those are bridged via mutate_opcode in a
-            // future step); the encodability filter downstream drops any
-            // mutation that violates SP/XZR or writeback-aliasing rules.
-            Instruction::Ldr { rt, addr, .. }
-            | Instruction::Ldrs { rt, addr, .. }
-            | Instruction::Str { rt, addr, .. } => {
-                if rng.random_bool(0.5) {
-                    *rt = self.random_register(rng);
+                if field == 0 {
+                    // Mutate width: bound by current lsb so the pair stays valid.
+                    let max_w = (bound - *lsb as u32).max(1);
+                    *width = ((rng.random::<u32>() % max_w) + 1) as u8;
                 } else {
-                    mutate_address_operand(self, rng, addr);
-                }
-            }
-            Instruction::Ldp { rt1, rt2, addr, .. } | Instruction::Stp { rt1, rt2, addr, .. } => {
-                let choice = rng.random_range(0..3);
-                match choice {
-                    0 => *rt1 = self.random_register(rng),
-                    1 => *rt2 = self.random_register(rng),
-                    _ => mutate_address_operand(self, rng, addr),
+                    // Mutate lsb; clamp width down if the new lsb would
+                    // overflow the (lsb + width <= bound) constraint.
+                    *lsb = (rng.random::<u32>() % bound) as u8;
+                    if (*lsb as u16 + *width as u16) > bound as u16 {
+                        *width = bound as u8 - *lsb;
+                    }
                 }
             }
+            // Memory ops (issue #68 step 16): the address is the one field
+            // beyond the data registers. Keep address-mode and width
+            // unchanged here (those are bridged via mutate_opcode in a future
+            // step); the encodability filter downstream drops any mutation
+            // that violates SP/XZR or writeback-aliasing rules.
+            Instruction::Ldr { addr, .. }
+            | Instruction::Ldrs { addr, .. }
+            | Instruction::Str { addr, .. }
+            | Instruction::Ldp { addr, .. }
+            | Instruction::Stp { addr, .. } => mutate_address_operand(self, rng, addr),
+            _ => {}
         }
     }
 
@@ -663,7 +531,7 @@ impl Mutator {
                 if rng.random_bool(0.5) {
                     Instruction::MovImm {
                         rd,
-                        imm: self.random_immediate(rng),
+                        imm: self.random_mov_immediate(rng),
                     }
                 } else {
                     Instruction::MovReg { rd, rn }
@@ -699,7 +567,7 @@ impl Mutator {
                 } else {
                     Instruction::MovImm {
                         rd,
-                        imm: self.random_immediate(rng),
+                        imm: self.random_mov_immediate(rng),
                     }
                 }
             }
@@ -962,11 +830,10 @@ impl Mutator {
                 1 => Instruction::MovK { rd, imm, shift },
                 // MovZ → MovImm uses the raw u16 `imm`, NOT `imm << shift`. We
                 // deliberately discard the shift here: MCMC is exploring the
-                // value space, and binding the new MovImm to the shifted bit
-                // pattern would only widen `MovImm`'s effective range beyond
-                // its 0..=0xFFFF encoding window. The neighbouring MovImm has
-                // its own per-field mutator that will refine `imm` on later
-                // steps.
+                // value space, and under `lsl #48` the shifted bit pattern can
+                // be negative, which no single `mov` encodes. The neighbouring
+                // MovImm has its own per-field mutator that will refine `imm`
+                // on later steps.
                 2 => Instruction::MovImm {
                     rd,
                     imm: imm as i64,
@@ -1558,7 +1425,25 @@ impl Mutator {
             return;
         }
         let idx = rng.random_range(0..rewritable);
-        sequence[idx] = generate_random_instruction(rng, &self.registers, &self.immediates);
+        // Fresh instructions come from the ISA's generator, which is also
+        // what the stochastic search draws its initial candidates from.
+        let fresh = AArch64InstructionGenerator.generate_random_with_shifts(
+            rng,
+            &self.registers,
+            &self.immediates,
+            self.shift_immediates,
+        );
+        sequence[idx] = match fresh {
+            // The generator draws MOV immediates from the raw table; resample
+            // a wide one from the encodable MOV pool.
+            Instruction::MovImm { rd, imm } if !self.mov_immediates.contains(&imm) => {
+                Instruction::MovImm {
+                    rd,
+                    imm: self.random_mov_immediate(rng),
+                }
+            }
+            instr => instr,
+        };
     }
 
     fn random_address_offset<R: RngExt>(&self, rng: &mut R) -> i64 {
@@ -1593,12 +1478,8 @@ impl Mutator {
         }
     }
 
-    fn random_immediate<R: RngExt>(&self, rng: &mut R) -> i64 {
-        if self.immediates.is_empty() {
-            0
-        } else {
-            self.immediates[rng.random_range(0..self.immediates.len())]
-        }
+    fn random_mov_immediate<R: RngExt>(&self, rng: &mut R) -> i64 {
+        self.random_immediate_from_pool(rng, &self.mov_immediates)
     }
 
     fn random_logical_immediate<R: RngExt>(&self, rng: &mut R, width: RegisterWidth) -> i64 {
@@ -1799,7 +1680,7 @@ impl Mutator {
 
     fn random_shift_operand<R: RngExt>(&self, rng: &mut R) -> Operand {
         if rng.random_bool(0.7) {
-            let shifts = AARCH64_RANDOM_SHIFT_IMMEDIATES;
+            let shifts = self.shift_immediates;
             Operand::Immediate(shifts[rng.random_range(0..shifts.len())])
         } else if !self.registers.is_empty() {
             Operand::Register(self.random_register(rng))
@@ -1822,6 +1703,11 @@ impl AArch64Mutator {
         Self(Mutator::new(registers, immediates, weights))
     }
 
+    /// See [`Mutator::with_trivial_shifts`].
+    pub fn with_trivial_shifts(self, include: bool) -> Self {
+        Self(self.0.with_trivial_shifts(include))
+    }
+
     /// Access the inner free `Mutator` for consumers that haven't migrated yet.
     pub fn inner(&self) -> &Mutator {
         &self.0
@@ -1874,6 +1760,67 @@ fn mutate_address_operand<R: RngExt>(
     }
 }
 
+/// A length-changing proposal and the probabilities the Hastings ratio
+/// needs: `forward_prob` of proposing `sequence` from the current state,
+/// `backward_prob` of proposing the reverse move from `sequence`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LengthProposal<T> {
+    pub sequence: Vec<T>,
+    pub forward_prob: f64,
+    pub backward_prob: f64,
+}
+
+/// Insert `fresh` at, or delete one instruction from, a uniformly chosen
+/// position of the first `rewritable` instructions (any trailing terminator
+/// stays put), keeping the rewritable length within `min..=max`.
+///
+/// Where both moves are legal each is picked with probability 1/2; at a
+/// bound the only legal one is picked with probability 1. The position
+/// choice cancels between a move and its reverse, so it is this asymmetry
+/// at the bounds that the reported probabilities carry. The draw of `fresh`
+/// itself is treated as symmetric with the deletion that undoes it. Returns
+/// `None` when `min == max`.
+pub fn propose_length_change<T: Clone, R: RngExt>(
+    rng: &mut R,
+    sequence: &[T],
+    rewritable: usize,
+    min: usize,
+    max: usize,
+    fresh: impl FnOnce(&mut R) -> T,
+) -> Option<LengthProposal<T>> {
+    let insert_prob = |len: usize| match (len > min, len < max) {
+        (true, true) => 0.5,
+        (false, true) => 1.0,
+        _ => 0.0,
+    };
+    let delete_prob = |len: usize| match (len > min, len < max) {
+        (true, true) => 0.5,
+        (true, false) => 1.0,
+        _ => 0.0,
+    };
+    let p_insert = insert_prob(rewritable);
+    if p_insert == 0.0 && delete_prob(rewritable) == 0.0 {
+        return None;
+    }
+    let mut next = sequence.to_vec();
+    if rng.random::<f64>() < p_insert {
+        let slots = rewritable + 1;
+        next.insert(rng.random_range(0..slots), fresh(rng));
+        Some(LengthProposal {
+            sequence: next,
+            forward_prob: p_insert / slots as f64,
+            backward_prob: delete_prob(rewritable + 1) / slots as f64,
+        })
+    } else {
+        next.remove(rng.random_range(0..rewritable));
+        Some(LengthProposal {
+            sequence: next,
+            forward_prob: delete_prob(rewritable) / rewritable as f64,
+            backward_prob: insert_prob(rewritable - 1) / rewritable as f64,
+        })
+    }
+}
+
 /// Perform operand mutation on a specific instruction (for testing)
 /// Number of instructions a mutation operator may rewrite. Equals
 /// `sequence.len()` for terminator-free sequences and `sequence.len() - 1`
@@ -1917,6 +1864,8 @@ pub fn mutate_opcode_in_place<R: RngExt>(rng: &mut R, instr: &mut Instruction) {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::isa::InstructionGenerator;
+    use crate::isa::aarch64::AArch64InstructionGenerator;
     use crate::search::config::SearchConfig;
     use proptest::prelude::*;
     use rand::SeedableRng;
@@ -1932,6 +1881,150 @@ mod tests {
         )
     }
 
+    /// On a flat cost landscape the Hastings-corrected insert/delete chain
+    /// spends equal time at every length; the bare Metropolis rule would
+    /// over-visit the middle length, whose neighbours can only move toward it.
+    #[test]
+    fn length_moves_with_hastings_correction_visit_lengths_uniformly() {
+        use crate::search::stochastic::acceptance::AcceptanceCriterion;
+
+        const STEPS: usize = 120_000;
+        let criterion = AcceptanceCriterion::new(1.0);
+        let mut rng = StdRng::seed_from_u64(604);
+        let mut sequence = vec![0u8];
+        let mut visits = [0usize; 3];
+        for _ in 0..STEPS {
+            let len = sequence.len();
+            let proposal = propose_length_change(&mut rng, &sequence, len, 1, 3, |_| 0u8)
+                .expect("lengths 1..=3 always allow a move");
+            assert_eq!(proposal.sequence.len().abs_diff(len), 1);
+            if criterion.accept_with_proposal_ratio(
+                &mut rng,
+                0,
+                0,
+                proposal.forward_prob,
+                proposal.backward_prob,
+            ) {
+                sequence = proposal.sequence;
+            }
+            visits[sequence.len() - 1] += 1;
+        }
+        for count in visits {
+            let share = count as f64 / STEPS as f64;
+            assert!((share - 1.0 / 3.0).abs() < 0.02, "visits {visits:?}");
+        }
+        assert_eq!(
+            propose_length_change(&mut rng, &[0u8, 0], 2, 2, 2, |_| 0u8),
+            None
+        );
+    }
+
+    #[test]
+    fn mutation_never_produces_an_unencodable_instruction() {
+        // Fixed-seed random walk over a deliberately hostile immediate table:
+        // negatives, every imm12/imm16 boundary, and values only a MOVZ/MOVK
+        // chain could materialize. Every proposal must stay encodable.
+        let immediates = vec![
+            i64::MIN,
+            -65537,
+            -4097,
+            -4096,
+            -256,
+            -33,
+            -1,
+            0,
+            1,
+            2,
+            3,
+            31,
+            32,
+            63,
+            64,
+            255,
+            4095,
+            4096,
+            4097,
+            0xFFFF,
+            0x1_0000,
+            0x1_2345,
+            0xFFFF_FFFF,
+            0x1_0000_0000,
+            i64::MAX,
+        ];
+        let registers = vec![Register::X0, Register::X1, Register::X2, Register::X3];
+        let mutator = Mutator::new(registers, immediates, MutationWeights::default());
+        let mut rng = ChaCha8Rng::seed_from_u64(641);
+        let mut current = vec![
+            Instruction::MovReg {
+                rd: Register::X0,
+                rn: Register::X1,
+            },
+            Instruction::Add {
+                rd: Register::X0,
+                rn: Register::X0,
+                rm: Operand::Immediate(1),
+            },
+            Instruction::MovImm {
+                rd: Register::X2,
+                imm: 0xFFFF,
+            },
+        ];
+
+        for step in 0..100_000 {
+            let next = mutator.mutate(&mut rng, &current);
+            if let Some(bad) = next.iter().find(|instr| !instr.is_encodable_aarch64()) {
+                panic!("step {step}: mutating {current:?} produced unencodable `{bad}`");
+            }
+            current = next;
+        }
+    }
+
+    #[test]
+    fn mutation_reaches_every_opcode_id() {
+        use crate::ir::VectorRegister;
+        use crate::isa::InstructionType;
+
+        let registers = vec![
+            Register::X0,
+            Register::X1,
+            Register::X2,
+            Register::Vector(VectorRegister::V0),
+            Register::Vector(VectorRegister::V1),
+        ];
+        let mutator = Mutator::new(registers, vec![-1, 0, 1, 2, 31], MutationWeights::default());
+        let mut rng = ChaCha8Rng::seed_from_u64(647);
+        let mut current = vec![
+            Instruction::MovReg {
+                rd: Register::X0,
+                rn: Register::X1,
+            };
+            3
+        ];
+        let mut reached = std::collections::BTreeSet::new();
+        for _ in 0..50_000 {
+            current = mutator.mutate(&mut rng, &current);
+            reached.extend(current.iter().map(InstructionType::opcode_id));
+        }
+
+        let missing: Vec<u8> = (0..AArch64InstructionGenerator.opcode_count())
+            .filter(|id| !reached.contains(id))
+            .collect();
+        assert!(missing.is_empty(), "opcode ids never proposed: {missing:?}");
+    }
+
+    #[test]
+    fn mov_immediate_pool_keeps_only_single_instruction_values() {
+        assert_eq!(
+            mov_immediate_pool(&[0x1_2345, -1, 0xFFFF, -1, 0x1_0000, -65537]),
+            vec![-1, 0xFFFF, 0x1_0000, -65537]
+        );
+        assert_eq!(
+            mov_immediate_pool(&[0x1_0001, 0xFFFF_0000_0000, 0x7FFF_0000_0000_0000]),
+            vec![0xFFFF_0000_0000, 0x7FFF_0000_0000_0000]
+        );
+        assert_eq!(mov_immediate_pool(&[0x1_0001, i64::MIN]), vec![0]);
+    }
+
     #[test]
     fn normalized_immediate_pool_keeps_unique_residues_in_first_seen_order() {
         assert_eq!(
@@ -2070,6 +2163,16 @@ mod tests {
         );
     }
 
+    #[test]
+    fn trivial_shifts_are_sampled_when_included() {
+        let mutator = default_mutator().with_trivial_shifts(true);
+        let mut rng = ChaCha8Rng::seed_from_u64(0x666);
+
+        let saw_zero =
+            (0..2_000).any(|_| mutator.random_shift_operand(&mut rng) == Operand::Immediate(0));
+        assert!(saw_zero, "random_shift_operand never sampled shift #0");
+    }
+
     #[test]
     fn mutate_operand_samples_encodable_logical_immediates_for_both_widths() {
         let mutator = Mutator::new(
@@ -3555,6 +3658,7 @@ mod tests {
                 MutationType::Opcode => opcode_count += 1,
                 MutationType::Swap => swap_count += 1,
                 MutationType::Instruction => instr_count += 1,
+                MutationType::Canonicalize => panic!("canonicalize is off by default"),
             }
         }
 
@@ -3581,6 +3685,7 @@ mod tests {
                     opcode: 0.0,
                     swap: 0.0,
                     instruction: 0.0,
+                    canonicalize: 0.0,
                 },
                 MutationType::Operand,
             ),
@@ -3590,6 +3695,7 @@ mod tests {
                     opcode: 1.0,
                     swap: 0.0,
                     instruction: 0.0,
+                    canonicalize: 0.0,
                 },
                 MutationType::Opcode,
             ),
@@ -3599,6 +3705,7 @@ mod tests {
                     opcode: 0.0,
                     swap: 1.0,
                     instruction: 0.0,
+                    canonicalize: 0.0,
                 },
                 MutationType::Swap,
             ),
@@ -3608,9 +3715,20 @@ mod tests {
                     opcode: 0.0,
                     swap: 0.0,
                     instruction: 1.0,
+                    canonicalize: 0.0,
                 },
                 MutationType::Instruction,
             ),
+            (
+                MutationWeights {
+                    operand: 0.0,
+                    opcode: 0.0,
+                    swap: 0.0,
+                    instruction: 0.0,
+                    canonicalize: 1.0,
+                },
+                MutationType::Canonicalize,
+            ),
         ];
         let mut rng = rand::rng();
         for (weights, expected) in cases {
@@ -3621,6 +3739,79 @@ mod tests {
         }
     }
 
+    #[test]
+    fn canonicalize_mutation_changes_reachable_states_and_keeps_length() {
+        use rand::SeedableRng;
+        use std::collections::HashSet;
+
+        // `csinc x0, x1, x2, al` canonicalizes to `mov x0, x1`, and the
+        // `csel ..., ne` flips to its `eq` spelling: neither form is one
+        // ordinary mutation away from the original.
+        let start = vec![
+            Instruction::Csinc {
+                rd: Register::X0,
+                rn: Register::X1,
+                rm: Register::X2,
+                cond: Condition::AL,
+            },
+            Instruction::Csel {
+                rd: Register::X3,
+                rn: Register::X1,
+                rm: Register::X2,
+                cond: Condition::NE,
+            },
+        ];
+        let reachable = |canonicalize: f64| {
+            let weights = MutationWeights {
+                canonicalize,
+                ..MutationWeights::default()
+            };
+            let mutator = Mutator::new(
+                vec![Register::X0, Register::X1, Register::X2, Register::X3],
+                vec![0, 1],
+                weights,
+            );
+            let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(613);
+            let mut seen = HashSet::new();
+            for _ in 0..2_000 {
+                let proposal = mutator.mutate(&mut rng, &start);
+                assert_eq!(proposal.len(), start.len(), "length changed: {proposal:?}");
+                seen.insert(proposal);
+            }
+            seen
+        };
+
+        let plain = reachable(0.0);
+        let with_macro = reachable(0.3);
+        assert!(
+            !plain
+                .iter()
+                .any(|seq| matches!(seq[0], Instruction::MovReg { .. })
+                    && matches!(
+                        seq[1],
+                        Instruction::Csel {
+                            cond: Condition::EQ,
+                            ..
+                        }
+                    )),
+            "plain mutations should not reach the doubly canonical neighbourhood"
+        );
+        assert!(
+            with_macro
+                .iter()
+                .any(|seq| matches!(seq[0], Instruction::MovReg { .. })
+                    && matches!(
+                        seq[1],
+                        Instruction::Csel {
+                            cond: Condition::EQ,
+                            ..
+                        }
+                    )),
+            "canonicalize mutation should reach the canonical neighbourhood"
+        );
+        assert_ne!(plain, with_macro);
+    }
+
     #[test]
     fn test_mutate_produces_different_sequence() {
         let mutator = default_mutator();
@@ -28,6 +28,17 @@ pub trait SymbolicBackend<I: ISA>: Sized {
     /// the supplied register and immediate pools.
     fn enumerate_all(regs: &[I::Register], imms: &[i64]) -> Vec<I::Instruction>;
 
+    /// Candidate pool for `config`. Defaults to `enumerate_all`; backends
+    /// override it to honour config knobs such as `max_immediate_bits`.
+    fn enumerate_for_config(
+        config: &SearchConfig,
+        regs: &[I::Register],
+        imms: &[i64],
+    ) -> Vec<I::Instruction> {
+        let _ = config;
+        Self::enumerate_all(regs, imms)
+    }
+
     /// Return the target's trailing terminator if any. The synthesis
     /// loop appends it to each candidate proposal so the equivalence
     /// check's terminator-equality precheck doesn't reject every
@@ -51,13 +62,28 @@ pub trait SymbolicBackend<I: ISA>: Sized {
 
     /// Sum the cost of every instruction in the sequence.
     fn sequence_cost(seq: &[I::Instruction], metric: &CostMetric, width: u32) -> u64;
+    /// As [`EnumerativeBackend::bind_live_out`].
+    ///
+    /// [`EnumerativeBackend::bind_live_out`]: crate::search::enumerative::search::EnumerativeBackend::bind_live_out
+    fn bind_live_out(metric: CostMetric, _live_out: &Self::LiveOut) -> CostMetric {
+        metric
+    }
 
-    /// Run the full equivalence check.
+    /// Bytes `seq` assembles to, for `SearchConfig::max_bytes`. `None` (the
+    /// default) when the backend cannot size it; such candidates are never
+    /// pruned by size.
+    fn encoded_length(_seq: &[I::Instruction]) -> Option<usize> {
+        None
+    }
+
+    /// Run the full equivalence check, trying `random_tests` concrete inputs
+    /// before the solver.
     fn check_equivalence(
         target: &[I::Instruction],
         proposal: &[I::Instruction],
         live_out: &Self::LiveOut,
         width: u32,
+        random_tests: usize,
         timeout: Duration,
     ) -> (EquivalenceResult, EquivalenceMetrics);
 
@@ -73,7 +99,7 @@ impl SymbolicBackend<crate::isa::AArch64> for crate::isa::AArch64 {
     type LiveOut = crate::semantics::live_out::LiveOut;
 
     fn registers_from_config(config: &SearchConfig) -> Vec<crate::ir::Register> {
-        config.available_registers.clone()
+        config.search_registers()
     }
 
     fn immediates_from_config(config: &SearchConfig) -> Vec<i64> {
@@ -84,6 +110,35 @@ impl SymbolicBackend<crate::isa::AArch64> for crate::isa::AArch64 {
         crate::search::candidate::generate_all_encodable_instructions(regs, imms)
     }
 
+    fn can_improve_at_same_instruction_count(
+        _target: &[crate::ir::Instruction],
+        config: &SearchConfig,
+    ) -> bool {
+        // An equal-length rewrite can still clobber fewer registers, or be
+        // faster under a composite latency term.
+        match config.cost_metric {
+            CostMetric::Clobbers(_) => true,
+            CostMetric::Composite(weights, _) => weights.latency > 0 || weights.clobbers > 0,
+            _ => false,
+        }
+    }
+
+    fn enumerate_for_config(
+        config: &SearchConfig,
+        regs: &[crate::ir::Register],
+        imms: &[i64],
+    ) -> Vec<crate::ir::Instruction> {
+        crate::search::candidate::generate_candidates_for_config(config, regs, imms)
+    }
+
+    fn encoded_length(seq: &[crate::ir::Instruction]) -> Option<usize> {
+        Some(seq.iter().map(crate::ir::Instruction::encoded_length).sum())
+    }
+
+    fn bind_live_out(metric: CostMetric, live_out: &Self::LiveOut) -> CostMetric {
+        metric.with_live_out(live_out)
+    }
+
     fn sequence_cost(seq: &[crate::ir::Instruction], metric: &CostMetric, _width: u32) -> u64 {
         <crate::isa::AArch64 as CostModel<crate::ir::Instruction>>::sequence_cost(
             &crate::isa::AArch64,
@@ -97,6 +152,7 @@ impl SymbolicBackend<crate::isa::AArch64> for crate::isa::AArch64 {
         proposal: &[crate::ir::Instruction],
         live_out: &Self::LiveOut,
         _width: u32,
+        random_tests: usize,
         timeout: Duration,
     ) -> (EquivalenceResult, EquivalenceMetrics) {
         // Honor the caller's live-out mask: ELF optimization derives
@@ -106,7 +162,7 @@ impl SymbolicBackend<crate::isa::AArch64> for crate::isa::AArch64 {
         // `check_equivalence_with_config` re-derives it from
         // `touches_memory()` on the candidate / target. See ADR-0007.
         let cfg = crate::semantics::EquivalenceConfig::with_live_out(live_out.clone())
-            .random_tests(5)
+            .random_tests(random_tests)
             .timeout(timeout)
             .with_flags(live_out.flags_live())
             .with_memory(true);
@@ -180,11 +236,13 @@ impl SymbolicBackend<crate::isa::X86_64> for crate::isa::X86_64 {
         proposal: &[crate::isa::x86::X86Instruction],
         live_out: &Self::LiveOut,
         _width: u32,
+        random_tests: usize,
         timeout: Duration,
     ) -> (EquivalenceResult, EquivalenceMetrics) {
         let cfg =
             crate::semantics::equivalence::EquivalenceConfigFor::<crate::isa::X86_64>::default()
                 .live_out(live_out.clone())
+                .random_tests(random_tests)
                 .timeout(timeout);
         crate::semantics::equivalence::check_equivalence_for_metrics::<crate::isa::X86_64>(
             target, proposal, &cfg,
@@ -267,11 +325,13 @@ impl SymbolicBackend<crate::isa::X86_32> for crate::isa::X86_32 {
         proposal: &[crate::isa::x86::X86Instruction],
         live_out: &Self::LiveOut,
         _width: u32,
+        random_tests: usize,
         timeout: Duration,
     ) -> (EquivalenceResult, EquivalenceMetrics) {
         let cfg =
             crate::semantics::equivalence::EquivalenceConfigFor::<crate::isa::X86_32>::default()
                 .live_out(live_out.clone())
+                .random_tests(random_tests)
                 .timeout(timeout);
         crate::semantics::equivalence::check_equivalence_for_metrics::<crate::isa::X86_32>(
             target, proposal, &cfg,
@@ -313,6 +373,7 @@ mod tests {
             &proposal,
             &live_out,
             64,
+            5,
             Duration::from_secs(2),
         );
 
@@ -323,6 +384,7 @@ mod tests {
             &proposal,
             &live_out.with_flags(true),
             64,
+            5,
             Duration::from_secs(2),
         );
 
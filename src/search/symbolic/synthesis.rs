@@ -4,17 +4,28 @@
 //! The approach uses linear cost search: try candidate prefix lengths in
 //! ascending order (bounded by the configured synthesis window and the
 //! target length), and for each length, enumerate candidates and verify
-//! equivalence with SMT.
+//! equivalence with SMT. Length one is enumerated exhaustively, and so is
+//! length two up to `SymbolicConfig::exhaustive_pair_limit` sequences;
+//! beyond that, `SymbolicConfig::sample_size` candidates are sampled as
+//! `SymbolicConfig::sampling` directs, so the search there is not
+//! exhaustive.
 //!
 //! Note: Full symbolic synthesis with symbolic opcodes/operands is very complex.
 //! This implementation uses a hybrid approach: enumerate concrete candidates
 //! and verify them with SMT, rather than synthesizing from purely symbolic sketches.
 
-use crate::isa::ISA;
-use crate::search::config::{SearchConfig, SearchMode};
+use crate::ir::Instruction;
+use crate::isa::{AArch64, ISA};
+use crate::search::config::{Sampling, SearchConfig, SearchMode, SymbolicConfig};
+use crate::search::normalize::{canonicalize, normalize_registers};
 use crate::search::result::{SearchResultFor, SearchStatistics};
 use crate::search::symbolic::backend::SymbolicBackend;
+use crate::search::trace::{opcode_summary, search_event};
 use crate::search::{Algorithm, SearchAlgorithm};
+use crate::semantics::live_out::LiveOut;
+use rand::{RngExt, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::collections::HashSet;
 use std::marker::PhantomData;
 use std::sync::atomic::Ordering;
 use std::time::Instant;
@@ -56,6 +67,110 @@ where
     unbounded_end.min(window_end)
 }
 
+/// Candidate sequences of one length drawn from an instruction pool, for the
+/// lengths `search_at_length` samples rather than enumerates.
+///
+/// Yields at most `sample_size` sequences, and nothing for an empty pool.
+/// `FirstN` walks the enumeration order as an odometer over pool indices, so
+/// it stops early once every sequence has been produced; `Random` draws each
+/// slot independently. A budget that covers every sequence of the length
+/// enumerates them instead, so no sequence is drawn twice.
+struct SequenceSampler<'a, T> {
+    pool: &'a [T],
+    length: usize,
+    remaining: usize,
+    order: SampleOrder,
+}
+
+enum SampleOrder {
+    /// Pool indices of the next sequence; `None` once the odometer wrapped.
+    FirstN(Option<Vec<usize>>),
+    Random(Box<ChaCha8Rng>),
+}
+
+impl<'a, T: Copy> SequenceSampler<'a, T> {
+    fn new(pool: &'a [T], length: usize, config: &SymbolicConfig) -> Self {
+        let sequence_count = u32::try_from(length)
+            .ok()
+            .and_then(|length| pool.len().checked_pow(length));
+        if sequence_count.is_some_and(|count| count <= config.sample_size) {
+            return Self::exhaustive(pool, length);
+        }
+        let order = match config.sampling {
+            Sampling::FirstN => SampleOrder::FirstN(Some(vec![0; length])),
+            Sampling::Random { seed } => {
+                SampleOrder::Random(Box::new(ChaCha8Rng::seed_from_u64(seed)))
+            }
+        };
+        Self {
+            pool,
+            length,
+            remaining: if pool.is_empty() {
+                0
+            } else {
+                config.sample_size
+            },
+            order,
+        }
+    }
+
+    /// Every sequence of `length` over `pool`, in enumeration order.
+    fn exhaustive(pool: &'a [T], length: usize) -> Self {
+        Self {
+            pool,
+            length,
+            remaining: if pool.is_empty() { 0 } else { usize::MAX },
+            order: SampleOrder::FirstN(Some(vec![0; length])),
+        }
+    }
+
+    /// The candidates `search_at_length` tries at `length`: all of them at
+    /// length one, and at length two while the pair count is within
+    /// `exhaustive_pair_limit`; the configured sample otherwise.
+    fn at_length(pool: &'a [T], length: usize, config: &SymbolicConfig) -> Self {
+        let pairs_enumerable = || {
+            config
+                .exhaustive_pair_limit
+                .is_none_or(|limit| pool.len().saturating_mul(pool.len()) <= limit)
+        };
+        if length <= 1 || (length == 2 && pairs_enumerable()) {
+            Self::exhaustive(pool, length)
+        } else {
+            Self::new(pool, length, config)
+        }
+    }
+}
+
+impl<T: Copy> Iterator for SequenceSampler<'_, T> {
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Vec<T>> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let sequence = match &mut self.order {
+            SampleOrder::FirstN(indices) => {
+                let current = indices.take()?;
+                let sequence = current.iter().map(|&i| self.pool[i]).collect();
+                let mut next = current;
+                // Advance the odometer; leave `None` behind once every slot
+                // has wrapped back to zero.
+                if let Some(slot) = next.iter().rposition(|&i| i + 1 < self.pool.len()) {
+                    next[slot] += 1;
+                    next[slot + 1..].fill(0);
+                    *indices = Some(next);
+                }
+                sequence
+            }
+            SampleOrder::Random(rng) => (0..self.length)
+                .map(|_| self.pool[rng.random_range(0..self.pool.len())])
+                .collect(),
+        };
+        self.remaining -= 1;
+        Some(sequence)
+    }
+}
+
 /// Symbolic search using SMT-based synthesis, generic over ISA.
 ///
 /// Routes through `SymbolicBackend<I>` for every ISA-specific operation:
@@ -130,7 +245,8 @@ where
             width,
             start_time,
         };
-        let all_instructions = <I as SymbolicBackend<I>>::enumerate_all(&regs, &imms);
+        let all_instructions =
+            <I as SymbolicBackend<I>>::enumerate_for_config(config, &regs, &imms);
 
         let original_cost =
             <I as SymbolicBackend<I>>::sequence_cost(target, &config.cost_metric, width);
@@ -144,15 +260,15 @@ where
         // both the configured synthesis window and the x86 same-count
         // code-size extension.
         for length in 1..candidate_length_exclusive_end::<I>(target, config) {
-            if config.verbose {
-                println!("Searching for equivalent sequences of length {}...", length);
-            }
+            search_event!(debug, config.verbose; "Searching for equivalent sequences of length {}...", length);
 
             // Check timeout / cooperative-cancel flag.
             if should_stop(config, start_time) {
-                if config.verbose {
-                    println!("Search timed out");
-                }
+                search_event!(info, config.verbose; "Search timed out");
+                break;
+            }
+            if self.candidate_budget_spent(config) {
+                search_event!(info, config.verbose; "Candidate budget exhausted");
                 break;
             }
 
@@ -204,97 +320,32 @@ where
                     CandidateEval::Rejected => {}
                     CandidateEval::Improved { candidate, cost } => {
                         best_at_length = Some(candidate);
-                        if ctx.config.verbose {
-                            println!("Found equivalent: {} (cost {})", instr, cost);
-                        }
-                    }
-                }
-            }
-        } else if length == 2 {
-            // Two instruction search
-            for instr1 in all_instructions {
-                // Check timeout / cooperative-cancel flag periodically.
-                if should_stop(ctx.config, ctx.start_time) {
-                    return best_at_length;
-                }
-
-                for instr2 in all_instructions {
-                    if should_stop(ctx.config, ctx.start_time) {
-                        return best_at_length;
-                    }
-
-                    let candidate = with_term(vec![*instr1, *instr2]);
-                    match self.evaluate_candidate(ctx, candidate, best_cost) {
-                        CandidateEval::Stopped => return best_at_length,
-                        CandidateEval::Rejected => {}
-                        CandidateEval::Improved { candidate, cost } => {
-                            best_at_length = Some(candidate);
-                            if ctx.config.verbose {
-                                println!(
-                                    "Found equivalent: {}; {} (cost {})",
-                                    instr1, instr2, cost
-                                );
-                            }
-                        }
+                        search_event!(info, ctx.config.verbose; "Found equivalent: {} (cost {})", instr, cost);
                     }
                 }
             }
         } else {
-            // For length >= 3, use iterative deepening with early termination
-            // This is a simplified version - full enumeration is exponential
-            let sample_size = 10000; // Limit candidates to sample
-            let mut count = 0;
-
-            for instr1 in all_instructions {
-                if count >= sample_size {
-                    break;
-                }
+            // Full enumeration is exponential in the length, so past the
+            // configured pair limit spend the sample budget instead.
+            let sampler =
+                SequenceSampler::at_length(all_instructions, length, &ctx.config.symbolic);
+            for sequence in sampler {
                 if should_stop(ctx.config, ctx.start_time) {
                     return best_at_length;
                 }
 
-                for instr2 in all_instructions {
-                    if count >= sample_size {
-                        break;
-                    }
-                    if should_stop(ctx.config, ctx.start_time) {
-                        return best_at_length;
-                    }
-
-                    for instr3 in all_instructions {
-                        if count >= sample_size {
-                            break;
-                        }
-                        if should_stop(ctx.config, ctx.start_time) {
-                            return best_at_length;
-                        }
-
-                        let candidate = if length == 3 {
-                            with_term(vec![*instr1, *instr2, *instr3])
+                match self.evaluate_candidate(ctx, with_term(sequence), best_cost) {
+                    CandidateEval::Stopped => return best_at_length,
+                    CandidateEval::Rejected => {}
+                    CandidateEval::Improved { candidate, cost } => {
+                        if length == 2 {
+                            search_event!(info, ctx.config.verbose; "Found equivalent: {}; {} (cost {})",
+                                    candidate[0], candidate[1], cost);
                         } else {
-                            // For longer sequences, fill with first instruction
-                            let mut seq = vec![*instr1, *instr2, *instr3];
-                            while seq.len() < length {
-                                seq.push(all_instructions[0]);
-                            }
-                            with_term(seq)
-                        };
-
-                        match self.evaluate_candidate(ctx, candidate, best_cost) {
-                            CandidateEval::Stopped => return best_at_length,
-                            CandidateEval::Rejected => {}
-                            CandidateEval::Improved { candidate, cost } => {
-                                best_at_length = Some(candidate);
-                                if ctx.config.verbose {
-                                    println!(
-                                        "Found equivalent sequence of length {} (cost {})",
-                                        length, cost
-                                    );
-                                }
-                            }
+                            search_event!(info, ctx.config.verbose; "Found equivalent sequence of length {}: {} (cost {})",
+                                    length, opcode_summary(&candidate), cost);
                         }
-
-                        count += 1;
+                        best_at_length = Some(candidate);
                     }
                 }
             }
@@ -322,12 +373,16 @@ where
             &ctx.config.cost_metric,
             ctx.width,
         );
-        if should_stop(ctx.config, ctx.start_time) {
+        if should_stop(ctx.config, ctx.start_time) || self.candidate_budget_spent(ctx.config) {
             return CandidateEval::Stopped;
         }
 
         self.statistics.candidates_evaluated += 1;
-        if candidate_cost >= *best_cost {
+        if candidate_cost >= *best_cost
+            || ctx
+                .config
+                .exceeds_max_bytes(<I as SymbolicBackend<I>>::encoded_length(&candidate))
+        {
             self.statistics.candidates_pruned_by_cost += 1;
             return CandidateEval::Rejected;
         }
@@ -350,6 +405,13 @@ where
         }
     }
 
+    /// Whether `config.max_candidates` candidates have been evaluated.
+    fn candidate_budget_spent(&self, config: &SearchConfig) -> bool {
+        config
+            .max_candidates
+            .is_some_and(|max| self.statistics.candidates_evaluated >= max)
+    }
+
     /// Verify equivalence using SMT
     fn verify_equivalence(
         &mut self,
@@ -368,7 +430,12 @@ where
         let width = <I as SymbolicBackend<I>>::width();
 
         let (verdict, metrics) = <I as SymbolicBackend<I>>::check_equivalence(
-            target, candidate, live_out, width, timeout,
+            target,
+            candidate,
+            live_out,
+            width,
+            config.prefilter_tests,
+            timeout,
         );
         self.statistics.record_verification(&metrics, &verdict)
     }
@@ -388,6 +455,77 @@ where
     }
 }
 
+impl SymbolicSearch<AArch64> {
+    /// Every equivalent of `target` at the optimal cost this search can
+    /// reach, up to `max_results` of them.
+    ///
+    /// A normal search first fixes the optimal cost. The candidates it
+    /// considers are then walked again, and every one at that cost is
+    /// verified, rather than stopping at the first hit. Candidates that
+    /// differ only by scratch-register renaming or an alternative spelling
+    /// (see [`crate::search::normalize`]) count once. The walk gets its own
+    /// `config.timeout` budget. Returns an empty list when the search finds
+    /// no improvement.
+    pub fn find_all_equivalents(
+        &mut self,
+        target: &[Instruction],
+        live_out: &LiveOut,
+        config: &SearchConfig,
+        max_results: usize,
+    ) -> Vec<Vec<Instruction>> {
+        if max_results == 0 {
+            return Vec::new();
+        }
+        let Some(best) = self.search(target, live_out, config).optimized_sequence else {
+            return Vec::new();
+        };
+
+        let width = <AArch64 as SymbolicBackend<AArch64>>::width();
+        // Price candidates with the live-out bound, as `search` did.
+        let metric = config.cost_metric.with_live_out(live_out);
+        let optimal_cost =
+            <AArch64 as SymbolicBackend<AArch64>>::sequence_cost(&best, &metric, width);
+        let regs = <AArch64 as SymbolicBackend<AArch64>>::registers_from_config(config);
+        let imms = <AArch64 as SymbolicBackend<AArch64>>::immediates_from_config(config);
+        let all_instructions =
+            <AArch64 as SymbolicBackend<AArch64>>::enumerate_for_config(config, &regs, &imms);
+        let terminator = <AArch64 as SymbolicBackend<AArch64>>::target_terminator(target);
+        let key = |seq: &[Instruction]| canonicalize(&normalize_registers(seq, live_out));
+
+        let mut seen = HashSet::from([key(&best)]);
+        let mut found = vec![best];
+        let start_time = Instant::now();
+        for length in 1..candidate_length_exclusive_end::<AArch64>(target, config) {
+            for mut candidate in
+                SequenceSampler::at_length(&all_instructions, length, &config.symbolic)
+            {
+                if found.len() >= max_results || should_stop(config, start_time) {
+                    return found;
+                }
+                candidate.extend(terminator);
+                let cost = <AArch64 as SymbolicBackend<AArch64>>::sequence_cost(
+                    &candidate, &metric, width,
+                );
+                // Renamings of a refuted candidate are refuted too, so the
+                // key is claimed before verifying.
+                if cost != optimal_cost
+                    || config.exceeds_max_bytes(
+                        <AArch64 as SymbolicBackend<AArch64>>::encoded_length(&candidate),
+                    )
+                    || !seen.insert(key(&candidate))
+                {
+                    continue;
+                }
+                self.statistics.candidates_evaluated += 1;
+                if self.verify_equivalence(target, &candidate, live_out, config, start_time) {
+                    found.push(candidate);
+                }
+            }
+        }
+        found
+    }
+}
+
 impl<I> Default for SymbolicSearch<I> {
     fn default() -> Self {
         Self::new()
@@ -410,11 +548,18 @@ where
         self.reset();
         let start_time = Instant::now();
         let width = <I as SymbolicBackend<I>>::width();
+        let config = &config
+            .clone()
+            .with_cost_metric(<I as SymbolicBackend<I>>::bind_live_out(
+                config.cost_metric,
+                live_out,
+            ));
 
         let original_cost =
             <I as SymbolicBackend<I>>::sequence_cost(target, &config.cost_metric, width);
         self.statistics.original_cost = original_cost;
         self.statistics.best_cost_found = original_cost;
+        self.statistics.cost_metric = config.cost_metric;
 
         if target.is_empty() || candidate_length_exclusive_end::<I>(target, config) <= 1 {
             self.statistics.elapsed_time = start_time.elapsed();
@@ -434,7 +579,12 @@ where
         if let Some(optimized) = result {
             self.statistics.best_cost_found =
                 <I as SymbolicBackend<I>>::sequence_cost(&optimized, &config.cost_metric, width);
-            SearchResultFor::with_optimization(target.to_vec(), optimized, self.statistics.clone())
+            SearchResultFor::with_optimization(
+                target.to_vec(),
+                optimized,
+                self.statistics.clone(),
+                |seq| <I as SymbolicBackend<I>>::sequence_cost(seq, &config.cost_metric, width),
+            )
         } else {
             SearchResultFor::no_optimization(target.to_vec(), self.statistics.clone())
         }
@@ -452,13 +602,13 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::ir::{Instruction, Operand, Register};
+    use crate::ir::{Instruction, Operand, Register, RegisterWidth};
     use crate::isa::{AArch64, ISA, ISAMutator, InstructionType, OperandType, RegisterType, U64};
     use crate::search::config::SymbolicConfig;
     use crate::semantics::cost::CostMetric;
     use crate::semantics::live_out::LiveOut;
-    use crate::semantics::state::ConcreteMachineState;
-    use crate::semantics::{EquivalenceMetrics, EquivalenceResult};
+    use crate::semantics::{EquivalenceMetrics, EquivalenceResult, FastCounterexample};
+    use crate::test_utils::sequence_fixtures::{mov_add_sequence, mov_zero_sequence, x0_live};
     use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
     use std::sync::{Arc, Mutex};
     use std::time::Duration;
@@ -678,6 +828,7 @@ mod tests {
             _proposal: &[TestInstruction],
             _live_out: &Self::LiveOut,
             _width: u32,
+            _random_tests: usize,
             timeout: Duration,
         ) -> (EquivalenceResult, EquivalenceMetrics) {
             let check_number = TEST_EQUIVALENCE_CHECKS.fetch_add(1, Ordering::SeqCst) + 1;
@@ -698,7 +849,7 @@ mod tests {
             }
             if TEST_EQUIVALENCE_FAST_FAILURE.load(Ordering::SeqCst) {
                 return (
-                    EquivalenceResult::NotEquivalentFast(ConcreteMachineState::new_zeroed()),
+                    EquivalenceResult::NotEquivalentFast(FastCounterexample::structural()),
                     metrics,
                 );
             }
@@ -710,27 +861,6 @@ mod tests {
         }
     }
 
-    fn mov_add_sequence() -> Vec<Instruction> {
-        vec![
-            Instruction::MovReg {
-                rd: Register::X0,
-                rn: Register::X1,
-            },
-            Instruction::Add {
-                rd: Register::X0,
-                rn: Register::X0,
-                rm: Operand::Immediate(1),
-            },
-        ]
-    }
-
-    fn mov_zero_sequence() -> Vec<Instruction> {
-        vec![Instruction::MovImm {
-            rd: Register::X0,
-            imm: 0,
-        }]
-    }
-
     #[test]
     fn test_symbolic_search_creation() {
         let search: SymbolicSearch<AArch64> = SymbolicSearch::new();
@@ -742,7 +872,7 @@ mod tests {
     fn test_symbolic_search_empty_sequence() {
         let mut search: SymbolicSearch<AArch64> = SymbolicSearch::new();
         let config = SearchConfig::default();
-        let live_out = LiveOut::from_registers(vec![Register::X0]);
+        let live_out = x0_live();
 
         let result = search.search(&[], &live_out, &config);
         assert!(!result.found_optimization);
@@ -752,7 +882,7 @@ mod tests {
     fn test_symbolic_search_single_instruction() {
         let mut search: SymbolicSearch<AArch64> = SymbolicSearch::new();
         let config = SearchConfig::default();
-        let live_out = LiveOut::from_registers(vec![Register::X0]);
+        let live_out = x0_live();
 
         // Single instruction can't be optimized to shorter
         let result = search.search(&mov_zero_sequence(), &live_out, &config);
@@ -763,12 +893,9 @@ mod tests {
     fn test_symbolic_finds_mov_add_fusion() {
         let mut search: SymbolicSearch<AArch64> = SymbolicSearch::new();
 
-        let config = SearchConfig::default()
-            .with_solver_timeout(Duration::from_secs(10))
-            .with_registers(vec![Register::X0, Register::X1, Register::X2])
-            .with_immediates(vec![-1, 0, 1, 2]);
+        let config = SearchConfig::minimal().with_solver_timeout(Duration::from_secs(10));
 
-        let live_out = LiveOut::from_registers(vec![Register::X0]);
+        let live_out = x0_live();
 
         // Target: MOV X0, X1; ADD X0, X0, #1 (2 instructions)
         // Should find an equivalent 1-instruction sequence (e.g., ADD X0, X1, #1)
@@ -786,17 +913,79 @@ mod tests {
         }
     }
 
+    #[test]
+    fn default_config_finds_mov_add_fusion_without_overrides() {
+        let live_out = x0_live();
+        let result = SymbolicSearch::<AArch64>::new().search(
+            &mov_add_sequence(),
+            &live_out,
+            &SearchConfig::default(),
+        );
+
+        assert!(result.found_optimization);
+        assert_eq!(
+            result.optimized_sequence,
+            Some(vec![Instruction::Add {
+                rd: Register::X0,
+                rn: Register::X1,
+                rm: Operand::Immediate(1),
+            }])
+        );
+    }
+
+    #[test]
+    fn symbolic_reports_only_encodable_rewrites_unless_allowed() {
+        // x0 &= 5 through a scratch register. `and x0, x0, #5` would fuse it,
+        // but 0b101 is not a logical immediate.
+        let target = vec![
+            Instruction::MovImm {
+                rd: Register::X1,
+                imm: 5,
+            },
+            Instruction::And {
+                rd: Register::X0,
+                rn: Register::X0,
+                rm: Operand::Register(Register::X1),
+                width: RegisterWidth::X64,
+            },
+        ];
+        let live_out = x0_live();
+        let config = SearchConfig::default()
+            .with_solver_timeout(Duration::from_secs(10))
+            .with_registers(vec![Register::X0, Register::X1])
+            .with_immediates(vec![0, 1, 5]);
+
+        // The fused `and` is the only shorter rewrite, so the default
+        // encodable pool finds nothing.
+        let result = SymbolicSearch::<AArch64>::new().search(&target, &live_out, &config);
+        assert!(!result.found_optimization);
+        assert_eq!(result.optimized_sequence, None);
+
+        let result = SymbolicSearch::<AArch64>::new().search(
+            &target,
+            &live_out,
+            &config.with_allow_unencodable(true),
+        );
+        assert_eq!(
+            result.optimized_sequence,
+            Some(vec![Instruction::And {
+                rd: Register::X0,
+                rn: Register::X0,
+                rm: Operand::Immediate(5),
+                width: RegisterWidth::X64,
+            }])
+        );
+    }
+
     #[test]
     fn symbolic_cost_bound_zero_prevents_known_mov_add_rewrite() {
         let mut search: SymbolicSearch<AArch64> = SymbolicSearch::new();
 
-        let config = SearchConfig::default()
+        let config = SearchConfig::minimal()
             .with_symbolic(SymbolicConfig::default().with_cost_bound(0))
-            .with_solver_timeout(Duration::from_secs(10))
-            .with_registers(vec![Register::X0, Register::X1, Register::X2])
-            .with_immediates(vec![-1, 0, 1, 2]);
+            .with_solver_timeout(Duration::from_secs(10));
 
-        let live_out = LiveOut::from_registers(vec![Register::X0]);
+        let live_out = x0_live();
         let target = mov_add_sequence();
 
         let result = search.search(&target, &live_out, &config);
@@ -963,7 +1152,7 @@ mod tests {
             .with_symbolic(SymbolicConfig::default())
             .with_registers(vec![Register::X0, Register::X1]);
 
-        let live_out = LiveOut::from_registers(vec![Register::X0]);
+        let live_out = x0_live();
         let target = mov_add_sequence();
 
         let result = search.search(&target, &live_out, &config);
@@ -983,7 +1172,7 @@ mod tests {
             .with_immediates(vec![0, 1]);
 
         // Only X0 is live-out, X1 can differ
-        let live_out = LiveOut::from_registers(vec![Register::X0]);
+        let live_out = x0_live();
 
         // Target modifies both X0 and X1
         let target = vec![
@@ -1035,7 +1224,7 @@ mod tests {
                 .with_immediates(vec![
                     0, 1, 2, 3, 4, 5, 7, 8, 10, 15, 16, 31, 32, 63, 64, 100, 255, 256, 1000, 4095,
                 ]);
-            let live_out = LiveOut::from_registers(vec![Register::X0]);
+            let live_out = x0_live();
             let target = mov_add_sequence();
             search.search(&target, &live_out, &config)
         });
@@ -1402,7 +1591,7 @@ mod tests {
     fn test_verify_equivalence() {
         let mut search: SymbolicSearch<AArch64> = SymbolicSearch::new();
         let config = SearchConfig::default();
-        let live_out = LiveOut::from_registers(vec![Register::X0]);
+        let live_out = x0_live();
 
         // These should be equivalent
         let target = vec![Instruction::MovImm {
@@ -1429,7 +1618,7 @@ mod tests {
     fn test_verify_non_equivalence() {
         let mut search: SymbolicSearch<AArch64> = SymbolicSearch::new();
         let config = SearchConfig::default();
-        let live_out = LiveOut::from_registers(vec![Register::X0]);
+        let live_out = x0_live();
 
         // These should NOT be equivalent
         let target = vec![Instruction::MovImm {
@@ -1997,4 +2186,246 @@ mod tests {
         assert_eq!(best_cost, 5);
         assert_eq!(TEST_EQUIVALENCE_CHECKS.load(Ordering::SeqCst), 0);
     }
+
+    #[test]
+    fn random_sampling_is_reproducible_and_varies_every_slot() {
+        let pool: Vec<u32> = (0..20).collect();
+        let config = SymbolicConfig::default()
+            .with_sample_size(200)
+            .with_sampling(Sampling::Random { seed: 649 });
+
+        let first: Vec<_> = SequenceSampler::new(&pool, 5, &config).collect();
+        let second: Vec<_> = SequenceSampler::new(&pool, 5, &config).collect();
+        assert_eq!(first.len(), 200);
+        assert_eq!(first, second);
+
+        for slot in 3..5 {
+            let values: std::collections::HashSet<_> = first.iter().map(|seq| seq[slot]).collect();
+            assert!(values.len() > 1, "slot {slot} always holds {values:?}");
+        }
+
+        let reseeded: Vec<_> = SequenceSampler::new(
+            &pool,
+            5,
+            &config.clone().with_sampling(Sampling::Random { seed: 650 }),
+        )
+        .collect();
+        assert_ne!(first, reseeded);
+    }
+
+    #[test]
+    fn first_n_sampling_walks_enumeration_order_and_stops_when_exhausted() {
+        let pool = [1, 2];
+        let config = SymbolicConfig::default().with_sample_size(3);
+        let sampled: Vec<_> = SequenceSampler::new(&pool, 3, &config).collect();
+        assert_eq!(sampled, vec![vec![1, 1, 1], vec![1, 1, 2], vec![1, 2, 1]]);
+
+        let config = SymbolicConfig::default().with_sample_size(100);
+        let sampled: Vec<_> = SequenceSampler::new(&pool, 3, &config).collect();
+        assert_eq!(sampled.len(), 8);
+        assert_eq!(sampled.last(), Some(&vec![2, 2, 2]));
+
+        assert_eq!(SequenceSampler::<u32>::new(&[], 3, &config).count(), 0);
+    }
+
+    #[test]
+    fn pair_limit_switches_length_two_to_sampling() {
+        let pool: Vec<u32> = (0..1000).collect();
+        let exhaustive = SymbolicConfig::default().with_sample_size(5_000);
+        assert_eq!(
+            SequenceSampler::at_length(&pool, 2, &exhaustive).count(),
+            1_000_000
+        );
+        // The default limit stops there: one more instruction samples.
+        let wider: Vec<u32> = (0..1001).collect();
+        assert_eq!(
+            SequenceSampler::at_length(&wider, 2, &exhaustive).count(),
+            5_000
+        );
+        // Small pools stay exhaustive under the limit.
+        let limited = exhaustive
+            .clone()
+            .with_exhaustive_pair_limit(10_000)
+            .with_sampling(Sampling::Random { seed: 663 });
+        assert_eq!(
+            SequenceSampler::at_length(&pool[..100], 2, &limited).count(),
+            10_000
+        );
+
+        // Past it the sample budget bounds the pairs tried, and an optimum
+        // planted as every pair ending in 7 (one in a thousand) is still hit.
+        let sampled: Vec<_> = SequenceSampler::at_length(&pool, 2, &limited).collect();
+        assert_eq!(sampled.len(), 5_000);
+        assert!(sampled.iter().any(|pair| pair[1] == 7));
+    }
+
+    #[test]
+    fn sampled_length_two_tries_each_pair_once() {
+        let _guard = SYMBOLIC_INNER_LOOP_TEST_LOCK
+            .lock()
+            .expect("symbolic inner-loop test lock poisoned");
+        reset_symbolic_inner_loop_test_state();
+        // Check 1 is the length-one candidate; the only pair is the planted
+        // optimum.
+        TEST_EQUIVALENCE_EQUIVALENT_ON_CHECK.store(2, Ordering::SeqCst);
+
+        let mut search: SymbolicSearch<TestIsa> = SymbolicSearch::new();
+        let config = SearchConfig::default().with_symbolic(
+            SymbolicConfig::default()
+                .with_sample_size(5)
+                .with_sampling(Sampling::Random { seed: 663 })
+                .with_exhaustive_pair_limit(0),
+        );
+        let target = [
+            TestInstruction(100),
+            TestInstruction(101),
+            TestInstruction(102),
+        ];
+
+        let result = search.search(&target, &(), &config);
+
+        assert!(result.found_optimization);
+        assert_eq!(result.statistics.best_cost_found, 2);
+        assert_eq!(TEST_EQUIVALENCE_CHECKS.load(Ordering::SeqCst), 2);
+        // The one-instruction pool has a single pair, so the budget of 5
+        // shrinks to it rather than drawing that pair five times.
+        assert_eq!(result.statistics.candidates_evaluated, 1 + 1);
+    }
+
+    #[test]
+    fn max_candidates_stops_with_the_best_so_far() {
+        // mov x0, x1; add x0, x0, #1
+        let target = vec![
+            Instruction::MovReg {
+                rd: Register::X0,
+                rn: Register::X1,
+            },
+            Instruction::Add {
+                rd: Register::X0,
+                rn: Register::X0,
+                rm: Operand::Immediate(1),
+            },
+        ];
+        let live_out = x0_live();
+        let config = SearchConfig::default()
+            .with_solver_timeout(Duration::from_secs(5))
+            .with_registers(vec![Register::X0, Register::X1])
+            .with_immediates(vec![0, 1]);
+
+        let mut unbounded = SymbolicSearch::<AArch64>::new();
+        let full = unbounded.search(&target, &live_out, &config);
+        assert!(full.statistics.candidates_evaluated > 1_000);
+
+        // `add x0, x1, #1` comes up within the first thousand candidates.
+        let mut capped = SymbolicSearch::<AArch64>::new();
+        let result = capped.search(
+            &target,
+            &live_out,
+            &config.clone().with_max_candidates(1_000),
+        );
+        assert_eq!(result.statistics.candidates_evaluated, 1_000);
+        assert!(result.found_optimization);
+        let optimized = result
+            .optimized_sequence
+            .expect("the capped run keeps the rewrite it found");
+        assert_eq!(
+            optimized,
+            vec![Instruction::Add {
+                rd: Register::X0,
+                rn: Register::X1,
+                rm: Operand::Immediate(1),
+            }]
+        );
+        assert_eq!(result.statistics.best_cost_found, 1);
+    }
+
+    #[test]
+    fn more_prefilter_tests_mean_fewer_smt_queries() {
+        // lsr x0, x0, #18; and x0, x0, #1 extracts bit 18, which every
+        // edge-case input agrees with bit 16 on, so `ubfx x0, x0, #16, #1`
+        // survives the edge cases and only a random test or the solver
+        // refutes it.
+        let target = vec![
+            Instruction::Lsr {
+                rd: Register::X0,
+                rn: Register::X0,
+                shift: Operand::Immediate(18),
+            },
+            Instruction::And {
+                rd: Register::X0,
+                rn: Register::X0,
+                rm: Operand::Immediate(1),
+                width: RegisterWidth::X64,
+            },
+        ];
+        let live_out = x0_live();
+        let smt_queries = |prefilter_tests| {
+            let config = SearchConfig::default()
+                .with_solver_timeout(Duration::from_secs(5))
+                .with_registers(vec![Register::X0])
+                .with_immediates(vec![1])
+                .with_prefilter_tests(prefilter_tests);
+            let mut search = SymbolicSearch::<AArch64>::new();
+            let result = search.search(&target, &live_out, &config);
+            assert!(!result.found_optimization);
+            result.statistics.smt_queries
+        };
+
+        let without_prefilter = smt_queries(0);
+        let with_prefilter = smt_queries(16);
+        assert!(
+            with_prefilter < without_prefilter,
+            "{with_prefilter} SMT queries with the prefilter, {without_prefilter} without"
+        );
+    }
+
+    #[test]
+    fn find_all_equivalents_collects_distinct_single_instruction_fusions() {
+        // mov x0, x1; add x0, x0, #1
+        let target = vec![
+            Instruction::MovReg {
+                rd: Register::X0,
+                rn: Register::X1,
+            },
+            Instruction::Add {
+                rd: Register::X0,
+                rn: Register::X0,
+                rm: Operand::Immediate(1),
+            },
+        ];
+        let config = SearchConfig::default()
+            .with_solver_timeout(Duration::from_secs(5))
+            .with_registers(vec![Register::X0, Register::X1])
+            .with_immediates(vec![0, 1]);
+        let live_out = x0_live();
+
+        let mut search = SymbolicSearch::<AArch64>::new();
+        let found = search.find_all_equivalents(&target, &live_out, &config, 16);
+        // `adds x0, x1, #1` qualifies too: the flags are not live-out.
+        assert!(found.contains(&vec![Instruction::Add {
+            rd: Register::X0,
+            rn: Register::X1,
+            rm: Operand::Immediate(1),
+        }]));
+        assert!(found.contains(&vec![Instruction::Adds {
+            rd: Register::X0,
+            rn: Register::X1,
+            rm: Operand::Immediate(1),
+        }]));
+        assert!(found.iter().all(|seq| seq.len() == 1));
+
+        let keys: HashSet<_> = found
+            .iter()
+            .map(|seq| canonicalize(&normalize_registers(seq, &live_out)))
+            .collect();
+        assert_eq!(keys.len(), found.len(), "duplicates in {found:?}");
+
+        let capped = search.find_all_equivalents(&target, &live_out, &config, 1);
+        assert_eq!(capped.len(), 1);
+        assert!(
+            search
+                .find_all_equivalents(&target, &live_out, &config, 0)
+                .is_empty()
+        );
+    }
 }
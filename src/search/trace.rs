@@ -0,0 +1,141 @@
+//! Search milestone reporting.
+//!
+//! Backends report milestones (a new best candidate, a timeout, a worker
+//! finishing) through [`search_event!`]. Without the `logging` feature an
+//! event is an `eprintln!` gated on the caller's `verbose` flag, which is the
+//! CLI's `--verbose` output. With the feature it becomes a `log` record under
+//! the `s11::search` target at the given level, and the embedder's logger
+//! decides what to keep; the `verbose` flag is then ignored. Until a logger
+//! enables some level (`log::max_level()` is still `Off`, as in the CLI) the
+//! feature build falls back to the `--verbose` `eprintln!`.
+//!
+//! The log-only form, `search_event!(level; ...)`, is for events that have no
+//! `--verbose` line and are silent without the feature.
+
+/// Emit a search milestone; see the module docs for the two forms.
+macro_rules! search_event {
+    ($level:ident, $verbose:expr; $($arg:tt)+) => {{
+        #[cfg(feature = "logging")]
+        if ::log::max_level() != ::log::LevelFilter::Off {
+            ::log::$level!(target: "s11::search", $($arg)+);
+        } else if $verbose {
+            eprintln!($($arg)+);
+        }
+        #[cfg(not(feature = "logging"))]
+        if $verbose {
+            eprintln!($($arg)+);
+        }
+    }};
+    ($level:ident; $($arg:tt)+) => {{
+        #[cfg(feature = "logging")]
+        ::log::$level!(target: "s11::search", $($arg)+);
+        #[cfg(not(feature = "logging"))]
+        {
+            let _ = format_args!($($arg)+);
+        }
+    }};
+}
+
+pub(crate) use search_event;
+
+/// The opcodes of `seq` for a milestone line, with condition codes
+/// (`csel.eq, add`), so candidates that differ only in a condition read
+/// apart.
+pub(crate) fn opcode_summary<T: crate::isa::InstructionType>(seq: &[T]) -> String {
+    seq.iter()
+        .map(T::suffixed_mnemonic)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(all(test, feature = "logging"))]
+mod tests {
+    use crate::ir::{Condition, Instruction, Operand, Register};
+    use crate::search::config::{Algorithm, SearchConfig};
+    use crate::semantics::live_out::LiveOut;
+    use log::{Level, Log, Metadata, Record};
+    use std::sync::{Mutex, Once};
+
+    /// Logger that keeps every `s11::search` record for inspection. Tests
+    /// share it because `log` accepts a single global logger per process.
+    struct Capture(Mutex<Vec<(Level, String)>>);
+
+    impl Log for Capture {
+        fn enabled(&self, metadata: &Metadata) -> bool {
+            metadata.target() == "s11::search"
+        }
+
+        fn log(&self, record: &Record) {
+            if self.enabled(record.metadata()) {
+                self.0
+                    .lock()
+                    .unwrap()
+                    .push((record.level(), record.args().to_string()));
+            }
+        }
+
+        fn flush(&self) {}
+    }
+
+    static CAPTURE: Capture = Capture(Mutex::new(Vec::new()));
+    static INSTALL: Once = Once::new();
+
+    fn install_capture() {
+        INSTALL.call_once(|| {
+            log::set_logger(&CAPTURE).expect("no other logger installed in tests");
+            log::set_max_level(log::LevelFilter::Trace);
+        });
+    }
+
+    #[test]
+    fn mov_add_fusion_emits_an_improvement_event() {
+        install_capture();
+        // mov x0, x1; add x0, x0, #1  ==>  add x0, x1, #1
+        let target = [
+            Instruction::MovReg {
+                rd: Register::X0,
+                rn: Register::X1,
+            },
+            Instruction::Add {
+                rd: Register::X0,
+                rn: Register::X0,
+                rm: Operand::Immediate(1),
+            },
+        ];
+        let config = SearchConfig::default()
+            .with_algorithm(Algorithm::Symbolic)
+            .with_registers(vec![Register::X0, Register::X1])
+            .with_immediates(vec![0, 1]);
+        let live_out = LiveOut::from_registers(vec![Register::X0]);
+
+        let result = crate::search::optimize_sequence(&target, &live_out, &config);
+        assert!(result.found_optimization);
+
+        let records = CAPTURE.0.lock().unwrap();
+        assert!(
+            records.iter().any(|(level, message)| *level == Level::Info
+                && message.starts_with("Found equivalent: add x0, x1, #1")),
+            "no improvement event in {records:?}"
+        );
+    }
+
+    #[test]
+    fn opcode_summary_keeps_condition_codes() {
+        let csel = |cond| Instruction::Csel {
+            rd: Register::X0,
+            rn: Register::X1,
+            rm: Register::X2,
+            cond,
+        };
+        let add = Instruction::Add {
+            rd: Register::X0,
+            rn: Register::X0,
+            rm: Operand::Immediate(1),
+        };
+
+        assert_eq!(
+            super::opcode_summary(&[csel(Condition::EQ), csel(Condition::NE), add]),
+            "csel.eq, csel.ne, add"
+        );
+    }
+}
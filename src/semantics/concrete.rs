@@ -4,7 +4,7 @@ use crate::ir::types::{AccessWidth, AddressOperand, IndexMode};
 use crate::ir::{
     Condition, Instruction, Operand, Register, RegisterWidth, ShiftKind, VectorArrangement,
 };
-use crate::semantics::live_out::RegisterSet;
+use crate::semantics::live_out::{MemLiveOut, RegisterSet};
 use crate::semantics::state::{ConcreteMachineState, ConcreteValue, ConditionFlags};
 
 /// Evaluate an operand to get its concrete value
@@ -48,7 +48,7 @@ fn mask_for_register_width(width: RegisterWidth) -> u64 {
 }
 
 fn set_w_register(state: &mut ConcreteMachineState, reg: Register, value: u64) {
-    state.set_register(reg, ConcreteValue::new(value & u32::MAX as u64));
+    state.set_register(reg, ConcreteValue::from_u32(value as u32));
 }
 
 // Store a bit-field op's 64-bit result into `rd`, honouring the register width.
@@ -68,10 +68,10 @@ fn store_bitfield_result(
 
 fn eval_w_operand(state: &ConcreteMachineState, operand: &Operand) -> u64 {
     match operand {
-        Operand::Register(reg) => state.get_register(*reg).as_u64() & u32::MAX as u64,
+        Operand::Register(reg) => u64::from(state.get_register(*reg).as_u32()),
         Operand::Immediate(imm) => *imm as u64 & u32::MAX as u64,
         Operand::ShiftedRegister { reg, kind, amount } => {
-            let value = state.get_register(*reg).as_u64() as u32;
+            let value = state.get_register(*reg).as_u32();
             match kind {
                 ShiftKind::Lsl => value.wrapping_shl(u32::from(*amount)) as u64,
                 ShiftKind::Lsr => value.wrapping_shr(u32::from(*amount)) as u64,
@@ -171,7 +171,7 @@ pub fn apply_instruction_concrete(
             state.set_register(*rd, ConcreteValue::new(result));
         }
         Instruction::AddW { rd, rn, rm } => {
-            let lhs = state.get_register(*rn).as_u64() & u32::MAX as u64;
+            let lhs = u64::from(state.get_register(*rn).as_u32());
             let rhs = eval_w_operand(&state, rm);
             set_w_register(&mut state, *rd, lhs.wrapping_add(rhs));
         }
@@ -182,7 +182,7 @@ pub fn apply_instruction_concrete(
             state.set_register(*rd, ConcreteValue::new(result));
         }
         Instruction::SubW { rd, rn, rm } => {
-            let lhs = state.get_register(*rn).as_u64() & u32::MAX as u64;
+            let lhs = u64::from(state.get_register(*rn).as_u32());
             let rhs = eval_w_operand(&state, rm);
             set_w_register(&mut state, *rd, lhs.wrapping_sub(rhs));
         }
@@ -281,16 +281,16 @@ pub fn apply_instruction_concrete(
         }
         // CMP: Compare (subtract and set flags, discard result)
         Instruction::Cmp { rn, rm } => {
-            let lhs = state.get_register(*rn).as_u64();
-            let rhs = eval_operand(&state, rm).as_u64();
-            let flags = ConditionFlags::from_sub(lhs, rhs, lhs.wrapping_sub(rhs));
+            let (_, flags) = state
+                .get_register(*rn)
+                .sub_with_flags(eval_operand(&state, rm));
             state.set_flags(flags);
         }
         // CMN: Compare negative (add and set flags, discard result)
         Instruction::Cmn { rn, rm } => {
-            let lhs = state.get_register(*rn).as_u64();
-            let rhs = eval_operand(&state, rm).as_u64();
-            let flags = ConditionFlags::from_add(lhs, rhs, lhs.wrapping_add(rhs));
+            let (_, flags) = state
+                .get_register(*rn)
+                .add_with_flags(eval_operand(&state, rm));
             state.set_flags(flags);
         }
         // TST: Test (AND and set flags, discard result)
@@ -305,9 +305,10 @@ pub fn apply_instruction_concrete(
         // NZCV to the 4-bit immediate.
         Instruction::Ccmp { rn, rm, nzcv, cond } => {
             let flags = if evaluate_condition(&state, *cond) {
-                let lhs = state.get_register(*rn).as_u64();
-                let rhs = eval_operand(&state, rm).as_u64();
-                ConditionFlags::from_sub(lhs, rhs, lhs.wrapping_sub(rhs))
+                let (_, flags) = state
+                    .get_register(*rn)
+                    .sub_with_flags(eval_operand(&state, rm));
+                flags
             } else {
                 unpack_nzcv(*nzcv)
             };
@@ -317,9 +318,10 @@ pub fn apply_instruction_concrete(
         // addition for the true branch.
         Instruction::Ccmn { rn, rm, nzcv, cond } => {
             let flags = if evaluate_condition(&state, *cond) {
-                let lhs = state.get_register(*rn).as_u64();
-                let rhs = eval_operand(&state, rm).as_u64();
-                ConditionFlags::from_add(lhs, rhs, lhs.wrapping_add(rhs))
+                let (_, flags) = state
+                    .get_register(*rn)
+                    .add_with_flags(eval_operand(&state, rm));
+                flags
             } else {
                 unpack_nzcv(*nzcv)
             };
@@ -380,10 +382,8 @@ pub fn apply_instruction_concrete(
         }
         // NEGS: NEG with flag side-effect, same as `SUBS rd, XZR, rm`
         Instruction::Negs { rd, rm } => {
-            let rhs = state.get_register(*rm).as_u64();
-            let result = 0_u64.wrapping_sub(rhs);
-            state.set_register(*rd, ConcreteValue::new(result));
-            let flags = ConditionFlags::from_sub(0, rhs, result);
+            let (result, flags) = ConcreteValue::new(0).sub_with_flags(state.get_register(*rm));
+            state.set_register(*rd, result);
             state.set_flags(flags);
         }
         // MOVN: rd = !((imm as u64) << shift)
@@ -431,18 +431,18 @@ pub fn apply_instruction_concrete(
         }
         // Flag-setting arithmetic / logical
         Instruction::Adds { rd, rn, rm } => {
-            let lhs = state.get_register(*rn).as_u64();
-            let rhs = eval_operand(&state, rm).as_u64();
-            let result = lhs.wrapping_add(rhs);
-            state.set_register(*rd, ConcreteValue::new(result));
-            state.set_flags(ConditionFlags::from_add(lhs, rhs, result));
+            let (result, flags) = state
+                .get_register(*rn)
+                .add_with_flags(eval_operand(&state, rm));
+            state.set_register(*rd, result);
+            state.set_flags(flags);
         }
         Instruction::Subs { rd, rn, rm } => {
-            let lhs = state.get_register(*rn).as_u64();
-            let rhs = eval_operand(&state, rm).as_u64();
-            let result = lhs.wrapping_sub(rhs);
-            state.set_register(*rd, ConcreteValue::new(result));
-            state.set_flags(ConditionFlags::from_sub(lhs, rhs, result));
+            let (result, flags) = state
+                .get_register(*rn)
+                .sub_with_flags(eval_operand(&state, rm));
+            state.set_register(*rd, result);
+            state.set_flags(flags);
         }
         // Add with carry: rd = rn + rm + C. Adc leaves flags untouched;
         // Adcs writes NZCV.
@@ -738,7 +738,7 @@ pub fn apply_instruction_concrete(
         Instruction::Str { rt, addr, width } => {
             let (effective, writeback) = compute_address(&state, addr);
             let value = state.get_register(*rt).as_u64();
-            state.write_bytes(effective, value, *width);
+            state.store_bytes(effective, value, *width);
             if let Some((base, new_base)) = writeback {
                 state.set_register(base, ConcreteValue::new(new_base));
             }
@@ -783,8 +783,8 @@ pub fn apply_instruction_concrete(
             let bytes = width.bytes() as u64;
             let v1 = state.get_register(*rt1).as_u64();
             let v2 = state.get_register(*rt2).as_u64();
-            state.write_bytes(effective, v1, access_width);
-            state.write_bytes(effective.wrapping_add(bytes), v2, access_width);
+            state.store_bytes(effective, v1, access_width);
+            state.store_bytes(effective.wrapping_add(bytes), v2, access_width);
             if let Some((base, new_base)) = writeback {
                 state.set_register(base, ConcreteValue::new(new_base));
             }
@@ -876,25 +876,7 @@ fn unpack_nzcv(byte: u8) -> ConditionFlags {
 }
 
 fn evaluate_condition(state: &ConcreteMachineState, cond: Condition) -> bool {
-    let flags = state.get_flags();
-    match cond {
-        Condition::EQ => flags.z,                          // Equal (Z=1)
-        Condition::NE => !flags.z,                         // Not equal (Z=0)
-        Condition::CS => flags.c,                          // Carry set (C=1)
-        Condition::CC => !flags.c,                         // Carry clear (C=0)
-        Condition::MI => flags.n,                          // Minus/negative (N=1)
-        Condition::PL => !flags.n,                         // Plus/positive or zero (N=0)
-        Condition::VS => flags.v,                          // Overflow (V=1)
-        Condition::VC => !flags.v,                         // No overflow (V=0)
-        Condition::HI => flags.c && !flags.z,              // Unsigned higher (C=1 && Z=0)
-        Condition::LS => !flags.c || flags.z,              // Unsigned lower or same (C=0 || Z=1)
-        Condition::GE => flags.n == flags.v,               // Signed greater or equal (N=V)
-        Condition::LT => flags.n != flags.v,               // Signed less than (N!=V)
-        Condition::GT => !flags.z && (flags.n == flags.v), // Signed greater than (Z=0 && N=V)
-        Condition::LE => flags.z || (flags.n != flags.v),  // Signed less or equal (Z=1 || N!=V)
-        Condition::AL => true,                             // Always
-        Condition::NV => true, // Never (but executes as always on AArch64)
-    }
+    state.get_flags().evaluate(cond)
 }
 
 /// Apply a sequence of instructions to a concrete machine state
@@ -910,18 +892,18 @@ pub fn apply_sequence_concrete(
 
 /// Check if two concrete states are equal for the specified live-out contract,
 /// including the NZCV condition flags when `live_out.flags_live()` is set and
-/// the whole memory map when `memory_live` is set.
+/// the memory `memory_live` selects.
 ///
-/// `memory_live` is derived automatically by callers from whether either
-/// sequence touches memory (see ADR-0007). When set, every memory cell
-/// must agree between the two states — equivalently, the two `BTreeMap`s
-/// must be structurally equal (prune-on-write guarantees structural ==
-/// semantic equality).
+/// Equivalence checking derives `memory_live` from whether either sequence
+/// touches memory (see ADR-0007) and then compares all of it. Every memory
+/// cell in the selected ranges must agree between the two states —
+/// equivalently, the two `BTreeMap`s must be structurally equal over those
+/// ranges (prune-on-write guarantees structural == semantic equality).
 pub fn states_equal_for_live_out(
     state1: &ConcreteMachineState,
     state2: &ConcreteMachineState,
     live_out: &RegisterSet<Register>,
-    memory_live: bool,
+    memory_live: &MemLiveOut,
 ) -> bool {
     for reg in live_out.iter() {
         match reg {
@@ -937,10 +919,21 @@ pub fn states_equal_for_live_out(
     if live_out.flags_live() && state1.get_flags() != state2.get_flags() {
         return false;
     }
-    if memory_live && state1.memory() != state2.memory() {
-        return false;
+    match memory_live {
+        MemLiveOut::None => true,
+        MemLiveOut::All => state1.memory() == state2.memory(),
+        MemLiveOut::Ranges(ranges) => {
+            ranges
+                .iter()
+                .filter(|range| range.start < range.end)
+                .all(|range| {
+                    state1
+                        .memory()
+                        .range(range.clone())
+                        .eq(state2.memory().range(range.clone()))
+                })
+        }
     }
-    true
 }
 
 /// Find the first differing register between two states for live-out registers.
@@ -1003,6 +996,22 @@ mod tests {
         ConcreteMachineState::from_values(map)
     }
 
+    #[test]
+    fn sp_operand_reads_its_initial_value_not_zero() {
+        let mut state = ConcreteMachineState::new_zeroed();
+        state.set_register(Register::SP, ConcreteValue::new(0x7ff0));
+        let result = apply_instruction_concrete(
+            state,
+            &Instruction::Add {
+                rd: Register::X0,
+                rn: Register::SP,
+                rm: Operand::Immediate(16),
+            },
+        );
+        assert_eq!(result.get_register(Register::X0).as_u64(), 0x8000);
+        assert_eq!(result.get_register(Register::SP).as_u64(), 0x7ff0);
+    }
+
     #[test]
     fn vector_add_wraps_each_lane_and_lane_move_reads_selected_doubleword() {
         let mut state = ConcreteMachineState::new_zeroed();
@@ -1552,7 +1561,10 @@ mod tests {
 
         let live_out = RegisterSet::<Register>::from_registers(vec![Register::X0]);
         assert!(states_equal_for_live_out(
-            &state1, &state2, &live_out, false
+            &state1,
+            &state2,
+            &live_out,
+            &MemLiveOut::None
         ));
     }
 
@@ -1563,7 +1575,10 @@ mod tests {
 
         let live_out = RegisterSet::<Register>::from_registers(vec![Register::X0]);
         assert!(!states_equal_for_live_out(
-            &state1, &state2, &live_out, false
+            &state1,
+            &state2,
+            &live_out,
+            &MemLiveOut::None
         ));
     }
 
@@ -1589,16 +1604,66 @@ mod tests {
             &state1,
             &state2,
             &live_out.clone().with_flags(false),
-            false
+            &MemLiveOut::None
         ));
         assert!(!states_equal_for_live_out(
             &state1,
             &state2,
             &live_out.with_flags(true),
-            false
+            &MemLiveOut::None
         ));
     }
 
+    #[test]
+    fn states_equal_for_live_out_compares_only_live_memory() {
+        let store = |offset| Instruction::Str {
+            rt: Register::X1,
+            addr: AddressOperand::Imm {
+                base: Register::X2,
+                offset,
+                mode: IndexMode::Offset,
+            },
+            width: AccessWidth::Extended,
+        };
+        let state = state_with(vec![(Register::X1, 0x55), (Register::X2, 0x1000)]);
+        let one_store = apply_sequence_concrete(state.clone(), &[store(0)]);
+        let two_stores = apply_sequence_concrete(state, &[store(0), store(16)]);
+        assert_eq!(
+            two_stores.written_addresses(),
+            (0x1000..0x1008).chain(0x1010..0x1018).collect()
+        );
+
+        let live_out = RegisterSet::<Register>::from_registers(vec![Register::X1]);
+        // The extra store lands outside the live ranges: still equal.
+        let first_slot = MemLiveOut::Ranges(vec![0x1000..0x1008, 0x1020..0x1028]);
+        assert!(states_equal_for_live_out(
+            &one_store,
+            &two_stores,
+            &live_out,
+            &first_slot
+        ));
+        // A reversed range covers nothing rather than panicking.
+        let (start, end) = (0x1018, 0x1010);
+        assert!(states_equal_for_live_out(
+            &one_store,
+            &two_stores,
+            &live_out,
+            &MemLiveOut::Ranges(vec![start..end, 0x1000..0x1008])
+        ));
+        // Once its address is live, the extra store is observable.
+        for memory in [
+            MemLiveOut::Ranges(vec![0x1000..0x1008, 0x1010..0x1018]),
+            MemLiveOut::All,
+        ] {
+            assert!(!states_equal_for_live_out(
+                &one_store,
+                &two_stores,
+                &live_out,
+                &memory
+            ));
+        }
+    }
+
     #[test]
     fn test_find_first_difference() {
         let state1 = state_with(vec![(Register::X0, 42), (Register::X1, 100)]);
@@ -1668,7 +1733,10 @@ mod tests {
 
         let live_out = RegisterSet::<Register>::from_registers(vec![Register::X0]);
         assert!(states_equal_for_live_out(
-            &state1, &state2, &live_out, false
+            &state1,
+            &state2,
+            &live_out,
+            &MemLiveOut::None
         ));
     }
 
@@ -2,7 +2,10 @@
 
 #![allow(dead_code)]
 
-use crate::ir::Instruction;
+use crate::ir::{Instruction, Register};
+use crate::isa::InstructionType;
+use crate::semantics::live_out::LiveOut;
+use std::collections::HashMap;
 
 /// Cost metric for evaluating instruction sequences
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -12,125 +15,465 @@ pub enum CostMetric {
     InstructionCount,
     /// Sum of instruction latencies
     Latency,
+    /// `Latency` with per-opcode numbers from a user-supplied table, for
+    /// targeting a specific core. The table is indexed by AArch64
+    /// `opcode_id`; x86 costs ignore it and score as `Latency`, so the CLI
+    /// refuses a table for x86 binaries.
+    CustomLatency(LatencyTable),
     /// Total code size in bytes (4 per instruction for AArch64)
     CodeSize,
+    /// Instruction count, with ties broken by how many distinct registers
+    /// the sequence clobbers, i.e. writes outside the live-out set: fewer
+    /// clobbered scratch registers is cheaper. Each instruction weighs
+    /// `CLOBBER_INSTRUCTION_WEIGHT`, so a shorter sequence always beats a
+    /// longer one. The live-out set starts empty; searches bind the window's
+    /// with [`CostMetric::with_live_out`].
+    Clobbers(LiveRegisterMask),
+    /// Weighted sum of instruction count, `Latency` and clobbered registers;
+    /// see [`CompositeWeights`]. The mask plays the same role as under
+    /// `Clobbers`.
+    Composite(CompositeWeights, LiveRegisterMask),
 }
 
-/// Get the cost of a single instruction
+impl CostMetric {
+    /// `self` with the clobber term exempting the registers in `live_out`:
+    /// writing a result register is not a clobber. Metrics without a clobber
+    /// term are returned unchanged.
+    pub fn with_live_out(self, live_out: &LiveOut) -> Self {
+        let mask = LiveRegisterMask::from_live_out(live_out);
+        match self {
+            CostMetric::Clobbers(_) => CostMetric::Clobbers(mask),
+            CostMetric::Composite(weights, _) => CostMetric::Composite(weights, mask),
+            other => other,
+        }
+    }
+
+    /// True if the metric has a clobber term, which only the AArch64 cost
+    /// model implements.
+    pub fn counts_clobbers(&self) -> bool {
+        match self {
+            CostMetric::Clobbers(_) => true,
+            CostMetric::Composite(weights, _) => weights.clobbers > 0,
+            _ => false,
+        }
+    }
+}
+
+/// The live-out registers a clobber term leaves out, one bit per
+/// [`Register::sort_key`]. Kept as a bitmask so [`CostMetric`] stays `Copy`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct LiveRegisterMask(u128);
+
+impl LiveRegisterMask {
+    /// No register is live-out: every written register counts.
+    pub const EMPTY: Self = Self(0);
+
+    pub fn from_live_out(live_out: &LiveOut) -> Self {
+        Self(
+            live_out
+                .iter()
+                .fold(0, |mask, reg| mask | 1u128 << reg.sort_key()),
+        )
+    }
+
+    pub fn contains(self, reg: Register) -> bool {
+        self.0 & 1u128 << reg.sort_key() != 0
+    }
+}
+
+/// Spells every metric the way [`FromStr`](std::str::FromStr) reads it back.
+/// The clobber mask is not spelled: searches bind it from the live-out set.
+impl std::fmt::Display for CostMetric {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CostMetric::InstructionCount => write!(f, "instruction-count"),
+            CostMetric::Latency => write!(f, "latency"),
+            CostMetric::CustomLatency(table) => {
+                write!(f, "custom-latency:")?;
+                for (i, (opcode_id, latency)) in table.overrides().enumerate() {
+                    let sep = if i == 0 { "" } else { "," };
+                    write!(f, "{}{}={}", sep, opcode_id, latency)?;
+                }
+                Ok(())
+            }
+            CostMetric::CodeSize => write!(f, "code-size"),
+            CostMetric::Clobbers(_) => write!(f, "clobbers"),
+            CostMetric::Composite(weights, _) => write!(
+                f,
+                "composite:{},{},{}",
+                weights.count, weights.latency, weights.clobbers
+            ),
+        }
+    }
+}
+
+/// Parses `instruction-count` (`count`, `instructions`), `latency`,
+/// `code-size` (`size`, `bytes`) and `clobbers`, plus the two metrics that
+/// carry data: `custom-latency:ID=LAT,...` (opcode id to latency overrides)
+/// and `composite:COUNT,LATENCY,CLOBBERS` (see [`CompositeWeights`]). Case
+/// and `_`/`-` are ignored in the name.
+impl std::str::FromStr for CostMetric {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, data) = match s.split_once(':') {
+            Some((name, data)) => (name, Some(data)),
+            None => (s, None),
+        };
+        match (name.to_lowercase().replace('_', "-").as_str(), data) {
+            ("instruction-count" | "count" | "instructions", None) => {
+                Ok(CostMetric::InstructionCount)
+            }
+            ("latency", None) => Ok(CostMetric::Latency),
+            ("custom-latency", data) => {
+                parse_latency_overrides(data.unwrap_or("")).map(CostMetric::CustomLatency)
+            }
+            ("code-size" | "size" | "bytes", None) => Ok(CostMetric::CodeSize),
+            ("clobbers", None) => Ok(CostMetric::Clobbers(LiveRegisterMask::EMPTY)),
+            ("composite", Some(data)) => parse_composite_weights(data)
+                .map(|weights| CostMetric::Composite(weights, LiveRegisterMask::EMPTY)),
+            _ => Err(format!(
+                "Unknown cost metric: '{}'. Valid options: instruction-count, latency, \
+                 custom-latency:ID=LAT,..., code-size, clobbers, composite:COUNT,LATENCY,CLOBBERS",
+                s
+            )),
+        }
+    }
+}
+
+/// The `ID=LAT,...` list of a `custom-latency:` metric; empty is the default
+/// table.
+fn parse_latency_overrides(data: &str) -> Result<LatencyTable, String> {
+    let overrides = data
+        .split(',')
+        .filter(|entry| !entry.trim().is_empty())
+        .map(|entry| {
+            let (id, latency) = entry
+                .split_once('=')
+                .ok_or_else(|| format!("latency override '{}' is not ID=LAT", entry))?;
+            let id = id
+                .trim()
+                .parse::<u8>()
+                .map_err(|e| format!("opcode id '{}': {}", id, e))?;
+            let latency = latency
+                .trim()
+                .parse::<u64>()
+                .map_err(|e| format!("latency '{}': {}", latency, e))?;
+            Ok((id, latency))
+        })
+        .collect::<Result<HashMap<u8, u64>, String>>()?;
+    LatencyTable::from_overrides(&overrides)
+}
+
+/// The `COUNT,LATENCY,CLOBBERS` weights of a `composite:` metric.
+fn parse_composite_weights(data: &str) -> Result<CompositeWeights, String> {
+    let weights = data
+        .split(',')
+        .map(|weight| {
+            weight
+                .trim()
+                .parse::<u64>()
+                .map_err(|e| format!("composite weight '{}': {}", weight, e))
+        })
+        .collect::<Result<Vec<u64>, String>>()?;
+    match weights[..] {
+        [count, latency, clobbers] => Ok(CompositeWeights::new(count, latency, clobbers)),
+        _ => Err(format!(
+            "composite takes COUNT,LATENCY,CLOBBERS weights, got '{}'",
+            data
+        )),
+    }
+}
+
+/// Weights for [`CostMetric::Composite`]. A sequence costs
+/// `count * instructions + latency * summed latency + clobbers * distinct
+/// registers written`, each term measured as its own metric measures it.
+///
+/// Weights far apart turn the sum into a lexicographic order: with
+/// `CompositeWeights::new(1000, 10, 1)` count dominates, latency breaks
+/// count ties and clobbers break the rest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CompositeWeights {
+    pub count: u64,
+    pub latency: u64,
+    pub clobbers: u64,
+}
+
+impl CompositeWeights {
+    pub const fn new(count: u64, latency: u64, clobbers: u64) -> Self {
+        Self {
+            count,
+            latency,
+            clobbers,
+        }
+    }
+}
+
+/// Per-instruction weight under `CostMetric::Clobbers`. Larger than any
+/// possible count of distinct destination registers, so the register term
+/// only ever breaks ties between equal-length sequences.
+pub const CLOBBER_INSTRUCTION_WEIGHT: u64 = 128;
+
+/// Get the cost of a single instruction. Disguised moves (`add x0, x1, #0`,
+/// `orr x0, x1, xzr`, ...) are costed as the `mov` they simplify to.
+///
+/// An instruction the assembler expands to several words (a wide `MovImm`
+/// becoming a MOVZ/MOVK chain, see [`Instruction::encoded_length`]) is
+/// charged once per emitted word under every metric.
+///
+/// Under `Composite` this is the count and latency terms only; the clobber
+/// term belongs to the whole sequence and is added by [`sequence_cost`].
 pub fn instruction_cost(instr: &Instruction, metric: &CostMetric) -> u64 {
+    if let CostMetric::Composite(weights, _) = metric {
+        return weights.count * instruction_cost(instr, &CostMetric::InstructionCount)
+            + weights.latency * instruction_cost(instr, &CostMetric::Latency);
+    }
+    let bytes = instr.encoded_length() as u64;
+    let words = bytes / 4;
+    if words > 1 {
+        return match metric {
+            CostMetric::CodeSize => bytes,
+            CostMetric::Clobbers(_) => CLOBBER_INSTRUCTION_WEIGHT * words,
+            _ => words,
+        };
+    }
     match metric {
         CostMetric::InstructionCount => 1,
-        CostMetric::Latency => instruction_latency(instr),
-        CostMetric::CodeSize => 4,
+        CostMetric::Latency => instruction_latency(&instr.simplified()),
+        CostMetric::CustomLatency(table) => table.latency(instr.simplified().opcode_id()),
+        CostMetric::CodeSize => bytes,
+        CostMetric::Clobbers(_) => CLOBBER_INSTRUCTION_WEIGHT,
+        CostMetric::Composite(..) => unreachable!("composite costs return early"),
     }
 }
 
-/// Get the latency of an instruction (simplified model)
-fn instruction_latency(instr: &Instruction) -> u64 {
-    match instr {
-        Instruction::MovReg { .. }
-        | Instruction::MovRegW { .. }
-        | Instruction::MovImm { .. }
-        | Instruction::Movi { .. } => 1,
-        // SIMD/FP-to-GPR transfer crosses register files on representative
-        // AArch64 cores and is costed one cycle above same-file moves.
-        Instruction::MovFromVectorLane { .. } => 2,
-        Instruction::Add { .. }
-        | Instruction::AddW { .. }
-        | Instruction::Sub { .. }
-        | Instruction::SubW { .. }
-        | Instruction::VectorAdd { .. } => 1,
-        Instruction::And { .. } | Instruction::Orr { .. } | Instruction::Eor { .. } => 1,
-        Instruction::Lsl { .. } | Instruction::Lsr { .. } | Instruction::Asr { .. } => 1,
-        // Multiply has higher latency than simple ALU ops
-        Instruction::Mul { .. } => 3,
-        // Multiply-accumulate fuses with the multiply pipeline
-        Instruction::Madd { .. } | Instruction::Msub { .. } | Instruction::Mneg { .. } => 3,
-        // High-half multiply: one extra cycle vs MUL on Cortex-A72/A76.
-        Instruction::Smulh { .. } | Instruction::Umulh { .. } => 4,
-        // Division has the highest latency
-        Instruction::Sdiv { .. } | Instruction::Udiv { .. } => 12,
-        // Comparison instructions (just set flags)
-        Instruction::Cmp { .. } | Instruction::Cmn { .. } | Instruction::Tst { .. } => 1,
-        // Conditional comparisons (read NZCV, write NZCV)
-        Instruction::Ccmp { .. } | Instruction::Ccmn { .. } => 1,
-        // Conditional selects
-        Instruction::Csel { .. }
-        | Instruction::Csinc { .. }
-        | Instruction::Csinv { .. }
-        | Instruction::Csneg { .. } => 1,
-        // Unary bitwise / negation / move-wide-immediate family
-        Instruction::Mvn { .. }
-        | Instruction::Neg { .. }
-        | Instruction::Negs { .. }
-        | Instruction::MovN { .. }
-        | Instruction::MovZ { .. }
-        | Instruction::MovK { .. } => 1,
-        // Inverted-logical
-        Instruction::Bic { .. }
-        | Instruction::Bics { .. }
-        | Instruction::Orn { .. }
-        | Instruction::Eon { .. } => 1,
-        // Flag-setting arith / logical
-        Instruction::Adds { .. } | Instruction::Subs { .. } | Instruction::Ands { .. } => 1,
-        // Add/subtract with carry
-        Instruction::Adc { .. }
-        | Instruction::Adcs { .. }
-        | Instruction::Sbc { .. }
-        | Instruction::Sbcs { .. } => 1,
-        // Conditional set aliases
-        Instruction::Cset { .. } | Instruction::Csetm { .. } => 1,
-        // Rotate right
-        Instruction::Ror { .. } => 1,
-        // Single-source bit-manipulation (CLZ/CLS/RBIT/REV*): single-cycle ALU.
-        // Extends to SXT*/UXT* extended-register instructions (issue #60).
-        Instruction::Clz { .. }
-        | Instruction::Cls { .. }
-        | Instruction::Rbit { .. }
-        | Instruction::Rev { .. }
-        | Instruction::Rev32 { .. }
-        | Instruction::Rev16 { .. }
-        | Instruction::Sxtb { .. }
-        | Instruction::Sxth { .. }
-        | Instruction::Sxtw { .. }
-        | Instruction::Uxtb { .. }
-        | Instruction::Uxth { .. } => 1,
-        // Bit-field manipulation (UBFX/SBFX/BFI/BFXIL/UBFIZ/SBFIZ): single-cycle ALU.
-        Instruction::Ubfx { .. }
-        | Instruction::Sbfx { .. }
-        | Instruction::Bfi { .. }
-        | Instruction::Bfxil { .. }
-        | Instruction::Ubfiz { .. }
-        | Instruction::Sbfiz { .. } => 1,
-        // Branches: 1-cycle latency (predicted; we don't model misprediction).
-        Instruction::B { .. }
-        | Instruction::BCond { .. }
-        | Instruction::Ret { .. }
-        | Instruction::Cbz { .. }
-        | Instruction::Cbnz { .. }
-        | Instruction::Tbz { .. }
-        | Instruction::Tbnz { .. }
-        | Instruction::Bl { .. }
-        | Instruction::Br { .. } => 1,
-        // Loads (issue #68): Cortex-A72/A76 L1-hit latency ~ 4 cycles. See
-        // ADR-0007 §Consequences for the calibration rationale.
-        Instruction::Ldr { .. } | Instruction::Ldrs { .. } => 4,
-        // Stores commit to the L1 store buffer in 1 cycle.
-        Instruction::Str { .. } => 1,
-        // Pair loads take one extra cycle vs single load (issue address
-        // generation + two-register writeback).
-        Instruction::Ldp { .. } => 5,
-        // Pair stores: two store-buffer entries.
-        Instruction::Stp { .. } => 2,
+/// Number of distinct registers `instructions` writes that are not in
+/// `live_out` — the scratch registers a caller must treat as clobbered.
+/// XZR writes are discarded and never count.
+pub fn sequence_clobbers(instructions: &[Instruction], live_out: &LiveOut) -> usize {
+    clobbered_registers(instructions, LiveRegisterMask::from_live_out(live_out))
+}
+
+fn clobbered_registers(instructions: &[Instruction], live_out: LiveRegisterMask) -> usize {
+    written_registers(instructions)
+        .into_iter()
+        .filter(|reg| !live_out.contains(*reg))
+        .count()
+}
+
+fn written_registers(instructions: &[Instruction]) -> Vec<Register> {
+    let mut written: Vec<Register> = instructions
+        .iter()
+        .flat_map(|instr| instr.destinations())
+        .filter(|reg| *reg != Register::XZR)
+        .collect();
+    written.sort_by_key(|reg| reg.sort_key());
+    written.dedup();
+    written
+}
+
+/// Static scheduling data for one opcode.
+///
+/// `latency` is cycles from issue until the result can be consumed;
+/// `throughput` is the reciprocal throughput, the cycles before another
+/// instruction of the same opcode can issue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OpcodeInfo {
+    pub latency: u8,
+    pub throughput: u8,
+}
+
+impl OpcodeInfo {
+    pub const fn new(latency: u8, throughput: u8) -> Self {
+        Self {
+            latency,
+            throughput,
+        }
+    }
+}
+
+/// Per-opcode latency and throughput, indexed by `InstructionType::opcode_id`.
+///
+/// The defaults model a generic Cortex-A-class out-of-order core (numbers
+/// calibrated against the Cortex-A72/A76 optimization guides): single-cycle
+/// ALU, a pipelined 3-cycle multiplier, an unpipelined divider and an L1-hit
+/// load latency of 4. Every AArch64 `opcode_id`, including branches, memory
+/// and add-with-carry above `opcode_count`, has exactly one entry.
+pub const OPCODE_INFO: [OpcodeInfo; 81] = [
+    OpcodeInfo::new(1, 1), //  0 MOV (register), MOV (register, 32-bit)
+    OpcodeInfo::new(1, 1), //  1 MOV (immediate)
+    OpcodeInfo::new(1, 1), //  2 ADD
+    OpcodeInfo::new(1, 1), //  3 SUB
+    OpcodeInfo::new(1, 1), //  4 AND
+    OpcodeInfo::new(1, 1), //  5 ORR
+    OpcodeInfo::new(1, 1), //  6 EOR
+    OpcodeInfo::new(1, 1), //  7 LSL
+    OpcodeInfo::new(1, 1), //  8 LSR
+    OpcodeInfo::new(1, 1), //  9 ASR
+    // Multiply has higher latency than simple ALU ops
+    OpcodeInfo::new(3, 1), // 10 MUL
+    // Division has the highest latency; the divider is not pipelined, so
+    // a second divide waits for the first.
+    OpcodeInfo::new(12, 12), // 11 SDIV
+    OpcodeInfo::new(12, 12), // 12 UDIV
+    OpcodeInfo::new(1, 1),   // 13 CMP
+    OpcodeInfo::new(1, 1),   // 14 CMN
+    OpcodeInfo::new(1, 1),   // 15 TST
+    OpcodeInfo::new(1, 1),   // 16 CSEL
+    OpcodeInfo::new(1, 1),   // 17 CSINC
+    OpcodeInfo::new(1, 1),   // 18 CSINV
+    OpcodeInfo::new(1, 1),   // 19 CSNEG
+    OpcodeInfo::new(1, 1),   // 20 MVN
+    OpcodeInfo::new(1, 1),   // 21 NEG
+    OpcodeInfo::new(1, 1),   // 22 NEGS
+    OpcodeInfo::new(1, 1),   // 23 MOVN
+    OpcodeInfo::new(1, 1),   // 24 BIC
+    OpcodeInfo::new(1, 1),   // 25 BICS
+    OpcodeInfo::new(1, 1),   // 26 ORN
+    OpcodeInfo::new(1, 1),   // 27 EON
+    OpcodeInfo::new(1, 1),   // 28 ADDS
+    OpcodeInfo::new(1, 1),   // 29 SUBS
+    OpcodeInfo::new(1, 1),   // 30 ANDS
+    OpcodeInfo::new(1, 1),   // 31 CSET
+    OpcodeInfo::new(1, 1),   // 32 CSETM
+    OpcodeInfo::new(1, 1),   // 33 ROR
+    OpcodeInfo::new(1, 1),   // 34 MOVZ
+    OpcodeInfo::new(1, 1),   // 35 MOVK
+    OpcodeInfo::new(1, 1),   // 36 CLZ
+    OpcodeInfo::new(1, 1),   // 37 CLS
+    OpcodeInfo::new(1, 1),   // 38 RBIT
+    OpcodeInfo::new(1, 1),   // 39 REV
+    OpcodeInfo::new(1, 1),   // 40 REV32
+    OpcodeInfo::new(1, 1),   // 41 REV16
+    // Multiply-accumulate fuses with the multiply pipeline
+    OpcodeInfo::new(3, 1), // 42 MADD
+    OpcodeInfo::new(3, 1), // 43 MSUB
+    OpcodeInfo::new(3, 1), // 44 MNEG
+    // High-half multiply: one extra cycle vs MUL on Cortex-A72/A76.
+    OpcodeInfo::new(4, 2), // 45 SMULH
+    OpcodeInfo::new(4, 2), // 46 UMULH
+    OpcodeInfo::new(1, 1), // 47 CCMP
+    OpcodeInfo::new(1, 1), // 48 CCMN
+    OpcodeInfo::new(1, 1), // 49 SXTB
+    OpcodeInfo::new(1, 1), // 50 SXTH
+    OpcodeInfo::new(1, 1), // 51 SXTW
+    OpcodeInfo::new(1, 1), // 52 UXTB
+    OpcodeInfo::new(1, 1), // 53 UXTH
+    OpcodeInfo::new(1, 1), // 54 UBFX
+    OpcodeInfo::new(1, 1), // 55 SBFX
+    OpcodeInfo::new(1, 1), // 56 BFI
+    OpcodeInfo::new(1, 1), // 57 BFXIL
+    OpcodeInfo::new(1, 1), // 58 UBFIZ
+    OpcodeInfo::new(1, 1), // 59 SBFIZ
+    OpcodeInfo::new(1, 1), // 60 MOVI
+    OpcodeInfo::new(1, 1), // 61 ADD (vector)
+    // SIMD/FP-to-GPR transfer crosses register files on representative
+    // AArch64 cores and is costed one cycle above same-file moves.
+    OpcodeInfo::new(2, 1), // 62 UMOV / FMOV to GPR
+    // Branches: 1-cycle latency (predicted; we don't model misprediction).
+    OpcodeInfo::new(1, 1), // 63 B
+    OpcodeInfo::new(1, 1), // 64 B.cond
+    OpcodeInfo::new(1, 1), // 65 RET
+    OpcodeInfo::new(1, 1), // 66 CBZ
+    OpcodeInfo::new(1, 1), // 67 CBNZ
+    OpcodeInfo::new(1, 1), // 68 TBZ
+    OpcodeInfo::new(1, 1), // 69 TBNZ
+    OpcodeInfo::new(1, 1), // 70 BL
+    OpcodeInfo::new(1, 1), // 71 BR
+    // Loads (issue #68): Cortex-A72/A76 L1-hit latency ~ 4 cycles. See
+    // ADR-0007 §Consequences for the calibration rationale.
+    OpcodeInfo::new(4, 1), // 72 LDR / LDRB / LDRH
+    OpcodeInfo::new(4, 1), // 73 LDRSB / LDRSH / LDRSW
+    // Stores commit to the L1 store buffer in 1 cycle.
+    OpcodeInfo::new(1, 1), // 74 STR / STRB / STRH
+    // Pair loads take one extra cycle vs single load (issue address
+    // generation + two-register writeback).
+    OpcodeInfo::new(5, 1), // 75 LDP / LDPSW
+    // Pair stores: two store-buffer entries.
+    OpcodeInfo::new(2, 2), // 76 STP
+    OpcodeInfo::new(1, 1), // 77 ADC
+    OpcodeInfo::new(1, 1), // 78 ADCS
+    OpcodeInfo::new(1, 1), // 79 SBC
+    OpcodeInfo::new(1, 1), // 80 SBCS
+];
+
+impl Instruction {
+    /// Scheduling data for this instruction's opcode; see [`OPCODE_INFO`].
+    pub fn info(&self) -> OpcodeInfo {
+        OPCODE_INFO[self.opcode_id() as usize]
+    }
+}
+
+/// Per-opcode latencies for [`CostMetric::CustomLatency`], indexed by
+/// `opcode_id`. Opcodes a user does not override keep their
+/// [`OPCODE_INFO`] latency.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LatencyTable([u8; OPCODE_INFO.len()]);
+
+impl Default for LatencyTable {
+    fn default() -> Self {
+        Self(OPCODE_INFO.map(|info| info.latency))
     }
 }
 
+impl LatencyTable {
+    /// The default table with `overrides` (opcode id to latency) applied.
+    /// Rejects ids with no opcode and latencies above 255 cycles.
+    pub fn from_overrides(overrides: &HashMap<u8, u64>) -> Result<Self, String> {
+        let mut table = Self::default();
+        for (&opcode_id, &latency) in overrides {
+            let slot = table
+                .0
+                .get_mut(opcode_id as usize)
+                .ok_or_else(|| format!("no opcode with id {}", opcode_id))?;
+            *slot = u8::try_from(latency).map_err(|_| {
+                format!(
+                    "latency {} for opcode {} exceeds 255 cycles",
+                    latency, opcode_id
+                )
+            })?;
+        }
+        Ok(table)
+    }
+
+    /// Latency of the opcode with id `opcode_id`.
+    pub fn latency(&self, opcode_id: u8) -> u64 {
+        self.0[opcode_id as usize] as u64
+    }
+
+    /// The `(opcode_id, latency)` entries that differ from [`OPCODE_INFO`],
+    /// by ascending id: the overrides that rebuild this table.
+    pub fn overrides(&self) -> impl Iterator<Item = (u8, u64)> + '_ {
+        let default = Self::default();
+        (0..self.0.len())
+            .filter(move |&id| self.0[id] != default.0[id])
+            .map(|id| (id as u8, self.0[id] as u64))
+    }
+}
+
+/// Get the latency of an instruction from [`OPCODE_INFO`].
+fn instruction_latency(instr: &Instruction) -> u64 {
+    instr.info().latency as u64
+}
+
 /// Calculate the total cost of an instruction sequence
+///
+/// Under `Clobbers` the register term is [`sequence_clobbers`] against the
+/// live-out set bound into the metric.
 pub fn sequence_cost(instructions: &[Instruction], metric: &CostMetric) -> u64 {
-    instructions
+    let per_instruction: u64 = instructions
         .iter()
         .map(|i| instruction_cost(i, metric))
-        .sum()
+        .sum();
+    match metric {
+        CostMetric::Clobbers(live_out) => {
+            per_instruction + clobbered_registers(instructions, *live_out) as u64
+        }
+        CostMetric::Composite(weights, live_out) => {
+            per_instruction + weights.clobbers * clobbered_registers(instructions, *live_out) as u64
+        }
+        _ => per_instruction,
+    }
 }
 
 /// Check if sequence `a` is cheaper than sequence `b`
@@ -166,6 +509,157 @@ mod tests {
         }
     }
 
+    #[test]
+    fn cost_metric_parses_short_and_long_names() {
+        for (names, metric) in [
+            (["count", "instruction-count"], CostMetric::InstructionCount),
+            (["size", "code_size"], CostMetric::CodeSize),
+            (["latency", "LATENCY"], CostMetric::Latency),
+            (
+                ["clobbers", "Clobbers"],
+                CostMetric::Clobbers(LiveRegisterMask::EMPTY),
+            ),
+        ] {
+            for name in names {
+                assert_eq!(name.parse::<CostMetric>(), Ok(metric), "{name}");
+            }
+            assert_eq!(metric.to_string().parse::<CostMetric>(), Ok(metric));
+        }
+        assert!("speed".parse::<CostMetric>().is_err());
+    }
+
+    #[test]
+    fn every_cost_metric_parses_back_from_its_display() {
+        let table = LatencyTable::from_overrides(&HashMap::from([(10, 1), (3, 40)])).unwrap();
+        for metric in [
+            CostMetric::InstructionCount,
+            CostMetric::Latency,
+            CostMetric::CustomLatency(table),
+            CostMetric::CustomLatency(LatencyTable::default()),
+            CostMetric::CodeSize,
+            CostMetric::Clobbers(LiveRegisterMask::EMPTY),
+            CostMetric::Composite(CompositeWeights::new(1000, 10, 1), LiveRegisterMask::EMPTY),
+        ] {
+            assert_eq!(
+                metric.to_string().parse::<CostMetric>(),
+                Ok(metric),
+                "{metric}"
+            );
+        }
+        assert_eq!(
+            CostMetric::CustomLatency(table).to_string(),
+            "custom-latency:3=40,10=1"
+        );
+        assert_eq!(
+            "composite:1,2,3".parse::<CostMetric>(),
+            Ok(CostMetric::Composite(
+                CompositeWeights::new(1, 2, 3),
+                LiveRegisterMask::EMPTY
+            ))
+        );
+        for bad in [
+            "composite:1,2",
+            "composite",
+            "custom-latency:3",
+            "latency:3=1",
+        ] {
+            assert!(bad.parse::<CostMetric>().is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn composite_breaks_count_ties_by_latency() {
+        let metric =
+            CostMetric::Composite(CompositeWeights::new(1000, 10, 1), LiveRegisterMask::EMPTY);
+        // Both compute x0 = x1 * 2 in one instruction; the shift is faster.
+        let shift = vec![Instruction::Lsl {
+            rd: Register::X0,
+            rn: Register::X1,
+            shift: Operand::Immediate(1),
+        }];
+        let multiply = vec![Instruction::Mul {
+            rd: Register::X0,
+            rn: Register::X1,
+            rm: Register::X1,
+        }];
+
+        assert_eq!(sequence_cost(&shift, &metric), 1000 + 10 + 1);
+        assert_eq!(sequence_cost(&multiply, &metric), 1000 + 30 + 1);
+        assert!(is_cheaper(&shift, &multiply, &metric));
+        // Count still dominates: two fast instructions lose to one slow one.
+        let two_adds = vec![
+            add_imm(Register::X0, Register::X1, 1),
+            add_imm(Register::X0, Register::X0, 1),
+        ];
+        assert!(is_cheaper(&multiply, &two_adds, &metric));
+    }
+
+    #[test]
+    fn composite_with_a_single_weight_matches_that_metric() {
+        let sequence = vec![
+            add_imm(Register::X2, Register::X1, 1),
+            mov_imm(Register::X0, 0x1234_5678),
+        ];
+        for (weights, metric) in [
+            (CompositeWeights::new(1, 0, 0), CostMetric::InstructionCount),
+            (CompositeWeights::new(0, 1, 0), CostMetric::Latency),
+        ] {
+            assert_eq!(
+                sequence_cost(
+                    &sequence,
+                    &CostMetric::Composite(weights, LiveRegisterMask::EMPTY)
+                ),
+                sequence_cost(&sequence, &metric)
+            );
+        }
+        assert_eq!(
+            sequence_cost(
+                &sequence,
+                &CostMetric::Composite(CompositeWeights::new(0, 0, 1), LiveRegisterMask::EMPTY)
+            ),
+            2
+        );
+    }
+
+    #[test]
+    fn test_clobbers_prefers_fewer_scratch_registers() {
+        // Both compute x0 = x1 + 2; the first goes through scratch x2.
+        let via_scratch = vec![
+            add_imm(Register::X2, Register::X1, 1),
+            add_imm(Register::X0, Register::X2, 1),
+        ];
+        let in_place = vec![
+            add_imm(Register::X0, Register::X1, 1),
+            add_imm(Register::X0, Register::X0, 1),
+        ];
+        let live_out = LiveOut::from_registers(vec![Register::X0]);
+
+        assert_eq!(sequence_clobbers(&via_scratch, &live_out), 1);
+        assert_eq!(sequence_clobbers(&in_place, &live_out), 0);
+        assert_eq!(
+            sequence_cost(&via_scratch, &CostMetric::InstructionCount),
+            sequence_cost(&in_place, &CostMetric::InstructionCount)
+        );
+        let clobbers = CostMetric::Clobbers(LiveRegisterMask::EMPTY).with_live_out(&live_out);
+        // The live-out x0 is a result, not a clobber: only the scratch counts.
+        assert_eq!(
+            sequence_cost(&in_place, &clobbers),
+            2 * CLOBBER_INSTRUCTION_WEIGHT
+        );
+        assert_eq!(
+            sequence_cost(&via_scratch, &clobbers),
+            2 * CLOBBER_INSTRUCTION_WEIGHT + 1
+        );
+        // Length still dominates: one instruction beats two with no scratch.
+        let single = vec![add_imm(Register::X0, Register::X1, 2)];
+        assert!(sequence_cost(&single, &clobbers) < sequence_cost(&in_place, &clobbers));
+        // Unbound, every written register counts.
+        assert_eq!(
+            sequence_cost(&in_place, &CostMetric::Clobbers(LiveRegisterMask::EMPTY)),
+            2 * CLOBBER_INSTRUCTION_WEIGHT + 1
+        );
+    }
+
     #[test]
     fn test_instruction_cost_count() {
         let instr = mov_imm(Register::X0, 0);
@@ -317,6 +811,88 @@ mod tests {
         }
     }
 
+    #[test]
+    fn opcode_info_covers_every_opcode_id() {
+        let highest = Instruction::Sbcs {
+            rd: Register::X0,
+            rn: Register::X1,
+            rm: Register::X2,
+        };
+        assert_eq!(OPCODE_INFO.len(), highest.opcode_id() as usize + 1);
+        for fixture in aarch64_instruction_families() {
+            let info = fixture.instruction.info();
+            assert!(info.latency > 0 && info.throughput > 0);
+        }
+        assert!(OPCODE_INFO.iter().all(|info| info.latency > 0));
+    }
+
+    #[test]
+    fn custom_latency_table_overrides_only_the_listed_opcodes() {
+        let mul = Instruction::Mul {
+            rd: Register::X0,
+            rn: Register::X1,
+            rm: Register::X2,
+        };
+        let add = add_imm(Register::X0, Register::X1, 1);
+        let table = LatencyTable::from_overrides(&HashMap::from([(mul.opcode_id(), 1)])).unwrap();
+        let custom = CostMetric::CustomLatency(table);
+
+        assert_eq!(instruction_cost(&mul, &custom), 1);
+        assert_eq!(instruction_cost(&mul, &CostMetric::Latency), 3);
+        assert_eq!(
+            instruction_cost(&add, &custom),
+            instruction_cost(&add, &CostMetric::Latency)
+        );
+        assert_eq!(
+            LatencyTable::default(),
+            LatencyTable::from_overrides(&HashMap::new()).unwrap()
+        );
+        assert!(LatencyTable::from_overrides(&HashMap::from([(200, 1)])).is_err());
+        assert!(LatencyTable::from_overrides(&HashMap::from([(mul.opcode_id(), 256)])).is_err());
+    }
+
+    #[test]
+    fn custom_latency_table_flips_the_cheaper_equivalent() {
+        // Equivalent under live-out x0 (see the equivalence tests'
+        // `test_madd_equivalent_to_mul_then_add`).
+        let mul_add = vec![
+            Instruction::Mul {
+                rd: Register::X4,
+                rn: Register::X1,
+                rm: Register::X2,
+            },
+            Instruction::Add {
+                rd: Register::X0,
+                rn: Register::X4,
+                rm: Operand::Register(Register::X3),
+            },
+        ];
+        let madd = vec![Instruction::Madd {
+            rd: Register::X0,
+            rn: Register::X1,
+            rm: Register::X2,
+            ra: Register::X3,
+        }];
+        assert!(is_cheaper(&madd, &mul_add, &CostMetric::Latency));
+
+        let cheap_mul =
+            LatencyTable::from_overrides(&HashMap::from([(mul_add[0].opcode_id(), 1)])).unwrap();
+        let custom = CostMetric::CustomLatency(cheap_mul);
+        assert!(is_cheaper(&mul_add, &madd, &custom));
+    }
+
+    #[test]
+    fn opcode_info_sdiv_is_slower_than_add() {
+        let add = add_imm(Register::X0, Register::X1, 1);
+        let sdiv = Instruction::Sdiv {
+            rd: Register::X0,
+            rn: Register::X1,
+            rm: Register::X2,
+        };
+        assert!(sdiv.info().latency > add.info().latency);
+        assert!(sdiv.info().throughput > add.info().throughput);
+    }
+
     #[test]
     fn test_mul_div_latency() {
         let mul = Instruction::Mul {
@@ -73,12 +73,19 @@ use crate::semantics::cost::CostMetric;
 /// critical path equals its own latency, so the two agree on length-1 inputs.
 pub fn instruction_cost(instr: &X86Instruction, metric: &CostMetric, width: u32) -> u64 {
     match metric {
-        CostMetric::InstructionCount => match instr {
+        // x86 has no clobber model: `Clobbers` scores as instruction count
+        // here, and the CLI rejects it for x86 binaries.
+        CostMetric::InstructionCount | CostMetric::Clobbers(_) => match instr {
             X86Instruction::Setcc { .. } => 2,
             _ => 1,
         },
-        CostMetric::Latency => instruction_latency(instr),
+        CostMetric::Latency | CostMetric::CustomLatency(_) => instruction_latency(instr),
         CostMetric::CodeSize => instruction_code_size(instr, width),
+        // Clobbers are not modelled on x86, so their weight contributes nothing.
+        CostMetric::Composite(weights, _) => {
+            weights.count * instruction_cost(instr, &CostMetric::InstructionCount, width)
+                + weights.latency * instruction_latency(instr)
+        }
     }
 }
 
@@ -261,10 +268,14 @@ fn instruction_code_size(instr: &X86Instruction, width: u32) -> u64 {
 /// instruction cases (a single instruction's critical path equals its latency).
 pub fn sequence_cost(seq: &[X86Instruction], metric: &CostMetric, width: u32) -> u64 {
     match metric {
-        CostMetric::Latency => critical_path_latency(seq),
-        CostMetric::InstructionCount | CostMetric::CodeSize => {
+        CostMetric::Latency | CostMetric::CustomLatency(_) => critical_path_latency(seq),
+        CostMetric::InstructionCount | CostMetric::CodeSize | CostMetric::Clobbers(_) => {
             seq.iter().map(|i| instruction_cost(i, metric, width)).sum()
         }
+        CostMetric::Composite(weights, _) => {
+            weights.count * sequence_cost(seq, &CostMetric::InstructionCount, width)
+                + weights.latency * critical_path_latency(seq)
+        }
     }
 }
 
@@ -6,13 +6,14 @@
 
 #![allow(dead_code)]
 
-use crate::ir::Instruction;
 use crate::ir::instructions::split_terminator;
+use crate::ir::{Instruction, Register};
 use crate::isa::{AArch64, ISA};
 use crate::semantics::concrete::{
     apply_sequence_concrete, find_first_difference, states_equal_for_live_out,
 };
-use crate::semantics::live_out::{LiveOut, RegisterSet};
+use crate::semantics::live_out::{LiveOut, MemLiveOut, RegisterSet};
+#[cfg(feature = "z3")]
 use crate::semantics::smt::{
     MachineState, SolverConfig, apply_sequence, create_solver_with_config, states_not_equal,
     states_not_equal_for_live_out,
@@ -23,6 +24,7 @@ use crate::validation::random::{
     RandomInputConfig, generate_edge_case_inputs, generate_random_inputs,
 };
 use std::time::Duration;
+#[cfg(feature = "z3")]
 use z3::SatResult;
 
 /// Cheap pre-SMT fast-path: rejects when only one sequence has flag-writers
@@ -50,6 +52,41 @@ fn flag_writers_diverge(target: &[Instruction], candidate: &[Instruction]) -> bo
     writes_any_flag(target) != writes_any_flag(candidate)
 }
 
+/// Backward liveness sweep: drop every instruction whose results are all
+/// overwritten or never observed under `live_out`. Memory operations and
+/// terminators are always kept, as are instructions with neither a register
+/// destination nor a flag write, since their effect is not tracked here.
+fn without_dead_instructions(seq: &[Instruction], live_out: &LiveOut) -> Vec<Instruction> {
+    let mut live = live_out.clone();
+    let mut kept = Vec::with_capacity(seq.len());
+    for instr in seq.iter().rev() {
+        let destinations = instr.destinations();
+        let observed = instr.is_memory_op()
+            || instr.is_terminator()
+            || (instr.modifies_flags() && live.flags_live())
+            || destinations.iter().any(|reg| live.contains(*reg))
+            || (destinations.is_empty() && !instr.modifies_flags());
+        if !observed {
+            continue;
+        }
+        for reg in destinations {
+            live.remove(reg);
+        }
+        if instr.modifies_flags() {
+            live.set_flags_live(false);
+        }
+        for reg in instr.source_registers() {
+            live.add(reg);
+        }
+        if instr.reads_flags() {
+            live.set_flags_live(true);
+        }
+        kept.push(*instr);
+    }
+    kept.reverse();
+    kept
+}
+
 /// Combined pre-SMT soundness guard. Single source of truth for the
 /// short-circuit applied at every public entry point — returning `Some(r)`
 /// here means callers must return `r` (or the metrics-wrapped equivalent)
@@ -81,11 +118,33 @@ pub enum EquivalenceResult {
     /// The sequences are not equivalent
     NotEquivalent,
     /// Not equivalent, found quickly by concrete testing (includes counterexample state)
-    NotEquivalentFast(ConcreteMachineState),
+    NotEquivalentFast(FastCounterexample),
     /// Could not determine (timeout, unknown, etc.)
     Unknown(String),
 }
 
+/// Concrete refutation carried by `EquivalenceResult::NotEquivalentFast`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FastCounterexample {
+    /// Input state on which the two sequences diverge.
+    pub input: ConcreteMachineState,
+    /// First differing live-out location, as reported by
+    /// `find_first_difference` (`XZR` stands for NZCV). `None` when the
+    /// divergence is memory-only or the refutation is structural (e.g. a
+    /// terminator mismatch) rather than input-driven.
+    pub first_difference: Option<Register>,
+}
+
+impl FastCounterexample {
+    /// A refutation that does not depend on any particular input.
+    pub fn structural() -> Self {
+        Self {
+            input: ConcreteMachineState::new_zeroed(),
+            first_difference: None,
+        }
+    }
+}
+
 /// Per-ISA default live-out policy for equivalence configuration.
 ///
 /// AArch64 preserves the historical `EquivalenceConfig::default()` behavior of
@@ -126,6 +185,67 @@ impl EquivalenceConfigDefaults for crate::isa::RiscV32 {
     }
 }
 
+/// A precondition on one input register: `reg & mask == value & mask`.
+///
+/// Lets the caller scope equivalence to the states the surrounding code can
+/// actually reach, e.g. a non-negative count or an 8-byte-aligned pointer.
+/// The constructors cover the common shapes; constraints on the same
+/// register combine conjunctively.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InputConstraint<R> {
+    pub reg: R,
+    pub mask: u64,
+    pub value: u64,
+}
+
+impl<R> InputConstraint<R> {
+    /// `reg >= 0` as a signed 64-bit value.
+    pub fn non_negative(reg: R) -> Self {
+        Self {
+            reg,
+            mask: 1 << 63,
+            value: 0,
+        }
+    }
+
+    /// `reg` is a multiple of `alignment`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `alignment` is not a power of two.
+    pub fn aligned(reg: R, alignment: u64) -> Self {
+        assert!(
+            alignment.is_power_of_two(),
+            "alignment must be a power of two, got {alignment}"
+        );
+        Self {
+            reg,
+            mask: alignment - 1,
+            value: 0,
+        }
+    }
+
+    /// `reg == value`.
+    pub fn equals(reg: R, value: u64) -> Self {
+        Self {
+            reg,
+            mask: u64::MAX,
+            value,
+        }
+    }
+
+    /// Whether `value` satisfies the constraint.
+    pub fn holds(&self, value: u64) -> bool {
+        value & self.mask == self.value & self.mask
+    }
+
+    /// `value` with the constrained bits overwritten so it satisfies the
+    /// constraint; the unconstrained bits are kept.
+    pub fn conform(&self, value: u64) -> u64 {
+        (value & !self.mask) | (self.value & self.mask)
+    }
+}
+
 /// Configuration for equivalence checking, parameterized by ISA.
 #[derive(Debug, Clone)]
 pub struct EquivalenceConfigFor<I: ISA> {
@@ -139,11 +259,16 @@ pub struct EquivalenceConfigFor<I: ISA> {
     pub smt_timeout: Option<Duration>,
     /// Skip SMT verification (fast path only)
     pub fast_only: bool,
-    /// Treat the entire memory image as live-out — every cell must agree
-    /// between the two sequences' final states. Auto-derived in
-    /// `check_equivalence_with_config` whenever either sequence touches
-    /// memory (see ADR-0007).
-    pub memory_live: bool,
+    /// Which memory must agree between the two sequences' final states.
+    /// `check_equivalence_with_config` raises `None` to the entire image
+    /// whenever either sequence touches memory (see ADR-0007); explicit
+    /// ranges are kept as given.
+    pub memory_live: MemLiveOut,
+    /// Preconditions on the initial state: asserted in the SMT query and
+    /// imposed on every concrete test input. Only the AArch64 checker
+    /// honours them; the x86 checker still proves equivalence for all
+    /// inputs.
+    pub input_constraints: Vec<InputConstraint<I::Register>>,
 }
 
 /// AArch64 compatibility alias. Existing callers keep using
@@ -160,7 +285,8 @@ where
             random_test_count: 10,
             smt_timeout: Some(Duration::from_secs(30)),
             fast_only: false,
-            memory_live: false,
+            memory_live: MemLiveOut::None,
+            input_constraints: Vec::new(),
         }
     }
 }
@@ -246,15 +372,51 @@ where
     /// `touches_memory()` on the candidate / target sequences. See
     /// ADR-0007.
     pub fn with_memory(mut self, memory_live: bool) -> Self {
-        self.memory_live = memory_live;
+        self.memory_live = memory_live.into();
+        self
+    }
+
+    /// Builder method to make only the bytes in `ranges` (half-open) live.
+    /// Errors if a range starts past its end.
+    pub fn with_memory_ranges(mut self, ranges: Vec<std::ops::Range<u64>>) -> Result<Self, String> {
+        self.memory_live = MemLiveOut::ranges(ranges)?;
+        Ok(self)
+    }
+
+    /// Builder method to restrict equivalence to initial states satisfying
+    /// every constraint. Replaces any constraints set earlier.
+    pub fn with_input_constraints(
+        mut self,
+        constraints: Vec<InputConstraint<I::Register>>,
+    ) -> Self {
+        self.input_constraints = constraints;
         self
     }
 }
 
+/// Named presets for the two common safety levels, so a caller states its
+/// intent instead of hand-building a mask. Searches take the preset's
+/// `live_out`, e.g. `search(&target, &EquivalenceConfig::drop_in_replacement().live_out, &config)`.
+impl EquivalenceConfig {
+    /// The rewrite may replace the target anywhere: every register and
+    /// NZCV must match, so no scratch register or flag may be clobbered.
+    pub fn drop_in_replacement() -> Self {
+        Self::with_live_out(LiveOut::all_registers()).with_flags(true)
+    }
+
+    /// Only the registers in `live_out` must match; any other register
+    /// and the flags are free to differ. A flags bit already set on
+    /// `live_out` is cleared.
+    pub fn value_only(live_out: LiveOut) -> Self {
+        Self::with_live_out(live_out.with_flags(false))
+    }
+}
+
 /// Check if two instruction sequences are semantically equivalent
 ///
 /// Returns true if for all possible initial states, both sequences
 /// produce the same final state.
+#[cfg(feature = "z3")]
 pub fn check_equivalence(seq1: &[Instruction], seq2: &[Instruction]) -> EquivalenceResult {
     // Issue #69: terminator-identity precheck. If either sequence ends in a
     // branch / control-flow instruction, both must end in the SAME terminator
@@ -264,7 +426,7 @@ pub fn check_equivalence(seq1: &[Instruction], seq2: &[Instruction]) -> Equivale
     let (prefix1, terminator1) = split_terminator(seq1);
     let (prefix2, terminator2) = split_terminator(seq2);
     if terminator1 != terminator2 {
-        return EquivalenceResult::NotEquivalentFast(ConcreteMachineState::new_zeroed());
+        return EquivalenceResult::NotEquivalentFast(FastCounterexample::structural());
     }
 
     // Unmasked entry point compares full state including NZCV (see
@@ -313,6 +475,10 @@ pub struct EquivalenceMetrics {
     /// the solver was not invoked (fast path resolved the candidate or the
     /// pre-SMT guard fired).
     pub smt_elapsed: Duration,
+    /// Initial state read from the solver's model when it proved the
+    /// sequences differ (AArch64 only). Memory is not part of the model
+    /// extraction.
+    pub smt_counterexample: Option<ConcreteMachineState>,
 }
 
 /// ISA-specific hooks underneath the generic equivalence control flow.
@@ -347,17 +513,34 @@ pub trait EquivalenceBackend: EquivalenceConfigDefaults + Sized {
         None
     }
 
+    /// `seq` with every instruction that cannot affect the live-out state
+    /// removed, or `None` if the backend cannot tell which those are.
+    fn live_instructions_for(
+        _seq: &[Self::Instruction],
+        _config: &EquivalenceConfigFor<Self>,
+    ) -> Option<Vec<Self::Instruction>> {
+        None
+    }
+
     fn run_fast_path_for(
         seq1: &[Self::Instruction],
         seq2: &[Self::Instruction],
         config: &EquivalenceConfigFor<Self>,
     ) -> Option<EquivalenceResult>;
 
+    #[cfg(feature = "z3")]
     fn build_smt_solver_for(
         seq1: &[Self::Instruction],
         seq2: &[Self::Instruction],
         config: &EquivalenceConfigFor<Self>,
     ) -> z3::Solver;
+
+    /// Concrete initial state from the model of a solver built by
+    /// `build_smt_solver_for` whose `check()` returned `Sat`.
+    #[cfg(feature = "z3")]
+    fn smt_counterexample(_solver: &z3::Solver) -> Option<ConcreteMachineState> {
+        None
+    }
 }
 
 impl EquivalenceBackend for AArch64 {
@@ -366,7 +549,7 @@ impl EquivalenceBackend for AArch64 {
     }
 
     fn terminator_mismatch_result() -> EquivalenceResult {
-        EquivalenceResult::NotEquivalentFast(ConcreteMachineState::new_zeroed())
+        EquivalenceResult::NotEquivalentFast(FastCounterexample::structural())
     }
 
     fn adjust_config_for_sequences(
@@ -392,7 +575,9 @@ impl EquivalenceBackend for AArch64 {
                     );
                 });
             }
-            config.memory_live = true;
+            if config.memory_live == MemLiveOut::None {
+                config.memory_live = MemLiveOut::All;
+            }
             config.fast_only = false;
         }
 
@@ -409,6 +594,13 @@ impl EquivalenceBackend for AArch64 {
         pre_smt_guard(seq1, seq2, config.live_out.flags_live())
     }
 
+    fn live_instructions_for(
+        seq: &[Instruction],
+        config: &EquivalenceConfigFor<Self>,
+    ) -> Option<Vec<Instruction>> {
+        Some(without_dead_instructions(seq, &config.live_out))
+    }
+
     fn run_fast_path_for(
         seq1: &[Instruction],
         seq2: &[Instruction],
@@ -417,6 +609,7 @@ impl EquivalenceBackend for AArch64 {
         run_fast_path(seq1, seq2, config)
     }
 
+    #[cfg(feature = "z3")]
     fn build_smt_solver_for(
         seq1: &[Instruction],
         seq2: &[Instruction],
@@ -424,6 +617,12 @@ impl EquivalenceBackend for AArch64 {
     ) -> z3::Solver {
         build_smt_solver(seq1, seq2, config)
     }
+
+    #[cfg(feature = "z3")]
+    fn smt_counterexample(solver: &z3::Solver) -> Option<ConcreteMachineState> {
+        let model = solver.get_model()?;
+        Some(MachineState::new_symbolic(SMT_INITIAL_STATE_PREFIX).concretize(&model))
+    }
 }
 
 impl EquivalenceBackend for crate::isa::X86_64 {
@@ -458,6 +657,7 @@ impl EquivalenceBackend for crate::isa::X86_64 {
         run_fast_path_x86(seq1, seq2, config, 64)
     }
 
+    #[cfg(feature = "z3")]
     fn build_smt_solver_for(
         seq1: &[crate::isa::x86::X86Instruction],
         seq2: &[crate::isa::x86::X86Instruction],
@@ -499,6 +699,7 @@ impl EquivalenceBackend for crate::isa::X86_32 {
         run_fast_path_x86(seq1, seq2, config, 32)
     }
 
+    #[cfg(feature = "z3")]
     fn build_smt_solver_for(
         seq1: &[crate::isa::x86::X86Instruction],
         seq2: &[crate::isa::x86::X86Instruction],
@@ -570,6 +771,39 @@ fn fast_path_initial_nzcv_variants(
     variants
 }
 
+/// Overwrite the constrained bits of every register named by
+/// `config.input_constraints`, so each test input is a state the caller
+/// says is reachable.
+fn conform_inputs(inputs: &mut [ConcreteMachineState], config: &EquivalenceConfig) {
+    for input in inputs {
+        for constraint in &config.input_constraints {
+            let value = input.get_register(constraint.reg).as_u64();
+            input.set_register(
+                constraint.reg,
+                crate::semantics::state::ConcreteValue::new(constraint.conform(value)),
+            );
+        }
+    }
+}
+
+/// `NotEquivalentFast` for `input` when the two output states differ on the
+/// live-out contract of `config`, `None` when they agree.
+fn fast_refutation(
+    input: &ConcreteMachineState,
+    state1: &ConcreteMachineState,
+    state2: &ConcreteMachineState,
+    config: &EquivalenceConfig,
+) -> Option<EquivalenceResult> {
+    if states_equal_for_live_out(state1, state2, &config.live_out, &config.memory_live) {
+        return None;
+    }
+    Some(EquivalenceResult::NotEquivalentFast(FastCounterexample {
+        input: input.clone(),
+        first_difference: find_first_difference(state1, state2, &config.live_out)
+            .map(|(reg, _, _)| reg),
+    }))
+}
+
 /// Run the fast-path random + edge-case checks. Returns either a
 /// `NotEquivalentFast` refutation, `None` if the fast path passed, or
 /// `Some(Equivalent)` if `fast_only` short-circuits.
@@ -606,24 +840,26 @@ fn run_fast_path(
             0
         },
     };
-    let random_inputs = generate_random_inputs(&random_config);
+    let mut random_inputs = generate_random_inputs(&random_config);
+    conform_inputs(&mut random_inputs, config);
 
     for input in &random_inputs {
         let state1 = apply_sequence_concrete(input.clone(), seq1);
         let state2 = apply_sequence_concrete(input.clone(), seq2);
 
-        if !states_equal_for_live_out(&state1, &state2, live_out_registers, config.memory_live) {
-            return Some(EquivalenceResult::NotEquivalentFast(input.clone()));
+        if let Some(refutation) = fast_refutation(input, &state1, &state2, config) {
+            return Some(refutation);
         }
     }
 
-    let edge_inputs = generate_edge_case_inputs(&input_regs);
+    let mut edge_inputs = generate_edge_case_inputs(&input_regs);
+    conform_inputs(&mut edge_inputs, config);
     for input in &edge_inputs {
         let state1 = apply_sequence_concrete(input.clone(), seq1);
         let state2 = apply_sequence_concrete(input.clone(), seq2);
 
-        if !states_equal_for_live_out(&state1, &state2, live_out_registers, config.memory_live) {
-            return Some(EquivalenceResult::NotEquivalentFast(input.clone()));
+        if let Some(refutation) = fast_refutation(input, &state1, &state2, config) {
+            return Some(refutation);
         }
     }
 
@@ -639,12 +875,13 @@ fn run_fast_path(
     // under `--live-out x0`) also needs the variants for the fast path to
     // catch divergence on the condition-true branch.
     if config.fast_only && (reads_flags_before_writing(seq1) || reads_flags_before_writing(seq2)) {
-        for input in &fast_path_initial_nzcv_variants(&input_regs) {
+        let mut variants = fast_path_initial_nzcv_variants(&input_regs);
+        conform_inputs(&mut variants, config);
+        for input in &variants {
             let state1 = apply_sequence_concrete(input.clone(), seq1);
             let state2 = apply_sequence_concrete(input.clone(), seq2);
-            if !states_equal_for_live_out(&state1, &state2, live_out_registers, config.memory_live)
-            {
-                return Some(EquivalenceResult::NotEquivalentFast(input.clone()));
+            if let Some(refutation) = fast_refutation(input, &state1, &state2, config) {
+                return Some(refutation);
             }
         }
     }
@@ -686,6 +923,59 @@ where
     result
 }
 
+/// Approximate equivalence using only the concrete interpreter.
+///
+/// Runs `num_tests` random inputs plus the edge-case inputs (and, for
+/// flag-reading sequences, every initial NZCV combination) and never builds
+/// a Z3 solver. A counterexample is still a proof of non-equivalence, but
+/// `Equivalent` only means no test input told the two sequences apart —
+/// callers that need a sound verdict must use `check_equivalence_with_config`.
+///
+/// Unlike `fast_only`, memory-touching sequences are not promoted to SMT
+/// (ADR-0007): the memory image is compared concretely like any other
+/// live-out state. The verdict is always `Equivalent` or
+/// `NotEquivalentFast`.
+pub fn check_equivalence_approx(
+    seq1: &[Instruction],
+    seq2: &[Instruction],
+    live_out: &LiveOut,
+    num_tests: usize,
+) -> EquivalenceResult {
+    let config = EquivalenceConfig::with_live_out(live_out.clone()).random_tests(num_tests);
+    check_equivalence_approx_for::<AArch64>(seq1, seq2, &config)
+}
+
+/// ISA-generic variant of `check_equivalence_approx`. `config.fast_only` and
+/// `config.smt_timeout` are ignored: this path never reaches the solver.
+pub fn check_equivalence_approx_for<I>(
+    seq1: &[I::Instruction],
+    seq2: &[I::Instruction],
+    config: &EquivalenceConfigFor<I>,
+) -> EquivalenceResult
+where
+    I: EquivalenceBackend,
+{
+    let (prefix1, terminator1) = I::split_terminator(seq1);
+    let (prefix2, terminator2) = I::split_terminator(seq2);
+    if terminator1 != terminator2 {
+        return I::terminator_mismatch_result();
+    }
+
+    // Adjust with `fast_only` cleared so the memory carve-out stays silent,
+    // then switch it on to get the widened input-register mask.
+    let mut effective_config = EquivalenceConfigFor {
+        fast_only: false,
+        ..config.clone()
+    };
+    I::adjust_config_for_sequences(&mut effective_config, prefix1, prefix2, terminator1);
+    effective_config.fast_only = true;
+
+    // No `pre_smt_guard_for`: its `NotEquivalent` reads as a solver verdict,
+    // and every refutation here must carry a concrete counterexample.
+    I::run_fast_path_for(prefix1, prefix2, &effective_config)
+        .unwrap_or(EquivalenceResult::Equivalent)
+}
+
 /// Issue #240: derive the prefix's effective live-out contract from the
 /// caller-supplied config and the stripped trailing terminator.
 ///
@@ -705,14 +995,10 @@ fn augment_config_for_terminator(
     config: &EquivalenceConfig,
     terminator: &Instruction,
 ) -> EquivalenceConfig {
-    let mut live_out = config.live_out.clone();
-    for reg in terminator.source_registers() {
-        live_out.add(reg);
-    }
-    let flags_live = live_out.flags_live() || terminator.reads_flags();
-    let live_out = live_out.with_flags(flags_live);
+    let terminator_inputs =
+        LiveOut::from_registers(terminator.source_registers()).with_flags(terminator.reads_flags());
     EquivalenceConfig {
-        live_out,
+        live_out: config.live_out.union(&terminator_inputs),
         ..config.clone()
     }
 }
@@ -753,6 +1039,16 @@ where
 
     let mut effective_config = config.clone();
     I::adjust_config_for_sequences(&mut effective_config, prefix1, prefix2, terminator1);
+    // Without a solver the concrete tests are the whole verdict, so run them
+    // with the widened `fast_only` inputs, as `check_equivalence_approx` does.
+    #[cfg(not(feature = "z3"))]
+    {
+        effective_config.fast_only = true;
+    }
+
+    if prefix1 == prefix2 {
+        return (EquivalenceResult::Equivalent, metrics);
+    }
 
     if let Some(early) = I::pre_smt_guard_for(prefix1, prefix2, &effective_config) {
         return (
@@ -764,11 +1060,34 @@ where
         );
     }
 
+    // Sequences that differ only in dead instructions compute the same
+    // live-out state by construction; no inputs or solver needed.
+    if let Some(live1) = I::live_instructions_for(prefix1, &effective_config)
+        && let Some(live2) = I::live_instructions_for(prefix2, &effective_config)
+        && live1 == live2
+    {
+        return (EquivalenceResult::Equivalent, metrics);
+    }
+
     if let Some(fast) = I::run_fast_path_for(prefix1, prefix2, &effective_config) {
         return (fast, metrics);
     }
 
-    let solver = I::build_smt_solver_for(prefix1, prefix2, &effective_config);
+    solve_for::<I>(prefix1, prefix2, &effective_config)
+}
+
+/// Final stage of `check_equivalence_for_metrics`: ask Z3 whether any input
+/// tells the two (terminator-free) prefixes apart.
+#[cfg(feature = "z3")]
+fn solve_for<I>(
+    prefix1: &[I::Instruction],
+    prefix2: &[I::Instruction],
+    effective_config: &EquivalenceConfigFor<I>,
+) -> (EquivalenceResult, EquivalenceMetrics)
+where
+    I: EquivalenceBackend,
+{
+    let solver = I::build_smt_solver_for(prefix1, prefix2, effective_config);
     let smt_start = std::time::Instant::now();
     let sat_result = solver.check();
     let smt_elapsed = smt_start.elapsed();
@@ -777,6 +1096,11 @@ where
     } else {
         None
     };
+    let smt_counterexample = if sat_result == SatResult::Sat {
+        I::smt_counterexample(&solver)
+    } else {
+        None
+    };
     let result = interpret_smt_result(sat_result);
     (
         result,
@@ -784,13 +1108,37 @@ where
             smt_called: true,
             smt_formula_bytes,
             smt_elapsed,
+            smt_counterexample,
             ..EquivalenceMetrics::default()
         },
     )
 }
 
+/// Without the `z3` feature there is no solver to consult: prefixes that
+/// survived the concrete fast path are reported `Equivalent`. This is the
+/// same unsound verdict as `check_equivalence_approx` — a rewrite is only
+/// as trustworthy as the random and edge-case inputs that failed to refute
+/// it.
+#[cfg(not(feature = "z3"))]
+fn solve_for<I>(
+    _prefix1: &[I::Instruction],
+    _prefix2: &[I::Instruction],
+    _effective_config: &EquivalenceConfigFor<I>,
+) -> (EquivalenceResult, EquivalenceMetrics)
+where
+    I: EquivalenceBackend,
+{
+    (EquivalenceResult::Equivalent, EquivalenceMetrics::default())
+}
+
+/// Name prefix of the symbolic initial state in `build_smt_solver`; models
+/// are read back through a state rebuilt with the same constant names.
+#[cfg(feature = "z3")]
+const SMT_INITIAL_STATE_PREFIX: &str = "init";
+
 /// Build a Z3 solver populated with the assertion that the two sequences
 /// disagree on the live-out state. Caller invokes `check()` next.
+#[cfg(feature = "z3")]
 fn build_smt_solver(
     seq1: &[Instruction],
     seq2: &[Instruction],
@@ -801,7 +1149,13 @@ fn build_smt_solver(
     };
     let solver = create_solver_with_config(&solver_config);
 
-    let initial_state = MachineState::new_symbolic("init");
+    let initial_state = MachineState::new_symbolic(SMT_INITIAL_STATE_PREFIX);
+    for constraint in &config.input_constraints {
+        let reg = initial_state.get_register(constraint.reg);
+        let mask = z3::ast::BV::from_u64(constraint.mask, 64);
+        let value = z3::ast::BV::from_u64(constraint.value & constraint.mask, 64);
+        solver.assert(reg.bvand(&mask).eq(value));
+    }
     let final_state1 = apply_sequence(initial_state.clone(), seq1);
     let final_state2 = apply_sequence(initial_state, seq2);
 
@@ -818,11 +1172,12 @@ fn build_smt_solver(
         &final_state1,
         &final_state2,
         &config.live_out,
-        config.memory_live,
+        &config.memory_live,
     ));
     solver
 }
 
+#[cfg(feature = "z3")]
 fn interpret_smt_result(result: SatResult) -> EquivalenceResult {
     match result {
         SatResult::Unsat => EquivalenceResult::Equivalent,
@@ -839,6 +1194,7 @@ fn interpret_smt_result(result: SatResult) -> EquivalenceResult {
 /// where register is the first differing register and value1/value2
 /// are the values in the respective final states.
 #[allow(dead_code)]
+#[cfg(feature = "z3")]
 pub fn find_counterexample(
     seq1: &[Instruction],
     seq2: &[Instruction],
@@ -901,7 +1257,8 @@ pub fn find_counterexample_concrete(
             0
         },
     };
-    let random_inputs = generate_random_inputs(&random_config);
+    let mut random_inputs = generate_random_inputs(&random_config);
+    conform_inputs(&mut random_inputs, config);
 
     for input in &random_inputs {
         let state1 = apply_sequence_concrete(input.clone(), seq1);
@@ -912,7 +1269,8 @@ pub fn find_counterexample_concrete(
         }
     }
 
-    let edge_inputs = generate_edge_case_inputs(&input_regs);
+    let mut edge_inputs = generate_edge_case_inputs(&input_regs);
+    conform_inputs(&mut edge_inputs, config);
     for input in &edge_inputs {
         let state1 = apply_sequence_concrete(input.clone(), seq1);
         let state2 = apply_sequence_concrete(input.clone(), seq2);
@@ -929,6 +1287,7 @@ pub fn find_counterexample_concrete(
 // x86 equivalence checking
 // ============================================================================
 
+#[cfg(feature = "z3")]
 fn build_smt_solver_x86<I>(
     seq1: &[crate::isa::x86::X86Instruction],
     seq2: &[crate::isa::x86::X86Instruction],
@@ -1118,6 +1477,59 @@ mod tests {
     use crate::isa::x86::{X86Instruction, X86Register};
     use crate::semantics::live_out::X86LiveOut;
 
+    #[test]
+    fn memory_live_ranges_limit_the_compared_bytes() {
+        use crate::ir::types::{AccessWidth, AddressOperand, IndexMode};
+        let mov_base = Instruction::MovImm {
+            rd: Register::X2,
+            imm: 0x1000,
+        };
+        let store = |offset| Instruction::Str {
+            rt: Register::X1,
+            addr: AddressOperand::Imm {
+                base: Register::X2,
+                offset,
+                mode: IndexMode::Offset,
+            },
+            width: AccessWidth::Extended,
+        };
+        let target = [mov_base, store(0), store(16)];
+        let candidate = [mov_base, store(0)];
+        let config = EquivalenceConfig::with_live_out(LiveOut::from_registers(vec![]));
+
+        // Auto-derived whole-memory contract sees the second store.
+        assert!(matches!(
+            check_equivalence_with_config(&target, &candidate, &config),
+            EquivalenceResult::NotEquivalent | EquivalenceResult::NotEquivalentFast(_)
+        ));
+        // The second store lands between the live slots: dead.
+        let first_slot = config
+            .clone()
+            .with_memory_ranges(vec![0x1000..0x1008, 0x1020..0x1028])
+            .unwrap();
+        assert_eq!(
+            check_equivalence_with_config(&target, &candidate, &first_slot),
+            EquivalenceResult::Equivalent
+        );
+        let both_slots = config
+            .clone()
+            .with_memory_ranges(vec![0x1000..0x1008, 0x1010..0x1018])
+            .unwrap();
+        assert!(matches!(
+            check_equivalence_with_config(&target, &candidate, &both_slots),
+            EquivalenceResult::NotEquivalent | EquivalenceResult::NotEquivalentFast(_)
+        ));
+    }
+
+    #[test]
+    #[allow(clippy::reversed_empty_ranges)]
+    fn reversed_memory_live_range_is_an_error() {
+        let err = EquivalenceConfig::default()
+            .with_memory_ranges(vec![0x1000..0x1008, 0x2000..0x1000])
+            .unwrap_err();
+        assert!(err.contains("0x2000..0x1000"), "{err}");
+    }
+
     #[test]
     fn aarch64_with_flags_writes_through_mask() {
         // After moving `flags_live` from `EquivalenceConfig` onto the mask,
@@ -1129,6 +1541,47 @@ mod tests {
         assert!(!config.live_out.flags_live());
     }
 
+    #[test]
+    fn presets_differ_only_in_what_may_be_clobbered() {
+        let target = [Instruction::Add {
+            rd: Register::X0,
+            rn: Register::X1,
+            rm: Operand::Immediate(1),
+        }];
+        let clobbers_x2 = [
+            Instruction::MovImm {
+                rd: Register::X2,
+                imm: 0,
+            },
+            target[0],
+        ];
+        let clobbers_flags = [Instruction::Adds {
+            rd: Register::X0,
+            rn: Register::X1,
+            rm: Operand::Immediate(1),
+        }];
+
+        let strict = EquivalenceConfig::drop_in_replacement();
+        assert!(strict.live_out.contains(Register::X2) && strict.live_out.flags_live());
+        let loose = EquivalenceConfig::value_only(
+            LiveOut::from_registers(vec![Register::X0]).with_flags(true),
+        );
+        assert!(!loose.live_out.contains(Register::X2) && !loose.live_out.flags_live());
+
+        for candidate in [&clobbers_x2[..], &clobbers_flags[..]] {
+            assert_ne!(
+                check_equivalence_with_config(&target, candidate, &strict),
+                EquivalenceResult::Equivalent,
+                "{candidate:?}"
+            );
+            assert_eq!(
+                check_equivalence_with_config(&target, candidate, &loose),
+                EquivalenceResult::Equivalent,
+                "{candidate:?}"
+            );
+        }
+    }
+
     #[test]
     fn neon_add_and_extract_proves_equivalent_to_scalar_lane_add() {
         let scalar = [
@@ -1475,6 +1928,84 @@ mod tests {
         assert!(metrics.smt_formula_bytes.is_none());
     }
 
+    #[test]
+    fn identical_sequences_are_equivalent_without_testing() {
+        let seq = [Instruction::Add {
+            rd: Register::X0,
+            rn: Register::X1,
+            rm: Operand::Register(Register::X2),
+        }];
+        // Not fast-only, so anything but the short-circuit would reach Z3.
+        let config = EquivalenceConfig::default();
+
+        let (result, metrics) = check_equivalence_for_metrics::<AArch64>(&seq, &seq, &config);
+
+        assert_eq!(result, EquivalenceResult::Equivalent);
+        assert!(!metrics.smt_called);
+        assert!(!metrics.flag_guard_rejected);
+    }
+
+    #[test]
+    fn sequences_differing_in_dead_instructions_are_equivalent_without_testing() {
+        let add = Instruction::Add {
+            rd: Register::X0,
+            rn: Register::X1,
+            rm: Operand::Immediate(1),
+        };
+        let dead_mov = Instruction::MovImm {
+            rd: Register::X2,
+            imm: 5,
+        };
+        // The first write to x0 is overwritten before it is read.
+        let overwritten = Instruction::MovReg {
+            rd: Register::X0,
+            rn: Register::X3,
+        };
+        let config =
+            EquivalenceConfig::default().live_out(LiveOut::from_registers(vec![Register::X0]));
+
+        let (result, metrics) = check_equivalence_for_metrics::<AArch64>(
+            &[dead_mov, overwritten, add],
+            &[add],
+            &config,
+        );
+        assert_eq!(result, EquivalenceResult::Equivalent);
+        assert!(!metrics.smt_called);
+
+        // Once x2 is observed the MOV is no longer dead, and the two differ.
+        let config = EquivalenceConfig::default()
+            .live_out(LiveOut::from_registers(vec![Register::X0, Register::X2]));
+        let (result, _) =
+            check_equivalence_for_metrics::<AArch64>(&[dead_mov, add], &[add], &config);
+        assert_ne!(result, EquivalenceResult::Equivalent);
+    }
+
+    #[test]
+    fn dead_instruction_sweep_keeps_partial_writes_and_live_flags() {
+        // MOVK reads the register it updates, so the MOVZ feeding it stays.
+        let seq = [
+            Instruction::MovImm {
+                rd: Register::X0,
+                imm: 0x1234,
+            },
+            Instruction::MovK {
+                rd: Register::X0,
+                imm: 1,
+                shift: 16,
+            },
+            Instruction::Cmp {
+                rn: Register::X1,
+                rm: Operand::Immediate(0),
+            },
+        ];
+        let live_out = LiveOut::from_registers(vec![Register::X0]);
+        assert_eq!(without_dead_instructions(&seq, &live_out), seq[..2]);
+        assert_eq!(
+            without_dead_instructions(&seq, &live_out.with_flags(true)),
+            seq
+        );
+    }
+
     fn assert_x86_fast_path_refutes_edge_case_only_divergence<I>()
     where
         I: EquivalenceBackend<Instruction = X86Instruction, Register = X86Register>,
@@ -2348,6 +2879,170 @@ mod tests {
         );
     }
 
+    #[test]
+    fn approx_agrees_with_smt_on_known_pairs() {
+        let live_out = LiveOut::from_registers(vec![Register::X0]);
+        let pairs: Vec<(Vec<Instruction>, Vec<Instruction>)> = vec![
+            (
+                vec![Instruction::MovImm {
+                    rd: Register::X0,
+                    imm: 0,
+                }],
+                vec![Instruction::Eor {
+                    rd: Register::X0,
+                    rn: Register::X1,
+                    rm: Operand::Register(Register::X1),
+                    width: crate::ir::RegisterWidth::X64,
+                }],
+            ),
+            (
+                vec![Instruction::Add {
+                    rd: Register::X0,
+                    rn: Register::X1,
+                    rm: Operand::Register(Register::X1),
+                }],
+                vec![Instruction::Lsl {
+                    rd: Register::X0,
+                    rn: Register::X1,
+                    shift: Operand::Immediate(1),
+                }],
+            ),
+            (
+                vec![Instruction::Add {
+                    rd: Register::X0,
+                    rn: Register::X1,
+                    rm: Operand::Immediate(1),
+                }],
+                vec![Instruction::Add {
+                    rd: Register::X0,
+                    rn: Register::X1,
+                    rm: Operand::Immediate(2),
+                }],
+            ),
+            (
+                vec![Instruction::Sub {
+                    rd: Register::X0,
+                    rn: Register::X1,
+                    rm: Operand::Register(Register::X2),
+                }],
+                vec![Instruction::Sub {
+                    rd: Register::X0,
+                    rn: Register::X2,
+                    rm: Operand::Register(Register::X1),
+                }],
+            ),
+            (
+                vec![
+                    Instruction::MovReg {
+                        rd: Register::X3,
+                        rn: Register::X1,
+                    },
+                    Instruction::Add {
+                        rd: Register::X0,
+                        rn: Register::X3,
+                        rm: Operand::Register(Register::X2),
+                    },
+                ],
+                vec![Instruction::Add {
+                    rd: Register::X0,
+                    rn: Register::X2,
+                    rm: Operand::Register(Register::X1),
+                }],
+            ),
+        ];
+
+        let smt_config = EquivalenceConfig::with_live_out(live_out.clone());
+        for (seq1, seq2) in &pairs {
+            let exact = check_equivalence_with_config(seq1, seq2, &smt_config);
+            let approx = check_equivalence_approx(seq1, seq2, &live_out, 64);
+            assert_eq!(
+                matches!(exact, EquivalenceResult::Equivalent),
+                matches!(approx, EquivalenceResult::Equivalent),
+                "approx verdict {approx:?} disagrees with SMT verdict {exact:?} for {seq1:?} vs {seq2:?}",
+            );
+            assert!(matches!(
+                approx,
+                EquivalenceResult::Equivalent | EquivalenceResult::NotEquivalentFast(_)
+            ));
+        }
+    }
+
+    #[test]
+    fn approx_refutes_a_flag_writer_divergence_with_a_counterexample() {
+        // The pre-SMT guard would answer `NotEquivalent` here without a
+        // witness; the approximate path must find one concretely instead.
+        let live_out = LiveOut::from_registers(vec![Register::X0]).with_flags(true);
+        let adds = vec![Instruction::Adds {
+            rd: Register::X0,
+            rn: Register::X1,
+            rm: Operand::Immediate(0),
+        }];
+        let add = vec![Instruction::Add {
+            rd: Register::X0,
+            rn: Register::X1,
+            rm: Operand::Immediate(0),
+        }];
+
+        let approx = check_equivalence_approx(&adds, &add, &live_out, 64);
+        let EquivalenceResult::NotEquivalentFast(counterexample) = approx else {
+            panic!("expected a concrete refutation, got {approx:?}");
+        };
+        assert_eq!(counterexample.first_difference, Some(Register::XZR));
+    }
+
+    /// Soundness contract of the solver-free path: over a seeded batch of
+    /// random short pairs, `check_equivalence_approx` never calls a pair
+    /// `Equivalent` that Z3 refutes, and never refutes a pair Z3 proves.
+    #[test]
+    fn approx_never_contradicts_smt_on_random_pairs() {
+        use crate::search::candidate::generate_random_sequence;
+        use rand::{RngExt, SeedableRng};
+
+        let registers = [Register::X0, Register::X1, Register::X2];
+        let immediates = [0, 1, 2, 3, 63, 255];
+        let live_out = LiveOut::from_registers(vec![Register::X0]);
+        let smt_config = EquivalenceConfig::with_live_out(live_out.clone());
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(605);
+
+        let (mut proved, mut refuted) = (0, 0);
+        for _ in 0..80 {
+            let len1 = rng.random_range(1..=2);
+            let len2 = rng.random_range(1..=2);
+            let seq1 = generate_random_sequence(&mut rng, len1, &registers, &immediates);
+            let seq2 = generate_random_sequence(&mut rng, len2, &registers, &immediates);
+            if seq1.iter().chain(&seq2).any(Instruction::is_memory_op) {
+                continue;
+            }
+
+            let exact = check_equivalence_with_config(&seq1, &seq2, &smt_config);
+            let approx = check_equivalence_approx(&seq1, &seq2, &live_out, 256);
+            match exact {
+                EquivalenceResult::Equivalent => {
+                    proved += 1;
+                    assert_eq!(
+                        approx,
+                        EquivalenceResult::Equivalent,
+                        "approx refuted SMT-proved pair {seq1:?} vs {seq2:?}"
+                    );
+                }
+                EquivalenceResult::NotEquivalent | EquivalenceResult::NotEquivalentFast(_) => {
+                    refuted += 1;
+                    assert_ne!(
+                        approx,
+                        EquivalenceResult::Equivalent,
+                        "approx accepted SMT-refuted pair {seq1:?} vs {seq2:?}"
+                    );
+                }
+                EquivalenceResult::Unknown(_) => {}
+            }
+        }
+        // The batch must exercise both directions of the contract.
+        assert!(
+            proved > 10 && refuted > 10,
+            "proved {proved}, refuted {refuted}"
+        );
+    }
+
     #[test]
     fn test_add_commutativity() {
         let seq1 = vec![Instruction::Add {
@@ -2635,6 +3330,60 @@ mod tests {
         assert_eq!(v2.as_u64(), 10);
     }
 
+    #[test]
+    fn input_constraint_conform_satisfies_holds() {
+        let non_negative = InputConstraint::non_negative(Register::X0);
+        assert_eq!(non_negative.conform(u64::MAX), i64::MAX as u64);
+        assert!(non_negative.holds(0) && !non_negative.holds(1 << 63));
+
+        let aligned = InputConstraint::aligned(Register::X0, 8);
+        assert_eq!(aligned.conform(0x1f), 0x18);
+        assert!(aligned.holds(0x20) && !aligned.holds(0x1c));
+
+        let equals = InputConstraint::equals(Register::X0, 42);
+        assert_eq!(equals.conform(7), 42);
+        assert!(equals.holds(42) && !equals.holds(43));
+    }
+
+    #[test]
+    #[should_panic(expected = "power of two")]
+    fn input_constraint_rejects_non_power_of_two_alignment() {
+        InputConstraint::aligned(Register::X0, 6);
+    }
+
+    #[test]
+    fn asr_matches_lsr_only_under_a_non_negative_input_constraint() {
+        let shift = |lsr: bool| {
+            let (rd, rn, shift) = (Register::X0, Register::X0, Operand::Immediate(1));
+            vec![if lsr {
+                Instruction::Lsr { rd, rn, shift }
+            } else {
+                Instruction::Asr { rd, rn, shift }
+            }]
+        };
+        let config = EquivalenceConfig::with_live_out(LiveOut::from_registers(vec![Register::X0]));
+        assert!(!matches!(
+            check_equivalence_with_config(&shift(false), &shift(true), &config),
+            EquivalenceResult::Equivalent
+        ));
+
+        let constrained =
+            config.with_input_constraints(vec![InputConstraint::non_negative(Register::X0)]);
+        assert_eq!(
+            check_equivalence_with_config(&shift(false), &shift(true), &constrained),
+            EquivalenceResult::Equivalent
+        );
+        // Without random tests the edge-case inputs (conformed to x0 >= 0)
+        // all agree, so the SMT query under the constraint decides.
+        let (verdict, metrics) = check_equivalence_with_config_metrics(
+            &shift(false),
+            &shift(true),
+            &constrained.random_tests(0),
+        );
+        assert_eq!(verdict, EquivalenceResult::Equivalent);
+        assert!(metrics.smt_called);
+    }
+
     // --- Tier 1 algebraic identities --------------------------------------
 
     #[test]
@@ -4137,4 +4886,40 @@ mod tests {
             "smt_elapsed must be zero on fast-path rejection"
         );
     }
+
+    #[test]
+    fn fast_refutation_carries_input_and_first_differing_register() {
+        use crate::ir::Operand;
+
+        let seq1 = vec![Instruction::Add {
+            rd: Register::X2,
+            rn: Register::X1,
+            rm: Operand::Immediate(1),
+        }];
+        let seq2 = vec![Instruction::Add {
+            rd: Register::X2,
+            rn: Register::X1,
+            rm: Operand::Immediate(2),
+        }];
+        let live_out = LiveOut::from_registers(vec![Register::X0, Register::X1, Register::X2]);
+        let cfg = EquivalenceConfig::default().live_out(live_out.clone());
+
+        let EquivalenceResult::NotEquivalentFast(counterexample) =
+            check_equivalence_with_config(&seq1, &seq2, &cfg)
+        else {
+            panic!("expected a fast-path refutation");
+        };
+        assert_eq!(counterexample.first_difference, Some(Register::X2));
+
+        let out1 = apply_sequence_concrete(counterexample.input.clone(), &seq1);
+        let out2 = apply_sequence_concrete(counterexample.input.clone(), &seq2);
+        assert_ne!(
+            out1.get_register(Register::X2),
+            out2.get_register(Register::X2)
+        );
+        assert_eq!(
+            find_first_difference(&out1, &out2, &live_out).map(|(reg, _, _)| reg),
+            counterexample.first_difference
+        );
+    }
 }
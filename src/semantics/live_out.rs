@@ -15,6 +15,7 @@ use crate::ir::Register;
 use crate::isa::RegisterType;
 use std::collections::HashSet;
 use std::fmt;
+use std::ops::Range;
 
 /// Generic live-out mask parameterised on register type.
 ///
@@ -52,7 +53,6 @@ impl<R: RegisterType> RegisterSet<R> {
     }
 
     /// Remove a register from the set.
-    #[allow(dead_code)]
     pub fn remove(&mut self, reg: R) {
         self.regs.remove(&reg);
     }
@@ -96,6 +96,25 @@ impl<R: RegisterType> RegisterSet<R> {
         self.flags_live = flags_live;
         self
     }
+
+    /// Registers live in either set; flags are live if either has them live.
+    /// Registers from `other` go through `add`, so zero registers are dropped.
+    pub fn union(&self, other: &Self) -> Self {
+        let mut merged = self.clone();
+        for reg in other.iter() {
+            merged.add(*reg);
+        }
+        merged.flags_live |= other.flags_live;
+        merged
+    }
+
+    /// Registers live in both sets; flags are live only if both have them live.
+    pub fn intersect(&self, other: &Self) -> Self {
+        Self {
+            regs: self.regs.intersection(&other.regs).copied().collect(),
+            flags_live: self.flags_live && other.flags_live,
+        }
+    }
 }
 
 impl RegisterSet<Register> {
@@ -124,6 +143,55 @@ impl RegisterSet<Register> {
     }
 }
 
+/// Which memory a concrete live-out comparison inspects: the memory
+/// counterpart of the register set.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum MemLiveOut {
+    /// Memory is not part of the contract.
+    #[default]
+    None,
+    /// Every byte must agree; what equivalence checking selects for a
+    /// memory-touching pair left at `None`.
+    All,
+    /// Only bytes in these half-open address ranges must agree. Build it with
+    /// [`MemLiveOut::ranges`]; a reversed range covers no byte.
+    Ranges(Vec<Range<u64>>),
+}
+
+impl MemLiveOut {
+    /// `Ranges(ranges)`, or an error naming the first range whose start lies
+    /// past its end.
+    pub fn ranges(ranges: Vec<Range<u64>>) -> Result<Self, String> {
+        if let Some(range) = ranges.iter().find(|range| range.start > range.end) {
+            return Err(format!(
+                "memory live-out range {:#x}..{:#x} starts past its end",
+                range.start, range.end
+            ));
+        }
+        Ok(MemLiveOut::Ranges(ranges))
+    }
+
+    /// True if the byte at `addr` is part of the contract.
+    pub fn contains(&self, addr: u64) -> bool {
+        match self {
+            MemLiveOut::None => false,
+            MemLiveOut::All => true,
+            MemLiveOut::Ranges(ranges) => ranges.iter().any(|range| range.contains(&addr)),
+        }
+    }
+}
+
+impl From<bool> for MemLiveOut {
+    /// `All` when memory is live, `None` otherwise.
+    fn from(memory_live: bool) -> Self {
+        if memory_live {
+            MemLiveOut::All
+        } else {
+            MemLiveOut::None
+        }
+    }
+}
+
 /// Self-documenting Display for the neutral register-set carrier.
 ///
 /// `RegisterSet` is used for both live-in and live-out contexts, so the
@@ -234,6 +302,48 @@ mod tests {
         assert!(mask.flags_live());
     }
 
+    #[test]
+    fn union_and_intersect_combine_registers_and_flags() {
+        let a = LiveOut::from_registers(vec![Register::X0, Register::X1]).with_flags(true);
+        let b = LiveOut::from_registers(vec![Register::X1, Register::X2]);
+
+        let union = a.union(&b);
+        assert_eq!(union.len(), 3);
+        assert!(
+            [Register::X0, Register::X1, Register::X2]
+                .into_iter()
+                .all(|reg| union.contains(reg))
+        );
+        assert!(union.flags_live());
+        assert_eq!(union, b.union(&a));
+
+        let intersection = a.intersect(&b);
+        assert_eq!(intersection, LiveOut::from_registers(vec![Register::X1]));
+        assert!(!intersection.flags_live());
+        assert_eq!(intersection, b.intersect(&a));
+    }
+
+    #[test]
+    fn union_is_idempotent_and_empty_is_its_identity() {
+        let a = LiveOut::from_registers(vec![Register::X0, Register::X3]).with_flags(true);
+        let empty = LiveOut::empty();
+
+        assert_eq!(a.union(&a), a);
+        assert_eq!(a.intersect(&a), a);
+        assert_eq!(a.union(&empty), a);
+        assert_eq!(empty.union(&a), a);
+        assert_eq!(a.intersect(&empty), empty);
+        assert_eq!(empty.union(&empty), empty);
+    }
+
+    #[test]
+    fn union_drops_zero_registers_like_add() {
+        let with_xzr = LiveOut::from_registers(vec![Register::XZR, Register::X0]);
+        let union = LiveOut::empty().union(&with_xzr);
+        assert!(!union.contains(Register::XZR));
+        assert_eq!(union, LiveOut::from_registers(vec![Register::X0]));
+    }
+
     #[test]
     fn test_live_out_display_single_register() {
         let live_out = LiveOut::from_registers(vec![Register::X0]);
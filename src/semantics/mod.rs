@@ -6,7 +6,9 @@ pub mod cost;
 pub mod cost_x86;
 pub mod equivalence;
 pub mod live_out;
+#[cfg(feature = "z3")]
 pub mod smt;
+#[cfg(feature = "z3")]
 pub mod smt_x86;
 pub mod state;
 
@@ -17,8 +19,11 @@ pub use concrete::apply_sequence_concrete;
 pub use equivalence::{EquivalenceConfig, EquivalenceResult, check_equivalence_with_config};
 
 #[allow(unused_imports)]
-pub use equivalence::{EquivalenceMetrics, check_equivalence_with_config_metrics};
+pub use equivalence::{
+    EquivalenceMetrics, FastCounterexample, InputConstraint, check_equivalence_approx,
+    check_equivalence_with_config_metrics,
+};
 #[allow(unused_imports)]
-pub use live_out::{LiveOut, RegisterSet};
+pub use live_out::{LiveOut, MemLiveOut, RegisterSet};
 #[allow(unused_imports)]
 pub use state::{ConcreteMachineState, ConcreteValue, ConditionFlags};
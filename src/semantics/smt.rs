@@ -6,7 +6,8 @@ use crate::ir::types::{AccessWidth, AddressOperand, IndexMode};
 use crate::ir::{
     ExtendKind, Instruction, Operand, Register, RegisterWidth, VectorArrangement, VectorRegister,
 };
-use crate::semantics::live_out::RegisterSet;
+use crate::semantics::live_out::{MemLiveOut, RegisterSet};
+use crate::semantics::state::{ConcreteMachineState, ConcreteValue, ConditionFlags};
 use std::collections::HashMap;
 use std::time::Duration;
 use z3::ast::{Array, BV};
@@ -308,6 +309,33 @@ impl MachineState {
         self.width
     }
 
+    /// Read this state's register, vector and flag values out of `model`,
+    /// typically the symbolic initial state of a satisfiable
+    /// non-equivalence query. Memory is not extracted: the returned state
+    /// starts from an empty memory image.
+    pub fn concretize(&self, model: &z3::Model) -> ConcreteMachineState {
+        let eval_u64 = |bv: &BV| model.eval(bv, true).and_then(|v| v.as_u64()).unwrap_or(0);
+
+        let mut state = ConcreteMachineState::new_zeroed();
+        for (reg, value) in &self.registers {
+            if *reg != Register::XZR {
+                state.set_register(*reg, ConcreteValue::new(eval_u64(value)));
+            }
+        }
+        for (reg, value) in &self.vectors {
+            let low = eval_u64(&value.extract(63, 0));
+            let high = eval_u64(&value.extract(127, 64));
+            state.set_vector(*reg, (u128::from(high) << 64) | u128::from(low));
+        }
+        state.set_flags(ConditionFlags {
+            n: eval_u64(&self.n) != 0,
+            z: eval_u64(&self.z) != 0,
+            c: eval_u64(&self.c) != 0,
+            v: eval_u64(&self.v) != 0,
+        });
+        state
+    }
+
     /// Get the value of a register
     pub fn get_register(&self, reg: Register) -> &BV {
         self.registers.get(&reg).expect("Register not found")
@@ -1425,14 +1453,19 @@ pub fn states_not_equal(state1: &MachineState, state2: &MachineState) -> z3::ast
     z3::ast::Bool::or(&[&not_equal, &state1.memory.eq(&state2.memory).not()])
 }
 
+/// Most live bytes compared one by one under `MemLiveOut::Ranges`; wider
+/// ranges compare the whole memory image instead, which is stricter but
+/// keeps the query small.
+const MAX_LIVE_MEMORY_BYTES: u64 = 256;
+
 /// Check if two machine states are not equal for the specified live-out
 /// contract, including the NZCV flag bits when `live_out.flags_live()` is set
-/// and the whole memory image when `memory_live` is set (see ADR-0007).
+/// and the memory `memory_live` selects (see ADR-0007).
 pub fn states_not_equal_for_live_out(
     state1: &MachineState,
     state2: &MachineState,
     live_out: &RegisterSet<Register>,
-    memory_live: bool,
+    memory_live: &MemLiveOut,
 ) -> z3::ast::Bool {
     let mut not_equal = z3::ast::Bool::from_bool(false);
 
@@ -1451,8 +1484,26 @@ pub fn states_not_equal_for_live_out(
         not_equal = z3::ast::Bool::or(&[&not_equal, &flags_not_equal(state1, state2)]);
     }
 
-    if memory_live {
-        not_equal = z3::ast::Bool::or(&[&not_equal, &state1.memory.eq(&state2.memory).not()]);
+    let whole_memory_not_equal = || state1.memory.eq(&state2.memory).not();
+    match memory_live {
+        MemLiveOut::None => {}
+        MemLiveOut::All => {
+            not_equal = z3::ast::Bool::or(&[&not_equal, &whole_memory_not_equal()]);
+        }
+        MemLiveOut::Ranges(ranges) => {
+            let live = ranges.iter().filter(|range| range.start < range.end);
+            let live_bytes: u64 = live.clone().map(|range| range.end - range.start).sum();
+            if live_bytes > MAX_LIVE_MEMORY_BYTES {
+                not_equal = z3::ast::Bool::or(&[&not_equal, &whole_memory_not_equal()]);
+            } else {
+                for addr in live.flat_map(|range| range.clone()) {
+                    let addr = BV::from_u64(addr, 64);
+                    let byte1 = state1.memory.select(&addr);
+                    let byte2 = state2.memory.select(&addr);
+                    not_equal = z3::ast::Bool::or(&[&not_equal, &byte1.eq(&byte2).not()]);
+                }
+            }
+        }
     }
 
     not_equal
@@ -1791,8 +1842,12 @@ mod tests {
         let live_out = RegisterSet::<Register>::from_registers(vec![Register::X0]);
 
         let solver = Solver::new();
-        let diseq =
-            states_not_equal_for_live_out(&state_cls, &state_signfold_clz, &live_out, false);
+        let diseq = states_not_equal_for_live_out(
+            &state_cls,
+            &state_signfold_clz,
+            &live_out,
+            &MemLiveOut::None,
+        );
         solver.assert(diseq);
         assert_eq!(
             solver.check(),
@@ -1809,7 +1864,10 @@ mod tests {
 
         let solver = Solver::new();
         solver.assert(states_not_equal_for_live_out(
-            &state1, &state2, &live_out, false,
+            &state1,
+            &state2,
+            &live_out,
+            &MemLiveOut::None,
         ));
         assert_eq!(solver.check(), SatResult::Unsat);
 
@@ -1818,7 +1876,7 @@ mod tests {
             &state1,
             &state2,
             &live_out.with_flags(true),
-            false,
+            &MemLiveOut::None,
         ));
         assert_eq!(solver.check(), SatResult::Sat);
     }
@@ -2331,6 +2389,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::clone_on_copy)]
     fn test_flag_writers_set_symbolic_flags() {
         // Apply each flag-writing instruction over symbolic x0/x1 and prove
         // its final NZCV agrees with the helper that mirrors concrete
@@ -2541,6 +2600,106 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_condition_to_smt_always_conditions_hold_for_symbolic_flags() {
+        use crate::ir::types::Condition;
+        let n = BV::new_const("al_n", 1);
+        let z = BV::new_const("al_z", 1);
+        let c = BV::new_const("al_c", 1);
+        let v = BV::new_const("al_v", 1);
+        for cond in [Condition::AL, Condition::NV] {
+            let solver = Solver::new();
+            solver.assert(condition_to_smt(cond, &n, &z, &c, &v).eq(bv_one()).not());
+            assert_eq!(
+                solver.check(),
+                SatResult::Unsat,
+                "{cond:?} must hold for every NZCV"
+            );
+        }
+    }
+
+    /// `csel x0, x1, x2, <cond>` over fully symbolic NZCV must pick, for
+    /// every one of the 16 flag assignments, the operand the concrete
+    /// interpreter picks. This goes through `apply_instruction`, so it also
+    /// covers how CSEL reads the state's flags, not just `condition_to_smt`.
+    #[test]
+    fn test_csel_matches_concrete_for_every_condition_over_symbolic_nzcv() {
+        use crate::ir::types::Condition;
+        use crate::semantics::concrete::apply_instruction_concrete;
+        use crate::semantics::state::{ConcreteMachineState, ConcreteValue, ConditionFlags};
+
+        let conds = [
+            Condition::EQ,
+            Condition::NE,
+            Condition::CS,
+            Condition::CC,
+            Condition::MI,
+            Condition::PL,
+            Condition::VS,
+            Condition::VC,
+            Condition::HI,
+            Condition::LS,
+            Condition::GE,
+            Condition::LT,
+            Condition::GT,
+            Condition::LE,
+            Condition::AL,
+            Condition::NV,
+        ];
+        for cond in conds {
+            let csel = Instruction::Csel {
+                rd: Register::X0,
+                rn: Register::X1,
+                rm: Register::X2,
+                cond,
+            };
+            let pre = MachineState::new_symbolic("csel_cond");
+            let (n, z, c, v) = pre.get_flags();
+            let (n, z, c, v) = (n.clone(), z.clone(), c.clone(), v.clone());
+            let x1 = pre.get_register(Register::X1).clone();
+            let x2 = pre.get_register(Register::X2).clone();
+            let post = apply_instruction(pre, &csel);
+            let x0 = post.get_register(Register::X0);
+
+            // One implication per NZCV assignment: flags == nzcv => x0 is
+            // whichever of x1 / x2 the concrete interpreter selected.
+            let mut agreements = Vec::new();
+            for nzcv in 0..16u8 {
+                let flags = ConditionFlags {
+                    n: nzcv & 0b1000 != 0,
+                    z: nzcv & 0b0100 != 0,
+                    c: nzcv & 0b0010 != 0,
+                    v: nzcv & 0b0001 != 0,
+                };
+                let mut concrete = ConcreteMachineState::new_zeroed();
+                concrete.set_register(Register::X1, ConcreteValue::new(1));
+                concrete.set_register(Register::X2, ConcreteValue::new(2));
+                concrete.set_flags(flags);
+                let picked = apply_instruction_concrete(concrete, &csel)
+                    .get_register(Register::X0)
+                    .as_u64();
+                let expected = if picked == 1 { &x1 } else { &x2 };
+                let bit = |set: bool| BV::from_u64(set as u64, 1);
+                let flags_match = z3::ast::Bool::and(&[
+                    &n.eq(bit(flags.n)),
+                    &z.eq(bit(flags.z)),
+                    &c.eq(bit(flags.c)),
+                    &v.eq(bit(flags.v)),
+                ]);
+                agreements.push(flags_match.implies(x0.eq(expected)));
+            }
+            let agreements: Vec<&z3::ast::Bool> = agreements.iter().collect();
+
+            let solver = Solver::new();
+            solver.assert(z3::ast::Bool::and(&agreements).not());
+            assert_eq!(
+                solver.check(),
+                SatResult::Unsat,
+                "csel x0, x1, x2, {cond} disagrees with the concrete interpreter for some NZCV"
+            );
+        }
+    }
+
     #[test]
     fn test_set_flags_round_trip() {
         // set_flags writes; get_flags reads back the exact BVs.
@@ -2693,6 +2852,7 @@ mod tests {
     /// agreement. Used by flag-setting (CMP/CMN/TST/ADDS/SUBS/ANDS/BICS/NEGS),
     /// conditional (CSEL/CSINC/CSINV/CSNEG/CSET/CSETM), and flag+condition
     /// (CCMP/CCMN) opcodes.
+    #[allow(clippy::clone_on_copy)]
     fn assert_concrete_smt_parity_full(
         instr: &Instruction,
         pre_values: &[(Register, u64)],
@@ -3024,6 +3184,59 @@ mod tests {
         }
     }
 
+    /// The register-form shifts with only the shift amount pinned and the
+    /// shifted value left symbolic: an amount of 64 must shift by 0 and 65 by
+    /// 1 for every input, and amounts 64 apart must agree for every pair of
+    /// inputs, as AArch64 takes the amount modulo the register width.
+    #[test]
+    fn register_shift_amounts_wrap_at_the_register_width_for_symbolic_inputs() {
+        type Shift = fn(Register, Register, Operand) -> Instruction;
+        let shifts: [(&str, Shift); 3] = [
+            ("lsl", |rd, rn, shift| Instruction::Lsl { rd, rn, shift }),
+            ("lsr", |rd, rn, shift| Instruction::Lsr { rd, rn, shift }),
+            ("asr", |rd, rn, shift| Instruction::Asr { rd, rn, shift }),
+        ];
+        for (name, shift) in shifts {
+            let by_register = shift(Register::X0, Register::X1, Operand::Register(Register::X2));
+            let post = |pre: MachineState| {
+                apply_instruction(pre, &by_register)
+                    .get_register(Register::X0)
+                    .clone()
+            };
+
+            for (amount, equivalent) in [(64, 0), (65, 1)] {
+                let pre = MachineState::new_symbolic("pre");
+                let by_immediate =
+                    shift(Register::X0, Register::X1, Operand::Immediate(equivalent));
+                let expected = apply_instruction(pre.clone(), &by_immediate)
+                    .get_register(Register::X0)
+                    .clone();
+                let solver = Solver::new();
+                solver.assert(pre.get_register(Register::X2).eq(BV::from_u64(amount, 64)));
+                solver.assert(post(pre).eq(&expected).not());
+                assert_eq!(
+                    solver.check(),
+                    SatResult::Unsat,
+                    "{name} by x2 = {amount} must shift by {equivalent}"
+                );
+            }
+
+            let pre = MachineState::new_symbolic("pre");
+            let mut wrapped = pre.clone();
+            wrapped.set_register(
+                Register::X2,
+                pre.get_register(Register::X2).bvadd(BV::from_u64(64, 64)),
+            );
+            let solver = Solver::new();
+            solver.assert(post(pre).eq(post(wrapped)).not());
+            assert_eq!(
+                solver.check(),
+                SatResult::Unsat,
+                "{name} must not distinguish shift amounts 64 apart"
+            );
+        }
+    }
+
     #[test]
     fn test_lsl_reg_concrete_smt_parity() {
         // Register-form LSL: shift amount comes from a register and may
@@ -5,7 +5,8 @@
 use crate::ir::Register;
 use crate::ir::types::{AccessWidth, Condition, VectorRegister};
 use crate::isa::x86::{X86Register, X86RegisterView};
-use std::collections::{BTreeMap, HashMap};
+use rand::{Rng, RngExt};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fmt;
 
 /// NZCV condition flags (Negative, Zero, Carry, oVerflow)
@@ -286,6 +287,18 @@ impl ConcreteValue {
         ConcreteValue(value as u64)
     }
 
+    /// A W-register result: zero-extended into the 64-bit slot, as AArch64
+    /// clears bits [63:32] on every 32-bit write.
+    pub fn from_u32(value: u32) -> Self {
+        ConcreteValue(u64::from(value))
+    }
+
+    /// Like `from_u32`: the bit pattern is zero-extended, never
+    /// sign-extended, so `from_i32(-1)` is `0x0000_0000_ffff_ffff`.
+    pub fn from_i32(value: i32) -> Self {
+        Self::from_u32(value as u32)
+    }
+
     pub fn as_u64(&self) -> u64 {
         self.0
     }
@@ -293,6 +306,36 @@ impl ConcreteValue {
     pub fn as_i64(&self) -> i64 {
         self.0 as i64
     }
+
+    /// The low 32 bits, as a W-register read sees them.
+    pub fn as_u32(&self) -> u32 {
+        self.0 as u32
+    }
+
+    /// The low 32 bits reinterpreted as signed.
+    pub fn as_i32(&self) -> i32 {
+        self.0 as i32
+    }
+
+    /// `self + rhs` together with the NZCV flags `ADDS`/`CMN` set: C is the
+    /// unsigned carry out, V the signed overflow.
+    pub fn add_with_flags(self, rhs: ConcreteValue) -> (ConcreteValue, ConditionFlags) {
+        let result = self.0.wrapping_add(rhs.0);
+        (
+            ConcreteValue(result),
+            ConditionFlags::from_add(self.0, rhs.0, result),
+        )
+    }
+
+    /// `self - rhs` together with the NZCV flags `SUBS`/`CMP` set. C means
+    /// *no borrow* (`self >= rhs` unsigned), the inverse of x86's CF.
+    pub fn sub_with_flags(self, rhs: ConcreteValue) -> (ConcreteValue, ConditionFlags) {
+        let result = self.0.wrapping_sub(rhs.0);
+        (
+            ConcreteValue(result),
+            ConditionFlags::from_sub(self.0, rhs.0, result),
+        )
+    }
 }
 
 impl fmt::Display for ConcreteValue {
@@ -301,13 +344,35 @@ impl fmt::Display for ConcreteValue {
     }
 }
 
+/// Byte stores an instruction sequence applied to a `ConcreteMachineState`,
+/// as `(address, value)` pairs in program order. Unlike the memory map it
+/// keeps stores of zero and overwritten bytes, so it answers which
+/// addresses a sequence wrote, not just what memory holds afterwards.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MemoryLog(Vec<(u64, u8)>);
+
+impl MemoryLog {
+    /// Every logged byte store, oldest first.
+    pub fn writes(&self) -> &[(u64, u8)] {
+        &self.0
+    }
+
+    /// The distinct addresses written.
+    pub fn addresses(&self) -> BTreeSet<u64> {
+        self.0.iter().map(|&(addr, _)| addr).collect()
+    }
+}
+
 /// Concrete machine state for fast validation.
 ///
 /// Per ADR-0004 decision 6, the state carries a width tag and masks writes
 /// before storing (mask-on-write). AArch64 is always width=64, which makes
 /// the mask a no-op; the field is there so stages 1+ can parameterise this
 /// type and reuse the same struct for narrower widths.
-#[derive(Debug, Clone, PartialEq, Eq)]
+///
+/// Equality ignores the memory log: two states holding the same values are
+/// equal however they got there.
+#[derive(Debug, Clone)]
 pub struct ConcreteMachineState {
     registers: HashMap<Register, ConcreteValue>,
     vectors: HashMap<VectorRegister, u128>,
@@ -318,8 +383,22 @@ pub struct ConcreteMachineState {
     /// ADR-0007). `write_bytes` prunes zero entries to maintain that
     /// invariant.
     memory: BTreeMap<u64, u8>,
+    /// Stores made through `store_bytes`; see [`MemoryLog`].
+    memory_log: MemoryLog,
+}
+
+impl PartialEq for ConcreteMachineState {
+    fn eq(&self, other: &Self) -> bool {
+        self.registers == other.registers
+            && self.vectors == other.vectors
+            && self.flags == other.flags
+            && self.width == other.width
+            && self.memory == other.memory
+    }
 }
 
+impl Eq for ConcreteMachineState {}
+
 impl ConcreteMachineState {
     /// Create a new AArch64 (width=64) state with all registers set to zero.
     pub fn new_zeroed() -> Self {
@@ -350,6 +429,7 @@ impl ConcreteMachineState {
             flags: ConditionFlags::new(),
             width,
             memory: BTreeMap::new(),
+            memory_log: MemoryLog::default(),
         }
     }
 
@@ -358,6 +438,23 @@ impl ConcreteMachineState {
         self.width
     }
 
+    /// Create an AArch64 state with `registers` set to values drawn from
+    /// `rng` (full 128 bits for vector registers); every other register stays
+    /// zero. The same seeded `rng` always yields the same state.
+    pub fn new_random<R: Rng + ?Sized>(rng: &mut R, registers: &[Register]) -> Self {
+        let mut state = Self::new_zeroed();
+        for &reg in registers {
+            match reg {
+                Register::Vector(vector) => state.set_vector(
+                    vector,
+                    (u128::from(rng.random::<u64>()) << 64) | u128::from(rng.random::<u64>()),
+                ),
+                _ => state.set_register(reg, ConcreteValue::new(rng.random::<u64>())),
+            }
+        }
+        state
+    }
+
     /// Create state from a map of register values
     pub fn from_values(values: HashMap<Register, u64>) -> Self {
         let mut state = Self::new_zeroed();
@@ -456,6 +553,27 @@ impl ConcreteMachineState {
             }
         }
     }
+
+    /// `write_bytes` on behalf of an executed store: the bytes also go into
+    /// the memory log. Seeding input memory uses `write_bytes` directly so
+    /// the log holds only what the sequence itself wrote.
+    pub fn store_bytes(&mut self, addr: u64, value: u64, width: AccessWidth) {
+        self.write_bytes(addr, value, width);
+        for i in 0..width.bytes() as u64 {
+            let byte = ((value >> (8 * i)) & 0xff) as u8;
+            self.memory_log.0.push((addr.wrapping_add(i), byte));
+        }
+    }
+
+    /// The stores logged so far.
+    pub fn memory_log(&self) -> &MemoryLog {
+        &self.memory_log
+    }
+
+    /// The distinct addresses the logged stores wrote.
+    pub fn written_addresses(&self) -> BTreeSet<u64> {
+        self.memory_log.addresses()
+    }
 }
 
 impl fmt::Display for ConcreteMachineState {
@@ -582,6 +700,34 @@ impl X86ConcreteMachineState {
 mod tests {
     use super::*;
 
+    #[test]
+    fn new_random_is_reproducible_and_leaves_unlisted_registers_zero() {
+        use rand::SeedableRng;
+
+        let registers = [
+            Register::X0,
+            Register::X5,
+            Register::Vector(VectorRegister::V1),
+        ];
+        let first = ConcreteMachineState::new_random(
+            &mut rand_chacha::ChaCha8Rng::seed_from_u64(681),
+            &registers,
+        );
+        let second = ConcreteMachineState::new_random(
+            &mut rand_chacha::ChaCha8Rng::seed_from_u64(681),
+            &registers,
+        );
+        assert_eq!(first, second);
+
+        let other = ConcreteMachineState::new_random(
+            &mut rand_chacha::ChaCha8Rng::seed_from_u64(682),
+            &registers,
+        );
+        assert_ne!(first, other);
+        assert_eq!(first.get_register(Register::X1).as_u64(), 0);
+        assert_eq!(first.get_vector(VectorRegister::V0), 0);
+    }
+
     #[test]
     fn test_concrete_value_wrapping() {
         let v = ConcreteValue::new(u64::MAX);
@@ -592,6 +738,115 @@ mod tests {
         assert_eq!(v2.as_u64(), u64::MAX);
     }
 
+    #[test]
+    fn thirty_two_bit_values_zero_extend() {
+        assert_eq!(ConcreteValue::from_i32(-1).as_u64(), 0x0000_0000_FFFF_FFFF);
+        assert_eq!(
+            ConcreteValue::from_i32(i32::MIN).as_u64(),
+            0x0000_0000_8000_0000
+        );
+        assert_eq!(
+            ConcreteValue::from_u32(u32::MAX).as_u64(),
+            0x0000_0000_FFFF_FFFF
+        );
+
+        let v = ConcreteValue::new(0xDEAD_BEEF_FFFF_FFFE);
+        assert_eq!(v.as_u32(), 0xFFFF_FFFE);
+        assert_eq!(v.as_i32(), -2);
+        assert_eq!(ConcreteValue::from_i32(v.as_i32()).as_u64(), 0xFFFF_FFFE);
+    }
+
+    /// Operands covering every small signed value plus the unsigned and
+    /// signed wrap points, so each pair exercises a distinct NZCV corner.
+    fn flag_operands() -> Vec<u64> {
+        let mut values: Vec<u64> = (-8i64..=8).map(|v| v as u64).collect();
+        for edge in [i64::MIN, i64::MIN + 1, i64::MAX - 1, i64::MAX] {
+            values.push(edge as u64);
+        }
+        values
+    }
+
+    /// NZCV of `a + b + carry_in` computed by widening, as the ARM ARM's
+    /// `AddWithCarry` pseudocode defines it.
+    fn add_with_carry_reference(a: u64, b: u64, carry_in: u64) -> (u64, ConditionFlags) {
+        let unsigned = u128::from(a) + u128::from(b) + u128::from(carry_in);
+        let signed = i128::from(a as i64) + i128::from(b as i64) + i128::from(carry_in);
+        let result = unsigned as u64;
+        let flags = ConditionFlags {
+            n: (result as i64) < 0,
+            z: result == 0,
+            c: unsigned != u128::from(result),
+            v: signed != i128::from(result as i64),
+        };
+        (result, flags)
+    }
+
+    #[test]
+    fn add_with_flags_matches_add_with_carry_for_small_and_edge_values() {
+        for &a in &flag_operands() {
+            for &b in &flag_operands() {
+                let (result, flags) = ConcreteValue(a).add_with_flags(ConcreteValue(b));
+                let (expected, expected_flags) = add_with_carry_reference(a, b, 0);
+                assert_eq!(result.as_u64(), expected, "{a:#x} + {b:#x}");
+                assert_eq!(flags, expected_flags, "{a:#x} + {b:#x}");
+            }
+        }
+    }
+
+    #[test]
+    fn sub_with_flags_matches_add_with_carry_of_the_complement() {
+        // SUBS is AddWithCarry(a, NOT b, 1), which is what makes C a
+        // "no borrow" flag.
+        for &a in &flag_operands() {
+            for &b in &flag_operands() {
+                let (result, flags) = ConcreteValue(a).sub_with_flags(ConcreteValue(b));
+                let (expected, expected_flags) = add_with_carry_reference(a, !b, 1);
+                assert_eq!(result.as_u64(), expected, "{a:#x} - {b:#x}");
+                assert_eq!(flags, expected_flags, "{a:#x} - {b:#x}");
+            }
+        }
+    }
+
+    #[test]
+    fn int_min_and_minus_one_overflow_cases() {
+        let min = ConcreteValue::from_i64(i64::MIN);
+        let minus_one = ConcreteValue::from_i64(-1);
+
+        // MIN + -1 wraps to MAX: signed overflow, and an unsigned carry out.
+        let (sum, flags) = min.add_with_flags(minus_one);
+        assert_eq!(sum.as_i64(), i64::MAX);
+        assert_eq!(
+            flags,
+            ConditionFlags {
+                n: false,
+                z: false,
+                c: true,
+                v: true
+            }
+        );
+
+        // MIN - -1 is MIN + 1: representable, but MIN < u64::MAX borrows.
+        let (difference, flags) = min.sub_with_flags(minus_one);
+        assert_eq!(difference.as_i64(), i64::MIN + 1);
+        assert_eq!(
+            flags,
+            ConditionFlags {
+                n: true,
+                z: false,
+                c: false,
+                v: false
+            }
+        );
+
+        // -1 - MIN is MAX: no borrow, no overflow. 0 - MIN overflows back to MIN.
+        let (difference, flags) = minus_one.sub_with_flags(min);
+        assert_eq!(difference.as_i64(), i64::MAX);
+        assert!(flags.c && !flags.v && !flags.n);
+        let (difference, flags) = ConcreteValue::new(0).sub_with_flags(min);
+        assert_eq!(difference.as_i64(), i64::MIN);
+        assert!(!flags.c && flags.v && flags.n);
+    }
+
     #[test]
     fn test_machine_state_new_zeroed() {
         let state = ConcreteMachineState::new_zeroed();
@@ -656,6 +911,28 @@ mod tests {
         assert!(flags.evaluate(Condition::NV));
     }
 
+    #[test]
+    fn sp_is_tracked_separately_from_xzr() {
+        let mut state = ConcreteMachineState::new_zeroed();
+        state.set_register(Register::SP, ConcreteValue::new(0x7ff0));
+        assert_eq!(state.get_register(Register::SP).as_u64(), 0x7ff0);
+        assert_eq!(state.get_register(Register::XZR).as_u64(), 0);
+    }
+
+    #[test]
+    fn always_conditions_hold_for_every_nzcv() {
+        for nzcv in 0..16u8 {
+            let flags = ConditionFlags {
+                n: nzcv & 0b1000 != 0,
+                z: nzcv & 0b0100 != 0,
+                c: nzcv & 0b0010 != 0,
+                v: nzcv & 0b0001 != 0,
+            };
+            assert!(flags.evaluate(Condition::AL), "AL at {flags:?}");
+            assert!(flags.evaluate(Condition::NV), "NV at {flags:?}");
+        }
+    }
+
     #[test]
     fn aarch64_state_display_and_accessors_show_nonzero_parts() {
         let mut state = ConcreteMachineState::new_zeroed();
@@ -1006,6 +1283,21 @@ mod tests {
         assert_eq!(a, b, "writing zeroes must not perturb structural equality");
     }
 
+    #[test]
+    fn stores_are_logged_but_do_not_affect_equality() {
+        use crate::ir::types::AccessWidth;
+        let mut stored = ConcreteMachineState::new_zeroed();
+        stored.store_bytes(0x100, 0x00AB, AccessWidth::Half);
+        // Zero bytes are pruned from memory but still count as written.
+        assert_eq!(stored.memory_log().writes(), &[(0x100, 0xAB), (0x101, 0)]);
+        assert_eq!(stored.written_addresses(), [0x100, 0x101].into());
+
+        let mut seeded = ConcreteMachineState::new_zeroed();
+        seeded.write_bytes(0x100, 0xAB, AccessWidth::Byte);
+        assert!(seeded.written_addresses().is_empty());
+        assert_eq!(stored, seeded);
+    }
+
     // --- Eflags::evaluate(X86Condition) ---
 
     #[test]
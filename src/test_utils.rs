@@ -7,6 +7,10 @@ use std::sync::atomic::{AtomicU64, Ordering};
 #[path = "test_utils/instruction_fixtures.rs"]
 pub(crate) mod instruction_fixtures;
 
+#[allow(dead_code)]
+#[path = "test_utils/sequence_fixtures.rs"]
+pub(crate) mod sequence_fixtures;
+
 static TEMP_FILE_COUNTER: AtomicU64 = AtomicU64::new(0);
 
 pub(crate) struct TempFile {
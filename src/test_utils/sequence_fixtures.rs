@@ -0,0 +1,45 @@
+use crate::ir::{Instruction, Operand, Register};
+use crate::search::config::SearchConfig;
+use crate::semantics::LiveOut;
+
+/// `mov x0, x1; add x0, x0, #1`, which every search should fuse into
+/// `add x0, x1, #1` under [`x0_live`].
+pub(crate) fn mov_add_sequence() -> Vec<Instruction> {
+    vec![
+        Instruction::MovReg {
+            rd: Register::X0,
+            rn: Register::X1,
+        },
+        Instruction::Add {
+            rd: Register::X0,
+            rn: Register::X0,
+            rm: Operand::Immediate(1),
+        },
+    ]
+}
+
+/// `mov x0, #0`: a single instruction no search can beat while x0 is live.
+pub(crate) fn mov_zero_sequence() -> Vec<Instruction> {
+    vec![Instruction::MovImm {
+        rd: Register::X0,
+        imm: 0,
+    }]
+}
+
+/// A live-out contract of `registers` with the flags dead.
+pub(crate) fn live_out(registers: &[Register]) -> LiveOut {
+    LiveOut::from_registers(registers.to_vec())
+}
+
+/// Only x0 live, the contract the sequences above are written against.
+pub(crate) fn x0_live() -> LiveOut {
+    live_out(&[Register::X0])
+}
+
+/// Search pools of x0/x1 and the immediates 0..=2, enough to rewrite
+/// [`mov_add_sequence`] while keeping the candidate space small.
+pub(crate) fn small_search_config() -> SearchConfig {
+    SearchConfig::default()
+        .with_registers(vec![Register::X0, Register::X1])
+        .with_immediates(vec![0, 1, 2])
+}
@@ -2,6 +2,8 @@
 
 pub mod downstream;
 pub mod live_out;
+#[cfg(feature = "qemu")]
+pub mod qemu;
 pub mod random;
 
 #[allow(unused_imports)]
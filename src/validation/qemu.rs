@@ -0,0 +1,497 @@
+//! Runtime cross-check of AArch64 rewrites under `qemu-aarch64`.
+//!
+//! The SMT proof and the concrete interpreter share one instruction model, so
+//! a modelling bug can make both agree on a wrong rewrite. This module runs
+//! the original and optimized windows on QEMU instead: each sequence is
+//! wrapped in a tiny static ELF harness that loads random register inputs,
+//! executes the sequence, and writes the live-out registers (and NZCV when
+//! flags are live) to stdout. Both harnesses see the same inputs and their
+//! outputs must match byte for byte.
+//!
+//! Only straight-line, memory-free, scalar sequences that leave SP alone are
+//! supported; anything else is rejected up front rather than guessed at.
+
+use crate::assembler::AArch64Assembler;
+use crate::ir::{Instruction, Register};
+use crate::semantics::live_out::LiveOut;
+use rand::{RngExt, SeedableRng};
+use std::fmt;
+use std::path::Path;
+use std::process::Command;
+
+/// Default QEMU user-mode emulator invoked for each harness run.
+pub const DEFAULT_QEMU_BIN: &str = "qemu-aarch64";
+
+/// Load address of the harness image. The code follows the ELF and program
+/// headers in the same page.
+const HARNESS_BASE: u64 = 0x40_0000;
+const ELF_HEADER_SIZE: usize = 64;
+const PROGRAM_HEADER_SIZE: usize = 56;
+const CODE_OFFSET: usize = ELF_HEADER_SIZE + PROGRAM_HEADER_SIZE;
+
+/// Configuration for a QEMU cross-check run.
+#[derive(Debug, Clone)]
+pub struct QemuVerifyConfig {
+    /// Emulator binary, resolved through `PATH` when not absolute.
+    pub qemu_bin: String,
+    /// Number of random register inputs run through both harnesses.
+    pub num_inputs: usize,
+    /// Seed for the input generator; `None` draws from the thread RNG.
+    pub seed: Option<u64>,
+}
+
+impl Default for QemuVerifyConfig {
+    fn default() -> Self {
+        Self {
+            qemu_bin: DEFAULT_QEMU_BIN.to_string(),
+            num_inputs: 100,
+            seed: None,
+        }
+    }
+}
+
+/// One input on which the two harnesses disagreed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QemuMismatch {
+    /// Register values loaded before the sequence ran.
+    pub inputs: Vec<(Register, u64)>,
+    /// The first differing output: a register name or `nzcv`.
+    pub output: String,
+    pub original: u64,
+    pub optimized: u64,
+}
+
+impl fmt::Display for QemuMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} differs under qemu: original 0x{:x}, optimized 0x{:x} (inputs:",
+            self.output, self.original, self.optimized
+        )?;
+        for (reg, value) in &self.inputs {
+            write!(f, " {}=0x{:x}", reg, value)?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// Outcome of `qemu_verify`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QemuVerdict {
+    /// Every input produced identical live-out values.
+    Agree { inputs: usize },
+    /// The harnesses disagreed on at least one input.
+    Mismatch(QemuMismatch),
+}
+
+/// Run `original` and `optimized` under QEMU on `config.num_inputs` random
+/// inputs and compare the registers in `live_out` (plus NZCV when
+/// `live_out.flags_live()`).
+///
+/// Returns `Err` when a sequence is outside the supported subset or the
+/// emulator cannot be run; a disagreement is an `Ok(QemuVerdict::Mismatch)`.
+pub fn qemu_verify(
+    original: &[Instruction],
+    optimized: &[Instruction],
+    live_out: &LiveOut,
+    config: &QemuVerifyConfig,
+) -> Result<QemuVerdict, String> {
+    check_supported(original)?;
+    check_supported(optimized)?;
+    let outputs = output_registers(live_out)?;
+    let inputs = input_registers(original, optimized, &outputs);
+
+    let seed = config.seed.unwrap_or_else(|| rand::rng().random());
+    let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(seed);
+    let dir = tempfile::tempdir().map_err(|e| format!("failed to create temp dir: {}", e))?;
+
+    for _ in 0..config.num_inputs {
+        let values: Vec<(Register, u64)> = inputs
+            .iter()
+            .map(|reg| (*reg, rng.random::<u64>()))
+            .collect();
+        let nzcv = u64::from(rng.random::<u8>() & 0xF) << 28;
+
+        let original_out = run_harness(
+            &config.qemu_bin,
+            &dir.path().join("original"),
+            &build_harness(original, &values, nzcv, &outputs, live_out.flags_live())?,
+        )?;
+        let optimized_out = run_harness(
+            &config.qemu_bin,
+            &dir.path().join("optimized"),
+            &build_harness(optimized, &values, nzcv, &outputs, live_out.flags_live())?,
+        )?;
+
+        let names = outputs
+            .iter()
+            .map(|reg| reg.to_string())
+            .chain(live_out.flags_live().then(|| "nzcv".to_string()));
+        for (name, (a, b)) in names.zip(original_out.iter().zip(&optimized_out)) {
+            if a != b {
+                return Ok(QemuVerdict::Mismatch(QemuMismatch {
+                    inputs: values,
+                    output: name,
+                    original: *a,
+                    optimized: *b,
+                }));
+            }
+        }
+    }
+
+    Ok(QemuVerdict::Agree {
+        inputs: config.num_inputs,
+    })
+}
+
+fn check_supported(seq: &[Instruction]) -> Result<(), String> {
+    for instr in seq {
+        if instr.is_terminator() {
+            return Err(format!("qemu verification cannot run branch `{}`", instr));
+        }
+        if instr.is_memory_op() {
+            return Err(format!(
+                "qemu verification does not support memory access `{}`",
+                instr
+            ));
+        }
+        let touches = instr
+            .destinations()
+            .into_iter()
+            .chain(instr.source_registers());
+        for reg in touches {
+            if !reg.is_general_or_zero() {
+                return Err(format!(
+                    "qemu verification does not support register {} in `{}`",
+                    reg, instr
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Live-out registers the harness stores, in slot order. XZR is dropped
+/// since it always reads zero.
+fn output_registers(live_out: &LiveOut) -> Result<Vec<Register>, String> {
+    let mut outputs = Vec::new();
+    for reg in live_out.iter().copied() {
+        if reg == Register::XZR {
+            continue;
+        }
+        if !reg.is_general_or_zero() {
+            return Err(format!(
+                "qemu verification does not support live-out register {}",
+                reg
+            ));
+        }
+        outputs.push(reg);
+    }
+    outputs.sort_by_key(|reg| reg.sort_key());
+    outputs.dedup();
+    Ok(outputs)
+}
+
+/// Every general register either sequence reads or writes, plus the
+/// live-outs: a live-out one side leaves untouched reports its input value.
+fn input_registers(
+    original: &[Instruction],
+    optimized: &[Instruction],
+    outputs: &[Register],
+) -> Vec<Register> {
+    let mut regs: Vec<Register> = original
+        .iter()
+        .chain(optimized)
+        .flat_map(|instr| {
+            instr
+                .source_registers()
+                .into_iter()
+                .chain(instr.destinations())
+        })
+        .chain(outputs.iter().copied())
+        .filter(|reg| *reg != Register::XZR && reg.is_general_or_zero())
+        .collect();
+    regs.sort_by_key(|reg| reg.sort_key());
+    regs.dedup();
+    regs
+}
+
+/// Build a static AArch64 Linux ELF that loads `inputs` and `nzcv`, runs
+/// `seq`, then writes each of `outputs` (and NZCV when `flags_live`) to
+/// stdout as little-endian u64s before exiting with status 0.
+pub fn build_harness(
+    seq: &[Instruction],
+    inputs: &[(Register, u64)],
+    nzcv: u64,
+    outputs: &[Register],
+    flags_live: bool,
+) -> Result<Vec<u8>, String> {
+    let slots = outputs.len() + usize::from(flags_live);
+    let frame = (slots * 8).next_multiple_of(16) as u32;
+
+    let mut words = vec![sub_sp_imm(frame)];
+    words.extend(mov_u64(0, nzcv));
+    words.push(msr_nzcv(0));
+    for (reg, value) in inputs {
+        words.extend(mov_u64(gpr(*reg)?, *value));
+    }
+
+    let seq_addr = HARNESS_BASE + (CODE_OFFSET + words.len() * 4) as u64;
+    let seq_bytes = AArch64Assembler::new().assemble_instructions(seq, seq_addr)?;
+
+    let mut tail = Vec::new();
+    for (slot, reg) in outputs.iter().enumerate() {
+        tail.push(str_sp_slot(gpr(*reg)?, slot as u32));
+    }
+    if flags_live {
+        tail.push(mrs_nzcv(0));
+        tail.push(str_sp_slot(0, outputs.len() as u32));
+    }
+    // write(1, sp, slots * 8); exit(0)
+    tail.extend([
+        movz(0, 1, 0),
+        ADD_X1_SP,
+        movz(2, (slots * 8) as u16, 0),
+        movz(8, 64, 0),
+        SVC_0,
+        movz(0, 0, 0),
+        movz(8, 93, 0),
+        SVC_0,
+    ]);
+
+    let mut code: Vec<u8> = words.iter().flat_map(|w| w.to_le_bytes()).collect();
+    code.extend(seq_bytes);
+    code.extend(tail.iter().flat_map(|w| w.to_le_bytes()));
+    Ok(elf_image(&code))
+}
+
+/// Run one harness image and decode its stdout into u64 slots.
+fn run_harness(qemu_bin: &str, path: &Path, image: &[u8]) -> Result<Vec<u64>, String> {
+    std::fs::write(path, image)
+        .map_err(|e| format!("failed to write harness {}: {}", path.display(), e))?;
+    let output = Command::new(qemu_bin)
+        .arg(path)
+        .output()
+        .map_err(|e| format!("failed to run `{}`: {}", qemu_bin, e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "`{}` exited with {}: {}",
+            qemu_bin,
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(output
+        .stdout
+        .as_chunks::<8>()
+        .0
+        .iter()
+        .map(|chunk| u64::from_le_bytes(*chunk))
+        .collect())
+}
+
+fn gpr(reg: Register) -> Result<u32, String> {
+    reg.index()
+        .filter(|_| reg.is_general_or_zero())
+        .map(u32::from)
+        .ok_or_else(|| format!("register {} has no general-purpose encoding", reg))
+}
+
+const ADD_X1_SP: u32 = 0x9100_03e1;
+const SVC_0: u32 = 0xd400_0001;
+
+fn sub_sp_imm(imm: u32) -> u32 {
+    0xd100_03ff | (imm << 10)
+}
+
+fn movz(rd: u32, imm: u16, shift: u32) -> u32 {
+    0xd280_0000 | ((shift / 16) << 21) | (u32::from(imm) << 5) | rd
+}
+
+fn movk(rd: u32, imm: u16, shift: u32) -> u32 {
+    0xf280_0000 | ((shift / 16) << 21) | (u32::from(imm) << 5) | rd
+}
+
+fn mov_u64(rd: u32, value: u64) -> [u32; 4] {
+    [
+        movz(rd, value as u16, 0),
+        movk(rd, (value >> 16) as u16, 16),
+        movk(rd, (value >> 32) as u16, 32),
+        movk(rd, (value >> 48) as u16, 48),
+    ]
+}
+
+fn msr_nzcv(rt: u32) -> u32 {
+    0xd51b_4200 | rt
+}
+
+fn mrs_nzcv(rt: u32) -> u32 {
+    0xd53b_4200 | rt
+}
+
+fn str_sp_slot(rt: u32, slot: u32) -> u32 {
+    0xf900_03e0 | (slot << 10) | rt
+}
+
+/// Wrap `code` in a minimal ELF64 executable with one R+X PT_LOAD segment
+/// covering the whole file.
+fn elf_image(code: &[u8]) -> Vec<u8> {
+    let total = (CODE_OFFSET + code.len()) as u64;
+    let mut image = Vec::with_capacity(total as usize);
+
+    image.extend_from_slice(b"\x7fELF");
+    image.extend_from_slice(&[2, 1, 1, 0]); // ELFCLASS64, little-endian, v1, SysV
+    image.extend_from_slice(&[0; 8]);
+    image.extend_from_slice(&2u16.to_le_bytes()); // ET_EXEC
+    image.extend_from_slice(&183u16.to_le_bytes()); // EM_AARCH64
+    image.extend_from_slice(&1u32.to_le_bytes());
+    image.extend_from_slice(&(HARNESS_BASE + CODE_OFFSET as u64).to_le_bytes());
+    image.extend_from_slice(&(ELF_HEADER_SIZE as u64).to_le_bytes()); // e_phoff
+    image.extend_from_slice(&0u64.to_le_bytes()); // e_shoff
+    image.extend_from_slice(&0u32.to_le_bytes()); // e_flags
+    image.extend_from_slice(&(ELF_HEADER_SIZE as u16).to_le_bytes());
+    image.extend_from_slice(&(PROGRAM_HEADER_SIZE as u16).to_le_bytes());
+    image.extend_from_slice(&1u16.to_le_bytes()); // e_phnum
+    image.extend_from_slice(&[0; 6]); // e_shentsize, e_shnum, e_shstrndx
+
+    image.extend_from_slice(&1u32.to_le_bytes()); // PT_LOAD
+    image.extend_from_slice(&5u32.to_le_bytes()); // PF_R | PF_X
+    image.extend_from_slice(&0u64.to_le_bytes()); // p_offset
+    image.extend_from_slice(&HARNESS_BASE.to_le_bytes());
+    image.extend_from_slice(&HARNESS_BASE.to_le_bytes());
+    image.extend_from_slice(&total.to_le_bytes()); // p_filesz
+    image.extend_from_slice(&total.to_le_bytes()); // p_memsz
+    image.extend_from_slice(&0x1000u64.to_le_bytes());
+
+    image.extend_from_slice(code);
+    image
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::Operand;
+    use crate::ir::types::{AccessWidth, AddressOperand, IndexMode};
+    use capstone::prelude::*;
+
+    fn add_imm(rd: Register, rn: Register, imm: i64) -> Instruction {
+        Instruction::Add {
+            rd,
+            rn,
+            rm: Operand::Immediate(imm),
+        }
+    }
+
+    fn disassemble_code(image: &[u8]) -> Vec<String> {
+        let cs = Capstone::new()
+            .arm64()
+            .mode(capstone::arch::arm64::ArchMode::Arm)
+            .build()
+            .expect("capstone");
+        cs.disasm_all(&image[CODE_OFFSET..], HARNESS_BASE + CODE_OFFSET as u64)
+            .expect("disassemble harness")
+            .iter()
+            .map(|i| {
+                format!(
+                    "{} {}",
+                    i.mnemonic().unwrap_or(""),
+                    i.op_str().unwrap_or("")
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn harness_loads_inputs_runs_sequence_and_writes_live_outs() {
+        let seq = [add_imm(Register::X0, Register::X1, 2)];
+        let image = build_harness(
+            &seq,
+            &[(Register::X1, 0x1234_5678_9abc_def0)],
+            0,
+            &[Register::X0],
+            true,
+        )
+        .expect("harness");
+
+        assert_eq!(&image[..4], b"\x7fELF");
+        assert_eq!(u16::from_le_bytes([image[18], image[19]]), 183);
+
+        let text = disassemble_code(&image);
+        assert_eq!(text[0], "sub sp, sp, #0x10");
+        assert!(text.contains(&"msr nzcv, x0".to_string()));
+        assert!(text.contains(&"movk x1, #0x1234, lsl #48".to_string()));
+        assert!(text.contains(&"add x0, x1, #2".to_string()));
+        assert!(text.contains(&"str x0, [sp]".to_string()));
+        assert!(text.contains(&"mrs x0, nzcv".to_string()));
+        assert!(text.contains(&"str x0, [sp, #8]".to_string()));
+        assert_eq!(text.last().map(String::as_str), Some("svc #0"));
+    }
+
+    #[test]
+    fn qemu_verify_rejects_unsupported_sequences() {
+        let live_out = LiveOut::from_registers(vec![Register::X0]);
+        let config = QemuVerifyConfig::default();
+
+        let load = Instruction::Ldr {
+            rt: Register::X0,
+            addr: AddressOperand::Imm {
+                base: Register::X1,
+                offset: 0,
+                mode: IndexMode::Offset,
+            },
+            width: AccessWidth::Extended,
+        };
+        let err = qemu_verify(&[load], &[load], &live_out, &config).unwrap_err();
+        assert!(err.contains("memory access"), "{err}");
+
+        let sp = add_imm(Register::X0, Register::SP, 8);
+        let err = qemu_verify(&[sp], &[sp], &live_out, &config).unwrap_err();
+        assert!(err.contains("register sp"), "{err}");
+    }
+
+    #[test]
+    fn qemu_verify_reports_a_missing_emulator() {
+        let seq = [add_imm(Register::X0, Register::X1, 1)];
+        let config = QemuVerifyConfig {
+            qemu_bin: "/nonexistent/qemu-aarch64".to_string(),
+            num_inputs: 1,
+            seed: Some(1),
+        };
+        let live_out = LiveOut::from_registers(vec![Register::X0]);
+        let err = qemu_verify(&seq, &seq, &live_out, &config).unwrap_err();
+        assert!(err.contains("failed to run"), "{err}");
+    }
+
+    /// Exercises a real emulator when one is installed; skipped otherwise.
+    #[test]
+    fn qemu_verify_distinguishes_equivalent_and_broken_rewrites() {
+        if Command::new(DEFAULT_QEMU_BIN)
+            .arg("--version")
+            .output()
+            .is_err()
+        {
+            eprintln!("skipping: {} not found", DEFAULT_QEMU_BIN);
+            return;
+        }
+        let live_out = LiveOut::from_registers(vec![Register::X0]);
+        let config = QemuVerifyConfig {
+            num_inputs: 8,
+            seed: Some(7),
+            ..QemuVerifyConfig::default()
+        };
+        let original = [
+            add_imm(Register::X0, Register::X1, 1),
+            add_imm(Register::X0, Register::X0, 1),
+        ];
+
+        let good = [add_imm(Register::X0, Register::X1, 2)];
+        assert_eq!(
+            qemu_verify(&original, &good, &live_out, &config),
+            Ok(QemuVerdict::Agree { inputs: 8 })
+        );
+
+        let bad = [add_imm(Register::X0, Register::X1, 3)];
+        let verdict = qemu_verify(&original, &bad, &live_out, &config).expect("qemu runs");
+        assert!(matches!(verdict, QemuVerdict::Mismatch(m) if m.output == "x0"));
+    }
+}
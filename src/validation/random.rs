@@ -51,19 +51,7 @@ pub fn generate_random_inputs(config: &RandomInputConfig) -> Vec<ConcreteMachine
     let mut inputs = Vec::with_capacity(config.count);
 
     for _ in 0..config.count {
-        let mut state = ConcreteMachineState::new_zeroed();
-        for reg in &config.registers {
-            match reg {
-                Register::Vector(vector) => state.set_vector(
-                    *vector,
-                    (u128::from(rng.random::<u64>()) << 64) | u128::from(rng.random::<u64>()),
-                ),
-                _ => state.set_register(
-                    *reg,
-                    crate::semantics::state::ConcreteValue::new(rng.random::<u64>()),
-                ),
-            }
-        }
+        let mut state = ConcreteMachineState::new_random(&mut rng, &config.registers);
         if config.memory_seed_size > 0 {
             for i in 0..config.memory_seed_size {
                 let byte = rng.random::<u8>();
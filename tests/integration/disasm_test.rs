@@ -24,6 +24,7 @@ fn get_binary_path() -> PathBuf {
 }
 
 #[test]
+#[allow(clippy::trim_split_whitespace)]
 fn test_disasm_simple_binary() {
     let binary = get_binary_path();
     let test_elf = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
@@ -232,10 +232,10 @@ fn memory_operations_are_consistently_documented_with_known_gaps() {
         assert!(
             matches!(
                 s11::parser::parse_line(&line),
-                Err(s11::parser::ParseLineError::UnknownInstruction(ref unsupported))
+                Ok(s11::parser::LineResult::Unsupported(ref unsupported))
                     if unsupported == mnemonic
             ),
-            "parser must reject unsupported memory instruction `{line}`"
+            "parser must report unsupported memory instruction `{line}`"
         );
     }
 
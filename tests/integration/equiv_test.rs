@@ -135,3 +135,31 @@ fn equiv_fast_only_register_window_does_not_warn() {
         stderr
     );
 }
+
+#[test]
+fn equiv_quiet_json_stdout_is_a_single_json_document() {
+    let dir = tempfile::tempdir().expect("create temp dir for equiv fixtures");
+    let seq1 = dir.path().join("seq1.s");
+    let seq2 = dir.path().join("seq2.s");
+    std::fs::write(&seq1, "mov x0, x1\n").expect("write first sequence");
+    std::fs::write(&seq2, "mov x0, x2\n").expect("write second sequence");
+
+    // --verbose would normally narrate parsing and live-out; --quiet must keep
+    // all of that off stdout so the JSON document parses on its own.
+    let output = Command::new(get_binary_path())
+        .arg("--quiet")
+        .arg("equiv")
+        .arg(&seq1)
+        .arg(&seq2)
+        .args(["--live-out", "x0", "--fast-only", "--format", "json", "-v"])
+        .output()
+        .expect("execute s11 equiv");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(output.status.code(), Some(1), "stdout:\n{}", stdout);
+    let json: serde_json::Value =
+        serde_json::from_str(&stdout).expect("stdout must be exactly one JSON document");
+    assert_eq!(json["verdict"], "not-equivalent");
+    assert_eq!(json["exit_code"], 1);
+    assert!(json["counterexample"]["input"]["x0"].is_string());
+}
@@ -1,7 +1,10 @@
 mod bump_version_test;
+#[cfg(feature = "z3")]
 mod carry_chain;
 mod disasm_test;
 mod docs_capability;
 mod equiv_test;
 mod live_out_cli_test;
+#[cfg(not(feature = "z3"))]
+mod no_z3_test;
 mod opt_test;
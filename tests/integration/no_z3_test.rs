@@ -0,0 +1,90 @@
+//! Searches in a build without the `z3` feature
+//! (`cargo test --no-default-features --test integration_tests no_z3`).
+//! Verification falls back to concrete testing, so these check that the
+//! searches still run to completion and return rewrites the tests accept.
+
+use s11::ir::{Instruction, Operand, Register};
+use s11::search::{Algorithm, SearchConfig, StochasticConfig};
+use s11::semantics::{EquivalenceResult, LiveOut, check_equivalence_approx};
+use std::time::Duration;
+
+/// mov x0, x1; add x0, x0, #1  ==>  add x0, x1, #1
+fn mov_add_target() -> Vec<Instruction> {
+    vec![
+        Instruction::MovReg {
+            rd: Register::X0,
+            rn: Register::X1,
+        },
+        Instruction::Add {
+            rd: Register::X0,
+            rn: Register::X0,
+            rm: Operand::Immediate(1),
+        },
+    ]
+}
+
+fn base_config(algorithm: Algorithm) -> SearchConfig {
+    SearchConfig::default()
+        .with_algorithm(algorithm)
+        .with_registers(vec![Register::X0, Register::X1])
+        .with_immediates(vec![0, 1])
+        .with_timeout(Duration::from_secs(30))
+}
+
+#[test]
+fn no_z3_stochastic_search_runs_to_completion() {
+    let target = mov_add_target();
+    let live_out = LiveOut::from_registers(vec![Register::X0]);
+    let config = base_config(Algorithm::Stochastic).with_stochastic(
+        StochasticConfig::default()
+            .with_seed(1)
+            .with_iterations(20_000),
+    );
+    config
+        .validate()
+        .expect("stochastic search needs no solver");
+
+    let result = s11::optimize_sequence(&target, &live_out, &config);
+
+    assert!(result.statistics.candidates_evaluated > 0);
+    assert_eq!(
+        result.statistics.smt_queries, 0,
+        "a build without z3 must never report a solver query"
+    );
+    if let Some(optimized) = result
+        .optimized_sequence
+        .filter(|_| result.found_optimization)
+    {
+        assert_eq!(
+            check_equivalence_approx(&target, &optimized, &live_out, 1000),
+            EquivalenceResult::Equivalent
+        );
+    }
+}
+
+#[test]
+fn no_z3_enumerative_search_accepts_a_concretely_tested_rewrite() {
+    let target = mov_add_target();
+    let live_out = LiveOut::from_registers(vec![Register::X0]);
+    let config = base_config(Algorithm::Enumerative);
+
+    let result = s11::optimize_sequence(&target, &live_out, &config);
+
+    assert!(result.found_optimization);
+    assert_eq!(
+        result.optimized_sequence,
+        Some(vec![Instruction::Add {
+            rd: Register::X0,
+            rn: Register::X1,
+            rm: Operand::Immediate(1),
+        }])
+    );
+}
+
+#[test]
+fn no_z3_rejects_symbolic_search() {
+    let err = base_config(Algorithm::Symbolic)
+        .validate()
+        .expect_err("symbolic search cannot run without a solver");
+    assert!(err.contains("z3"), "unexpected error: {err}");
+}
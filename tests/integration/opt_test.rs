@@ -54,6 +54,127 @@ fn write_minimal_riscv_elf(class: elf::file::Class) -> tempfile::NamedTempFile {
     file
 }
 
+// ELF64 AArch64 file with a single executable `.text` section holding
+// `text` at `vaddr`, plus a symbol table naming `functions` as global
+// `STT_FUNC` symbols: (name, offset into `text`, size). Enough for `opt` to
+// validate, disassemble and patch a window, or find function symbols,
+// without the prebuilt `binaries/` fixtures.
+fn write_minimal_aarch64_elf(
+    text: &[u8],
+    vaddr: u64,
+    functions: &[(&str, u64, u64)],
+) -> tempfile::NamedTempFile {
+    const EHDR: usize = 64;
+    const SHDR: usize = 64;
+    const SYM: usize = 24;
+    let shstrtab = b"\0.text\0.symtab\0.strtab\0.shstrtab\0";
+    let mut strtab = vec![0u8];
+    let mut symtab = vec![0u8; SYM];
+    for (name, offset, size) in functions {
+        let mut symbol = [0u8; SYM];
+        symbol[..4].copy_from_slice(&(strtab.len() as u32).to_le_bytes());
+        symbol[4] = (elf::abi::STB_GLOBAL << 4) | elf::abi::STT_FUNC;
+        symbol[6..8].copy_from_slice(&1u16.to_le_bytes());
+        symbol[8..16].copy_from_slice(&(vaddr + offset).to_le_bytes());
+        symbol[16..24].copy_from_slice(&size.to_le_bytes());
+        symtab.extend_from_slice(&symbol);
+        strtab.extend_from_slice(name.as_bytes());
+        strtab.push(0);
+    }
+
+    let text_offset = EHDR;
+    let symtab_offset = text_offset + text.len();
+    let strtab_offset = symtab_offset + symtab.len();
+    let shstrtab_offset = strtab_offset + strtab.len();
+    let shoff = shstrtab_offset + shstrtab.len();
+    let mut bytes = vec![0u8; shoff + 5 * SHDR];
+
+    bytes[..4].copy_from_slice(&elf::abi::ELFMAGIC);
+    bytes[elf::abi::EI_CLASS] = elf::abi::ELFCLASS64;
+    bytes[elf::abi::EI_DATA] = elf::abi::ELFDATA2LSB;
+    bytes[elf::abi::EI_VERSION] = elf::abi::EV_CURRENT;
+    bytes[16..18].copy_from_slice(&elf::abi::ET_EXEC.to_le_bytes());
+    bytes[18..20].copy_from_slice(&elf::abi::EM_AARCH64.to_le_bytes());
+    bytes[20..24].copy_from_slice(&(elf::abi::EV_CURRENT as u32).to_le_bytes());
+    bytes[40..48].copy_from_slice(&(shoff as u64).to_le_bytes());
+    bytes[52..54].copy_from_slice(&(EHDR as u16).to_le_bytes());
+    bytes[58..60].copy_from_slice(&(SHDR as u16).to_le_bytes());
+    bytes[60..62].copy_from_slice(&5u16.to_le_bytes());
+    bytes[62..64].copy_from_slice(&4u16.to_le_bytes());
+    bytes[text_offset..symtab_offset].copy_from_slice(text);
+    bytes[symtab_offset..strtab_offset].copy_from_slice(&symtab);
+    bytes[strtab_offset..shstrtab_offset].copy_from_slice(&strtab);
+    bytes[shstrtab_offset..shoff].copy_from_slice(shstrtab);
+
+    // (name, type, flags, addr, offset, size, link, info, entsize) for
+    // .text, .symtab, .strtab and .shstrtab.
+    let headers = [
+        (
+            1u32,
+            elf::abi::SHT_PROGBITS,
+            (elf::abi::SHF_ALLOC | elf::abi::SHF_EXECINSTR) as u64,
+            vaddr,
+            text_offset,
+            text.len(),
+            0u32,
+            0u32,
+            0u64,
+        ),
+        (
+            7,
+            elf::abi::SHT_SYMTAB,
+            0,
+            0,
+            symtab_offset,
+            symtab.len(),
+            3,
+            1,
+            SYM as u64,
+        ),
+        (
+            15,
+            elf::abi::SHT_STRTAB,
+            0,
+            0,
+            strtab_offset,
+            strtab.len(),
+            0,
+            0,
+            0,
+        ),
+        (
+            23,
+            elf::abi::SHT_STRTAB,
+            0,
+            0,
+            shstrtab_offset,
+            shstrtab.len(),
+            0,
+            0,
+            0,
+        ),
+    ];
+    for (index, (name, kind, flags, addr, offset, size, link, info, entsize)) in
+        headers.into_iter().enumerate()
+    {
+        let base = shoff + (index + 1) * SHDR;
+        bytes[base..base + 4].copy_from_slice(&name.to_le_bytes());
+        bytes[base + 4..base + 8].copy_from_slice(&kind.to_le_bytes());
+        bytes[base + 8..base + 16].copy_from_slice(&flags.to_le_bytes());
+        bytes[base + 16..base + 24].copy_from_slice(&addr.to_le_bytes());
+        bytes[base + 24..base + 32].copy_from_slice(&(offset as u64).to_le_bytes());
+        bytes[base + 32..base + 40].copy_from_slice(&(size as u64).to_le_bytes());
+        bytes[base + 40..base + 44].copy_from_slice(&link.to_le_bytes());
+        bytes[base + 44..base + 48].copy_from_slice(&info.to_le_bytes());
+        bytes[base + 48..base + 56].copy_from_slice(&1u64.to_le_bytes());
+        bytes[base + 56..base + 64].copy_from_slice(&entsize.to_le_bytes());
+    }
+
+    let file = tempfile::NamedTempFile::new().expect("create temporary AArch64 ELF");
+    fs::write(file.path(), &bytes).expect("write temporary AArch64 ELF");
+    file
+}
+
 // AArch64 only: scans at 4-byte-aligned offsets in every executable section
 // of `elf_path` for a little-endian AArch64 encoding matching `expected`
 // under `mask` (i.e. `bytes[i] & mask[i] == expected[i] & mask[i]` for each
@@ -147,7 +268,7 @@ fn find_supported_aarch64_instruction_window(
 
                 match parse_line(&line) {
                     Ok(LineResult::Instruction(instruction)) => !instruction.is_terminator(),
-                    Ok(LineResult::Skip) | Err(_) => false,
+                    Ok(LineResult::Skip | LineResult::Unsupported(_)) | Err(_) => false,
                 }
             });
 
@@ -262,11 +383,10 @@ fn assert_opt_arch_mismatch_rejected(test_elf: &Path, arch: &str, detected_arch:
         stderr
     );
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
     assert!(
-        !stdout.contains("Optimizing ELF binary") && !stdout.contains("Optimizing x86 ELF binary"),
-        "Should reject before starting optimization, stdout: {}",
-        stdout
+        !stderr.contains("Optimizing ELF binary") && !stderr.contains("Optimizing x86 ELF binary"),
+        "Should reject before starting optimization, stderr: {}",
+        stderr
     );
 }
 
@@ -317,46 +437,48 @@ fn test_opt_basic_functionality() {
         );
     }
 
+    // Progress goes to stderr; stdout only carries results.
     let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
 
     // Check that optimization completed successfully
     assert!(
-        stdout.contains("Optimization completed successfully"),
+        stderr.contains("Optimization completed successfully"),
         "Should complete optimization successfully"
     );
 
     // Check that it shows the expected steps
     assert!(
-        stdout.contains("Optimizing ELF binary"),
+        stderr.contains("Optimizing ELF binary"),
         "Should show optimization message"
     );
     assert!(
-        stdout.contains("Address window"),
+        stderr.contains("Address window"),
         "Should show address window"
     );
     assert!(
-        stdout.contains("Window is within section"),
+        stderr.contains("Window is within section"),
         "Should validate window"
     );
     assert!(
-        stdout.contains("Disassembled"),
+        stderr.contains("Disassembled"),
         "Should disassemble instructions"
     );
     assert!(
-        stdout.contains("Disassembled 4 instructions"),
-        "Should disassemble a multi-instruction window; stdout: {stdout}"
+        stderr.contains("Disassembled 4 instructions"),
+        "Should disassemble a multi-instruction window; stderr: {stderr}"
     );
-    assert!(stdout.contains("Converted"), "Should convert to IR");
+    assert!(stderr.contains("Converted"), "Should convert to IR");
     assert!(
-        stdout.contains("Converted 4 instructions"),
-        "Should convert a multi-instruction window to IR; stdout: {stdout}"
+        stderr.contains("Converted 4 instructions"),
+        "Should convert a multi-instruction window to IR; stderr: {stderr}"
     );
     assert!(
-        stdout.contains("Running stochastic (MCMC) search"),
-        "Should run the bounded stochastic search path; stdout: {stdout}"
+        stderr.contains("Running stochastic (MCMC) search"),
+        "Should run the bounded stochastic search path; stderr: {stderr}"
     );
     assert!(
-        stdout.contains("Reassembled"),
+        stderr.contains("Reassembled"),
         "Should reassemble instructions"
     );
     assert!(
@@ -374,6 +496,217 @@ fn test_opt_basic_functionality() {
     let _ = fs::remove_file(optimized_path);
 }
 
+#[test]
+fn test_opt_explain_reports_live_out_and_smt_verdict_for_mov_add_fusion() {
+    // mov x0, x1; add x0, x0, #1  ==>  add x0, x1, #1
+    let text = [0xe0, 0x03, 0x01, 0xaa, 0x00, 0x04, 0x00, 0x91];
+    let input = write_minimal_aarch64_elf(&text, 0x1000, &[]);
+    let output_dir = tempfile::tempdir().expect("create output directory");
+    let optimized = output_dir.path().join("fused");
+
+    let output = Command::new(get_binary_path())
+        .arg("opt")
+        .arg(input.path())
+        .args(["--start-addr", "0x1000", "--end-addr", "0x1008"])
+        .args(["--algorithm", "enumerative", "--timeout", "30"])
+        .arg("--explain")
+        .arg("-o")
+        .arg(&optimized)
+        .output()
+        .expect("Failed to execute s11");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "opt --explain failed\nstdout: {stdout}\nstderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(stdout.contains("Explanation:"), "stdout: {stdout}");
+    assert!(stdout.contains("  Live-out: x0"), "stdout: {stdout}");
+    assert!(
+        stdout.contains("  Concrete tests passed: "),
+        "stdout: {stdout}"
+    );
+    assert!(
+        stdout.contains("  Verdict: SMT-proven equivalent"),
+        "stdout: {stdout}"
+    );
+    assert!(optimized.exists(), "the fused window should be written");
+}
+
+#[test]
+fn test_opt_optimize_all_patches_only_modeled_functions() {
+    let text = [
+        // add_one: mov x0, x1; add x0, x0, #1; ret
+        0xe0, 0x03, 0x01, 0xaa, 0x00, 0x04, 0x00, 0x91, 0xc0, 0x03, 0x5f, 0xd6,
+        // signed: paciasp; ret
+        0x3f, 0x23, 0x03, 0xd5, 0xc0, 0x03, 0x5f, 0xd6, // stub: ret
+        0xc0, 0x03, 0x5f, 0xd6,
+    ];
+    let input = write_minimal_aarch64_elf(
+        &text,
+        0x1000,
+        &[("add_one", 0, 12), ("signed", 12, 8), ("stub", 20, 4)],
+    );
+    let output_dir = tempfile::tempdir().expect("create output directory");
+    let optimized = output_dir.path().join("all");
+
+    let output = Command::new(get_binary_path())
+        .arg("opt")
+        .arg(input.path())
+        .args(["--optimize-all", "--min-size", "8"])
+        .args(["--algorithm", "enumerative", "--timeout", "30"])
+        .arg("-o")
+        .arg(&optimized)
+        .output()
+        .expect("Failed to execute s11");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "opt --optimize-all failed\nstdout: {stdout}\nstderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let summary_line = |name: &str| {
+        stdout
+            .lines()
+            .find(|line| line.trim_start().starts_with(name))
+            .unwrap_or_else(|| panic!("no summary line for {name}; stdout: {stdout}"))
+            .to_string()
+    };
+    assert!(summary_line("add_one").ends_with("saved 4 bytes"));
+    assert!(summary_line("signed").ends_with("skipped: unsupported instruction paciasp"));
+    assert!(
+        !stdout.contains("stub"),
+        "functions below --min-size are not attempted; stdout: {stdout}"
+    );
+    assert!(
+        stdout.contains("Functions: 1 improved, 0 unchanged, 1 skipped; 4 bytes saved"),
+        "stdout: {stdout}"
+    );
+
+    // Only the bytes of add_one may differ.
+    let original = fs::read(input.path()).expect("read input");
+    let patched = fs::read(&optimized).expect("read optimized copy");
+    assert_eq!(original.len(), patched.len());
+    let text_offset = 64;
+    let changed: Vec<usize> = original
+        .iter()
+        .zip(&patched)
+        .enumerate()
+        .filter(|(_, (a, b))| a != b)
+        .map(|(offset, _)| offset)
+        .collect();
+    assert!(!changed.is_empty());
+    assert!(
+        changed
+            .iter()
+            .all(|&offset| (text_offset..text_offset + 12).contains(&offset)),
+        "bytes outside add_one changed at offsets {changed:?}"
+    );
+}
+
+#[test]
+fn test_opt_compare_binary_optimizes_the_function_in_both_builds() {
+    // add_one: mov x0, x1; add x0, x0, #1; ret
+    let add_one = [
+        0xe0, 0x03, 0x01, 0xaa, 0x00, 0x04, 0x00, 0x91, 0xc0, 0x03, 0x5f, 0xd6,
+    ];
+    let baseline = write_minimal_aarch64_elf(&add_one, 0x1000, &[("add_one", 0, 12)]);
+    // The second build places a `ret` stub ahead of add_one.
+    let mut text = vec![0xc0, 0x03, 0x5f, 0xd6];
+    text.extend_from_slice(&add_one);
+    let candidate = write_minimal_aarch64_elf(&text, 0x2000, &[("stub", 0, 4), ("add_one", 4, 12)]);
+
+    let output = Command::new(get_binary_path())
+        .arg("opt")
+        .arg(baseline.path())
+        .arg("--compare-binary")
+        .arg(candidate.path())
+        .args(["--function", "add_one"])
+        .args(["--algorithm", "enumerative", "--timeout", "30"])
+        .output()
+        .expect("Failed to execute s11");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "opt --compare-binary failed\nstdout: {stdout}\nstderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let row = |label: &str| {
+        stdout
+            .lines()
+            .find(|line| line.trim_start().starts_with(label))
+            .unwrap_or_else(|| panic!("no {label} row; stdout: {stdout}"))
+            .to_string()
+    };
+    let window = row("window");
+    assert!(window.contains("0x00001000-0x0000100c"), "{window}");
+    assert!(window.contains("0x00002004-0x00002010"), "{window}");
+    assert_eq!(
+        row("result").matches("saved 4 bytes").count(),
+        2,
+        "stdout: {stdout}"
+    );
+    assert_eq!(row("cost").matches("3 -> 2").count(), 2, "stdout: {stdout}");
+}
+
+#[test]
+fn test_opt_bench_search_reports_positive_rates() {
+    let binary = get_binary_path();
+    let source_elf = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("binaries")
+        .join("arrays_debug");
+    check_test_binary(&source_elf);
+    let (start_addr, end_addr) = find_supported_aarch64_instruction_window(&source_elf, 4);
+
+    let tmp_dir = tempfile::tempdir().expect("create temp output dir");
+    let output = Command::new(binary)
+        .arg("opt")
+        .arg(&source_elf)
+        .arg("--bench-search")
+        .arg("--algorithm")
+        .arg("stochastic")
+        .arg("--seed")
+        .arg("0")
+        .arg("--timeout")
+        .arg("1")
+        .arg("--start-addr")
+        .arg(format!("0x{start_addr:x}"))
+        .arg("--end-addr")
+        .arg(format!("0x{end_addr:x}"))
+        .arg("--output")
+        .arg(tmp_dir.path().join("arrays_debug_optimized"))
+        .output()
+        .expect("Failed to execute s11");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "bench run failed\nstdout: {stdout}\nstderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let rate = |label: &str| -> f64 {
+        stdout
+            .lines()
+            .find_map(|line| line.trim().strip_prefix(label))
+            .unwrap_or_else(|| panic!("missing {label:?} in stdout: {stdout}"))
+            .trim()
+            .trim_end_matches('%')
+            .parse()
+            .unwrap_or_else(|e| panic!("unparsable {label:?} rate: {e}"))
+    };
+    // A one-second MCMC run evaluates a proposal per iteration and accepts
+    // at least some of them, so these are strictly positive.
+    assert!(rate("Candidates/sec:") > 0.0, "stdout: {stdout}");
+    assert!(rate("Acceptance rate:") > 0.0, "stdout: {stdout}");
+    // Fast passes and SMT queries depend on what the chain proposes.
+    assert!(rate("Fast passes/sec:") >= 0.0, "stdout: {stdout}");
+    assert!(rate("SMT queries/sec:") >= 0.0, "stdout: {stdout}");
+}
+
 #[test]
 fn test_opt_requires_binary() {
     let binary = get_binary_path();
@@ -528,11 +861,11 @@ fn test_opt_matching_riscv_arch_reports_unsupported() {
             "{arch} should not expose an ELF-reading error"
         );
 
-        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
         assert!(
-            !stdout.contains("Optimizing ELF binary")
-                && !stdout.contains("Optimizing x86 ELF binary"),
-            "{arch} should be rejected before optimization starts, stdout: {stdout}"
+            !stderr.contains("Optimizing ELF binary")
+                && !stderr.contains("Optimizing x86 ELF binary"),
+            "{arch} should be rejected before optimization starts, stderr: {stderr}"
         );
     }
 }
@@ -710,6 +1043,54 @@ fn test_opt_rejects_partially_decoded_window_with_first_bad_address() {
     );
 }
 
+#[test]
+fn test_opt_rejects_pc_relative_window_unless_allowed() {
+    // adrp x0, #0x1000; add x0, x0, #1
+    let text = [0x00, 0x00, 0x00, 0x90, 0x00, 0x04, 0x00, 0x91];
+    let input = write_minimal_aarch64_elf(&text, 0x1000, &[]);
+    let output_dir = tempfile::tempdir().expect("create output directory");
+    let optimized = output_dir.path().join("pcrel");
+
+    let output = Command::new(get_binary_path())
+        .arg("opt")
+        .arg(input.path())
+        .args(["--start-addr", "0x1000", "--end-addr", "0x1008"])
+        .arg("-o")
+        .arg(&optimized)
+        .output()
+        .expect("Failed to execute s11");
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        !output.status.success(),
+        "opt must refuse a window containing adrp; stderr: {stderr}"
+    );
+    assert!(
+        stderr.contains("PC-relative instruction 'adrp")
+            && stderr.contains("0x1000")
+            && stderr.contains("--allow-pcrel"),
+        "stderr should explain the refusal and how to override it; got: {stderr}"
+    );
+    assert!(!optimized.exists(), "nothing should be written");
+
+    // With the override the guard is skipped and the window reaches IR
+    // conversion, which does not model adrp yet.
+    let output = Command::new(get_binary_path())
+        .arg("opt")
+        .arg(input.path())
+        .args(["--start-addr", "0x1000", "--end-addr", "0x1008"])
+        .arg("--allow-pcrel")
+        .arg("-o")
+        .arg(&optimized)
+        .output()
+        .expect("Failed to execute s11");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        !stderr.contains("PC-relative") && stderr.contains("unsupported instruction 'adrp"),
+        "--allow-pcrel should bypass only the PC-relative guard; got: {stderr}"
+    );
+}
+
 #[test]
 fn test_opt_rejects_unsupported_instruction_window() {
     let binary = get_binary_path();
@@ -808,13 +1189,13 @@ fn assert_opt_succeeds_on_window(source_elf: &Path, start_addr: u64) {
         );
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let ok = stdout.contains("Optimization completed successfully");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let ok = stderr.contains("Optimization completed successfully");
     let optimized_exists = optimized_path.exists();
 
     assert!(
         ok,
-        "memory-op window must round-trip end-to-end; stdout: {stdout}",
+        "memory-op window must round-trip end-to-end; stderr: {stderr}",
     );
     assert!(
         optimized_exists,
@@ -1162,13 +1543,14 @@ fn test_opt_x86_64_known_shortening() {
     );
 
     let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
     assert!(
-        stdout.contains("Detected: X86_64"),
-        "should run the x86-64 opt path; stdout: {stdout}"
+        stderr.contains("Detected: X86_64"),
+        "should run the x86-64 opt path; stderr: {stderr}"
     );
     assert!(
-        stdout.contains("Disassembled 2 instructions"),
-        "should disassemble the two-instruction window; stdout: {stdout}"
+        stderr.contains("Disassembled 2 instructions"),
+        "should disassemble the two-instruction window; stderr: {stderr}"
     );
     // The known shortening: 2 instructions collapse to 1.
     assert!(
@@ -1180,8 +1562,8 @@ fn test_opt_x86_64_known_shortening() {
         "should write the optimized binary; stdout: {stdout}"
     );
     assert!(
-        stdout.contains("Optimization completed successfully"),
-        "should complete the optimization; stdout: {stdout}"
+        stderr.contains("Optimization completed successfully"),
+        "should complete the optimization; stderr: {stderr}"
     );
     assert!(
         optimized_path.exists(),